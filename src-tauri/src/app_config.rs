@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::services::skill::SkillStore;
@@ -190,6 +191,23 @@ pub struct McpServer {
     pub tags: Vec<String>,
 }
 
+/// MCP Profile：一组服务器及其在各应用的启用状态，可一键应用
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProfile {
+    pub name: String,
+    /// 服务器 ID -> 应用该 Profile 后的启用状态
+    #[serde(default)]
+    pub servers: HashMap<String, McpApps>,
+    /// 固定的服务器：不在 Profile 中时保持当前状态，不会被禁用
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
 /// MCP 配置：单客户端维度（v3.6.x 及以前，保留用于向后兼容）
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct McpConfig {
@@ -515,6 +533,34 @@ impl MultiAppConfig {
         Ok(config)
     }
 
+    /// 迁移到数据库后归档的旧配置文件路径（config.json.migrated）
+    pub fn archived_path() -> PathBuf {
+        get_app_config_path().with_extension("json.migrated")
+    }
+
+    /// 只读加载归档的旧配置（不保存、不自动导入提示词），用于按需重新导入
+    pub fn load_archived(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            return Err(AppError::localized(
+                "config.archived_not_found",
+                format!("未找到归档的旧配置文件: {}", path.display()),
+                format!("Archived legacy config not found: {}", path.display()),
+            ));
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let mut config: Self =
+            serde_json::from_str(&content).map_err(|e| AppError::json(path, e))?;
+
+        config.migrate_mcp_to_unified()?;
+        if let Some(old_claude_snippet) = config.claude_common_config_snippet.take() {
+            config
+                .common_config_snippets
+                .claude
+                .get_or_insert(old_claude_snippet);
+        }
+        Ok(config)
+    }
+
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), AppError> {
         let config_path = get_app_config_path();
@@ -671,6 +717,7 @@ impl MultiAppConfig {
             content,
             description: Some("Automatically imported on first launch".to_string()),
             enabled: true, // 自动启用
+            order_index: 0,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -7,6 +7,7 @@ use crate::error::AppError;
 
 /// Store 中的键名
 const STORE_KEY_APP_CONFIG_DIR: &str = "app_config_dir_override";
+const STORE_KEY_LANGUAGE: &str = "language";
 
 /// 缓存当前的 app_config_dir 覆盖路径，避免存储 AppHandle
 static APP_CONFIG_DIR_OVERRIDE: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
@@ -26,6 +27,13 @@ pub fn get_app_config_dir_override() -> Option<PathBuf> {
     override_cache().read().ok()?.clone()
 }
 
+/// 缓存 Store 中的界面语言，供 AppState 创建前的启动对话框使用
+static LANGUAGE_OVERRIDE: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+
+fn language_cache() -> &'static RwLock<Option<String>> {
+    LANGUAGE_OVERRIDE.get_or_init(|| RwLock::new(None))
+}
+
 fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
     let store = match app.store_builder("app_paths.json").build() {
         Ok(store) => store,
@@ -105,8 +113,65 @@ pub fn set_app_config_dir_to_store(
     Ok(())
 }
 
+/// 获取缓存中的界面语言（`zh` / `en` / `ja`）
+pub fn get_language_override() -> Option<String> {
+    language_cache().read().ok()?.clone()
+}
+
+fn read_language_from_store(app: &tauri::AppHandle) -> Option<String> {
+    let store = match app.store_builder("app_paths.json").build() {
+        Ok(store) => store,
+        Err(e) => {
+            log::warn!("无法创建 Store: {e}");
+            return None;
+        }
+    };
+
+    match store.get(STORE_KEY_LANGUAGE) {
+        Some(Value::String(lang)) if crate::settings::is_supported_language(lang.trim()) => {
+            Some(lang.trim().to_string())
+        }
+        Some(_) => {
+            log::warn!("Store 中的 {STORE_KEY_LANGUAGE} 无效，已忽略");
+            None
+        }
+        None => None,
+    }
+}
+
+/// 从 Store 刷新界面语言并更新缓存（在 setup 最早阶段调用）
+pub fn refresh_language_override(app: &tauri::AppHandle) -> Option<String> {
+    let value = read_language_from_store(app);
+    if let Ok(mut guard) = language_cache().write() {
+        *guard = value.clone();
+    }
+    value
+}
+
+/// 写入界面语言到 Tauri Store（None 表示删除，跟随系统语言）
+pub fn set_language_to_store(app: &tauri::AppHandle, lang: Option<&str>) -> Result<(), AppError> {
+    let store = app
+        .store_builder("app_paths.json")
+        .build()
+        .map_err(|e| AppError::Message(format!("创建 Store 失败: {e}")))?;
+
+    match lang {
+        Some(lang) => store.set(STORE_KEY_LANGUAGE, Value::String(lang.to_string())),
+        None => {
+            store.delete(STORE_KEY_LANGUAGE);
+        }
+    }
+
+    store
+        .save()
+        .map_err(|e| AppError::Message(format!("保存 Store 失败: {e}")))?;
+
+    refresh_language_override(app);
+    Ok(())
+}
+
 /// 解析路径，支持 ~ 开头的相对路径
-fn resolve_path(raw: &str) -> PathBuf {
+pub(crate) fn resolve_path(raw: &str) -> PathBuf {
     if raw == "~" {
         if let Some(home) = dirs::home_dir() {
             return home;
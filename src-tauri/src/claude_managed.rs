@@ -0,0 +1,142 @@
+//! Claude Code 企业托管配置（managed-settings.json）
+//!
+//! 托管配置由管理员部署在系统目录中，优先级高于用户的 settings.json。cc-switch 只读取
+//! 这些文件，用于在状态页显示其位置，并在托管配置会覆盖 cc-switch 写入的字段时给出提示。
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::read_json_file;
+
+const MANAGED_SETTINGS_FILE: &str = "managed-settings.json";
+
+/// 托管配置状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSettingsStatus {
+    pub path: String,
+    /// cc-switch 从不写入托管配置
+    pub read_only: bool,
+    /// 会被托管配置覆盖的字段（`env.X` 或顶层键），为空表示没有冲突
+    pub overridden_keys: Vec<String>,
+}
+
+/// Claude Code 读取托管配置的系统路径（按优先顺序）
+pub fn managed_settings_paths() -> Vec<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        vec![PathBuf::from("/Library/Application Support/ClaudeCode").join(MANAGED_SETTINGS_FILE)]
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let program_data = std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+        vec![
+            program_data.join("ClaudeCode").join(MANAGED_SETTINGS_FILE),
+            // 旧版本 Claude Code 使用的位置
+            PathBuf::from(r"C:\Program Files\ClaudeCode").join(MANAGED_SETTINGS_FILE),
+        ]
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        vec![PathBuf::from("/etc/claude-code").join(MANAGED_SETTINGS_FILE)]
+    }
+}
+
+/// 读取第一个存在的托管配置；无法解析时按空配置处理（仍报告其位置）
+fn read_managed_settings() -> Option<(PathBuf, Value)> {
+    let path = managed_settings_paths()
+        .into_iter()
+        .find(|path| path.is_file())?;
+    let value = read_json_file::<Value>(&path).unwrap_or_else(|e| {
+        log::warn!("读取 Claude 托管配置失败 ({}): {e}", path.display());
+        Value::Null
+    });
+    Some((path, value))
+}
+
+/// 列出 `settings` 中会被托管配置覆盖的字段
+///
+/// `env` 按变量逐个比较，其余顶层键整体比较；取值相同的字段不算冲突。
+pub fn overridden_keys(managed: &Value, settings: &Value) -> Vec<String> {
+    let (Some(managed), Some(settings)) = (managed.as_object(), settings.as_object()) else {
+        return Vec::new();
+    };
+
+    let mut keys = Vec::new();
+    for (key, managed_value) in managed {
+        let Some(value) = settings.get(key) else {
+            continue;
+        };
+        if key == "env" {
+            let (Some(managed_env), Some(env)) = (managed_value.as_object(), value.as_object())
+            else {
+                continue;
+            };
+            for (name, managed_var) in managed_env {
+                if env.get(name).is_some_and(|var| var != managed_var) {
+                    keys.push(format!("env.{name}"));
+                }
+            }
+        } else if value != managed_value {
+            keys.push(key.clone());
+        }
+    }
+    keys.sort();
+    keys
+}
+
+/// 检测托管配置，并与 cc-switch 写入（或将要写入）的配置比较
+pub fn managed_settings_status(settings: &Value) -> Option<ManagedSettingsStatus> {
+    let (path, managed) = read_managed_settings()?;
+    Some(ManagedSettingsStatus {
+        path: path.to_string_lossy().to_string(),
+        read_only: true,
+        overridden_keys: overridden_keys(&managed, settings),
+    })
+}
+
+/// 与当前 Claude Live 配置比较（状态页使用）
+pub fn live_managed_settings_status() -> Option<ManagedSettingsStatus> {
+    let live_path = crate::services::provider::get_claude_live_path();
+    let live = read_json_file::<Value>(&live_path).unwrap_or(Value::Null);
+    managed_settings_status(&live)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn lists_env_and_top_level_keys_overridden_by_managed_settings() {
+        let managed = json!({
+            "env": {
+                "ANTHROPIC_BASE_URL": "https://gateway.corp.example",
+                "DISABLE_TELEMETRY": "1",
+                "HTTPS_PROXY": "http://proxy:8080",
+            },
+            "model": "claude-sonnet",
+            "permissions": { "deny": ["WebFetch"] },
+        });
+        let settings = json!({
+            "env": {
+                "ANTHROPIC_BASE_URL": "https://relay.example",
+                "ANTHROPIC_AUTH_TOKEN": "sk-test",
+                "DISABLE_TELEMETRY": "1",
+            },
+            "model": "relay-model",
+        });
+
+        assert_eq!(
+            overridden_keys(&managed, &settings),
+            vec!["env.ANTHROPIC_BASE_URL", "model"]
+        );
+        assert!(overridden_keys(&Value::Null, &settings).is_empty());
+    }
+}
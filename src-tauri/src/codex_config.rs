@@ -6,11 +6,16 @@ use crate::config::{
     write_text_file,
 };
 use crate::error::AppError;
+use crate::field_error::FieldError;
+use serde::Serialize;
 use serde_json::Value;
 use serde_json::json;
 use std::fs;
 use std::path::Path;
 
+/// Codex 内置的 model_provider（无需 `[model_providers.<name>]` 表）
+const BUILTIN_MODEL_PROVIDERS: &[&str] = &["openai", "oss", "ollama", "lmstudio"];
+
 /// 获取 Codex 配置目录路径
 pub fn get_codex_config_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_codex_override_dir() {
@@ -61,10 +66,15 @@ pub fn delete_codex_provider_config(
 }
 
 /// 原子写 Codex 的 `auth.json` 与 `config.toml`，在第二步失败时回滚第一步
+///
+/// `config.toml` 按传入文本原样写入；切换供应商时需要保留用户内容的调用方
+/// 应先通过 `merge_with_live_config` 合并。
 pub fn write_codex_live_atomic(
     auth: &Value,
     config_text_opt: Option<&str>,
 ) -> Result<(), AppError> {
+    crate::settings::ensure_live_writable()?;
+
     let auth_path = get_codex_auth_path();
     let config_path = get_codex_config_path();
 
@@ -174,6 +184,14 @@ pub fn read_codex_config_text() -> Result<String, AppError> {
     }
 }
 
+/// 将供应商的 config.toml 文本合并到现有 Live 配置上，供切换供应商时写入
+///
+/// Live 配置不存在时直接返回供应商配置。
+pub fn merge_with_live_config(provider_config: &str) -> Result<String, AppError> {
+    let live = read_codex_config_text()?;
+    merge_codex_config(&live, provider_config)
+}
+
 /// 对非空的 TOML 文本进行语法校验
 pub fn validate_config_toml(text: &str) -> Result<(), AppError> {
     if text.trim().is_empty() {
@@ -190,3 +208,535 @@ pub fn read_and_validate_codex_config_text() -> Result<String, AppError> {
     validate_config_toml(&s)?;
     Ok(s)
 }
+
+/// 获取当前生效供应商的 base_url
+///
+/// 按 `model_provider` 定位 `[model_providers.<name>]` 表并读取其中的 `base_url`，
+/// 不会误取 `mcp_servers` 等其他表里的同名字段；未设置 `model_provider` 时回退到顶层 `base_url`。
+pub fn get_active_base_url(text: &str) -> Result<Option<String>, AppError> {
+    let doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Message(format!("TOML parse error: {e}")))?;
+
+    let base_url = match doc.get("model_provider").and_then(|v| v.as_str()) {
+        Some(name) => doc
+            .get("model_providers")
+            .and_then(|p| p.as_table_like())
+            .and_then(|p| p.get(name))
+            .and_then(|t| t.as_table_like())
+            .and_then(|t| t.get("base_url")),
+        None => doc.get("base_url"),
+    };
+
+    Ok(base_url
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string))
+}
+
+/// `source` 未设置时需要从 `target` 中移除的顶层键
+const MANAGED_CODEX_KEYS: [&str; 2] = ["model", "model_provider"];
+
+/// 将 `source` 中由 cc-switch 管理的部分应用到 `target` 上，返回合并后的文本
+///
+/// 改动 `source` 中的全部顶层键值（如 `model_reasoning_effort`、
+/// `disable_response_storage`）以及 `source` 当前生效的 `[model_providers.<id>]` 表；
+/// `source` 未设置 `model`、`model_provider` 时从 `target` 中移除。
+/// `[profiles.*]`、`[mcp_servers.*]`、`[tui]` 等其余内容（含注释与表顺序）保持原样。
+/// `target` 为空或无法解析时直接返回 `source`。
+///
+/// 写入 Live 时 `target` 为现有 config.toml、`source` 为供应商配置；
+/// 切换前回填时反过来，仅把 Live 中的托管部分写回供应商配置。
+pub fn merge_codex_config(target: &str, source: &str) -> Result<String, AppError> {
+    if target.trim().is_empty() {
+        return Ok(source.to_string());
+    }
+    let mut doc = match target.parse::<toml_edit::DocumentMut>() {
+        Ok(doc) => doc,
+        Err(e) => {
+            log::warn!("现有 Codex config.toml 无法解析，将整体覆盖: {e}");
+            return Ok(source.to_string());
+        }
+    };
+    let source_doc = source
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Message(format!("TOML parse error: {e}")))?;
+
+    let top_level_keys = source_doc
+        .iter()
+        .filter(|(_, item)| item.is_value())
+        .map(|(key, _)| key.to_string())
+        .chain(MANAGED_CODEX_KEYS.iter().map(|key| key.to_string()))
+        .collect::<indexmap::IndexSet<_>>();
+    for key in &top_level_keys {
+        match (doc.get_mut(key), source_doc.get(key)) {
+            // 只替换值，保留原有的行内注释与空白
+            (Some(toml_edit::Item::Value(old)), Some(toml_edit::Item::Value(new))) => {
+                let decor = old.decor().clone();
+                *old = new.clone();
+                *old.decor_mut() = decor;
+            }
+            (_, Some(new)) => {
+                doc.insert(key, new.clone());
+            }
+            (_, None) => {
+                doc.remove(key);
+            }
+        }
+    }
+
+    let active_table = source_doc
+        .get("model_provider")
+        .and_then(|v| v.as_str())
+        .and_then(|id| {
+            source_doc
+                .get("model_providers")
+                .and_then(|p| p.as_table_like())
+                .and_then(|p| p.get(id))
+                .and_then(|t| t.as_table())
+                .map(|t| (id, t.clone()))
+        });
+    if let Some((id, mut table)) = active_table {
+        let next_position = max_table_position(doc.as_table()) + 1;
+        if !doc.contains_key("model_providers") {
+            let mut parent = toml_edit::Table::new();
+            parent.set_implicit(true);
+            doc.insert("model_providers", toml_edit::Item::Table(parent));
+        }
+        let Some(providers) = doc
+            .get_mut("model_providers")
+            .and_then(toml_edit::Item::as_table_mut)
+        else {
+            log::warn!("现有 Codex config.toml 的 model_providers 不是标准表，跳过供应商表更新");
+            return Ok(doc.to_string());
+        };
+
+        table.set_dotted(false);
+        match providers.get(id).and_then(|t| t.as_table()) {
+            // 原地替换：沿用原表的位置与表头前的注释
+            Some(old) => {
+                let position = old.position().unwrap_or(next_position);
+                *table.decor_mut() = old.decor().clone();
+                set_table_position(&mut table, position);
+            }
+            // 新增的表追加到文件末尾
+            None => {
+                table.decor_mut().set_prefix("\n");
+                set_table_position(&mut table, next_position);
+            }
+        }
+        providers.insert(id, toml_edit::Item::Table(table));
+    }
+
+    Ok(doc.to_string())
+}
+
+/// 文档中已有表的最大输出位置
+fn max_table_position(table: &toml_edit::Table) -> usize {
+    table
+        .iter()
+        .map(|(_, item)| match item {
+            toml_edit::Item::Table(child) => {
+                child.position().unwrap_or(0).max(max_table_position(child))
+            }
+            toml_edit::Item::ArrayOfTables(array) => array
+                .iter()
+                .map(|child| child.position().unwrap_or(0).max(max_table_position(child)))
+                .max()
+                .unwrap_or(0),
+            _ => 0,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// 将表及其子表放到同一输出位置（同位置按父表在前的顺序输出）
+fn set_table_position(table: &mut toml_edit::Table, position: usize) {
+    table.set_position(position);
+    for (_, item) in table.iter_mut() {
+        match item {
+            toml_edit::Item::Table(child) => set_table_position(child, position),
+            toml_edit::Item::ArrayOfTables(array) => {
+                for child in array.iter_mut() {
+                    set_table_position(child, position);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Codex config.toml 格式化结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexConfigFormat {
+    /// 按键排序并规整空行后的 TOML
+    pub formatted: String,
+    /// 检查发现的问题（不影响格式化结果）
+    pub warnings: Vec<FieldError>,
+}
+
+/// 格式化 Codex config.toml：按键排序、每个表头前保留一个空行，并检查 model_provider 引用
+///
+/// 注释随所在的键/表一同移动，不会丢失。
+pub fn format_codex_config(text: &str) -> Result<CodexConfigFormat, AppError> {
+    validate_config_toml(text)?;
+
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Message(format!("TOML parse error: {e}")))?;
+
+    let mut position = 1;
+    sort_toml_table(doc.as_table_mut(), &mut position);
+    let warnings = lint_codex_config(&doc);
+
+    let mut formatted = collapse_blank_lines(&doc.to_string());
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+
+    Ok(CodexConfigFormat {
+        formatted,
+        warnings,
+    })
+}
+
+/// 合并连续空行（最多保留一个），并去除首尾空白
+pub(crate) fn collapse_blank_lines(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut blank_run = 0usize;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run <= 1 {
+                cleaned.push('\n');
+            }
+            continue;
+        }
+        blank_run = 0;
+        cleaned.push_str(line);
+        cleaned.push('\n');
+    }
+
+    cleaned.trim().to_string()
+}
+
+/// 递归排序表内键，并按排序结果重新分配子表的输出顺序
+fn sort_toml_table(table: &mut toml_edit::Table, position: &mut usize) {
+    table.sort_values();
+    for (_, item) in table.iter_mut() {
+        match item {
+            toml_edit::Item::Table(child) if child.is_dotted() => {
+                sort_toml_table(child, position);
+            }
+            toml_edit::Item::Table(child) => {
+                place_toml_table(child, position);
+                sort_toml_table(child, position);
+            }
+            toml_edit::Item::ArrayOfTables(array) => {
+                for child in array.iter_mut() {
+                    place_toml_table(child, position);
+                    sort_toml_table(child, position);
+                }
+            }
+            toml_edit::Item::Value(toml_edit::Value::InlineTable(inline)) => {
+                inline.sort_values();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn place_toml_table(table: &mut toml_edit::Table, position: &mut usize) {
+    table.set_position(*position);
+    *position += 1;
+
+    // 表头前仅有空白时统一为一个空行；包含注释则原样保留
+    let decor = table.decor_mut();
+    let whitespace_only = decor
+        .prefix()
+        .and_then(|p| p.as_str())
+        .map(|p| p.trim().is_empty())
+        .unwrap_or(true);
+    if whitespace_only {
+        decor.set_prefix("\n");
+    }
+}
+
+/// 检查 model_provider 是否指向存在且配置了 base_url 的 `[model_providers.<name>]` 表
+fn lint_codex_config(doc: &toml_edit::DocumentMut) -> Vec<FieldError> {
+    let mut warnings = Vec::new();
+    let Some(item) = doc.get("model_provider") else {
+        return warnings;
+    };
+
+    let Some(name) = item.as_str() else {
+        warnings.push(FieldError::warning(
+            "model_provider",
+            "codex.model_provider.invalid",
+            "model_provider 必须是字符串",
+            "model_provider must be a string",
+        ));
+        return warnings;
+    };
+
+    let provider_table = doc
+        .get("model_providers")
+        .and_then(|p| p.as_table_like())
+        .and_then(|p| p.get(name))
+        .and_then(|t| t.as_table_like());
+
+    match provider_table {
+        None if BUILTIN_MODEL_PROVIDERS.contains(&name) => {}
+        None => warnings.push(FieldError::warning(
+            "model_provider",
+            "codex.model_provider.missing_table",
+            format!("model_provider = \"{name}\"，但未找到 [model_providers.{name}] 表"),
+            format!("model_provider = \"{name}\" but no [model_providers.{name}] table exists"),
+        )),
+        Some(table) => {
+            let has_base_url = table
+                .get("base_url")
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| !v.trim().is_empty());
+            if !has_base_url {
+                warnings.push(FieldError::warning(
+                    format!("model_providers.{name}.base_url"),
+                    "codex.model_provider.missing_base_url",
+                    format!("[model_providers.{name}] 缺少 base_url"),
+                    format!("[model_providers.{name}] is missing base_url"),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_codex_config_sorts_keys_and_tables() {
+        let input = r#"model_provider = "custom"
+model = "gpt-5"
+
+
+[model_providers.custom]
+wire_api = "responses"
+base_url = "https://api.example.com/v1"
+name = "custom"
+[mcp_servers.a]
+command = "npx"
+"#;
+        let result = format_codex_config(input).expect("format should succeed");
+        assert_eq!(
+            result.formatted,
+            r#"model = "gpt-5"
+model_provider = "custom"
+
+[mcp_servers.a]
+command = "npx"
+
+[model_providers.custom]
+base_url = "https://api.example.com/v1"
+name = "custom"
+wire_api = "responses"
+"#
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn format_codex_config_warns_on_missing_provider_table() {
+        let input = "model_provider = \"azure\"\n\n[model_providers.other]\nbase_url = \"x\"\n";
+        let result = format_codex_config(input).expect("format should succeed");
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(
+            result.warnings[0].code,
+            "codex.model_provider.missing_table"
+        );
+
+        let builtin = format_codex_config("model_provider = \"openai\"\n").unwrap();
+        assert!(builtin.warnings.is_empty());
+    }
+
+    #[test]
+    fn format_codex_config_keeps_comments() {
+        let input = "# top comment\nb = 1\na = 2 # inline\n";
+        let result = format_codex_config(input).expect("format should succeed");
+        assert!(result.formatted.contains("# top comment"));
+        assert!(result.formatted.contains("a = 2 # inline"));
+    }
+
+    #[test]
+    fn get_active_base_url_ignores_mcp_servers() {
+        let input = r#"model_provider = "azure"
+
+[mcp_servers.my_server]
+base_url = "http://localhost:8080"
+
+[model_providers.other]
+base_url = "https://other.example/v1"
+
+[model_providers.azure]
+base_url = "https://azure.example/v1"
+"#;
+        assert_eq!(
+            get_active_base_url(input).unwrap().as_deref(),
+            Some("https://azure.example/v1")
+        );
+    }
+
+    #[test]
+    fn get_active_base_url_handles_missing_values() {
+        assert_eq!(
+            get_active_base_url("base_url = 'https://top.example'\n")
+                .unwrap()
+                .as_deref(),
+            Some("https://top.example")
+        );
+        assert_eq!(
+            get_active_base_url("model_provider = \"openai\"\n").unwrap(),
+            None
+        );
+        assert_eq!(
+            get_active_base_url("[mcp_servers.a]\nbase_url = \"http://localhost\"\n").unwrap(),
+            None
+        );
+        assert!(get_active_base_url("model = ").is_err());
+    }
+
+    const LIVE_WITH_PROFILES: &str = r#"# 全局设置
+model = "gpt-5" # 默认模型
+model_provider = "old"
+approval_policy = "on-request"
+
+[profiles.work]
+model = "o3"
+model_provider = "azure"
+
+[profiles.personal]
+model = "gpt-5-mini"
+
+[model_providers.old]
+name = "old"
+base_url = "https://old.example/v1"
+
+[mcp_servers.fs]
+command = "npx"
+
+# 仅调试时开启
+[mcp_servers.fs.env]
+DEBUG = "1"
+
+[tui]
+notifications = true
+"#;
+
+    const RELAY_PROVIDER: &str = r#"model = "gpt-5-codex"
+model_provider = "relay"
+disable_response_storage = true
+
+[model_providers.relay]
+name = "relay"
+base_url = "https://relay.example/v1"
+wire_api = "responses"
+"#;
+
+    #[test]
+    fn merge_codex_config_keeps_unmanaged_tables_in_place() {
+        let merged = merge_codex_config(LIVE_WITH_PROFILES, RELAY_PROVIDER).unwrap();
+
+        assert!(merged.starts_with("# 全局设置\nmodel = \"gpt-5-codex\" # 默认模型\n"));
+        assert!(merged.contains("model_provider = \"relay\"\napproval_policy = \"on-request\"\n"));
+        // 非托管的表及注释逐字保留且顺序不变
+        let untouched = &LIVE_WITH_PROFILES[LIVE_WITH_PROFILES.find("[profiles.work]").unwrap()..];
+        assert!(merged.contains(untouched), "unexpected output:\n{merged}");
+        // 新供应商表追加在末尾，供应商的其他顶层键一并写入
+        assert!(merged.ends_with(
+            "\n[model_providers.relay]\nname = \"relay\"\nbase_url = \"https://relay.example/v1\"\nwire_api = \"responses\"\n"
+        ));
+        assert!(
+            merged.contains("approval_policy = \"on-request\"\ndisable_response_storage = true\n")
+        );
+
+        // 再次写入相同供应商不产生变化
+        assert_eq!(merge_codex_config(&merged, RELAY_PROVIDER).unwrap(), merged);
+    }
+
+    #[test]
+    fn merge_codex_config_overrides_every_provider_top_level_key() {
+        let live = "model_reasoning_effort = \"low\" # 调试\napproval_policy = \"never\"\n";
+        let provider = "model_reasoning_effort = \"high\"\nmodel_provider = \"relay\"\n";
+
+        let merged = merge_codex_config(live, provider).unwrap();
+        let table: toml::Table = toml::from_str(&merged).unwrap();
+
+        assert!(merged.starts_with("model_reasoning_effort = \"high\" # 调试\n"));
+        assert_eq!(table["approval_policy"].as_str(), Some("never"));
+        assert_eq!(table["model_provider"].as_str(), Some("relay"));
+    }
+
+    #[test]
+    fn merge_codex_config_replaces_active_provider_table_in_place() {
+        let live = "model_provider = \"relay\"\n\n[model_providers.relay]\nbase_url = \"https://stale.example/v1\"\n\n[profiles.work]\nmodel = \"o3\"\n";
+
+        let merged = merge_codex_config(live, RELAY_PROVIDER).unwrap();
+
+        assert!(!merged.contains("stale.example"));
+        let relay = merged.find("[model_providers.relay]").unwrap();
+        let profile = merged.find("[profiles.work]").unwrap();
+        assert!(relay < profile, "provider table should keep its position");
+        assert!(merged.ends_with("[profiles.work]\nmodel = \"o3\"\n"));
+    }
+
+    #[test]
+    fn merge_codex_config_backfill_reads_only_managed_parts() {
+        // 回填方向：把 Live 的托管部分写回供应商配置
+        let live = merge_codex_config(LIVE_WITH_PROFILES, RELAY_PROVIDER)
+            .unwrap()
+            .replace("https://relay.example/v1", "https://relay.example/v2");
+
+        let stored = merge_codex_config(RELAY_PROVIDER, &live).unwrap();
+        let table: toml::Table = toml::from_str(&stored).unwrap();
+
+        assert_eq!(table["model"].as_str(), Some("gpt-5-codex"));
+        assert_eq!(table["disable_response_storage"].as_bool(), Some(true));
+        assert_eq!(
+            table["model_providers"]["relay"]["base_url"].as_str(),
+            Some("https://relay.example/v2")
+        );
+        assert!(!table.contains_key("profiles"));
+        assert!(!table.contains_key("mcp_servers"));
+        assert!(!table.contains_key("tui"));
+    }
+
+    #[test]
+    fn merge_codex_config_handles_empty_and_builtin_sources() {
+        assert_eq!(
+            merge_codex_config("", RELAY_PROVIDER).unwrap(),
+            RELAY_PROVIDER
+        );
+        assert_eq!(
+            merge_codex_config("not = [valid", RELAY_PROVIDER).unwrap(),
+            RELAY_PROVIDER
+        );
+
+        // 官方登录：未设置 model / model_provider 时从 Live 中移除，其余表保留
+        let merged = merge_codex_config(LIVE_WITH_PROFILES, "").unwrap();
+        let table: toml::Table = toml::from_str(&merged).unwrap();
+        assert!(!table.contains_key("model"));
+        assert!(!table.contains_key("model_provider"));
+        assert!(table["model_providers"].get("old").is_some());
+        assert!(table.contains_key("profiles"));
+        assert!(merge_codex_config(LIVE_WITH_PROFILES, "model = ").is_err());
+    }
+
+    #[test]
+    fn format_codex_config_rejects_invalid_toml() {
+        assert!(format_codex_config("model = ").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! 后台任务管理命令
+//!
+//! 列出并取消 CC Switch 启动的后台任务（目前为 OAuth 登录会话及其本地回调监听），
+//! 用于登录卡住时无需重启应用即可释放回调端口。
+
+use serde::Serialize;
+
+use super::codex_auth::{cancel_codex_oauth_session, codex_oauth_background_tasks};
+use super::gemini_auth::{cancel_gemini_oauth_session, gemini_oauth_background_tasks};
+
+const CODEX_OAUTH_TASK_PREFIX: &str = "codex-oauth:";
+const GEMINI_OAUTH_TASK_PREFIX: &str = "gemini-oauth:";
+
+/// 后台任务类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundTaskKind {
+    CodexOauth,
+    GeminiOauth,
+}
+
+/// 后台任务信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTask {
+    /// 任务 ID（`<类型前缀><会话 ID>`），用于取消
+    pub id: String,
+    pub kind: BackgroundTaskKind,
+    /// 本地回调监听端口（127.0.0.1）
+    pub port: Option<u16>,
+    pub started_at: i64,
+    pub expires_at: i64,
+    /// 是否已收到授权回调、等待前端换取 Token
+    pub callback_received: bool,
+}
+
+impl BackgroundTask {
+    pub(crate) fn codex_oauth(
+        session_id: &str,
+        port: u16,
+        started_at: i64,
+        expires_at: i64,
+        callback_received: bool,
+    ) -> Self {
+        Self {
+            id: format!("{CODEX_OAUTH_TASK_PREFIX}{session_id}"),
+            kind: BackgroundTaskKind::CodexOauth,
+            port: Some(port),
+            started_at,
+            expires_at,
+            callback_received,
+        }
+    }
+
+    pub(crate) fn gemini_oauth(
+        session_id: &str,
+        port: u16,
+        started_at: i64,
+        expires_at: i64,
+        callback_received: bool,
+    ) -> Self {
+        Self {
+            id: format!("{GEMINI_OAUTH_TASK_PREFIX}{session_id}"),
+            kind: BackgroundTaskKind::GeminiOauth,
+            port: Some(port),
+            started_at,
+            expires_at,
+            callback_received,
+        }
+    }
+}
+
+/// 列出当前活动的后台任务（按启动时间排序）
+#[tauri::command]
+pub fn list_background_tasks() -> Vec<BackgroundTask> {
+    let mut tasks = codex_oauth_background_tasks();
+    tasks.extend(gemini_oauth_background_tasks());
+    tasks.sort_by_key(|task| task.started_at);
+    tasks
+}
+
+/// 取消后台任务，返回任务是否存在
+///
+/// OAuth 会话会被移除，对应的回调监听随之停止并释放端口。
+#[tauri::command]
+pub fn cancel_background_task(id: String) -> Result<bool, String> {
+    if let Some(session_id) = id.strip_prefix(CODEX_OAUTH_TASK_PREFIX) {
+        return Ok(cancel_codex_oauth_session(session_id));
+    }
+    if let Some(session_id) = id.strip_prefix(GEMINI_OAUTH_TASK_PREFIX) {
+        return Ok(cancel_gemini_oauth_session(session_id));
+    }
+    Err(format!("未知的后台任务: {id}"))
+}
@@ -8,7 +8,6 @@ use base64::engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD};
 use base64::Engine;
 use chrono::Utc;
 use once_cell::sync::Lazy;
-use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -17,9 +16,13 @@ use tauri::State;
 use url::Url;
 use uuid::Uuid;
 
+use super::background_tasks::BackgroundTask;
 use crate::app_config::AppType;
+use crate::codex_config::get_active_base_url;
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::services::codex_quota::CodexQuotaService;
+pub use crate::services::codex_quota::{CodexQuotaUsage, CodexQuotaWindow};
 use crate::store::AppState;
 
 const CODEX_OAUTH_CLIENT_ID: &str = "app_EMoamEEZ73f0CkXaXp7hrann";
@@ -62,26 +65,6 @@ pub struct CodexOauthPollResponse {
     pub error_description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CodexQuotaWindow {
-    pub used_percent: i64,
-    pub limit_window_seconds: i64,
-    pub reset_at: i64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CodexQuotaUsage {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub plan_type: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub five_hour: Option<CodexQuotaWindow>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub weekly: Option<CodexQuotaWindow>,
-    pub fetched_at: i64,
-}
-
 #[derive(Debug, Clone)]
 struct CodexPkceOauthSession {
     started_at: i64,
@@ -639,10 +622,65 @@ fn remove_oauth_session(session_id: &str) {
     }
 }
 
+/// 当前未过期的 OAuth 会话（每个会话对应一个 1455 端口的回调监听）
+pub(crate) fn codex_oauth_background_tasks() -> Vec<BackgroundTask> {
+    cleanup_expired_oauth_sessions();
+    let Ok(sessions) = CODEX_OAUTH_SESSIONS.lock() else {
+        return Vec::new();
+    };
+    sessions
+        .iter()
+        .map(|(id, session)| {
+            BackgroundTask::codex_oauth(
+                id,
+                CODEX_OAUTH_CALLBACK_PORT,
+                session.started_at,
+                session.expires_at,
+                session.auth_code.is_some(),
+            )
+        })
+        .collect()
+}
+
+/// 取消 OAuth 会话：通知回调服务器 `/cancel` 使其立即退出，并移除会话
+///
+/// 返回会话是否存在。
+pub(crate) fn cancel_codex_oauth_session(session_id: &str) -> bool {
+    let exists = CODEX_OAUTH_SESSIONS
+        .lock()
+        .map(|sessions| sessions.contains_key(session_id))
+        .unwrap_or(false);
+    if !exists {
+        return false;
+    }
+
+    if let Err(e) = request_callback_cancel() {
+        log::debug!("[CodexOAuth] 通知回调服务器取消失败（将直接移除会话）: {e}");
+    }
+    remove_oauth_session(session_id);
+    log::info!("[CodexOAuth] 已取消 OAuth 会话 {session_id}");
+    true
+}
+
+/// 向本地回调服务器发送 `/cancel` 请求（尽力而为，超时很短）
+fn request_callback_cancel() -> std::io::Result<()> {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], CODEX_OAUTH_CALLBACK_PORT));
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_millis(300))?;
+    stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+    stream.write_all(b"GET /cancel HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")?;
+    let mut response = [0u8; 256];
+    let _ = stream.read(&mut response)?;
+    Ok(())
+}
+
+/// 查询 Codex 官方额度
+///
+/// 缓存未过期时直接返回缓存值，`force_refresh` 为 true 时强制请求远端。
 #[tauri::command]
 pub async fn codex_get_quota(
     state: State<'_, AppState>,
     provider_id: String,
+    force_refresh: Option<bool>,
 ) -> Result<CodexQuotaUsage, String> {
     let provider = state
         .db
@@ -650,7 +688,32 @@ pub async fn codex_get_quota(
         .map_err(|e: AppError| e.to_string())?
         .ok_or_else(|| format!("未找到 Codex 供应商: {provider_id}"))?;
 
-    let (token, account_id, base_url) = extract_token_and_context(&provider)?;
+    CodexQuotaService::get_quota(&state.db, &provider, force_refresh.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取 Codex 额度缓存有效期（秒）
+#[tauri::command]
+pub async fn get_codex_quota_ttl(state: State<'_, AppState>) -> Result<u64, String> {
+    state
+        .db
+        .get_codex_quota_ttl_secs()
+        .map_err(|e| e.to_string())
+}
+
+/// 设置 Codex 额度缓存有效期（秒），同时作为后台刷新间隔；返回实际保存的值
+#[tauri::command]
+pub async fn set_codex_quota_ttl(state: State<'_, AppState>, seconds: u64) -> Result<u64, String> {
+    state
+        .db
+        .set_codex_quota_ttl_secs(seconds)
+        .map_err(|e| e.to_string())
+}
+
+/// 直接请求 ChatGPT 后端查询额度（不经过缓存）
+pub(crate) async fn fetch_codex_quota(provider: &Provider) -> Result<CodexQuotaUsage, String> {
+    let (token, account_id, base_url) = extract_token_and_context(provider)?;
     let (normalized_base_url, use_wham_path) = normalize_usage_base_url(base_url.as_deref());
     let usage_url = if use_wham_path {
         format!("{}/wham/usage", normalized_base_url)
@@ -742,7 +805,7 @@ fn extract_token_and_context(
     let base_url = settings
         .get("config")
         .and_then(Value::as_str)
-        .and_then(extract_base_url_from_toml);
+        .and_then(|config| get_active_base_url(config).ok().flatten());
 
     Ok((token, account_id, base_url))
 }
@@ -767,13 +830,6 @@ fn normalize_usage_base_url(base_url: Option<&str>) -> (String, bool) {
     (raw.to_string(), false)
 }
 
-fn extract_base_url_from_toml(config_toml: &str) -> Option<String> {
-    let re = Regex::new(r#"(?m)^\s*base_url\s*=\s*[\"']([^\"']+)[\"']\s*$"#).ok()?;
-    re.captures(config_toml)
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().trim().to_string())
-}
-
 fn parse_quota_payload(payload: &Value) -> CodexQuotaUsage {
     let now = Utc::now().timestamp();
 
@@ -965,7 +1021,11 @@ async fn fetch_user_email(client: &Client, access_token: &str) -> Result<Option<
 
 #[cfg(test)]
 mod tests {
-    use super::{build_auth_url, parse_query_params};
+    use super::{
+        build_auth_url, cancel_codex_oauth_session, codex_oauth_background_tasks,
+        parse_query_params, CodexPkceOauthSession, CODEX_OAUTH_SESSIONS,
+    };
+    use chrono::Utc;
 
     #[test]
     fn parse_query_params_decodes_values() {
@@ -987,4 +1047,37 @@ mod tests {
         assert!(url.contains("state=state-token"));
         assert!(url.contains("redirect_uri=http%3A%2F%2Flocalhost%3A1455%2Fauth%2Fcallback"));
     }
+
+    #[test]
+    fn cancel_background_oauth_session_removes_it() {
+        let session_id = "test-cancel-session";
+        let now = Utc::now().timestamp();
+        CODEX_OAUTH_SESSIONS.lock().unwrap().insert(
+            session_id.to_string(),
+            CodexPkceOauthSession {
+                started_at: now,
+                expires_at: now + 60,
+                state_token: "state".to_string(),
+                code_verifier: "verifier".to_string(),
+                redirect_uri: "http://localhost:1455/auth/callback".to_string(),
+                auth_url: "https://auth.example.com".to_string(),
+                auth_code: None,
+            },
+        );
+
+        let task_id = format!("codex-oauth:{session_id}");
+        let task = codex_oauth_background_tasks()
+            .into_iter()
+            .find(|task| task.id == task_id)
+            .expect("session listed as background task");
+        assert_eq!(task.port, Some(1455));
+        assert!(!task.callback_received);
+
+        assert!(cancel_codex_oauth_session(session_id));
+        assert!(!CODEX_OAUTH_SESSIONS
+            .lock()
+            .unwrap()
+            .contains_key(session_id));
+        assert!(!cancel_codex_oauth_session(session_id));
+    }
 }
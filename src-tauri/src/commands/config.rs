@@ -31,8 +31,9 @@ fn invalid_json_format_error(error: serde_json::Error) -> String {
 
 #[tauri::command]
 pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
-    match AppType::from_str(&app).map_err(|e| e.to_string())? {
-        AppType::Claude => Ok(config::get_claude_config_status()),
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let mut status = match app_type {
+        AppType::Claude => config::get_claude_config_status(),
         AppType::Codex => {
             let auth_path = codex_config::get_codex_auth_path();
             let exists = auth_path.exists();
@@ -40,7 +41,12 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
                 .to_string_lossy()
                 .to_string();
 
-            Ok(ConfigStatus { exists, path })
+            ConfigStatus {
+                exists,
+                path,
+                override_dir: None,
+                managed: None,
+            }
         }
         AppType::Gemini => {
             let env_path = crate::gemini_config::get_gemini_env_path();
@@ -49,7 +55,12 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
                 .to_string_lossy()
                 .to_string();
 
-            Ok(ConfigStatus { exists, path })
+            ConfigStatus {
+                exists,
+                path,
+                override_dir: None,
+                managed: None,
+            }
         }
         AppType::OpenCode => {
             let config_path = crate::opencode_config::get_opencode_config_path();
@@ -58,9 +69,18 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
                 .to_string_lossy()
                 .to_string();
 
-            Ok(ConfigStatus { exists, path })
+            ConfigStatus {
+                exists,
+                path,
+                override_dir: None,
+                managed: None,
+            }
         }
-    }
+    };
+
+    status.override_dir = settings::get_override_dir(&app_type)
+        .map(|dir| config::inspect_override_dir(&app_type, &dir));
+    Ok(status)
 }
 
 /// 获取 Claude Code 配置文件路径
@@ -270,3 +290,82 @@ pub async fn extract_common_config_snippet(
     crate::services::provider::ProviderService::extract_common_config_snippet(&state, app)
         .map_err(|e| e.to_string())
 }
+
+/// 获取指定应用的命名配置片段列表
+#[tauri::command]
+pub async fn list_config_snippets(
+    app_type: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<Vec<crate::database::ConfigSnippet>, String> {
+    let app = AppType::from_str(&app_type).map_err(|e| e.to_string())?;
+    state
+        .db
+        .list_config_snippets(app.as_str())
+        .map_err(|e| e.to_string())
+}
+
+/// 保存命名配置片段（同名覆盖）
+#[tauri::command]
+pub async fn save_config_snippet(
+    app_type: String,
+    name: String,
+    content: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<(), String> {
+    let app = AppType::from_str(&app_type).map_err(|e| e.to_string())?;
+    crate::services::provider::ProviderService::save_config_snippet(&state, app, &name, &content)
+        .map_err(|e| e.to_string())
+}
+
+/// 删除命名配置片段
+#[tauri::command]
+pub async fn delete_config_snippet(
+    app_type: String,
+    name: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<bool, String> {
+    let app = AppType::from_str(&app_type).map_err(|e| e.to_string())?;
+    state
+        .db
+        .delete_named_config_snippet(app.as_str(), &name)
+        .map_err(|e| e.to_string())
+}
+
+/// 将命名配置片段合并进指定供应商（冲突字段以片段为准），返回配置是否变化
+#[tauri::command]
+pub async fn apply_snippet_to_provider(
+    app_type: String,
+    provider_id: String,
+    snippet_name: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<bool, String> {
+    let app = AppType::from_str(&app_type).map_err(|e| e.to_string())?;
+    crate::services::provider::ProviderService::apply_snippet_to_provider(
+        &state,
+        app,
+        &provider_id,
+        &snippet_name,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 将通用配置片段合并进该应用的所有供应商（冲突字段以片段为准），返回发生变化的供应商数量
+#[tauri::command]
+pub async fn apply_common_config_to_all(
+    app_type: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<usize, String> {
+    let app = AppType::from_str(&app_type).map_err(|e| e.to_string())?;
+    crate::services::provider::ProviderService::apply_common_snippet_to_all(&state, app)
+        .map_err(|e| e.to_string())
+}
+
+/// 格式化 Codex config.toml
+///
+/// 返回按键排序后的 TOML 以及 model_provider 引用缺失等检查警告，供编辑器“格式化”按钮使用。
+#[tauri::command]
+pub async fn format_codex_config(
+    tomlString: String,
+) -> Result<codex_config::CodexConfigFormat, String> {
+    codex_config::format_codex_config(&tomlString).map_err(|e| e.to_string())
+}
@@ -1,6 +1,9 @@
+use crate::database::MergeStrategy;
 use crate::deeplink::{
-    import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_from_deeplink,
-    import_skill_from_deeplink, parse_deeplink_url, DeepLinkImportRequest,
+    import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_batch_from_deeplink,
+    import_provider_from_deeplink, import_provider_from_deeplink_with_strategy,
+    import_skill_from_deeplink, parse_deeplink_url, validate_deeplink_url, DeepLinkError,
+    DeepLinkImportRequest, DeepLinkValidation,
 };
 use crate::store::AppState;
 use tauri::State;
@@ -22,6 +25,16 @@ pub fn merge_deeplink_config(
     crate::deeplink::parse_and_merge_config(&request).map_err(|e| e.to_string())
 }
 
+/// Validate a deep link URL without importing it
+///
+/// 执行解析、配置合并与供应商校验，返回将要导入的供应商概要与警告；
+/// 失败时返回带 `reason` 原因码的结构化错误。
+#[tauri::command]
+pub fn validate_deeplink(url: String) -> Result<DeepLinkValidation, DeepLinkError> {
+    log::info!("Validating deep link URL");
+    validate_deeplink_url(&url)
+}
+
 /// Import a provider from a deep link request (legacy, kept for compatibility)
 #[tauri::command]
 pub fn import_from_deeplink(
@@ -42,20 +55,25 @@ pub fn import_from_deeplink(
 }
 
 /// Import resource from a deep link request (unified handler)
+///
+/// `merge_strategy` 决定同名供应商的处理方式；未指定时始终新建（原有行为）。
 #[tauri::command]
 pub async fn import_from_deeplink_unified(
     state: State<'_, AppState>,
     request: DeepLinkImportRequest,
+    merge_strategy: Option<MergeStrategy>,
 ) -> Result<serde_json::Value, String> {
     log::info!("Importing {} resource from deep link", request.resource);
 
     match request.resource.as_str() {
         "provider" => {
-            let provider_id =
-                import_provider_from_deeplink(&state, request).map_err(|e| e.to_string())?;
+            let outcome =
+                import_provider_from_deeplink_with_strategy(&state, request, merge_strategy)
+                    .map_err(|e| e.to_string())?;
             Ok(serde_json::json!({
                 "type": "provider",
-                "id": provider_id
+                "id": outcome.id,
+                "action": outcome.action
             }))
         }
         "prompt" => {
@@ -76,6 +94,15 @@ pub async fn import_from_deeplink_unified(
                 "failed": result.failed
             }))
         }
+        "batch" => {
+            let result = import_provider_batch_from_deeplink(&state, request, merge_strategy)
+                .map_err(|e| e.to_string())?;
+            Ok(serde_json::json!({
+                "type": "batch",
+                "providers": result.providers,
+                "failed": result.failed
+            }))
+        }
         "skill" => {
             let skill_key =
                 import_skill_from_deeplink(&state, request).map_err(|e| e.to_string())?;
@@ -1,6 +1,7 @@
 use crate::services::env_checker::{check_env_conflicts as check_conflicts, EnvConflict};
 use crate::services::env_manager::{
-    delete_env_vars as delete_vars, restore_from_backup, BackupInfo,
+    auto_resolve_env_conflicts as auto_resolve, delete_env_vars as delete_vars,
+    restore_from_backup, BackupInfo, EnvAutoResolveReport,
 };
 
 /// Check environment variable conflicts for a specific app
@@ -20,3 +21,9 @@ pub fn delete_env_vars(conflicts: Vec<EnvConflict>) -> Result<BackupInfo, String
 pub fn restore_env_backup(backup_path: String) -> Result<(), String> {
     restore_from_backup(backup_path)
 }
+
+/// Comment out shell-profile exports that override the app's managed config (with backup)
+#[tauri::command]
+pub fn auto_resolve_env_conflicts(app: String) -> Result<EnvAutoResolveReport, String> {
+    auto_resolve(&app)
+}
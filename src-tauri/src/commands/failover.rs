@@ -161,7 +161,8 @@ pub async fn set_auto_failover_enabled(
     }
 
     // 刷新托盘菜单，确保状态同步
-    if let Ok(new_menu) = crate::tray::create_tray_menu(&app, &state) {
+    let proxy_running = state.proxy_service.is_running().await;
+    if let Ok(new_menu) = crate::tray::create_tray_menu(&app, &state, proxy_running) {
         if let Some(tray) = app.tray_by_id("main") {
             let _ = tray.set_menu(Some(new_menu));
         }
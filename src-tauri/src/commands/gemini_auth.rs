@@ -13,6 +13,8 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
+use super::background_tasks::BackgroundTask;
+
 const GOOGLE_OAUTH_CLIENT_ID: &str =
     "1071006060591-tmhssin2h21lcre235vtolojh4g403ep.apps.googleusercontent.com";
 const GOOGLE_OAUTH_CLIENT_SECRET: &str = "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";
@@ -591,3 +593,37 @@ fn remove_oauth_session(session_id: &str) {
         sessions.remove(session_id);
     }
 }
+
+/// 当前未过期的 OAuth 会话（每个会话对应一个 1456 端口的回调监听）
+pub(crate) fn gemini_oauth_background_tasks() -> Vec<BackgroundTask> {
+    cleanup_expired_oauth_sessions();
+    let Ok(sessions) = GOOGLE_OAUTH_SESSIONS.lock() else {
+        return Vec::new();
+    };
+    sessions
+        .iter()
+        .map(|(id, session)| {
+            BackgroundTask::gemini_oauth(
+                id,
+                GOOGLE_OAUTH_CALLBACK_PORT,
+                session.started_at,
+                session.expires_at,
+                session.auth_code.is_some(),
+            )
+        })
+        .collect()
+}
+
+/// 取消 OAuth 会话，返回会话是否存在
+///
+/// 回调服务器轮询时发现会话已移除即退出并释放端口。
+pub(crate) fn cancel_gemini_oauth_session(session_id: &str) -> bool {
+    let removed = GOOGLE_OAUTH_SESSIONS
+        .lock()
+        .map(|mut sessions| sessions.remove(session_id).is_some())
+        .unwrap_or(false);
+    if removed {
+        log::info!("[GeminiOAuth] 已取消 OAuth 会话 {session_id}");
+    }
+    removed
+}
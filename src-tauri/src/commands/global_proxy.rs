@@ -3,10 +3,14 @@
 //! 提供获取、设置和测试全局代理的 Tauri 命令。
 
 use crate::proxy::http_client;
+use crate::proxy::system_proxy::{self, OsProxyDetector};
+use crate::proxy::types::{GlobalNetworkConfig, ProxyMode};
 use crate::store::AppState;
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
-use std::net::{Ipv4Addr, SocketAddrV4, TcpStream};
+use std::net::{Ipv4Addr, SocketAddr};
 use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// 获取全局代理 URL
 ///
@@ -31,6 +35,8 @@ pub fn get_global_proxy_url(state: tauri::State<'_, AppState>) -> Result<Option<
 ///
 /// 执行顺序：先验证 → 写 DB → 再应用
 /// 这样确保 DB 写失败时不会出现运行态与持久化不一致的问题
+///
+/// 仅在 manual 模式下立即生效；其他模式下只保存，切回 manual 时使用。
 #[tauri::command]
 pub fn set_global_proxy_url(state: tauri::State<'_, AppState>, url: String) -> Result<(), String> {
     // 调试：显示接收到的 URL 信息（不包含敏感内容）
@@ -57,6 +63,13 @@ pub fn set_global_proxy_url(state: tauri::State<'_, AppState>, url: String) -> R
         .map_err(|e| e.to_string())?;
 
     // 3. DB 写入成功后再应用到运行态
+    if http_client::current_proxy_mode() != ProxyMode::Manual {
+        log::info!(
+            "[GlobalProxy] Proxy URL saved; not applied in '{}' mode",
+            http_client::current_proxy_mode().as_str()
+        );
+        return Ok(());
+    }
     http_client::apply_proxy(url_opt)?;
 
     log::info!(
@@ -69,6 +82,76 @@ pub fn set_global_proxy_url(state: tauri::State<'_, AppState>, url: String) -> R
     Ok(())
 }
 
+/// 获取全局出站代理模式（manual / system / none）
+#[tauri::command]
+pub fn get_proxy_mode(state: tauri::State<'_, AppState>) -> Result<ProxyMode, String> {
+    state.db.get_proxy_mode().map_err(|e| e.to_string())
+}
+
+/// 设置全局出站代理模式
+///
+/// 按新模式计算实际代理 URL（system 模式会立即检测系统代理），
+/// 与 set_global_proxy_url 相同：先验证 → 写 DB → 再重建全局客户端。
+#[tauri::command]
+pub fn set_proxy_mode(
+    state: tauri::State<'_, AppState>,
+    mode: ProxyMode,
+) -> Result<UpstreamProxyStatus, String> {
+    let manual_url = state.db.get_global_proxy_url().map_err(|e| e.to_string())?;
+    let proxy_url = system_proxy::resolve_proxy_url(mode, manual_url, &OsProxyDetector);
+
+    http_client::validate_proxy_mode(mode, proxy_url.as_deref())?;
+    state.db.set_proxy_mode(mode).map_err(|e| e.to_string())?;
+    http_client::apply_proxy_mode(mode, proxy_url.as_deref())?;
+
+    log::info!("[GlobalProxy] Proxy mode set to '{}'", mode.as_str());
+    Ok(get_upstream_proxy_status())
+}
+
+/// 重新检测系统代理并重建全局客户端
+///
+/// 仅在 system 模式下生效（例如 macOS 切换网络位置后调用）；其他模式直接返回当前状态。
+#[tauri::command]
+pub fn refresh_system_proxy(
+    state: tauri::State<'_, AppState>,
+) -> Result<UpstreamProxyStatus, String> {
+    let mode = state.db.get_proxy_mode().map_err(|e| e.to_string())?;
+    if mode == ProxyMode::System {
+        let proxy_url = system_proxy::resolve_proxy_url(mode, None, &OsProxyDetector);
+        http_client::apply_proxy_mode(mode, proxy_url.as_deref())?;
+    }
+    Ok(get_upstream_proxy_status())
+}
+
+/// 获取全局出站网络配置（DNS 覆盖 / IPv6 优先）
+#[tauri::command]
+pub fn get_global_network_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<GlobalNetworkConfig, String> {
+    state
+        .db
+        .get_global_network_config()
+        .map_err(|e| e.to_string())
+}
+
+/// 设置全局出站网络配置
+///
+/// 与 set_global_proxy_url 相同：先验证 → 写 DB → 再重建全局客户端
+#[tauri::command]
+pub fn set_global_network_config(
+    state: tauri::State<'_, AppState>,
+    config: GlobalNetworkConfig,
+) -> Result<(), String> {
+    http_client::validate_network_config(&config)?;
+
+    state
+        .db
+        .set_global_network_config(&config)
+        .map_err(|e| e.to_string())?;
+
+    http_client::apply_network_config(config)
+}
+
 /// 代理测试结果
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -85,6 +168,7 @@ pub struct ProxyTestResult {
 ///
 /// 通过指定的代理 URL 发送测试请求，返回连接结果和延迟。
 /// 使用多个测试目标，任一成功即认为代理可用。
+/// 测试客户端会应用当前的 DNS 覆盖与 IPv6 优先设置。
 #[tauri::command]
 pub async fn test_proxy_url(url: String) -> Result<ProxyTestResult, String> {
     if url.trim().is_empty() {
@@ -94,14 +178,7 @@ pub async fn test_proxy_url(url: String) -> Result<ProxyTestResult, String> {
     let start = Instant::now();
 
     // 构建带代理的临时客户端
-    let proxy = reqwest::Proxy::all(&url).map_err(|e| format!("Invalid proxy URL: {e}"))?;
-
-    let client = reqwest::Client::builder()
-        .proxy(proxy)
-        .timeout(std::time::Duration::from_secs(10))
-        .connect_timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to build client: {e}"))?;
+    let client = http_client::build_test_client(&url, Duration::from_secs(10))?;
 
     // 使用多个测试目标，提高兼容性
     // 优先使用 httpbin（专门用于 HTTP 测试），回退到其他公共端点
@@ -159,11 +236,12 @@ pub async fn test_proxy_url(url: String) -> Result<ProxyTestResult, String> {
 
 /// 获取当前出站代理状态
 ///
-/// 返回当前是否启用了出站代理以及代理 URL。
+/// 返回当前代理模式、是否启用了出站代理以及实际生效的代理 URL。
 #[tauri::command]
 pub fn get_upstream_proxy_status() -> UpstreamProxyStatus {
     let url = http_client::get_current_proxy_url();
     UpstreamProxyStatus {
+        mode: http_client::current_proxy_mode(),
         enabled: url.is_some(),
         proxy_url: url,
     }
@@ -173,9 +251,11 @@ pub fn get_upstream_proxy_status() -> UpstreamProxyStatus {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpstreamProxyStatus {
+    /// 当前代理模式
+    pub mode: ProxyMode,
     /// 是否启用代理
     pub enabled: bool,
-    /// 代理 URL
+    /// 实际生效的代理 URL（system 模式下为检测结果）
     pub proxy_url: Option<String>,
 }
 
@@ -189,59 +269,415 @@ pub struct DetectedProxy {
     pub proxy_type: String,
     /// 端口
     pub port: u16,
+    /// 监听该端口的进程名（通过进程扫描发现时）
+    pub process: Option<String>,
+    /// 经该代理请求探测地址的耗时（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 是否能通过该代理完成真实的 HTTPS 请求
+    pub works: bool,
+    /// 探测失败原因
+    pub error: Option<String>,
 }
 
-/// 常见代理端口配置
-/// 格式：(端口, 主要类型, 是否同时支持 http 和 socks5)
-/// 对于 mixed 端口，会同时返回两种协议供用户选择
-const PROXY_PORTS: &[(u16, &str, bool)] = &[
-    (7890, "http", true),     // Clash (mixed mode)
-    (7891, "socks5", false),  // Clash SOCKS only
-    (1080, "socks5", false),  // 通用 SOCKS5
-    (8080, "http", false),    // 通用 HTTP
-    (8888, "http", false),    // Charles/Fiddler
-    (3128, "http", false),    // Squid
-    (10808, "socks5", false), // V2Ray SOCKS
-    (10809, "http", false),   // V2Ray HTTP
+/// 默认探测的常见代理端口
+const DEFAULT_PROXY_PORTS: &[u16] = &[
+    7890,  // Clash (mixed)
+    7891,  // Clash SOCKS
+    7892,  // Clash redir / mixed
+    7897,  // Clash Verge (mixed)
+    1080,  // Shadowsocks / 通用 SOCKS5
+    1087,  // ShadowsocksX-NG HTTP
+    10808, // V2Ray SOCKS
+    10809, // V2Ray HTTP
+    20170, // v2rayA SOCKS
+    20171, // v2rayA HTTP
+    2080,  // sing-box (mixed)
+    6152,  // Surge HTTP
+    6153,  // Surge SOCKS5
+    8080,  // 通用 HTTP
+    8888,  // Charles/Fiddler
+    3128,  // Squid
+];
+
+/// 常见代理软件的进程名关键字（小写匹配）
+const PROXY_PROCESS_KEYWORDS: &[&str] = &[
+    "clash",
+    "mihomo",
+    "verge",
+    "v2ray",
+    "xray",
+    "sing-box",
+    "ss-local",
+    "sslocal",
+    "shadowsocks",
+    "trojan",
+    "hysteria",
+    "naive",
+    "surge",
+    "qv2ray",
+    "nekoray",
+    "nekobox",
+    "privoxy",
 ];
 
+/// 同时进行的探测数量上限
+const MAX_CONCURRENT_PROBES: usize = 8;
+/// 端口连通性检测超时
+const PORT_CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+/// 握手超时
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+/// 真实 HTTPS 请求超时
+const PROBE_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+/// 真实请求探测地址（响应体为空）
+const PROBE_URL: &str = "https://www.gstatic.com/generate_204";
+/// HTTP CONNECT 握手的目标
+const PROBE_CONNECT_TARGET: &str = "www.gstatic.com:443";
+
 /// 扫描本地代理
 ///
-/// 检测常见端口是否有代理服务在运行。
-/// 使用异步任务避免阻塞 UI 线程。
+/// 1. 合并默认端口、调用方传入的端口以及从代理软件进程解析出的监听端口；
+/// 2. 对开放端口分别尝试 HTTP CONNECT 与 SOCKS5 握手；
+/// 3. 对握手成功的候选通过该代理发起真实 HTTPS 请求（3 秒超时）验证可用性。
+///
+/// 探测并发执行（上限 MAX_CONCURRENT_PROBES），结果按“可用优先、延迟升序”排序。
 #[tauri::command]
-pub async fn scan_local_proxies() -> Vec<DetectedProxy> {
-    // 使用 spawn_blocking 避免阻塞主线程
-    tokio::task::spawn_blocking(|| {
-        let mut found = Vec::new();
-
-        for &(port, primary_type, is_mixed) in PROXY_PORTS {
-            let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
-            if TcpStream::connect_timeout(&addr.into(), Duration::from_millis(100)).is_ok() {
-                // 添加主要类型
-                found.push(DetectedProxy {
-                    url: format!("{primary_type}://127.0.0.1:{port}"),
-                    proxy_type: primary_type.to_string(),
-                    port,
-                });
-                // 对于 mixed 端口，同时添加另一种协议
-                if is_mixed {
-                    let alt_type = if primary_type == "http" {
-                        "socks5"
-                    } else {
-                        "http"
-                    };
-                    found.push(DetectedProxy {
-                        url: format!("{alt_type}://127.0.0.1:{port}"),
-                        proxy_type: alt_type.to_string(),
-                        port,
-                    });
+pub async fn scan_local_proxies(ports: Option<Vec<u16>>) -> Vec<DetectedProxy> {
+    let process_ports = tokio::task::spawn_blocking(discover_process_ports)
+        .await
+        .unwrap_or_default();
+
+    let own_port = http_client::get_proxy_port();
+    let mut targets: Vec<(u16, Option<String>)> = Vec::new();
+    let mut push_target = |port: u16, process: Option<String>| {
+        if port == 0 || port == own_port {
+            return;
+        }
+        match targets.iter_mut().find(|(p, _)| *p == port) {
+            Some((_, existing)) => {
+                if existing.is_none() {
+                    *existing = process;
                 }
             }
+            None => targets.push((port, process)),
         }
+    };
+    for &port in DEFAULT_PROXY_PORTS {
+        push_target(port, None);
+    }
+    for port in ports.unwrap_or_default() {
+        push_target(port, None);
+    }
+    for (port, process) in process_ports {
+        push_target(port, Some(process));
+    }
 
-        found
-    })
-    .await
-    .unwrap_or_default()
+    let mut found: Vec<DetectedProxy> = stream::iter(targets)
+        .map(|(port, process)| probe_port(port, process))
+        .buffer_unordered(MAX_CONCURRENT_PROBES)
+        .flat_map(stream::iter)
+        .collect()
+        .await;
+
+    sort_detected(&mut found);
+    found
+}
+
+/// 可用优先，其次按延迟升序，最后按端口与类型排序保证稳定
+fn sort_detected(found: &mut [DetectedProxy]) {
+    found.sort_by(|a, b| {
+        b.works
+            .cmp(&a.works)
+            .then_with(|| {
+                a.latency_ms
+                    .unwrap_or(u64::MAX)
+                    .cmp(&b.latency_ms.unwrap_or(u64::MAX))
+            })
+            .then_with(|| a.port.cmp(&b.port))
+            .then_with(|| a.proxy_type.cmp(&b.proxy_type))
+    });
+}
+
+/// 探测单个端口：开放后分别尝试两种握手，再对每种成功的协议做真实请求
+async fn probe_port(port: u16, process: Option<String>) -> Vec<DetectedProxy> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let open = tokio::time::timeout(PORT_CONNECT_TIMEOUT, tokio::net::TcpStream::connect(addr))
+        .await
+        .is_ok_and(|r| r.is_ok());
+    if !open {
+        return Vec::new();
+    }
+
+    let (http_ok, socks_ok) = tokio::join!(http_connect_handshake(addr), socks5_handshake(addr));
+
+    let mut results = Vec::new();
+    for (proxy_type, ok) in [("http", http_ok), ("socks5", socks_ok)] {
+        if !ok {
+            continue;
+        }
+        let url = format!("{proxy_type}://127.0.0.1:{port}");
+        let (latency_ms, error) = match probe_through_proxy(&url).await {
+            Ok(latency) => (Some(latency), None),
+            Err(e) => (None, Some(e)),
+        };
+        results.push(DetectedProxy {
+            url,
+            proxy_type: proxy_type.to_string(),
+            port,
+            process: process.clone(),
+            latency_ms,
+            works: error.is_none(),
+            error,
+        });
+    }
+    results
+}
+
+/// HTTP CONNECT 握手：收到任意 HTTP 状态行即认为是 HTTP 代理（含 407 需要认证）
+async fn http_connect_handshake(addr: SocketAddr) -> bool {
+    let handshake = async {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.ok()?;
+        let request = format!(
+            "CONNECT {PROBE_CONNECT_TARGET} HTTP/1.1\r\nHost: {PROBE_CONNECT_TARGET}\r\n\r\n"
+        );
+        stream.write_all(request.as_bytes()).await.ok()?;
+        let mut buf = [0u8; 12];
+        let n = stream.read(&mut buf).await.ok()?;
+        Some(buf[..n].starts_with(b"HTTP/1."))
+    };
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
+
+/// SOCKS5 握手：发送“无认证”方法协商，期望返回 0x05 0x00
+async fn socks5_handshake(addr: SocketAddr) -> bool {
+    let handshake = async {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.ok()?;
+        stream.write_all(&[0x05, 0x01, 0x00]).await.ok()?;
+        let mut buf = [0u8; 2];
+        stream.read_exact(&mut buf).await.ok()?;
+        Some(buf == [0x05, 0x00])
+    };
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
+
+/// 通过代理发起真实 HTTPS 请求，返回耗时（毫秒）
+async fn probe_through_proxy(proxy_url: &str) -> Result<u64, String> {
+    let client = http_client::build_test_client(proxy_url, PROBE_REQUEST_TIMEOUT)?;
+    let start = Instant::now();
+    let resp = client
+        .get(PROBE_URL)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.status().is_server_error() || resp.status().as_u16() == 407 {
+        return Err(format!("HTTP {}", resp.status().as_u16()));
+    }
+    Ok(start.elapsed().as_millis() as u64)
+}
+
+/// 从正在运行的代理软件进程中解析本地监听端口
+///
+/// - macOS / Linux：`lsof -nP -iTCP -sTCP:LISTEN`
+/// - Windows：`netstat -ano -p TCP` + `tasklist /FO CSV /NH`
+///
+/// 命令不存在或执行失败时返回空列表。
+fn discover_process_ports() -> Vec<(u16, String)> {
+    #[cfg(unix)]
+    {
+        let Ok(output) = std::process::Command::new("lsof")
+            .args(["-nP", "-iTCP", "-sTCP:LISTEN"])
+            .output()
+        else {
+            return Vec::new();
+        };
+        parse_lsof_listeners(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let run = |program: &str, args: &[&str]| {
+            std::process::Command::new(program)
+                .args(args)
+                .creation_flags(CREATE_NO_WINDOW)
+                .output()
+                .ok()
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        };
+        let (Some(netstat), Some(tasklist)) = (
+            run("netstat", &["-ano", "-p", "TCP"]),
+            run("tasklist", &["/FO", "CSV", "/NH"]),
+        ) else {
+            return Vec::new();
+        };
+        parse_windows_listeners(&netstat, &tasklist)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        Vec::new()
+    }
+}
+
+fn is_proxy_process(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    PROXY_PROCESS_KEYWORDS.iter().any(|k| lower.contains(k))
+}
+
+/// 监听地址是否可从本机访问（loopback 或通配地址）
+fn is_local_listen_addr(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    matches!(host, "*" | "0.0.0.0" | "::" | "localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// 解析 `host:port` 形式的监听地址
+fn parse_listen_port(addr: &str) -> Option<u16> {
+    let (host, port) = addr.rsplit_once(':')?;
+    if !is_local_listen_addr(host) {
+        return None;
+    }
+    port.parse().ok()
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_lsof_listeners(output: &str) -> Vec<(u16, String)> {
+    let mut result = Vec::new();
+    for line in output.lines().skip(1) {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        // COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME (LISTEN)
+        let (Some(command), Some(name)) = (columns.first(), columns.iter().rev().nth(1)) else {
+            continue;
+        };
+        // lsof 会将进程名中的空格转义为 \x20
+        let command = command.replace("\\x20", " ");
+        if !is_proxy_process(&command) {
+            continue;
+        }
+        if let Some(port) = parse_listen_port(name) {
+            if !result.iter().any(|(p, _)| *p == port) {
+                result.push((port, command));
+            }
+        }
+    }
+    result
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_windows_listeners(netstat: &str, tasklist: &str) -> Vec<(u16, String)> {
+    // tasklist CSV: "clash-verge.exe","1234","Console","1","12,345 K"
+    let processes: std::collections::HashMap<&str, &str> = tasklist
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split("\",\"");
+            let name = fields.next()?.trim_start_matches('"');
+            let pid = fields.next()?;
+            Some((pid, name))
+        })
+        .collect();
+
+    let mut result = Vec::new();
+    for line in netstat.lines() {
+        // TCP    127.0.0.1:7890    0.0.0.0:0    LISTENING    1234
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 5 || !columns[0].eq_ignore_ascii_case("TCP") {
+            continue;
+        }
+        if !columns[3].eq_ignore_ascii_case("LISTENING") {
+            continue;
+        }
+        let Some(name) = processes.get(columns[4]) else {
+            continue;
+        };
+        if !is_proxy_process(name) {
+            continue;
+        }
+        if let Some(port) = parse_listen_port(columns[1]) {
+            if !result.iter().any(|(p, _)| *p == port) {
+                result.push((port, name.to_string()));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lsof_listeners_keeps_local_proxy_processes() {
+        let output = "\
+COMMAND     PID USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+verge-mih  1234 me    10u  IPv4 0x1234567890abcdef      0t0  TCP 127.0.0.1:7897 (LISTEN)
+verge-mih  1234 me    11u  IPv6 0x1234567890abcdef      0t0  TCP [::1]:7897 (LISTEN)
+sing-box   2345 me     7u  IPv6 0x1234567890abcdef      0t0  TCP *:2081 (LISTEN)
+node       3456 me    20u  IPv4 0x1234567890abcdef      0t0  TCP 127.0.0.1:3000 (LISTEN)
+xray       4567 me     5u  IPv4 0x1234567890abcdef      0t0  TCP 192.168.1.2:10808 (LISTEN)
+";
+        assert_eq!(
+            parse_lsof_listeners(output),
+            vec![
+                (7897, "verge-mih".to_string()),
+                (2081, "sing-box".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_windows_listeners_joins_pid_with_process_name() {
+        let netstat = "\
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1000
+  TCP    127.0.0.1:7890         0.0.0.0:0              LISTENING       1234
+  TCP    127.0.0.1:7890         127.0.0.1:51000        ESTABLISHED     1234
+  TCP    [::1]:10809            [::]:0                 LISTENING       2345
+";
+        let tasklist = "\
+\"svchost.exe\",\"1000\",\"Services\",\"0\",\"12,000 K\"
+\"clash-verge.exe\",\"1234\",\"Console\",\"1\",\"80,000 K\"
+\"v2rayN.exe\",\"2345\",\"Console\",\"1\",\"40,000 K\"
+";
+        assert_eq!(
+            parse_windows_listeners(netstat, tasklist),
+            vec![
+                (7890, "clash-verge.exe".to_string()),
+                (10809, "v2rayN.exe".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_detected_puts_working_fastest_first() {
+        let entry = |port: u16, latency_ms: Option<u64>, works: bool| DetectedProxy {
+            url: format!("http://127.0.0.1:{port}"),
+            proxy_type: "http".to_string(),
+            port,
+            process: None,
+            latency_ms,
+            works,
+            error: None,
+        };
+        let mut found = vec![
+            entry(1080, None, false),
+            entry(7890, Some(300), true),
+            entry(10809, Some(120), true),
+        ];
+        sort_detected(&mut found);
+        let ports: Vec<u16> = found.iter().map(|p| p.port).collect();
+        assert_eq!(ports, vec![10809, 7890, 1080]);
+    }
 }
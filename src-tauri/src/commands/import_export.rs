@@ -1,12 +1,20 @@
 #![allow(non_snake_case)]
 
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tauri::State;
 use tauri_plugin_dialog::DialogExt;
 
+use crate::app_config::MultiAppConfig;
+use crate::config::{read_json_file, write_json_file};
+use crate::database::{
+    BundleImportResult, ConfigBundle, ImportApplyResult, ImportPreview, ImportResolution,
+    JsonReimportReport, JsonReimportSection, MergeStrategy,
+};
 use crate::error::AppError;
 use crate::services::provider::ProviderService;
+use crate::services::{ExternalFormat, ExternalImportResult};
 use crate::store::AppState;
 
 /// 导出数据库为 SQL 备份
@@ -30,17 +38,63 @@ pub async fn export_config_to_file(
     .map_err(|e: AppError| e.to_string())
 }
 
+/// 导出数据库为加密的 SQL 备份
+#[tauri::command]
+pub async fn export_config_encrypted(
+    #[allow(non_snake_case)] filePath: String,
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let target_path = PathBuf::from(&filePath);
+        db.export_sql_encrypted(&target_path, &passphrase)?;
+        Ok::<_, AppError>(json!({
+            "success": true,
+            "message": "Encrypted backup exported successfully",
+            "filePath": filePath
+        }))
+    })
+    .await
+    .map_err(|e| format!("导出加密备份失败: {e}"))?
+    .map_err(encrypted_backup_error)
+}
+
 /// 从 SQL 备份导入数据库
+///
+/// 加密备份需要携带 `passphrase`：首次调用未提供口令时返回错误码
+/// `backup.encrypted.passphrase_required`，前端提示输入后再次调用。
+///
+/// 未指定 `mergeStrategy` 时整体覆盖导入（原有行为）；指定后改为逐项合并，
+/// 与本地冲突的条目按策略处理，返回值中的 `summary` 记录每个条目的处理结果。
 #[tauri::command]
 pub async fn import_config_from_file(
     #[allow(non_snake_case)] filePath: String,
+    passphrase: Option<String>,
+    mergeStrategy: Option<MergeStrategy>,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
     let db = state.db.clone();
     let db_for_state = db.clone();
     tauri::async_runtime::spawn_blocking(move || {
         let path_buf = PathBuf::from(&filePath);
-        let backup_id = db.import_sql(&path_buf)?;
+        if let Some(strategy) = mergeStrategy {
+            let summary =
+                db.apply_import_with_strategy(&path_buf, passphrase.as_deref(), strategy)?;
+
+            let app_state = AppState::new(db_for_state);
+            if let Err(err) = ProviderService::sync_current_to_live(&app_state) {
+                log::warn!("导入后同步 live 配置失败: {err}");
+            }
+
+            return Ok::<_, AppError>(json!({
+                "success": true,
+                "message": "SQL merged successfully",
+                "backupId": summary.backup_id.clone(),
+                "summary": summary
+            }));
+        }
+        let backup_id = db.import_sql_with_passphrase(&path_buf, passphrase.as_deref())?;
 
         // 导入后同步当前供应商到各自的 live 配置
         let app_state = AppState::new(db_for_state);
@@ -61,9 +115,167 @@ pub async fn import_config_from_file(
     })
     .await
     .map_err(|e| format!("导入配置失败: {e}"))?
+    .map_err(encrypted_backup_error)
+}
+
+/// 预览导入：返回新增 / 相同 / 冲突条目报告，不修改数据库
+#[tauri::command]
+pub async fn preview_import_config(
+    #[allow(non_snake_case)] filePath: String,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ImportPreview, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.preview_import(&PathBuf::from(&filePath), passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("预览导入失败: {e}"))?
+    .map_err(encrypted_backup_error)
+}
+
+/// 按冲突处理方式选择性导入
+///
+/// `resolutions` 为条目 key → `keep_local` | `take_import` | `duplicate_rename`，
+/// 未指定的冲突项保留本地版本。
+#[tauri::command]
+pub async fn apply_import_config(
+    #[allow(non_snake_case)] filePath: String,
+    resolutions: HashMap<String, ImportResolution>,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ImportApplyResult, String> {
+    let db = state.db.clone();
+    let db_for_state = db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = db.apply_import(
+            &PathBuf::from(&filePath),
+            passphrase.as_deref(),
+            &resolutions,
+        )?;
+
+        // 导入后同步当前供应商到各自的 live 配置
+        let app_state = AppState::new(db_for_state);
+        if let Err(err) = ProviderService::sync_current_to_live(&app_state) {
+            log::warn!("导入后同步 live 配置失败: {err}");
+        }
+
+        Ok::<_, AppError>(result)
+    })
+    .await
+    .map_err(|e| format!("导入配置失败: {e}"))?
+    .map_err(encrypted_backup_error)
+}
+
+/// 从归档的旧配置（config.json.migrated）按分区重新导入
+///
+/// `sections` 可选 providers / mcp / prompts / skills / settings；只导入数据库中不存在的条目，
+/// `dryRun` 为 true 时只返回报告，不写入数据库。
+#[tauri::command]
+pub async fn reimport_from_archived_json(
+    sections: Vec<String>,
+    dryRun: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<JsonReimportReport, String> {
+    let sections = sections
+        .iter()
+        .map(|s| s.parse::<JsonReimportSection>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let dry_run = dryRun.unwrap_or(false);
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = MultiAppConfig::load_archived(&MultiAppConfig::archived_path())?;
+        let report = db.reimport_from_json(&config, &sections, dry_run)?;
+
+        let providers_imported = report
+            .sections
+            .iter()
+            .any(|s| s.section == JsonReimportSection::Providers && s.imported > 0);
+        if !dry_run && providers_imported {
+            let app_state = AppState::new(db);
+            if let Err(err) = ProviderService::sync_current_to_live(&app_state) {
+                log::warn!("重新导入后同步 live 配置失败: {err}");
+            }
+        }
+
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("重新导入旧配置失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 导出 MCP / 提示词 / Skills 配置包（JSON）
+#[tauri::command]
+pub async fn export_bundle(
+    #[allow(non_snake_case)] filePath: String,
+    state: State<'_, AppState>,
+) -> Result<ConfigBundle, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let bundle = db.export_bundle()?;
+        write_json_file(&PathBuf::from(&filePath), &bundle)?;
+        Ok::<_, AppError>(bundle)
+    })
+    .await
+    .map_err(|e| format!("导出配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 导入配置包
+///
+/// 每个类别（MCP / 提示词 / Skills）在独立事务中导入；`resolutions` 的键为
+/// 导入报告中的条目 key，未指定的冲突项保留本地版本。
+#[tauri::command]
+pub async fn import_bundle(
+    #[allow(non_snake_case)] filePath: String,
+    resolutions: Option<HashMap<String, ImportResolution>>,
+    state: State<'_, AppState>,
+) -> Result<BundleImportResult, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let bundle: ConfigBundle = read_json_file(&PathBuf::from(&filePath))?;
+        db.import_bundle(&bundle, &resolutions.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("导入配置包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 从其他工具的配置文件导入供应商
+///
+/// `format` 目前支持 `claude-code-router`（别名 `ccr`）；同名供应商重复导入时覆盖更新，
+/// 未映射的字段在结果的 `unmapped` 中列出。
+#[tauri::command]
+pub async fn import_from_external_format(
+    format: String,
+    #[allow(non_snake_case)] filePath: String,
+    state: State<'_, AppState>,
+) -> Result<ExternalImportResult, String> {
+    let format: ExternalFormat = format.parse().map_err(|e: AppError| e.to_string())?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_state = AppState::new(db);
+        ProviderService::import_external(&app_state, format, &PathBuf::from(&filePath))
+    })
+    .await
+    .map_err(|e| format!("导入外部配置失败: {e}"))?
     .map_err(|e: AppError| e.to_string())
 }
 
+/// 加密备份相关错误以 JSON 返回（包含 code），便于前端区分“需要口令”和“口令错误”
+fn encrypted_backup_error(err: AppError) -> String {
+    match &err {
+        AppError::Localized { key, .. } if key.starts_with("backup.encrypted.") => json!({
+            "code": key,
+            "message": err.to_string(),
+        })
+        .to_string(),
+        _ => err.to_string(),
+    }
+}
+
 #[tauri::command]
 pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<Value, String> {
     let db = state.db.clone();
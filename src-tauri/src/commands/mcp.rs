@@ -204,3 +204,90 @@ pub async fn import_mcp_from_apps(state: State<'_, AppState>) -> Result<usize, S
     total += McpService::import_from_opencode(&state).unwrap_or(0);
     Ok(total)
 }
+
+/// 解析 Claude Desktop 配置路径（省略时使用当前平台的默认位置）
+fn resolve_claude_desktop_path(path: Option<String>) -> Result<std::path::PathBuf, String> {
+    match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => Ok(std::path::PathBuf::from(p)),
+        None => crate::mcp::default_desktop_config_path()
+            .ok_or_else(|| "无法确定 Claude Desktop 配置文件路径".to_string()),
+    }
+}
+
+/// 从 Claude Desktop 配置（claude_desktop_config.json）导入 MCP 服务器
+#[tauri::command]
+pub async fn import_mcp_from_claude_desktop(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<crate::services::mcp::ClaudeDesktopImportResult, String> {
+    let path = resolve_claude_desktop_path(path)?;
+    McpService::import_from_claude_desktop(&state, &path).map_err(|e| e.to_string())
+}
+
+/// 导出 MCP 服务器到 Claude Desktop 配置（serverIds 省略时导出全部）
+#[tauri::command]
+pub async fn export_mcp_to_claude_desktop(
+    state: State<'_, AppState>,
+    path: Option<String>,
+    serverIds: Option<Vec<String>>,
+) -> Result<crate::services::mcp::ClaudeDesktopExportResult, String> {
+    let path = resolve_claude_desktop_path(path)?;
+    McpService::export_to_claude_desktop(&state, &path, serverIds.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// 获取所有 MCP Profile
+#[tauri::command]
+pub async fn get_mcp_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::app_config::McpProfile>, String> {
+    McpService::get_profiles(&state).map_err(|e| e.to_string())
+}
+
+/// 添加或更新 MCP Profile
+#[tauri::command]
+pub async fn upsert_mcp_profile(
+    state: State<'_, AppState>,
+    profile: crate::app_config::McpProfile,
+) -> Result<(), String> {
+    McpService::upsert_profile(&state, profile).map_err(|e| e.to_string())
+}
+
+/// 删除 MCP Profile
+#[tauri::command]
+pub async fn delete_mcp_profile(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    McpService::delete_profile(&state, &name).map_err(|e| e.to_string())
+}
+
+/// 一键应用 MCP Profile（按 Profile 更新所有服务器启用状态并同步到各应用）
+#[tauri::command]
+pub async fn apply_mcp_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<crate::services::mcp::McpProfileApplyReport, String> {
+    McpService::apply_profile(&state, &name).map_err(|e| e.to_string())
+}
+
+/// 获取由 CC Switch 管理 MCP 的应用
+#[tauri::command]
+pub async fn get_mcp_managed_apps() -> Result<crate::settings::McpManagedApps, String> {
+    Ok(crate::settings::get_mcp_managed_apps())
+}
+
+/// 设置由 CC Switch 管理 MCP 的应用
+///
+/// 未管理的应用不再写入或删除 MCP 条目；重新纳入管理的应用会立即同步一次启用的服务器。
+#[tauri::command]
+pub async fn set_mcp_managed_apps(
+    state: State<'_, AppState>,
+    apps: crate::settings::McpManagedApps,
+) -> Result<bool, String> {
+    let previous = crate::settings::get_mcp_managed_apps();
+    crate::settings::set_mcp_managed_apps(apps.clone()).map_err(|e| e.to_string())?;
+    let newly_managed =
+        AppType::all().any(|app| apps.is_managed(&app) && !previous.is_managed(&app));
+    if newly_managed {
+        McpService::sync_all_enabled(&state).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
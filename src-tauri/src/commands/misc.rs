@@ -1,14 +1,17 @@
 #![allow(non_snake_case)]
 
 use crate::app_config::AppType;
-use crate::init_status::{InitErrorPayload, SkillsMigrationPayload};
+use crate::init_status::{
+    AppOnboardingStatus, InitErrorPayload, OnboardingReport, SkillsMigrationPayload, StartupStatus,
+    StartupTimings,
+};
 use crate::services::ProviderService;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::path::Path;
-use std::str::FromStr;
 use std::process::Command;
+use std::str::FromStr;
 use tauri::AppHandle;
+use tauri::Manager;
 use tauri::State;
 use tauri_plugin_opener::OpenerExt;
 
@@ -193,7 +196,175 @@ pub async fn get_skills_migration_result() -> Result<Option<SkillsMigrationPaylo
     Ok(crate::init_status::take_skills_migration_result())
 }
 
+/// 获取启动阶段结果汇总：初始化错误、JSON→SQLite 迁移、Skills 迁移与代理自动恢复。
+/// 与上面的一次性命令共享状态，但本命令不消费结果，可重复调用。
+#[tauri::command]
+pub async fn get_startup_status() -> Result<StartupStatus, String> {
+    Ok(crate::init_status::get_startup_status())
+}
+
+/// 获取启动耗时报告：各阶段（数据库初始化、托盘、延后导入、代理恢复等）的耗时，
+/// 以及主窗口显示与延后导入完成的时间点。
+#[tauri::command]
+pub async fn get_startup_timings() -> Result<StartupTimings, String> {
+    Ok(crate::init_status::get_startup_timings())
+}
+
+/// 检查数据库目录、各应用 Live 配置目录与 Skills 目录是否可写、剩余空间是否足够，
+/// 供前端显示磁盘/权限健康提示。
+#[tauri::command]
+pub async fn run_preflight_checks() -> Result<Vec<crate::preflight::PreflightCheck>, String> {
+    tauri::async_runtime::spawn_blocking(crate::preflight::run_preflight_checks)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取首次运行引导报告：各应用的 CLI、Live 配置、自动导入结果与检测到的 MCP / 提示词 / Skills。
+/// 报告在启动阶段后台生成，本命令通常直接返回缓存结果。
+#[tauri::command]
+pub async fn get_onboarding_report(handle: AppHandle) -> Result<OnboardingReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = handle.state::<crate::store::AppState>();
+        crate::services::OnboardingService::get_report(&state).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 针对单个应用重新执行初始导入（用户安装缺失的 CLI 后使用），返回该应用最新的检测结果
+#[tauri::command]
+pub async fn rerun_initial_import(
+    handle: AppHandle,
+    app: String,
+) -> Result<AppOnboardingStatus, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let status = tauri::async_runtime::spawn_blocking({
+        let handle = handle.clone();
+        move || {
+            let state = handle.state::<crate::store::AppState>();
+            crate::services::OnboardingService::rerun_initial_import(&state, app_type)
+                .map_err(|e| e.to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // 可能新导入了供应商，刷新托盘菜单
+    crate::tray::refresh_tray_menu(&handle);
+    Ok(status)
+}
+
+/// 状态快照中的供应商摘要
+#[derive(serde::Serialize)]
+pub struct SnapshotProvider {
+    id: String,
+    name: Option<String>,
+}
+
+/// 状态快照中单个应用的代理开关
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotAppProxy {
+    takeover: bool,
+    auto_failover: bool,
+}
+
+/// 状态快照中处于非关闭状态的熔断器
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotCircuit {
+    app_type: String,
+    provider_id: String,
+    state: crate::proxy::CircuitState,
+    consecutive_failures: u32,
+}
+
+/// 最近一次 `get_tool_versions` 的结果
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedToolVersions {
+    checked_at: String,
+    tools: Vec<ToolVersion>,
+}
+
+/// 只读状态快照（供脚本 / Raycast 等外部工具轮询）
 #[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusSnapshot {
+    generated_at: String,
+    current_providers: std::collections::BTreeMap<String, Option<SnapshotProvider>>,
+    proxy_running: bool,
+    proxy_apps: std::collections::BTreeMap<String, SnapshotAppProxy>,
+    /// 未调用过 `get_tool_versions` 时为空（快照本身不触发版本检测）
+    tool_versions: Option<CachedToolVersions>,
+    open_circuits: Vec<SnapshotCircuit>,
+}
+
+static TOOL_VERSIONS_CACHE: Lazy<std::sync::RwLock<Option<CachedToolVersions>>> =
+    Lazy::new(|| std::sync::RwLock::new(None));
+
+/// 获取只读状态快照：各应用当前供应商、代理运行/接管/故障转移状态、
+/// 缓存的工具版本以及未关闭的熔断器。
+#[tauri::command]
+pub async fn get_status_snapshot(
+    state: State<'_, crate::store::AppState>,
+) -> Result<StatusSnapshot, String> {
+    let mut current_providers = std::collections::BTreeMap::new();
+    for app_type in AppType::all() {
+        // OpenCode 为叠加模式，没有“当前供应商”
+        if matches!(app_type, AppType::OpenCode) {
+            continue;
+        }
+        let id = ProviderService::current(&state, app_type.clone()).map_err(|e| e.to_string())?;
+        let provider = (!id.is_empty()).then(|| {
+            let name = state
+                .db
+                .get_provider_by_id(&id, app_type.as_str())
+                .ok()
+                .flatten()
+                .map(|p| p.name);
+            SnapshotProvider { id, name }
+        });
+        current_providers.insert(app_type.as_str().to_string(), provider);
+    }
+
+    let mut proxy_apps = std::collections::BTreeMap::new();
+    for app_type in ["claude", "codex", "gemini", "opencode"] {
+        let (takeover, auto_failover) = state.db.get_proxy_flags_sync(app_type);
+        proxy_apps.insert(
+            app_type.to_string(),
+            SnapshotAppProxy {
+                takeover,
+                auto_failover,
+            },
+        );
+    }
+
+    let open_circuits = state
+        .proxy_service
+        .get_all_circuit_breaker_stats()
+        .await
+        .into_iter()
+        .filter(|(_, _, stats)| stats.state != crate::proxy::CircuitState::Closed)
+        .map(|(app_type, provider_id, stats)| SnapshotCircuit {
+            app_type,
+            provider_id,
+            state: stats.state,
+            consecutive_failures: stats.consecutive_failures,
+        })
+        .collect();
+
+    Ok(StatusSnapshot {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        current_providers,
+        proxy_running: state.proxy_service.is_running().await,
+        proxy_apps,
+        tool_versions: TOOL_VERSIONS_CACHE.read().ok().and_then(|c| c.clone()),
+        open_circuits,
+    })
+}
+
+#[derive(Clone, serde::Serialize)]
 pub struct ToolVersion {
     name: String,
     version: Option<String>,
@@ -210,20 +381,8 @@ pub async fn get_tool_versions() -> Result<Vec<ToolVersion>, String> {
     let client = crate::proxy::http_client::get();
 
     for tool in tools {
-        // 1. 获取本地版本 - 先尝试直接执行，失败则扫描常见路径
-        let (local_version, local_error) = if let Some(distro) = wsl_distro_for_tool(tool) {
-            try_get_version_wsl(tool, &distro)
-        } else {
-            // 先尝试直接执行
-            let direct_result = try_get_version(tool);
-
-            if direct_result.0.is_some() {
-                direct_result
-            } else {
-                // 扫描常见的 npm 全局安装路径
-                scan_cli_version(tool)
-            }
-        };
+        // 1. 获取本地版本
+        let (local_version, local_error) = detect_cli_version(tool);
 
         // 2. 获取远程最新版本
         let latest_version = match tool {
@@ -242,9 +401,32 @@ pub async fn get_tool_versions() -> Result<Vec<ToolVersion>, String> {
         });
     }
 
+    if let Ok(mut cache) = TOOL_VERSIONS_CACHE.write() {
+        *cache = Some(CachedToolVersions {
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            tools: results.clone(),
+        });
+    }
+
     Ok(results)
 }
 
+/// 获取已安装 CLI 的版本：优先使用 get_tool_versions 的缓存结果，未缓存时直接检测
+pub(crate) fn installed_cli_version(tool: &str) -> Option<String> {
+    let cached = TOOL_VERSIONS_CACHE.read().ok().and_then(|cache| {
+        cache
+            .as_ref()?
+            .tools
+            .iter()
+            .find(|t| t.name == tool)
+            .map(|t| t.version.clone())
+    });
+    match cached {
+        Some(version) => version,
+        None => detect_cli_version(tool).0,
+    }
+}
+
 /// Helper function to fetch latest version from npm registry
 async fn fetch_npm_latest_version(client: &reqwest::Client, package: &str) -> Option<String> {
     let url = format!("https://registry.npmjs.org/{package}");
@@ -298,6 +480,24 @@ fn extract_version(raw: &str) -> String {
         .unwrap_or_else(|| raw.to_string())
 }
 
+/// 检测本地 CLI 版本：配置了 WSL 目录时在 WSL 中执行，否则先直接执行，失败则扫描常见安装路径
+///
+/// 返回 (版本, 错误信息)。
+pub(crate) fn detect_cli_version(tool: &str) -> (Option<String>, Option<String>) {
+    if let Some(distro) = wsl_distro_for_tool(tool) {
+        return try_get_version_wsl(tool, &distro);
+    }
+
+    // 先尝试直接执行
+    let direct_result = try_get_version(tool);
+    if direct_result.0.is_some() {
+        return direct_result;
+    }
+
+    // 扫描常见的 npm 全局安装路径
+    scan_cli_version(tool)
+}
+
 /// 尝试直接执行命令获取版本
 fn try_get_version(tool: &str) -> (Option<String>, Option<String>) {
     use std::process::Command;
@@ -555,38 +755,160 @@ fn wsl_distro_for_tool(tool: &str) -> Option<String> {
         _ => None,
     }?;
 
-    wsl_distro_from_path(&override_dir)
+    crate::config::wsl_distro_from_path(&override_dir)
+}
+
+/// 各 CLI 的更新命令
+///
+/// OpenCode 使用自带的 `opencode upgrade`，从 GitHub Release 下载最新版本。
+fn tool_update_script(tool: &str) -> Option<&'static str> {
+    match tool {
+        "claude" => Some("npm i -g @anthropic-ai/claude-code@latest"),
+        "codex" => Some("npm i -g @openai/codex@latest"),
+        "gemini" => Some("npm i -g @google/gemini-cli@latest"),
+        "opencode" => Some("opencode upgrade"),
+        _ => None,
+    }
 }
 
-/// 从 UNC 路径中提取 WSL 发行版名称
-/// 支持 `\\wsl$\Ubuntu\...` 和 `\\wsl.localhost\Ubuntu\...` 两种格式
+/// 构造执行更新脚本的命令：与版本检测相同，Windows 使用 `cmd /C`，其他平台使用 `sh -c`；
+/// 配置了 WSL 目录时在对应发行版中执行
 #[cfg(target_os = "windows")]
-fn wsl_distro_from_path(path: &Path) -> Option<String> {
-    use std::path::{Component, Prefix};
-    let Some(Component::Prefix(prefix)) = path.components().next() else {
-        return None;
-    };
-    match prefix.kind() {
-        Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
-            let server_name = server.to_string_lossy();
-            if server_name.eq_ignore_ascii_case("wsl$")
-                || server_name.eq_ignore_ascii_case("wsl.localhost")
-            {
-                let distro = share.to_string_lossy().to_string();
-                if !distro.is_empty() {
-                    return Some(distro);
-                }
+fn tool_update_command(script: &str, distro: Option<&str>) -> Result<Command, String> {
+    let mut command = match distro {
+        Some(distro) => {
+            if !is_valid_wsl_distro_name(distro) {
+                return Err(format!("[WSL:{distro}] invalid distro name"));
             }
-            None
+            let mut command = Command::new("wsl.exe");
+            command.args(["-d", distro, "--", "sh", "-lc", script]);
+            command
         }
-        _ => None,
-    }
+        None => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", script]);
+            command
+        }
+    };
+    command.creation_flags(CREATE_NO_WINDOW);
+    Ok(command)
 }
 
-/// 非 Windows 平台不支持 WSL 路径解析
+/// 非 Windows 平台不会配置 WSL 目录（`wsl_distro_from_path` 总是返回 None）
 #[cfg(not(target_os = "windows"))]
-fn wsl_distro_from_path(_path: &Path) -> Option<String> {
-    None
+fn tool_update_command(script: &str, _distro: Option<&str>) -> Result<Command, String> {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script);
+    Ok(command)
+}
+
+/// 更新过程中的一行输出（事件 `tool-update-output`）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolUpdateOutput {
+    tool: String,
+    /// stdout / stderr
+    stream: &'static str,
+    line: String,
+}
+
+/// 将子进程的一路输出逐行转发给前端
+fn forward_tool_output<R: std::io::Read + Send + 'static>(
+    app: AppHandle,
+    tool: String,
+    stream: &'static str,
+    reader: R,
+) -> std::thread::JoinHandle<()> {
+    use std::io::BufRead;
+    use tauri::Emitter;
+
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            log::debug!("[update_tool:{tool}] {line}");
+            let payload = ToolUpdateOutput {
+                tool: tool.clone(),
+                stream,
+                line,
+            };
+            if let Err(e) = app.emit("tool-update-output", payload) {
+                log::warn!("发射 tool-update-output 事件失败: {e}");
+            }
+        }
+    })
+}
+
+/// 更新本地 CLI 到最新版本
+///
+/// 仅更新已检测到的 CLI；执行过程中的输出通过 `tool-update-output` 事件逐行推送，
+/// 返回更新命令是否执行成功。
+#[tauri::command]
+pub async fn update_tool(app: AppHandle, tool: String) -> Result<bool, String> {
+    let script = tool_update_script(&tool).ok_or_else(|| format!("不支持更新的工具: {tool}"))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        use std::process::Stdio;
+
+        if detect_cli_version(&tool).0.is_none() {
+            return Err(format!("未检测到 {tool}，请先安装后再更新"));
+        }
+
+        let distro = wsl_distro_for_tool(&tool);
+        log::info!(
+            "开始更新 {tool}: {script}{}",
+            distro
+                .as_deref()
+                .map(|d| format!(" (WSL:{d})"))
+                .unwrap_or_default()
+        );
+
+        let mut child = tool_update_command(script, distro.as_deref())?
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("执行更新命令失败: {e}"))?;
+
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(forward_tool_output(
+                app.clone(),
+                tool.clone(),
+                "stdout",
+                stdout,
+            ));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(forward_tool_output(
+                app.clone(),
+                tool.clone(),
+                "stderr",
+                stderr,
+            ));
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| format!("等待更新命令结束失败: {e}"))?;
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        if status.success() {
+            log::info!("{tool} 更新完成");
+            // 版本已变化，清除状态快照中的版本缓存
+            if let Ok(mut cache) = TOOL_VERSIONS_CACHE.write() {
+                *cache = None;
+            }
+        } else {
+            log::warn!("{tool} 更新失败: {status}");
+        }
+        Ok(status.success())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// 打开指定提供商的终端
@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 
+mod background_tasks;
 mod codex_auth;
 mod config;
 mod deeplink;
@@ -18,8 +19,10 @@ mod session_manager;
 mod settings;
 pub mod skill;
 mod stream_check;
+mod sync;
 mod usage;
 
+pub use background_tasks::*;
 pub use codex_auth::*;
 pub use config::*;
 pub use deeplink::*;
@@ -38,4 +41,5 @@ pub use session_manager::*;
 pub use settings::*;
 pub use skill::*;
 pub use stream_check::*;
+pub use sync::*;
 pub use usage::*;
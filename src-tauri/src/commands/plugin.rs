@@ -9,6 +9,8 @@ pub async fn get_claude_plugin_status() -> Result<ConfigStatus, String> {
         .map(|(exists, path)| ConfigStatus {
             exists,
             path: path.to_string_lossy().to_string(),
+            override_dir: None,
+            managed: None,
         })
         .map_err(|e| e.to_string())
 }
@@ -1,10 +1,13 @@
 use indexmap::IndexMap;
+use std::path::Path;
 use std::str::FromStr;
 
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
 
 use crate::app_config::AppType;
 use crate::prompt::Prompt;
+use crate::services::prompt::PromptFileContent;
 use crate::services::PromptService;
 use crate::store::AppState;
 
@@ -58,7 +61,77 @@ pub async fn import_prompt_from_file(
 }
 
 #[tauri::command]
-pub async fn get_current_prompt_file_content(app: String) -> Result<Option<String>, String> {
+pub async fn reorder_prompts(
+    app: String,
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::reorder_prompts(&state, app_type, &ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_current_prompt_file_content(
+    app: String,
+) -> Result<Option<PromptFileContent>, String> {
     let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
     PromptService::get_current_file_content(app_type).map_err(|e| e.to_string())
 }
+
+/// 从 Markdown 文件（支持 YAML front-matter）导入提示词
+///
+/// 未指定路径时弹出文件选择框，用户取消时返回 None。
+#[tauri::command]
+pub async fn import_prompt_markdown(
+    app_handle: AppHandle,
+    app: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let path = match path {
+        Some(path) => path,
+        None => match app_handle
+            .dialog()
+            .file()
+            .add_filter("Markdown", &["md", "markdown"])
+            .blocking_pick_file()
+        {
+            Some(picked) => picked.to_string(),
+            None => return Ok(None),
+        },
+    };
+    PromptService::import_markdown(&state, app_type, Path::new(&path))
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// 将提示词导出为 Markdown 文件
+///
+/// 未指定路径时弹出保存对话框，返回写入的路径；用户取消时返回 None。
+#[tauri::command]
+pub async fn export_prompt_markdown(
+    app_handle: AppHandle,
+    app: String,
+    id: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let path = match path {
+        Some(path) => path,
+        None => match app_handle
+            .dialog()
+            .file()
+            .add_filter("Markdown", &["md"])
+            .set_file_name(format!("{id}.md"))
+            .blocking_save_file()
+        {
+            Some(picked) => picked.to_string(),
+            None => return Ok(None),
+        },
+    };
+    PromptService::export_markdown(&state, app_type, &id, Path::new(&path))
+        .map_err(|e| e.to_string())?;
+    Ok(Some(path))
+}
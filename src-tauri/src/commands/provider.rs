@@ -1,14 +1,31 @@
 use indexmap::IndexMap;
-use tauri::State;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{Manager, State};
 
 use crate::app_config::AppType;
+use crate::claude_managed::ManagedSettingsStatus;
+use crate::database::{
+    EndpointLatencyRecord, InvalidProviderRow, LiveConfigVersion, ProviderAuditEntry,
+};
 use crate::error::AppError;
-use crate::provider::Provider;
-use crate::services::{EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService};
+use crate::field_error::FieldError;
+use crate::provider::{Provider, ProviderSummary};
+use crate::services::{
+    CredentialIssue, EndpointLatency, EnvImportResult, OrphanedUniversalChild,
+    PresetCatalogService, PresetCatalogStatus, ProviderIconService, ProviderMetadata,
+    ProviderMetadataService, ProviderService, ProviderSortUpdate, ResolvedProviderIcon,
+    SpeedtestService,
+};
 use crate::store::AppState;
+use crate::usage_script::ScriptSandbox;
 use std::str::FromStr;
 
-/// 获取所有供应商
+/// 获取所有供应商（远程图标附带本地缓存，缺失的在后台下载）
+///
+/// 注意：返回每个供应商完整的 settingsConfig，数据量较大（Codex 配置可达数十 KB）。
+/// 列表视图请使用 `get_providers_summary`，编辑时再通过 `get_provider_settings` 按需获取。
+/// 保留此命令以兼容旧前端。
 #[tauri::command]
 pub fn get_providers(
     state: State<'_, AppState>,
@@ -18,6 +35,54 @@ pub fn get_providers(
     ProviderService::list(state.inner(), app_type).map_err(|e| e.to_string())
 }
 
+/// 获取供应商摘要列表（不含 settingsConfig，用于列表视图）
+#[tauri::command]
+pub fn get_providers_summary(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<ProviderSummary>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let mut summaries =
+        ProviderService::list_summaries(state.inner(), app_type).map_err(|e| e.to_string())?;
+    ProviderIconService::fill_cached_summaries(&handle, &mut summaries);
+    Ok(summaries)
+}
+
+/// 获取单个供应商的完整 settingsConfig（编辑对话框打开时按需加载）
+#[tauri::command]
+pub fn get_provider_settings(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Value, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::get_settings_config(state.inner(), app_type, &id).map_err(|e| e.to_string())
+}
+
+/// 校验供应商配置，返回全部字段级问题（供编辑器高亮）
+#[tauri::command]
+pub fn validate_provider_config(
+    app: String,
+    #[allow(non_snake_case)] settingsConfig: Value,
+) -> Result<Vec<FieldError>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    Ok(ProviderService::validate_config(app_type, settingsConfig))
+}
+
+/// 按名称/备注/网址搜索供应商，可选按分类过滤
+#[tauri::command]
+pub fn search_providers(
+    state: State<'_, AppState>,
+    app: String,
+    query: String,
+    category: Option<String>,
+) -> Result<IndexMap<String, Provider>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::search(state.inner(), app_type, &query, category.as_deref())
+        .map_err(|e| e.to_string())
+}
+
 /// 获取当前供应商ID
 #[tauri::command]
 pub fn get_current_provider(state: State<'_, AppState>, app: String) -> Result<String, String> {
@@ -70,11 +135,137 @@ pub fn remove_provider_from_live_config(app: String, id: String) -> Result<bool,
         .map_err(|e| e.to_string())
 }
 
+/// 用数据库中保存的配置强制重写供应商的 Live 配置（不回填）
+#[tauri::command]
+pub fn restore_live_config(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    confirmed: Option<bool>,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::restore_live_from_stored(
+        state.inner(),
+        app_type,
+        &id,
+        confirmed.unwrap_or(false),
+    )
+    .map(|_| true)
+    .map_err(|e| e.to_string())
+}
+
+/// 获取 Live 配置历史版本（最新在前）
+#[tauri::command]
+pub fn list_live_config_history(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<LiveConfigVersion>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::list_live_config_history(state.inner(), app_type).map_err(|e| e.to_string())
+}
+
+/// 将 Live 配置恢复到指定历史版本，并回填到当前供应商
+#[tauri::command]
+pub fn restore_live_config_version(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] versionId: i64,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::restore_live_config_version(state.inner(), app_type, versionId)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 审计日志默认返回条数
+const DEFAULT_AUDIT_LOG_LIMIT: u32 = 100;
+
+/// 扫描所有供应商的凭据问题（API Key 为空、令牌已过期、缺少 Base URL）
+#[tauri::command]
+pub fn audit_credentials(state: State<'_, AppState>) -> Result<Vec<CredentialIssue>, String> {
+    ProviderService::audit_credentials(state.inner()).map_err(|e| e.to_string())
+}
+
+/// 获取供应商变更审计日志（最新在前）；不指定 `provider_id` 时返回该应用下的全部记录
+#[tauri::command]
+pub fn get_provider_audit_log(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ProviderAuditEntry>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    state
+        .db
+        .list_provider_audit_log(
+            app_type.as_str(),
+            provider_id.as_deref(),
+            limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// 获取审计日志保留天数（0 表示永久保留）
+#[tauri::command]
+pub fn get_provider_audit_retention_days(state: State<'_, AppState>) -> Result<u32, String> {
+    state
+        .db
+        .get_provider_audit_retention_days()
+        .map_err(|e| e.to_string())
+}
+
+/// 设置审计日志保留天数，并立即清理过期记录
+#[tauri::command]
+pub fn set_provider_audit_retention_days(
+    state: State<'_, AppState>,
+    days: u32,
+) -> Result<bool, String> {
+    state
+        .db
+        .set_provider_audit_retention_days(days)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 检查供应商表中无法解析的行（`settings_config` / `meta` 损坏）
+#[tauri::command]
+pub fn validate_providers(state: State<'_, AppState>) -> Result<Vec<InvalidProviderRow>, String> {
+    let invalid = state.db.validate_providers().map_err(|e| e.to_string())?;
+    crate::init_status::set_invalid_providers(invalid.clone());
+    Ok(invalid)
+}
+
+/// 将无法解析的供应商行移入隔离表，返回被隔离的行
+#[tauri::command]
+pub fn repair_providers(state: State<'_, AppState>) -> Result<Vec<InvalidProviderRow>, String> {
+    let quarantined = state.db.repair_providers().map_err(|e| e.to_string())?;
+    crate::init_status::set_invalid_providers(Vec::new());
+    Ok(quarantined)
+}
+
 /// 切换供应商
 fn switch_provider_internal(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
     ProviderService::switch(state, app_type, id)
 }
 
+/// 切换前健康检查失败以 JSON 返回（包含 code 与 HTTP 状态），便于前端提示“仍要切换”
+fn switch_error(err: AppError) -> String {
+    match &err {
+        AppError::ProviderVerificationFailed {
+            provider_id,
+            http_status,
+            message,
+        } => json!({
+            "code": "provider.verify_failed",
+            "providerId": provider_id,
+            "httpStatus": http_status,
+            "message": message,
+        })
+        .to_string(),
+        _ => err.to_string(),
+    }
+}
+
 #[cfg_attr(not(feature = "test-hooks"), doc(hidden))]
 pub fn switch_provider_test_hook(
     state: &AppState,
@@ -84,14 +275,90 @@ pub fn switch_provider_test_hook(
     switch_provider_internal(state, app_type, id)
 }
 
+/// 切换供应商的结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchProviderResult {
+    /// Live 配置是否已写入：只读模式下仅更新当前供应商标记，为 false
+    pub live_written: bool,
+    /// Claude 企业托管配置会覆盖的字段（存在冲突时返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed_override: Option<ManagedSettingsStatus>,
+}
+
+/// 切换供应商
+///
+/// 开启 `verifyBeforeSwitch` 时目标供应商健康检查失败会返回错误码
+/// `provider.verify_failed`，前端确认后可携带 `force: true` 跳过检查。
+/// 健康检查可能等待网络超时，因此在阻塞线程中执行，避免卡住主线程。
 #[tauri::command]
-pub fn switch_provider(
-    state: State<'_, AppState>,
+pub async fn switch_provider(
+    handle: tauri::AppHandle,
     app: String,
     id: String,
+    force: Option<bool>,
+) -> Result<SwitchProviderResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = handle
+            .try_state::<AppState>()
+            .ok_or_else(|| "应用状态不可用".to_string())?;
+        switch_provider_blocking(&state, app_type, &id, force.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("切换供应商失败: {e}"))?
+}
+
+/// 切换供应商的同步实现，供命令（阻塞线程中）与托盘菜单调用
+pub fn switch_provider_blocking(
+    state: &AppState,
+    app_type: AppType,
+    id: &str,
+    force: bool,
+) -> Result<SwitchProviderResult, String> {
+    ProviderService::switch_with_options(state, app_type.clone(), id, force)
+        .map_err(switch_error)?;
+
+    let managed_override = ProviderService::managed_settings_override(state, &app_type, id)
+        .unwrap_or_else(|e| {
+            log::warn!("检查 Claude 托管配置失败: {e}");
+            None
+        });
+    if let Some(managed) = &managed_override {
+        log::warn!(
+            "Claude 托管配置 {} 将覆盖以下字段: {}",
+            managed.path,
+            managed.overridden_keys.join(", ")
+        );
+    }
+
+    Ok(SwitchProviderResult {
+        live_written: !crate::settings::is_read_only_mode(),
+        managed_override,
+    })
+}
+
+/// 获取切换供应商时是否回填当前 live 配置
+#[tauri::command]
+pub fn get_backfill_setting(state: State<'_, AppState>, app: String) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    state
+        .db
+        .get_backfill_on_switch_enabled(app_type.as_str())
+        .map_err(|e| e.to_string())
+}
+
+/// 设置切换供应商时是否回填当前 live 配置
+#[tauri::command]
+pub fn set_backfill_setting(
+    state: State<'_, AppState>,
+    app: String,
+    enabled: bool,
 ) -> Result<bool, String> {
     let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    switch_provider_internal(&state, app_type, &id)
+    state
+        .db
+        .set_backfill_on_switch_enabled(app_type.as_str(), enabled)
         .map(|_| true)
         .map_err(|e| e.to_string())
 }
@@ -103,6 +370,16 @@ pub fn antigravity_import_current_session(
     crate::services::antigravity::import_current_session_from_local_db().map_err(|e| e.to_string())
 }
 
+/// 导入本机 Antigravity 当前会话并直接创建 Gemini 供应商（同邮箱则原地更新 Token）
+#[tauri::command]
+pub fn create_antigravity_provider(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<crate::services::antigravity::AntigravityProviderResult, String> {
+    crate::services::antigravity::create_provider_from_current_session(state.inner(), &name)
+        .map_err(|e| e.to_string())
+}
+
 /// 拉起浏览器登录入口（Google 账号登录，登录后回到 Antigravity 官网）
 #[tauri::command]
 pub fn antigravity_start_login() -> Result<bool, String> {
@@ -242,6 +519,16 @@ pub fn import_default_config(state: State<'_, AppState>, app: String) -> Result<
     import_default_config_internal(&state, app_type).map_err(Into::into)
 }
 
+/// 从当前环境变量导入供应商（已存在相同 base_url + key 时返回其 ID）
+#[tauri::command]
+pub fn import_provider_from_env(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<EnvImportResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::import_from_env(state.inner(), app_type).map_err(|e| e.to_string())
+}
+
 /// 查询供应商用量
 #[allow(non_snake_case)]
 #[tauri::command]
@@ -271,6 +558,7 @@ pub async fn testUsageScript(
     #[allow(non_snake_case)] accessToken: Option<String>,
     #[allow(non_snake_case)] userId: Option<String>,
     #[allow(non_snake_case)] templateType: Option<String>,
+    #[allow(non_snake_case)] allowedHosts: Option<Vec<String>>,
 ) -> Result<crate::provider::UsageResult, String> {
     let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
     ProviderService::test_usage_script(
@@ -278,12 +566,12 @@ pub async fn testUsageScript(
         app_type,
         &providerId,
         &scriptCode,
-        timeout.unwrap_or(10),
         apiKey.as_deref(),
         baseUrl.as_deref(),
         accessToken.as_deref(),
         userId.as_deref(),
         templateType.as_deref(),
+        &ScriptSandbox::new(timeout, allowedHosts.as_deref()),
     )
     .await
     .map_err(|e| e.to_string())
@@ -297,12 +585,89 @@ pub fn read_live_provider_settings(app: String) -> Result<serde_json::Value, Str
 }
 
 /// 测试第三方/自定义供应商端点的网络延迟
+///
+/// 传入 `app` 与 `providerId` 时，结果会记入该供应商的端点测速历史。
 #[tauri::command]
 pub async fn test_api_endpoints(
+    state: State<'_, AppState>,
     urls: Vec<String>,
     #[allow(non_snake_case)] timeoutSecs: Option<u64>,
+    app: Option<String>,
+    #[allow(non_snake_case)] providerId: Option<String>,
 ) -> Result<Vec<EndpointLatency>, String> {
-    SpeedtestService::test_endpoints(urls, timeoutSecs)
+    let target = match (app, providerId) {
+        (Some(app), Some(provider_id)) => {
+            let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+            Some((app_type, provider_id))
+        }
+        _ => None,
+    };
+
+    let results = SpeedtestService::test_endpoints(urls, timeoutSecs)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some((app_type, provider_id)) = target {
+        SpeedtestService::record_history(&state.db, app_type.as_str(), &provider_id, &results);
+    }
+    Ok(results)
+}
+
+/// 获取端点测速历史（最新在前，`limit` 默认 20）
+#[tauri::command]
+pub fn get_endpoint_latency_history(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    url: String,
+    limit: Option<u32>,
+) -> Result<Vec<EndpointLatencyRecord>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let url = url.trim().trim_end_matches('/').to_string();
+    state
+        .db
+        .get_endpoint_latency_history(app_type.as_str(), &providerId, &url, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
+
+/// 抓取供应商网站的标题、描述与图标，用于预填名称、图标和备注
+#[tauri::command]
+pub async fn fetch_provider_metadata(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<ProviderMetadata, String> {
+    ProviderMetadataService::fetch(&state.db, &url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 下载并校验远程供应商预设目录，成功后替换本地缓存
+#[tauri::command]
+pub async fn refresh_provider_presets(
+    state: State<'_, AppState>,
+) -> Result<PresetCatalogStatus, String> {
+    PresetCatalogService::refresh(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取缓存的远程预设（前端按名称覆盖到内置预设之上）
+#[tauri::command]
+pub fn get_provider_presets(state: State<'_, AppState>, app: String) -> Result<Vec<Value>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PresetCatalogService::get_presets(&state.db, &app_type).map_err(|e| e.to_string())
+}
+
+/// 解析供应商图标：远程图标返回本地缓存（必要时下载），失败回退为名称首字母
+#[tauri::command]
+pub async fn resolve_provider_icon(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+) -> Result<ResolvedProviderIcon, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderIconService::resolve(&handle, &state.db, app_type.as_str(), &providerId)
         .await
         .map_err(|e| e.to_string())
 }
@@ -358,6 +723,12 @@ pub fn update_endpoint_last_used(
         .map_err(|e| e.to_string())
 }
 
+/// 合并所有供应商中重复的自定义端点，返回删除的条数
+#[tauri::command]
+pub fn dedupe_custom_endpoints(state: State<'_, AppState>) -> Result<usize, String> {
+    ProviderService::dedupe_custom_endpoints(state.inner()).map_err(|e| e.to_string())
+}
+
 /// 更新多个供应商的排序
 #[tauri::command]
 pub fn update_providers_sort_order(
@@ -369,6 +740,37 @@ pub fn update_providers_sort_order(
     ProviderService::update_sort_order(state.inner(), app_type, updates).map_err(|e| e.to_string())
 }
 
+/// 获取供应商单独设置的成本倍率（未设置时返回 null，使用应用默认倍率）
+#[tauri::command]
+pub fn get_provider_cost_multiplier(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Option<String>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::get_cost_multiplier(state.inner(), app_type, &id).map_err(|e| e.to_string())
+}
+
+/// 设置供应商成本倍率（正数；传 null 或空字符串清除）
+#[tauri::command]
+pub fn set_provider_cost_multiplier(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    multiplier: Option<String>,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::set_cost_multiplier(state.inner(), app_type, &id, multiplier.as_deref())
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 迁移所有 Claude 供应商中的旧模型键，返回更新数量
+#[tauri::command]
+pub fn normalize_claude_models(state: State<'_, AppState>) -> Result<usize, String> {
+    ProviderService::normalize_all_claude_providers(state.inner()).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // 统一供应商（Universal Provider）命令
 // ============================================================================
@@ -463,6 +865,33 @@ pub fn sync_universal_provider(
     Ok(result)
 }
 
+/// 列出父统一供应商已不存在的子供应商
+#[tauri::command]
+pub fn find_orphaned_universal_children(
+    state: State<'_, AppState>,
+) -> Result<Vec<OrphanedUniversalChild>, String> {
+    ProviderService::find_orphaned_universal_children(state.inner()).map_err(|e| e.to_string())
+}
+
+/// 删除孤立的统一供应商子供应商（跳过正在使用的），返回已删除的条目
+#[tauri::command]
+pub fn cleanup_orphaned_universal_children(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<OrphanedUniversalChild>, String> {
+    let removed = ProviderService::cleanup_orphaned_universal_children(state.inner())
+        .map_err(|e| e.to_string())?;
+
+    // 发送事件通知前端刷新（每个统一供应商 ID 一次）
+    let universal_ids: std::collections::BTreeSet<&str> =
+        removed.iter().map(|o| o.universal_id.as_str()).collect();
+    for id in universal_ids {
+        emit_universal_provider_synced(&app, "cleanup", id);
+    }
+
+    Ok(removed)
+}
+
 // ============================================================================
 // OpenCode 专属命令
 // ============================================================================
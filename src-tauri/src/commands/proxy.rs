@@ -3,8 +3,9 @@
 //! 提供前端调用的 API 接口
 
 use crate::error::AppError;
+use crate::proxy::failover_simulation::FailoverSimulation;
 use crate::proxy::types::*;
-use crate::proxy::{CircuitBreakerConfig, CircuitBreakerStats};
+use crate::proxy::{CircuitBreakerConfig, CircuitBreakerEvent, CircuitBreakerStats};
 use crate::store::AppState;
 
 /// 启动代理服务器（仅启动服务，不接管 Live 配置）
@@ -21,6 +22,24 @@ pub async fn stop_proxy_with_restore(state: tauri::State<'_, AppState>) -> Resul
     state.proxy_service.stop_with_restore().await
 }
 
+/// 优雅停止代理服务器（等待进行中的请求完成，默认最多 10 秒），然后恢复 Live 配置
+#[tauri::command]
+pub async fn stop_proxy_graceful(
+    state: tauri::State<'_, AppState>,
+    #[allow(non_snake_case)] timeoutSecs: Option<u64>,
+) -> Result<(), String> {
+    let timeout = std::time::Duration::from_secs(timeoutSecs.unwrap_or(10));
+    state.proxy_service.stop_graceful(timeout).await
+}
+
+/// 紧急恢复：停止代理并把所有应用的 Live 配置恢复到可用状态，返回逐应用的处理结果
+#[tauri::command]
+pub async fn emergency_restore_all(
+    state: tauri::State<'_, AppState>,
+) -> Result<EmergencyRestoreReport, String> {
+    Ok(state.proxy_service.emergency_restore_all().await)
+}
+
 /// 获取各应用接管状态
 #[tauri::command]
 pub async fn get_proxy_takeover_status(
@@ -259,6 +278,59 @@ pub async fn switch_proxy_provider(
         .await
 }
 
+/// 将已记录的请求重放到指定供应商（调试用，需开启请求体捕获）
+#[tauri::command]
+pub async fn replay_proxy_request(
+    state: tauri::State<'_, AppState>,
+    request_id: String,
+    target_provider_id: String,
+) -> Result<crate::proxy::replay::ReplayResult, String> {
+    state
+        .proxy_service
+        .replay_request(&request_id, &target_provider_id)
+        .await
+}
+
+/// 将已记录的请求还原为 curl 命令，便于在外部复现
+///
+/// 认证头默认以 `[REDACTED]` 占位；只有显式传入 `include_auth = true` 才写入真实密钥。
+#[tauri::command]
+pub fn get_request_as_curl(
+    state: tauri::State<'_, AppState>,
+    request_id: String,
+    include_auth: Option<bool>,
+) -> Result<crate::proxy::replay::CurlExport, String> {
+    state
+        .proxy_service
+        .request_as_curl(&request_id, include_auth.unwrap_or(false))
+}
+
+// ==================== 本地 HTTP API ====================
+
+/// 获取本地 API 访问 token（每次启动重新生成）
+#[tauri::command]
+pub fn get_local_api_token() -> String {
+    crate::proxy::local_api::token().to_string()
+}
+
+/// 获取是否启用本地 API
+#[tauri::command]
+pub fn get_local_api_enabled(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    state.db.get_local_api_enabled().map_err(|e| e.to_string())
+}
+
+/// 设置是否启用本地 API（立即生效，无需重启代理）
+#[tauri::command]
+pub fn set_local_api_enabled(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    state
+        .db
+        .set_local_api_enabled(enabled)
+        .map_err(|e| e.to_string())
+}
+
 // ==================== 故障转移相关命令 ====================
 
 /// 获取供应商健康状态
@@ -362,6 +434,44 @@ pub async fn reset_circuit_breaker(
     Ok(())
 }
 
+/// 手动设置熔断器状态
+///
+/// `mode`：`auto`（由熔断器自动判断）、`open`（强制熔断）、`closed`（强制放行）；
+/// `until` 为手动状态的失效时间（毫秒时间戳），为空表示直到手动恢复 `auto`。
+#[tauri::command]
+pub async fn set_provider_circuit_state(
+    state: tauri::State<'_, AppState>,
+    provider_id: String,
+    app_type: String,
+    mode: String,
+    until: Option<i64>,
+) -> Result<(), String> {
+    let proxy_service = &state.proxy_service;
+    match mode.as_str() {
+        "auto" => {
+            proxy_service
+                .force_auto_circuit(&provider_id, &app_type)
+                .await
+        }
+        "open" => {
+            proxy_service
+                .force_open_circuit(&provider_id, &app_type, until)
+                .await
+        }
+        "closed" => {
+            proxy_service
+                .force_close_circuit(&provider_id, &app_type, until)
+                .await
+        }
+        other => Err(AppError::localized(
+            "circuit.invalid_mode",
+            format!("无效的熔断器模式: {other}"),
+            format!("Invalid circuit breaker mode: {other}"),
+        )
+        .to_string()),
+    }
+}
+
 /// 获取熔断器配置
 #[tauri::command]
 pub async fn get_circuit_breaker_config(
@@ -395,6 +505,34 @@ pub async fn update_circuit_breaker_config(
     Ok(())
 }
 
+/// 获取熔断器状态转换历史
+///
+/// `provider_id` 为空时返回该应用下所有供应商的事件，`limit` 默认 100。
+#[tauri::command]
+pub async fn get_circuit_breaker_history(
+    state: tauri::State<'_, AppState>,
+    app_type: String,
+    provider_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<CircuitBreakerEvent>, String> {
+    state
+        .proxy_service
+        .get_circuit_breaker_history(&app_type, provider_id.as_deref(), limit)
+}
+
+/// 模拟指定供应商故障时代理会依次尝试哪些供应商（不发送真实请求）
+#[tauri::command]
+pub async fn simulate_failover(
+    state: tauri::State<'_, AppState>,
+    app_type: String,
+    failing_provider_ids: Vec<String>,
+) -> Result<FailoverSimulation, String> {
+    state
+        .proxy_service
+        .simulate_failover(&app_type, &failing_provider_ids)
+        .await
+}
+
 /// 获取熔断器统计信息（仅当代理服务器运行时）
 #[tauri::command]
 pub async fn get_circuit_breaker_stats(
@@ -402,8 +540,8 @@ pub async fn get_circuit_breaker_stats(
     provider_id: String,
     app_type: String,
 ) -> Result<Option<CircuitBreakerStats>, String> {
-    // 这个功能需要访问运行中的代理服务器的内存状态
-    // 目前先返回 None，后续可以通过 ProxyService 暴露接口来实现
-    let _ = (state, provider_id, app_type);
-    Ok(None)
+    Ok(state
+        .proxy_service
+        .get_circuit_breaker_stats(&provider_id, &app_type)
+        .await)
 }
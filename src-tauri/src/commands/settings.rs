@@ -1,6 +1,6 @@
 #![allow(non_snake_case)]
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter, Manager};
 
 /// 获取设置
 #[tauri::command]
@@ -9,12 +9,51 @@ pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
 }
 
 /// 保存设置
+///
+/// 配置目录覆盖有变化时先做健康检查：有错误时拒绝，只有警告时需 `acknowledgeWarnings`。
 #[tauri::command]
-pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
+pub async fn save_settings(
+    app: AppHandle,
+    settings: crate::settings::AppSettings,
+    acknowledgeWarnings: Option<bool>,
+) -> Result<bool, String> {
+    crate::settings::check_override_dir_changes(&settings, acknowledgeWarnings.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
     crate::settings::update_settings(settings).map_err(|e| e.to_string())?;
+    sync_language_to_store(&app);
+    Ok(true)
+}
+
+/// 设置界面语言（影响原生对话框与后端错误信息），同时写入 Store 供下次启动早期读取
+#[tauri::command]
+pub async fn set_language(app: AppHandle, lang: String) -> Result<bool, String> {
+    crate::settings::set_language(&lang).map_err(|e| e.to_string())?;
+    sync_language_to_store(&app);
     Ok(true)
 }
 
+/// 将设置中的界面语言同步到 Store（失败只记录日志）
+fn sync_language_to_store(app: &AppHandle) {
+    let lang = crate::settings::get_language();
+    if lang == crate::app_store::get_language_override() {
+        return;
+    }
+    if let Err(e) = crate::app_store::set_language_to_store(app, lang.as_deref()) {
+        log::warn!("同步界面语言到 Store 失败: {e}");
+    }
+}
+
+/// 检查配置目录覆盖是否可用（存在性、可写性、WSL 路径、原子 rename）
+#[tauri::command]
+pub async fn validate_override_dir(
+    app: String,
+    path: String,
+) -> Result<crate::config::OverrideDirReport, String> {
+    use std::str::FromStr;
+    let app_type = crate::app_config::AppType::from_str(&app).map_err(|e| e.to_string())?;
+    Ok(crate::settings::validate_override_dir(&app_type, &path))
+}
+
 /// 重启应用程序（当 app_config_dir 变更后使用）
 #[tauri::command]
 pub async fn restart_app(app: AppHandle) -> Result<bool, String> {
@@ -43,6 +82,46 @@ pub async fn set_app_config_dir_override(
     Ok(true)
 }
 
+/// 将当前配置目录迁移到新位置（复制数据后更新目录覆盖，重启后生效）
+///
+/// 代理运行时拒绝执行，避免迁移过程中写入 Live 配置。
+#[tauri::command]
+pub async fn relocate_config_dir(
+    app: AppHandle,
+    state: tauri::State<'_, crate::AppState>,
+    newPath: String,
+) -> Result<crate::services::config::ConfigDirRelocation, String> {
+    let trimmed = newPath.trim();
+    if trimmed.is_empty() {
+        return Err("新目录路径不能为空".to_string());
+    }
+    if state.proxy_service.is_running().await {
+        return Err(crate::error::AppError::localized(
+            "config.relocate.proxy_running",
+            "代理服务正在运行，请先停止代理再迁移配置目录",
+            "The proxy is running. Stop it before relocating the config directory",
+        )
+        .to_string());
+    }
+
+    let current_dir = crate::config::get_app_config_dir();
+    let target_dir = crate::app_store::resolve_path(trimmed);
+    if !target_dir.is_absolute() {
+        return Err("新目录必须为绝对路径".to_string());
+    }
+    let db = state.db.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        crate::services::ConfigService::relocate_config_dir(&db, &current_dir, &target_dir)
+    })
+    .await
+    .map_err(|e| format!("迁移配置目录失败: {e}"))?
+    .map_err(|e| e.to_string())?;
+
+    crate::app_store::set_app_config_dir_to_store(&app, Some(&result.new_path))
+        .map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
 /// 设置开机自启
 #[tauri::command]
 pub async fn set_auto_launch(enabled: bool) -> Result<bool, String> {
@@ -60,6 +139,58 @@ pub async fn get_auto_launch_status() -> Result<bool, String> {
     crate::auto_launch::is_auto_launch_enabled().map_err(|e| format!("获取开机自启状态失败: {e}"))
 }
 
+/// 获取启动延迟（秒）
+#[tauri::command]
+pub async fn get_startup_delay() -> Result<u32, String> {
+    Ok(crate::settings::get_startup_delay_seconds())
+}
+
+/// 设置启动延迟（秒），下次启动时生效
+#[tauri::command]
+pub async fn set_startup_delay(seconds: u32) -> Result<bool, String> {
+    crate::settings::set_startup_delay_seconds(seconds).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 只读模式切换后发送的事件（payload 为是否开启）
+pub const READ_ONLY_MODE_CHANGED_EVENT: &str = "read-only-mode-changed";
+
+/// 获取只读模式开关
+#[tauri::command]
+pub async fn get_read_only_mode() -> Result<bool, String> {
+    Ok(crate::settings::is_read_only_mode())
+}
+
+/// 开启/关闭只读模式：开启后所有 Live 配置写入都会返回 `ReadOnlyMode` 错误
+#[tauri::command]
+pub async fn set_read_only_mode(app: AppHandle, enabled: bool) -> Result<bool, String> {
+    crate::settings::set_read_only_mode(enabled).map_err(|e| e.to_string())?;
+    log::info!("只读模式已{}", if enabled { "开启" } else { "关闭" });
+
+    apply_read_only_window_title(&app);
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::tray::refresh_tray_menu(&handle));
+    if let Err(e) = app.emit(READ_ONLY_MODE_CHANGED_EVENT, enabled) {
+        log::error!("发射只读模式事件失败: {e}");
+    }
+    Ok(true)
+}
+
+/// 在主窗口标题上标注只读模式
+pub fn apply_read_only_window_title(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let title = if crate::settings::is_read_only_mode() {
+        format!("{} (Read-only)", app.package_info().name)
+    } else {
+        String::new()
+    };
+    if let Err(e) = window.set_title(&title) {
+        log::warn!("更新窗口标题失败: {e}");
+    }
+}
+
 /// 获取整流器配置
 #[tauri::command]
 pub async fn get_rectifier_config(
@@ -95,15 +226,102 @@ pub async fn set_log_config(
     state: tauri::State<'_, crate::AppState>,
     config: crate::proxy::types::LogConfig,
 ) -> Result<bool, String> {
+    crate::log_filter::validate(&config)?;
     state
         .db
         .set_log_config(&config)
         .map_err(|e| e.to_string())?;
-    log::set_max_level(config.to_level_filter());
+    crate::log_filter::apply(&config);
     log::info!(
-        "日志配置已更新: enabled={}, level={}",
+        "日志配置已更新: enabled={}, level={}, module_levels={:?}",
         config.enabled,
-        config.level
+        config.level,
+        config.module_levels
     );
     Ok(true)
 }
+
+/// 导出偏好设置（AppSettings + 日志配置 + 代理监听默认值）到 JSON 文件，不含供应商密钥
+#[tauri::command]
+pub async fn export_settings(
+    state: tauri::State<'_, crate::AppState>,
+    path: String,
+) -> Result<crate::services::SettingsExport, String> {
+    crate::services::SettingsTransferService::export_to_file(&state.db, std::path::Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从 JSON 文件导入偏好设置，日志级别立即生效
+#[tauri::command]
+pub async fn import_settings(
+    state: tauri::State<'_, crate::AppState>,
+    path: String,
+) -> Result<crate::settings::AppSettings, String> {
+    crate::services::SettingsTransferService::import_from_file(
+        &state.db,
+        std::path::Path::new(&path),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 获取窗口主题配置
+#[tauri::command]
+pub async fn get_theme_config() -> Result<crate::theme::ThemeConfig, String> {
+    Ok(crate::settings::get_theme_config())
+}
+
+/// 保存窗口主题配置并立即应用（会发送 `theme-changed` 事件）
+#[tauri::command]
+pub async fn set_theme_config(
+    app: AppHandle,
+    config: crate::theme::ThemeConfig,
+) -> Result<bool, String> {
+    crate::settings::set_theme_config(config).map_err(|e| e.to_string())?;
+    crate::theme::apply(&app, true);
+    Ok(true)
+}
+
+/// 获取全局快捷键配置
+#[tauri::command]
+pub async fn get_hotkey_config() -> Result<crate::hotkeys::HotkeyConfig, String> {
+    Ok(crate::settings::get_hotkey_config())
+}
+
+/// 保存全局快捷键配置并重新注册，返回被占用等原因注册失败的绑定
+#[tauri::command]
+pub async fn set_hotkey_config(
+    app: AppHandle,
+    config: crate::hotkeys::HotkeyConfig,
+) -> Result<Vec<crate::hotkeys::HotkeyBindingError>, String> {
+    crate::settings::set_hotkey_config(config).map_err(|e| e.to_string())?;
+    Ok(crate::hotkeys::register_all(&app))
+}
+
+/// 读取崩溃日志（仅返回最后 `maxKb` KB，默认 256KB）
+#[tauri::command]
+pub async fn get_crash_log(maxKb: Option<u64>) -> Result<crate::panic_hook::LogTail, String> {
+    let max_bytes = maxKb.unwrap_or(256).clamp(1, 10 * 1024) * 1024;
+    tauri::async_runtime::spawn_blocking(move || crate::panic_hook::read_crash_log(max_bytes))
+        .await
+        .map_err(|e| format!("读取崩溃日志失败: {e}"))?
+        .map_err(|e| format!("读取崩溃日志失败: {e}"))
+}
+
+/// 清空崩溃日志
+#[tauri::command]
+pub async fn clear_crash_log() -> Result<bool, String> {
+    crate::panic_hook::clear_crash_log().map_err(|e| format!("清空崩溃日志失败: {e}"))?;
+    Ok(true)
+}
+
+/// 读取应用日志 cc-switch.log 的最后若干行（默认 500 行）
+#[tauri::command]
+pub async fn get_app_log_tail(lines: Option<usize>) -> Result<crate::panic_hook::LogTail, String> {
+    let lines = lines.unwrap_or(500).clamp(1, 10_000);
+    tauri::async_runtime::spawn_blocking(move || crate::panic_hook::read_app_log_tail(lines))
+        .await
+        .map_err(|e| format!("读取应用日志失败: {e}"))?
+        .map_err(|e| format!("读取应用日志失败: {e}"))
+}
@@ -6,7 +6,10 @@
 
 use crate::app_config::{AppType, InstalledSkill, UnmanagedSkill};
 use crate::error::format_skill_error;
-use crate::services::skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
+use crate::services::skill::{
+    DiscoverableSkill, Skill, SkillBulkInstallResult, SkillEnablementRow, SkillRepo, SkillRepoHost,
+    SkillService,
+};
 use crate::store::AppState;
 use std::sync::Arc;
 use tauri::State;
@@ -54,6 +57,37 @@ pub async fn install_skill_unified(
         .map_err(|e| e.to_string())
 }
 
+/// 从仓库批量安装 Skills
+///
+/// 参数：
+/// - branch: 分支（为空时使用已配置仓库的分支或 main）
+/// - filter: 目录名 glob（如 `lint-*`），为空时安装全部
+#[tauri::command]
+pub async fn install_skills_from_repo(
+    owner: String,
+    name: String,
+    branch: Option<String>,
+    filter: Option<String>,
+    current_app: String,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SkillBulkInstallResult>, String> {
+    let app_type = parse_app_type(&current_app)?;
+
+    service
+        .0
+        .install_all_from_repo(
+            &app_state.db,
+            &owner,
+            &name,
+            branch.as_deref(),
+            filter.as_deref(),
+            &app_type,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 卸载 Skill（新版统一卸载）
 #[tauri::command]
 pub fn uninstall_skill_unified(id: String, app_state: State<'_, AppState>) -> Result<bool, String> {
@@ -74,6 +108,27 @@ pub fn toggle_skill_app(
     Ok(true)
 }
 
+/// 获取所有 Skill 的应用启用矩阵
+#[tauri::command]
+pub fn get_skill_enablement_matrix(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SkillEnablementRow>, String> {
+    SkillService::get_enablement_matrix(&app_state.db).map_err(|e| e.to_string())
+}
+
+/// 切换启用矩阵中的单个开关（同步写入/移除对应应用目录）
+#[tauri::command]
+pub fn set_skill_enablement(
+    skill_id: String,
+    app_type: String,
+    enabled: bool,
+    app_state: State<'_, AppState>,
+) -> Result<SkillEnablementRow, String> {
+    let app_type = parse_app_type(&app_type)?;
+    SkillService::set_enablement(&app_state.db, &skill_id, &app_type, enabled)
+        .map_err(|e| e.to_string())
+}
+
 /// 扫描未管理的 Skills
 #[tauri::command]
 pub fn scan_unmanaged_skills(
@@ -227,8 +282,22 @@ pub fn get_skill_repos(app_state: State<'_, AppState>) -> Result<Vec<SkillRepo>,
 }
 
 /// 添加技能仓库
+///
+/// raw git 仓库必须提供 clone 地址（`baseUrl`）；访问令牌请通过 `set_skill_repo_token` 单独设置。
 #[tauri::command]
 pub fn add_skill_repo(repo: SkillRepo, app_state: State<'_, AppState>) -> Result<bool, String> {
+    let missing_url = repo
+        .base_url
+        .as_deref()
+        .is_none_or(|url| url.trim().is_empty());
+    if repo.host_kind == SkillRepoHost::RawGit && missing_url {
+        return Err(format_skill_error(
+            "MISSING_REPO_INFO",
+            &[("owner", &repo.owner), ("name", &repo.name)],
+            Some("checkRepoUrl"),
+        ));
+    }
+
     app_state
         .db
         .save_skill_repo(&repo)
@@ -250,6 +319,21 @@ pub fn remove_skill_repo(
     Ok(true)
 }
 
+/// 设置或清除技能仓库访问令牌（GitLab / 私有仓库），令牌保存在设置中而非仓库表
+#[tauri::command]
+pub fn set_skill_repo_token(
+    owner: String,
+    name: String,
+    token: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<bool, String> {
+    app_state
+        .db
+        .set_skill_repo_token(&owner, &name, token.as_deref())
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 /// 从 ZIP 文件安装 Skills
 #[tauri::command]
 pub fn install_skills_from_zip(
@@ -3,20 +3,33 @@
 use crate::app_config::AppType;
 use crate::error::AppError;
 use crate::services::stream_check::{
-    HealthStatus, StreamCheckConfig, StreamCheckResult, StreamCheckService,
+    StreamCheckConfig, StreamCheckHistoryBucket, StreamCheckResult, StreamCheckService,
+    DEFAULT_CHECK_CONCURRENCY,
 };
+use crate::services::stream_check_scheduler::StreamCheckScheduler;
 use crate::store::AppState;
-use std::collections::HashSet;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+
+/// 检查历史的时间桶大小（1 小时）
+const HISTORY_BUCKET_SECS: i64 = 60 * 60;
+
+/// 批量检查中每个供应商完成时发送的事件
+pub const STREAM_CHECK_PROGRESS_EVENT: &str = "stream-check-progress";
 
 /// 流式健康检查（单个供应商）
+///
+/// `model` 指定时仅本次检查使用该模型；长期固定请通过 `save_stream_check_config` 的 `providerModels`。
 #[tauri::command]
 pub async fn stream_check_provider(
     state: State<'_, AppState>,
     app_type: AppType,
     provider_id: String,
+    model: Option<String>,
 ) -> Result<StreamCheckResult, AppError> {
-    let config = state.db.get_stream_check_config()?;
+    let mut config = state.db.get_stream_check_config()?;
+    if let Some(model) = model.filter(|m| !m.trim().is_empty()) {
+        config.pin_model(&app_type, &provider_id, model);
+    }
 
     let providers = state.db.get_all_providers(app_type.as_str())?;
     let provider = providers
@@ -35,59 +48,32 @@ pub async fn stream_check_provider(
 }
 
 /// 批量流式健康检查
+///
+/// `provider_ids` 指定时只检查这些供应商；`concurrency` 为同时进行的检查数（默认 4，上限 16）。
+/// 每个供应商检查完成后发送 `stream-check-progress` 事件。与该应用的定时检查互斥。
 #[tauri::command]
 pub async fn stream_check_all_providers(
+    app: AppHandle,
     state: State<'_, AppState>,
     app_type: AppType,
     proxy_targets_only: bool,
+    provider_ids: Option<Vec<String>>,
+    concurrency: Option<usize>,
 ) -> Result<Vec<(String, StreamCheckResult)>, AppError> {
-    let config = state.db.get_stream_check_config()?;
-    let providers = state.db.get_all_providers(app_type.as_str())?;
-
-    let mut results = Vec::new();
-    let allowed_ids: Option<HashSet<String>> = if proxy_targets_only {
-        let mut ids = HashSet::new();
-        if let Ok(Some(current_id)) = state.db.get_current_provider(app_type.as_str()) {
-            ids.insert(current_id);
-        }
-        if let Ok(queue) = state.db.get_failover_queue(app_type.as_str()) {
-            for item in queue {
-                ids.insert(item.provider_id);
+    let _guard = StreamCheckScheduler::acquire_run_lock(&app_type).await;
+    StreamCheckService::check_providers(
+        &state.db,
+        &app_type,
+        proxy_targets_only,
+        provider_ids.as_deref(),
+        concurrency.unwrap_or(DEFAULT_CHECK_CONCURRENCY),
+        |progress| {
+            if let Err(e) = app.emit(STREAM_CHECK_PROGRESS_EVENT, &progress) {
+                log::error!("[StreamCheck] 发射检查进度事件失败: {e}");
             }
-        }
-        Some(ids)
-    } else {
-        None
-    };
-
-    for (id, provider) in providers {
-        if let Some(ids) = &allowed_ids {
-            if !ids.contains(&id) {
-                continue;
-            }
-        }
-
-        let result = StreamCheckService::check_with_retry(&app_type, &provider, &config)
-            .await
-            .unwrap_or_else(|e| StreamCheckResult {
-                status: HealthStatus::Failed,
-                success: false,
-                message: e.to_string(),
-                response_time_ms: None,
-                http_status: None,
-                model_used: String::new(),
-                tested_at: chrono::Utc::now().timestamp(),
-                retry_count: 0,
-            });
-
-        let _ = state
-            .db
-            .save_stream_check_log(&id, &provider.name, app_type.as_str(), &result);
-
-        results.push((id, result));
-    }
-
-    Ok(results)
+        },
+    )
+    .await
 }
 
 /// 获取流式检查配置
@@ -96,11 +82,30 @@ pub fn get_stream_check_config(state: State<'_, AppState>) -> Result<StreamCheck
     state.db.get_stream_check_config()
 }
 
-/// 保存流式检查配置
+/// 保存流式检查配置，并按新的定时间隔重建后台检查任务
 #[tauri::command]
 pub fn save_stream_check_config(
+    app: AppHandle,
     state: State<'_, AppState>,
     config: StreamCheckConfig,
 ) -> Result<(), AppError> {
-    state.db.save_stream_check_config(&config)
+    state.db.save_stream_check_config(&config)?;
+    StreamCheckScheduler::reschedule(&app, &config);
+    Ok(())
+}
+
+/// 获取供应商检查历史（按小时聚合的成功率与延迟，默认最近 48 个桶）
+#[tauri::command]
+pub fn get_stream_check_history(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<StreamCheckHistoryBucket>, AppError> {
+    state.db.get_stream_check_history(
+        app_type.as_str(),
+        &provider_id,
+        HISTORY_BUCKET_SECS,
+        limit.unwrap_or(48).clamp(1, 24 * 90),
+    )
 }
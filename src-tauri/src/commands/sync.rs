@@ -0,0 +1,90 @@
+use serde_json::json;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::services::provider::ProviderService;
+use crate::services::sync::{SyncConfig, SyncResult};
+use crate::services::SyncService;
+use crate::store::AppState;
+
+/// 获取云同步配置
+#[tauri::command]
+pub fn get_sync_config(state: State<'_, AppState>) -> Result<SyncConfig, String> {
+    state.db.get_sync_config().map_err(|e| e.to_string())
+}
+
+/// 保存云同步配置
+#[tauri::command]
+pub fn set_sync_config(state: State<'_, AppState>, config: SyncConfig) -> Result<bool, String> {
+    state
+        .db
+        .set_sync_config(&config)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 推送本地配置到云端
+///
+/// 远端有本机未拉取的修订时返回错误码 `sync.conflict.remote_newer`，
+/// 前端确认后可携带 `force: true` 覆盖远端。
+#[tauri::command]
+pub async fn sync_push(
+    state: State<'_, AppState>,
+    passphrase: String,
+    force: Option<bool>,
+) -> Result<SyncResult, String> {
+    SyncService::push(state.db.clone(), passphrase, force.unwrap_or(false))
+        .await
+        .map_err(sync_error)
+}
+
+/// 从云端拉取配置覆盖本地
+///
+/// 本地有未同步修改时返回错误码 `sync.conflict.local_modified`，
+/// 前端确认后可携带 `force: true` 丢弃本地修改。
+#[tauri::command]
+pub async fn sync_pull(
+    state: State<'_, AppState>,
+    passphrase: String,
+    force: Option<bool>,
+) -> Result<SyncResult, String> {
+    let result = SyncService::pull(state.db.clone(), passphrase, force.unwrap_or(false))
+        .await
+        .map_err(sync_error)?;
+
+    if result.transferred {
+        // 拉取后同步当前供应商到各自的 live 配置
+        let db = state.db.clone();
+        let synced = tauri::async_runtime::spawn_blocking(move || {
+            let app_state = AppState::new(db);
+            ProviderService::sync_current_to_live(&app_state)
+        })
+        .await;
+        if let Ok(Err(err)) = synced {
+            log::warn!("拉取后同步 live 配置失败: {err}");
+        }
+
+        // 重新加载设置到内存缓存，确保拉取的设置生效
+        if let Err(err) = crate::settings::reload_settings() {
+            log::warn!("拉取后重载设置失败: {err}");
+        }
+    }
+
+    Ok(result)
+}
+
+/// 同步冲突与加密相关错误以 JSON 返回（包含 code），便于前端区分处理
+fn sync_error(err: AppError) -> String {
+    match &err {
+        AppError::Localized { key, .. }
+            if key.starts_with("sync.") || key.starts_with("backup.encrypted.") =>
+        {
+            json!({
+                "code": key,
+                "message": err.to_string(),
+            })
+            .to_string()
+        }
+        _ => err.to_string(),
+    }
+}
@@ -1,8 +1,10 @@
 //! 使用统计相关命令
 
 use crate::error::AppError;
+use crate::services::currency::{CurrencyConversion, CurrencyService};
 use crate::services::usage_stats::*;
 use crate::store::AppState;
+use std::str::FromStr;
 use tauri::State;
 
 /// 获取使用量汇总
@@ -12,23 +14,58 @@ pub fn get_usage_summary(
     start_date: Option<i64>,
     end_date: Option<i64>,
 ) -> Result<UsageSummary, AppError> {
-    state.db.get_usage_summary(start_date, end_date)
+    let mut summary = state.db.get_usage_summary(start_date, end_date)?;
+    if let Some(conversion) = CurrencyConversion::from_settings() {
+        summary.display_cost = conversion.convert(&summary.total_cost);
+    }
+    Ok(summary)
 }
 
-/// 获取每日趋势
+/// 获取使用趋势
+///
+/// 未指定 bucket 时沿用滑动窗口（<=24h 按小时，>24h 按天）；
+/// 指定 hour/day/week/month 时按本地时区的自然边界分桶。
 #[tauri::command]
 pub fn get_usage_trends(
     state: State<'_, AppState>,
     start_date: Option<i64>,
     end_date: Option<i64>,
+    bucket: Option<TrendBucket>,
 ) -> Result<Vec<DailyStats>, AppError> {
-    state.db.get_daily_trends(start_date, end_date)
+    let mut stats = match bucket {
+        Some(bucket) => state
+            .db
+            .get_usage_trends_bucketed(bucket, start_date, end_date)?,
+        None => state.db.get_daily_trends(start_date, end_date)?,
+    };
+    if let Some(conversion) = CurrencyConversion::from_settings() {
+        for stat in &mut stats {
+            stat.display_cost = conversion.convert(&stat.total_cost);
+        }
+    }
+    Ok(stats)
+}
+
+/// 获取 Provider 统计（未指定时间范围时统计全部历史）
+#[tauri::command]
+pub fn get_provider_stats(
+    state: State<'_, AppState>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+) -> Result<Vec<ProviderStats>, AppError> {
+    let mut stats = state.db.get_provider_stats(start_date, end_date)?;
+    if let Some(conversion) = CurrencyConversion::from_settings() {
+        for stat in &mut stats {
+            stat.display_cost = conversion.convert(&stat.total_cost);
+        }
+    }
+    Ok(stats)
 }
 
-/// 获取 Provider 统计
+/// 获取 1 USD 兑换指定货币的汇率（只返回结果，不写入设置）
 #[tauri::command]
-pub fn get_provider_stats(state: State<'_, AppState>) -> Result<Vec<ProviderStats>, AppError> {
-    state.db.get_provider_stats()
+pub async fn fetch_usd_exchange_rate(currency: String) -> Result<String, AppError> {
+    CurrencyService::fetch_usd_rate(&currency).await
 }
 
 /// 获取模型统计
@@ -37,6 +74,23 @@ pub fn get_model_stats(state: State<'_, AppState>) -> Result<Vec<ModelStats>, Ap
     state.db.get_model_stats()
 }
 
+/// 对比各供应商的实际成本（按每 1K 输出 token 成本升序）
+#[tauri::command]
+pub fn get_provider_cost_comparison(
+    state: State<'_, AppState>,
+    app: Option<String>,
+    model_filter: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+) -> Result<Vec<ProviderCostComparison>, AppError> {
+    state.db.get_provider_cost_comparison(
+        app.as_deref(),
+        model_filter.as_deref(),
+        start_date,
+        end_date,
+    )
+}
+
 /// 获取请求日志列表
 #[tauri::command]
 pub fn get_request_logs(
@@ -107,6 +161,42 @@ pub fn get_model_pricing(state: State<'_, AppState>) -> Result<Vec<ModelPricingI
     Ok(pricing)
 }
 
+/// 预估请求成本
+///
+/// `input_tokens` 含缓存命中部分，与代理记录时的口径一致；
+/// `cost_multiplier` 缺省为 1。
+#[tauri::command]
+pub fn estimate_request_cost(
+    state: State<'_, AppState>,
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_read_tokens: Option<u64>,
+    cache_creation_tokens: Option<u64>,
+    cost_multiplier: Option<String>,
+) -> Result<CostEstimate, AppError> {
+    let multiplier = match cost_multiplier.as_deref().map(str::trim) {
+        Some(raw) if !raw.is_empty() => rust_decimal::Decimal::from_str(raw).map_err(|e| {
+            AppError::localized(
+                "usage.invalid_cost_multiplier",
+                format!("无效的成本倍率 '{raw}': {e}"),
+                format!("Invalid cost multiplier '{raw}': {e}"),
+            )
+        })?,
+        _ => rust_decimal::Decimal::ONE,
+    };
+
+    state.db.ensure_model_pricing_seeded()?;
+    state.db.estimate_cost(
+        &model,
+        input_tokens,
+        output_tokens,
+        cache_read_tokens.unwrap_or(0),
+        cache_creation_tokens.unwrap_or(0),
+        multiplier,
+    )
+}
+
 /// 更新模型定价
 #[tauri::command]
 pub fn update_model_pricing(
@@ -166,6 +256,43 @@ pub fn delete_model_pricing(state: State<'_, AppState>, model_id: String) -> Res
     Ok(())
 }
 
+/// 按保留策略立即清理请求日志
+#[tauri::command]
+pub async fn prune_request_logs(
+    state: State<'_, AppState>,
+) -> Result<crate::services::log_retention::PruneResult, AppError> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::services::LogRetentionService::prune(&db))
+        .await
+        .map_err(|e| AppError::Message(format!("清理请求日志失败: {e}")))?
+}
+
+/// 获取数据库统计信息（各表行数与文件大小），用于排查问题和判断是否需要清理
+#[tauri::command]
+pub fn get_database_stats(
+    state: State<'_, AppState>,
+) -> Result<crate::database::DatabaseStats, AppError> {
+    state.db.stats()
+}
+
+/// 获取请求日志保留策略
+#[tauri::command]
+pub fn get_log_retention_config(
+    state: State<'_, AppState>,
+) -> Result<crate::proxy::types::LogRetentionConfig, AppError> {
+    state.db.get_log_retention_config()
+}
+
+/// 设置请求日志保留策略
+#[tauri::command]
+pub fn set_log_retention_config(
+    state: State<'_, AppState>,
+    config: crate::proxy::types::LogRetentionConfig,
+) -> Result<bool, AppError> {
+    state.db.set_log_retention_config(&config)?;
+    Ok(true)
+}
+
 /// 模型定价信息
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::app_config::AppType;
 use crate::error::AppError;
 
 /// 获取用户主目录，带回退和日志
@@ -85,6 +88,40 @@ pub fn get_claude_settings_path() -> PathBuf {
     settings
 }
 
+/// Claude profiles 目录（profiles 写入模式下每个供应商一个配置文件）
+pub fn get_claude_profiles_dir() -> PathBuf {
+    get_claude_config_dir().join("profiles")
+}
+
+/// 供应商 ID 对应的 profile 名称：仅保留字母、数字、`-` 与 `_`，其余字符替换为 `_`
+///
+/// 发生替换时追加 ID 哈希后缀，避免 `a.b` 与 `a/b` 等不同供应商映射到同一文件。
+pub fn claude_profile_name(provider_id: &str) -> String {
+    let sanitized: String = provider_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized == provider_id {
+        return sanitized;
+    }
+    let hash = format!("{:x}", Sha256::digest(provider_id.as_bytes()));
+    format!("{sanitized}-{}", &hash[..8])
+}
+
+/// profile 文件路径；名称包含路径分隔符等非法字符时返回 None
+pub fn get_claude_profile_path(profile: &str) -> Option<PathBuf> {
+    if profile.is_empty() || claude_profile_name(profile) != profile {
+        return None;
+    }
+    Some(get_claude_profiles_dir().join(format!("{profile}.json")))
+}
+
 /// 获取应用配置目录路径 (~/.cc-switch)
 pub fn get_app_config_dir() -> PathBuf {
     if let Some(custom) = crate::app_store::get_app_config_dir_override() {
@@ -180,12 +217,95 @@ pub fn write_text_file(path: &Path, data: &str) -> Result<(), AppError> {
     atomic_write(path, data.as_bytes())
 }
 
+/// 文件被占用时的重试间隔（共 5 次尝试，约 1.5 秒）
+const WRITE_RETRY_DELAYS: [Duration; 4] = [
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(400),
+    Duration::from_millis(800),
+];
+
+/// 文件写入操作，便于在测试中注入模拟失败
+pub(crate) trait FileWriter {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AppError>;
+}
+
+/// 临时文件 + rename 的原子写入
+struct AtomicFileWriter;
+
+impl FileWriter for AtomicFileWriter {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AppError> {
+        atomic_write_once(path, data)
+    }
+}
+
 /// 原子写入：写入临时文件后 rename 替换，避免半写状态
+///
+/// 目标文件被其他程序短暂占用时（Windows 上 Claude Code、编辑器持有 settings.json 等）
+/// 按退避间隔重试，仍失败时返回指明被占用文件的错误。
 pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
     }
+    write_with_retry(&AtomicFileWriter, path, data, &WRITE_RETRY_DELAYS)
+}
+
+/// 写入失败且为文件占用类错误时按 `delays` 重试
+pub(crate) fn write_with_retry(
+    writer: &dyn FileWriter,
+    path: &Path,
+    data: &[u8],
+    delays: &[Duration],
+) -> Result<(), AppError> {
+    let mut attempt = 0;
+    loop {
+        let err = match writer.write(path, data) {
+            Ok(()) => return Ok(()),
+            Err(err) if is_file_locked_error(&err) => err,
+            Err(err) => return Err(err),
+        };
+        let Some(delay) = delays.get(attempt) else {
+            return Err(AppError::localized(
+                "config.file_locked",
+                format!(
+                    "文件被其他程序占用，写入失败（已尝试 {} 次）：{}：{err}",
+                    attempt + 1,
+                    path.display()
+                ),
+                format!(
+                    "File is locked by another program; write failed after {} attempts: {}: {err}",
+                    attempt + 1,
+                    path.display()
+                ),
+            ));
+        };
+        attempt += 1;
+        log::warn!(
+            "写入 {} 时文件被占用，{}ms 后第 {attempt} 次重试: {err}",
+            path.display(),
+            delay.as_millis()
+        );
+        std::thread::sleep(*delay);
+    }
+}
 
+/// 是否为文件被占用导致的暂时性错误
+/// （Windows: ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION；其他平台: EBUSY）
+fn is_file_locked_error(err: &AppError) -> bool {
+    let source = match err {
+        AppError::Io { source, .. } | AppError::IoContext { source, .. } => source,
+        _ => return false,
+    };
+    #[cfg(windows)]
+    {
+        if matches!(source.raw_os_error(), Some(32 | 33)) {
+            return true;
+        }
+    }
+    source.kind() == std::io::ErrorKind::ResourceBusy
+}
+
+fn atomic_write_once(path: &Path, data: &[u8]) -> Result<(), AppError> {
     let parent = path
         .parent()
         .ok_or_else(|| AppError::Config("无效的路径".to_string()))?;
@@ -222,17 +342,23 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
         if path.exists() {
             let _ = fs::remove_file(path);
         }
-        fs::rename(&tmp, path).map_err(|e| AppError::IoContext {
-            context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
-            source: e,
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            AppError::IoContext {
+                context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
+                source: e,
+            }
         })?;
     }
 
     #[cfg(not(windows))]
     {
-        fs::rename(&tmp, path).map_err(|e| AppError::IoContext {
-            context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
-            source: e,
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            AppError::IoContext {
+                context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
+                source: e,
+            }
         })?;
     }
     Ok(())
@@ -271,6 +397,94 @@ mod tests {
         let override_dir = PathBuf::from("/");
         assert!(derive_mcp_path_from_override(&override_dir).is_none());
     }
+
+    #[test]
+    fn claude_profile_name_disambiguates_sanitized_ids() {
+        assert_eq!(claude_profile_name("my-provider_1"), "my-provider_1");
+
+        let dotted = claude_profile_name("a.b");
+        let slashed = claude_profile_name("a/b");
+        assert!(dotted.starts_with("a_b-"));
+        assert_ne!(dotted, slashed);
+        assert!(get_claude_profile_path(&dotted).is_some());
+        assert!(get_claude_profile_path("../a").is_none());
+    }
+
+    struct FlakyWriter {
+        failures: std::cell::Cell<u32>,
+        kind: std::io::ErrorKind,
+        calls: std::cell::Cell<u32>,
+    }
+
+    impl FileWriter for FlakyWriter {
+        fn write(&self, path: &Path, _data: &[u8]) -> Result<(), AppError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.failures.get() == 0 {
+                return Ok(());
+            }
+            self.failures.set(self.failures.get() - 1);
+            Err(AppError::io(path, std::io::Error::from(self.kind)))
+        }
+    }
+
+    fn flaky(failures: u32, kind: std::io::ErrorKind) -> FlakyWriter {
+        FlakyWriter {
+            failures: std::cell::Cell::new(failures),
+            kind,
+            calls: std::cell::Cell::new(0),
+        }
+    }
+
+    #[test]
+    fn write_with_retry_retries_locked_files() {
+        let delays = [Duration::ZERO; 4];
+        let path = Path::new("/tmp/settings.json");
+
+        let writer = flaky(3, std::io::ErrorKind::ResourceBusy);
+        assert!(write_with_retry(&writer, path, b"{}", &delays).is_ok());
+        assert_eq!(writer.calls.get(), 4);
+
+        let writer = flaky(10, std::io::ErrorKind::ResourceBusy);
+        let err = write_with_retry(&writer, path, b"{}", &delays).expect_err("still locked");
+        assert_eq!(writer.calls.get(), 5);
+        assert!(err.to_string().contains("settings.json"));
+
+        // 非占用类错误不重试
+        let writer = flaky(1, std::io::ErrorKind::PermissionDenied);
+        assert!(write_with_retry(&writer, path, b"{}", &delays).is_err());
+        assert_eq!(writer.calls.get(), 1);
+    }
+
+    #[test]
+    fn validate_override_dir_reports_capabilities() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let dir = root.path().join(".claude");
+
+        let missing = validate_override_dir(&AppType::Claude, &dir);
+        assert!(missing.is_valid());
+        assert_eq!(missing.warnings, vec!["missing"]);
+
+        fs::create_dir_all(&dir).expect("create dir");
+        let empty = validate_override_dir(&AppType::Claude, &dir);
+        assert!(empty.writable && empty.atomic_rename == Some(true));
+        assert_eq!(empty.warnings, vec!["config_not_found"]);
+
+        // 只读检查不写探测文件
+        let inspected = inspect_override_dir(&AppType::Claude, &dir);
+        assert!(inspected.writable && inspected.atomic_rename.is_none());
+        assert_eq!(fs::read_dir(&dir).expect("read dir").count(), 0);
+
+        fs::write(dir.join("settings.json"), "{}").expect("write settings");
+        let healthy = validate_override_dir(&AppType::Claude, &dir);
+        assert!(healthy.errors.is_empty() && healthy.warnings.is_empty());
+        // 探测文件不残留
+        assert_eq!(fs::read_dir(&dir).expect("read dir").count(), 1);
+
+        let file = validate_override_dir(&AppType::Claude, &dir.join("settings.json"));
+        assert_eq!(file.errors, vec!["not_directory"]);
+        let relative = validate_override_dir(&AppType::Codex, Path::new("relative/.codex"));
+        assert!(relative.errors.contains(&"not_absolute".to_string()));
+    }
 }
 
 /// 复制文件
@@ -295,6 +509,16 @@ pub fn delete_file(path: &Path) -> Result<(), AppError> {
 pub struct ConfigStatus {
     pub exists: bool,
     pub path: String,
+    /// 已设置目录覆盖时的健康检查结果
+    #[serde(
+        rename = "overrideDir",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub override_dir: Option<OverrideDirReport>,
+    /// Claude Code 企业托管配置（仅 Claude，存在时返回）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managed: Option<crate::claude_managed::ManagedSettingsStatus>,
 }
 
 /// 获取 Claude Code 配置状态
@@ -303,5 +527,149 @@ pub fn get_claude_config_status() -> ConfigStatus {
     ConfigStatus {
         exists: path.exists(),
         path: path.to_string_lossy().to_string(),
+        override_dir: None,
+        managed: crate::claude_managed::live_managed_settings_status(),
+    }
+}
+
+/// 配置目录覆盖的健康检查结果
+///
+/// `errors` 中的问题会阻止保存；`warnings` 中的问题允许在确认后继续使用，但部分功能可能受限。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideDirReport {
+    pub path: String,
+    pub exists: bool,
+    pub writable: bool,
+    /// WSL UNC 路径对应的发行版
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wsl_distro: Option<String>,
+    /// 目录内的 rename 覆盖是否可用（Live 配置依赖它实现原子写入）；未做写入探测时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atomic_rename: Option<bool>,
+    /// not_absolute / not_directory / not_writable
+    pub errors: Vec<String>,
+    /// missing / wsl_path / atomic_rename_failed / config_not_found
+    pub warnings: Vec<String>,
+}
+
+impl OverrideDirReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 检查配置目录覆盖：存在性、可写性、是否为 WSL 路径以及原子 rename 是否可用
+///
+/// 会在目录内写入并删除探测文件，只用于显式检查（保存设置、预检命令）。
+pub fn validate_override_dir(app_type: &AppType, dir: &Path) -> OverrideDirReport {
+    check_override_dir(app_type, dir, true)
+}
+
+/// 只读检查配置目录覆盖：不写探测文件，可写性取自权限位，不检查原子 rename
+pub fn inspect_override_dir(app_type: &AppType, dir: &Path) -> OverrideDirReport {
+    check_override_dir(app_type, dir, false)
+}
+
+fn check_override_dir(app_type: &AppType, dir: &Path, probe_writes: bool) -> OverrideDirReport {
+    let mut report = OverrideDirReport {
+        path: dir.to_string_lossy().to_string(),
+        exists: dir.exists(),
+        wsl_distro: wsl_distro_from_path(dir),
+        ..Default::default()
+    };
+
+    if !dir.is_absolute() {
+        report.errors.push("not_absolute".to_string());
+    }
+    if report.wsl_distro.is_some() {
+        report.warnings.push("wsl_path".to_string());
+    }
+
+    if !report.exists {
+        report.warnings.push("missing".to_string());
+        return report;
+    }
+    if !dir.is_dir() {
+        report.errors.push("not_directory".to_string());
+        return report;
     }
+
+    if probe_writes {
+        let (writable, atomic_rename) = probe_dir_writes(dir);
+        report.writable = writable;
+        report.atomic_rename = Some(atomic_rename);
+    } else {
+        report.writable = fs::metadata(dir).is_ok_and(|meta| !meta.permissions().readonly());
+    }
+    if !report.writable {
+        report.errors.push("not_writable".to_string());
+    } else if report.atomic_rename == Some(false) {
+        report.warnings.push("atomic_rename_failed".to_string());
+    }
+
+    let markers: &[&str] = match app_type {
+        AppType::Claude => &["settings.json"],
+        AppType::Codex => &["config.toml", "auth.json"],
+        AppType::Gemini => &[".env", "settings.json"],
+        AppType::OpenCode => &["opencode.json"],
+    };
+    if !markers.iter().any(|name| dir.join(name).exists()) {
+        report.warnings.push("config_not_found".to_string());
+    }
+
+    report
+}
+
+/// 在目录内写入探测文件并用 rename 覆盖已有文件（与 `atomic_write` 相同的操作），
+/// 返回（可写, rename 可用）
+fn probe_dir_writes(dir: &Path) -> (bool, bool) {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let target = dir.join(format!(".cc-switch-probe.{ts}"));
+    let tmp = dir.join(format!(".cc-switch-probe.{ts}.tmp"));
+
+    if fs::write(&target, b"old").is_err() {
+        return (false, false);
+    }
+    let renamed = fs::write(&tmp, b"new").is_ok()
+        && fs::rename(&tmp, &target).is_ok()
+        && fs::read(&target).is_ok_and(|content| content == b"new");
+
+    let _ = fs::remove_file(&tmp);
+    let _ = fs::remove_file(&target);
+    (true, renamed)
+}
+
+/// 从 UNC 路径中提取 WSL 发行版名称
+/// 支持 `\\wsl$\Ubuntu\...` 和 `\\wsl.localhost\Ubuntu\...` 两种格式
+#[cfg(target_os = "windows")]
+pub fn wsl_distro_from_path(path: &Path) -> Option<String> {
+    use std::path::{Component, Prefix};
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return None;
+    };
+    match prefix.kind() {
+        Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+            let server_name = server.to_string_lossy();
+            if server_name.eq_ignore_ascii_case("wsl$")
+                || server_name.eq_ignore_ascii_case("wsl.localhost")
+            {
+                let distro = share.to_string_lossy().to_string();
+                if !distro.is_empty() {
+                    return Some(distro);
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// 非 Windows 平台不支持 WSL 路径解析
+#[cfg(not(target_os = "windows"))]
+pub fn wsl_distro_from_path(_path: &Path) -> Option<String> {
+    None
 }
@@ -2,24 +2,50 @@
 //!
 //! 提供 SQL 导出/导入和二进制快照备份功能。
 
-use super::{lock_conn, Database, DB_BACKUP_RETAIN};
+use super::sync_payload::SYNC_SETTING_PREFIX;
+use super::{backup_crypto, lock_conn, Database, DB_BACKUP_RETAIN};
 use crate::config::get_app_config_dir;
 use crate::error::AppError;
 use chrono::Utc;
 use rusqlite::backup::Backup;
 use rusqlite::types::ValueRef;
-use rusqlite::Connection;
+use rusqlite::{params, Connection};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tempfile::NamedTempFile;
 
 const CC_SWITCH_SQL_EXPORT_HEADER: &str = "-- CC Switch SQLite 导出";
 
+/// 正在进行中的导出数量（导出期间禁止清理请求日志等破坏性维护操作）
+static EXPORTS_IN_PROGRESS: AtomicUsize = AtomicUsize::new(0);
+
+/// 导出期间持有的守卫，Drop 时自动递减计数
+pub(crate) struct ExportGuard;
+
+impl ExportGuard {
+    pub(crate) fn acquire() -> Self {
+        EXPORTS_IN_PROGRESS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for ExportGuard {
+    fn drop(&mut self) {
+        EXPORTS_IN_PROGRESS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 当前是否有导出正在进行
+pub(crate) fn is_export_in_progress() -> bool {
+    EXPORTS_IN_PROGRESS.load(Ordering::SeqCst) > 0
+}
+
 impl Database {
     /// 导出为 SQLite 兼容的 SQL 文本
     pub fn export_sql(&self, target_path: &Path) -> Result<(), AppError> {
-        let snapshot = self.snapshot_to_memory()?;
-        let dump = Self::dump_sql(&snapshot)?;
+        let _guard = ExportGuard::acquire();
+        let dump = self.dump_for_export()?;
 
         if let Some(parent) = target_path.parent() {
             fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
@@ -28,8 +54,67 @@ impl Database {
         crate::config::atomic_write(target_path, dump.as_bytes())
     }
 
+    /// 导出为加密备份（SQL 文本经 gzip 压缩后使用口令加密）
+    pub fn export_sql_encrypted(
+        &self,
+        target_path: &Path,
+        passphrase: &str,
+    ) -> Result<(), AppError> {
+        let _guard = ExportGuard::acquire();
+        let dump = self.dump_for_export()?;
+        let encrypted = backup_crypto::encrypt(dump.as_bytes(), passphrase)?;
+
+        if let Some(parent) = target_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+
+        crate::config::atomic_write(target_path, &encrypted)
+    }
+
     /// 从 SQL 文件导入，返回生成的备份 ID（若无备份则为空字符串）
     pub fn import_sql(&self, source_path: &Path) -> Result<String, AppError> {
+        self.import_sql_with_passphrase(source_path, None)
+    }
+
+    /// 从 SQL 文件（可能已加密）导入，返回生成的备份 ID
+    ///
+    /// 加密文件未提供口令时返回 `backup.encrypted.passphrase_required` 错误；
+    /// 解密在导入前完整完成，口令错误不会对数据库产生任何修改。
+    pub fn import_sql_with_passphrase(
+        &self,
+        source_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<String, AppError> {
+        let (_temp_file, temp_conn) = Self::load_sql_export(source_path, passphrase)?;
+
+        // 导入前备份现有数据库
+        let backup_path = self.backup_database_file()?;
+
+        // 使用 Backup 将临时库原子写回主库；导出文件不含同步设置项，沿用本机的
+        {
+            let mut main_conn = lock_conn!(self.conn);
+            Self::copy_sync_settings(&main_conn, &temp_conn)?;
+            let backup = Backup::new(&temp_conn, &mut main_conn)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            backup
+                .step(-1)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        let backup_id = backup_path
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_default();
+
+        Ok(backup_id)
+    }
+
+    /// 将 SQL 导出文件（可加密）加载到临时数据库，并补齐表结构、执行迁移与基础校验
+    ///
+    /// 返回的临时文件需与连接一同持有，连接关闭前不能释放。
+    pub(crate) fn load_sql_export(
+        source_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<(NamedTempFile, Connection), AppError> {
         if !source_path.exists() {
             return Err(AppError::InvalidInput(format!(
                 "SQL 文件不存在: {}",
@@ -37,13 +122,24 @@ impl Database {
             )));
         }
 
-        let sql_raw = fs::read_to_string(source_path).map_err(|e| AppError::io(source_path, e))?;
+        let raw = fs::read(source_path).map_err(|e| AppError::io(source_path, e))?;
+        let raw = if backup_crypto::is_encrypted(&raw) {
+            let passphrase = passphrase
+                .filter(|p| !p.is_empty())
+                .ok_or_else(backup_crypto::passphrase_required)?;
+            backup_crypto::decrypt(&raw, passphrase)?
+        } else {
+            raw
+        };
+        let sql_raw = String::from_utf8(raw).map_err(|e| {
+            AppError::io(
+                source_path,
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })?;
         let sql_content = sql_raw.trim_start_matches('\u{feff}');
         Self::validate_cc_switch_sql_export(sql_content)?;
 
-        // 导入前备份现有数据库
-        let backup_path = self.backup_database_file()?;
-
         // 在临时数据库执行导入，确保失败不会污染主库
         let temp_file = NamedTempFile::new().map_err(|e| AppError::IoContext {
             context: "创建临时数据库文件失败".to_string(),
@@ -62,21 +158,7 @@ impl Database {
         Self::apply_schema_migrations_on_conn(&temp_conn)?;
         Self::validate_basic_state(&temp_conn)?;
 
-        // 使用 Backup 将临时库原子写回主库
-        {
-            let mut main_conn = lock_conn!(self.conn);
-            let backup = Backup::new(&temp_conn, &mut main_conn)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-            backup
-                .step(-1)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-        }
-
-        let backup_id = backup_path
-            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
-            .unwrap_or_default();
-
-        Ok(backup_id)
+        Ok((temp_file, temp_conn))
     }
 
     /// 创建内存快照以避免长时间持有数据库锁
@@ -109,8 +191,21 @@ impl Database {
         ))
     }
 
+    /// 将当前数据库的一致性快照写入指定路径
+    pub(crate) fn snapshot_to(&self, dest_path: &Path) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut dest_conn =
+            Connection::open(dest_path).map_err(|e| AppError::Database(e.to_string()))?;
+        let backup =
+            Backup::new(&conn, &mut dest_conn).map_err(|e| AppError::Database(e.to_string()))?;
+        backup
+            .step(-1)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 生成一致性快照备份，返回备份文件路径（不存在主库时返回 None）
-    fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
+    pub(crate) fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
         let db_path = get_app_config_dir().join("cc-switch.db");
         if !db_path.exists() {
             return Ok(None);
@@ -133,16 +228,7 @@ impl Database {
             counter += 1;
         }
 
-        {
-            let conn = lock_conn!(self.conn);
-            let mut dest_conn =
-                Connection::open(&backup_path).map_err(|e| AppError::Database(e.to_string()))?;
-            let backup = Backup::new(&conn, &mut dest_conn)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-            backup
-                .step(-1)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-        }
+        self.snapshot_to(&backup_path)?;
 
         Self::cleanup_db_backups(&backup_dir)?;
         Ok(Some(backup_path))
@@ -197,8 +283,44 @@ impl Database {
         Ok(())
     }
 
+    /// 生成用户导出的 SQL 文本
+    ///
+    /// 同步设置项（`sync_` 前缀，含 WebDAV 密码 / S3 密钥）以明文保存在本机 settings 表，
+    /// 导出文件可能被分享或上传，因此不包含这些设置项。
+    fn dump_for_export(&self) -> Result<String, AppError> {
+        let snapshot = self.snapshot_to_memory()?;
+        snapshot
+            .execute(
+                "DELETE FROM settings WHERE substr(key, 1, length(?1)) = ?1",
+                params![SYNC_SETTING_PREFIX],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::dump_sql(&snapshot)
+    }
+
+    /// 把 `from` 中的同步设置项写入 `to`（覆盖同名项）
+    fn copy_sync_settings(from: &Connection, to: &Connection) -> Result<(), AppError> {
+        let mut stmt = from
+            .prepare("SELECT key, value FROM settings WHERE substr(key, 1, length(?1)) = ?1")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![SYNC_SETTING_PREFIX], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for row in rows {
+            let (key, value) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            to.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     /// 导出数据库为 SQL 文本
-    fn dump_sql(conn: &Connection) -> Result<String, AppError> {
+    pub(super) fn dump_sql(conn: &Connection) -> Result<String, AppError> {
         let mut output = String::new();
         let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let user_version: i64 = conn
@@ -284,7 +406,10 @@ impl Database {
     }
 
     /// 获取表的列名列表
-    fn get_table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, AppError> {
+    pub(super) fn get_table_columns(
+        conn: &Connection,
+        table: &str,
+    ) -> Result<Vec<String>, AppError> {
         let mut stmt = conn
             .prepare(&format!("PRAGMA table_info(\"{table}\")"))
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -0,0 +1,233 @@
+//! 加密备份格式
+//!
+//! 导出内容先 gzip 压缩，再使用口令派生的密钥（PBKDF2-HMAC-SHA256）进行 AES-256-GCM 加密。
+//!
+//! ```text
+//! +----------+---------+-----+------------+----------+------+-------+------------------+
+//! | magic(8) | ver(1)  | kdf | iter(u32)  | salt_len | salt | nonce | ciphertext + tag |
+//! +----------+---------+-----+------------+----------+------+-------+------------------+
+//! ```
+//!
+//! 文件头整体作为 AEAD 附加数据参与认证，任何篡改都会导致解密失败。
+
+use crate::error::AppError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+
+/// 加密备份文件魔数
+const MAGIC: &[u8; 8] = b"CCSWENC\0";
+/// 当前格式版本
+const FORMAT_VERSION: u8 = 1;
+/// KDF 标识：PBKDF2-HMAC-SHA256
+const KDF_PBKDF2_SHA256: u8 = 1;
+/// PBKDF2 迭代次数（OWASP 2023 推荐值）
+#[cfg(not(test))]
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// 测试中使用较小的迭代次数，避免 debug 构建下测试过慢
+#[cfg(test)]
+const PBKDF2_ITERATIONS: u32 = 1_000;
+/// 解密时允许的最大迭代次数，防止恶意文件导致长时间阻塞
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// 判断数据是否为加密备份格式
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// 压缩并加密明文
+pub(crate) fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if passphrase.is_empty() {
+        return Err(passphrase_required());
+    }
+
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce_bytes))
+        .map_err(|_| AppError::Message("生成随机数失败".to_string()))?;
+
+    let mut header = Vec::with_capacity(MAGIC.len() + 7 + SALT_LEN + NONCE_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(FORMAT_VERSION);
+    header.push(KDF_PBKDF2_SHA256);
+    header.extend_from_slice(&PBKDF2_ITERATIONS.to_be_bytes());
+    header.push(SALT_LEN as u8);
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce_bytes);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(plaintext)
+        .map_err(|e| AppError::IoContext {
+            context: "压缩备份内容失败".to_string(),
+            source: e,
+        })?;
+    let mut in_out = encoder.finish().map_err(|e| AppError::IoContext {
+        context: "压缩备份内容失败".to_string(),
+        source: e,
+    })?;
+
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce_bytes),
+        Aad::from(header.as_slice()),
+        &mut in_out,
+    )
+    .map_err(|_| AppError::Message("加密备份内容失败".to_string()))?;
+
+    header.extend_from_slice(&in_out);
+    Ok(header)
+}
+
+/// 解密并解压加密备份
+pub(crate) fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if !is_encrypted(data) {
+        return Err(corrupted());
+    }
+    if passphrase.is_empty() {
+        return Err(passphrase_required());
+    }
+
+    let mut pos = MAGIC.len();
+    let version = *data.get(pos).ok_or_else(corrupted)?;
+    if version != FORMAT_VERSION {
+        return Err(AppError::localized(
+            "backup.encrypted.unsupported_version",
+            format!("不支持的加密备份版本: {version}，请升级 CC Switch 后再试"),
+            format!("Unsupported encrypted backup version: {version}, please upgrade CC Switch"),
+        ));
+    }
+    pos += 1;
+
+    let kdf = *data.get(pos).ok_or_else(corrupted)?;
+    if kdf != KDF_PBKDF2_SHA256 {
+        return Err(corrupted());
+    }
+    pos += 1;
+
+    let iterations = data
+        .get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(corrupted)?;
+    if iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS {
+        return Err(corrupted());
+    }
+    pos += 4;
+
+    let salt_len = *data.get(pos).ok_or_else(corrupted)? as usize;
+    pos += 1;
+    let salt = data.get(pos..pos + salt_len).ok_or_else(corrupted)?;
+    pos += salt_len;
+
+    let nonce_slice = data.get(pos..pos + NONCE_LEN).ok_or_else(corrupted)?;
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    nonce_bytes.copy_from_slice(nonce_slice);
+    pos += NONCE_LEN;
+
+    let (header, ciphertext) = data.split_at(pos);
+    let mut in_out = ciphertext.to_vec();
+
+    let key = derive_key(passphrase, salt, iterations)?;
+    let compressed = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::from(header),
+            &mut in_out,
+        )
+        .map_err(|_| {
+            AppError::localized(
+                "backup.encrypted.wrong_passphrase",
+                "口令错误或备份文件已损坏",
+                "Wrong passphrase or the backup file is corrupted",
+            )
+        })?;
+
+    let mut plaintext = Vec::new();
+    GzDecoder::new(&compressed[..])
+        .read_to_end(&mut plaintext)
+        .map_err(|_| corrupted())?;
+    Ok(plaintext)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, AppError> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(corrupted)?;
+    let mut key_bytes = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key_bytes,
+    );
+    let unbound = UnboundKey::new(&AES_256_GCM, &key_bytes)
+        .map_err(|_| AppError::Message("初始化加密密钥失败".to_string()))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+pub(crate) fn passphrase_required() -> AppError {
+    AppError::localized(
+        "backup.encrypted.passphrase_required",
+        "该备份文件已加密，请输入口令",
+        "This backup is encrypted, a passphrase is required",
+    )
+}
+
+fn corrupted() -> AppError {
+    AppError::localized(
+        "backup.encrypted.corrupted",
+        "加密备份文件格式无效或已损坏",
+        "The encrypted backup file is invalid or corrupted",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let data = b"-- CC Switch SQLite \xE5\xAF\xBC\xE5\x87\xBA\nSELECT 1;";
+        let encrypted = encrypt(data, "secret").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(&encrypted, "secret").unwrap(), data);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_distinct_error() {
+        let encrypted = encrypt(b"payload", "secret").unwrap();
+        let err = decrypt(&encrypted, "wrong").unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "backup.encrypted.wrong_passphrase",
+                ..
+            }
+        ));
+
+        let err = decrypt(&encrypted, "").unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "backup.encrypted.passphrase_required",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tampered_header_fails_authentication() {
+        let mut encrypted = encrypt(b"payload", "secret").unwrap();
+        // 修改 salt 的一个字节
+        encrypted[MAGIC.len() + 7] ^= 0xFF;
+        assert!(decrypt(&encrypted, "secret").is_err());
+    }
+}
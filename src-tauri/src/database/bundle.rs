@@ -0,0 +1,724 @@
+//! 团队配置包
+//!
+//! 与面向供应商的 SQL 备份不同，配置包是一份带版本号的 JSON，包含：
+//! - MCP 服务器（完整定义与各应用启用状态）
+//! - 提示词（按应用区分）
+//! - Skills 元数据（仓库来源 + 启用状态，不包含技能文件本身）与 Skill 仓库
+//!
+//! 导出时从各 DAO 读取数据；导入时按类别（MCP / 提示词 / Skills）各自在一个事务中完成，
+//! 某一类别失败只回滚该类别，并在结果中逐项报告新增 / 相同 / 冲突状态。
+
+use super::selective_import::{ImportItemStatus, ImportResolution};
+use super::{lock_conn, Database};
+use crate::app_config::{AppType, InstalledSkill, McpServer, SkillApps};
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::services::skill::SkillRepo;
+use indexmap::IndexMap;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// 配置包格式标识
+pub const BUNDLE_FORMAT: &str = "cc-switch-bundle";
+
+/// 当前配置包版本
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 配置包内容清单
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    /// MCP 服务器 ID
+    pub mcp_servers: Vec<String>,
+    /// 提示词（`app:id`）
+    pub prompts: Vec<String>,
+    /// Skill ID
+    pub skills: Vec<String>,
+    /// Skill 仓库（`owner/name`）
+    pub skill_repos: Vec<String>,
+}
+
+/// 配置包中的提示词（附带所属应用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePrompt {
+    pub app_type: String,
+    #[serde(flatten)]
+    pub prompt: Prompt,
+}
+
+/// 配置包中的 Skill：仅记录仓库来源与启用状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSkill {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub directory: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_branch: Option<String>,
+    pub apps: SkillApps,
+}
+
+impl From<&InstalledSkill> for BundleSkill {
+    fn from(skill: &InstalledSkill) -> Self {
+        Self {
+            id: skill.id.clone(),
+            name: skill.name.clone(),
+            description: skill.description.clone(),
+            directory: skill.directory.clone(),
+            repo_owner: skill.repo_owner.clone(),
+            repo_name: skill.repo_name.clone(),
+            repo_branch: skill.repo_branch.clone(),
+            apps: skill.apps.clone(),
+        }
+    }
+}
+
+/// 配置包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub manifest: BundleManifest,
+    #[serde(default)]
+    pub mcp_servers: Vec<McpServer>,
+    #[serde(default)]
+    pub prompts: Vec<BundlePrompt>,
+    #[serde(default)]
+    pub skills: Vec<BundleSkill>,
+    #[serde(default)]
+    pub skill_repos: Vec<SkillRepo>,
+}
+
+/// 配置包类别（每个类别在独立事务中导入）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleCategory {
+    McpServers,
+    Prompts,
+    Skills,
+}
+
+/// 单个条目的导入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleItemOutcome {
+    Added,
+    Replaced,
+    Renamed,
+    /// 冲突且保留本地版本
+    Skipped,
+    /// 与本地一致，无需修改
+    Unchanged,
+    /// 本地尚未安装该 Skill，需要从仓库安装后才会生效
+    PendingInstall,
+}
+
+/// 导入报告中的单个条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportItem {
+    /// 条目唯一键（作为 resolutions 的键），如 `mcp:fetch`、`prompt:claude:default`
+    pub key: String,
+    pub category: BundleCategory,
+    pub id: String,
+    pub name: String,
+    pub status: ImportItemStatus,
+    pub outcome: BundleItemOutcome,
+}
+
+/// 类别导入失败信息（该类别的修改已整体回滚）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCategoryError {
+    pub category: BundleCategory,
+    pub message: String,
+}
+
+/// 配置包导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResult {
+    pub items: Vec<BundleImportItem>,
+    pub conflict_count: usize,
+    pub errors: Vec<BundleCategoryError>,
+}
+
+impl Database {
+    /// 从各 DAO 读取数据生成配置包
+    pub fn export_bundle(&self) -> Result<ConfigBundle, AppError> {
+        let mcp_servers: Vec<McpServer> = self.get_all_mcp_servers()?.into_values().collect();
+
+        let mut prompts = Vec::new();
+        for app in AppType::all() {
+            for prompt in self.get_prompts(app.as_str())?.into_values() {
+                prompts.push(BundlePrompt {
+                    app_type: app.as_str().to_string(),
+                    prompt,
+                });
+            }
+        }
+
+        let skills: Vec<BundleSkill> = self
+            .get_all_installed_skills()?
+            .values()
+            .map(BundleSkill::from)
+            .collect();
+        let skill_repos = self.get_skill_repos()?;
+
+        let manifest = BundleManifest {
+            mcp_servers: mcp_servers.iter().map(|s| s.id.clone()).collect(),
+            prompts: prompts
+                .iter()
+                .map(|p| format!("{}:{}", p.app_type, p.prompt.id))
+                .collect(),
+            skills: skills.iter().map(|s| s.id.clone()).collect(),
+            skill_repos: skill_repos
+                .iter()
+                .map(|r| format!("{}/{}", r.owner, r.name))
+                .collect(),
+        };
+
+        Ok(ConfigBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            manifest,
+            mcp_servers,
+            prompts,
+            skills,
+            skill_repos,
+        })
+    }
+
+    /// 导入配置包
+    ///
+    /// 新条目直接写入；冲突条目按 `resolutions` 处理，未指定时保留本地版本。
+    /// 本地未安装的 Skill 不会写入数据库，仅报告为待安装并补充其来源仓库。
+    pub fn import_bundle(
+        &self,
+        bundle: &ConfigBundle,
+        resolutions: &HashMap<String, ImportResolution>,
+    ) -> Result<BundleImportResult, AppError> {
+        validate_bundle(bundle)?;
+
+        let local_mcp = self.get_all_mcp_servers()?;
+        let mut local_prompts = HashMap::new();
+        for app in AppType::all() {
+            local_prompts.insert(app.as_str().to_string(), self.get_prompts(app.as_str())?);
+        }
+        let local_skills = self.get_all_installed_skills()?;
+        let local_repos = self.get_skill_repos()?;
+
+        let mut result = BundleImportResult::default();
+        let mut conn = lock_conn!(self.conn);
+
+        run_category(&mut conn, BundleCategory::McpServers, &mut result, |tx| {
+            import_mcp_servers(tx, &bundle.mcp_servers, &local_mcp, resolutions)
+        })?;
+        run_category(&mut conn, BundleCategory::Prompts, &mut result, |tx| {
+            import_prompts(tx, &bundle.prompts, &local_prompts, resolutions)
+        })?;
+        run_category(&mut conn, BundleCategory::Skills, &mut result, |tx| {
+            import_skills(tx, bundle, &local_skills, &local_repos, resolutions)
+        })?;
+
+        result.conflict_count = result
+            .items
+            .iter()
+            .filter(|item| item.status == ImportItemStatus::Conflict)
+            .count();
+        Ok(result)
+    }
+}
+
+/// 在独立事务中导入一个类别；类别内失败时回滚并记录错误，不影响其他类别
+fn run_category(
+    conn: &mut Connection,
+    category: BundleCategory,
+    result: &mut BundleImportResult,
+    import: impl FnOnce(&Connection) -> Result<Vec<BundleImportItem>, AppError>,
+) -> Result<(), AppError> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Database(format!("开启导入事务失败: {e}")))?;
+
+    match import(&tx) {
+        Ok(items) => {
+            tx.commit()
+                .map_err(|e| AppError::Database(format!("提交导入事务失败: {e}")))?;
+            result.items.extend(items);
+        }
+        Err(e) => {
+            // tx 在此处 drop，自动回滚
+            log::warn!("导入配置包 {category:?} 失败，已回滚: {e}");
+            result.errors.push(BundleCategoryError {
+                category,
+                message: e.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_bundle(bundle: &ConfigBundle) -> Result<(), AppError> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(AppError::localized(
+            "bundle.invalid_format",
+            "不是有效的 CC Switch 配置包",
+            "Not a valid CC Switch bundle",
+        ));
+    }
+    if bundle.version == 0 || bundle.version > BUNDLE_VERSION {
+        return Err(AppError::localized(
+            "bundle.unsupported_version",
+            format!(
+                "配置包版本 {} 不受支持（当前支持 {BUNDLE_VERSION}）",
+                bundle.version
+            ),
+            format!(
+                "Bundle version {} is not supported (supported: {BUNDLE_VERSION})",
+                bundle.version
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 根据比较结果与用户选择确定处理方式（相同条目返回 None）
+fn resolve(
+    status: ImportItemStatus,
+    key: &str,
+    resolutions: &HashMap<String, ImportResolution>,
+) -> Option<ImportResolution> {
+    match status {
+        ImportItemStatus::Identical => None,
+        ImportItemStatus::New => Some(ImportResolution::TakeImport),
+        ImportItemStatus::Conflict => Some(
+            resolutions
+                .get(key)
+                .copied()
+                .unwrap_or(ImportResolution::KeepLocal),
+        ),
+    }
+}
+
+/// 生成不与现有 ID 冲突的新 ID
+fn unique_id(base: &str, exists: impl Fn(&str) -> bool) -> String {
+    let mut candidate = format!("{base}-imported");
+    let mut counter = 2;
+    while exists(&candidate) {
+        candidate = format!("{base}-imported-{counter}");
+        counter += 1;
+    }
+    candidate
+}
+
+fn import_mcp_servers(
+    conn: &Connection,
+    servers: &[McpServer],
+    local: &IndexMap<String, McpServer>,
+    resolutions: &HashMap<String, ImportResolution>,
+) -> Result<Vec<BundleImportItem>, AppError> {
+    let mut taken: HashSet<String> = local.keys().cloned().collect();
+    let mut items = Vec::new();
+
+    for server in servers {
+        let key = format!("mcp:{}", server.id);
+        let status = match local.get(&server.id) {
+            None => ImportItemStatus::New,
+            Some(existing) if same_json(existing, server) => ImportItemStatus::Identical,
+            Some(_) => ImportItemStatus::Conflict,
+        };
+
+        let outcome = match resolve(status, &key, resolutions) {
+            None => BundleItemOutcome::Unchanged,
+            Some(ImportResolution::TakeImport) => {
+                Database::save_mcp_server_on_conn(conn, server)?;
+                taken.insert(server.id.clone());
+                if status == ImportItemStatus::New {
+                    BundleItemOutcome::Added
+                } else {
+                    BundleItemOutcome::Replaced
+                }
+            }
+            Some(ImportResolution::DuplicateRename) => {
+                let mut renamed = server.clone();
+                renamed.id = unique_id(&server.id, |id| taken.contains(id));
+                renamed.name = format!("{} (imported)", server.name);
+                Database::save_mcp_server_on_conn(conn, &renamed)?;
+                taken.insert(renamed.id);
+                BundleItemOutcome::Renamed
+            }
+            Some(ImportResolution::KeepLocal) => BundleItemOutcome::Skipped,
+        };
+
+        items.push(BundleImportItem {
+            key,
+            category: BundleCategory::McpServers,
+            id: server.id.clone(),
+            name: server.name.clone(),
+            status,
+            outcome,
+        });
+    }
+    Ok(items)
+}
+
+fn import_prompts(
+    conn: &Connection,
+    prompts: &[BundlePrompt],
+    local: &HashMap<String, IndexMap<String, Prompt>>,
+    resolutions: &HashMap<String, ImportResolution>,
+) -> Result<Vec<BundleImportItem>, AppError> {
+    let mut items = Vec::new();
+    let mut taken: HashSet<(String, String)> = local
+        .iter()
+        .flat_map(|(app, prompts)| prompts.keys().map(move |id| (app.clone(), id.clone())))
+        .collect();
+
+    for entry in prompts {
+        let app_type = entry.app_type.parse::<AppType>()?;
+        let app = app_type.as_str().to_string();
+        let prompt = &entry.prompt;
+        let key = format!("prompt:{app}:{}", prompt.id);
+
+        let existing = local.get(&app).and_then(|p| p.get(&prompt.id));
+        let status = match existing {
+            None => ImportItemStatus::New,
+            Some(existing) if same_prompt_content(existing, prompt) => ImportItemStatus::Identical,
+            Some(_) => ImportItemStatus::Conflict,
+        };
+
+        let outcome = match resolve(status, &key, resolutions) {
+            None => BundleItemOutcome::Unchanged,
+            Some(ImportResolution::TakeImport) => {
+                // 启用状态属于本机：新增默认不启用，覆盖时保留本地状态
+                let mut incoming = prompt.clone();
+                incoming.enabled = existing.is_some_and(|p| p.enabled);
+                if let Some(existing) = existing {
+                    incoming.created_at = existing.created_at;
+                }
+                Database::save_prompt_on_conn(conn, &app, &incoming)?;
+                taken.insert((app.clone(), prompt.id.clone()));
+                if status == ImportItemStatus::New {
+                    BundleItemOutcome::Added
+                } else {
+                    BundleItemOutcome::Replaced
+                }
+            }
+            Some(ImportResolution::DuplicateRename) => {
+                let mut renamed = prompt.clone();
+                renamed.id = unique_id(&prompt.id, |id| {
+                    taken.contains(&(app.clone(), id.to_string()))
+                });
+                renamed.name = format!("{} (imported)", prompt.name);
+                renamed.enabled = false;
+                Database::save_prompt_on_conn(conn, &app, &renamed)?;
+                taken.insert((app.clone(), renamed.id));
+                BundleItemOutcome::Renamed
+            }
+            Some(ImportResolution::KeepLocal) => BundleItemOutcome::Skipped,
+        };
+
+        items.push(BundleImportItem {
+            key,
+            category: BundleCategory::Prompts,
+            id: prompt.id.clone(),
+            name: prompt.name.clone(),
+            status,
+            outcome,
+        });
+    }
+    Ok(items)
+}
+
+fn import_skills(
+    conn: &Connection,
+    bundle: &ConfigBundle,
+    local_skills: &IndexMap<String, InstalledSkill>,
+    local_repos: &[SkillRepo],
+    resolutions: &HashMap<String, ImportResolution>,
+) -> Result<Vec<BundleImportItem>, AppError> {
+    let mut items = Vec::new();
+
+    // 仓库：待安装 Skill 依赖其来源仓库，因此先于 Skill 处理
+    let mut known_repos: HashSet<(String, String)> = local_repos
+        .iter()
+        .map(|r| (r.owner.clone(), r.name.clone()))
+        .collect();
+    for repo in &bundle.skill_repos {
+        let key = format!("skill_repo:{}/{}", repo.owner, repo.name);
+        let existing = local_repos
+            .iter()
+            .find(|r| r.owner == repo.owner && r.name == repo.name);
+        let status = match existing {
+            None => ImportItemStatus::New,
+            Some(r) if r.branch == repo.branch && r.enabled == repo.enabled => {
+                ImportItemStatus::Identical
+            }
+            Some(_) => ImportItemStatus::Conflict,
+        };
+
+        // 仓库以 owner/name 唯一标识，另存为新 ID 没有意义，按保留本地处理
+        let outcome = match resolve(status, &key, resolutions) {
+            None => BundleItemOutcome::Unchanged,
+            Some(ImportResolution::TakeImport) => {
+                Database::save_skill_repo_on_conn(conn, repo)?;
+                known_repos.insert((repo.owner.clone(), repo.name.clone()));
+                if status == ImportItemStatus::New {
+                    BundleItemOutcome::Added
+                } else {
+                    BundleItemOutcome::Replaced
+                }
+            }
+            Some(_) => BundleItemOutcome::Skipped,
+        };
+
+        items.push(BundleImportItem {
+            key,
+            category: BundleCategory::Skills,
+            id: format!("{}/{}", repo.owner, repo.name),
+            name: repo.name.clone(),
+            status,
+            outcome,
+        });
+    }
+
+    for skill in &bundle.skills {
+        let key = format!("skill:{}", skill.id);
+        let Some(existing) = local_skills.get(&skill.id) else {
+            // 技能文件不在配置包中：确保来源仓库存在，交由用户安装
+            if let (Some(owner), Some(name)) = (&skill.repo_owner, &skill.repo_name) {
+                if known_repos.insert((owner.clone(), name.clone())) {
+                    Database::save_skill_repo_on_conn(
+                        conn,
+                        &SkillRepo {
+                            owner: owner.clone(),
+                            name: name.clone(),
+                            branch: skill
+                                .repo_branch
+                                .clone()
+                                .unwrap_or_else(|| "main".to_string()),
+                            enabled: true,
+                            ..Default::default()
+                        },
+                    )?;
+                }
+            }
+            items.push(BundleImportItem {
+                key,
+                category: BundleCategory::Skills,
+                id: skill.id.clone(),
+                name: skill.name.clone(),
+                status: ImportItemStatus::New,
+                outcome: BundleItemOutcome::PendingInstall,
+            });
+            continue;
+        };
+
+        let status = if existing.apps == skill.apps {
+            ImportItemStatus::Identical
+        } else {
+            ImportItemStatus::Conflict
+        };
+        let outcome = match resolve(status, &key, resolutions) {
+            None => BundleItemOutcome::Unchanged,
+            Some(ImportResolution::TakeImport) => {
+                Database::update_skill_apps_on_conn(conn, &skill.id, &skill.apps)?;
+                BundleItemOutcome::Replaced
+            }
+            Some(_) => BundleItemOutcome::Skipped,
+        };
+
+        items.push(BundleImportItem {
+            key,
+            category: BundleCategory::Skills,
+            id: skill.id.clone(),
+            name: skill.name.clone(),
+            status,
+            outcome,
+        });
+    }
+    Ok(items)
+}
+
+fn same_json<T: Serialize>(a: &T, b: &T) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 比较提示词内容（忽略启用状态与时间戳）
+fn same_prompt_content(a: &Prompt, b: &Prompt) -> bool {
+    a.name == b.name && a.content == b.content && a.description == b.description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::McpApps;
+    use serde_json::json;
+
+    fn mcp_server(id: &str, command: &str) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: id.to_string(),
+            server: json!({ "type": "stdio", "command": command }),
+            apps: McpApps {
+                claude: true,
+                ..Default::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn prompt(id: &str, content: &str, enabled: bool) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            enabled,
+            order_index: 0,
+            created_at: Some(1),
+            updated_at: Some(1),
+        }
+    }
+
+    fn skill(id: &str, apps: SkillApps) -> InstalledSkill {
+        InstalledSkill {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            directory: id.to_string(),
+            repo_owner: Some("team".to_string()),
+            repo_name: Some("skills".to_string()),
+            repo_branch: Some("main".to_string()),
+            readme_url: None,
+            apps,
+            installed_at: 1,
+        }
+    }
+
+    fn outcome_of(result: &BundleImportResult, key: &str) -> BundleItemOutcome {
+        result
+            .items
+            .iter()
+            .find(|item| item.key == key)
+            .unwrap_or_else(|| panic!("missing item {key}"))
+            .outcome
+    }
+
+    #[test]
+    fn test_bundle_round_trip_into_empty_database() -> Result<(), AppError> {
+        let source = Database::memory()?;
+        source.save_mcp_server(&mcp_server("fetch", "uvx"))?;
+        source.save_prompt("claude", &prompt("review", "Review carefully", true))?;
+        source.save_skill(&skill(
+            "team/skills:lint",
+            SkillApps {
+                claude: true,
+                ..Default::default()
+            },
+        ))?;
+
+        let bundle = source.export_bundle()?;
+        assert_eq!(bundle.manifest.mcp_servers, vec!["fetch".to_string()]);
+        assert_eq!(bundle.manifest.prompts, vec!["claude:review".to_string()]);
+        assert_eq!(bundle.manifest.skills, vec!["team/skills:lint".to_string()]);
+
+        // 经过 JSON 序列化后再导入
+        let json = serde_json::to_string(&bundle).expect("serialize bundle");
+        let bundle: ConfigBundle = serde_json::from_str(&json).expect("parse bundle");
+
+        let target = Database::memory()?;
+        let result = target.import_bundle(&bundle, &HashMap::new())?;
+        assert!(result.errors.is_empty());
+        assert_eq!(outcome_of(&result, "mcp:fetch"), BundleItemOutcome::Added);
+        assert_eq!(
+            outcome_of(&result, "prompt:claude:review"),
+            BundleItemOutcome::Added
+        );
+        assert_eq!(
+            outcome_of(&result, "skill:team/skills:lint"),
+            BundleItemOutcome::PendingInstall
+        );
+
+        assert!(target.get_all_mcp_servers()?.contains_key("fetch"));
+        let prompts = target.get_prompts("claude")?;
+        assert!(
+            !prompts["review"].enabled,
+            "imported prompts start disabled"
+        );
+        assert!(target.get_installed_skill("team/skills:lint")?.is_none());
+        assert!(target
+            .get_skill_repos()?
+            .iter()
+            .any(|r| r.owner == "team" && r.name == "skills"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_conflicts_respect_resolutions() -> Result<(), AppError> {
+        let source = Database::memory()?;
+        source.save_mcp_server(&mcp_server("fetch", "uvx"))?;
+        source.save_mcp_server(&mcp_server("git", "uvx"))?;
+        source.save_prompt("codex", &prompt("style", "new style", false))?;
+        let bundle = source.export_bundle()?;
+
+        let target = Database::memory()?;
+        target.save_mcp_server(&mcp_server("fetch", "npx"))?;
+        target.save_mcp_server(&mcp_server("git", "npx"))?;
+        target.save_prompt("codex", &prompt("style", "old style", true))?;
+
+        let resolutions = HashMap::from([
+            ("mcp:fetch".to_string(), ImportResolution::TakeImport),
+            ("mcp:git".to_string(), ImportResolution::DuplicateRename),
+        ]);
+        let result = target.import_bundle(&bundle, &resolutions)?;
+        assert_eq!(result.conflict_count, 3);
+        assert_eq!(
+            outcome_of(&result, "mcp:fetch"),
+            BundleItemOutcome::Replaced
+        );
+        assert_eq!(outcome_of(&result, "mcp:git"), BundleItemOutcome::Renamed);
+        assert_eq!(
+            outcome_of(&result, "prompt:codex:style"),
+            BundleItemOutcome::Skipped
+        );
+
+        let servers = target.get_all_mcp_servers()?;
+        assert_eq!(servers["fetch"].server["command"], "uvx");
+        assert_eq!(servers["git"].server["command"], "npx");
+        assert_eq!(servers["git-imported"].server["command"], "uvx");
+        assert_eq!(target.get_prompts("codex")?["style"].content, "old style");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_rejects_unknown_format_and_version() {
+        let db = Database::memory().expect("memory db");
+        let mut bundle = db.export_bundle().expect("export bundle");
+
+        bundle.version = BUNDLE_VERSION + 1;
+        assert!(db.import_bundle(&bundle, &HashMap::new()).is_err());
+
+        bundle.version = BUNDLE_VERSION;
+        bundle.format = "something-else".to_string();
+        assert!(db.import_bundle(&bundle, &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! 熔断器事件历史 DAO
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::proxy::circuit_breaker::{CircuitBreakerEvent, CircuitState};
+
+/// 熔断器事件表最多保留的行数（超出部分按时间删除最旧记录）
+const CIRCUIT_BREAKER_EVENTS_MAX_ROWS: i64 = 5000;
+
+impl Database {
+    /// 保存熔断器状态转换事件，并裁剪超出上限的旧记录
+    pub fn save_circuit_breaker_event(&self, event: &CircuitBreakerEvent) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        conn.execute(
+            "INSERT INTO circuit_breaker_events
+             (app_type, provider_id, from_state, to_state, consecutive_failures,
+              failed_requests, total_requests, error_message, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                event.app_type,
+                event.provider_id,
+                event.from_state.to_string(),
+                event.to_state.to_string(),
+                event.consecutive_failures as i64,
+                event.failed_requests as i64,
+                event.total_requests as i64,
+                event.error,
+                event.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        let id = conn.last_insert_rowid();
+
+        conn.execute(
+            "DELETE FROM circuit_breaker_events WHERE id <= (
+                SELECT id FROM circuit_breaker_events ORDER BY id DESC LIMIT 1 OFFSET ?1
+            )",
+            [CIRCUIT_BREAKER_EVENTS_MAX_ROWS],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(id)
+    }
+
+    /// 查询熔断器事件历史（按时间倒序）
+    ///
+    /// `provider_id` 为 None 时返回该应用下所有供应商的事件。
+    pub fn get_circuit_breaker_events(
+        &self,
+        app_type: &str,
+        provider_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<CircuitBreakerEvent>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, provider_id, from_state, to_state, consecutive_failures,
+                        failed_requests, total_requests, error_message, created_at
+                 FROM circuit_breaker_events
+                 WHERE app_type = ?1 AND (?2 IS NULL OR provider_id = ?2)
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?3",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![app_type, provider_id, limit], |row| {
+                Ok(CircuitBreakerEvent {
+                    id: Some(row.get(0)?),
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    from_state: parse_state(row, 3)?,
+                    to_state: parse_state(row, 4)?,
+                    consecutive_failures: row.get::<_, i64>(5)? as u32,
+                    failed_requests: row.get::<_, i64>(6)? as u32,
+                    total_requests: row.get::<_, i64>(7)? as u32,
+                    error: row.get(8)?,
+                    created_at: row.get(9)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(events)
+    }
+}
+
+fn parse_state(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<CircuitState> {
+    let value: String = row.get(idx)?;
+    value.parse().map_err(|e: String| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, e.into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::circuit_breaker::CircuitTransition;
+
+    fn transition(from: CircuitState, to: CircuitState) -> CircuitTransition {
+        CircuitTransition {
+            from,
+            to,
+            consecutive_failures: 3,
+            failed_requests: 3,
+            total_requests: 5,
+        }
+    }
+
+    #[test]
+    fn test_save_and_query_circuit_breaker_events() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        let opened = CircuitBreakerEvent::new(
+            "claude",
+            "p1",
+            transition(CircuitState::Closed, CircuitState::Open),
+            Some("HTTP 529".to_string()),
+        );
+        db.save_circuit_breaker_event(&opened)?;
+        db.save_circuit_breaker_event(&CircuitBreakerEvent::new(
+            "claude",
+            "p2",
+            transition(CircuitState::Open, CircuitState::HalfOpen),
+            None,
+        ))?;
+        db.save_circuit_breaker_event(&CircuitBreakerEvent::new(
+            "codex",
+            "p1",
+            transition(CircuitState::Closed, CircuitState::Open),
+            None,
+        ))?;
+
+        let all_claude = db.get_circuit_breaker_events("claude", None, 10)?;
+        assert_eq!(all_claude.len(), 2);
+        assert_eq!(all_claude[0].provider_id, "p2");
+
+        let p1 = db.get_circuit_breaker_events("claude", Some("p1"), 10)?;
+        assert_eq!(p1.len(), 1);
+        assert_eq!(p1[0].from_state, CircuitState::Closed);
+        assert_eq!(p1[0].to_state, CircuitState::Open);
+        assert_eq!(p1[0].error.as_deref(), Some("HTTP 529"));
+        assert_eq!(p1[0].consecutive_failures, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_circuit_breaker_events_are_capped() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let event = CircuitBreakerEvent::new(
+            "claude",
+            "p1",
+            transition(CircuitState::Closed, CircuitState::Open),
+            None,
+        );
+        for _ in 0..(CIRCUIT_BREAKER_EVENTS_MAX_ROWS + 3) {
+            db.save_circuit_breaker_event(&event)?;
+        }
+
+        let count: i64 = {
+            let conn = lock_conn!(db.conn);
+            conn.query_row("SELECT COUNT(*) FROM circuit_breaker_events", [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        assert_eq!(count, CIRCUIT_BREAKER_EVENTS_MAX_ROWS);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! 命名配置片段数据访问对象
+//!
+//! 每个应用可保存多个可复用的配置片段（按名称区分），
+//! 与 settings 表中的单一通用配置片段（`common_config_{app}`）相互独立。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// 命名配置片段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSnippet {
+    pub app_type: String,
+    pub name: String,
+    pub content: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Database {
+    /// 获取指定应用的所有命名配置片段（按名称排序）
+    pub fn list_config_snippets(&self, app_type: &str) -> Result<Vec<ConfigSnippet>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_type, name, content, created_at, updated_at
+                 FROM config_snippets WHERE app_type = ?1
+                 ORDER BY name COLLATE NOCASE ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type], |row| {
+                Ok(ConfigSnippet {
+                    app_type: row.get(0)?,
+                    name: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 按名称获取命名配置片段
+    pub fn get_named_config_snippet(
+        &self,
+        app_type: &str,
+        name: &str,
+    ) -> Result<Option<ConfigSnippet>, AppError> {
+        Ok(self
+            .list_config_snippets(app_type)?
+            .into_iter()
+            .find(|snippet| snippet.name == name))
+    }
+
+    /// 保存命名配置片段（同名则覆盖内容，保留创建时间）
+    pub fn save_named_config_snippet(
+        &self,
+        app_type: &str,
+        name: &str,
+        content: &str,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        let now = chrono::Utc::now().timestamp_millis();
+        conn.execute(
+            "INSERT INTO config_snippets (app_type, name, content, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(app_type, name) DO UPDATE SET
+                content = excluded.content,
+                updated_at = excluded.updated_at",
+            params![app_type, name, content, now],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除命名配置片段，返回是否存在并被删除
+    pub fn delete_named_config_snippet(
+        &self,
+        app_type: &str,
+        name: &str,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "DELETE FROM config_snippets WHERE app_type = ?1 AND name = ?2",
+                params![app_type, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+}
@@ -0,0 +1,193 @@
+//! 端点测速历史数据访问对象
+//!
+//! 保存每次测速的结果，便于跨时间比较自定义端点；每个端点保留最近
+//! [`ENDPOINT_LATENCY_HISTORY_LIMIT`] 条记录。
+
+use std::collections::HashMap;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// 每个端点保留的测速记录数量
+pub const ENDPOINT_LATENCY_HISTORY_LIMIT: i64 = 100;
+
+/// 端点测速记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointLatencyRecord {
+    pub id: i64,
+    pub provider_id: String,
+    pub app_type: String,
+    pub url: String,
+    /// 延迟（毫秒），请求失败时为空
+    pub latency: Option<u64>,
+    pub status: Option<u16>,
+    pub tested_at: i64,
+}
+
+impl Database {
+    /// 批量保存一次测速的结果（单个事务），并清理超出保留数量的旧记录
+    ///
+    /// `results` 为 (url, latency_ms, http_status)。
+    pub fn record_endpoint_latencies(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        results: &[(String, Option<u64>, Option<u16>)],
+    ) -> Result<(), AppError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let tested_at = chrono::Utc::now().timestamp_millis();
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for (url, latency, status) in results {
+            tx.execute(
+                "INSERT INTO endpoint_latency_history
+                 (provider_id, app_type, url, latency_ms, status, tested_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    provider_id,
+                    app_type,
+                    url,
+                    latency.map(|v| v as i64),
+                    status,
+                    tested_at
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            tx.execute(
+                "DELETE FROM endpoint_latency_history
+                 WHERE app_type = ?1 AND provider_id = ?2 AND url = ?3 AND id NOT IN (
+                    SELECT id FROM endpoint_latency_history
+                    WHERE app_type = ?1 AND provider_id = ?2 AND url = ?3
+                    ORDER BY id DESC LIMIT ?4
+                 )",
+                params![app_type, provider_id, url, ENDPOINT_LATENCY_HISTORY_LIMIT],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取指定端点的测速历史（最新在前）
+    pub fn get_endpoint_latency_history(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        limit: u32,
+    ) -> Result<Vec<EndpointLatencyRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider_id, app_type, url, latency_ms, status, tested_at
+                 FROM endpoint_latency_history
+                 WHERE app_type = ?1 AND provider_id = ?2 AND url = ?3
+                 ORDER BY id DESC LIMIT ?4",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(
+                params![app_type, provider_id, url, limit],
+                Self::row_to_endpoint_latency,
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取供应商各端点最近一次测速记录（url -> 记录）
+    pub fn get_latest_endpoint_latencies(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<HashMap<String, EndpointLatencyRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider_id, app_type, url, latency_ms, status, tested_at
+                 FROM endpoint_latency_history
+                 WHERE id IN (
+                    SELECT MAX(id) FROM endpoint_latency_history
+                    WHERE app_type = ?1 AND provider_id = ?2
+                    GROUP BY url
+                 )",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(
+                params![app_type, provider_id],
+                Self::row_to_endpoint_latency,
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.map(|row| row.map(|record| (record.url.clone(), record)))
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    fn row_to_endpoint_latency(row: &rusqlite::Row) -> rusqlite::Result<EndpointLatencyRecord> {
+        Ok(EndpointLatencyRecord {
+            id: row.get(0)?,
+            provider_id: row.get(1)?,
+            app_type: row.get(2)?,
+            url: row.get(3)?,
+            latency: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
+            status: row.get(5)?,
+            tested_at: row.get(6)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_latest_per_endpoint() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let a = "https://a.example.com".to_string();
+        let b = "https://b.example.com".to_string();
+
+        for i in 0..(ENDPOINT_LATENCY_HISTORY_LIMIT + 3) {
+            db.record_endpoint_latencies(
+                "claude",
+                "p1",
+                &[
+                    (a.clone(), Some(i as u64), Some(200)),
+                    (b.clone(), None, None),
+                ],
+            )?;
+        }
+
+        let history = db.get_endpoint_latency_history("claude", "p1", &a, 1000)?;
+        assert_eq!(history.len() as i64, ENDPOINT_LATENCY_HISTORY_LIMIT);
+        assert_eq!(
+            history[0].latency,
+            Some((ENDPOINT_LATENCY_HISTORY_LIMIT + 2) as u64)
+        );
+        assert_eq!(
+            db.get_endpoint_latency_history("claude", "p1", &a, 5)?
+                .len(),
+            5
+        );
+
+        let latest = db.get_latest_endpoint_latencies("claude", "p1")?;
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[&b].latency, None);
+        assert!(db.get_latest_endpoint_latencies("codex", "p1")?.is_empty());
+        Ok(())
+    }
+}
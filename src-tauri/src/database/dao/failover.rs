@@ -86,11 +86,11 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
 
         // 2. 清除该供应商的健康状态（退出队列后不再需要健康监控）
-        conn.execute(
-            "DELETE FROM provider_health WHERE provider_id = ?1 AND app_type = ?2",
+        Self::clear_provider_health_where(
+            &conn,
+            "provider_id = ?1 AND app_type = ?2",
             rusqlite::params![provider_id, app_type],
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        )?;
 
         log::info!("已从故障转移队列移除供应商 {provider_id} ({app_type}), 并清除其健康状态");
 
@@ -0,0 +1,180 @@
+//! Live 配置历史数据访问对象
+//!
+//! 每次写入 Live 配置文件前保存其原内容，按应用保留最近 [`LIVE_CONFIG_HISTORY_LIMIT`] 个版本。
+//! 相同内容（按 SHA-256 判断）只保留一份，重复写入时仅将其提升为最新版本。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 每个应用保留的历史版本数量
+pub const LIVE_CONFIG_HISTORY_LIMIT: i64 = 20;
+
+/// Live 配置历史版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveConfigVersion {
+    pub id: i64,
+    pub app_type: String,
+    /// 文件快照（JSON 对象：文件名 -> 原始文本）
+    pub content: String,
+    pub content_hash: String,
+    pub created_at: i64,
+}
+
+impl Database {
+    /// 记录一个 Live 配置版本，返回是否新增了记录
+    ///
+    /// 与最新版本内容相同时直接跳过；与更早版本相同时删除旧记录后重新插入。
+    /// 插入后自动清理超出保留数量的旧版本。
+    pub fn record_live_config_version(
+        &self,
+        app_type: &str,
+        content: &str,
+    ) -> Result<bool, AppError> {
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        let mut conn = lock_conn!(self.conn);
+
+        let latest_hash: Option<String> = conn
+            .query_row(
+                "SELECT content_hash FROM live_config_history
+                 WHERE app_type = ?1 ORDER BY id DESC LIMIT 1",
+                params![app_type],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if latest_hash.as_deref() == Some(hash.as_str()) {
+            return Ok(false);
+        }
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM live_config_history WHERE app_type = ?1 AND content_hash = ?2",
+            params![app_type, hash],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "INSERT INTO live_config_history (app_type, content, content_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                app_type,
+                content,
+                hash,
+                chrono::Utc::now().timestamp_millis()
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM live_config_history WHERE app_type = ?1 AND id NOT IN (
+                SELECT id FROM live_config_history WHERE app_type = ?1
+                ORDER BY id DESC LIMIT ?2
+             )",
+            params![app_type, LIVE_CONFIG_HISTORY_LIMIT],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// 获取指定应用的 Live 配置历史（最新在前）
+    pub fn list_live_config_history(
+        &self,
+        app_type: &str,
+    ) -> Result<Vec<LiveConfigVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, content, content_hash, created_at
+                 FROM live_config_history WHERE app_type = ?1
+                 ORDER BY id DESC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type], Self::row_to_live_config_version)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取指定的 Live 配置历史版本
+    pub fn get_live_config_version(
+        &self,
+        app_type: &str,
+        id: i64,
+    ) -> Result<Option<LiveConfigVersion>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT id, app_type, content, content_hash, created_at
+             FROM live_config_history WHERE app_type = ?1 AND id = ?2",
+            params![app_type, id],
+            Self::row_to_live_config_version,
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    fn row_to_live_config_version(row: &rusqlite::Row) -> rusqlite::Result<LiveConfigVersion> {
+        Ok(LiveConfigVersion {
+            id: row.get(0)?,
+            app_type: row.get(1)?,
+            content: row.get(2)?,
+            content_hash: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_skips_unchanged_and_dedups_by_hash() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        assert!(db.record_live_config_version("claude", "a")?);
+        assert!(!db.record_live_config_version("claude", "a")?);
+        assert!(db.record_live_config_version("claude", "b")?);
+        // 回到旧内容：旧记录被提升为最新，而不是重复保存
+        assert!(db.record_live_config_version("claude", "a")?);
+        assert!(db.record_live_config_version("codex", "a")?);
+
+        let history = db.list_live_config_history("claude")?;
+        let contents: Vec<_> = history.iter().map(|v| v.content.as_str()).collect();
+        assert_eq!(contents, vec!["a", "b"]);
+
+        let latest = db
+            .get_live_config_version("claude", history[0].id)?
+            .expect("version exists");
+        assert_eq!(latest.content, "a");
+        assert!(db
+            .get_live_config_version("codex", history[0].id)?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn record_prunes_to_limit() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        for i in 0..(LIVE_CONFIG_HISTORY_LIMIT + 5) {
+            db.record_live_config_version("gemini", &format!("v{i}"))?;
+        }
+
+        let history = db.list_live_config_history("gemini")?;
+        assert_eq!(history.len() as i64, LIVE_CONFIG_HISTORY_LIMIT);
+        assert_eq!(
+            history[0].content,
+            format!("v{}", LIVE_CONFIG_HISTORY_LIMIT + 4)
+        );
+        assert_eq!(history.last().map(|v| v.content.as_str()), Some("v5"));
+        Ok(())
+    }
+}
@@ -1,17 +1,24 @@
 //! MCP 服务器数据访问对象
 //!
-//! 提供 MCP 服务器的 CRUD 操作。
+//! 提供 MCP 服务器与 MCP Profile 的 CRUD 操作。
 
-use crate::app_config::{McpApps, McpServer};
+use crate::app_config::{McpApps, McpProfile, McpServer};
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
 
 impl Database {
     /// 获取所有 MCP 服务器
     pub fn get_all_mcp_servers(&self) -> Result<IndexMap<String, McpServer>, AppError> {
         let conn = lock_conn!(self.conn);
+        Self::get_all_mcp_servers_on_conn(&conn)
+    }
+
+    /// 在指定连接（或事务）上获取所有 MCP 服务器
+    pub(crate) fn get_all_mcp_servers_on_conn(
+        conn: &Connection,
+    ) -> Result<IndexMap<String, McpServer>, AppError> {
         let mut stmt = conn.prepare(
             "SELECT id, name, server_config, description, homepage, docs, tags, enabled_claude, enabled_codex, enabled_gemini, enabled_opencode
              FROM mcp_servers
@@ -67,6 +74,14 @@ impl Database {
     /// 保存 MCP 服务器
     pub fn save_mcp_server(&self, server: &McpServer) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::save_mcp_server_on_conn(&conn, server)
+    }
+
+    /// 在指定连接（或事务）上保存 MCP 服务器
+    pub(crate) fn save_mcp_server_on_conn(
+        conn: &Connection,
+        server: &McpServer,
+    ) -> Result<(), AppError> {
         conn.execute(
             "INSERT OR REPLACE INTO mcp_servers (
                 id, name, server_config, description, homepage, docs, tags,
@@ -100,4 +115,171 @@ impl Database {
             .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// 获取所有 MCP Profile（按名称排序）
+    pub fn get_mcp_profiles(&self) -> Result<Vec<McpProfile>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, servers, pinned, created_at, updated_at
+                 FROM mcp_profiles ORDER BY name ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], Self::row_to_mcp_profile)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取指定 MCP Profile
+    pub fn get_mcp_profile(&self, name: &str) -> Result<Option<McpProfile>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT name, servers, pinned, created_at, updated_at
+             FROM mcp_profiles WHERE name = ?1",
+            params![name],
+            Self::row_to_mcp_profile,
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 保存 MCP Profile（已存在时保留创建时间）
+    pub fn save_mcp_profile(&self, profile: &McpProfile) -> Result<(), AppError> {
+        let servers = serde_json::to_string(&profile.servers)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        let pinned = serde_json::to_string(&profile.pinned)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        let now = chrono::Utc::now().timestamp();
+
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO mcp_profiles (name, servers, pinned, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                servers = excluded.servers,
+                pinned = excluded.pinned,
+                updated_at = excluded.updated_at",
+            params![profile.name, servers, pinned, now],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除 MCP Profile，返回是否存在
+    pub fn delete_mcp_profile(&self, name: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM mcp_profiles WHERE name = ?1", params![name])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 在单个事务中按 Profile 更新所有服务器的启用状态
+    ///
+    /// Profile 中的服务器使用其配置的启用状态；其余服务器除固定（pinned）外全部禁用。
+    /// 返回启用状态有变化的服务器（更新前状态, 更新后的服务器）。
+    pub fn apply_mcp_profile_flags(
+        &self,
+        profile: &McpProfile,
+    ) -> Result<Vec<(McpApps, McpServer)>, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut changed = Vec::new();
+        for (id, mut server) in Self::get_all_mcp_servers_on_conn(&tx)? {
+            let target = match profile.servers.get(&id) {
+                Some(apps) => apps.clone(),
+                None if profile.pinned.contains(&id) => continue,
+                None => McpApps::default(),
+            };
+            if server.apps == target {
+                continue;
+            }
+            let previous = std::mem::replace(&mut server.apps, target);
+            Self::save_mcp_server_on_conn(&tx, &server)?;
+            changed.push((previous, server));
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(changed)
+    }
+
+    fn row_to_mcp_profile(row: &rusqlite::Row) -> rusqlite::Result<McpProfile> {
+        let servers: String = row.get(1)?;
+        let pinned: String = row.get(2)?;
+        Ok(McpProfile {
+            name: row.get(0)?,
+            servers: serde_json::from_str(&servers).unwrap_or_default(),
+            pinned: serde_json::from_str(&pinned).unwrap_or_default(),
+            created_at: row.get(3)?,
+            updated_at: row.get(4)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, apps: McpApps) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: id.to_string(),
+            server: serde_json::json!({ "command": "npx" }),
+            apps,
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn apply_profile_flags_disables_unlisted_servers_except_pinned() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let all = McpApps {
+            claude: true,
+            codex: true,
+            gemini: true,
+            opencode: true,
+        };
+        db.save_mcp_server(&server("jira", McpApps::default()))?;
+        db.save_mcp_server(&server("home", all.clone()))?;
+        db.save_mcp_server(&server("fetch", all.clone()))?;
+
+        let claude_only = McpApps {
+            claude: true,
+            ..Default::default()
+        };
+        let profile = McpProfile {
+            name: "work".to_string(),
+            servers: [("jira".to_string(), claude_only.clone())].into(),
+            pinned: vec!["fetch".to_string()],
+            ..Default::default()
+        };
+        db.save_mcp_profile(&profile)?;
+        let saved = db.get_mcp_profile("work")?.expect("profile saved");
+        assert_eq!(saved.servers["jira"], claude_only);
+        assert!(saved.created_at > 0);
+
+        let changed = db.apply_mcp_profile_flags(&saved)?;
+        let mut ids: Vec<_> = changed.iter().map(|(_, s)| s.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["home", "jira"]);
+
+        let servers = db.get_all_mcp_servers()?;
+        assert_eq!(servers["jira"].apps, claude_only);
+        assert_eq!(servers["home"].apps, McpApps::default());
+        assert_eq!(servers["fetch"].apps, all);
+
+        // 再次应用没有变化
+        assert!(db.apply_mcp_profile_flags(&saved)?.is_empty());
+        assert!(db.delete_mcp_profile("work")?);
+        assert!(db.get_mcp_profiles()?.is_empty());
+        Ok(())
+    }
 }
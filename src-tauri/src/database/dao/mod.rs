@@ -2,11 +2,18 @@
 //!
 //! Database access operations for each domain
 
+pub mod circuit_breaker_events;
+pub mod config_snippets;
+pub mod endpoint_latency;
 pub mod failover;
+pub mod live_config_history;
 pub mod mcp;
 pub mod prompts;
+pub mod provider_audit_log;
+pub mod provider_integrity;
 pub mod providers;
 pub mod proxy;
+pub mod request_logs;
 pub mod settings;
 pub mod skills;
 pub mod stream_check;
@@ -14,4 +21,11 @@ pub mod universal_providers;
 
 // 所有 DAO 方法都通过 Database impl 提供，无需单独导出
 // 导出 FailoverQueueItem 供外部使用
+pub use config_snippets::ConfigSnippet;
+pub use endpoint_latency::EndpointLatencyRecord;
 pub use failover::FailoverQueueItem;
+pub use live_config_history::LiveConfigVersion;
+pub use provider_audit_log::ProviderAuditEntry;
+pub use provider_integrity::InvalidProviderRow;
+pub use providers::{CustomEndpointMerge, CustomEndpointRow};
+pub use request_logs::ReplaySource;
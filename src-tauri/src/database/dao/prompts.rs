@@ -6,7 +6,8 @@ use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use crate::prompt::Prompt;
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
 
 impl Database {
     /// 获取指定应用类型的所有提示词
@@ -14,9 +15,9 @@ impl Database {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
-                "SELECT id, name, content, description, enabled, created_at, updated_at
+                "SELECT id, name, content, description, enabled, created_at, updated_at, order_index
              FROM prompts WHERE app_type = ?1
-             ORDER BY created_at ASC, id ASC",
+             ORDER BY order_index ASC, created_at ASC, id ASC",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -29,6 +30,7 @@ impl Database {
                 let enabled: bool = row.get(4)?;
                 let created_at: Option<i64> = row.get(5)?;
                 let updated_at: Option<i64> = row.get(6)?;
+                let order_index: i64 = row.get(7)?;
 
                 Ok((
                     id.clone(),
@@ -38,6 +40,7 @@ impl Database {
                         content,
                         description,
                         enabled,
+                        order_index,
                         created_at,
                         updated_at,
                     },
@@ -56,10 +59,20 @@ impl Database {
     /// 保存提示词
     pub fn save_prompt(&self, app_type: &str, prompt: &Prompt) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::save_prompt_on_conn(&conn, app_type, prompt)
+    }
+
+    /// 在指定连接（或事务）上保存提示词
+    pub(crate) fn save_prompt_on_conn(
+        conn: &Connection,
+        app_type: &str,
+        prompt: &Prompt,
+    ) -> Result<(), AppError> {
         conn.execute(
             "INSERT OR REPLACE INTO prompts (
-                id, app_type, name, content, description, enabled, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                id, app_type, name, content, description, enabled, created_at, updated_at,
+                order_index
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 prompt.id,
                 app_type,
@@ -69,6 +82,7 @@ impl Database {
                 prompt.enabled,
                 prompt.created_at,
                 prompt.updated_at,
+                prompt.order_index,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -85,4 +99,27 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// 按给定顺序重排提示词（未列出的提示词保持相对顺序排在其后）
+    pub fn reorder_prompts(&self, app_type: &str, ids: &[String]) -> Result<(), AppError> {
+        let prompts = self.get_prompts(app_type)?;
+        let mut seen = HashSet::new();
+        let ordered = ids
+            .iter()
+            .filter(|id| prompts.contains_key(id.as_str()) && seen.insert(id.as_str()))
+            .chain(prompts.keys().filter(|id| !ids.contains(id)));
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (index, id) in ordered.enumerate() {
+            tx.execute(
+                "UPDATE prompts SET order_index = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![index as i64, id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
 }
@@ -0,0 +1,187 @@
+//! 供应商变更审计日志数据访问对象
+//!
+//! 记录供应商的新增、修改、删除与切换，修改记录附带脱敏后的配置差异。
+//! 写入时顺带清理超出保留天数的旧记录。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 默认保留天数
+pub const DEFAULT_PROVIDER_AUDIT_RETENTION_DAYS: u32 = 90;
+
+const RETENTION_SETTING_KEY: &str = "provider_audit_retention_days";
+
+/// 供应商审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAuditEntry {
+    pub id: i64,
+    pub app_type: String,
+    pub provider_id: String,
+    /// add / update / delete / switch
+    pub action: String,
+    /// ui / deeplink / universal-sync / failover / local-api
+    pub source: String,
+    /// 脱敏后的 `settings_config` 差异（仅修改操作）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<Value>,
+    /// 毫秒时间戳
+    pub created_at: i64,
+}
+
+impl Database {
+    /// 写入一条审计记录，并清理超出保留天数的旧记录
+    pub fn record_provider_audit(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        action: &str,
+        source: &str,
+        diff: Option<&Value>,
+    ) -> Result<(), AppError> {
+        let diff = diff.map(crate::database::to_json_string).transpose()?;
+        {
+            let conn = lock_conn!(self.conn);
+            conn.execute(
+                "INSERT INTO provider_audit_log
+                 (app_type, provider_id, action, source, diff, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    app_type,
+                    provider_id,
+                    action,
+                    source,
+                    diff,
+                    chrono::Utc::now().timestamp_millis()
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        self.prune_provider_audit_log()?;
+        Ok(())
+    }
+
+    /// 查询审计记录（最新在前）；`provider_id` 为空时返回该应用下所有供应商的记录
+    pub fn list_provider_audit_log(
+        &self,
+        app_type: &str,
+        provider_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<ProviderAuditEntry>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, provider_id, action, source, diff, created_at
+                 FROM provider_audit_log
+                 WHERE app_type = ?1 AND (?2 IS NULL OR provider_id = ?2)
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?3",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type, provider_id, limit], |row| {
+                let diff: Option<String> = row.get(5)?;
+                Ok(ProviderAuditEntry {
+                    id: row.get(0)?,
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    action: row.get(3)?,
+                    source: row.get(4)?,
+                    diff: diff.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 删除超出保留天数的审计记录，返回删除条数（保留天数为 0 时不清理）
+    pub fn prune_provider_audit_log(&self) -> Result<usize, AppError> {
+        let days = self.get_provider_audit_retention_days()?;
+        if days == 0 {
+            return Ok(0);
+        }
+        let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(days) * 86_400_000;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM provider_audit_log WHERE created_at < ?1",
+            params![cutoff],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取审计记录保留天数（0 表示永久保留）
+    pub fn get_provider_audit_retention_days(&self) -> Result<u32, AppError> {
+        Ok(self
+            .get_setting(RETENTION_SETTING_KEY)?
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_PROVIDER_AUDIT_RETENTION_DAYS))
+    }
+
+    /// 设置审计记录保留天数，并立即按新设置清理
+    pub fn set_provider_audit_retention_days(&self, days: u32) -> Result<(), AppError> {
+        self.set_setting(RETENTION_SETTING_KEY, &days.to_string())?;
+        self.prune_provider_audit_log()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn record_and_list_filters_by_provider() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        db.record_provider_audit("claude", "p1", "add", "ui", None)?;
+        let diff = json!([{"path": "/env/MODEL", "kind": "changed"}]);
+        db.record_provider_audit("claude", "p1", "update", "deeplink", Some(&diff))?;
+        db.record_provider_audit("claude", "p2", "switch", "failover", None)?;
+        db.record_provider_audit("codex", "p1", "delete", "ui", None)?;
+
+        let p1 = db.list_provider_audit_log("claude", Some("p1"), 10)?;
+        let actions: Vec<_> = p1.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, vec!["update", "add"]);
+        assert_eq!(p1[0].source, "deeplink");
+        assert_eq!(p1[0].diff, Some(diff));
+        assert!(p1[1].diff.is_none());
+
+        assert_eq!(db.list_provider_audit_log("claude", None, 10)?.len(), 3);
+        assert_eq!(db.list_provider_audit_log("claude", None, 1)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn prune_respects_retention_days() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        db.record_provider_audit("claude", "p1", "add", "ui", None)?;
+        {
+            let conn = lock_conn!(db.conn);
+            let old = chrono::Utc::now().timestamp_millis() - 10 * 86_400_000;
+            conn.execute(
+                "INSERT INTO provider_audit_log
+                 (app_type, provider_id, action, source, created_at)
+                 VALUES ('claude', 'p1', 'update', 'ui', ?1)",
+                params![old],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        db.set_provider_audit_retention_days(0)?;
+        assert_eq!(db.list_provider_audit_log("claude", None, 10)?.len(), 2);
+
+        db.set_provider_audit_retention_days(7)?;
+        let remaining = db.list_provider_audit_log("claude", None, 10)?;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].action, "add");
+        Ok(())
+    }
+}
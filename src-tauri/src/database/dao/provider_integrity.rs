@@ -0,0 +1,171 @@
+//! 供应商表完整性检查
+//!
+//! 逐行解析 `providers` 表的 `settings_config` 与 `meta`。读取供应商时解析失败会被
+//! 静默替换为空值，导致应用表现异常却没有任何提示；这里把这些行找出来，并可将其
+//! 移入 `providers_quarantine` 表，保留原始内容以便手动恢复。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::provider::ProviderMeta;
+use rusqlite::params;
+use serde::Serialize;
+
+/// 无法解析的供应商行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidProviderRow {
+    pub app_type: String,
+    pub id: String,
+    pub name: String,
+    /// 出错的列：settings_config / meta
+    pub field: String,
+    pub error: String,
+}
+
+/// 校验单行，返回第一个出错的列与原因
+fn check_provider_row(settings_config: &str, meta: &str) -> Option<(&'static str, String)> {
+    match serde_json::from_str::<serde_json::Value>(settings_config) {
+        Ok(value) if value.is_object() => {}
+        Ok(_) => return Some(("settings_config", "not a JSON object".to_string())),
+        Err(e) => return Some(("settings_config", e.to_string())),
+    }
+    if let Err(e) = serde_json::from_str::<ProviderMeta>(meta) {
+        return Some(("meta", e.to_string()));
+    }
+    None
+}
+
+impl Database {
+    /// 检查所有供应商行，返回无法解析的行（不修改数据）
+    pub fn validate_providers(&self) -> Result<Vec<InvalidProviderRow>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_type, id, name, CAST(settings_config AS TEXT), CAST(meta AS TEXT)
+                 FROM providers ORDER BY app_type, id",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                    row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut invalid = Vec::new();
+        for row in rows {
+            let (app_type, id, name, settings_config, meta) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            if let Some((field, error)) = check_provider_row(&settings_config, &meta) {
+                invalid.push(InvalidProviderRow {
+                    app_type,
+                    id,
+                    name,
+                    field: field.to_string(),
+                    error,
+                });
+            }
+        }
+        Ok(invalid)
+    }
+
+    /// 将无法解析的供应商行移入 `providers_quarantine` 表，返回被隔离的行
+    ///
+    /// 原始内容原样保留在隔离表中；同时删除这些供应商的自定义端点。
+    pub fn repair_providers(&self) -> Result<Vec<InvalidProviderRow>, AppError> {
+        let invalid = self.validate_providers()?;
+        if invalid.is_empty() {
+            return Ok(invalid);
+        }
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let now = chrono::Utc::now().timestamp_millis();
+        for row in &invalid {
+            let reason = format!("{}: {}", row.field, row.error);
+            tx.execute(
+                "INSERT INTO providers_quarantine
+                 (app_type, provider_id, name, settings_config, meta, website_url, notes,
+                  reason, quarantined_at)
+                 SELECT app_type, id, name, CAST(settings_config AS TEXT), CAST(meta AS TEXT),
+                        website_url, notes, ?3, ?4
+                 FROM providers WHERE id = ?1 AND app_type = ?2",
+                params![row.id, row.app_type, reason, now],
+            )
+            .map_err(|e| AppError::Database(format!("隔离供应商失败: {e}")))?;
+            tx.execute(
+                "DELETE FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2",
+                params![row.id, row.app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            tx.execute(
+                "DELETE FROM providers WHERE id = ?1 AND app_type = ?2",
+                params![row.id, row.app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+
+        log::warn!("已隔离 {} 个无法解析的供应商", invalid.len());
+        Ok(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+
+    #[test]
+    fn repair_quarantines_unparseable_rows() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let good = Provider::with_id("good".into(), "Good".into(), json!({ "env": {} }), None);
+        let bad = Provider::with_id("bad".into(), "Bad".into(), json!({ "env": {} }), None);
+        db.save_provider("claude", &good)?;
+        db.save_provider("claude", &bad)?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "UPDATE providers SET settings_config = '{broken' WHERE id = 'bad'",
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            conn.execute(
+                r#"UPDATE providers SET meta = '"oops"' WHERE id = 'good'"#,
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        let invalid = db.validate_providers()?;
+        let fields: Vec<(&str, &str)> = invalid
+            .iter()
+            .map(|r| (r.id.as_str(), r.field.as_str()))
+            .collect();
+        assert_eq!(fields, vec![("bad", "settings_config"), ("good", "meta")]);
+
+        let quarantined = db.repair_providers()?;
+        assert_eq!(quarantined, invalid);
+        assert!(db.validate_providers()?.is_empty());
+        assert!(db.get_all_providers("claude")?.is_empty());
+
+        let conn = lock_conn!(db.conn);
+        let raw: String = conn
+            .query_row(
+                "SELECT settings_config FROM providers_quarantine WHERE provider_id = 'bad'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        assert_eq!(raw, "{broken");
+        Ok(())
+    }
+}
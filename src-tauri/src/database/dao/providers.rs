@@ -4,11 +4,32 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::provider::{Provider, ProviderMeta};
+use crate::provider::{Provider, ProviderMeta, ProviderSummary};
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 use std::collections::HashMap;
 
+/// `provider_endpoints` 中的一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomEndpointRow {
+    pub id: i64,
+    pub app_type: String,
+    pub provider_id: String,
+    pub url: String,
+    pub added_at: Option<i64>,
+    pub last_used: Option<i64>,
+}
+
+/// 一组重复端点的合并方案：保留 `keep_id` 并写入合并后的字段，删除 `remove_ids`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomEndpointMerge {
+    pub keep_id: i64,
+    pub url: String,
+    pub added_at: Option<i64>,
+    pub last_used: Option<i64>,
+    pub remove_ids: Vec<i64>,
+}
+
 impl Database {
     /// 获取指定应用类型的所有供应商
     pub fn get_all_providers(
@@ -56,6 +77,7 @@ impl Database {
                         icon,
                         icon_color,
                         in_failover_queue,
+                        icon_cached: None,
                     },
                 ))
             })
@@ -68,19 +90,22 @@ impl Database {
 
             // 加载 endpoints
             let mut stmt_endpoints = conn.prepare(
-                "SELECT url, added_at FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2 ORDER BY added_at ASC, url ASC"
+                "SELECT url, added_at, last_used FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2 ORDER BY added_at ASC, url ASC"
             ).map_err(|e| AppError::Database(e.to_string()))?;
 
             let endpoints_iter = stmt_endpoints
                 .query_map(params![id, app_type], |row| {
                     let url: String = row.get(0)?;
                     let added_at: Option<i64> = row.get(1)?;
+                    let last_used: Option<i64> = row.get(2)?;
                     Ok((
                         url,
                         crate::settings::CustomEndpoint {
                             url: "".to_string(),
                             added_at: added_at.unwrap_or(0),
-                            last_used: None,
+                            last_used,
+                            last_latency: None,
+                            last_tested_at: None,
                         },
                     ))
                 })
@@ -123,6 +148,89 @@ impl Database {
         }
     }
 
+    /// 获取指定应用类型的供应商摘要（列表视图用）
+    ///
+    /// 只读取轻量字段；meta 标记与请求地址由 SQLite JSON 函数提取，
+    /// 不在 Rust 侧反序列化整个 settings_config。Codex 的 TOML 仅在 env 中没有地址时解析。
+    pub fn get_provider_summaries(&self, app_type: &str) -> Result<Vec<ProviderSummary>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, category, website_url, icon, icon_color, sort_index, created_at,
+                        is_current, in_failover_queue,
+                        CASE WHEN json_valid(meta)
+                             THEN COALESCE(json_extract(meta, '$.isPartner') = 1, 0) ELSE 0 END,
+                        CASE WHEN json_valid(meta)
+                             THEN COALESCE(json_extract(meta, '$.usage_script.enabled') = 1, 0) ELSE 0 END,
+                        CASE WHEN json_valid(settings_config) THEN CAST(COALESCE(
+                             json_extract(settings_config, '$.env.ANTHROPIC_BASE_URL'),
+                             json_extract(settings_config, '$.env.GOOGLE_GEMINI_BASE_URL'),
+                             json_extract(settings_config, '$.env.GEMINI_BASE_URL'),
+                             json_extract(settings_config, '$.env.BASE_URL')) AS TEXT) END,
+                        CASE WHEN json_valid(settings_config) THEN
+                             CASE WHEN json_type(settings_config, '$.config') = 'text'
+                                  THEN json_extract(settings_config, '$.config') END END
+                 FROM providers WHERE app_type = ?1
+                 ORDER BY COALESCE(sort_index, 999999), created_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type], |row| {
+                let env_base_url: Option<String> = row.get(12)?;
+                let config_toml: Option<String> = row.get(13)?;
+                let base_url = env_base_url
+                    .or_else(|| {
+                        config_toml.and_then(|toml| {
+                            crate::codex_config::get_active_base_url(&toml)
+                                .ok()
+                                .flatten()
+                        })
+                    })
+                    .map(|url| url.trim_end_matches('/').to_string())
+                    .filter(|url| !url.is_empty());
+
+                Ok(ProviderSummary {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    category: row.get(2)?,
+                    website_url: row.get(3)?,
+                    icon: row.get(4)?,
+                    icon_color: row.get(5)?,
+                    icon_cached: None,
+                    sort_index: row.get(6)?,
+                    created_at: row.get(7)?,
+                    is_current: row.get(8)?,
+                    in_failover_queue: row.get(9)?,
+                    is_partner: row.get(10)?,
+                    usage_script_enabled: row.get(11)?,
+                    base_url,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 只读取单个供应商的 settings_config（编辑对话框按需加载）
+    pub fn get_provider_settings_config(
+        &self,
+        id: &str,
+        app_type: &str,
+    ) -> Result<Option<serde_json::Value>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let raw: Option<String> = conn
+            .query_row(
+                "SELECT settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
+                params![id, app_type],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(raw.map(|raw| serde_json::from_str(&raw).unwrap_or(serde_json::Value::Null)))
+    }
+
     /// 根据 ID 获取单个供应商
     pub fn get_provider_by_id(
         &self,
@@ -163,6 +271,7 @@ impl Database {
                     icon,
                     icon_color,
                     in_failover_queue,
+                    icon_cached: None,
                 })
             },
         );
@@ -271,9 +380,15 @@ impl Database {
             // 只有新增时才同步 endpoints
             for (url, endpoint) in endpoints {
                 tx.execute(
-                    "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
-                     VALUES (?1, ?2, ?3, ?4)",
-                    params![provider.id, app_type, url, endpoint.added_at],
+                    "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at, last_used)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        provider.id,
+                        app_type,
+                        url,
+                        endpoint.added_at,
+                        endpoint.last_used
+                    ],
                 )
                 .map_err(|e| AppError::Database(e.to_string()))?;
             }
@@ -291,6 +406,14 @@ impl Database {
             params![id, app_type],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        // Codex 额度缓存随供应商一起删除
+        if app_type == "codex" {
+            conn.execute(
+                "DELETE FROM settings WHERE key = ?1",
+                params![super::settings::codex_quota_cache_key(id)],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
         Ok(())
     }
 
@@ -319,6 +442,17 @@ impl Database {
         Ok(())
     }
 
+    /// 清除指定应用的当前供应商标记（切换失败回滚到"无当前供应商"时使用）
+    pub fn clear_current_provider(&self, app_type: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE providers SET is_current = 0 WHERE app_type = ?1",
+            params![app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 更新供应商的 settings_config（仅更新配置，不改变其他字段）
     pub fn update_provider_settings_config(
         &self,
@@ -341,6 +475,33 @@ impl Database {
         Ok(())
     }
 
+    /// 在同一事务中批量更新多个供应商的 settings_config，任一失败则全部回滚
+    pub fn update_providers_settings_config(
+        &self,
+        app_type: &str,
+        updates: &[(String, serde_json::Value)],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (provider_id, settings_config) in updates {
+            tx.execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![
+                    serde_json::to_string(settings_config).map_err(|e| AppError::Database(
+                        format!("Failed to serialize settings_config: {e}")
+                    ))?,
+                    provider_id,
+                    app_type
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 添加自定义端点
     pub fn add_custom_endpoint(
         &self,
@@ -372,4 +533,70 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// 更新自定义端点的最后使用时间，返回是否找到该端点
+    pub fn touch_custom_endpoint(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        last_used: i64,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let updated = conn
+            .execute(
+                "UPDATE provider_endpoints SET last_used = ?4
+                 WHERE provider_id = ?1 AND app_type = ?2 AND url = ?3",
+                params![provider_id, app_type, url, last_used],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(updated > 0)
+    }
+
+    /// 列出所有应用、所有供应商的自定义端点（用于去重整理）
+    pub fn list_custom_endpoint_rows(&self) -> Result<Vec<CustomEndpointRow>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, provider_id, url, added_at, last_used
+                 FROM provider_endpoints ORDER BY id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(CustomEndpointRow {
+                    id: row.get(0)?,
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    url: row.get(3)?,
+                    added_at: row.get(4)?,
+                    last_used: row.get(5)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rows)
+    }
+
+    /// 在单个事务中应用端点合并：更新保留行，删除其余重复行
+    pub fn merge_custom_endpoints(&self, merges: &[CustomEndpointMerge]) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for merge in merges {
+            tx.execute(
+                "UPDATE provider_endpoints SET url = ?2, added_at = ?3, last_used = ?4 WHERE id = ?1",
+                params![merge.keep_id, merge.url, merge.added_at, merge.last_used],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            for id in &merge.remove_ids {
+                tx.execute("DELETE FROM provider_endpoints WHERE id = ?1", params![id])
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
 }
@@ -19,7 +19,7 @@ impl Database {
         let result = {
             let conn = lock_conn!(self.conn);
             conn.query_row(
-                "SELECT proxy_enabled, listen_address, listen_port, enable_logging
+                "SELECT proxy_enabled, listen_address, listen_port, enable_logging, auto_port
                  FROM proxy_config WHERE app_type = 'claude'",
                 [],
                 |row| {
@@ -28,6 +28,7 @@ impl Database {
                         listen_address: row.get(1)?,
                         listen_port: row.get::<_, i32>(2)? as u16,
                         enable_logging: row.get::<_, i32>(3)? != 0,
+                        auto_port: row.get::<_, i32>(4)? != 0,
                     })
                 },
             )
@@ -44,6 +45,7 @@ impl Database {
                     listen_address: "127.0.0.1".to_string(),
                     listen_port: 15721,
                     enable_logging: true,
+                    auto_port: false,
                 })
             }
             Err(e) => Err(AppError::Database(e.to_string())),
@@ -63,12 +65,14 @@ impl Database {
                 listen_address = ?2,
                 listen_port = ?3,
                 enable_logging = ?4,
+                auto_port = ?5,
                 updated_at = datetime('now')",
             rusqlite::params![
                 if config.proxy_enabled { 1 } else { 0 },
                 config.listen_address,
                 config.listen_port as i32,
                 if config.enable_logging { 1 } else { 0 },
+                if config.auto_port { 1 } else { 0 },
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -200,7 +204,8 @@ impl Database {
                 "SELECT app_type, enabled, auto_failover_enabled,
                         max_retries, streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
                         circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
-                        circuit_error_rate_threshold, circuit_min_requests
+                        circuit_error_rate_threshold, circuit_min_requests,
+                        capture_bodies, capture_max_bytes
                  FROM proxy_config WHERE app_type = ?1",
                 [app_type],
                 |row| {
@@ -217,6 +222,8 @@ impl Database {
                         circuit_timeout_seconds: row.get::<_, i32>(9)? as u32,
                         circuit_error_rate_threshold: row.get(10)?,
                         circuit_min_requests: row.get::<_, i32>(11)? as u32,
+                        capture_bodies: row.get::<_, i32>(12)? != 0,
+                        capture_max_bytes: row.get::<_, i64>(13)?.max(0) as u32,
                     })
                 },
             )
@@ -241,6 +248,8 @@ impl Database {
                     circuit_timeout_seconds: 60,
                     circuit_error_rate_threshold: 0.6,
                     circuit_min_requests: 10,
+                    capture_bodies: false,
+                    capture_max_bytes: default_capture_max_bytes(),
                 })
             }
             Err(e) => Err(AppError::Database(e.to_string())),
@@ -267,6 +276,8 @@ impl Database {
                 circuit_timeout_seconds = ?10,
                 circuit_error_rate_threshold = ?11,
                 circuit_min_requests = ?12,
+                capture_bodies = ?13,
+                capture_max_bytes = ?14,
                 updated_at = datetime('now')
              WHERE app_type = ?1",
             rusqlite::params![
@@ -282,6 +293,8 @@ impl Database {
                 config.circuit_timeout_seconds as i32,
                 config.circuit_error_rate_threshold,
                 config.circuit_min_requests as i32,
+                if config.capture_bodies { 1 } else { 0 },
+                config.capture_max_bytes as i64,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -374,6 +387,9 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // opencode: 与 codex 相同的默认配置
+        Self::seed_opencode_proxy_config(&conn)?;
+
         Ok(())
     }
 
@@ -483,7 +499,8 @@ impl Database {
 
             conn.query_row(
                 "SELECT provider_id, app_type, is_healthy, consecutive_failures,
-                        last_success_at, last_failure_at, last_error, updated_at
+                        last_success_at, last_failure_at, last_error, updated_at,
+                        forced_state, forced_until
                  FROM provider_health
                  WHERE provider_id = ?1 AND app_type = ?2",
                 rusqlite::params![provider_id, app_type],
@@ -497,13 +514,25 @@ impl Database {
                         last_failure_at: row.get(5)?,
                         last_error: row.get(6)?,
                         updated_at: row.get(7)?,
+                        forced_state: row
+                            .get::<_, Option<String>>(8)?
+                            .as_deref()
+                            .and_then(ForcedCircuitState::parse),
+                        forced_until: row.get(9)?,
                     })
                 },
             )
         };
 
         match result {
-            Ok(health) => Ok(health),
+            Ok(mut health) => {
+                // 已过期的手动状态视为自动
+                if Self::circuit_override_expired(health.forced_until) {
+                    health.forced_state = None;
+                    health.forced_until = None;
+                }
+                Ok(health)
+            }
             // 缺少记录时视为健康（关闭后清空状态，再次打开时默认正常）
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(ProviderHealth {
                 provider_id: provider_id.to_string(),
@@ -514,6 +543,8 @@ impl Database {
                 last_failure_at: None,
                 last_error: None,
                 updated_at: chrono::Utc::now().to_rfc3339(),
+                forced_state: None,
+                forced_until: None,
             }),
             Err(e) => Err(AppError::Database(e.to_string())),
         }
@@ -575,17 +606,22 @@ impl Database {
             (None, Some(now.clone()))
         };
 
-        // UPSERT
+        // UPSERT（保留手动熔断状态）
         conn.execute(
             "INSERT OR REPLACE INTO provider_health
              (provider_id, app_type, is_healthy, consecutive_failures,
-              last_success_at, last_failure_at, last_error, updated_at)
+              last_success_at, last_failure_at, last_error, updated_at,
+              forced_state, forced_until)
              VALUES (?1, ?2, ?3, ?4,
                      COALESCE(?5, (SELECT last_success_at FROM provider_health
                                    WHERE provider_id = ?1 AND app_type = ?2)),
                      COALESCE(?6, (SELECT last_failure_at FROM provider_health
                                    WHERE provider_id = ?1 AND app_type = ?2)),
-                     ?7, ?8)",
+                     ?7, ?8,
+                     (SELECT forced_state FROM provider_health
+                      WHERE provider_id = ?1 AND app_type = ?2),
+                     (SELECT forced_until FROM provider_health
+                      WHERE provider_id = ?1 AND app_type = ?2))",
             rusqlite::params![
                 provider_id,
                 app_type,
@@ -610,11 +646,11 @@ impl Database {
     ) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
 
-        conn.execute(
-            "DELETE FROM provider_health WHERE provider_id = ?1 AND app_type = ?2",
+        Self::clear_provider_health_where(
+            &conn,
+            "provider_id = ?1 AND app_type = ?2",
             rusqlite::params![provider_id, app_type],
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        )?;
 
         log::debug!("Reset health status for provider {provider_id} (app: {app_type})");
 
@@ -625,11 +661,7 @@ impl Database {
     pub async fn clear_provider_health_for_app(&self, app_type: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
 
-        conn.execute(
-            "DELETE FROM provider_health WHERE app_type = ?1",
-            [app_type],
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::clear_provider_health_where(&conn, "app_type = ?1", rusqlite::params![app_type])?;
 
         log::debug!("Cleared provider health records for app {app_type}");
         Ok(())
@@ -639,13 +671,118 @@ impl Database {
     pub async fn clear_all_provider_health(&self) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
 
-        conn.execute("DELETE FROM provider_health", [])
-            .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::clear_provider_health_where(&conn, "1 = 1", rusqlite::params![])?;
 
         log::debug!("Cleared all provider health records");
         Ok(())
     }
 
+    /// 清空匹配行的健康统计
+    ///
+    /// 无手动熔断状态的行直接删除；有手动状态的行只重置统计字段，
+    /// 手动状态需显式清除或到期后才失效。
+    pub(crate) fn clear_provider_health_where(
+        conn: &rusqlite::Connection,
+        condition: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<(), AppError> {
+        conn.execute(
+            &format!("DELETE FROM provider_health WHERE ({condition}) AND forced_state IS NULL"),
+            params,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            &format!(
+                "UPDATE provider_health
+                 SET is_healthy = 1, consecutive_failures = 0, last_error = NULL
+                 WHERE ({condition}) AND forced_state IS NOT NULL"
+            ),
+            params,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    // ==================== Circuit Override ====================
+
+    /// 设置供应商的手动熔断状态
+    ///
+    /// - `Some(state)`：强制打开/关闭，`until` 为失效时间（毫秒时间戳），None 表示直到手动清除
+    /// - `None`：恢复自动
+    pub async fn set_provider_circuit_override(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        forced_state: Option<ForcedCircuitState>,
+        until: Option<i64>,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        let now = chrono::Utc::now().to_rfc3339();
+
+        match forced_state {
+            Some(state) => conn.execute(
+                "INSERT INTO provider_health
+                 (provider_id, app_type, updated_at, forced_state, forced_until)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(provider_id, app_type) DO UPDATE SET
+                     forced_state = excluded.forced_state,
+                     forced_until = excluded.forced_until,
+                     updated_at = excluded.updated_at",
+                rusqlite::params![provider_id, app_type, now, state.as_str(), until],
+            ),
+            None => conn.execute(
+                "UPDATE provider_health
+                 SET forced_state = NULL, forced_until = NULL, updated_at = ?3
+                 WHERE provider_id = ?1 AND app_type = ?2",
+                rusqlite::params![provider_id, app_type, now],
+            ),
+        }
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 列出所有手动熔断状态，返回 (provider_id, app_type, 状态, 失效时间)
+    ///
+    /// 包含已过期的记录，由调用方在使用时按失效时间判断（见 [`Self::circuit_override_expired`]）。
+    pub async fn list_provider_circuit_overrides(
+        &self,
+    ) -> Result<Vec<(String, String, ForcedCircuitState, Option<i64>)>, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider_id, app_type, forced_state, forced_until FROM provider_health
+                 WHERE forced_state IS NOT NULL",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut overrides = Vec::new();
+        for row in rows {
+            let (provider_id, app_type, state, until) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            if let Some(state) = ForcedCircuitState::parse(&state) {
+                overrides.push((provider_id, app_type, state, until));
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// 手动熔断状态是否已过期（`until` 为毫秒时间戳）
+    pub(crate) fn circuit_override_expired(until: Option<i64>) -> bool {
+        until.is_some_and(|until| until <= chrono::Utc::now().timestamp_millis())
+    }
+
     // ==================== Circuit Breaker Config (Legacy Compatibility) ====================
 
     /// 获取熔断器配置（兼容旧接口，从 claude 行读取）
@@ -913,4 +1050,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_circuit_override_survives_health_updates_and_clear() -> Result<(), AppError> {
+        use crate::provider::Provider;
+        use crate::proxy::types::ForcedCircuitState;
+
+        let db = Database::memory()?;
+        let provider = Provider::with_id(
+            "a".to_string(),
+            "Provider A".to_string(),
+            serde_json::json!({}),
+            None,
+        );
+        db.save_provider("claude", &provider)?;
+
+        db.set_provider_circuit_override("a", "claude", Some(ForcedCircuitState::Open), None)
+            .await?;
+        db.update_provider_health_with_threshold("a", "claude", false, Some("x".into()), 1)
+            .await?;
+        assert_eq!(
+            db.list_provider_circuit_overrides().await?,
+            vec![(
+                "a".to_string(),
+                "claude".to_string(),
+                ForcedCircuitState::Open,
+                None
+            )]
+        );
+
+        // 代理停止时清空健康统计，但保留手动状态
+        db.clear_all_provider_health().await?;
+        let health = db.get_provider_health("a", "claude").await?;
+        assert!(health.is_healthy);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.forced_state, Some(ForcedCircuitState::Open));
+
+        db.set_provider_circuit_override("a", "claude", None, None)
+            .await?;
+        assert!(db.list_provider_circuit_overrides().await?.is_empty());
+        db.clear_provider_health_for_app("claude").await?;
+        assert_eq!(
+            db.get_provider_health("a", "claude").await?.forced_state,
+            None
+        );
+
+        Ok(())
+    }
 }
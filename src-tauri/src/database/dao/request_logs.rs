@@ -0,0 +1,382 @@
+//! 请求日志维护数据访问对象
+//!
+//! 提供 proxy_request_logs 的批量清理、行数统计与空间回收等维护操作，
+//! 以及失败请求的请求/响应体捕获（proxy_request_bodies）的写入与重放读取。
+//! 查询与聚合统计见 services/usage_stats.rs。
+
+use crate::database::{lock_conn, Database, AUTO_VACUUM_INCREMENTAL};
+use crate::error::AppError;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, OptionalExtension};
+use serde_json::{Map, Value};
+
+/// 重放请求所需的原始请求信息（请求日志 + 请求体捕获）
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    pub app_type: String,
+    pub provider_id: String,
+    pub model: String,
+    pub is_streaming: bool,
+    /// 上游端点（含查询参数）；v19 之前捕获的记录为空
+    pub endpoint: Option<String>,
+    /// 脱敏后的请求头 JSON
+    pub request_headers: Option<String>,
+    /// 脱敏后的请求体 JSON；未开启请求体捕获时为空
+    pub request_body: Option<String>,
+    /// 捕获时是否有任一部分（请求头、请求体或响应体）被截断
+    pub truncated: bool,
+}
+
+impl Database {
+    /// 统计请求日志总行数
+    pub fn count_request_logs(&self) -> Result<u64, AppError> {
+        let conn = lock_conn!(self.conn);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM proxy_request_logs", [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(count.max(0) as u64)
+    }
+
+    /// 计算满足行数上限所需的清理截止时间
+    ///
+    /// 返回 Some(cutoff) 表示 created_at < cutoff 的行需要清理，cutoff 为需保留的
+    /// 最旧一行的时间戳（与其同一时间戳的行一并保留）；返回 None 表示当前行数未超出上限。
+    pub fn request_log_row_cutoff(&self, max_rows: u64) -> Result<Option<i64>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let total: i64 = conn
+            .query_row("SELECT COUNT(*) FROM proxy_request_logs", [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if total.max(0) as u64 <= max_rows {
+            return Ok(None);
+        }
+        if max_rows == 0 {
+            return Ok(Some(i64::MAX));
+        }
+
+        conn.query_row(
+            "SELECT created_at FROM proxy_request_logs
+             ORDER BY created_at DESC LIMIT 1 OFFSET ?1",
+            params![(max_rows - 1) as i64],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 在单个事务中删除一批 created_at < cutoff 的请求日志
+    ///
+    /// `collect_rows` 为 true 时返回被删除行的 JSON 表示（用于归档），
+    /// 否则仅返回删除数量。
+    pub fn delete_request_logs_batch(
+        &self,
+        cutoff: i64,
+        batch_size: usize,
+        collect_rows: bool,
+    ) -> Result<(usize, Vec<Value>), AppError> {
+        self.delete_request_logs_batch_with(cutoff, batch_size, collect_rows, |_| Ok(()))
+    }
+
+    /// 同 [`Database::delete_request_logs_batch`]，但在删除前以待删除行调用 `before_delete`
+    ///
+    /// `before_delete` 返回错误时事务回滚、不删除任何行，用于保证归档先于删除落盘。
+    pub fn delete_request_logs_batch_with<F>(
+        &self,
+        cutoff: i64,
+        batch_size: usize,
+        collect_rows: bool,
+        before_delete: F,
+    ) -> Result<(usize, Vec<Value>), AppError>
+    where
+        F: FnOnce(&[Value]) -> Result<(), AppError>,
+    {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut ids = Vec::new();
+        let mut rows_json = Vec::new();
+        {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT * FROM proxy_request_logs
+                     WHERE created_at < ?1
+                     ORDER BY created_at ASC
+                     LIMIT ?2",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+            let id_idx = columns
+                .iter()
+                .position(|c| c == "request_id")
+                .ok_or_else(|| {
+                    AppError::Database("proxy_request_logs 缺少 request_id 列".into())
+                })?;
+
+            let mut rows = stmt
+                .query(params![cutoff, batch_size as i64])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
+                let id: String = row
+                    .get(id_idx)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                ids.push(id);
+
+                if collect_rows {
+                    let mut obj = Map::with_capacity(columns.len());
+                    for (idx, name) in columns.iter().enumerate() {
+                        let value = row
+                            .get_ref(idx)
+                            .map_err(|e| AppError::Database(e.to_string()))?;
+                        obj.insert(name.clone(), value_ref_to_json(value));
+                    }
+                    rows_json.push(Value::Object(obj));
+                }
+            }
+        }
+
+        before_delete(&rows_json)?;
+
+        {
+            let mut delete = tx
+                .prepare("DELETE FROM proxy_request_logs WHERE request_id = ?1")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            // 捕获的请求/响应体随日志一起清理
+            let mut delete_bodies = tx
+                .prepare("DELETE FROM proxy_request_bodies WHERE request_id = ?1")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            for id in &ids {
+                delete
+                    .execute(params![id])
+                    .map_err(|e| AppError::Database(format!("删除请求日志失败: {e}")))?;
+                delete_bodies
+                    .execute(params![id])
+                    .map_err(|e| AppError::Database(format!("删除请求体捕获失败: {e}")))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| AppError::Database(format!("提交请求日志清理事务失败: {e}")))?;
+        if !ids.is_empty() {
+            self.invalidate_provider_spend();
+        }
+
+        Ok((ids.len(), rows_json))
+    }
+
+    /// 保存失败请求的请求/响应体捕获
+    #[allow(clippy::too_many_arguments)]
+    pub fn save_request_bodies(
+        &self,
+        request_id: &str,
+        endpoint: Option<&str>,
+        request_headers: Option<&str>,
+        request_body: Option<&str>,
+        response_body: Option<&str>,
+        truncated: bool,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO proxy_request_bodies (
+                request_id, endpoint, request_headers, request_body, response_body, truncated,
+                created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                request_id,
+                endpoint,
+                request_headers,
+                request_body,
+                response_body,
+                truncated as i64,
+                chrono::Utc::now().timestamp(),
+            ],
+        )
+        .map_err(|e| AppError::Database(format!("保存请求体捕获失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 读取重放请求所需的信息；请求日志不存在时返回 None
+    pub fn get_replay_source(&self, request_id: &str) -> Result<Option<ReplaySource>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT l.app_type, l.provider_id, l.model, l.is_streaming,
+                    b.endpoint, b.request_headers, b.request_body, b.truncated
+             FROM proxy_request_logs l
+             LEFT JOIN proxy_request_bodies b ON b.request_id = l.request_id
+             WHERE l.request_id = ?1",
+            params![request_id],
+            |row| {
+                Ok(ReplaySource {
+                    app_type: row.get(0)?,
+                    provider_id: row.get(1)?,
+                    model: row.get(2)?,
+                    is_streaming: row.get::<_, i64>(3)? != 0,
+                    endpoint: row.get(4)?,
+                    request_headers: row.get(5)?,
+                    request_body: row.get(6)?,
+                    truncated: row.get::<_, Option<i64>>(7)?.unwrap_or(0) != 0,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| AppError::Database(format!("读取重放请求失败: {e}")))
+    }
+
+    /// 回收已释放的数据库页
+    ///
+    /// 仅在 auto_vacuum=INCREMENTAL 时执行 `PRAGMA incremental_vacuum`；其余模式不做完整
+    /// VACUUM（会长时间占用连接），记录日志后跳过。模式切换在启动时完成，
+    /// 见 [`Database::convert_to_incremental_auto_vacuum`]。
+    pub fn incremental_vacuum(&self) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        let mode: i64 = conn
+            .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if mode != AUTO_VACUUM_INCREMENTAL {
+            log::info!("数据库 auto_vacuum={mode}，非 INCREMENTAL 模式，跳过空间回收");
+            return Ok(());
+        }
+        conn.execute_batch("PRAGMA incremental_vacuum;")
+            .map_err(|e| AppError::Database(format!("执行 incremental_vacuum 失败: {e}")))
+    }
+}
+
+fn value_ref_to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::String(String::from_utf8_lossy(b).into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{lock_conn, Database};
+    use crate::error::AppError;
+    use rusqlite::params;
+
+    fn insert_log(db: &Database, id: &str, created_at: i64) -> Result<(), AppError> {
+        let conn = lock_conn!(db.conn);
+        conn.execute(
+            "INSERT INTO proxy_request_logs (
+                request_id, provider_id, app_type, model, latency_ms, status_code, created_at
+            ) VALUES (?1, 'p1', 'claude', 'claude-3', 100, 200, ?2)",
+            params![id, created_at],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_request_logs_batch_respects_cutoff_and_batch_size() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        for i in 0..5 {
+            insert_log(&db, &format!("old-{i}"), 100 + i)?;
+        }
+        insert_log(&db, "new", 10_000)?;
+
+        let (deleted, rows) = db.delete_request_logs_batch(1_000, 3, true)?;
+        assert_eq!(deleted, 3);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["request_id"], "old-0");
+
+        let (deleted, rows) = db.delete_request_logs_batch(1_000, 3, false)?;
+        assert_eq!(deleted, 2);
+        assert!(rows.is_empty());
+
+        assert_eq!(db.count_request_logs()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_request_logs_batch_keeps_rows_when_archive_fails() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_log(&db, "old", 100)?;
+
+        let result = db.delete_request_logs_batch_with(1_000, 10, true, |rows| {
+            assert_eq!(rows.len(), 1);
+            Err(AppError::Message("disk full".into()))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(db.count_request_logs()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_vacuum_skips_non_incremental_databases() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        db.incremental_vacuum()?;
+
+        // 清理任务不会顺带切换模式（即不会执行完整 VACUUM）
+        let conn = lock_conn!(db.conn);
+        let mode: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+        assert_ne!(mode, super::AUTO_VACUUM_INCREMENTAL);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_request_logs_batch_removes_captured_bodies() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_log(&db, "old", 100)?;
+        insert_log(&db, "new", 10_000)?;
+        db.save_request_bodies("old", None, None, Some("{}"), Some("error"), false)?;
+        db.save_request_bodies("new", None, None, Some("{}"), Some("error"), false)?;
+
+        db.delete_request_logs_batch(1_000, 10, false)?;
+
+        let conn = lock_conn!(db.conn);
+        let remaining: Vec<String> = conn
+            .prepare("SELECT request_id FROM proxy_request_bodies")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        assert_eq!(remaining, vec!["new".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_replay_source_joins_captured_bodies() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_log(&db, "captured", 100)?;
+        insert_log(&db, "plain", 100)?;
+        db.save_request_bodies(
+            "captured",
+            Some("/v1/messages"),
+            Some(r#"{"content-type":"application/json"}"#),
+            Some(r#"{"model":"claude-3"}"#),
+            Some("error"),
+            false,
+        )?;
+
+        let source = db.get_replay_source("captured")?.expect("source");
+        assert_eq!(source.app_type, "claude");
+        assert_eq!(source.endpoint.as_deref(), Some("/v1/messages"));
+        assert_eq!(
+            source.request_body.as_deref(),
+            Some(r#"{"model":"claude-3"}"#)
+        );
+
+        let plain = db.get_replay_source("plain")?.expect("source");
+        assert!(plain.request_body.is_none());
+        assert!(db.get_replay_source("missing")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_log_row_cutoff() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        for i in 0..4 {
+            insert_log(&db, &format!("r{i}"), 100 + i)?;
+        }
+
+        assert_eq!(db.request_log_row_cutoff(10)?, None);
+        // 保留最新 2 行：第 3 新的行（created_at=101）及更早的需要清理
+        assert_eq!(db.request_log_row_cutoff(2)?, Some(102));
+        Ok(())
+    }
+}
@@ -6,6 +6,11 @@ use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use rusqlite::params;
 
+/// 某个 Codex 供应商额度缓存在 settings 表中的键
+pub(crate) fn codex_quota_cache_key(provider_id: &str) -> String {
+    format!("codex_quota_cache:{provider_id}")
+}
+
 impl Database {
     /// 获取设置值
     pub fn get_setting(&self, key: &str) -> Result<Option<String>, AppError> {
@@ -63,6 +68,41 @@ impl Database {
         }
     }
 
+    // --- 切换时回填 ---
+
+    /// 获取指定应用切换供应商时是否回填当前 live 配置（默认开启）
+    pub fn get_backfill_on_switch_enabled(&self, app_type: &str) -> Result<bool, AppError> {
+        let key = format!("backfill_on_switch_enabled_{app_type}");
+        match self.get_setting(&key)? {
+            Some(value) => Ok(value != "false"),
+            None => Ok(true),
+        }
+    }
+
+    /// 设置指定应用切换供应商时是否回填当前 live 配置
+    pub fn set_backfill_on_switch_enabled(
+        &self,
+        app_type: &str,
+        enabled: bool,
+    ) -> Result<(), AppError> {
+        let key = format!("backfill_on_switch_enabled_{app_type}");
+        let value = if enabled { "true" } else { "false" };
+        self.set_setting(&key, value)
+    }
+
+    // --- 本地 HTTP API ---
+
+    /// 获取是否启用代理端口上的本地 HTTP API（默认关闭）
+    pub fn get_local_api_enabled(&self) -> Result<bool, AppError> {
+        Ok(self.get_setting("local_api_enabled")?.as_deref() == Some("true"))
+    }
+
+    /// 设置是否启用本地 HTTP API
+    pub fn set_local_api_enabled(&self, enabled: bool) -> Result<(), AppError> {
+        let value = if enabled { "true" } else { "false" };
+        self.set_setting("local_api_enabled", value)
+    }
+
     // --- 全局出站代理 ---
 
     /// 全局代理 URL 的存储键名
@@ -98,6 +138,42 @@ impl Database {
         }
     }
 
+    /// 获取全局出站代理模式
+    ///
+    /// 未设置或无法识别时返回 Manual，保持旧版本行为。
+    pub fn get_proxy_mode(&self) -> Result<crate::proxy::types::ProxyMode, AppError> {
+        Ok(self
+            .get_setting("proxy_mode")?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default())
+    }
+
+    /// 设置全局出站代理模式
+    pub fn set_proxy_mode(&self, mode: crate::proxy::types::ProxyMode) -> Result<(), AppError> {
+        self.set_setting("proxy_mode", mode.as_str())
+    }
+
+    /// 获取全局出站网络配置（DNS 覆盖 / IPv6 优先）
+    pub fn get_global_network_config(
+        &self,
+    ) -> Result<crate::proxy::types::GlobalNetworkConfig, AppError> {
+        match self.get_setting("global_network_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析全局网络配置失败: {e}"))),
+            None => Ok(crate::proxy::types::GlobalNetworkConfig::default()),
+        }
+    }
+
+    /// 更新全局出站网络配置
+    pub fn set_global_network_config(
+        &self,
+        config: &crate::proxy::types::GlobalNetworkConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化全局网络配置失败: {e}")))?;
+        self.set_setting("global_network_config", &json)
+    }
+
     // --- 代理接管状态管理（已废弃，使用 proxy_config.enabled 替代）---
 
     /// 获取指定应用的代理接管状态
@@ -204,4 +280,114 @@ impl Database {
             .map_err(|e| AppError::Database(format!("序列化日志配置失败: {e}")))?;
         self.set_setting("log_config", &json)
     }
+
+    // --- 请求日志保留策略 ---
+
+    /// 获取请求日志保留策略
+    pub fn get_log_retention_config(
+        &self,
+    ) -> Result<crate::proxy::types::LogRetentionConfig, AppError> {
+        match self.get_setting("log_retention_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析日志保留策略失败: {e}"))),
+            None => Ok(crate::proxy::types::LogRetentionConfig::default()),
+        }
+    }
+
+    /// 更新请求日志保留策略
+    pub fn set_log_retention_config(
+        &self,
+        config: &crate::proxy::types::LogRetentionConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化日志保留策略失败: {e}")))?;
+        self.set_setting("log_retention_config", &json)
+    }
+
+    // --- 云同步 ---
+
+    /// 获取云同步配置
+    pub fn get_sync_config(&self) -> Result<crate::services::sync::SyncConfig, AppError> {
+        match self.get_setting("sync_config")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析同步配置失败: {e}"))),
+            None => Ok(crate::services::sync::SyncConfig::default()),
+        }
+    }
+
+    /// 更新云同步配置
+    pub fn set_sync_config(
+        &self,
+        config: &crate::services::sync::SyncConfig,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| AppError::Database(format!("序列化同步配置失败: {e}")))?;
+        self.set_setting("sync_config", &json)
+    }
+
+    /// 获取本机同步状态（首次调用时生成设备 ID）
+    pub fn get_sync_state(&self) -> Result<crate::services::sync::SyncState, AppError> {
+        let mut state: crate::services::sync::SyncState = match self.get_setting("sync_state")? {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Database(format!("解析同步状态失败: {e}")))?,
+            None => Default::default(),
+        };
+        if state.device_id.is_empty() {
+            state.device_id = uuid::Uuid::new_v4().to_string();
+            self.set_sync_state(&state)?;
+        }
+        Ok(state)
+    }
+
+    /// 更新本机同步状态
+    pub fn set_sync_state(&self, state: &crate::services::sync::SyncState) -> Result<(), AppError> {
+        let json = serde_json::to_string(state)
+            .map_err(|e| AppError::Database(format!("序列化同步状态失败: {e}")))?;
+        self.set_setting("sync_state", &json)
+    }
+
+    // --- Codex 额度缓存 ---
+
+    /// 获取 Codex 额度缓存有效期（秒），未设置时返回默认值
+    pub fn get_codex_quota_ttl_secs(&self) -> Result<u64, AppError> {
+        use crate::services::codex_quota::{
+            DEFAULT_CODEX_QUOTA_TTL_SECS, MIN_CODEX_QUOTA_TTL_SECS,
+        };
+        Ok(self
+            .get_setting("codex_quota_ttl_secs")?
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|secs| secs.max(MIN_CODEX_QUOTA_TTL_SECS))
+            .unwrap_or(DEFAULT_CODEX_QUOTA_TTL_SECS))
+    }
+
+    /// 设置 Codex 额度缓存有效期（秒），低于下限时按下限保存
+    pub fn set_codex_quota_ttl_secs(&self, secs: u64) -> Result<u64, AppError> {
+        let secs = secs.max(crate::services::codex_quota::MIN_CODEX_QUOTA_TTL_SECS);
+        self.set_setting("codex_quota_ttl_secs", &secs.to_string())?;
+        Ok(secs)
+    }
+
+    /// 获取某个 Codex 供应商最近一次成功查询的额度
+    pub fn get_codex_quota_cache(
+        &self,
+        provider_id: &str,
+    ) -> Result<Option<crate::services::codex_quota::CodexQuotaUsage>, AppError> {
+        match self.get_setting(&codex_quota_cache_key(provider_id))? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| AppError::Database(format!("解析 Codex 额度缓存失败: {e}"))),
+            None => Ok(None),
+        }
+    }
+
+    /// 保存某个 Codex 供应商的额度查询结果
+    pub fn set_codex_quota_cache(
+        &self,
+        provider_id: &str,
+        usage: &crate::services::codex_quota::CodexQuotaUsage,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(usage)
+            .map_err(|e| AppError::Database(format!("序列化 Codex 额度缓存失败: {e}")))?;
+        self.set_setting(&codex_quota_cache_key(provider_id), &json)
+    }
 }
@@ -9,9 +9,9 @@
 use crate::app_config::{InstalledSkill, SkillApps};
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::services::skill::SkillRepo;
+use crate::services::skill::{SkillRepo, SkillRepoHost};
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
 impl Database {
     // ========== InstalledSkill CRUD ==========
@@ -143,6 +143,15 @@ impl Database {
     /// 更新 Skill 的应用启用状态
     pub fn update_skill_apps(&self, id: &str, apps: &SkillApps) -> Result<bool, AppError> {
         let conn = lock_conn!(self.conn);
+        Self::update_skill_apps_on_conn(&conn, id, apps)
+    }
+
+    /// 在指定连接（或事务）上更新 Skill 的应用启用状态
+    pub(crate) fn update_skill_apps_on_conn(
+        conn: &Connection,
+        id: &str,
+        apps: &SkillApps,
+    ) -> Result<bool, AppError> {
         let affected = conn
             .execute(
                 "UPDATE skills SET enabled_claude = ?1, enabled_codex = ?2, enabled_gemini = ?3, enabled_opencode = ?4 WHERE id = ?5",
@@ -159,17 +168,22 @@ impl Database {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
-                "SELECT owner, name, branch, enabled FROM skill_repos ORDER BY owner ASC, name ASC",
+                "SELECT owner, name, branch, enabled, host_kind, base_url
+                 FROM skill_repos ORDER BY owner ASC, name ASC",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let repo_iter = stmt
             .query_map([], |row| {
+                let host_kind: String = row.get(4)?;
                 Ok(SkillRepo {
                     owner: row.get(0)?,
                     name: row.get(1)?,
                     branch: row.get(2)?,
                     enabled: row.get(3)?,
+                    host_kind: SkillRepoHost::parse(&host_kind),
+                    base_url: row.get(5)?,
+                    token: None,
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -178,29 +192,81 @@ impl Database {
         for repo_res in repo_iter {
             repos.push(repo_res.map_err(|e| AppError::Database(e.to_string()))?);
         }
+        drop(stmt);
+        drop(conn);
+
+        for repo in &mut repos {
+            repo.token = self.get_skill_repo_token(&repo.owner, &repo.name)?;
+        }
         Ok(repos)
     }
 
+    /// 获取 Skill 仓库访问令牌（保存在 settings 表）
+    pub fn get_skill_repo_token(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Option<String>, AppError> {
+        self.get_setting(&skill_repo_token_key(owner, name))
+    }
+
+    /// 设置或清除 Skill 仓库访问令牌
+    pub fn set_skill_repo_token(
+        &self,
+        owner: &str,
+        name: &str,
+        token: Option<&str>,
+    ) -> Result<(), AppError> {
+        let key = skill_repo_token_key(owner, name);
+        match token.map(str::trim).filter(|t| !t.is_empty()) {
+            Some(token) => self.set_setting(&key, token),
+            None => {
+                let conn = lock_conn!(self.conn);
+                conn.execute("DELETE FROM settings WHERE key = ?1", params![key])
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                Ok(())
+            }
+        }
+    }
+
     /// 保存 Skill 仓库
     pub fn save_skill_repo(&self, repo: &SkillRepo) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::save_skill_repo_on_conn(&conn, repo)
+    }
+
+    /// 在指定连接（或事务）上保存 Skill 仓库
+    pub(crate) fn save_skill_repo_on_conn(
+        conn: &Connection,
+        repo: &SkillRepo,
+    ) -> Result<(), AppError> {
         conn.execute(
-            "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled) VALUES (?1, ?2, ?3, ?4)",
-            params![repo.owner, repo.name, repo.branch, repo.enabled],
+            "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled, host_kind, base_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                repo.owner,
+                repo.name,
+                repo.branch,
+                repo.enabled,
+                repo.host_kind.as_str(),
+                repo.base_url
+            ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
-    /// 删除 Skill 仓库
+    /// 删除 Skill 仓库（同时清除其访问令牌）
     pub fn delete_skill_repo(&self, owner: &str, name: &str) -> Result<(), AppError> {
-        let conn = lock_conn!(self.conn);
-        conn.execute(
-            "DELETE FROM skill_repos WHERE owner = ?1 AND name = ?2",
-            params![owner, name],
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
-        Ok(())
+        {
+            let conn = lock_conn!(self.conn);
+            conn.execute(
+                "DELETE FROM skill_repos WHERE owner = ?1 AND name = ?2",
+                params![owner, name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        self.set_skill_repo_token(owner, name, None)
     }
 
     /// 初始化默认的 Skill 仓库（启动时调用，补充缺失的默认仓库）
@@ -232,3 +298,7 @@ impl Database {
         Ok(count)
     }
 }
+
+fn skill_repo_token_key(owner: &str, name: &str) -> String {
+    format!("skill_repo_token:{owner}/{name}")
+}
@@ -2,7 +2,9 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::services::stream_check::{StreamCheckConfig, StreamCheckResult};
+use crate::services::stream_check::{
+    StreamCheckConfig, StreamCheckHistoryBucket, StreamCheckResult,
+};
 
 impl Database {
     /// 保存流式检查日志
@@ -54,4 +56,110 @@ impl Database {
             .map_err(|e| AppError::Message(format!("序列化配置失败: {e}")))?;
         self.set_setting("stream_check_config", &json)
     }
+
+    /// 按时间桶聚合某个供应商的检查历史（按时间升序，最多返回最近 `limit` 个桶）
+    pub fn get_stream_check_history(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        bucket_secs: i64,
+        limit: u32,
+    ) -> Result<Vec<StreamCheckHistoryBucket>, AppError> {
+        let bucket_secs = bucket_secs.max(60);
+        let conn = lock_conn!(self.conn);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT (tested_at / ?3) * ?3 AS bucket_start,
+                        COUNT(*),
+                        SUM(CASE WHEN success THEN 1 ELSE 0 END),
+                        AVG(response_time_ms),
+                        MAX(response_time_ms)
+                 FROM stream_check_logs
+                 WHERE app_type = ?1 AND provider_id = ?2
+                 GROUP BY bucket_start
+                 ORDER BY bucket_start DESC
+                 LIMIT ?4",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut buckets = stmt
+            .query_map(
+                rusqlite::params![app_type, provider_id, bucket_secs, limit],
+                |row| {
+                    let total: u32 = row.get(1)?;
+                    let success_count: u32 = row.get(2)?;
+                    let max_latency: Option<i64> = row.get(4)?;
+                    Ok(StreamCheckHistoryBucket {
+                        bucket_start: row.get(0)?,
+                        total,
+                        success_count,
+                        success_rate: if total > 0 {
+                            f64::from(success_count) / f64::from(total)
+                        } else {
+                            0.0
+                        },
+                        avg_latency_ms: row.get(3)?,
+                        max_latency_ms: max_latency.map(|v| v as u64),
+                    })
+                },
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        buckets.reverse();
+        Ok(buckets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::stream_check::HealthStatus;
+
+    fn result(success: bool, latency: Option<u64>, tested_at: i64) -> StreamCheckResult {
+        StreamCheckResult {
+            status: if success {
+                HealthStatus::Operational
+            } else {
+                HealthStatus::Failed
+            },
+            success,
+            message: String::new(),
+            response_time_ms: latency,
+            http_status: None,
+            model_used: "m".to_string(),
+            tested_at,
+            retry_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_stream_check_history_buckets() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let logs = [
+            result(true, Some(100), 3_600),
+            result(false, None, 3_700),
+            result(true, Some(300), 7_300),
+            result(true, Some(200), 10_900),
+        ];
+        for log in &logs {
+            db.save_stream_check_log("p1", "P1", "claude", log)?;
+        }
+        db.save_stream_check_log("p2", "P2", "claude", &logs[0])?;
+
+        let history = db.get_stream_check_history("claude", "p1", 3_600, 2)?;
+        let starts: Vec<i64> = history.iter().map(|b| b.bucket_start).collect();
+        assert_eq!(starts, vec![7_200, 10_800]);
+
+        let all = db.get_stream_check_history("claude", "p1", 3_600, 10)?;
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].total, 2);
+        assert_eq!(all[0].success_count, 1);
+        assert!((all[0].success_rate - 0.5).abs() < f64::EPSILON);
+        assert_eq!(all[0].avg_latency_ms, Some(100.0));
+        assert_eq!(all[1].max_latency_ms, Some(300));
+        Ok(())
+    }
 }
@@ -0,0 +1,112 @@
+//! 导入合并策略
+//!
+//! 文件导入与深链接导入共用的统一冲突处理策略（[`MergeStrategy`]），
+//! 以及逐项记录实际采取操作的导入结果（[`ImportItemOutcome`]）。
+
+use super::selective_import::{ImportItem, ImportItemKind, ImportResolution, RowMap};
+use crate::error::AppError;
+use crate::services::ProviderService;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 导入时与已有条目冲突的统一处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// 跳过已存在的条目（默认）
+    #[default]
+    SkipExisting,
+    /// 使用导入版本覆盖
+    Overwrite,
+    /// 合并配置字段（见 [`ImportResolution::MergeFields`]）
+    MergeFields,
+    /// 追加后缀另存，避免冲突
+    Rename,
+}
+
+impl MergeStrategy {
+    /// 对应的单项冲突处理方式
+    pub fn resolution(self) -> ImportResolution {
+        match self {
+            Self::SkipExisting => ImportResolution::KeepLocal,
+            Self::Overwrite => ImportResolution::TakeImport,
+            Self::MergeFields => ImportResolution::MergeFields,
+            Self::Rename => ImportResolution::DuplicateRename,
+        }
+    }
+}
+
+/// 对单个导入条目实际采取的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Added,
+    /// 与本地一致，未改动
+    Unchanged,
+    Skipped,
+    Replaced,
+    Merged,
+    Renamed,
+}
+
+/// 单个导入条目的处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemOutcome {
+    pub key: String,
+    pub kind: ImportItemKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub action: ImportAction,
+    /// 另存时使用的新 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_id: Option<String>,
+}
+
+impl ImportItemOutcome {
+    pub(super) fn new(item: &ImportItem, action: ImportAction, new_id: Option<String>) -> Self {
+        Self {
+            key: item.key.clone(),
+            kind: item.kind,
+            app_type: item.app_type.clone(),
+            id: item.id.clone(),
+            name: item.name.clone(),
+            action,
+            new_id,
+        }
+    }
+}
+
+/// 以本地供应商配置为底，合并导入版本的 `settings_config`（其余列保留本地值）
+pub(super) fn merge_provider_settings(conn: &Connection, import: &RowMap) -> Result<(), AppError> {
+    let (Some(SqlValue::Text(id)), Some(SqlValue::Text(app_type))) =
+        (import.get("id"), import.get("app_type"))
+    else {
+        return Ok(());
+    };
+    let parse = |text: &str| {
+        serde_json::from_str::<Value>(text)
+            .map_err(|e| AppError::Database(format!("解析供应商配置失败: {e}")))
+    };
+    let local: String = conn
+        .query_row(
+            "SELECT settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
+            params![id, app_type],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let mut merged = parse(&local)?;
+    if let Some(SqlValue::Text(import_settings)) = import.get("settings_config") {
+        ProviderService::merge_json(&mut merged, &parse(import_settings)?);
+    }
+    conn.execute(
+        "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+        params![merged.to_string(), id, app_type],
+    )
+    .map_err(|e| AppError::Database(format!("合并供应商配置失败: {e}")))?;
+    Ok(())
+}
@@ -1,11 +1,119 @@
 //! JSON → SQLite 数据迁移
 //!
 //! 将旧版 config.json (MultiAppConfig) 数据迁移到 SQLite 数据库。
+//! 迁移后旧文件归档为 config.json.migrated，可按分区以合并模式重新导入（`reimport_from_json`）。
 
 use super::{lock_conn, to_json_string, Database};
-use crate::app_config::MultiAppConfig;
+use crate::app_config::{McpServer, MultiAppConfig};
 use crate::error::AppError;
-use rusqlite::{params, Connection};
+use crate::prompt::Prompt;
+use crate::provider::Provider;
+use crate::services::skill::SkillRepo;
+use rusqlite::{params, Connection, OptionalExtension, Params, Transaction};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 可从归档 JSON 重新导入的分区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonReimportSection {
+    Providers,
+    Mcp,
+    Prompts,
+    Skills,
+    Settings,
+}
+
+impl FromStr for JsonReimportSection {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "providers" => Ok(Self::Providers),
+            "mcp" => Ok(Self::Mcp),
+            "prompts" => Ok(Self::Prompts),
+            "skills" => Ok(Self::Skills),
+            "settings" => Ok(Self::Settings),
+            other => Err(AppError::localized(
+                "migration.reimport.unknown_section",
+                format!("未知的导入分区: {other}（可选 providers/mcp/prompts/skills/settings）"),
+                format!(
+                    "Unknown section: {other} (expected providers/mcp/prompts/skills/settings)"
+                ),
+            )),
+        }
+    }
+}
+
+/// 单个分区的重新导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonReimportSectionReport {
+    pub section: JsonReimportSection,
+    pub imported: usize,
+    /// ID 已存在而跳过的条目
+    pub skipped: usize,
+    pub failed: usize,
+    /// 失败条目及原因
+    pub errors: Vec<String>,
+}
+
+/// 从归档 JSON 重新导入的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonReimportReport {
+    pub dry_run: bool,
+    pub sections: Vec<JsonReimportSectionReport>,
+}
+
+impl JsonReimportSectionReport {
+    fn new(section: JsonReimportSection) -> Self {
+        Self {
+            section,
+            imported: 0,
+            skipped: 0,
+            failed: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// 合并模式导入单个条目：已存在则跳过；失败时只回滚该条目并记录原因
+    fn import_item(
+        &mut self,
+        tx: &mut Transaction<'_>,
+        label: &str,
+        exists_sql: &str,
+        key: impl Params,
+        insert: impl FnOnce(&Connection) -> Result<(), AppError>,
+    ) {
+        let exists = tx
+            .query_row(exists_sql, key, |_| Ok(()))
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()));
+        let result = match exists {
+            Ok(Some(())) => {
+                self.skipped += 1;
+                return;
+            }
+            Ok(None) => tx
+                .savepoint()
+                .map_err(|e| AppError::Database(e.to_string()))
+                .and_then(|sp| {
+                    insert(&sp)?;
+                    sp.commit().map_err(|e| AppError::Database(e.to_string()))
+                }),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => self.imported += 1,
+            Err(e) => {
+                log::warn!("重新导入 {label} 失败: {e}");
+                self.failed += 1;
+                self.errors.push(format!("{label}: {e}"));
+            }
+        }
+    }
+}
 
 impl Database {
     /// 从 MultiAppConfig 迁移数据到数据库
@@ -41,6 +149,165 @@ impl Database {
         Ok(())
     }
 
+    /// 从归档的 config.json 按分区重新导入（合并模式）
+    ///
+    /// 只导入数据库中尚不存在的条目（按 ID 判断），每个条目独立回滚，失败不影响其余条目；
+    /// `dry_run` 时在同一事务中执行后回滚，报告与实际导入一致但不写入数据库。
+    pub fn reimport_from_json(
+        &self,
+        config: &MultiAppConfig,
+        sections: &[JsonReimportSection],
+        dry_run: bool,
+    ) -> Result<JsonReimportReport, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let mut tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut reports = Vec::new();
+        for section in sections {
+            if reports
+                .iter()
+                .any(|r: &JsonReimportSectionReport| r.section == *section)
+            {
+                continue;
+            }
+            let mut report = JsonReimportSectionReport::new(*section);
+            match section {
+                JsonReimportSection::Providers => {
+                    Self::reimport_providers(&mut tx, config, &mut report)?
+                }
+                JsonReimportSection::Mcp => {
+                    Self::reimport_mcp_servers(&mut tx, config, &mut report)
+                }
+                JsonReimportSection::Prompts => {
+                    Self::reimport_prompts(&mut tx, config, &mut report)
+                }
+                JsonReimportSection::Skills => {
+                    Self::reimport_skill_repos(&mut tx, config, &mut report)
+                }
+                JsonReimportSection::Settings => {
+                    Self::reimport_settings(&mut tx, config, &mut report)
+                }
+            }
+            log::info!(
+                "重新导入 {section:?}: 导入 {}，跳过 {}，失败 {}",
+                report.imported,
+                report.skipped,
+                report.failed
+            );
+            reports.push(report);
+        }
+
+        if dry_run {
+            drop(tx);
+        } else {
+            tx.commit()
+                .map_err(|e| AppError::Database(format!("Commit reimport failed: {e}")))?;
+        }
+        Ok(JsonReimportReport {
+            dry_run,
+            sections: reports,
+        })
+    }
+
+    fn reimport_providers(
+        tx: &mut Transaction<'_>,
+        config: &MultiAppConfig,
+        report: &mut JsonReimportSectionReport,
+    ) -> Result<(), AppError> {
+        for (app_type, manager) in &config.apps {
+            // 该应用已有当前供应商时，不再把导入的供应商设为当前
+            let has_current = tx
+                .query_row(
+                    "SELECT 1 FROM providers WHERE app_type = ?1 AND is_current = 1",
+                    params![app_type],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(|e| AppError::Database(e.to_string()))?
+                .is_some();
+
+            for (id, provider) in &manager.providers {
+                let is_current = !has_current && *id == manager.current;
+                report.import_item(
+                    tx,
+                    &format!("provider {app_type}/{id}"),
+                    "SELECT 1 FROM providers WHERE id = ?1 AND app_type = ?2",
+                    params![id, app_type],
+                    |conn| Self::insert_json_provider(conn, app_type, id, provider, is_current),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn reimport_mcp_servers(
+        tx: &mut Transaction<'_>,
+        config: &MultiAppConfig,
+        report: &mut JsonReimportSectionReport,
+    ) {
+        for (id, server) in config.mcp.servers.iter().flatten() {
+            report.import_item(
+                tx,
+                &format!("mcp {id}"),
+                "SELECT 1 FROM mcp_servers WHERE id = ?1",
+                params![id],
+                |conn| Self::insert_json_mcp_server(conn, id, server),
+            );
+        }
+    }
+
+    fn reimport_prompts(
+        tx: &mut Transaction<'_>,
+        config: &MultiAppConfig,
+        report: &mut JsonReimportSectionReport,
+    ) {
+        for (app_type, prompts) in Self::json_prompt_maps(config) {
+            for (id, prompt) in prompts {
+                report.import_item(
+                    tx,
+                    &format!("prompt {app_type}/{id}"),
+                    "SELECT 1 FROM prompts WHERE id = ?1 AND app_type = ?2",
+                    params![id, app_type],
+                    |conn| Self::insert_json_prompt(conn, app_type, id, prompt),
+                );
+            }
+        }
+    }
+
+    fn reimport_skill_repos(
+        tx: &mut Transaction<'_>,
+        config: &MultiAppConfig,
+        report: &mut JsonReimportSectionReport,
+    ) {
+        for repo in &config.skills.repos {
+            report.import_item(
+                tx,
+                &format!("skill repo {}/{}", repo.owner, repo.name),
+                "SELECT 1 FROM skill_repos WHERE owner = ?1 AND name = ?2",
+                params![repo.owner, repo.name],
+                |conn| Self::insert_json_skill_repo(conn, repo),
+            );
+        }
+    }
+
+    fn reimport_settings(
+        tx: &mut Transaction<'_>,
+        config: &MultiAppConfig,
+        report: &mut JsonReimportSectionReport,
+    ) {
+        for (key, snippet) in Self::json_common_config_snippets(config) {
+            report.import_item(
+                tx,
+                &format!("setting {key}"),
+                "SELECT 1 FROM settings WHERE key = ?1",
+                params![key],
+                |conn| Self::insert_json_setting(conn, key, snippet),
+            );
+        }
+    }
+
     /// 在事务中执行迁移
     fn migrate_from_json_tx(
         tx: &rusqlite::Transaction<'_>,
@@ -69,121 +336,146 @@ impl Database {
         tx: &rusqlite::Transaction<'_>,
         config: &MultiAppConfig,
     ) -> Result<(), AppError> {
-        for (app_key, manager) in &config.apps {
-            let app_type = app_key;
-            let current_id = &manager.current;
-
+        for (app_type, manager) in &config.apps {
             for (id, provider) in &manager.providers {
-                let is_current = if id == current_id { 1 } else { 0 };
-
-                // 处理 meta 和 endpoints
-                let mut meta_clone = provider.meta.clone().unwrap_or_default();
-                let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
-
-                tx.execute(
-                    "INSERT OR REPLACE INTO providers (
-                        id, app_type, name, settings_config, website_url, category,
-                        created_at, sort_index, notes, icon, icon_color, meta, is_current
-                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-                    params![
-                        id,
-                        app_type,
-                        provider.name,
-                        to_json_string(&provider.settings_config)?,
-                        provider.website_url,
-                        provider.category,
-                        provider.created_at,
-                        provider.sort_index,
-                        provider.notes,
-                        provider.icon,
-                        provider.icon_color,
-                        to_json_string(&meta_clone)?,
-                        is_current,
-                    ],
-                )
-                .map_err(|e| AppError::Database(format!("Migrate provider failed: {e}")))?;
-
-                // 迁移 Endpoints
-                for (url, endpoint) in endpoints {
-                    tx.execute(
-                        "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
-                         VALUES (?1, ?2, ?3, ?4)",
-                        params![id, app_type, url, endpoint.added_at],
-                    )
-                    .map_err(|e| AppError::Database(format!("Migrate endpoint failed: {e}")))?;
-                }
+                let is_current = *id == manager.current;
+                Self::insert_json_provider(tx, app_type, id, provider, is_current)?;
             }
         }
         Ok(())
     }
 
+    /// 写入单个供应商及其自定义端点
+    fn insert_json_provider(
+        conn: &Connection,
+        app_type: &str,
+        id: &str,
+        provider: &Provider,
+        is_current: bool,
+    ) -> Result<(), AppError> {
+        // 处理 meta 和 endpoints
+        let mut meta_clone = provider.meta.clone().unwrap_or_default();
+        let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
+
+        conn.execute(
+            "INSERT OR REPLACE INTO providers (
+                id, app_type, name, settings_config, website_url, category,
+                created_at, sort_index, notes, icon, icon_color, meta, is_current
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                id,
+                app_type,
+                provider.name,
+                to_json_string(&provider.settings_config)?,
+                provider.website_url,
+                provider.category,
+                provider.created_at,
+                provider.sort_index,
+                provider.notes,
+                provider.icon,
+                provider.icon_color,
+                to_json_string(&meta_clone)?,
+                is_current,
+            ],
+        )
+        .map_err(|e| AppError::Database(format!("Migrate provider failed: {e}")))?;
+
+        // 迁移 Endpoints
+        for (url, endpoint) in endpoints {
+            conn.execute(
+                "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![id, app_type, url, endpoint.added_at],
+            )
+            .map_err(|e| AppError::Database(format!("Migrate endpoint failed: {e}")))?;
+        }
+        Ok(())
+    }
+
     /// 迁移 MCP 服务器数据
     fn migrate_mcp_servers(
         tx: &rusqlite::Transaction<'_>,
         config: &MultiAppConfig,
     ) -> Result<(), AppError> {
-        if let Some(servers) = &config.mcp.servers {
-            for (id, server) in servers {
-                tx.execute(
-                    "INSERT OR REPLACE INTO mcp_servers (
-                        id, name, server_config, description, homepage, docs, tags,
-                        enabled_claude, enabled_codex, enabled_gemini
-                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-                    params![
-                        id,
-                        server.name,
-                        to_json_string(&server.server)?,
-                        server.description,
-                        server.homepage,
-                        server.docs,
-                        to_json_string(&server.tags)?,
-                        server.apps.claude,
-                        server.apps.codex,
-                        server.apps.gemini,
-                    ],
-                )
-                .map_err(|e| AppError::Database(format!("Migrate mcp server failed: {e}")))?;
-            }
+        for (id, server) in config.mcp.servers.iter().flatten() {
+            Self::insert_json_mcp_server(tx, id, server)?;
         }
         Ok(())
     }
 
+    fn insert_json_mcp_server(
+        conn: &Connection,
+        id: &str,
+        server: &McpServer,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_servers (
+                id, name, server_config, description, homepage, docs, tags,
+                enabled_claude, enabled_codex, enabled_gemini
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                id,
+                server.name,
+                to_json_string(&server.server)?,
+                server.description,
+                server.homepage,
+                server.docs,
+                to_json_string(&server.tags)?,
+                server.apps.claude,
+                server.apps.codex,
+                server.apps.gemini,
+            ],
+        )
+        .map_err(|e| AppError::Database(format!("Migrate mcp server failed: {e}")))?;
+        Ok(())
+    }
+
     /// 迁移提示词数据
     fn migrate_prompts(
         tx: &rusqlite::Transaction<'_>,
         config: &MultiAppConfig,
     ) -> Result<(), AppError> {
-        let migrate_app_prompts = |prompts_map: &std::collections::HashMap<
-            String,
-            crate::prompt::Prompt,
-        >,
-                                   app_type: &str|
-         -> Result<(), AppError> {
-            for (id, prompt) in prompts_map {
-                tx.execute(
-                        "INSERT OR REPLACE INTO prompts (
-                            id, app_type, name, content, description, enabled, created_at, updated_at
-                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-                        params![
-                            id,
-                            app_type,
-                            prompt.name,
-                            prompt.content,
-                            prompt.description,
-                            prompt.enabled,
-                            prompt.created_at,
-                            prompt.updated_at,
-                        ],
-                    )
-                    .map_err(|e| AppError::Database(format!("Migrate prompt failed: {e}")))?;
+        for (app_type, prompts) in Self::json_prompt_maps(config) {
+            for (id, prompt) in prompts {
+                Self::insert_json_prompt(tx, app_type, id, prompt)?;
             }
-            Ok(())
-        };
+        }
+        Ok(())
+    }
 
-        migrate_app_prompts(&config.prompts.claude.prompts, "claude")?;
-        migrate_app_prompts(&config.prompts.codex.prompts, "codex")?;
-        migrate_app_prompts(&config.prompts.gemini.prompts, "gemini")?;
+    /// 旧版配置中参与迁移的各应用提示词
+    fn json_prompt_maps(
+        config: &MultiAppConfig,
+    ) -> [(&'static str, &std::collections::HashMap<String, Prompt>); 3] {
+        [
+            ("claude", &config.prompts.claude.prompts),
+            ("codex", &config.prompts.codex.prompts),
+            ("gemini", &config.prompts.gemini.prompts),
+        ]
+    }
 
+    fn insert_json_prompt(
+        conn: &Connection,
+        app_type: &str,
+        id: &str,
+        prompt: &Prompt,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR REPLACE INTO prompts (
+                id, app_type, name, content, description, enabled, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                id,
+                app_type,
+                prompt.name,
+                prompt.content,
+                prompt.description,
+                prompt.enabled,
+                prompt.created_at,
+                prompt.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(format!("Migrate prompt failed: {e}")))?;
         Ok(())
     }
 
@@ -204,42 +496,51 @@ impl Database {
         // 来重建已安装技能记录。
 
         for repo in &config.skills.repos {
-            tx.execute(
-                "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled) VALUES (?1, ?2, ?3, ?4)",
-                params![repo.owner, repo.name, repo.branch, repo.enabled],
-            ).map_err(|e| AppError::Database(format!("Migrate skill repo failed: {e}")))?;
+            Self::insert_json_skill_repo(tx, repo)?;
         }
 
         Ok(())
     }
 
+    fn insert_json_skill_repo(conn: &Connection, repo: &SkillRepo) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR REPLACE INTO skill_repos (owner, name, branch, enabled) VALUES (?1, ?2, ?3, ?4)",
+            params![repo.owner, repo.name, repo.branch, repo.enabled],
+        )
+        .map_err(|e| AppError::Database(format!("Migrate skill repo failed: {e}")))?;
+        Ok(())
+    }
+
     /// 迁移通用配置片段
     fn migrate_common_config(
         tx: &rusqlite::Transaction<'_>,
         config: &MultiAppConfig,
     ) -> Result<(), AppError> {
-        if let Some(snippet) = &config.common_config_snippets.claude {
-            tx.execute(
-                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                params!["common_config_claude", snippet],
-            )
-            .map_err(|e| AppError::Database(format!("Migrate settings failed: {e}")))?;
-        }
-        if let Some(snippet) = &config.common_config_snippets.codex {
-            tx.execute(
-                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                params!["common_config_codex", snippet],
-            )
-            .map_err(|e| AppError::Database(format!("Migrate settings failed: {e}")))?;
-        }
-        if let Some(snippet) = &config.common_config_snippets.gemini {
-            tx.execute(
-                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                params!["common_config_gemini", snippet],
-            )
-            .map_err(|e| AppError::Database(format!("Migrate settings failed: {e}")))?;
+        for (key, snippet) in Self::json_common_config_snippets(config) {
+            Self::insert_json_setting(tx, key, snippet)?;
         }
+        Ok(())
+    }
+
+    /// 旧版配置中的通用配置片段（settings 表键 → 内容）
+    fn json_common_config_snippets(config: &MultiAppConfig) -> Vec<(&'static str, &str)> {
+        let snippets = &config.common_config_snippets;
+        [
+            ("common_config_claude", &snippets.claude),
+            ("common_config_codex", &snippets.codex),
+            ("common_config_gemini", &snippets.gemini),
+        ]
+        .into_iter()
+        .filter_map(|(key, snippet)| snippet.as_deref().map(|s| (key, s)))
+        .collect()
+    }
 
+    fn insert_json_setting(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )
+        .map_err(|e| AppError::Database(format!("Migrate settings failed: {e}")))?;
         Ok(())
     }
 }
@@ -14,40 +14,69 @@
 //! ├── mod.rs        - Database 结构体 + 初始化
 //! ├── schema.rs     - 表结构定义 + Schema 迁移
 //! ├── backup.rs     - SQL 导入导出 + 快照备份
+//! ├── backup_crypto.rs - 加密备份格式（gzip + AES-256-GCM）
+//! ├── bundle.rs     - 团队配置包（MCP + 提示词 + Skills）
+//! ├── import_merge.rs - 导入合并策略（统一冲突处理 + 逐项结果）
 //! ├── migration.rs  - JSON → SQLite 数据迁移
+//! ├── selective_import.rs - 选择性导入（冲突预览 + 按项合并）
+//! ├── sync_payload.rs - 云同步载荷（加密导出 + 按表替换）
 //! └── dao/          - 数据访问对象
 //!     ├── providers.rs
 //!     ├── mcp.rs
 //!     ├── prompts.rs
 //!     ├── skills.rs
+//!     ├── request_logs.rs
 //!     └── settings.rs
 //! ```
 
 mod backup;
+mod backup_crypto;
+mod bundle;
 mod dao;
+mod import_merge;
 mod migration;
 mod schema;
+mod selective_import;
+mod sync_payload;
 
 #[cfg(test)]
 mod tests;
 
 // DAO 类型导出供外部使用
-pub use dao::FailoverQueueItem;
+pub(crate) use backup::{is_export_in_progress, ExportGuard};
+pub use bundle::{BundleImportResult, ConfigBundle};
+pub use dao::{
+    ConfigSnippet, CustomEndpointMerge, CustomEndpointRow, EndpointLatencyRecord,
+    FailoverQueueItem, InvalidProviderRow, LiveConfigVersion, ProviderAuditEntry, ReplaySource,
+};
+pub use import_merge::{ImportAction, ImportItemOutcome, MergeStrategy};
+pub use migration::{JsonReimportReport, JsonReimportSection};
+pub use selective_import::{ImportApplyResult, ImportPreview, ImportResolution};
 
 use crate::config::get_app_config_dir;
 use crate::error::AppError;
+use crate::services::usage_stats::ProviderSpend;
 use rusqlite::Connection;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Mutex;
+use std::time::Duration;
 
 // DAO 方法通过 impl Database 提供，无需额外导出
 
 /// 数据库备份保留数量
 const DB_BACKUP_RETAIN: usize = 10;
 
+/// 数据库被锁定时的最长等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
 /// 当前 Schema 版本号
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
-pub(crate) const SCHEMA_VERSION: i32 = 6;
+pub(crate) const SCHEMA_VERSION: i32 = 22;
+
+/// `PRAGMA auto_vacuum` 中 INCREMENTAL 模式的取值
+pub(crate) const AUTO_VACUUM_INCREMENTAL: i64 = 2;
 
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError> {
@@ -73,6 +102,8 @@ pub(crate) use lock_conn;
 /// rusqlite::Connection 本身不是 Sync 的，因此需要这层包装。
 pub struct Database {
     pub(crate) conn: Mutex<Connection>,
+    /// 各供应商当前限额窗口的累计消费，供代理路由免查询判断限额
+    pub(crate) spend_cache: Mutex<HashMap<(String, String), ProviderSpend>>,
 }
 
 impl Database {
@@ -80,24 +111,50 @@ impl Database {
     ///
     /// 数据库文件位于 `~/.cc-switch/cc-switch.db`
     pub fn init() -> Result<Self, AppError> {
-        let db_path = get_app_config_dir().join("cc-switch.db");
+        Self::open_file(&get_app_config_dir().join("cc-switch.db"))
+    }
 
+    /// 打开（或创建）指定路径的数据库文件并完成建表与迁移
+    ///
+    /// 文件数据库使用 WAL 日志模式，会在同目录生成 `-wal` / `-shm` 附属文件，
+    /// 最近的写入可能只存在于 `-wal` 中：备份与目录迁移必须通过 SQLite 在线备份
+    /// （[`Database::snapshot_to`]）生成快照，或连同附属文件一起复制，不能只复制主库文件。
+    pub(crate) fn open_file(db_path: &Path) -> Result<Self, AppError> {
         // 确保父目录存在
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
         }
 
-        let conn = Connection::open(&db_path).map_err(|e| AppError::Database(e.to_string()))?;
+        let conn = Connection::open(db_path).map_err(|e| AppError::Database(e.to_string()))?;
 
         // 启用外键约束
         conn.execute("PRAGMA foreign_keys = ON;", [])
             .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 新建的数据库在建表前启用增量回收，日志清理后可通过 incremental_vacuum 释放空间；
+        // 已有数据库需 VACUUM 后才会生效，见 Database::convert_to_incremental_auto_vacuum
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL;")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 代理写日志与界面读统计并发时，等待锁释放而不是立即返回 "database is locked"
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // WAL：读写互不阻塞。部分文件系统（如网络盘）不支持 WAL，此时保留原日志模式
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode = WAL;", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if !journal_mode.eq_ignore_ascii_case("wal") {
+            log::warn!("数据库未能切换到 WAL 模式，当前日志模式: {journal_mode}");
+        }
+
         let db = Self {
             conn: Mutex::new(conn),
+            spend_cache: Mutex::new(HashMap::new()),
         };
         db.create_tables()?;
         db.apply_schema_migrations()?;
+        db.convert_to_incremental_auto_vacuum();
         db.ensure_model_pricing_seeded()?;
 
         Ok(db)
@@ -113,6 +170,7 @@ impl Database {
 
         let db = Self {
             conn: Mutex::new(conn),
+            spend_cache: Mutex::new(HashMap::new()),
         };
         db.create_tables()?;
         db.ensure_model_pricing_seeded()?;
@@ -137,4 +195,77 @@ impl Database {
             .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(count == 0)
     }
+
+    /// 获取数据库统计信息（主要表的行数、文件大小、WAL 中待合并的页数）
+    pub fn stats(&self) -> Result<DatabaseStats, AppError> {
+        let (row_counts, page_size) = {
+            let conn = lock_conn!(self.conn);
+            let mut row_counts = Vec::with_capacity(STATS_TABLES.len());
+            for table in STATS_TABLES {
+                let count: i64 = conn
+                    .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                        row.get(0)
+                    })
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                row_counts.push(TableRowCount {
+                    table: table.to_string(),
+                    rows: count as u64,
+                });
+            }
+            let page_size: i64 = conn
+                .query_row("PRAGMA page_size", [], |row| row.get(0))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            (row_counts, page_size as u64)
+        };
+
+        let db_path = get_app_config_dir().join("cc-switch.db");
+        let file_size = |path: &Path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let wal_size = file_size(&db_path.with_file_name("cc-switch.db-wal"));
+
+        Ok(DatabaseStats {
+            row_counts,
+            file_size_bytes: file_size(&db_path),
+            wal_size_bytes: wal_size,
+            wal_pending_pages: wal_frame_count(wal_size, page_size),
+        })
+    }
+}
+
+/// 统计行数的表
+const STATS_TABLES: [&str; 6] = [
+    "providers",
+    "mcp_servers",
+    "prompts",
+    "skills",
+    "proxy_request_logs",
+    "stream_check_logs",
+];
+
+/// 单表行数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: u64,
+}
+
+/// 数据库统计信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    pub row_counts: Vec<TableRowCount>,
+    /// 主库文件大小（字节）
+    pub file_size_bytes: u64,
+    /// WAL 文件大小（字节）
+    pub wal_size_bytes: u64,
+    /// WAL 中尚未合并回主库的页数（按 WAL 文件大小估算，检查点后文件不会截断，可能偏大）
+    pub wal_pending_pages: u64,
+}
+
+/// 根据 WAL 文件大小计算帧数：32 字节文件头 + 每帧（24 字节帧头 + 一页）
+fn wal_frame_count(wal_size: u64, page_size: u64) -> u64 {
+    if page_size == 0 {
+        return 0;
+    }
+    wal_size.saturating_sub(32) / (page_size + 24)
 }
@@ -2,7 +2,7 @@
 //!
 //! 负责数据库表结构的创建和版本迁移。
 
-use super::{lock_conn, Database, SCHEMA_VERSION};
+use super::{lock_conn, Database, AUTO_VACUUM_INCREMENTAL, SCHEMA_VERSION};
 use crate::error::AppError;
 use rusqlite::Connection;
 
@@ -46,6 +46,7 @@ impl Database {
                 app_type TEXT NOT NULL,
                 url TEXT NOT NULL,
                 added_at INTEGER,
+                last_used INTEGER,
                 FOREIGN KEY (provider_id, app_type) REFERENCES providers(id, app_type) ON DELETE CASCADE
             )",
             [],
@@ -68,6 +69,7 @@ impl Database {
         conn.execute("CREATE TABLE IF NOT EXISTS prompts (
             id TEXT NOT NULL, app_type TEXT NOT NULL, name TEXT NOT NULL, content TEXT NOT NULL,
             description TEXT, enabled BOOLEAN NOT NULL DEFAULT 1, created_at INTEGER, updated_at INTEGER,
+            order_index INTEGER NOT NULL DEFAULT 0,
             PRIMARY KEY (id, app_type)
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -96,7 +98,8 @@ impl Database {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS skill_repos (
             owner TEXT NOT NULL, name TEXT NOT NULL, branch TEXT NOT NULL DEFAULT 'main',
-            enabled BOOLEAN NOT NULL DEFAULT 1, PRIMARY KEY (owner, name)
+            enabled BOOLEAN NOT NULL DEFAULT 1, host_kind TEXT NOT NULL DEFAULT 'github',
+            base_url TEXT, PRIMARY KEY (owner, name)
         )",
             [],
         )
@@ -127,9 +130,9 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-        // 9. Proxy Config 表（三行结构，app_type 主键）
+        // 9. Proxy Config 表（每应用一行，app_type 主键）
         conn.execute("CREATE TABLE IF NOT EXISTS proxy_config (
-            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini')),
+            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini','opencode')),
             proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
             listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
             enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
@@ -140,14 +143,17 @@ impl Database {
             circuit_min_requests INTEGER NOT NULL DEFAULT 10,
             default_cost_multiplier TEXT NOT NULL DEFAULT '1',
             pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            capture_bodies INTEGER NOT NULL DEFAULT 0, capture_max_bytes INTEGER NOT NULL DEFAULT 16384,
+            auto_port INTEGER NOT NULL DEFAULT 0,
             created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
-        // 初始化三行数据（每应用不同默认值）
+        // 初始化每应用一行数据（每应用不同默认值）
         //
         // 兼容旧数据库：
-        // - 老版本 proxy_config 是单例表（没有 app_type 列），此时不能执行三行 seed insert；
-        // - 旧表会在 apply_schema_migrations() 中迁移为三行结构后再插入。
+        // - 老版本 proxy_config 是单例表（没有 app_type 列），此时不能执行 seed insert；
+        // - 旧表会在 apply_schema_migrations() 中迁移为三行结构后再插入；
+        // - v9 之前的 CHECK 约束不含 opencode，INSERT OR IGNORE 会跳过该行，由 v8 -> v9 迁移补齐。
         if Self::has_column(conn, "proxy_config", "app_type")? {
             conn.execute(
                 "INSERT OR IGNORE INTO proxy_config (app_type, max_retries,
@@ -176,6 +182,7 @@ impl Database {
                 [],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
+            Self::seed_opencode_proxy_config(conn)?;
         }
 
         // 10. Provider Health 表
@@ -183,6 +190,7 @@ impl Database {
             provider_id TEXT NOT NULL, app_type TEXT NOT NULL, is_healthy INTEGER NOT NULL DEFAULT 1,
             consecutive_failures INTEGER NOT NULL DEFAULT 0, last_success_at TEXT, last_failure_at TEXT,
             last_error TEXT, updated_at TEXT NOT NULL,
+            forced_state TEXT, forced_until INTEGER,
             PRIMARY KEY (provider_id, app_type),
             FOREIGN KEY (provider_id, app_type) REFERENCES providers(id, app_type) ON DELETE CASCADE
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
@@ -198,7 +206,8 @@ impl Database {
             total_cost_usd TEXT NOT NULL DEFAULT '0', latency_ms INTEGER NOT NULL, first_token_ms INTEGER,
             duration_ms INTEGER, status_code INTEGER NOT NULL, error_message TEXT, session_id TEXT,
             provider_type TEXT, is_streaming INTEGER NOT NULL DEFAULT 0,
-            cost_multiplier TEXT NOT NULL DEFAULT '1.0', created_at INTEGER NOT NULL
+            cost_multiplier TEXT NOT NULL DEFAULT '1.0', created_at INTEGER NOT NULL,
+            attempts TEXT, replay_of TEXT
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
         conn.execute("CREATE INDEX IF NOT EXISTS idx_request_logs_provider ON proxy_request_logs(provider_id, app_type)", [])
@@ -221,6 +230,17 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 11.1 Proxy Request Bodies 表（失败请求的请求/响应体捕获，随请求日志一起清理）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS proxy_request_bodies (
+            request_id TEXT PRIMARY KEY, request_headers TEXT, request_body TEXT,
+            response_body TEXT, truncated INTEGER NOT NULL DEFAULT 0, created_at INTEGER NOT NULL,
+            endpoint TEXT
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
         // 12. Model Pricing 表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS model_pricing (
@@ -250,6 +270,27 @@ impl Database {
 
         // 注意：circuit_breaker_config 已合并到 proxy_config 表中
 
+        // 13.1 Circuit Breaker Events 表（熔断器状态转换历史）
+        Self::create_circuit_breaker_events_table(conn)?;
+
+        // 13.2 Config Snippets 表（每应用多个命名配置片段）
+        Self::create_config_snippets_table(conn)?;
+
+        // 13.3 Live Config History 表（写入 Live 配置前的历史版本）
+        Self::create_live_config_history_table(conn)?;
+
+        // 13.4 Endpoint Latency History 表（端点测速历史）
+        Self::create_endpoint_latency_history_table(conn)?;
+
+        // 13.5 MCP Profiles 表（MCP 服务器组合）
+        Self::create_mcp_profiles_table(conn)?;
+
+        // 13.6 Provider Audit Log 表（供应商配置变更记录）
+        Self::create_provider_audit_log_table(conn)?;
+
+        // 13.7 Providers Quarantine 表（无法解析的供应商行）
+        Self::create_providers_quarantine_table(conn)?;
+
         // 14. Proxy Live Backup 表 (Live 配置备份)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS proxy_live_backup (
@@ -333,6 +374,37 @@ impl Database {
         Self::apply_schema_migrations_on_conn(&conn)
     }
 
+    /// 将旧版本创建的数据库切换为 auto_vacuum=INCREMENTAL
+    ///
+    /// 切换需要一次完整 VACUUM 才生效，且 VACUUM 不能在迁移事务内执行，
+    /// 因此在启动时（代理启动前）迁移完成后单独执行。失败只记录日志，
+    /// 之后日志清理会跳过空间回收，下次启动再尝试。
+    pub(crate) fn convert_to_incremental_auto_vacuum(&self) {
+        let Ok(conn) = self.conn.lock() else {
+            return;
+        };
+        let mode = match conn.query_row("PRAGMA auto_vacuum", [], |row| row.get::<_, i64>(0)) {
+            Ok(mode) => mode,
+            Err(e) => {
+                log::warn!("读取 auto_vacuum 模式失败: {e}");
+                return;
+            }
+        };
+        if mode == AUTO_VACUUM_INCREMENTAL {
+            return;
+        }
+
+        log::info!("将数据库切换为 auto_vacuum=INCREMENTAL（执行一次 VACUUM）");
+        let started = std::time::Instant::now();
+        match conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;") {
+            Ok(()) => log::info!(
+                "auto_vacuum 切换完成，耗时 {}ms",
+                started.elapsed().as_millis()
+            ),
+            Err(e) => log::warn!("切换 auto_vacuum 并执行 VACUUM 失败: {e}"),
+        }
+    }
+
     /// 在指定连接上应用 Schema 迁移
     pub(crate) fn apply_schema_migrations_on_conn(conn: &Connection) -> Result<(), AppError> {
         conn.execute("SAVEPOINT schema_migration;", [])
@@ -383,6 +455,86 @@ impl Database {
                         Self::migrate_v5_to_v6(conn)?;
                         Self::set_user_version(conn, 6)?;
                     }
+                    6 => {
+                        log::info!("迁移数据库从 v6 到 v7（请求体捕获支持）");
+                        Self::migrate_v6_to_v7(conn)?;
+                        Self::set_user_version(conn, 7)?;
+                    }
+                    7 => {
+                        log::info!("迁移数据库从 v7 到 v8（熔断器事件历史）");
+                        Self::migrate_v7_to_v8(conn)?;
+                        Self::set_user_version(conn, 8)?;
+                    }
+                    8 => {
+                        log::info!("迁移数据库从 v8 到 v9（OpenCode 代理接管）");
+                        Self::migrate_v8_to_v9(conn)?;
+                        Self::set_user_version(conn, 9)?;
+                    }
+                    9 => {
+                        log::info!("迁移数据库从 v9 到 v10（请求尝试时间线）");
+                        Self::migrate_v9_to_v10(conn)?;
+                        Self::set_user_version(conn, 10)?;
+                    }
+                    10 => {
+                        log::info!("迁移数据库从 v10 到 v11（命名配置片段）");
+                        Self::migrate_v10_to_v11(conn)?;
+                        Self::set_user_version(conn, 11)?;
+                    }
+                    11 => {
+                        log::info!("迁移数据库从 v11 到 v12（Skill 仓库托管类型）");
+                        Self::migrate_v11_to_v12(conn)?;
+                        Self::set_user_version(conn, 12)?;
+                    }
+                    12 => {
+                        log::info!("迁移数据库从 v12 到 v13（Live 配置历史）");
+                        Self::migrate_v12_to_v13(conn)?;
+                        Self::set_user_version(conn, 13)?;
+                    }
+                    13 => {
+                        log::info!("迁移数据库从 v13 到 v14（熔断器手动状态）");
+                        Self::migrate_v13_to_v14(conn)?;
+                        Self::set_user_version(conn, 14)?;
+                    }
+                    14 => {
+                        log::info!("迁移数据库从 v14 到 v15（端点测速历史）");
+                        Self::migrate_v14_to_v15(conn)?;
+                        Self::set_user_version(conn, 15)?;
+                    }
+                    15 => {
+                        log::info!("迁移数据库从 v15 到 v16（MCP Profiles）");
+                        Self::migrate_v15_to_v16(conn)?;
+                        Self::set_user_version(conn, 16)?;
+                    }
+                    16 => {
+                        log::info!("迁移数据库从 v16 到 v17（提示词排序）");
+                        Self::migrate_v16_to_v17(conn)?;
+                        Self::set_user_version(conn, 17)?;
+                    }
+                    17 => {
+                        log::info!("迁移数据库从 v17 到 v18（供应商变更审计）");
+                        Self::migrate_v17_to_v18(conn)?;
+                        Self::set_user_version(conn, 18)?;
+                    }
+                    18 => {
+                        log::info!("迁移数据库从 v18 到 v19（代理请求重放）");
+                        Self::migrate_v18_to_v19(conn)?;
+                        Self::set_user_version(conn, 19)?;
+                    }
+                    19 => {
+                        log::info!("迁移数据库从 v19 到 v20（自定义端点最后使用时间）");
+                        Self::migrate_v19_to_v20(conn)?;
+                        Self::set_user_version(conn, 20)?;
+                    }
+                    20 => {
+                        log::info!("迁移数据库从 v20 到 v21（供应商隔离表）");
+                        Self::migrate_v20_to_v21(conn)?;
+                        Self::set_user_version(conn, 21)?;
+                    }
+                    21 => {
+                        log::info!("迁移数据库从 v21 到 v22（代理自动换端口）");
+                        Self::migrate_v21_to_v22(conn)?;
+                        Self::set_user_version(conn, 22)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -451,6 +603,7 @@ impl Database {
         Self::add_column_if_missing(conn, "prompts", "enabled", "BOOLEAN NOT NULL DEFAULT 1")?;
         Self::add_column_if_missing(conn, "prompts", "created_at", "INTEGER")?;
         Self::add_column_if_missing(conn, "prompts", "updated_at", "INTEGER")?;
+        Self::add_column_if_missing(conn, "prompts", "order_index", "INTEGER NOT NULL DEFAULT 0")?;
 
         // skills 表
         Self::add_column_if_missing(conn, "skills", "installed_at", "INTEGER NOT NULL DEFAULT 0")?;
@@ -711,6 +864,7 @@ impl Database {
             circuit_min_requests INTEGER NOT NULL DEFAULT 10,
             default_cost_multiplier TEXT NOT NULL DEFAULT '1',
             pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            capture_bodies INTEGER NOT NULL DEFAULT 0, capture_max_bytes INTEGER NOT NULL DEFAULT 16384,
             created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )", [])?;
 
@@ -960,6 +1114,413 @@ impl Database {
         Ok(())
     }
 
+    /// v6 -> v7 迁移：新增请求体捕获配置与 proxy_request_bodies 表
+    fn migrate_v6_to_v7(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "capture_bodies",
+                "INTEGER NOT NULL DEFAULT 0",
+            )?;
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "capture_max_bytes",
+                "INTEGER NOT NULL DEFAULT 16384",
+            )?;
+        }
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS proxy_request_bodies (
+                request_id TEXT PRIMARY KEY,
+                request_headers TEXT,
+                request_body TEXT,
+                response_body TEXT,
+                truncated INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 proxy_request_bodies 表失败: {e}")))?;
+
+        log::info!("v6 -> v7 迁移完成：已添加请求体捕获支持");
+        Ok(())
+    }
+
+    /// v7 -> v8 迁移：新增 circuit_breaker_events 表
+    fn migrate_v7_to_v8(conn: &Connection) -> Result<(), AppError> {
+        Self::create_circuit_breaker_events_table(conn)?;
+        log::info!("v7 -> v8 迁移完成：已添加熔断器事件历史表");
+        Ok(())
+    }
+
+    /// v8 -> v9 迁移：proxy_config 的 CHECK 约束加入 opencode，并插入 OpenCode 默认配置
+    fn migrate_v8_to_v9(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "proxy_config")?
+            || !Self::has_column(conn, "proxy_config", "app_type")?
+        {
+            // 新安装或仍为单例结构：由 create_tables / 旧迁移负责建表
+            return Ok(());
+        }
+
+        let table_sql: String = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'proxy_config'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(format!("读取 proxy_config 表结构失败: {e}")))?;
+
+        // SQLite 不支持修改 CHECK 约束，只能重建表
+        if !table_sql.contains("'opencode'") {
+            let columns = "app_type, proxy_enabled, listen_address, listen_port, enable_logging,
+                enabled, auto_failover_enabled, max_retries, streaming_first_byte_timeout,
+                streaming_idle_timeout, non_streaming_timeout, circuit_failure_threshold,
+                circuit_success_threshold, circuit_timeout_seconds, circuit_error_rate_threshold,
+                circuit_min_requests, default_cost_multiplier, pricing_model_source,
+                capture_bodies, capture_max_bytes, created_at, updated_at";
+
+            conn.execute("DROP TABLE IF EXISTS proxy_config_new", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            conn.execute("CREATE TABLE proxy_config_new (
+                app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini','opencode')),
+                proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
+                listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
+                enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
+                max_retries INTEGER NOT NULL DEFAULT 3, streaming_first_byte_timeout INTEGER NOT NULL DEFAULT 60,
+                streaming_idle_timeout INTEGER NOT NULL DEFAULT 120, non_streaming_timeout INTEGER NOT NULL DEFAULT 600,
+                circuit_failure_threshold INTEGER NOT NULL DEFAULT 4, circuit_success_threshold INTEGER NOT NULL DEFAULT 2,
+                circuit_timeout_seconds INTEGER NOT NULL DEFAULT 60, circuit_error_rate_threshold REAL NOT NULL DEFAULT 0.6,
+                circuit_min_requests INTEGER NOT NULL DEFAULT 10,
+                default_cost_multiplier TEXT NOT NULL DEFAULT '1',
+                pricing_model_source TEXT NOT NULL DEFAULT 'response',
+                capture_bodies INTEGER NOT NULL DEFAULT 0, capture_max_bytes INTEGER NOT NULL DEFAULT 16384,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )", []).map_err(|e| AppError::Database(format!("创建 proxy_config_new 失败: {e}")))?;
+            conn.execute(
+                &format!(
+                    "INSERT INTO proxy_config_new ({columns}) SELECT {columns} FROM proxy_config"
+                ),
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("复制 proxy_config 数据失败: {e}")))?;
+            conn.execute("DROP TABLE proxy_config", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            conn.execute("ALTER TABLE proxy_config_new RENAME TO proxy_config", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        Self::seed_opencode_proxy_config(conn)?;
+
+        log::info!("v8 -> v9 迁移完成：proxy_config 已支持 OpenCode");
+        Ok(())
+    }
+
+    /// v9 -> v10 迁移：proxy_request_logs 增加 attempts 列（重试/故障转移时间线 JSON）
+    fn migrate_v9_to_v10(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_request_logs")? {
+            Self::add_column_if_missing(conn, "proxy_request_logs", "attempts", "TEXT")?;
+        }
+
+        log::info!("v9 -> v10 迁移完成：proxy_request_logs 已添加 attempts 列");
+        Ok(())
+    }
+
+    /// v10 -> v11 迁移：新增命名配置片段表
+    fn migrate_v10_to_v11(conn: &Connection) -> Result<(), AppError> {
+        Self::create_config_snippets_table(conn)?;
+        log::info!("v10 -> v11 迁移完成：已添加 config_snippets 表");
+        Ok(())
+    }
+
+    /// v11 -> v12 迁移：skill_repos 增加托管类型与地址覆盖（已有仓库均为 GitHub）
+    fn migrate_v11_to_v12(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "skill_repos")? {
+            Self::add_column_if_missing(
+                conn,
+                "skill_repos",
+                "host_kind",
+                "TEXT NOT NULL DEFAULT 'github'",
+            )?;
+            Self::add_column_if_missing(conn, "skill_repos", "base_url", "TEXT")?;
+        }
+
+        log::info!("v11 -> v12 迁移完成：skill_repos 已添加 host_kind / base_url 列");
+        Ok(())
+    }
+
+    /// v12 -> v13 迁移：新增 Live 配置历史表
+    fn migrate_v12_to_v13(conn: &Connection) -> Result<(), AppError> {
+        Self::create_live_config_history_table(conn)?;
+        log::info!("v12 -> v13 迁移完成：已添加 live_config_history 表");
+        Ok(())
+    }
+
+    /// v13 -> v14 迁移：provider_health 增加手动熔断状态
+    fn migrate_v13_to_v14(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "provider_health")? {
+            Self::add_column_if_missing(conn, "provider_health", "forced_state", "TEXT")?;
+            Self::add_column_if_missing(conn, "provider_health", "forced_until", "INTEGER")?;
+        }
+        log::info!("v13 -> v14 迁移完成：provider_health 已添加 forced_state / forced_until 列");
+        Ok(())
+    }
+
+    /// v14 -> v15 迁移：添加端点测速历史表
+    fn migrate_v14_to_v15(conn: &Connection) -> Result<(), AppError> {
+        Self::create_endpoint_latency_history_table(conn)?;
+        log::info!("v14 -> v15 迁移完成：已添加 endpoint_latency_history 表");
+        Ok(())
+    }
+
+    /// v15 -> v16 迁移：添加 MCP Profiles 表
+    fn migrate_v15_to_v16(conn: &Connection) -> Result<(), AppError> {
+        Self::create_mcp_profiles_table(conn)?;
+        log::info!("v15 -> v16 迁移完成：已添加 mcp_profiles 表");
+        Ok(())
+    }
+
+    /// v16 -> v17 迁移：提示词支持多选启用与排序
+    ///
+    /// 添加 order_index 列，并按原有的创建时间顺序为每个应用的提示词编号。
+    fn migrate_v16_to_v17(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(conn, "prompts", "order_index", "INTEGER NOT NULL DEFAULT 0")?;
+        conn.execute(
+            "UPDATE prompts SET order_index = (
+                SELECT COUNT(*) FROM prompts AS p
+                WHERE p.app_type = prompts.app_type
+                  AND (COALESCE(p.created_at, 0) < COALESCE(prompts.created_at, 0)
+                       OR (COALESCE(p.created_at, 0) = COALESCE(prompts.created_at, 0)
+                           AND p.id < prompts.id))
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("回填提示词排序失败: {e}")))?;
+        log::info!("v16 -> v17 迁移完成：prompts 已添加 order_index 列");
+        Ok(())
+    }
+
+    /// v17 -> v18 迁移：添加供应商变更审计表
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_audit_log_table(conn)?;
+        log::info!("v17 -> v18 迁移完成：已添加 provider_audit_log 表");
+        Ok(())
+    }
+
+    /// v18 -> v19 迁移：请求日志增加 replay_of 列（重放来源），请求体捕获增加 endpoint 列
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_request_logs")? {
+            Self::add_column_if_missing(conn, "proxy_request_logs", "replay_of", "TEXT")?;
+        }
+        if Self::table_exists(conn, "proxy_request_bodies")? {
+            Self::add_column_if_missing(conn, "proxy_request_bodies", "endpoint", "TEXT")?;
+        }
+
+        log::info!("v18 -> v19 迁移完成：已添加请求重放支持");
+        Ok(())
+    }
+
+    /// v19 -> v20 迁移：自定义端点增加 last_used 列（此前最后使用时间未持久化）
+    fn migrate_v19_to_v20(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "provider_endpoints")? {
+            Self::add_column_if_missing(conn, "provider_endpoints", "last_used", "INTEGER")?;
+        }
+
+        log::info!("v19 -> v20 迁移完成：已添加端点最后使用时间");
+        Ok(())
+    }
+
+    /// v20 -> v21 迁移：添加供应商隔离表
+    fn migrate_v20_to_v21(conn: &Connection) -> Result<(), AppError> {
+        Self::create_providers_quarantine_table(conn)?;
+        log::info!("v20 -> v21 迁移完成：已添加 providers_quarantine 表");
+        Ok(())
+    }
+
+    /// v21 -> v22 迁移：proxy_config 增加 auto_port 列（端口被占用时自动换端口）
+    fn migrate_v21_to_v22(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "auto_port",
+                "INTEGER NOT NULL DEFAULT 0",
+            )?;
+        }
+
+        log::info!("v21 -> v22 迁移完成：已添加代理自动换端口设置");
+        Ok(())
+    }
+
+    /// 插入 OpenCode 的默认代理配置（与 Codex 默认值一致）
+    pub(crate) fn seed_opencode_proxy_config(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT OR IGNORE INTO proxy_config (app_type, max_retries,
+            streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
+            circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
+            circuit_error_rate_threshold, circuit_min_requests)
+            VALUES ('opencode', 3, 60, 120, 600, 4, 2, 60, 0.6, 10)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    fn create_config_snippets_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS config_snippets (
+                app_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (app_type, name)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    fn create_live_config_history_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS live_config_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                content TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 live_config_history 表失败: {e}")))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_live_config_history_app
+             ON live_config_history(app_type, id DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    fn create_endpoint_latency_history_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS endpoint_latency_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                provider_id TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                url TEXT NOT NULL,
+                latency_ms INTEGER,
+                status INTEGER,
+                tested_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 endpoint_latency_history 表失败: {e}")))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_endpoint_latency_history_endpoint
+             ON endpoint_latency_history(app_type, provider_id, url, id DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    fn create_mcp_profiles_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_profiles (
+                name TEXT PRIMARY KEY,
+                servers TEXT NOT NULL DEFAULT '{}',
+                pinned TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 mcp_profiles 表失败: {e}")))?;
+        Ok(())
+    }
+
+    fn create_provider_audit_log_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                source TEXT NOT NULL,
+                diff TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 provider_audit_log 表失败: {e}")))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_audit_log_provider
+             ON provider_audit_log(app_type, provider_id, created_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_audit_log_created_at
+             ON provider_audit_log(created_at)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    fn create_providers_quarantine_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS providers_quarantine (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                settings_config TEXT,
+                meta TEXT,
+                website_url TEXT,
+                notes TEXT,
+                reason TEXT NOT NULL,
+                quarantined_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 providers_quarantine 表失败: {e}")))?;
+        Ok(())
+    }
+
+    fn create_circuit_breaker_events_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS circuit_breaker_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                from_state TEXT NOT NULL,
+                to_state TEXT NOT NULL,
+                consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                failed_requests INTEGER NOT NULL DEFAULT 0,
+                total_requests INTEGER NOT NULL DEFAULT 0,
+                error_message TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 circuit_breaker_events 表失败: {e}")))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_circuit_breaker_events_provider
+             ON circuit_breaker_events(app_type, provider_id, created_at DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
@@ -0,0 +1,912 @@
+//! 选择性导入
+//!
+//! 在覆盖式导入（`import_sql`）之外提供两阶段流程：
+//! 1. `preview_import` 将备份加载到临时数据库，与当前数据逐项比较，生成
+//!    新增 / 相同 / 冲突报告（冲突项附带字段级差异摘要）；
+//! 2. `apply_import` 按用户为每个冲突项选择的处理方式合并数据，
+//!    所有修改在同一个事务中完成，任何一步失败都不会改动数据库。
+//!
+//! 统一合并策略与逐项处理结果见 [`super::import_merge`]。
+//!
+//! 覆盖范围：供应商（按应用区分）、统一供应商、MCP 服务器、提示词、自定义端点。
+
+use super::import_merge::{
+    merge_provider_settings, ImportAction, ImportItemOutcome, MergeStrategy,
+};
+use super::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::ProviderService;
+use indexmap::IndexMap;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// 统一供应商在 settings 表中的存储键
+const UNIVERSAL_PROVIDERS_KEY: &str = "universal_providers";
+
+/// 差异摘要中单个值的最大字符数
+const DIFF_VALUE_MAX_CHARS: usize = 120;
+
+/// 导入项类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportItemKind {
+    Provider,
+    UniversalProvider,
+    McpServer,
+    Prompt,
+    CustomEndpoint,
+}
+
+/// 导入项与本地数据的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportItemStatus {
+    /// 本地不存在
+    New,
+    /// 本地存在且内容一致
+    Identical,
+    /// 本地存在但内容不同
+    Conflict,
+}
+
+/// 冲突项的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportResolution {
+    /// 保留本地版本（默认）
+    KeepLocal,
+    /// 使用导入版本覆盖本地
+    TakeImport,
+    /// 以新 ID 另存导入版本，本地版本保持不变
+    DuplicateRename,
+    /// 合并配置：以本地配置为底，导入版本覆盖同名字段（仅供应商与统一供应商，
+    /// 其余条目保留本地版本）
+    MergeFields,
+}
+
+/// 字段级差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    pub field: String,
+    pub local: String,
+    pub import: String,
+}
+
+/// 导入报告中的单个条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItem {
+    /// 条目唯一键（作为 resolutions 的键），如 `provider:claude:my-id`
+    pub key: String,
+    pub kind: ImportItemKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub status: ImportItemStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diff: Vec<FieldDiff>,
+}
+
+/// 导入预览报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub items: Vec<ImportItem>,
+    pub new_count: usize,
+    pub identical_count: usize,
+    pub conflict_count: usize,
+}
+
+/// 选择性导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportApplyResult {
+    pub added: usize,
+    pub replaced: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+    #[serde(default)]
+    pub merged: usize,
+    /// 逐项处理结果
+    #[serde(default)]
+    pub items: Vec<ImportItemOutcome>,
+    /// 导入前生成的数据库备份 ID
+    pub backup_id: String,
+}
+
+/// 基于行比较的表定义
+struct TableSpec {
+    kind: ImportItemKind,
+    prefix: &'static str,
+    table: &'static str,
+    key_columns: &'static [&'static str],
+    /// 不参与比较、覆盖时保留本地值的列
+    ignored_columns: &'static [&'static str],
+    /// 新增或另存时需要清零的状态列（如当前供应商、已启用提示词）
+    reset_columns: &'static [&'static str],
+}
+
+const PROVIDERS: TableSpec = TableSpec {
+    kind: ImportItemKind::Provider,
+    prefix: "provider",
+    table: "providers",
+    key_columns: &["app_type", "id"],
+    ignored_columns: &["is_current", "sort_index", "created_at"],
+    reset_columns: &["is_current"],
+};
+
+const MCP_SERVERS: TableSpec = TableSpec {
+    kind: ImportItemKind::McpServer,
+    prefix: "mcp",
+    table: "mcp_servers",
+    key_columns: &["id"],
+    ignored_columns: &[],
+    reset_columns: &[],
+};
+
+const PROMPTS: TableSpec = TableSpec {
+    kind: ImportItemKind::Prompt,
+    prefix: "prompt",
+    table: "prompts",
+    key_columns: &["app_type", "id"],
+    ignored_columns: &["enabled", "created_at", "updated_at"],
+    reset_columns: &["enabled"],
+};
+
+const ROW_TABLES: [&TableSpec; 3] = [&PROVIDERS, &MCP_SERVERS, &PROMPTS];
+
+pub(super) type RowMap = IndexMap<String, SqlValue>;
+
+enum Payload {
+    Row {
+        spec: &'static TableSpec,
+        import: RowMap,
+    },
+    Universal {
+        import: Value,
+    },
+    Endpoint {
+        app_type: String,
+        provider_id: String,
+        url: String,
+        added_at: Option<i64>,
+    },
+}
+
+struct PlannedItem {
+    item: ImportItem,
+    payload: Payload,
+}
+
+impl Database {
+    /// 预览导入：返回新增 / 相同 / 冲突条目报告，不修改数据库
+    pub fn preview_import(
+        &self,
+        source_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<ImportPreview, AppError> {
+        let (_temp_file, import_conn) = Self::load_sql_export(source_path, passphrase)?;
+        let planned = {
+            let conn = lock_conn!(self.conn);
+            plan_import(&conn, &import_conn)?
+        };
+
+        let mut preview = ImportPreview::default();
+        for planned_item in planned {
+            match planned_item.item.status {
+                ImportItemStatus::New => preview.new_count += 1,
+                ImportItemStatus::Identical => preview.identical_count += 1,
+                ImportItemStatus::Conflict => preview.conflict_count += 1,
+            }
+            preview.items.push(planned_item.item);
+        }
+        Ok(preview)
+    }
+
+    /// 按冲突处理方式选择性导入
+    ///
+    /// 未在 `resolutions` 中指定的冲突项默认保留本地版本。
+    /// 全部修改在单个事务中完成，失败时数据库保持不变。
+    pub fn apply_import(
+        &self,
+        source_path: &Path,
+        passphrase: Option<&str>,
+        resolutions: &HashMap<String, ImportResolution>,
+    ) -> Result<ImportApplyResult, AppError> {
+        self.apply_import_inner(
+            source_path,
+            passphrase,
+            resolutions,
+            ImportResolution::KeepLocal,
+        )
+    }
+
+    /// 用统一策略处理所有冲突项的选择性导入
+    pub fn apply_import_with_strategy(
+        &self,
+        source_path: &Path,
+        passphrase: Option<&str>,
+        strategy: MergeStrategy,
+    ) -> Result<ImportApplyResult, AppError> {
+        self.apply_import_inner(
+            source_path,
+            passphrase,
+            &HashMap::new(),
+            strategy.resolution(),
+        )
+    }
+
+    fn apply_import_inner(
+        &self,
+        source_path: &Path,
+        passphrase: Option<&str>,
+        resolutions: &HashMap<String, ImportResolution>,
+        default_resolution: ImportResolution,
+    ) -> Result<ImportApplyResult, AppError> {
+        let (_temp_file, import_conn) = Self::load_sql_export(source_path, passphrase)?;
+
+        // 导入前备份现有数据库
+        let backup_path = self.backup_database_file()?;
+
+        let mut result = ImportApplyResult {
+            backup_id: backup_path
+                .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+
+        let mut conn = lock_conn!(self.conn);
+        let planned = plan_import(&conn, &import_conn)?;
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(format!("开启导入事务失败: {e}")))?;
+
+        // 供应商的最终 ID（端点跟随供应商写入）；None 表示保留本地版本、跳过其端点
+        let mut provider_targets: HashMap<(String, String), Option<String>> = HashMap::new();
+        let mut universal: Option<serde_json::Map<String, Value>> = None;
+
+        for planned_item in &planned {
+            let item = &planned_item.item;
+            let resolution = match item.status {
+                ImportItemStatus::Identical => None,
+                ImportItemStatus::New => Some(ImportResolution::TakeImport),
+                ImportItemStatus::Conflict => Some(
+                    resolutions
+                        .get(&item.key)
+                        .copied()
+                        .unwrap_or(default_resolution),
+                ),
+            };
+            let mut new_id = None;
+
+            let action = match &planned_item.payload {
+                Payload::Row { spec, import } => {
+                    let (target_id, action) = match (item.status, resolution) {
+                        (ImportItemStatus::Identical, _) => {
+                            (Some(item.id.clone()), ImportAction::Unchanged)
+                        }
+                        (ImportItemStatus::New, _) => {
+                            insert_row(&tx, spec, &reset_row(spec, import.clone()))?;
+                            result.added += 1;
+                            (Some(item.id.clone()), ImportAction::Added)
+                        }
+                        (_, Some(ImportResolution::TakeImport)) => {
+                            update_row(&tx, spec, import)?;
+                            result.replaced += 1;
+                            (Some(item.id.clone()), ImportAction::Replaced)
+                        }
+                        (_, Some(ImportResolution::MergeFields))
+                            if spec.kind == ImportItemKind::Provider =>
+                        {
+                            merge_provider_settings(&tx, import)?;
+                            result.merged += 1;
+                            (Some(item.id.clone()), ImportAction::Merged)
+                        }
+                        (_, Some(ImportResolution::DuplicateRename)) => {
+                            let renamed_id = unique_row_id(&tx, spec, import)?;
+                            let mut row = reset_row(spec, import.clone());
+                            row.insert("id".to_string(), SqlValue::Text(renamed_id.clone()));
+                            if let Some(SqlValue::Text(name)) = row.get_mut("name") {
+                                name.push_str(" (imported)");
+                            }
+                            insert_row(&tx, spec, &row)?;
+                            result.renamed += 1;
+                            new_id = Some(renamed_id.clone());
+                            (Some(renamed_id), ImportAction::Renamed)
+                        }
+                        _ => {
+                            result.skipped += 1;
+                            (None, ImportAction::Skipped)
+                        }
+                    };
+
+                    if spec.kind == ImportItemKind::Provider {
+                        if let Some(app_type) = &item.app_type {
+                            provider_targets.insert((app_type.clone(), item.id.clone()), target_id);
+                        }
+                    }
+                    action
+                }
+                Payload::Universal { import } => {
+                    if item.status == ImportItemStatus::Identical {
+                        ImportAction::Unchanged
+                    } else {
+                        let map = match universal.take() {
+                            Some(map) => map,
+                            None => read_universal_providers(&tx)?,
+                        };
+                        let map = universal.insert(map);
+                        match resolution {
+                            Some(ImportResolution::TakeImport) => {
+                                map.insert(item.id.clone(), import.clone());
+                                if item.status == ImportItemStatus::New {
+                                    result.added += 1;
+                                    ImportAction::Added
+                                } else {
+                                    result.replaced += 1;
+                                    ImportAction::Replaced
+                                }
+                            }
+                            Some(ImportResolution::MergeFields) => {
+                                let mut merged = map.get(&item.id).cloned().unwrap_or(Value::Null);
+                                ProviderService::merge_json(&mut merged, import);
+                                map.insert(item.id.clone(), merged);
+                                result.merged += 1;
+                                ImportAction::Merged
+                            }
+                            Some(ImportResolution::DuplicateRename) => {
+                                let renamed_id = unique_universal_id(map, &item.id);
+                                let mut renamed = import.clone();
+                                if let Some(obj) = renamed.as_object_mut() {
+                                    obj.insert("id".to_string(), Value::String(renamed_id.clone()));
+                                    let name = format!("{} (imported)", item.name);
+                                    obj.insert("name".to_string(), Value::String(name));
+                                }
+                                map.insert(renamed_id.clone(), renamed);
+                                result.renamed += 1;
+                                new_id = Some(renamed_id);
+                                ImportAction::Renamed
+                            }
+                            _ => {
+                                result.skipped += 1;
+                                ImportAction::Skipped
+                            }
+                        }
+                    }
+                }
+                Payload::Endpoint {
+                    app_type,
+                    provider_id,
+                    url,
+                    added_at,
+                } => {
+                    if item.status == ImportItemStatus::Identical {
+                        result.items.push(ImportItemOutcome::new(
+                            item,
+                            ImportAction::Unchanged,
+                            None,
+                        ));
+                        continue;
+                    }
+                    let target = provider_targets
+                        .get(&(app_type.clone(), provider_id.clone()))
+                        .cloned()
+                        .unwrap_or_else(|| Some(provider_id.clone()));
+                    let Some(target_id) = target else {
+                        result.skipped += 1;
+                        result.items.push(ImportItemOutcome::new(
+                            item,
+                            ImportAction::Skipped,
+                            None,
+                        ));
+                        continue;
+                    };
+                    let exists: bool = tx
+                        .query_row(
+                            "SELECT EXISTS(SELECT 1 FROM provider_endpoints
+                             WHERE provider_id = ?1 AND app_type = ?2 AND url = ?3)",
+                            params![target_id, app_type, url],
+                            |row| row.get(0),
+                        )
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                    if !exists {
+                        tx.execute(
+                            "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
+                             VALUES (?1, ?2, ?3, ?4)",
+                            params![target_id, app_type, url, added_at],
+                        )
+                        .map_err(|e| AppError::Database(format!("导入自定义端点失败: {e}")))?;
+                        result.added += 1;
+                        ImportAction::Added
+                    } else {
+                        ImportAction::Unchanged
+                    }
+                }
+            };
+            result
+                .items
+                .push(ImportItemOutcome::new(item, action, new_id));
+        }
+
+        if let Some(map) = universal {
+            let json =
+                serde_json::to_string(&map).map_err(|source| AppError::JsonSerialize { source })?;
+            tx.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+                params![UNIVERSAL_PROVIDERS_KEY, json],
+            )
+            .map_err(|e| AppError::Database(format!("导入统一供应商失败: {e}")))?;
+        }
+
+        tx.commit()
+            .map_err(|e| AppError::Database(format!("提交导入事务失败: {e}")))?;
+
+        Ok(result)
+    }
+}
+
+/// 逐项比较导入库与本地库
+fn plan_import(local: &Connection, import: &Connection) -> Result<Vec<PlannedItem>, AppError> {
+    let mut planned = Vec::new();
+
+    for spec in ROW_TABLES {
+        let local_rows = read_rows(local, spec)?;
+        for (key, row) in read_rows(import, spec)? {
+            let (status, diff) = match local_rows.get(&key) {
+                None => (ImportItemStatus::New, Vec::new()),
+                Some(local_row) => {
+                    let diff = diff_rows(spec, local_row, &row);
+                    if diff.is_empty() {
+                        (ImportItemStatus::Identical, diff)
+                    } else {
+                        (ImportItemStatus::Conflict, diff)
+                    }
+                }
+            };
+            planned.push(PlannedItem {
+                item: ImportItem {
+                    key: format!("{}:{key}", spec.prefix),
+                    kind: spec.kind,
+                    app_type: row.get("app_type").map(display_value),
+                    id: row.get("id").map(display_value).unwrap_or_default(),
+                    name: row.get("name").map(display_value).unwrap_or_default(),
+                    status,
+                    diff,
+                },
+                payload: Payload::Row { spec, import: row },
+            });
+        }
+    }
+
+    let local_universal = read_universal_providers(local)?;
+    for (id, value) in read_universal_providers(import)? {
+        let (status, diff) = match local_universal.get(&id) {
+            None => (ImportItemStatus::New, Vec::new()),
+            Some(local_value) if local_value == &value => (ImportItemStatus::Identical, Vec::new()),
+            Some(local_value) => (ImportItemStatus::Conflict, diff_json(local_value, &value)),
+        };
+        let name = value
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or(&id)
+            .to_string();
+        planned.push(PlannedItem {
+            item: ImportItem {
+                key: format!("universal:{id}"),
+                kind: ImportItemKind::UniversalProvider,
+                app_type: None,
+                id,
+                name,
+                status,
+                diff,
+            },
+            payload: Payload::Universal { import: value },
+        });
+    }
+
+    let local_endpoints: HashSet<(String, String, String)> = read_endpoints(local)?
+        .into_iter()
+        .map(|(app_type, provider_id, url, _)| (app_type, provider_id, url))
+        .collect();
+    for (app_type, provider_id, url, added_at) in read_endpoints(import)? {
+        let status =
+            if local_endpoints.contains(&(app_type.clone(), provider_id.clone(), url.clone())) {
+                ImportItemStatus::Identical
+            } else {
+                ImportItemStatus::New
+            };
+        planned.push(PlannedItem {
+            item: ImportItem {
+                key: format!("endpoint:{app_type}:{provider_id}:{url}"),
+                kind: ImportItemKind::CustomEndpoint,
+                app_type: Some(app_type.clone()),
+                id: provider_id.clone(),
+                name: url.clone(),
+                status,
+                diff: Vec::new(),
+            },
+            payload: Payload::Endpoint {
+                app_type,
+                provider_id,
+                url,
+                added_at,
+            },
+        });
+    }
+
+    Ok(planned)
+}
+
+fn read_rows(conn: &Connection, spec: &TableSpec) -> Result<IndexMap<String, RowMap>, AppError> {
+    let sql = format!(
+        "SELECT * FROM {} ORDER BY {}",
+        spec.table,
+        spec.key_columns.join(", ")
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let mut result = IndexMap::new();
+    while let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
+        let mut map = RowMap::with_capacity(columns.len());
+        for (idx, name) in columns.iter().enumerate() {
+            let value: SqlValue = row
+                .get(idx)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            map.insert(name.clone(), value);
+        }
+        result.insert(row_key(spec, &map), map);
+    }
+    Ok(result)
+}
+
+fn row_key(spec: &TableSpec, row: &RowMap) -> String {
+    spec.key_columns
+        .iter()
+        .map(|col| row.get(*col).map(display_value).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn diff_rows(spec: &TableSpec, local: &RowMap, import: &RowMap) -> Vec<FieldDiff> {
+    import
+        .iter()
+        .filter(|(col, _)| !spec.ignored_columns.contains(&col.as_str()))
+        .filter_map(|(col, import_value)| {
+            let local_value = local.get(col).unwrap_or(&SqlValue::Null);
+            (local_value != import_value).then(|| FieldDiff {
+                field: col.clone(),
+                local: summarize(&display_value(local_value)),
+                import: summarize(&display_value(import_value)),
+            })
+        })
+        .collect()
+}
+
+fn diff_json(local: &Value, import: &Value) -> Vec<FieldDiff> {
+    let (Some(local_obj), Some(import_obj)) = (local.as_object(), import.as_object()) else {
+        return vec![FieldDiff {
+            field: String::new(),
+            local: summarize(&local.to_string()),
+            import: summarize(&import.to_string()),
+        }];
+    };
+
+    let mut fields: Vec<&String> = local_obj.keys().chain(import_obj.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let local_value = local_obj.get(field).unwrap_or(&Value::Null);
+            let import_value = import_obj.get(field).unwrap_or(&Value::Null);
+            (local_value != import_value).then(|| FieldDiff {
+                field: field.clone(),
+                local: summarize(&local_value.to_string()),
+                import: summarize(&import_value.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn display_value(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "null".to_string(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Real(f) => f.to_string(),
+        SqlValue::Text(t) => t.clone(),
+        SqlValue::Blob(b) => format!("<{} bytes>", b.len()),
+    }
+}
+
+fn summarize(text: &str) -> String {
+    if text.chars().count() <= DIFF_VALUE_MAX_CHARS {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(DIFF_VALUE_MAX_CHARS).collect();
+    format!("{truncated}…")
+}
+
+fn reset_row(spec: &TableSpec, mut row: RowMap) -> RowMap {
+    for col in spec.reset_columns {
+        if let Some(value) = row.get_mut(*col) {
+            *value = SqlValue::Integer(0);
+        }
+    }
+    row
+}
+
+fn insert_row(conn: &Connection, spec: &TableSpec, row: &RowMap) -> Result<(), AppError> {
+    let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{i}")).collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        spec.table,
+        columns.join(", "),
+        placeholders.join(", ")
+    );
+    conn.execute(&sql, params_from_iter(row.values()))
+        .map_err(|e| AppError::Database(format!("导入 {} 失败: {e}", spec.table)))?;
+    Ok(())
+}
+
+/// 用导入版本覆盖本地行（键列与忽略列保持本地值）
+fn update_row(conn: &Connection, spec: &TableSpec, row: &RowMap) -> Result<(), AppError> {
+    let updates: Vec<(&String, &SqlValue)> = row
+        .iter()
+        .filter(|(col, _)| {
+            !spec.key_columns.contains(&col.as_str())
+                && !spec.ignored_columns.contains(&col.as_str())
+        })
+        .collect();
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let set_clause: Vec<String> = updates
+        .iter()
+        .enumerate()
+        .map(|(i, (col, _))| format!("{col} = ?{}", i + 1))
+        .collect();
+    let where_clause: Vec<String> = spec
+        .key_columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| format!("{col} = ?{}", updates.len() + i + 1))
+        .collect();
+    let sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        spec.table,
+        set_clause.join(", "),
+        where_clause.join(" AND ")
+    );
+
+    let values = updates.iter().map(|(_, value)| *value).chain(
+        spec.key_columns
+            .iter()
+            .map(|col| row.get(*col).unwrap_or(&SqlValue::Null)),
+    );
+    conn.execute(&sql, params_from_iter(values))
+        .map_err(|e| AppError::Database(format!("覆盖 {} 失败: {e}", spec.table)))?;
+    Ok(())
+}
+
+/// 为另存的导入行生成不冲突的新 ID（`<id>-imported`、`<id>-imported-2`……）
+fn unique_row_id(conn: &Connection, spec: &TableSpec, row: &RowMap) -> Result<String, AppError> {
+    let base = row.get("id").map(display_value).unwrap_or_default();
+    let app_type = row.get("app_type").map(display_value);
+
+    for n in 1.. {
+        let candidate = if n == 1 {
+            format!("{base}-imported")
+        } else {
+            format!("{base}-imported-{n}")
+        };
+        let existing: Option<i64> = match &app_type {
+            Some(app_type) => conn
+                .query_row(
+                    &format!(
+                        "SELECT 1 FROM {} WHERE id = ?1 AND app_type = ?2",
+                        spec.table
+                    ),
+                    params![candidate, app_type],
+                    |r| r.get(0),
+                )
+                .optional(),
+            None => conn
+                .query_row(
+                    &format!("SELECT 1 FROM {} WHERE id = ?1", spec.table),
+                    params![candidate],
+                    |r| r.get(0),
+                )
+                .optional(),
+        }
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        if existing.is_none() {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("exhausted candidate ids")
+}
+
+fn unique_universal_id(map: &serde_json::Map<String, Value>, base: &str) -> String {
+    let mut candidate = format!("{base}-imported");
+    let mut n = 2;
+    while map.contains_key(&candidate) {
+        candidate = format!("{base}-imported-{n}");
+        n += 1;
+    }
+    candidate
+}
+
+fn read_universal_providers(conn: &Connection) -> Result<serde_json::Map<String, Value>, AppError> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [UNIVERSAL_PROVIDERS_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    match json {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| AppError::Database(format!("解析统一供应商数据失败: {e}"))),
+        None => Ok(serde_json::Map::new()),
+    }
+}
+
+type EndpointRow = (String, String, String, Option<i64>);
+
+fn read_endpoints(conn: &Connection) -> Result<Vec<EndpointRow>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_type, provider_id, url, added_at FROM provider_endpoints
+             ORDER BY app_type, provider_id, id",
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_provider(conn: &Connection, id: &str, name: &str, notes: Option<&str>) {
+        conn.execute(
+            "INSERT INTO providers (id, app_type, name, settings_config, notes, is_current)
+             VALUES (?1, 'claude', ?2, '{}', ?3, 1)",
+            params![id, name, notes],
+        )
+        .expect("insert provider");
+    }
+
+    fn setup() -> (Connection, Connection) {
+        let local = Connection::open_in_memory().expect("open local");
+        let import = Connection::open_in_memory().expect("open import");
+        Database::create_tables_on_conn(&local).expect("create local tables");
+        Database::create_tables_on_conn(&import).expect("create import tables");
+
+        insert_provider(&local, "same", "Same", None);
+        insert_provider(&import, "same", "Same", None);
+        insert_provider(&local, "conflict", "Local Name", Some("local"));
+        insert_provider(&import, "conflict", "Import Name", Some("import"));
+        insert_provider(&import, "fresh", "Fresh", None);
+        import
+            .execute(
+                "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
+                 VALUES ('conflict', 'claude', 'https://mirror.example.com', 1)",
+                [],
+            )
+            .expect("insert endpoint");
+        (local, import)
+    }
+
+    #[test]
+    fn test_plan_import_classifies_items() {
+        let (local, import) = setup();
+        let planned = plan_import(&local, &import).expect("plan import");
+
+        let status = |key: &str| {
+            planned
+                .iter()
+                .find(|p| p.item.key == key)
+                .map(|p| p.item.status)
+        };
+        assert_eq!(
+            status("provider:claude:same"),
+            Some(ImportItemStatus::Identical)
+        );
+        assert_eq!(status("provider:claude:fresh"), Some(ImportItemStatus::New));
+        assert_eq!(
+            status("provider:claude:conflict"),
+            Some(ImportItemStatus::Conflict)
+        );
+
+        let conflict = planned
+            .iter()
+            .find(|p| p.item.key == "provider:claude:conflict")
+            .expect("conflict item");
+        let fields: Vec<&str> = conflict
+            .item
+            .diff
+            .iter()
+            .map(|d| d.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["name", "notes"]);
+    }
+
+    #[test]
+    fn test_row_helpers_rename_and_update() {
+        let (local, import) = setup();
+        let import_rows = read_rows(&import, &PROVIDERS).expect("read import rows");
+        let row = &import_rows["claude:conflict"];
+
+        let new_id = unique_row_id(&local, &PROVIDERS, row).expect("unique id");
+        assert_eq!(new_id, "conflict-imported");
+
+        update_row(&local, &PROVIDERS, row).expect("update row");
+        let (name, is_current): (String, bool) = local
+            .query_row(
+                "SELECT name, is_current FROM providers WHERE id = 'conflict'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .expect("query updated row");
+        assert_eq!(name, "Import Name");
+        assert!(is_current, "ignored columns should keep local value");
+    }
+
+    #[test]
+    fn test_merge_provider_settings_keeps_local_fields() {
+        let (local, import) = setup();
+        local
+            .execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = 'conflict'",
+                [r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"local-key","KEEP":"1"}}"#],
+            )
+            .expect("set local settings");
+        import
+            .execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = 'conflict'",
+                [r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"import-key"},"model":"m"}"#],
+            )
+            .expect("set import settings");
+        let import_rows = read_rows(&import, &PROVIDERS).expect("read import rows");
+
+        merge_provider_settings(&local, &import_rows["claude:conflict"]).expect("merge");
+        let (settings, name): (String, String) = local
+            .query_row(
+                "SELECT settings_config, name FROM providers WHERE id = 'conflict'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .expect("query merged row");
+        let settings: Value = serde_json::from_str(&settings).expect("parse settings");
+        assert_eq!(settings["env"]["ANTHROPIC_AUTH_TOKEN"], "import-key");
+        assert_eq!(settings["env"]["KEEP"], "1");
+        assert_eq!(settings["model"], "m");
+        assert_eq!(name, "Local Name");
+        assert_eq!(
+            MergeStrategy::default().resolution(),
+            ImportResolution::KeepLocal
+        );
+    }
+}
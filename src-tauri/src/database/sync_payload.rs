@@ -0,0 +1,258 @@
+//! 云同步载荷
+//!
+//! 复用 SQL 导出/导入的序列化逻辑生成加密的同步载荷，并在拉取时按表替换本地数据。
+//! 同步配置本身（`sync_` 前缀的设置项）与设备本地数据表不会参与同步。
+
+use super::{backup_crypto, lock_conn, Database, ExportGuard};
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// 同步设置项的键名前缀（设备 ID、修订号、凭据等仅保存在本机）
+pub(crate) const SYNC_SETTING_PREFIX: &str = "sync_";
+
+/// 始终不参与同步的设备本地表
+const DEVICE_LOCAL_TABLES: &[&str] = &[
+    "proxy_live_backup",
+    "provider_health",
+    "circuit_breaker_events",
+    "live_config_history",
+    "provider_audit_log",
+    "endpoint_latency_history",
+    "providers_quarantine",
+];
+
+/// 仅在完整同步范围下才同步的运行数据表
+const RUNTIME_DATA_TABLES: &[&str] = &[
+    "proxy_request_logs",
+    "proxy_request_bodies",
+    "stream_check_logs",
+];
+
+/// 生成的同步载荷
+pub(crate) struct SyncPayload {
+    /// 加密后的载荷内容
+    pub data: Vec<u8>,
+    /// 载荷内容指纹（忽略导出时间等易变信息），用于检测本地是否有未同步修改
+    pub fingerprint: String,
+}
+
+impl Database {
+    /// 生成加密的同步载荷
+    ///
+    /// `include_runtime_data` 为 false 时不包含请求日志等运行数据。
+    pub(crate) fn export_sync_payload(
+        &self,
+        passphrase: &str,
+        include_runtime_data: bool,
+    ) -> Result<SyncPayload, AppError> {
+        let _guard = ExportGuard::acquire();
+        let snapshot = self.snapshot_to_memory()?;
+        Self::strip_unsynced_data(&snapshot, include_runtime_data)?;
+
+        let dump = Self::dump_sql(&snapshot)?;
+        let fingerprint = Self::payload_fingerprint(&dump);
+        let data = backup_crypto::encrypt(dump.as_bytes(), passphrase)?;
+
+        Ok(SyncPayload { data, fingerprint })
+    }
+
+    /// 计算当前本地数据的同步指纹
+    pub(crate) fn sync_fingerprint(&self, include_runtime_data: bool) -> Result<String, AppError> {
+        let snapshot = self.snapshot_to_memory()?;
+        Self::strip_unsynced_data(&snapshot, include_runtime_data)?;
+        let dump = Self::dump_sql(&snapshot)?;
+        Ok(Self::payload_fingerprint(&dump))
+    }
+
+    /// 应用远端同步载荷
+    ///
+    /// 载荷先完整解密并加载到临时库校验，随后在单个事务中逐表替换本地数据；
+    /// 同步设置项与不在同步范围内的表保留本地内容。
+    pub(crate) fn apply_sync_payload(
+        &self,
+        data: &[u8],
+        passphrase: &str,
+        include_runtime_data: bool,
+    ) -> Result<(), AppError> {
+        let mut source = NamedTempFile::new().map_err(|e| AppError::IoContext {
+            context: "创建临时同步文件失败".to_string(),
+            source: e,
+        })?;
+        source.write_all(data).map_err(|e| AppError::IoContext {
+            context: "写入临时同步文件失败".to_string(),
+            source: e,
+        })?;
+
+        let (incoming_file, incoming_conn) =
+            Self::load_sql_export(source.path(), Some(passphrase))?;
+        let synced_tables = Self::list_tables(&incoming_conn)?
+            .into_iter()
+            .filter(|t| Self::is_synced_table(t, include_runtime_data))
+            .collect::<Vec<_>>();
+        drop(incoming_conn);
+
+        // 拉取前备份现有数据库
+        self.backup_database_file()?;
+
+        let incoming_path = incoming_file.path().to_string_lossy().to_string();
+        let mut conn = lock_conn!(self.conn);
+        conn.execute(
+            "ATTACH DATABASE ?1 AS sync_incoming",
+            params![incoming_path],
+        )
+        .map_err(|e| AppError::Database(format!("挂载同步数据失败: {e}")))?;
+
+        let result = Self::replace_synced_tables(&mut conn, &synced_tables);
+
+        if let Err(e) = conn.execute("DETACH DATABASE sync_incoming", []) {
+            log::warn!("卸载同步数据失败: {e}");
+        }
+        result
+    }
+
+    fn replace_synced_tables(conn: &mut Connection, tables: &[String]) -> Result<(), AppError> {
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for table in tables {
+            let local_columns = Self::get_table_columns(&tx, table)?;
+            if local_columns.is_empty() {
+                continue;
+            }
+            let incoming_columns = Self::get_attached_table_columns(&tx, table)?;
+            let cols = local_columns
+                .iter()
+                .filter(|c| incoming_columns.contains(c))
+                .map(|c| format!("\"{c}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if table == "settings" {
+                tx.execute(
+                    "DELETE FROM main.settings WHERE substr(key, 1, length(?1)) != ?1",
+                    params![SYNC_SETTING_PREFIX],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+                tx.execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO main.settings ({cols}) SELECT {cols} FROM sync_incoming.settings
+                         WHERE substr(key, 1, length(?1)) != ?1"
+                    ),
+                    params![SYNC_SETTING_PREFIX],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+                continue;
+            }
+
+            tx.execute(&format!("DELETE FROM main.\"{table}\""), [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            tx.execute(
+                &format!(
+                    "INSERT INTO main.\"{table}\" ({cols}) SELECT {cols} FROM sync_incoming.\"{table}\""
+                ),
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("同步表 {table} 失败: {e}")))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 从快照中移除不参与同步的数据
+    fn strip_unsynced_data(conn: &Connection, include_runtime_data: bool) -> Result<(), AppError> {
+        conn.execute(
+            "DELETE FROM settings WHERE substr(key, 1, length(?1)) = ?1",
+            params![SYNC_SETTING_PREFIX],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for table in Self::list_tables(conn)? {
+            if !Self::is_synced_table(&table, include_runtime_data) {
+                conn.execute(&format!("DELETE FROM \"{table}\""), [])
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_synced_table(table: &str, include_runtime_data: bool) -> bool {
+        if DEVICE_LOCAL_TABLES.contains(&table) {
+            return false;
+        }
+        include_runtime_data || !RUNTIME_DATA_TABLES.contains(&table)
+    }
+
+    fn list_tables(conn: &Connection) -> Result<Vec<String>, AppError> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let iter = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut tables = Vec::new();
+        for table in iter {
+            tables.push(table.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(tables)
+    }
+
+    fn get_attached_table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, AppError> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA sync_incoming.table_info(\"{table}\")"))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let iter = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut columns = Vec::new();
+        for col in iter {
+            columns.push(col.map_err(|e| AppError::Database(e.to_string()))?);
+        }
+        Ok(columns)
+    }
+
+    /// 计算 SQL 导出内容的指纹（跳过注释行，避免导出时间影响结果）
+    fn payload_fingerprint(dump: &str) -> String {
+        let mut hasher = Sha256::new();
+        for line in dump.lines().filter(|l| !l.starts_with("--")) {
+            hasher.update(line.as_bytes());
+            hasher.update(b"\n");
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_comment_lines() {
+        let a = "-- 生成时间: 2024-01-01\nINSERT INTO t VALUES (1);\n";
+        let b = "-- 生成时间: 2025-06-30\nINSERT INTO t VALUES (1);\n";
+        let c = "-- 生成时间: 2025-06-30\nINSERT INTO t VALUES (2);\n";
+        assert_eq!(
+            Database::payload_fingerprint(a),
+            Database::payload_fingerprint(b)
+        );
+        assert_ne!(
+            Database::payload_fingerprint(b),
+            Database::payload_fingerprint(c)
+        );
+    }
+
+    #[test]
+    fn test_device_local_tables_never_synced() {
+        assert!(!Database::is_synced_table("proxy_live_backup", true));
+        assert!(!Database::is_synced_table("proxy_request_logs", false));
+        assert!(Database::is_synced_table("proxy_request_logs", true));
+        assert!(Database::is_synced_table("providers", false));
+    }
+}
@@ -150,6 +150,132 @@ fn normalize_default(default: &Option<String>) -> Option<String> {
         .map(|s| s.trim_matches('\'').trim_matches('"').to_string())
 }
 
+#[test]
+fn file_database_uses_wal_and_busy_timeout() -> Result<(), AppError> {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = Database::open_file(&dir.path().join("cc-switch.db"))?;
+
+    let conn = db.conn.lock().expect("lock conn");
+    let journal_mode: String = conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .expect("read journal_mode");
+    assert_eq!(journal_mode.to_lowercase(), "wal");
+    let busy_timeout: i64 = conn
+        .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+        .expect("read busy_timeout");
+    assert_eq!(busy_timeout, 5000);
+    drop(conn);
+
+    // 内存数据库不受影响
+    Database::memory()?;
+    Ok(())
+}
+
+#[test]
+fn open_file_converts_existing_database_to_incremental_auto_vacuum() -> Result<(), AppError> {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("cc-switch.db");
+    {
+        // 模拟旧版本创建的数据库：auto_vacuum=NONE
+        let conn = Connection::open(&path).expect("open legacy db");
+        conn.execute_batch("CREATE TABLE legacy (id INTEGER);")
+            .expect("create legacy table");
+        let mode: i64 = conn
+            .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+            .expect("read auto_vacuum");
+        assert_eq!(mode, 0);
+    }
+
+    let db = Database::open_file(&path)?;
+    let conn = db.conn.lock().expect("lock conn");
+    let mode: i64 = conn
+        .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+        .expect("read auto_vacuum");
+    assert_eq!(mode, crate::database::AUTO_VACUUM_INCREMENTAL);
+    Ok(())
+}
+
+#[test]
+fn stats_reports_row_counts_for_main_tables() -> Result<(), AppError> {
+    let db = Database::memory()?;
+    let provider = Provider::with_id("a".to_string(), "A".to_string(), json!({}), None);
+    db.save_provider("claude", &provider)?;
+
+    let stats = db.stats()?;
+    let rows: HashMap<_, _> = stats
+        .row_counts
+        .iter()
+        .map(|count| (count.table.as_str(), count.rows))
+        .collect();
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows["providers"], 1);
+    assert_eq!(rows["proxy_request_logs"], 0);
+
+    assert_eq!(wal_frame_count(0, 4096), 0);
+    assert_eq!(wal_frame_count(32 + 2 * (4096 + 24), 4096), 2);
+    Ok(())
+}
+
+#[test]
+fn provider_summaries_extract_flags_and_base_url_without_settings() -> Result<(), AppError> {
+    let db = Database::memory()?;
+    {
+        let conn = lock_conn!(db.conn);
+        let rows = [
+            (
+                "claude-a",
+                json!({"env": {"ANTHROPIC_BASE_URL": "https://relay.example/"}}).to_string(),
+                json!({"isPartner": true, "usage_script": {"enabled": true}}).to_string(),
+                1,
+            ),
+            (
+                "codex-b",
+                json!({"config": "model_provider = \"azure\"\n\n[model_providers.azure]\nbase_url = \"https://azure.example/v1\"\n"}).to_string(),
+                "{}".to_string(),
+                2,
+            ),
+            ("broken", "not json".to_string(), "not json".to_string(), 3),
+        ];
+        for (id, settings, meta, sort_index) in rows {
+            conn.execute(
+                "INSERT INTO providers (id, app_type, name, settings_config, meta, sort_index, is_current)
+                 VALUES (?1, 'claude', ?1, ?2, ?3, ?4, ?5)",
+                params![id, settings, meta, sort_index, id == "codex-b"],
+            )?;
+        }
+    }
+
+    let summaries = db.get_provider_summaries("claude")?;
+    let ids: Vec<_> = summaries.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, vec!["claude-a", "codex-b", "broken"]);
+
+    assert!(summaries[0].is_partner);
+    assert!(summaries[0].usage_script_enabled);
+    assert_eq!(
+        summaries[0].base_url.as_deref(),
+        Some("https://relay.example")
+    );
+
+    assert!(summaries[1].is_current);
+    assert!(!summaries[1].is_partner);
+    assert_eq!(
+        summaries[1].base_url.as_deref(),
+        Some("https://azure.example/v1")
+    );
+
+    assert!(summaries[2].base_url.is_none());
+    assert!(!summaries[2].usage_script_enabled);
+
+    assert_eq!(
+        db.get_provider_settings_config("claude-a", "claude")?,
+        Some(json!({"env": {"ANTHROPIC_BASE_URL": "https://relay.example/"}}))
+    );
+    assert!(db
+        .get_provider_settings_config("missing", "claude")?
+        .is_none());
+    Ok(())
+}
+
 #[test]
 fn schema_migration_sets_user_version_when_missing() {
     let conn = Connection::open_in_memory().expect("open memory db");
@@ -368,6 +494,424 @@ fn schema_migration_v5_adds_codex_accounts_table() {
     );
 }
 
+#[test]
+fn schema_migration_v6_adds_request_body_capture() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    // 先按当前结构建表，再模拟 v6 数据库（无 proxy_request_bodies 与捕获配置列）
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE IF EXISTS proxy_request_bodies", [])
+        .expect("drop proxy_request_bodies");
+    conn.execute("ALTER TABLE proxy_config DROP COLUMN capture_bodies", [])
+        .expect("drop capture_bodies");
+    conn.execute("ALTER TABLE proxy_config DROP COLUMN capture_max_bytes", [])
+        .expect("drop capture_max_bytes");
+
+    Database::set_user_version(&conn, 6).expect("set user_version=6");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "proxy_request_bodies").expect("check table"),
+        "proxy_request_bodies should exist after v6 -> v7 migration"
+    );
+    for column in ["capture_bodies", "capture_max_bytes"] {
+        assert!(
+            Database::has_column(&conn, "proxy_config", column).expect("check column"),
+            "proxy_config.{column} should exist after migration"
+        );
+    }
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v7_adds_circuit_breaker_events() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE IF EXISTS circuit_breaker_events", [])
+        .expect("drop circuit_breaker_events");
+
+    Database::set_user_version(&conn, 7).expect("set user_version=7");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "circuit_breaker_events").expect("check table"),
+        "circuit_breaker_events should exist after v7 -> v8 migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v8_allows_opencode_proxy_config() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    // 模拟 v8 数据库：proxy_config 的 CHECK 约束只允许三个应用
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute_batch(
+        r#"
+        DROP TABLE proxy_config;
+        CREATE TABLE proxy_config (
+            app_type TEXT PRIMARY KEY CHECK (app_type IN ('claude','codex','gemini')),
+            proxy_enabled INTEGER NOT NULL DEFAULT 0, listen_address TEXT NOT NULL DEFAULT '127.0.0.1',
+            listen_port INTEGER NOT NULL DEFAULT 15721, enable_logging INTEGER NOT NULL DEFAULT 1,
+            enabled INTEGER NOT NULL DEFAULT 0, auto_failover_enabled INTEGER NOT NULL DEFAULT 0,
+            max_retries INTEGER NOT NULL DEFAULT 3, streaming_first_byte_timeout INTEGER NOT NULL DEFAULT 60,
+            streaming_idle_timeout INTEGER NOT NULL DEFAULT 120, non_streaming_timeout INTEGER NOT NULL DEFAULT 600,
+            circuit_failure_threshold INTEGER NOT NULL DEFAULT 4, circuit_success_threshold INTEGER NOT NULL DEFAULT 2,
+            circuit_timeout_seconds INTEGER NOT NULL DEFAULT 60, circuit_error_rate_threshold REAL NOT NULL DEFAULT 0.6,
+            circuit_min_requests INTEGER NOT NULL DEFAULT 10,
+            default_cost_multiplier TEXT NOT NULL DEFAULT '1',
+            pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            capture_bodies INTEGER NOT NULL DEFAULT 0, capture_max_bytes INTEGER NOT NULL DEFAULT 16384,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        INSERT INTO proxy_config (app_type, enabled, max_retries) VALUES ('claude', 1, 7);
+        INSERT INTO proxy_config (app_type) VALUES ('codex');
+        INSERT INTO proxy_config (app_type) VALUES ('gemini');
+        "#,
+    )
+    .expect("seed v8 proxy_config");
+
+    Database::set_user_version(&conn, 8).expect("set user_version=8");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    let opencode_rows: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM proxy_config WHERE app_type = 'opencode'",
+            [],
+            |r| r.get(0),
+        )
+        .expect("count opencode rows");
+    assert_eq!(
+        opencode_rows, 1,
+        "opencode proxy_config row should be seeded"
+    );
+
+    let (enabled, max_retries): (i32, i32) = conn
+        .query_row(
+            "SELECT enabled, max_retries FROM proxy_config WHERE app_type = 'claude'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .expect("read claude row");
+    assert_eq!(
+        (enabled, max_retries),
+        (1, 7),
+        "existing rows should be preserved"
+    );
+
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v9_adds_request_log_attempts() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("ALTER TABLE proxy_request_logs DROP COLUMN attempts", [])
+        .expect("drop attempts");
+
+    Database::set_user_version(&conn, 9).expect("set user_version=9");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::has_column(&conn, "proxy_request_logs", "attempts").expect("check column"),
+        "proxy_request_logs.attempts should exist after v9 -> v10 migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v10_adds_config_snippets() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE IF EXISTS config_snippets", [])
+        .expect("drop config_snippets");
+
+    Database::set_user_version(&conn, 10).expect("set user_version=10");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "config_snippets").expect("check table"),
+        "config_snippets should exist after v10 -> v11 migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v11_marks_existing_skill_repos_as_github() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute_batch(
+        r#"
+        DROP TABLE skill_repos;
+        CREATE TABLE skill_repos (
+            owner TEXT NOT NULL, name TEXT NOT NULL, branch TEXT NOT NULL DEFAULT 'main',
+            enabled BOOLEAN NOT NULL DEFAULT 1, PRIMARY KEY (owner, name)
+        );
+        INSERT INTO skill_repos (owner, name, branch, enabled) VALUES ('acme', 'skills', 'main', 1);
+        "#,
+    )
+    .expect("seed v11 skill_repos");
+
+    Database::set_user_version(&conn, 11).expect("set user_version=11");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    let (host_kind, base_url): (String, Option<String>) = conn
+        .query_row(
+            "SELECT host_kind, base_url FROM skill_repos WHERE owner = 'acme'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .expect("read migrated repo");
+    assert_eq!(host_kind, "github");
+    assert_eq!(base_url, None);
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v12_adds_live_config_history_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE live_config_history", [])
+        .expect("drop live_config_history");
+
+    Database::set_user_version(&conn, 12).expect("set user_version=12");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "live_config_history").expect("check table"),
+        "live_config_history should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v13_adds_provider_health_override_columns() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute_batch(
+        r#"
+        DROP TABLE provider_health;
+        CREATE TABLE provider_health (
+            provider_id TEXT NOT NULL, app_type TEXT NOT NULL, is_healthy INTEGER NOT NULL DEFAULT 1,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0, last_success_at TEXT, last_failure_at TEXT,
+            last_error TEXT, updated_at TEXT NOT NULL,
+            PRIMARY KEY (provider_id, app_type)
+        );
+        "#,
+    )
+    .expect("seed legacy provider_health");
+
+    Database::set_user_version(&conn, 13).expect("set user_version=13");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    for column in ["forced_state", "forced_until"] {
+        assert!(
+            Database::has_column(&conn, "provider_health", column).expect("check column"),
+            "provider_health.{column} should exist after migration"
+        );
+    }
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v14_adds_endpoint_latency_history_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE endpoint_latency_history", [])
+        .expect("drop endpoint_latency_history");
+
+    Database::set_user_version(&conn, 14).expect("set user_version=14");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "endpoint_latency_history").expect("check table"),
+        "endpoint_latency_history should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v15_adds_mcp_profiles_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE mcp_profiles", [])
+        .expect("drop mcp_profiles");
+
+    Database::set_user_version(&conn, 15).expect("set user_version=15");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "mcp_profiles").expect("check table"),
+        "mcp_profiles should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v17_adds_provider_audit_log_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE provider_audit_log", [])
+        .expect("drop provider_audit_log");
+
+    Database::set_user_version(&conn, 17).expect("set user_version=17");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "provider_audit_log").expect("check table"),
+        "provider_audit_log should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v20_adds_providers_quarantine_table() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("DROP TABLE providers_quarantine", [])
+        .expect("drop providers_quarantine");
+
+    Database::set_user_version(&conn, 20).expect("set user_version=20");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::table_exists(&conn, "providers_quarantine").expect("check table"),
+        "providers_quarantine should exist after migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v18_adds_request_replay_columns() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("ALTER TABLE proxy_request_logs DROP COLUMN replay_of", [])
+        .expect("drop replay_of");
+    conn.execute("ALTER TABLE proxy_request_bodies DROP COLUMN endpoint", [])
+        .expect("drop endpoint");
+
+    Database::set_user_version(&conn, 18).expect("set user_version=18");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::has_column(&conn, "proxy_request_logs", "replay_of").expect("check column"),
+        "proxy_request_logs.replay_of should exist after v18 -> v19 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "proxy_request_bodies", "endpoint").expect("check column"),
+        "proxy_request_bodies.endpoint should exist after v18 -> v19 migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v19_adds_endpoint_last_used() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute("ALTER TABLE provider_endpoints DROP COLUMN last_used", [])
+        .expect("drop last_used");
+
+    Database::set_user_version(&conn, 19).expect("set user_version=19");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    assert!(
+        Database::has_column(&conn, "provider_endpoints", "last_used").expect("check column"),
+        "provider_endpoints.last_used should exist after v19 -> v20 migration"
+    );
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn schema_migration_v16_orders_prompts_by_creation() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute_batch(
+        r#"
+        ALTER TABLE prompts DROP COLUMN order_index;
+        INSERT INTO prompts (id, app_type, name, content, created_at) VALUES
+            ('late', 'claude', 'Late', 'b', 20),
+            ('early', 'claude', 'Early', 'a', 10),
+            ('other', 'codex', 'Other', 'c', 30);
+        "#,
+    )
+    .expect("seed legacy prompts");
+
+    Database::set_user_version(&conn, 16).expect("set user_version=16");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    let order = |id: &str| -> i64 {
+        conn.query_row(
+            "SELECT order_index FROM prompts WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .expect("read order_index")
+    };
+    assert_eq!(order("early"), 0);
+    assert_eq!(order("late"), 1);
+    assert_eq!(order("other"), 0);
+    assert_eq!(
+        Database::get_user_version(&conn).expect("version after migration"),
+        SCHEMA_VERSION
+    );
+}
+
 #[test]
 fn schema_create_tables_repairs_legacy_proxy_config_singleton_to_per_app() {
     let conn = Connection::open_in_memory().expect("open memory db");
@@ -520,11 +1064,11 @@ fn migration_from_v3_8_schema_v1_to_current_schema_v3() {
         "skills_ssot_migration_pending should be set after v2->v3 migration"
     );
 
-    // v3.9+ 新增：proxy_config 三行 seed 必须存在（否则 UI 会查不到默认值）
+    // v3.9+ 新增：proxy_config 每应用一行 seed 必须存在（否则 UI 会查不到默认值）
     let proxy_rows: i64 = conn
         .query_row("SELECT COUNT(*) FROM proxy_config", [], |r| r.get(0))
         .expect("count proxy_config rows");
-    assert_eq!(proxy_rows, 3);
+    assert_eq!(proxy_rows, 4);
 
     // model_pricing 应具备默认数据（迁移时会 seed）
     let pricing_rows: i64 = conn
@@ -578,6 +1122,7 @@ fn dry_run_validates_schema_compatibility() {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         },
     );
 
@@ -607,6 +1152,71 @@ fn dry_run_validates_schema_compatibility() {
     );
 }
 
+#[test]
+fn reimport_from_json_merges_missing_items_only() -> Result<(), AppError> {
+    let provider = |id: &str| Provider {
+        id: id.to_string(),
+        name: id.to_string(),
+        settings_config: json!({ "env": {} }),
+        website_url: None,
+        category: None,
+        created_at: None,
+        sort_index: None,
+        notes: None,
+        meta: None,
+        icon: None,
+        icon_color: None,
+        in_failover_queue: false,
+        icon_cached: None,
+    };
+    let mut providers = IndexMap::new();
+    providers.insert("kept".to_string(), provider("kept"));
+    providers.insert("lost".to_string(), provider("lost"));
+    let mut apps = HashMap::new();
+    apps.insert(
+        "claude".to_string(),
+        ProviderManager {
+            providers,
+            current: "lost".to_string(),
+        },
+    );
+    let mut config = MultiAppConfig {
+        version: 2,
+        apps,
+        mcp: Default::default(),
+        prompts: Default::default(),
+        skills: Default::default(),
+        common_config_snippets: Default::default(),
+        claude_common_config_snippet: None,
+    };
+    config.common_config_snippets.claude = Some("{}".to_string());
+
+    let db = Database::memory()?;
+    db.save_provider("claude", &provider("kept"))?;
+
+    let sections = [
+        JsonReimportSection::Providers,
+        JsonReimportSection::Settings,
+    ];
+    let preview = db.reimport_from_json(&config, &sections, true)?;
+    assert!(preview.dry_run);
+    assert_eq!(preview.sections[0].imported, 1);
+    assert_eq!(preview.sections[0].skipped, 1);
+    assert_eq!(preview.sections[1].imported, 1);
+    assert!(db.get_provider_by_id("lost", "claude")?.is_none());
+
+    let report = db.reimport_from_json(&config, &sections, false)?;
+    assert_eq!(report.sections[0].imported, 1);
+    assert_eq!(report.sections[0].failed, 0);
+    assert!(db.get_provider_by_id("lost", "claude")?.is_some());
+
+    let again = db.reimport_from_json(&config, &sections, false)?;
+    assert_eq!(again.sections[0].imported, 0);
+    assert_eq!(again.sections[0].skipped, 2);
+    assert_eq!(again.sections[1].skipped, 1);
+    Ok(())
+}
+
 #[test]
 fn schema_model_pricing_is_seeded_on_init() {
     let db = Database::memory().expect("create memory db");
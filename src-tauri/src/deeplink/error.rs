@@ -0,0 +1,78 @@
+//! Deep link parse errors
+//!
+//! 带机器可读原因码的解析错误，序列化后随 `deeplink-error` 事件发送给前端，
+//! 前端可按 `reason` 与参数/字段路径给出具体提示，而不是直接展示原始错误文本。
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::error::AppError;
+
+/// 深链接 URL 的最大长度（字节），超过时拒绝解析
+pub const MAX_DEEPLINK_URL_BYTES: usize = 64 * 1024;
+
+/// 批量导入链接中允许的最大供应商数量
+pub const MAX_DEEPLINK_BATCH_SIZE: usize = 20;
+
+/// 深链接解析错误
+///
+/// 序列化为 `{ "reason": "missing_param", "param": "name", "message": "..." }` 形式。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DeepLinkError {
+    /// URL 无法解析
+    #[error("{message}")]
+    InvalidUrl { message: String },
+    /// 不支持的资源类型
+    #[error("{message}")]
+    UnsupportedResource { resource: String, message: String },
+    /// 缺少必需参数
+    #[error("{message}")]
+    MissingParam { param: String, message: String },
+    /// 参数 Base64 解码失败
+    #[error("{message}")]
+    InvalidBase64 { param: String, message: String },
+    /// 链接或参数超过长度上限
+    #[error("{message}")]
+    PayloadTooLarge {
+        param: String,
+        size: usize,
+        limit: usize,
+        message: String,
+    },
+    /// 参数值不符合要求；`field` 为参数名或配置中的字段路径
+    #[error("{message}")]
+    SchemaMismatch { field: String, message: String },
+}
+
+impl DeepLinkError {
+    pub fn missing_param(param: &str, message: impl Into<String>) -> Self {
+        Self::MissingParam {
+            param: param.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn schema_mismatch(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::SchemaMismatch {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// `deeplink-error` 事件负载：原因码与字段之外附带原始 URL 与可读错误
+    pub fn event_payload(&self, url: &str) -> serde_json::Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}));
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("url".to_string(), serde_json::json!(url));
+            obj.insert("error".to_string(), serde_json::json!(self.to_string()));
+        }
+        payload
+    }
+}
+
+impl From<DeepLinkError> for AppError {
+    fn from(err: DeepLinkError) -> Self {
+        AppError::InvalidInput(err.to_string())
+    }
+}
@@ -6,15 +6,18 @@
 //! - MCP server configurations
 //! - Prompts
 //! - Skills
+//! - Multiple providers in one link (batch)
 //!
 //! See docs/ccswitch-deeplink-design.md for detailed design.
 
+mod error;
 mod mcp;
 mod parser;
 mod prompt;
 mod provider;
 mod skill;
 mod utils;
+mod validate;
 
 #[cfg(test)]
 mod tests;
@@ -22,11 +25,17 @@ mod tests;
 use serde::{Deserialize, Serialize};
 
 // Re-export public API
+pub use error::DeepLinkError;
 pub use mcp::import_mcp_from_deeplink;
 pub use parser::parse_deeplink_url;
 pub use prompt::import_prompt_from_deeplink;
-pub use provider::{import_provider_from_deeplink, parse_and_merge_config};
+pub use provider::{
+    import_provider_batch_from_deeplink, import_provider_from_deeplink,
+    import_provider_from_deeplink_with_strategy, parse_and_merge_config, BatchImportFailure,
+    BatchImportResult, ProviderImportOutcome,
+};
 pub use skill::import_skill_from_deeplink;
+pub use validate::{validate_deeplink_url, DeepLinkProviderPreview, DeepLinkValidation};
 
 /// Deep link import request model
 ///
@@ -37,7 +46,7 @@ pub use skill::import_skill_from_deeplink;
 pub struct DeepLinkImportRequest {
     /// Protocol version (e.g., "v1")
     pub version: String,
-    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill"
+    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill" | "batch"
     pub resource: String,
 
     // ============ Common fields ============
@@ -136,4 +145,9 @@ pub struct DeepLinkImportRequest {
     /// Auto query interval in minutes (0 to disable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_auto_interval: Option<u64>,
+
+    // ============ Batch-specific fields ============
+    /// 批量导入的供应商（resource = "batch"），每项都是一个 provider 请求
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<DeepLinkImportRequest>>,
 }
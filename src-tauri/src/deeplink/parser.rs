@@ -2,9 +2,9 @@
 //!
 //! Parses ccswitch:// URLs into DeepLinkImportRequest structures.
 
-use super::utils::validate_url;
+use super::error::{DeepLinkError, MAX_DEEPLINK_BATCH_SIZE, MAX_DEEPLINK_URL_BYTES};
+use super::utils::{decode_base64_param, validate_url};
 use super::DeepLinkImportRequest;
-use crate::error::AppError;
 use std::collections::HashMap;
 use url::Url;
 
@@ -12,38 +12,54 @@ use url::Url;
 ///
 /// Expected format:
 /// ccswitch://v1/import?resource={type}&...
-pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppError> {
+pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, DeepLinkError> {
+    if url_str.len() > MAX_DEEPLINK_URL_BYTES {
+        return Err(DeepLinkError::PayloadTooLarge {
+            param: "url".to_string(),
+            size: url_str.len(),
+            limit: MAX_DEEPLINK_URL_BYTES,
+            message: format!(
+                "Deep link is too large: {} bytes (limit {MAX_DEEPLINK_URL_BYTES})",
+                url_str.len()
+            ),
+        });
+    }
+
     // Parse URL
-    let url = Url::parse(url_str)
-        .map_err(|e| AppError::InvalidInput(format!("Invalid deep link URL: {e}")))?;
+    let url = Url::parse(url_str).map_err(|e| DeepLinkError::InvalidUrl {
+        message: format!("Invalid deep link URL: {e}"),
+    })?;
 
     // Validate scheme
     let scheme = url.scheme();
     if scheme != "ccswitch" {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid scheme: expected 'ccswitch', got '{scheme}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "scheme",
+            format!("Invalid scheme: expected 'ccswitch', got '{scheme}'"),
+        ));
     }
 
     // Extract version from host
     let version = url
         .host_str()
-        .ok_or_else(|| AppError::InvalidInput("Missing version in URL host".to_string()))?
+        .ok_or_else(|| DeepLinkError::schema_mismatch("version", "Missing version in URL host"))?
         .to_string();
 
     // Validate version
     if version != "v1" {
-        return Err(AppError::InvalidInput(format!(
-            "Unsupported protocol version: {version}"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "version",
+            format!("Unsupported protocol version: {version}"),
+        ));
     }
 
     // Extract path (should be "/import")
     let path = url.path();
     if path != "/import" {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid path: expected '/import', got '{path}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "path",
+            format!("Invalid path: expected '/import', got '{path}'"),
+        ));
     }
 
     // Parse query parameters
@@ -52,7 +68,7 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
     // Extract and validate resource type
     let resource = params
         .get("resource")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'resource' parameter".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("resource", "Missing 'resource' parameter"))?
         .clone();
 
     // Dispatch to appropriate parser based on resource type
@@ -61,9 +77,11 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
         "prompt" => parse_prompt_deeplink(&params, version, resource),
         "mcp" => parse_mcp_deeplink(&params, version, resource),
         "skill" => parse_skill_deeplink(&params, version, resource),
-        _ => Err(AppError::InvalidInput(format!(
-            "Unsupported resource type: {resource}"
-        ))),
+        "batch" => parse_batch_deeplink(&params, version, resource),
+        _ => Err(DeepLinkError::UnsupportedResource {
+            message: format!("Unsupported resource type: {resource}"),
+            resource,
+        }),
     }
 }
 
@@ -72,22 +90,23 @@ fn parse_provider_deeplink(
     params: &HashMap<String, String>,
     version: String,
     resource: String,
-) -> Result<DeepLinkImportRequest, AppError> {
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
     let app = params
         .get("app")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'app' parameter".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("app", "Missing 'app' parameter"))?
         .clone();
 
     // Validate app type
     if app != "claude" && app != "codex" && app != "gemini" {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid app type: must be 'claude', 'codex', or 'gemini', got '{app}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "app",
+            format!("Invalid app type: must be 'claude', 'codex', or 'gemini', got '{app}'"),
+        ));
     }
 
     let name = params
         .get("name")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'name' parameter".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("name", "Missing 'name' parameter"))?
         .clone();
 
     // Make these optional for config file auto-fill (v3.8+)
@@ -170,6 +189,7 @@ fn parse_provider_deeplink(
         usage_access_token,
         usage_user_id,
         usage_auto_interval,
+        providers: None,
     })
 }
 
@@ -178,28 +198,29 @@ fn parse_prompt_deeplink(
     params: &HashMap<String, String>,
     version: String,
     resource: String,
-) -> Result<DeepLinkImportRequest, AppError> {
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
     let app = params
         .get("app")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'app' parameter for prompt".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("app", "Missing 'app' parameter for prompt"))?
         .clone();
 
     // Validate app type
     if app != "claude" && app != "codex" && app != "gemini" {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid app type: must be 'claude', 'codex', or 'gemini', got '{app}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "app",
+            format!("Invalid app type: must be 'claude', 'codex', or 'gemini', got '{app}'"),
+        ));
     }
 
     let name = params
         .get("name")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'name' parameter for prompt".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("name", "Missing 'name' parameter for prompt"))?
         .clone();
 
     let content = params
         .get("content")
         .ok_or_else(|| {
-            AppError::InvalidInput("Missing 'content' parameter for prompt".to_string())
+            DeepLinkError::missing_param("content", "Missing 'content' parameter for prompt")
         })?
         .clone();
 
@@ -237,6 +258,7 @@ fn parse_prompt_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     })
 }
 
@@ -245,25 +267,30 @@ fn parse_mcp_deeplink(
     params: &HashMap<String, String>,
     version: String,
     resource: String,
-) -> Result<DeepLinkImportRequest, AppError> {
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
     let apps = params
         .get("apps")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'apps' parameter for MCP".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("apps", "Missing 'apps' parameter for MCP"))?
         .clone();
 
     // Validate apps format
     for app in apps.split(',') {
         let trimmed = app.trim();
         if trimmed != "claude" && trimmed != "codex" && trimmed != "gemini" {
-            return Err(AppError::InvalidInput(format!(
-                "Invalid app in 'apps': must be 'claude', 'codex', or 'gemini', got '{trimmed}'"
-            )));
+            return Err(DeepLinkError::schema_mismatch(
+                "apps",
+                format!(
+                    "Invalid app in 'apps': must be 'claude', 'codex', or 'gemini', got '{trimmed}'"
+                ),
+            ));
         }
     }
 
     let config = params
         .get("config")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'config' parameter for MCP".to_string()))?
+        .ok_or_else(|| {
+            DeepLinkError::missing_param("config", "Missing 'config' parameter for MCP")
+        })?
         .clone();
 
     let enabled = params.get("enabled").and_then(|v| v.parse::<bool>().ok());
@@ -299,6 +326,7 @@ fn parse_mcp_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     })
 }
 
@@ -307,17 +335,18 @@ fn parse_skill_deeplink(
     params: &HashMap<String, String>,
     version: String,
     resource: String,
-) -> Result<DeepLinkImportRequest, AppError> {
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
     let repo = params
         .get("repo")
-        .ok_or_else(|| AppError::InvalidInput("Missing 'repo' parameter for skill".to_string()))?
+        .ok_or_else(|| DeepLinkError::missing_param("repo", "Missing 'repo' parameter for skill"))?
         .clone();
 
     // Validate repo format (should be "owner/name")
     if !repo.contains('/') || repo.split('/').count() != 2 {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid repo format: expected 'owner/name', got '{repo}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            "repo",
+            format!("Invalid repo format: expected 'owner/name', got '{repo}'"),
+        ));
     }
 
     let directory = params.get("directory").cloned();
@@ -354,5 +383,145 @@ fn parse_skill_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     })
 }
+
+/// Parse batch deep link parameters
+///
+/// `providers` 为 Base64 编码的 JSON 数组，每项是与单个 provider 链接查询参数同名的对象，
+/// 例如 `[{"app":"claude","name":"A","endpoint":"https://...","apiKey":"sk-..."}]`。
+/// 每项按单个 provider 链接的规则解析，错误字段加上 `providers[i].` 前缀。
+fn parse_batch_deeplink(
+    params: &HashMap<String, String>,
+    version: String,
+    resource: String,
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
+    let raw = params.get("providers").ok_or_else(|| {
+        DeepLinkError::missing_param("providers", "Missing 'providers' parameter for batch")
+    })?;
+    let decoded = decode_base64_param("providers", raw)?;
+    let items: Vec<serde_json::Value> = serde_json::from_slice(&decoded).map_err(|e| {
+        DeepLinkError::schema_mismatch(
+            "providers",
+            format!("'providers' must be a JSON array of provider objects: {e}"),
+        )
+    })?;
+
+    if items.is_empty() {
+        return Err(DeepLinkError::schema_mismatch(
+            "providers",
+            "'providers' must contain at least one provider",
+        ));
+    }
+    if items.len() > MAX_DEEPLINK_BATCH_SIZE {
+        return Err(DeepLinkError::PayloadTooLarge {
+            param: "providers".to_string(),
+            size: items.len(),
+            limit: MAX_DEEPLINK_BATCH_SIZE,
+            message: format!(
+                "Too many providers in batch: {} (limit {MAX_DEEPLINK_BATCH_SIZE})",
+                items.len()
+            ),
+        });
+    }
+
+    let providers = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let prefix = format!("providers[{i}]");
+            let item_params = batch_item_params(item, &prefix)?;
+            parse_provider_deeplink(&item_params, version.clone(), "provider".to_string())
+                .map_err(|e| prefix_error_field(e, &prefix))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DeepLinkImportRequest {
+        version,
+        resource,
+        app: None,
+        name: None,
+        enabled: None,
+        homepage: None,
+        endpoint: None,
+        api_key: None,
+        icon: None,
+        model: None,
+        notes: None,
+        haiku_model: None,
+        sonnet_model: None,
+        opus_model: None,
+        content: None,
+        description: None,
+        apps: None,
+        repo: None,
+        directory: None,
+        branch: None,
+        config: None,
+        config_format: None,
+        config_url: None,
+        usage_enabled: None,
+        usage_script: None,
+        usage_api_key: None,
+        usage_base_url: None,
+        usage_access_token: None,
+        usage_user_id: None,
+        usage_auto_interval: None,
+        providers: Some(providers),
+    })
+}
+
+/// 将批量中的一项转换为查询参数形式（字符串、布尔与数字值；null 视为未提供）
+fn batch_item_params(
+    item: &serde_json::Value,
+    prefix: &str,
+) -> Result<HashMap<String, String>, DeepLinkError> {
+    let obj = item.as_object().ok_or_else(|| {
+        DeepLinkError::schema_mismatch(prefix, format!("'{prefix}' must be an object"))
+    })?;
+
+    let mut params = HashMap::new();
+    for (key, value) in obj {
+        let value = match value {
+            serde_json::Value::Null => continue,
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => {
+                return Err(DeepLinkError::schema_mismatch(
+                    format!("{prefix}.{key}"),
+                    format!("'{prefix}.{key}' must be a string, boolean or number"),
+                ))
+            }
+        };
+        params.insert(key.clone(), value);
+    }
+
+    if params.get("resource").is_some_and(|r| r != "provider") {
+        return Err(DeepLinkError::schema_mismatch(
+            format!("{prefix}.resource"),
+            "Batch deep links can only contain providers",
+        ));
+    }
+    Ok(params)
+}
+
+/// 为批量中某一项的错误加上 `providers[i].` 路径前缀
+pub(super) fn prefix_error_field(err: DeepLinkError, prefix: &str) -> DeepLinkError {
+    match err {
+        DeepLinkError::MissingParam { param, message } => DeepLinkError::MissingParam {
+            param: format!("{prefix}.{param}"),
+            message: format!("{prefix}: {message}"),
+        },
+        DeepLinkError::InvalidBase64 { param, message } => DeepLinkError::InvalidBase64 {
+            param: format!("{prefix}.{param}"),
+            message: format!("{prefix}: {message}"),
+        },
+        DeepLinkError::SchemaMismatch { field, message } => DeepLinkError::SchemaMismatch {
+            field: format!("{prefix}.{field}"),
+            message: format!("{prefix}: {message}"),
+        },
+        other => other,
+    }
+}
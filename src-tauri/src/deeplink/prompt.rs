@@ -67,6 +67,7 @@ pub fn import_prompt_from_deeplink(
         content,
         description: request.description,
         enabled: false, // Always start as disabled, will be enabled later if needed
+        order_index: 0,
         created_at: Some(timestamp),
         updated_at: Some(timestamp),
     };
@@ -74,7 +75,7 @@ pub fn import_prompt_from_deeplink(
     // Save using PromptService
     PromptService::upsert_prompt(state, app_type.clone(), &id, prompt)?;
 
-    // If enabled flag is set, enable this prompt (which will disable others)
+    // If enabled flag is set, enable this prompt (appended to the managed block)
     if should_enable {
         PromptService::enable_prompt(state, app_type, &id)?;
         log::info!("Successfully imported and enabled prompt '{name}' for {app_str}");
@@ -2,16 +2,30 @@
 //!
 //! Handles importing provider configurations via ccswitch:// URLs.
 
+use super::error::MAX_DEEPLINK_BATCH_SIZE;
 use super::utils::{decode_base64_param, infer_homepage_from_endpoint};
 use super::DeepLinkImportRequest;
+use crate::database::{ImportAction, MergeStrategy};
 use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta, UsageScript};
+use crate::services::provider::AuditSource;
 use crate::services::ProviderService;
 use crate::store::AppState;
 use crate::AppType;
+use indexmap::IndexMap;
+use serde::Serialize;
 use serde_json::json;
 use std::str::FromStr;
 
+/// 按合并策略导入供应商的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderImportOutcome {
+    /// 新建、覆盖、合并或跳过的供应商 ID
+    pub id: String,
+    pub action: ImportAction,
+}
+
 /// Import a provider from a deep link request
 ///
 /// This function:
@@ -24,6 +38,18 @@ pub fn import_provider_from_deeplink(
     state: &AppState,
     request: DeepLinkImportRequest,
 ) -> Result<String, AppError> {
+    import_provider_from_deeplink_with_strategy(state, request, None).map(|outcome| outcome.id)
+}
+
+/// Import a provider from a deep link request, resolving name collisions with `strategy`
+///
+/// 同一应用下名称相同（忽略大小写）的供应商视为已存在。`strategy` 为 None 时
+/// 始终新建（原有行为）。
+pub fn import_provider_from_deeplink_with_strategy(
+    state: &AppState,
+    request: DeepLinkImportRequest,
+    strategy: Option<MergeStrategy>,
+) -> Result<ProviderImportOutcome, AppError> {
     // Verify this is a provider request
     if request.resource != "provider" {
         return Err(AppError::InvalidInput(format!(
@@ -32,8 +58,227 @@ pub fn import_provider_from_deeplink(
         )));
     }
 
+    let prepared = prepare_provider(&request)?;
+    let app_type = prepared.app_type.clone();
+    let enabled = prepared.enabled;
+    let outcome = import_prepared(state, prepared, strategy)?;
+
+    // If enabled=true, set as current provider
+    if enabled && outcome.action != ImportAction::Skipped {
+        ProviderService::switch_with_source(
+            state,
+            app_type.clone(),
+            &outcome.id,
+            false,
+            AuditSource::Deeplink,
+        )?;
+        log::info!("Provider '{}' set as current for {app_type:?}", outcome.id);
+    }
+
+    Ok(outcome)
+}
+
+/// 导入失败（或导入后切换失败）的批量条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchImportFailure {
+    /// 条目在 `providers` 中的下标
+    pub index: usize,
+    pub name: String,
+    pub error: String,
+}
+
+/// 批量导入结果：成功的条目与失败的条目分别返回
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchImportResult {
+    pub providers: Vec<ProviderImportOutcome>,
+    pub failed: Vec<BatchImportFailure>,
+}
+
+/// Import every provider of a batch deep link request
+///
+/// 先合并配置并校验全部条目，任一失败则不写入；随后逐项按 `strategy` 导入，
+/// 单项写入失败不影响其余条目，失败项在结果的 `failed` 中返回。同一应用中有多个
+/// `enabled=true` 时，最后一个导入成功（且未被跳过）的成为当前供应商。
+pub fn import_provider_batch_from_deeplink(
+    state: &AppState,
+    request: DeepLinkImportRequest,
+    strategy: Option<MergeStrategy>,
+) -> Result<BatchImportResult, AppError> {
+    if request.resource != "batch" {
+        return Err(AppError::InvalidInput(format!(
+            "Expected batch resource, got '{}'",
+            request.resource
+        )));
+    }
+    let items = request.providers.unwrap_or_default();
+    if items.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Batch deep link contains no providers".to_string(),
+        ));
+    }
+    if items.len() > MAX_DEEPLINK_BATCH_SIZE {
+        return Err(AppError::InvalidInput(format!(
+            "Too many providers in batch: {} (limit {MAX_DEEPLINK_BATCH_SIZE})",
+            items.len()
+        )));
+    }
+
+    let mut prepared = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        if item.resource != "provider" {
+            return Err(AppError::InvalidInput(format!(
+                "providers[{i}]: expected provider resource, got '{}'",
+                item.resource
+            )));
+        }
+        let mut entry = prepare_provider(item)
+            .map_err(|e| AppError::InvalidInput(format!("providers[{i}]: {e}")))?;
+        // 同一批次内时间戳相同，追加序号避免 ID 冲突
+        entry.provider.id = format!("{}-{i}", entry.provider.id);
+        prepared.push(entry);
+    }
+
+    let mut result = BatchImportResult::default();
+    let mut current: IndexMap<String, (AppType, usize, String, String)> = IndexMap::new();
+    for (index, entry) in prepared.into_iter().enumerate() {
+        let app_type = entry.app_type.clone();
+        let name = entry.name.clone();
+        let enabled = entry.enabled;
+        match import_prepared(state, entry, strategy) {
+            Ok(outcome) => {
+                if enabled && outcome.action != ImportAction::Skipped {
+                    current.insert(
+                        app_type.as_str().to_string(),
+                        (app_type, index, name, outcome.id.clone()),
+                    );
+                }
+                result.providers.push(outcome);
+            }
+            Err(e) => {
+                log::warn!("Failed to import batch provider #{index} '{name}': {e}");
+                result.failed.push(BatchImportFailure {
+                    index,
+                    name,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    for (_, (app_type, index, name, provider_id)) in current {
+        match ProviderService::switch_with_source(
+            state,
+            app_type.clone(),
+            &provider_id,
+            false,
+            AuditSource::Deeplink,
+        ) {
+            Ok(_) => log::info!("Provider '{provider_id}' set as current for {app_type:?}"),
+            Err(e) => {
+                log::warn!("Failed to set '{provider_id}' as current for {app_type:?}: {e}");
+                result.failed.push(BatchImportFailure {
+                    index,
+                    name,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// 按 `strategy` 写入一个已准备好的供应商并添加额外端点（不切换当前供应商）
+///
+/// 同一应用下名称相同（忽略大小写）的供应商视为已存在；`strategy` 为 None 时始终新建。
+fn import_prepared(
+    state: &AppState,
+    prepared: PreparedProvider,
+    strategy: Option<MergeStrategy>,
+) -> Result<ProviderImportOutcome, AppError> {
+    let PreparedProvider {
+        app_type,
+        mut provider,
+        name,
+        extra_endpoints,
+        ..
+    } = prepared;
+
+    let existing = match strategy {
+        Some(_) => ProviderService::list(state, app_type.clone())?
+            .into_values()
+            .find(|p| p.name.trim().eq_ignore_ascii_case(name.trim())),
+        None => None,
+    };
+
+    let (provider_id, action) = match (strategy, existing) {
+        (Some(MergeStrategy::SkipExisting), Some(existing)) => {
+            log::info!("Provider '{}' already exists, skipping import", existing.id);
+            return Ok(ProviderImportOutcome {
+                id: existing.id,
+                action: ImportAction::Skipped,
+            });
+        }
+        (Some(MergeStrategy::Overwrite), Some(existing)) => {
+            provider.id = existing.id.clone();
+            provider.created_at = existing.created_at;
+            provider.sort_index = existing.sort_index;
+            provider.in_failover_queue = existing.in_failover_queue;
+            ProviderService::update(state, app_type.clone(), provider)?;
+            (existing.id, ImportAction::Replaced)
+        }
+        (Some(MergeStrategy::MergeFields), Some(mut existing)) => {
+            ProviderService::merge_json(&mut existing.settings_config, &provider.settings_config);
+            let id = existing.id.clone();
+            ProviderService::update(state, app_type.clone(), existing)?;
+            (id, ImportAction::Merged)
+        }
+        (Some(MergeStrategy::Rename), Some(_)) => {
+            provider.name = unique_provider_name(state, &app_type, &name)?;
+            let id = provider.id.clone();
+            ProviderService::add_with_source(
+                state,
+                app_type.clone(),
+                provider,
+                AuditSource::Deeplink,
+            )?;
+            (id, ImportAction::Renamed)
+        }
+        _ => {
+            let id = provider.id.clone();
+            ProviderService::add_with_source(
+                state,
+                app_type.clone(),
+                provider,
+                AuditSource::Deeplink,
+            )?;
+            (id, ImportAction::Added)
+        }
+    };
+
+    add_extra_endpoints(state, &app_type, &provider_id, &extra_endpoints);
+
+    Ok(ProviderImportOutcome {
+        id: provider_id,
+        action,
+    })
+}
+
+/// 合并配置、校验必填字段后构建的待导入供应商
+struct PreparedProvider {
+    app_type: AppType,
+    /// ID 已按 `名称-时间戳` 生成
+    provider: Provider,
+    name: String,
+    /// 主端点之外的其他端点，导入后添加为自定义端点
+    extra_endpoints: Vec<String>,
+    enabled: bool,
+}
+
+fn prepare_provider(request: &DeepLinkImportRequest) -> Result<PreparedProvider, AppError> {
     // Step 1: Merge config file if provided (v3.8+)
-    let mut merged_request = parse_and_merge_config(&request)?;
+    let mut merged_request = parse_and_merge_config(request)?;
 
     // Extract required fields (now as Option)
     let app_str = merged_request
@@ -107,33 +352,52 @@ pub fn import_provider_from_deeplink(
         .to_lowercase();
     provider.id = format!("{sanitized_name}-{timestamp}");
 
-    let provider_id = provider.id.clone();
-
-    // Use ProviderService to add the provider
-    ProviderService::add(state, app_type.clone(), provider)?;
+    Ok(PreparedProvider {
+        app_type,
+        provider,
+        name,
+        extra_endpoints: all_endpoints.into_iter().skip(1).collect(),
+        enabled: merged_request.enabled.unwrap_or(false),
+    })
+}
 
-    // Add extra endpoints as custom endpoints (skip first one as it's the primary)
-    for ep in all_endpoints.iter().skip(1) {
+/// Add extra endpoints as custom endpoints (the primary one is already in the config)
+fn add_extra_endpoints(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    endpoints: &[String],
+) {
+    for ep in endpoints {
         let normalized = ep.trim().trim_end_matches('/').to_string();
         if !normalized.is_empty() {
             if let Err(e) = ProviderService::add_custom_endpoint(
                 state,
                 app_type.clone(),
-                &provider_id,
+                provider_id,
                 normalized.clone(),
             ) {
                 log::warn!("Failed to add custom endpoint '{normalized}': {e}");
             }
         }
     }
+}
 
-    // If enabled=true, set as current provider
-    if merged_request.enabled.unwrap_or(false) {
-        ProviderService::switch(state, app_type.clone(), &provider_id)?;
-        log::info!("Provider '{provider_id}' set as current for {app_type:?}");
-    }
-
-    Ok(provider_id)
+/// 为重名的导入供应商生成不冲突的名称（`name (2)`、`name (3)`……）
+fn unique_provider_name(
+    state: &AppState,
+    app_type: &AppType,
+    name: &str,
+) -> Result<String, AppError> {
+    let existing: Vec<String> = ProviderService::list(state, app_type.clone())?
+        .into_values()
+        .map(|p| p.name.trim().to_lowercase())
+        .collect();
+    let base = name.trim();
+    Ok((2..)
+        .map(|n| format!("{base} ({n})"))
+        .find(|candidate| !existing.contains(&candidate.to_lowercase()))
+        .unwrap_or_else(|| base.to_string()))
 }
 
 /// Build a Provider structure from a deep link request
@@ -164,6 +428,7 @@ pub(crate) fn build_provider_from_request(
         icon: request.icon.clone(),
         icon_color: None,
         in_failover_queue: false,
+        icon_cached: None,
     };
 
     Ok(provider)
@@ -228,6 +493,7 @@ fn build_provider_meta(request: &DeepLinkImportRequest) -> Result<Option<Provide
         user_id: request.usage_user_id.clone(),
         template_type: None, // Deeplink providers don't specify template type (will use backward compatibility logic)
         auto_query_interval: request.usage_auto_interval,
+        allowed_hosts: None,
     };
 
     Ok(Some(ProviderMeta {
@@ -401,6 +667,18 @@ fn build_opencode_settings(request: &DeepLinkImportRequest) -> serde_json::Value
 pub fn parse_and_merge_config(
     request: &DeepLinkImportRequest,
 ) -> Result<DeepLinkImportRequest, AppError> {
+    // Batch: merge each provider's own config
+    if let Some(items) = &request.providers {
+        let mut merged = request.clone();
+        merged.providers = Some(
+            items
+                .iter()
+                .map(parse_and_merge_config)
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        return Ok(merged);
+    }
+
     // If no config provided, return original request
     if request.config.is_none() && request.config_url.is_none() {
         return Ok(request.clone());
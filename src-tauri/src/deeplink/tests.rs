@@ -1,10 +1,12 @@
 //! Deep link module tests
 
+use super::error::{DeepLinkError, MAX_DEEPLINK_BATCH_SIZE, MAX_DEEPLINK_URL_BYTES};
 use super::mcp::parse_mcp_apps;
 use super::parser::parse_deeplink_url;
 use super::prompt::import_prompt_from_deeplink;
 use super::provider::parse_and_merge_config;
 use super::utils::{infer_homepage_from_endpoint, validate_url};
+use super::validate::validate_deeplink_url;
 use super::DeepLinkImportRequest;
 use crate::AppType;
 use crate::{store::AppState, Database};
@@ -152,6 +154,7 @@ fn test_build_gemini_provider_with_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -205,6 +208,7 @@ fn test_build_gemini_provider_without_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -253,6 +257,7 @@ fn test_parse_and_merge_config_claude() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -303,6 +308,7 @@ fn test_parse_and_merge_config_url_override() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        providers: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -458,3 +464,160 @@ fn test_infer_homepage_from_endpoint_without_homepage() {
         Some("https://cubence.com".to_string())
     );
 }
+
+// =============================================================================
+// Error Reason & Validation Tests
+// =============================================================================
+
+#[test]
+fn test_parse_errors_carry_reason_codes() {
+    let err = parse_deeplink_url("ccswitch://v1/import?resource=theme").unwrap_err();
+    assert!(
+        matches!(err, DeepLinkError::UnsupportedResource { ref resource, .. } if resource == "theme")
+    );
+
+    let err = parse_deeplink_url("ccswitch://v1/import?resource=provider&app=claude").unwrap_err();
+    let payload = err.event_payload("ccswitch://v1/import");
+    assert_eq!(payload["reason"], "missing_param");
+    assert_eq!(payload["param"], "name");
+    assert_eq!(payload["url"], "ccswitch://v1/import");
+    assert!(payload["error"]
+        .as_str()
+        .unwrap()
+        .contains("Missing 'name' parameter"));
+
+    let err = parse_deeplink_url(
+        "ccswitch://v1/import?resource=provider&app=claude&name=T&endpoint=ftp%3A%2F%2Fx.com",
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, DeepLinkError::SchemaMismatch { ref field, .. } if field == "endpoint[0]")
+    );
+
+    let huge = format!(
+        "ccswitch://v1/import?resource=prompt&app=claude&name=T&content={}",
+        "A".repeat(MAX_DEEPLINK_URL_BYTES)
+    );
+    let err = parse_deeplink_url(&huge).unwrap_err();
+    assert!(matches!(err, DeepLinkError::PayloadTooLarge { ref param, .. } if param == "url"));
+}
+
+#[test]
+fn test_validate_deeplink_returns_preview_without_importing() {
+    let url = "ccswitch://v1/import?resource=provider&app=claude&name=Test&endpoint=https%3A%2F%2Fapi.example.com%2Chttps%3A%2F%2Fbackup.example.com&apiKey=sk-test&model=claude-x";
+
+    let validation = validate_deeplink_url(url).unwrap();
+    let provider = validation.provider.expect("provider preview");
+    assert_eq!(provider.name, "Test");
+    assert_eq!(provider.app, "claude");
+    assert_eq!(
+        provider.endpoints,
+        vec![
+            "https://api.example.com".to_string(),
+            "https://backup.example.com".to_string()
+        ]
+    );
+    assert_eq!(provider.website_url.as_deref(), Some("https://example.com"));
+    assert_eq!(provider.model.as_deref(), Some("claude-x"));
+    assert!(!provider.enabled);
+    assert!(validation.warnings.is_empty());
+}
+
+#[test]
+fn test_validate_deeplink_reports_missing_fields_and_bad_base64() {
+    let url = "ccswitch://v1/import?resource=provider&app=claude&name=Test&endpoint=https%3A%2F%2Fapi.example.com";
+    let err = validate_deeplink_url(url).unwrap_err();
+    assert!(matches!(err, DeepLinkError::MissingParam { ref param, .. } if param == "apiKey"));
+
+    let url = "ccswitch://v1/import?resource=provider&app=claude&name=Test&config=%25%25%25";
+    let err = validate_deeplink_url(url).unwrap_err();
+    assert!(matches!(err, DeepLinkError::InvalidBase64 { ref param, .. } if param == "config"));
+}
+
+// =============================================================================
+// Batch Tests
+// =============================================================================
+
+fn batch_url(providers: &serde_json::Value) -> String {
+    let encoded = BASE64_URL_SAFE_NO_PAD.encode(providers.to_string());
+    format!("ccswitch://v1/import?resource=batch&providers={encoded}")
+}
+
+#[test]
+fn test_parse_batch_deeplink_into_provider_requests() {
+    let url = batch_url(&serde_json::json!([
+        {
+            "app": "claude",
+            "name": "Relay A",
+            "endpoint": "https://a.example.com",
+            "apiKey": "sk-a",
+            "enabled": true,
+        },
+        {
+            "app": "codex",
+            "name": "Relay B",
+            "endpoint": "https://b.example.com/v1",
+            "apiKey": "sk-b",
+            "usageAutoInterval": 5,
+        },
+    ]));
+
+    let request = parse_deeplink_url(&url).unwrap();
+    assert_eq!(request.resource, "batch");
+    let providers = request.providers.expect("batch providers");
+    assert_eq!(providers.len(), 2);
+    assert_eq!(providers[0].resource, "provider");
+    assert_eq!(providers[0].name.as_deref(), Some("Relay A"));
+    assert_eq!(providers[0].enabled, Some(true));
+    assert_eq!(providers[1].app.as_deref(), Some("codex"));
+    assert_eq!(providers[1].usage_auto_interval, Some(5));
+
+    let validation = validate_deeplink_url(&url).unwrap();
+    assert!(validation.provider.is_none());
+    assert_eq!(validation.providers.len(), 2);
+    assert_eq!(
+        validation.providers[1].website_url.as_deref(),
+        Some("https://b.example.com")
+    );
+
+    // 单个供应商链接不受影响
+    let single = parse_deeplink_url(
+        "ccswitch://v1/import?resource=provider&app=claude&name=T&endpoint=https%3A%2F%2Fapi.example.com&apiKey=sk",
+    )
+    .unwrap();
+    assert!(single.providers.is_none());
+}
+
+#[test]
+fn test_batch_deeplink_errors_name_the_item_and_enforce_limit() {
+    let err = parse_deeplink_url(&batch_url(&serde_json::json!([
+        { "app": "claude", "name": "A" },
+        { "app": "claude", "endpoint": "https://b.example.com" },
+    ])))
+    .unwrap_err();
+    assert!(
+        matches!(err, DeepLinkError::MissingParam { ref param, .. } if param == "providers[1].name")
+    );
+
+    let url = batch_url(&serde_json::json!([
+        { "app": "claude", "name": "A", "endpoint": "https://a.example.com" },
+    ]));
+    let err = validate_deeplink_url(&url).unwrap_err();
+    assert!(
+        matches!(err, DeepLinkError::MissingParam { ref param, .. } if param == "providers[0].apiKey")
+    );
+
+    let err = parse_deeplink_url(&batch_url(&serde_json::json!([]))).unwrap_err();
+    assert!(matches!(err, DeepLinkError::SchemaMismatch { ref field, .. } if field == "providers"));
+
+    let too_many: Vec<serde_json::Value> = (0..=MAX_DEEPLINK_BATCH_SIZE)
+        .map(|i| serde_json::json!({ "app": "claude", "name": format!("P{i}") }))
+        .collect();
+    let err = parse_deeplink_url(&batch_url(&serde_json::json!(too_many))).unwrap_err();
+    assert!(matches!(
+        err,
+        DeepLinkError::PayloadTooLarge { ref param, size, limit, .. }
+            if param == "providers" && size == MAX_DEEPLINK_BATCH_SIZE + 1
+                && limit == MAX_DEEPLINK_BATCH_SIZE
+    ));
+}
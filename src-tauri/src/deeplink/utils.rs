@@ -2,20 +2,22 @@
 //!
 //! Common helpers for URL validation, Base64 decoding, etc.
 
-use crate::error::AppError;
+use super::error::DeepLinkError;
 use base64::prelude::*;
 use url::Url;
 
 /// Validate that a string is a valid HTTP(S) URL
-pub fn validate_url(url_str: &str, field_name: &str) -> Result<(), AppError> {
-    let url = Url::parse(url_str)
-        .map_err(|e| AppError::InvalidInput(format!("Invalid URL for '{field_name}': {e}")))?;
+pub fn validate_url(url_str: &str, field_name: &str) -> Result<(), DeepLinkError> {
+    let url = Url::parse(url_str).map_err(|e| {
+        DeepLinkError::schema_mismatch(field_name, format!("Invalid URL for '{field_name}': {e}"))
+    })?;
 
     let scheme = url.scheme();
     if scheme != "http" && scheme != "https" {
-        return Err(AppError::InvalidInput(format!(
-            "Invalid URL scheme for '{field_name}': must be http or https, got '{scheme}'"
-        )));
+        return Err(DeepLinkError::schema_mismatch(
+            field_name,
+            format!("Invalid URL scheme for '{field_name}': must be http or https, got '{scheme}'"),
+        ));
     }
 
     Ok(())
@@ -27,7 +29,7 @@ pub fn validate_url(url_str: &str, field_name: &str) -> Result<(), AppError> {
 /// - `+` being decoded as space
 /// - Missing padding `=`
 /// - Both standard and URL-safe Base64 variants
-pub fn decode_base64_param(field: &str, raw: &str) -> Result<Vec<u8>, AppError> {
+pub fn decode_base64_param(field: &str, raw: &str) -> Result<Vec<u8>, DeepLinkError> {
     let mut candidates: Vec<String> = Vec::new();
     // Keep spaces (to restore `+`), but remove newlines
     let trimmed = raw.trim_matches(|c| c == '\r' || c == '\n');
@@ -73,10 +75,13 @@ pub fn decode_base64_param(field: &str, raw: &str) -> Result<Vec<u8>, AppError>
         }
     }
 
-    Err(AppError::InvalidInput(format!(
-        "{field} 参数 Base64 解码失败：{}。请确认链接参数已用 Base64 编码并经过 URL 转义（尤其是将 '+' 编码为 %2B，或使用 URL-safe Base64）。",
-        last_error.unwrap_or_else(|| "未知错误".to_string())
-    )))
+    Err(DeepLinkError::InvalidBase64 {
+        param: field.to_string(),
+        message: format!(
+            "{field} 参数 Base64 解码失败：{}。请确认链接参数已用 Base64 编码并经过 URL 转义（尤其是将 '+' 编码为 %2B，或使用 URL-safe Base64）。",
+            last_error.unwrap_or_else(|| "未知错误".to_string())
+        ),
+    })
 }
 
 /// Infer homepage URL from API endpoint
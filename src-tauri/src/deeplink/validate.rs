@@ -0,0 +1,197 @@
+//! Validation-only deep link parsing
+//!
+//! 执行与导入相同的解析、配置合并与供应商校验，但不写入数据库，
+//! 用于在确认对话框中预览"将会导入什么"以及可能存在的问题。
+
+use super::error::DeepLinkError;
+use super::parser::{parse_deeplink_url, prefix_error_field};
+use super::provider::{build_provider_from_request, parse_and_merge_config};
+use super::utils::{decode_base64_param, infer_homepage_from_endpoint};
+use super::DeepLinkImportRequest;
+use crate::field_error::{FieldError, FieldSeverity};
+use crate::services::ProviderService;
+use crate::AppType;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// 将要导入的供应商概要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkProviderPreview {
+    pub app: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    /// 第一个为主端点
+    pub endpoints: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// 导入后是否立即启用
+    pub enabled: bool,
+}
+
+/// 校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkValidation {
+    /// 合并配置文件后的请求
+    pub request: DeepLinkImportRequest,
+    /// 仅 provider 资源有值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<DeepLinkProviderPreview>,
+    /// 仅 batch 资源有值，与 `request.providers` 顺序一致
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<DeepLinkProviderPreview>,
+    /// 不阻止导入的提示
+    pub warnings: Vec<FieldError>,
+}
+
+/// Parse and validate a deep link URL without importing anything
+///
+/// 错误与 `deeplink-error` 事件使用相同的原因码；供应商配置校验失败时
+/// 返回 `schema_mismatch`，字段路径形如 `settingsConfig.env.ANTHROPIC_BASE_URL`。
+pub fn validate_deeplink_url(url: &str) -> Result<DeepLinkValidation, DeepLinkError> {
+    let request = parse_deeplink_url(url)?;
+
+    if let Some(items) = request.providers.clone() {
+        return validate_batch_request(request, items);
+    }
+
+    let mut merged = decode_and_merge(&request)?;
+
+    if merged.resource != "provider" {
+        return Ok(DeepLinkValidation {
+            request: merged,
+            provider: None,
+            providers: Vec::new(),
+            warnings: Vec::new(),
+        });
+    }
+
+    let (provider, warnings) = validate_provider_request(&mut merged)?;
+    Ok(DeepLinkValidation {
+        request: merged,
+        provider: Some(provider),
+        providers: Vec::new(),
+        warnings,
+    })
+}
+
+/// 逐项校验批量请求，错误字段带 `providers[i].` 前缀
+fn validate_batch_request(
+    mut request: DeepLinkImportRequest,
+    items: Vec<DeepLinkImportRequest>,
+) -> Result<DeepLinkValidation, DeepLinkError> {
+    let mut merged_items = Vec::with_capacity(items.len());
+    let mut previews = Vec::with_capacity(items.len());
+    let mut warnings = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let prefix = format!("providers[{i}]");
+        let mut merged = decode_and_merge(item).map_err(|e| prefix_error_field(e, &prefix))?;
+        let (preview, item_warnings) =
+            validate_provider_request(&mut merged).map_err(|e| prefix_error_field(e, &prefix))?;
+        merged_items.push(merged);
+        previews.push(preview);
+        warnings.extend(item_warnings);
+    }
+    request.providers = Some(merged_items);
+
+    Ok(DeepLinkValidation {
+        request,
+        provider: None,
+        providers: previews,
+        warnings,
+    })
+}
+
+/// 解码 Base64 参数并合并配置文件
+fn decode_and_merge(
+    request: &DeepLinkImportRequest,
+) -> Result<DeepLinkImportRequest, DeepLinkError> {
+    // 先单独解码，使 Base64 问题以 invalid_base64 返回而不是笼统的配置错误
+    if let Some(config) = &request.config {
+        decode_base64_param("config", config)?;
+    }
+    if let Some(script) = &request.usage_script {
+        decode_base64_param("usage_script", script)?;
+    }
+
+    parse_and_merge_config(request)
+        .map_err(|e| DeepLinkError::schema_mismatch("config", e.to_string()))
+}
+
+/// 检查导入供应商所需的字段，并用 ProviderService 校验生成的配置
+fn validate_provider_request(
+    request: &mut DeepLinkImportRequest,
+) -> Result<(DeepLinkProviderPreview, Vec<FieldError>), DeepLinkError> {
+    let app = request.app.clone().unwrap_or_default();
+    let app_type = AppType::from_str(&app)
+        .map_err(|e| DeepLinkError::schema_mismatch("app", e.to_string()))?;
+
+    if request.api_key.as_deref().is_none_or(str::is_empty) {
+        return Err(DeepLinkError::missing_param(
+            "apiKey",
+            "API key is required (either in URL or config file)",
+        ));
+    }
+
+    let endpoints: Vec<String> = request
+        .endpoint
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .collect();
+    let Some(primary_endpoint) = endpoints.first() else {
+        return Err(DeepLinkError::missing_param(
+            "endpoint",
+            "Endpoint is required (either in URL or config file)",
+        ));
+    };
+
+    if request.homepage.as_deref().is_none_or(str::is_empty) {
+        request.homepage = infer_homepage_from_endpoint(primary_endpoint);
+    }
+    if request.homepage.as_deref().is_none_or(str::is_empty) {
+        return Err(DeepLinkError::missing_param(
+            "homepage",
+            "Homepage is required (either in URL or config file)",
+        ));
+    }
+
+    let provider = build_provider_from_request(&app_type, request)
+        .map_err(|e| DeepLinkError::schema_mismatch("usage_script", e.to_string()))?;
+
+    let mut warnings = Vec::new();
+    for issue in ProviderService::validate_config(app_type, provider.settings_config.clone()) {
+        if issue.severity == FieldSeverity::Error {
+            let field = if issue.path.is_empty() {
+                "settingsConfig".to_string()
+            } else {
+                format!("settingsConfig.{}", issue.path)
+            };
+            return Err(DeepLinkError::schema_mismatch(
+                field,
+                issue.to_app_error().to_string(),
+            ));
+        }
+        warnings.push(issue);
+    }
+
+    let preview = DeepLinkProviderPreview {
+        app,
+        name: provider.name,
+        website_url: provider.website_url,
+        endpoints,
+        model: request.model.clone(),
+        icon: provider.icon,
+        notes: provider.notes,
+        enabled: request.enabled.unwrap_or(false),
+    };
+    Ok((preview, warnings))
+}
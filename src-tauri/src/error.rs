@@ -56,6 +56,14 @@ pub enum AppError {
     AllProvidersCircuitOpen,
     #[error("未配置供应商")]
     NoProvidersConfigured,
+    #[error("供应商 {provider_id} 健康检查未通过，已取消切换: {message}")]
+    ProviderVerificationFailed {
+        provider_id: String,
+        http_status: Option<u16>,
+        message: String,
+    },
+    #[error("只读模式已开启，未修改 Live 配置文件")]
+    ReadOnlyMode,
 }
 
 impl AppError {
@@ -87,6 +95,20 @@ impl AppError {
             en: en.into(),
         }
     }
+
+    /// 按界面语言设置选择的错误信息（`Localized` 只返回一种语言，其他变体同 `to_string()`）
+    pub fn localized_message(&self) -> String {
+        match self {
+            Self::Localized { zh, en, .. } => {
+                if crate::settings::is_chinese_ui() {
+                    zh.clone()
+                } else {
+                    en.clone()
+                }
+            }
+            other => other.to_string(),
+        }
+    }
 }
 
 impl<T> From<PoisonError<T>> for AppError {
@@ -101,18 +123,32 @@ impl From<rusqlite::Error> for AppError {
     }
 }
 
+/// 命令层以 `String` 返回错误时按界面语言取 `Localized` 的单语文本
 impl From<AppError> for String {
     fn from(err: AppError) -> Self {
-        err.to_string()
+        err.localized_message()
     }
 }
 
+/// `Localized` 序列化为包含双语文本的对象，前端切换语言时无需重新请求；其他变体序列化为字符串
 impl serde::Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        use serde::ser::SerializeStruct;
+
+        match self {
+            Self::Localized { key, zh, en } => {
+                let mut state = serializer.serialize_struct("AppError", 4)?;
+                state.serialize_field("code", key)?;
+                state.serialize_field("message", &self.localized_message())?;
+                state.serialize_field("zh", zh)?;
+                state.serialize_field("en", en)?;
+                state.end()
+            }
+            other => serializer.serialize_str(&other.to_string()),
+        }
     }
 }
 
@@ -140,3 +176,31 @@ pub fn format_skill_error(
         format!("ERROR:{code}")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_error_serializes_both_languages() {
+        let err = AppError::localized("demo.failed", "操作失败", "Operation failed");
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["code"], "demo.failed");
+        assert_eq!(value["zh"], "操作失败");
+        assert_eq!(value["en"], "Operation failed");
+        assert!(value["message"] == "操作失败" || value["message"] == "Operation failed");
+
+        let plain = serde_json::to_value(AppError::Message("boom".to_string())).unwrap();
+        assert_eq!(plain, serde_json::json!("boom"));
+    }
+
+    #[test]
+    fn localized_error_converts_to_single_language_string() {
+        let message: String =
+            AppError::localized("demo.failed", "操作失败", "Operation failed").into();
+        assert!(message == "操作失败" || message == "Operation failed");
+
+        let plain: String = AppError::Message("boom".to_string()).into();
+        assert_eq!(plain, "boom");
+    }
+}
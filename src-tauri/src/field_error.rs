@@ -0,0 +1,77 @@
+//! 字段级校验问题
+//!
+//! 供各应用的配置校验（Gemini / Codex）与深链接校验共用，前端据此在编辑器中定位字段。
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// 字段级问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldSeverity {
+    /// 阻止保存
+    Error,
+    /// 仅提示，不阻止保存
+    Warning,
+}
+
+/// 字段级校验问题（供编辑器高亮定位）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// 字段路径，如 `env.GOOGLE_GEMINI_BASE_URL`
+    pub path: String,
+    /// 错误码（与 `AppError::Localized` 的 key 一致）
+    pub code: &'static str,
+    pub severity: FieldSeverity,
+    pub message_zh: String,
+    pub message_en: String,
+}
+
+impl FieldError {
+    pub(crate) fn error(
+        path: impl Into<String>,
+        code: &'static str,
+        zh: impl Into<String>,
+        en: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            code,
+            severity: FieldSeverity::Error,
+            message_zh: zh.into(),
+            message_en: en.into(),
+        }
+    }
+
+    pub(crate) fn warning(
+        path: impl Into<String>,
+        code: &'static str,
+        zh: impl Into<String>,
+        en: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: FieldSeverity::Warning,
+            ..Self::error(path, code, zh, en)
+        }
+    }
+
+    /// 将整体校验错误包装为字段级问题（路径为空，表示整个配置）
+    pub fn from_app_error(err: &AppError) -> Self {
+        match err {
+            AppError::Localized { key, zh, en } => Self::error("", *key, zh.clone(), en.clone()),
+            other => Self::error(
+                "",
+                "provider.settings.invalid",
+                other.to_string(),
+                other.to_string(),
+            ),
+        }
+    }
+
+    /// 转换为本地化错误，供只需要单个错误的调用方使用
+    pub fn to_app_error(&self) -> AppError {
+        AppError::localized(self.code, self.message_zh.clone(), self.message_en.clone())
+    }
+}
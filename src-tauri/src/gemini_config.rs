@@ -1,7 +1,8 @@
 use crate::config::{get_home_dir, write_text_file};
 use crate::error::AppError;
+use crate::field_error::FieldError;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
@@ -19,34 +20,171 @@ pub fn get_gemini_env_path() -> PathBuf {
     get_gemini_dir().join(".env")
 }
 
+/// cc-switch 管理的 .env 变量（写入时会更新或移除，其余变量原样保留）
+const MANAGED_ENV_KEYS: &[&str] = &[
+    "GEMINI_API_KEY",
+    "GOOGLE_GEMINI_BASE_URL",
+    "GEMINI_MODEL",
+    "GOOGLE_OAUTH_ACCESS_TOKEN",
+    "GOOGLE_OAUTH_REFRESH_TOKEN",
+    "GOOGLE_OAUTH_EMAIL",
+    "GOOGLE_OAUTH_PROJECT_ID",
+    "ANTIGRAVITY_ACCESS_TOKEN",
+    "ANTIGRAVITY_REFRESH_TOKEN",
+    "ANTIGRAVITY_EMAIL",
+    "ANTIGRAVITY_EXPIRES_AT",
+    "ANTIGRAVITY_PROJECT_ID",
+];
+
+/// 追加托管变量时使用的分隔注释
+const MANAGED_MARKER: &str = "# managed by cc-switch";
+
+/// 解析单行 `KEY=VALUE`（支持 `export ` 前缀、引号包裹的值和行尾注释）
+///
+/// 空行、注释和无效行返回 `None`。
+fn parse_env_line(line: &str) -> Option<(&str, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    // 验证 key 是否有效（不为空，只包含字母、数字和下划线）
+    if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some((key, unquote_env_value(value.trim())))
+}
+
+/// 去掉值两侧的引号；未加引号的值去掉 ` #` 开始的行尾注释
+fn unquote_env_value(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('"') {
+        if let Some(end) = inner.rfind('"') {
+            let mut out = String::with_capacity(end);
+            let mut chars = inner[..end].chars();
+            while let Some(c) = chars.next() {
+                match (c, chars.clone().next()) {
+                    ('\\', Some(next @ ('"' | '\\'))) => {
+                        out.push(next);
+                        chars.next();
+                    }
+                    ('\\', Some('n')) => {
+                        out.push('\n');
+                        chars.next();
+                    }
+                    _ => out.push(c),
+                }
+            }
+            return out;
+        }
+    }
+    if let Some(inner) = value.strip_prefix('\'') {
+        if let Some(end) = inner.rfind('\'') {
+            return inner[..end].to_string();
+        }
+    }
+
+    match value.find(" #") {
+        Some(pos) => value[..pos].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// 格式化写入的值：包含空白、`#` 或引号时使用双引号包裹
+fn format_env_value(value: &str) -> String {
+    let needs_quotes = value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '#' | '"' | '\'' | '\\'));
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
 /// 解析 .env 文件内容为键值对
 ///
 /// 此函数宽松地解析 .env 文件，跳过无效行。
 /// 对于需要严格验证的场景，请使用 `parse_env_file_strict`。
 pub fn parse_env_file(content: &str) -> HashMap<String, String> {
-    let mut map = HashMap::new();
+    content
+        .lines()
+        .filter_map(parse_env_line)
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
 
-    for line in content.lines() {
-        let line = line.trim();
+/// 将新的托管变量合并进已有 .env 内容
+///
+/// - 已存在的变量就地更新（值未变化时整行保持原样）；
+/// - `map` 中没有的托管变量（见 `MANAGED_ENV_KEYS`）被移除，重复出现的只保留第一处；
+/// - 其余行（注释、空行、其他工具的变量）逐字节保留；
+/// - 新增变量按键名排序追加到 `# managed by cc-switch` 注释之后。
+pub fn merge_env_file(existing: &str, map: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(existing.len());
+    let mut written: HashSet<&str> = HashSet::new();
+    let mut has_marker = false;
+
+    for raw in existing.split_inclusive('\n') {
+        let line = raw.trim_end_matches(['\r', '\n']);
+        if line.trim() == MANAGED_MARKER {
+            has_marker = true;
+        }
 
-        // 跳过空行和注释
-        if line.is_empty() || line.starts_with('#') {
+        let Some((key, old_value)) = parse_env_line(line) else {
+            out.push_str(raw);
             continue;
+        };
+        match map.get_key_value(key) {
+            Some((key, _)) if written.contains(key.as_str()) => {}
+            Some((key, value)) => {
+                written.insert(key.as_str());
+                if *value == old_value {
+                    out.push_str(raw);
+                } else {
+                    let export = if line.trim_start().starts_with("export ") {
+                        "export "
+                    } else {
+                        ""
+                    };
+                    let ending = &raw[line.len()..];
+                    out.push_str(&format!("{export}{key}={}", format_env_value(value)));
+                    out.push_str(ending);
+                }
+            }
+            None if MANAGED_ENV_KEYS.contains(&key) => {}
+            None => out.push_str(raw),
         }
+    }
 
-        // 解析 KEY=VALUE
-        if let Some((key, value)) = line.split_once('=') {
-            let key = key.trim().to_string();
-            let value = value.trim().to_string();
+    let missing: HashMap<String, String> = map
+        .iter()
+        .filter(|(key, _)| !written.contains(key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if missing.is_empty() {
+        return out;
+    }
 
-            // 验证 key 是否有效（不为空，只包含字母、数字和下划线）
-            if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                map.insert(key, value);
-            }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    if !has_marker {
+        if !out.is_empty() && !out.ends_with("\n\n") {
+            out.push('\n');
         }
+        out.push_str(MANAGED_MARKER);
+        out.push('\n');
     }
-
-    map
+    out.push_str(&serialize_env_file(&missing));
+    out.push('\n');
+    out
 }
 
 /// 严格解析 .env 文件内容，返回详细的错误信息
@@ -132,7 +270,7 @@ pub fn serialize_env_file(map: &HashMap<String, String>) -> String {
 
     for key in keys {
         if let Some(value) = map.get(key) {
-            lines.push(format!("{key}={value}"));
+            lines.push(format!("{key}={}", format_env_value(value)));
         }
     }
 
@@ -152,7 +290,7 @@ pub fn read_gemini_env() -> Result<HashMap<String, String>, AppError> {
     Ok(parse_env_file(&content))
 }
 
-/// 写入 Gemini .env 文件（原子操作）
+/// 写入 Gemini .env 文件（原子操作，保留注释与非托管变量）
 pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppError> {
     let path = get_gemini_env_path();
 
@@ -172,7 +310,13 @@ pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppE
         }
     }
 
-    let content = serialize_env_file(map);
+    // 保留已有文件中的注释与非托管变量
+    let existing = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?
+    } else {
+        String::new()
+    };
+    let content = merge_env_file(&existing, map);
     write_text_file(&path, &content)?;
 
     // 设置文件权限为 600（仅所有者可读写）
@@ -248,6 +392,112 @@ pub fn validate_gemini_settings(settings: &Value) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Gemini 配置允许的顶层字段
+const GEMINI_SETTINGS_KEYS: &[&str] = &["env", "config"];
+
+/// 按结构化规则校验 Gemini 配置，返回全部字段级问题
+///
+/// 与 `validate_gemini_settings` 相比额外检查：
+/// - env 中的值必须是字符串
+/// - `GOOGLE_GEMINI_BASE_URL` 必须是合法的 http(s) URL
+/// - 非 OAuth 配置缺少 `GEMINI_API_KEY`（警告，允许稍后填写）
+/// - 未知的顶层字段（警告）
+pub fn validate_gemini_settings_detailed(settings: &Value) -> Result<(), Vec<FieldError>> {
+    let mut problems = Vec::new();
+
+    let Some(obj) = settings.as_object() else {
+        return Err(vec![FieldError::error(
+            "",
+            "gemini.validation.not_object",
+            "Gemini 配置必须是 JSON 对象",
+            "Gemini config must be a JSON object",
+        )]);
+    };
+
+    for key in obj.keys() {
+        if !GEMINI_SETTINGS_KEYS.contains(&key.as_str()) {
+            problems.push(FieldError::warning(
+                key.clone(),
+                "gemini.validation.unknown_key",
+                format!("未知的配置字段: {key}"),
+                format!("Unknown config field: {key}"),
+            ));
+        }
+    }
+
+    if let Some(config) = obj.get("config") {
+        if !(config.is_object() || config.is_null()) {
+            problems.push(FieldError::error(
+                "config",
+                "gemini.validation.invalid_config",
+                "Gemini 配置格式错误: config 必须是对象",
+                "Gemini config invalid: config must be an object",
+            ));
+        }
+    }
+
+    match obj.get("env") {
+        Some(env) if !env.is_object() => problems.push(FieldError::error(
+            "env",
+            "gemini.validation.invalid_env",
+            "Gemini 配置格式错误: env 必须是对象",
+            "Gemini config invalid: env must be an object",
+        )),
+        Some(Value::Object(env)) => {
+            for (key, value) in env {
+                if !value.is_string() {
+                    problems.push(FieldError::error(
+                        format!("env.{key}"),
+                        "gemini.validation.invalid_env_value",
+                        format!("环境变量 {key} 的值必须是字符串"),
+                        format!("Environment variable {key} must be a string"),
+                    ));
+                }
+            }
+
+            if let Some(base_url) = env
+                .get("GOOGLE_GEMINI_BASE_URL")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+            {
+                let valid = url::Url::parse(base_url)
+                    .map(|u| matches!(u.scheme(), "http" | "https") && u.host().is_some())
+                    .unwrap_or(false);
+                if !valid {
+                    problems.push(FieldError::error(
+                        "env.GOOGLE_GEMINI_BASE_URL",
+                        "gemini.validation.invalid_base_url",
+                        format!("GOOGLE_GEMINI_BASE_URL 不是合法的 http(s) 地址: {base_url}"),
+                        format!("GOOGLE_GEMINI_BASE_URL is not a valid http(s) URL: {base_url}"),
+                    ));
+                }
+            }
+
+            // 空 env 表示 OAuth（如 Google 官方），不要求 API Key
+            let has_api_key = env
+                .get("GEMINI_API_KEY")
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| !v.trim().is_empty());
+            if !env.is_empty() && !has_api_key {
+                problems.push(FieldError::warning(
+                    "env.GEMINI_API_KEY",
+                    "gemini.validation.missing_api_key",
+                    "Gemini 配置缺少必需字段: GEMINI_API_KEY",
+                    "Gemini config missing required field: GEMINI_API_KEY",
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
 /// 严格验证 Gemini 配置（要求必需字段）
 ///
 /// 此函数在切换供应商时使用，确保配置包含所有必需的字段。
@@ -282,6 +532,53 @@ pub fn get_gemini_settings_path() -> PathBuf {
     get_gemini_dir().join("settings.json")
 }
 
+/// 开始从 settings.json 的 `env` 读取 `GOOGLE_GEMINI_BASE_URL` 等变量的最低 Gemini CLI 版本
+pub const GEMINI_SETTINGS_ENV_MIN_VERSION: (u64, u64, u64) = (0, 10, 0);
+
+/// 代理接管时需要写入的 Gemini 配置文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeminiLiveTargets {
+    /// `~/.gemini/.env`
+    pub env_file: bool,
+    /// `~/.gemini/settings.json` 中的 `env`
+    pub settings_file: bool,
+}
+
+impl GeminiLiveTargets {
+    pub fn any(&self) -> bool {
+        self.env_file || self.settings_file
+    }
+}
+
+/// 判断指定版本的 Gemini CLI 是否读取 settings.json 中的 `env`
+///
+/// 无法解析版本号时返回 `None`。
+pub fn gemini_cli_reads_settings_env(version: &str) -> Option<bool> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or(core);
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let parsed = (parts.next()??, parts.next()??, parts.next()??);
+    Some(parsed >= GEMINI_SETTINGS_ENV_MIN_VERSION)
+}
+
+/// 根据已安装的 Gemini CLI 版本与现有文件决定接管写入哪些配置
+///
+/// - `.env` 存在即写入（所有版本都会读取）
+/// - settings.json 存在，且 CLI 版本支持（或版本未知）时写入
+pub fn gemini_live_targets(
+    cli_version: Option<&str>,
+    env_exists: bool,
+    settings_exists: bool,
+) -> GeminiLiveTargets {
+    let reads_settings = cli_version
+        .and_then(gemini_cli_reads_settings_env)
+        .unwrap_or(true);
+    GeminiLiveTargets {
+        env_file: env_exists,
+        settings_file: settings_exists && reads_settings,
+    }
+}
+
 /// 更新 Gemini 目录 settings.json 中的 security.auth.selectedType 字段
 ///
 /// 此函数会：
@@ -373,6 +670,38 @@ pub fn write_google_oauth_settings() -> Result<(), AppError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::field_error::FieldSeverity;
+
+    #[test]
+    fn test_gemini_cli_reads_settings_env() {
+        assert_eq!(gemini_cli_reads_settings_env("0.10.0"), Some(true));
+        assert_eq!(gemini_cli_reads_settings_env("v0.12.3"), Some(true));
+        assert_eq!(gemini_cli_reads_settings_env("1.0.0-preview.1"), Some(true));
+        assert_eq!(gemini_cli_reads_settings_env("0.9.4"), Some(false));
+        assert_eq!(gemini_cli_reads_settings_env("0.10.0-nightly"), Some(true));
+        assert_eq!(gemini_cli_reads_settings_env("unknown"), None);
+        assert_eq!(gemini_cli_reads_settings_env("0.10"), None);
+    }
+
+    #[test]
+    fn test_gemini_live_targets_combinations() {
+        let targets = |version, env, settings| {
+            let t = gemini_live_targets(version, env, settings);
+            (t.env_file, t.settings_file)
+        };
+
+        // 旧版本只读取 .env
+        assert_eq!(targets(Some("0.9.0"), true, true), (true, false));
+        assert_eq!(targets(Some("0.9.0"), false, true), (false, false));
+        // 新版本两处都读取，只写入已存在的文件
+        assert_eq!(targets(Some("0.10.0"), true, true), (true, true));
+        assert_eq!(targets(Some("0.10.0"), true, false), (true, false));
+        assert_eq!(targets(Some("0.10.0"), false, true), (false, true));
+        // 版本未知（未安装或无法解析）时按已存在的文件处理
+        assert_eq!(targets(None, true, true), (true, true));
+        assert_eq!(targets(Some("dev"), false, true), (false, true));
+        assert!(!gemini_live_targets(None, false, false).any());
+    }
 
     #[test]
     fn test_parse_env_file() {
@@ -521,6 +850,84 @@ KEY_WITH-DASH=value";
         assert!(strict_result.is_err());
     }
 
+    #[test]
+    fn test_parse_env_file_quotes_and_export() {
+        let content = "export GOOGLE_CLOUD_PROJECT=\"my-project\"\nNAME='a # b'\nURL=https://x.example.com # inline\nESCAPED=\"say \\\"hi\\\"\"\n";
+
+        let map = parse_env_file(content);
+
+        assert_eq!(
+            map.get("GOOGLE_CLOUD_PROJECT"),
+            Some(&"my-project".to_string())
+        );
+        assert_eq!(map.get("NAME"), Some(&"a # b".to_string()));
+        assert_eq!(map.get("URL"), Some(&"https://x.example.com".to_string()));
+        assert_eq!(map.get("ESCAPED"), Some(&"say \"hi\"".to_string()));
+    }
+
+    #[test]
+    fn test_merge_env_file_preserves_unmanaged_lines() {
+        let existing = "# Gemini CLI\r\n\
+export GOOGLE_CLOUD_PROJECT=\"my-project\"  # for gcloud\r\n\
+\r\n\
+GEMINI_API_KEY=old-key\r\n\
+GEMINI_MODEL=gemini-2.5-pro\r\n\
+HTTP_PROXY='http://127.0.0.1:7890'\r\n\
+GEMINI_API_KEY=duplicate\r\n\
+ANTIGRAVITY_ACCESS_TOKEN=stale";
+
+        let mut map = HashMap::new();
+        map.insert("GEMINI_API_KEY".to_string(), "new key".to_string());
+        map.insert("GEMINI_MODEL".to_string(), "gemini-2.5-pro".to_string());
+        map.insert(
+            "GOOGLE_GEMINI_BASE_URL".to_string(),
+            "https://relay.example.com".to_string(),
+        );
+
+        let merged = merge_env_file(existing, &map);
+
+        assert_eq!(
+            merged,
+            "# Gemini CLI\r\n\
+export GOOGLE_CLOUD_PROJECT=\"my-project\"  # for gcloud\r\n\
+\r\n\
+GEMINI_API_KEY=\"new key\"\r\n\
+GEMINI_MODEL=gemini-2.5-pro\r\n\
+HTTP_PROXY='http://127.0.0.1:7890'\r\n\
+\n\
+# managed by cc-switch\n\
+GOOGLE_GEMINI_BASE_URL=https://relay.example.com\n"
+        );
+
+        // 读回时非托管变量同样可见，回填不会丢失
+        let parsed = parse_env_file(&merged);
+        assert_eq!(parsed.get("GEMINI_API_KEY"), Some(&"new key".to_string()));
+        assert_eq!(
+            parsed.get("GOOGLE_CLOUD_PROJECT"),
+            Some(&"my-project".to_string())
+        );
+        assert_eq!(
+            parsed.get("HTTP_PROXY"),
+            Some(&"http://127.0.0.1:7890".to_string())
+        );
+        assert!(!parsed.contains_key("ANTIGRAVITY_ACCESS_TOKEN"));
+
+        // 再次写入相同内容应保持不变
+        assert_eq!(merge_env_file(&merged, &parsed), merged);
+    }
+
+    #[test]
+    fn test_merge_env_file_into_empty_file() {
+        let mut map = HashMap::new();
+        map.insert("GEMINI_API_KEY".to_string(), "sk-test".to_string());
+
+        assert_eq!(
+            merge_env_file("", &map),
+            "# managed by cc-switch\nGEMINI_API_KEY=sk-test\n"
+        );
+        assert_eq!(merge_env_file("", &HashMap::new()), "");
+    }
+
     #[test]
     fn test_packycode_settings_structure() {
         // 验证 Packycode settings.json 的结构正确
@@ -642,6 +1049,41 @@ KEY_WITH-DASH=value";
         assert!(validate_gemini_settings_strict(&settings).is_err());
     }
 
+    #[test]
+    fn test_validate_detailed_reports_all_field_problems() {
+        let settings = serde_json::json!({
+            "env": {
+                "GOOGLE_GEMINI_BASE_URL": "not a url",
+                "GEMINI_MODEL": "gemini-3-pro"
+            },
+            "extra": true
+        });
+
+        let problems = validate_gemini_settings_detailed(&settings).unwrap_err();
+        let codes: Vec<_> = problems
+            .iter()
+            .map(|p| (p.path.as_str(), p.severity))
+            .collect();
+        assert!(codes.contains(&("extra", FieldSeverity::Warning)));
+        assert!(codes.contains(&("env.GOOGLE_GEMINI_BASE_URL", FieldSeverity::Error)));
+        assert!(codes.contains(&("env.GEMINI_API_KEY", FieldSeverity::Warning)));
+    }
+
+    #[test]
+    fn test_validate_detailed_accepts_valid_settings() {
+        let oauth = serde_json::json!({ "env": {} });
+        assert!(validate_gemini_settings_detailed(&oauth).is_ok());
+
+        let api_key = serde_json::json!({
+            "env": {
+                "GEMINI_API_KEY": "sk-test",
+                "GOOGLE_GEMINI_BASE_URL": "https://api.example.com/gemini"
+            },
+            "config": {}
+        });
+        assert!(validate_gemini_settings_detailed(&api_key).is_ok());
+    }
+
     #[test]
     fn test_validate_invalid_env_type() {
         // 测试 env 不是对象时会失败
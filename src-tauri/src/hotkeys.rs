@@ -0,0 +1,296 @@
+//! 全局快捷键
+//!
+//! 快捷键绑定保存在 `AppSettings.hotkeys` 中：
+//! - `nextProvider`：按应用配置，按 `sort_index` 顺序切换到下一个供应商（末尾回到第一个）；
+//! - `toggleProxy`：启动/停止本地代理。
+//!
+//! 启动时及配置变更后调用 [`register_all`] 重新注册；被系统或其他应用占用的快捷键
+//! 不会中断其余绑定，而是作为 [`HotkeyBindingError`] 返回给前端提示。
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcut, Shortcut, ShortcutState};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 已注册的快捷键 ID 与对应动作
+static REGISTERED: Mutex<Vec<(u32, HotkeyAction)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConfig {
+    /// 切换到下一个供应商的快捷键（键为应用 ID，如 `claude`；值为快捷键，如 `CmdOrCtrl+Alt+1`）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub next_provider: BTreeMap<String, String>,
+    /// 启动/停止本地代理的快捷键
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HotkeyAction {
+    NextProvider(AppType),
+    ToggleProxy,
+}
+
+impl HotkeyAction {
+    /// 返回给前端的动作标识，如 `nextProvider:claude`
+    fn label(&self) -> String {
+        match self {
+            HotkeyAction::NextProvider(app_type) => format!("nextProvider:{}", app_type.as_str()),
+            HotkeyAction::ToggleProxy => "toggleProxy".to_string(),
+        }
+    }
+}
+
+/// 注册失败的绑定
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBindingError {
+    pub action: String,
+    pub accelerator: String,
+    pub message: String,
+}
+
+impl HotkeyConfig {
+    /// 校验应用 ID 与快捷键格式，并拒绝重复的快捷键
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut seen: Vec<Shortcut> = Vec::new();
+        for (_, accelerator) in self.bindings()? {
+            let shortcut = parse_accelerator(&accelerator)?;
+            if seen.contains(&shortcut) {
+                return Err(AppError::localized(
+                    "hotkeys.duplicate",
+                    format!("快捷键 {accelerator} 被重复使用"),
+                    format!("Hotkey {accelerator} is used more than once"),
+                ));
+            }
+            seen.push(shortcut);
+        }
+        Ok(())
+    }
+
+    /// 展开为（动作, 快捷键）列表，忽略空白快捷键
+    fn bindings(&self) -> Result<Vec<(HotkeyAction, String)>, AppError> {
+        let mut bindings = Vec::new();
+        for (app, accelerator) in &self.next_provider {
+            let app_type = AppType::from_str(app)?;
+            if app_type.is_additive_mode() {
+                return Err(AppError::localized(
+                    "hotkeys.additive_app",
+                    format!("{app} 没有当前供应商，不支持切换快捷键"),
+                    format!("{app} has no current provider and cannot use a switch hotkey"),
+                ));
+            }
+            if !accelerator.trim().is_empty() {
+                bindings.push((HotkeyAction::NextProvider(app_type), accelerator.clone()));
+            }
+        }
+        if let Some(accelerator) = self.toggle_proxy.as_ref() {
+            if !accelerator.trim().is_empty() {
+                bindings.push((HotkeyAction::ToggleProxy, accelerator.clone()));
+            }
+        }
+        Ok(bindings)
+    }
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, AppError> {
+    Shortcut::from_str(accelerator.trim()).map_err(|e| {
+        AppError::localized(
+            "hotkeys.invalid",
+            format!("无效的快捷键 {accelerator}: {e}"),
+            format!("Invalid hotkey {accelerator}: {e}"),
+        )
+    })
+}
+
+fn registered() -> std::sync::MutexGuard<'static, Vec<(u32, HotkeyAction)>> {
+    REGISTERED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 全局快捷键插件（按下时分发到对应动作）
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                handle_shortcut(app, shortcut);
+            }
+        })
+        .build()
+}
+
+/// 按当前配置重新注册全部快捷键，返回注册失败的绑定
+pub fn register_all(app: &AppHandle) -> Vec<HotkeyBindingError> {
+    let Some(shortcuts) = app.try_state::<GlobalShortcut<tauri::Wry>>() else {
+        log::warn!("全局快捷键插件未初始化，跳过注册");
+        return Vec::new();
+    };
+    if let Err(e) = shortcuts.unregister_all() {
+        log::warn!("注销全局快捷键失败: {e}");
+    }
+
+    let config = crate::settings::get_hotkey_config();
+    let bindings = match config.bindings() {
+        Ok(bindings) => bindings,
+        Err(e) => {
+            log::warn!("快捷键配置无效，已跳过注册: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for (action, accelerator) in bindings {
+        let result = parse_accelerator(&accelerator).and_then(|shortcut| {
+            shortcuts
+                .register(shortcut)
+                .map(|_| shortcut.id())
+                .map_err(|e| AppError::Message(e.to_string()))
+        });
+        match result {
+            Ok(id) => entries.push((id, action)),
+            Err(e) => {
+                log::warn!("注册快捷键 {accelerator}（{}）失败: {e}", action.label());
+                failures.push(HotkeyBindingError {
+                    action: action.label(),
+                    accelerator,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    log::info!("已注册 {} 个全局快捷键", entries.len());
+    *registered() = entries;
+    failures
+}
+
+fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut) {
+    let action = registered()
+        .iter()
+        .find(|(id, _)| *id == shortcut.id())
+        .map(|(_, action)| action.clone());
+    let Some(action) = action else {
+        return;
+    };
+
+    let app_handle = app.clone();
+    match action {
+        HotkeyAction::NextProvider(app_type) => {
+            // 切换可能包含切换前健康检查，放到阻塞线程执行
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = switch_to_next_provider(&app_handle, &app_type) {
+                    log::error!("[Hotkey] 切换 {} 供应商失败: {e}", app_type.as_str());
+                }
+            });
+        }
+        HotkeyAction::ToggleProxy => {
+            tauri::async_runtime::spawn(async move {
+                let Some(app_state) = app_handle.try_state::<AppState>() else {
+                    return;
+                };
+                let proxy_service = &app_state.proxy_service;
+                // 菜单刷新由 ProxyService 在状态变化后统一完成
+                let result = if proxy_service.is_running().await {
+                    log::info!("[Hotkey] 停止代理服务并恢复配置");
+                    proxy_service.stop_with_restore().await
+                } else {
+                    log::info!("[Hotkey] 启动代理服务");
+                    proxy_service.start().await.map(|_| ())
+                };
+                if let Err(e) = result {
+                    log::error!("[Hotkey] 代理操作失败: {e}");
+                }
+            });
+        }
+    }
+}
+
+fn switch_to_next_provider(app: &AppHandle, app_type: &AppType) -> Result<(), AppError> {
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let ids: Vec<String> = app_state
+        .db
+        .get_all_providers(app_type.as_str())?
+        .into_keys()
+        .collect();
+    let current = crate::settings::get_effective_current_provider(&app_state.db, app_type)?;
+    let Some(next) = next_provider_id(&ids, current.as_deref()) else {
+        return Ok(());
+    };
+
+    log::info!("[Hotkey] 切换 {} 供应商: {next}", app_type.as_str());
+    ProviderService::switch(&app_state, app_type.clone(), &next)?;
+
+    crate::tray::refresh_tray_menu(app);
+    let event_data = serde_json::json!({
+        "appType": app_type.as_str(),
+        "providerId": next,
+        "source": "hotkey",
+    });
+    if let Err(e) = app.emit("provider-switched", event_data) {
+        log::error!("[Hotkey] 发射 provider-switched 事件失败: {e}");
+    }
+    Ok(())
+}
+
+/// 按列表顺序返回当前供应商的下一个（末尾回到第一个）；无可切换目标时返回 None
+fn next_provider_id(ids: &[String], current: Option<&str>) -> Option<String> {
+    let position = current.and_then(|current| ids.iter().position(|id| id == current));
+    let next = match position {
+        Some(index) => ids.get((index + 1) % ids.len())?,
+        None => ids.first()?,
+    };
+    (Some(next.as_str()) != current).then(|| next.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_provider_wraps_in_sort_order() {
+        let ids: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(next_provider_id(&ids, Some("a")).as_deref(), Some("b"));
+        assert_eq!(next_provider_id(&ids, Some("c")).as_deref(), Some("a"));
+        // 当前供应商不在列表中时从第一个开始
+        assert_eq!(next_provider_id(&ids, Some("gone")).as_deref(), Some("a"));
+        assert_eq!(next_provider_id(&ids, None).as_deref(), Some("a"));
+        assert_eq!(next_provider_id(&ids[..1], Some("a")), None);
+        assert_eq!(next_provider_id(&[], None), None);
+    }
+
+    #[test]
+    fn validate_rejects_bad_bindings() {
+        let mut config = HotkeyConfig {
+            next_provider: BTreeMap::from([
+                ("claude".to_string(), "CmdOrCtrl+Alt+1".to_string()),
+                ("codex".to_string(), String::new()),
+            ]),
+            toggle_proxy: Some("CmdOrCtrl+Alt+P".to_string()),
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.bindings().unwrap().len(), 2);
+
+        config.toggle_proxy = Some("CmdOrCtrl+Alt+1".to_string());
+        assert!(config.validate().is_err(), "duplicate accelerator");
+
+        config.toggle_proxy = Some("NotAKey+++".to_string());
+        assert!(config.validate().is_err(), "malformed accelerator");
+
+        config.toggle_proxy = None;
+        config
+            .next_provider
+            .insert("opencode".to_string(), "CmdOrCtrl+Alt+2".to_string());
+        assert!(config.validate().is_err(), "additive app");
+    }
+}
@@ -1,5 +1,8 @@
+use crate::database::InvalidProviderRow;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct InitErrorPayload {
@@ -7,90 +10,322 @@ pub struct InitErrorPayload {
     pub error: String,
 }
 
-static INIT_ERROR: OnceLock<RwLock<Option<InitErrorPayload>>> = OnceLock::new();
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillsMigrationPayload {
+    pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
 
-fn cell() -> &'static RwLock<Option<InitErrorPayload>> {
-    INIT_ERROR.get_or_init(|| RwLock::new(None))
+/// 启动阶段结果汇总（供前端一次性展示“启动时发生了什么”）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupStatus {
+    /// 初始化错误（若有）
+    pub init_error: Option<InitErrorPayload>,
+    /// 本次启动是否执行了 JSON→SQLite 迁移（且成功）
+    pub json_migrated: bool,
+    /// Skills 自动导入（SSOT）迁移结果
+    pub skills_migration: Option<SkillsMigrationPayload>,
+    /// 代理状态恢复是否已执行完毕（恢复在后台异步进行）
+    pub proxy_restore_done: bool,
+    /// 已自动恢复代理接管的应用
+    pub proxy_restored_apps: Vec<String>,
+    /// 启动时发现的无法解析的供应商行（修复后清空）
+    pub invalid_providers: Vec<InvalidProviderRow>,
+    /// 延后执行的导入与默认数据初始化是否已完成（完成时发射 `initial-import-finished`）
+    pub initial_import_done: bool,
 }
 
-#[allow(dead_code)]
-pub fn set_init_error(payload: InitErrorPayload) {
-    #[allow(clippy::unwrap_used)]
+/// 延后导入完成后发射的事件，负载为 [`StartupStatus`]
+pub const INITIAL_IMPORT_FINISHED_EVENT: &str = "initial-import-finished";
+
+/// 单个启动阶段的耗时
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPhaseTiming {
+    pub name: String,
+    pub duration_ms: u64,
+    /// 是否在窗口显示后于后台执行
+    pub deferred: bool,
+    /// 阶段开始时距 setup 开始的毫秒数
+    pub started_at_ms: u64,
+}
+
+/// 启动耗时报告
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupTimings {
+    /// 按开始时间排序的各阶段耗时
+    pub phases: Vec<StartupPhaseTiming>,
+    /// setup 开始到主窗口显示的毫秒数（尚未显示时为 None）
+    pub window_shown_ms: Option<u64>,
+    /// setup 开始到延后导入完成的毫秒数（尚未完成时为 None）
+    pub initial_import_finished_ms: Option<u64>,
+}
+
+/// 单个应用的自动导入结果（启动阶段或手动重新导入）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppImportOutcome {
+    /// 导入的供应商数量（OpenCode 可一次导入多个）
+    pub providers_imported: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_error: Option<String>,
+    pub mcp_imported: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_error: Option<String>,
+    pub prompts_imported: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts_error: Option<String>,
+}
+
+/// 首次运行引导：单个应用的检测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppOnboardingStatus {
+    pub app: String,
+    pub cli_found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cli_version: Option<String>,
+    /// 主 Live 配置文件路径
+    pub live_config_path: String,
+    pub live_config_exists: bool,
+    /// 当前已有的供应商数量
+    pub provider_count: usize,
+    /// 对该应用启用的 MCP 服务器数量
+    pub mcp_servers: usize,
+    pub prompt_file_exists: bool,
+    /// 应用 Skills 目录中的 Skill 数量
+    pub skills_detected: usize,
+    /// 最近一次自动导入的结果
+    pub import: AppImportOutcome,
+    /// 需要提示用户的导入错误（Live 配置不存在导致的“未找到”不计入）
+    pub errors: Vec<String>,
+}
+
+/// 首次运行引导报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingReport {
+    pub generated_at: i64,
+    pub apps: Vec<AppOnboardingStatus>,
+}
+
+/// 启动状态与各旧接口“只返回一次”的消费标记
+#[derive(Debug, Default)]
+struct StartupState {
+    status: StartupStatus,
+    migration_reported: bool,
+    skills_migration_reported: bool,
+    import_outcomes: BTreeMap<String, AppImportOutcome>,
+    onboarding_report: Option<OnboardingReport>,
+    timings: StartupTimings,
+    setup_started: Option<Instant>,
+}
+
+static STARTUP_STATE: OnceLock<RwLock<StartupState>> = OnceLock::new();
+
+fn cell() -> &'static RwLock<StartupState> {
+    STARTUP_STATE.get_or_init(|| RwLock::new(StartupState::default()))
+}
+
+fn update(f: impl FnOnce(&mut StartupState)) {
     if let Ok(mut guard) = cell().write() {
-        *guard = Some(payload);
+        f(&mut guard);
     }
 }
 
+/// 获取启动状态汇总（不消费各一次性结果）
+pub fn get_startup_status() -> StartupStatus {
+    cell()
+        .read()
+        .map(|guard| guard.status.clone())
+        .unwrap_or_default()
+}
+
+#[allow(dead_code)]
+pub fn set_init_error(payload: InitErrorPayload) {
+    update(|state| state.status.init_error = Some(payload));
+}
+
 pub fn get_init_error() -> Option<InitErrorPayload> {
-    cell().read().ok()?.clone()
+    cell().read().ok()?.status.init_error.clone()
 }
 
 // ============================================================
 // 迁移结果状态
 // ============================================================
 
-static MIGRATION_SUCCESS: OnceLock<RwLock<bool>> = OnceLock::new();
-
-fn migration_cell() -> &'static RwLock<bool> {
-    MIGRATION_SUCCESS.get_or_init(|| RwLock::new(false))
-}
-
 pub fn set_migration_success() {
-    if let Ok(mut guard) = migration_cell().write() {
-        *guard = true;
-    }
+    update(|state| state.status.json_migrated = true);
 }
 
 /// 获取并消费迁移成功状态（只返回一次 true，之后返回 false）
 pub fn take_migration_success() -> bool {
-    if let Ok(mut guard) = migration_cell().write() {
-        let val = *guard;
-        *guard = false;
-        val
-    } else {
-        false
-    }
+    let mut result = false;
+    update(|state| {
+        result = state.status.json_migrated && !state.migration_reported;
+        state.migration_reported = true;
+    });
+    result
 }
 
 // ============================================================
 // Skills SSOT 迁移结果状态
 // ============================================================
 
-#[derive(Debug, Clone, Serialize)]
-pub struct SkillsMigrationPayload {
-    pub count: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
-}
-
-static SKILLS_MIGRATION_RESULT: OnceLock<RwLock<Option<SkillsMigrationPayload>>> = OnceLock::new();
-
-fn skills_migration_cell() -> &'static RwLock<Option<SkillsMigrationPayload>> {
-    SKILLS_MIGRATION_RESULT.get_or_init(|| RwLock::new(None))
-}
-
 pub fn set_skills_migration_result(count: usize) {
-    if let Ok(mut guard) = skills_migration_cell().write() {
-        *guard = Some(SkillsMigrationPayload { count, error: None });
-    }
+    update(|state| {
+        state.status.skills_migration = Some(SkillsMigrationPayload { count, error: None });
+    });
 }
 
 pub fn set_skills_migration_error(error: String) {
-    if let Ok(mut guard) = skills_migration_cell().write() {
-        *guard = Some(SkillsMigrationPayload {
+    update(|state| {
+        state.status.skills_migration = Some(SkillsMigrationPayload {
             count: 0,
             error: Some(error),
         });
-    }
+    });
 }
 
 /// 获取并消费 Skills 迁移结果（只返回一次 Some，之后返回 None）
 pub fn take_skills_migration_result() -> Option<SkillsMigrationPayload> {
-    if let Ok(mut guard) = skills_migration_cell().write() {
-        guard.take()
-    } else {
-        None
-    }
+    let mut result = None;
+    update(|state| {
+        if !state.skills_migration_reported {
+            result = state.status.skills_migration.clone();
+            state.skills_migration_reported = result.is_some();
+        }
+    });
+    result
+}
+
+// ============================================================
+// 代理状态恢复结果
+// ============================================================
+
+/// 记录启动时代理接管状态的恢复结果
+pub fn set_proxy_restore_result(restored_apps: Vec<String>) {
+    update(|state| {
+        state.status.proxy_restore_done = true;
+        state.status.proxy_restored_apps = restored_apps;
+    });
+}
+
+// ============================================================
+// 启动耗时
+// ============================================================
+
+/// 标记 setup 开始，后续阶段的起始时间都相对于此刻
+pub fn mark_setup_started() {
+    let now = Instant::now();
+    update(|state| {
+        state.setup_started = Some(now);
+        state.timings = StartupTimings::default();
+    });
+}
+
+fn since_setup(state: &StartupState, at: Instant) -> u64 {
+    state
+        .setup_started
+        .map(|start| at.saturating_duration_since(start).as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 记录一个已完成阶段的耗时
+pub fn record_startup_phase(name: &str, started: Instant, duration: Duration, deferred: bool) {
+    update(|state| {
+        let started_at_ms = since_setup(state, started);
+        state.timings.phases.push(StartupPhaseTiming {
+            name: name.to_string(),
+            duration_ms: duration.as_millis() as u64,
+            deferred,
+            started_at_ms,
+        });
+        state.timings.phases.sort_by_key(|p| p.started_at_ms);
+    });
+}
+
+/// 执行 `f` 并记录其耗时
+pub fn time_startup_phase<R>(name: &str, deferred: bool, f: impl FnOnce() -> R) -> R {
+    let started = Instant::now();
+    let result = f();
+    record_startup_phase(name, started, started.elapsed(), deferred);
+    result
+}
+
+pub fn mark_window_shown() {
+    let now = Instant::now();
+    update(|state| state.timings.window_shown_ms = Some(since_setup(state, now)));
+}
+
+/// 标记延后导入完成
+pub fn set_initial_import_done() {
+    let now = Instant::now();
+    update(|state| {
+        state.status.initial_import_done = true;
+        state.timings.initial_import_finished_ms = Some(since_setup(state, now));
+    });
+}
+
+pub fn get_startup_timings() -> StartupTimings {
+    cell()
+        .read()
+        .map(|guard| guard.timings.clone())
+        .unwrap_or_default()
+}
+
+// ============================================================
+// 供应商表完整性
+// ============================================================
+
+/// 记录供应商表的校验结果（启动时或手动校验/修复后更新）
+pub fn set_invalid_providers(rows: Vec<InvalidProviderRow>) {
+    update(|state| state.status.invalid_providers = rows);
+}
+
+// ============================================================
+// 首次运行引导
+// ============================================================
+
+/// 记录某个应用的自动导入结果（覆盖之前的结果）
+pub fn set_import_outcome(app: &str, outcome: AppImportOutcome) {
+    update(|state| {
+        state.import_outcomes.insert(app.to_string(), outcome);
+    });
+}
+
+/// 获取某个应用最近一次的自动导入结果（未执行过时为默认值）
+pub fn get_import_outcome(app: &str) -> AppImportOutcome {
+    cell()
+        .read()
+        .ok()
+        .and_then(|guard| guard.import_outcomes.get(app).cloned())
+        .unwrap_or_default()
+}
+
+pub fn set_onboarding_report(report: OnboardingReport) {
+    update(|state| state.onboarding_report = Some(report));
+}
+
+/// 获取启动阶段生成的引导报告（后台生成尚未完成时为 None）
+pub fn get_onboarding_report() -> Option<OnboardingReport> {
+    cell().read().ok()?.onboarding_report.clone()
+}
+
+/// 用新的检测结果替换报告中对应应用的条目
+pub fn update_onboarding_app(status: AppOnboardingStatus) {
+    update(|state| {
+        let Some(report) = state.onboarding_report.as_mut() else {
+            return;
+        };
+        match report.apps.iter_mut().find(|a| a.app == status.app) {
+            Some(existing) => *existing = status,
+            None => report.apps.push(status),
+        }
+    });
 }
 
 #[cfg(test)]
@@ -108,4 +343,58 @@ mod tests {
         assert_eq!(got.path, payload.path);
         assert_eq!(got.error, payload.error);
     }
+
+    #[test]
+    fn startup_status_is_not_consumed_by_legacy_takes() {
+        set_migration_success();
+        set_skills_migration_result(3);
+        set_proxy_restore_result(vec!["claude".to_string()]);
+
+        assert!(take_migration_success());
+        assert!(!take_migration_success());
+        assert_eq!(take_skills_migration_result().map(|p| p.count), Some(3));
+        assert!(take_skills_migration_result().is_none());
+
+        let status = get_startup_status();
+        assert!(status.json_migrated);
+        assert_eq!(status.skills_migration.map(|p| p.count), Some(3));
+        assert!(status.proxy_restore_done);
+        assert_eq!(status.proxy_restored_apps, vec!["claude".to_string()]);
+    }
+
+    #[test]
+    fn startup_phases_are_recorded_in_start_order() {
+        mark_setup_started();
+        let tray_started = Instant::now();
+        let value = time_startup_phase("database_init", false, || 42);
+        assert_eq!(value, 42);
+        mark_window_shown();
+        record_startup_phase(
+            "initial_imports",
+            Instant::now(),
+            Duration::from_millis(7),
+            true,
+        );
+        // 晚记录但更早开始的阶段排在前面
+        record_startup_phase("tray", tray_started, Duration::from_millis(3), false);
+        set_initial_import_done();
+
+        let timings = get_startup_timings();
+        let names: Vec<&str> = timings.phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names.len(), 3);
+        assert!(timings
+            .phases
+            .windows(2)
+            .all(|w| w[0].started_at_ms <= w[1].started_at_ms));
+        let deferred = timings
+            .phases
+            .iter()
+            .find(|p| p.name == "initial_imports")
+            .expect("deferred phase");
+        assert!(deferred.deferred);
+        assert_eq!(deferred.duration_ms, 7);
+        assert!(timings.window_shown_ms.is_some());
+        assert!(timings.initial_import_finished_ms.is_some());
+        assert!(get_startup_status().initial_import_done);
+    }
 }
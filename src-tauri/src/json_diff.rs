@@ -0,0 +1,297 @@
+//! 通用 JSON 差异计算与敏感字段脱敏
+//!
+//! 对象按键递归比较，数组与标量视为整体；差异路径使用 JSON Pointer（如 `/env/BASE_URL`）。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 脱敏后的占位值
+pub const REDACTED: &str = "[REDACTED]";
+
+/// 键名（小写）包含以下片段时视为敏感字段
+const SECRET_KEY_PATTERNS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "api_key",
+    "apikey",
+    "api-key",
+    "authorization",
+    "cookie",
+    "credential",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// 单个字段的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonChange {
+    /// JSON Pointer 路径，根节点为空字符串
+    pub path: String,
+    pub kind: JsonChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+/// 计算 `before` → `after` 的差异（按路径排序）
+pub fn diff(before: &Value, after: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_at(String::new(), before, after, &mut changes);
+    changes
+}
+
+fn diff_at(path: String, before: &Value, after: &Value, changes: &mut Vec<JsonChange>) {
+    let (Some(before_obj), Some(after_obj)) = (before.as_object(), after.as_object()) else {
+        if before != after {
+            changes.push(JsonChange {
+                path,
+                kind: JsonChangeKind::Changed,
+                before: Some(before.clone()),
+                after: Some(after.clone()),
+            });
+        }
+        return;
+    };
+
+    let mut keys: Vec<&String> = before_obj.keys().chain(after_obj.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let child_path = format!("{path}/{}", escape_pointer_token(key));
+        match (before_obj.get(key), after_obj.get(key)) {
+            (Some(b), Some(a)) => diff_at(child_path, b, a, changes),
+            (Some(b), None) => changes.push(JsonChange {
+                path: child_path,
+                kind: JsonChangeKind::Removed,
+                before: Some(b.clone()),
+                after: None,
+            }),
+            (None, Some(a)) => changes.push(JsonChange {
+                path: child_path,
+                kind: JsonChangeKind::Added,
+                before: None,
+                after: Some(a.clone()),
+            }),
+            (None, None) => {}
+        }
+    }
+}
+
+/// 按 RFC 6901 转义 JSON Pointer 片段
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// 判断键名是否为敏感字段
+///
+/// 以 `tokens` 结尾的键（如 `MAX_OUTPUT_TOKENS`）表示数量而非凭据，不视为敏感。
+pub fn is_secret_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    if lower.ends_with("tokens") {
+        return false;
+    }
+    SECRET_KEY_PATTERNS.iter().any(|p| lower.contains(p)) || lower.ends_with("_key")
+}
+
+/// 以 TOML 文本形式保存的字段（Codex 的 `config`）
+const TOML_TEXT_KEY: &str = "config";
+
+/// 递归将敏感字段的字符串值替换为 [`REDACTED`]（保留对象结构，便于比较哪些字段变化）
+///
+/// Codex `config` 字段中的 TOML 文本按同样规则脱敏（如 `experimental_bearer_token`、
+/// `http_headers.Authorization`）。
+pub fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if v.is_string() && is_secret_key(key) {
+                    *v = Value::String(REDACTED.to_string());
+                } else if key == TOML_TEXT_KEY {
+                    redact_toml_value(v);
+                } else {
+                    redact_secrets(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// 字符串值按 TOML 文本脱敏，其他类型按 JSON 递归脱敏
+fn redact_toml_value(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = redact_toml_text(text),
+        other => redact_secrets(other),
+    }
+}
+
+/// 将 TOML 文本中敏感键的字符串值替换为 [`REDACTED`]；无法解析时整体隐藏
+fn redact_toml_text(text: &str) -> String {
+    match text.parse::<toml_edit::DocumentMut>() {
+        Ok(mut doc) => {
+            redact_toml_table(doc.as_table_mut());
+            doc.to_string()
+        }
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+fn redact_toml_table(table: &mut dyn toml_edit::TableLike) {
+    for (key, item) in table.iter_mut() {
+        if let Some(child) = item.as_table_like_mut() {
+            redact_toml_table(child);
+        } else if let Some(array) = item.as_array_of_tables_mut() {
+            for child in array.iter_mut() {
+                redact_toml_table(child);
+            }
+        } else if is_secret_key(key.get()) {
+            if let Some(value) = item.as_value_mut().filter(|v| v.is_str()) {
+                let decor = value.decor().clone();
+                *value = toml_edit::Value::from(REDACTED);
+                *value.decor_mut() = decor;
+            }
+        }
+    }
+}
+
+/// 计算差异并脱敏：先比较原始值（密钥变化也会被记录），再隐藏变化前后的敏感内容
+pub fn diff_redacted(before: &Value, after: &Value) -> Vec<JsonChange> {
+    let mut changes = diff(before, after);
+    for change in &mut changes {
+        let leaf = change
+            .path
+            .rsplit('/')
+            .next()
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .unwrap_or_default();
+        let leaf_is_secret = is_secret_key(&leaf);
+        for value in [&mut change.before, &mut change.after]
+            .into_iter()
+            .flatten()
+        {
+            if leaf_is_secret && value.is_string() {
+                *value = Value::String(REDACTED.to_string());
+            } else if leaf == TOML_TEXT_KEY {
+                redact_toml_value(value);
+            } else {
+                redact_secrets(value);
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_nested_added_removed_and_changed_fields() {
+        let before = json!({
+            "env": {"BASE_URL": "https://a", "MODEL": "m1", "a/b": 1},
+            "list": [1, 2],
+            "same": true
+        });
+        let after = json!({
+            "env": {"BASE_URL": "https://b", "TIMEOUT": 30, "a/b": 1},
+            "list": [1, 2, 3],
+            "same": true
+        });
+
+        let changes = diff(&before, &after);
+        let summary: Vec<(&str, JsonChangeKind)> =
+            changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/env/BASE_URL", JsonChangeKind::Changed),
+                ("/env/MODEL", JsonChangeKind::Removed),
+                ("/env/TIMEOUT", JsonChangeKind::Added),
+                ("/list", JsonChangeKind::Changed),
+            ]
+        );
+        assert_eq!(changes[0].before, Some(json!("https://a")));
+        assert_eq!(changes[0].after, Some(json!("https://b")));
+        assert!(diff(&before, &before).is_empty());
+
+        // 非对象根节点整体比较
+        let root = diff(&json!("x"), &json!({"k": 1}));
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].path, "");
+    }
+
+    #[test]
+    fn redact_secrets_masks_credential_values_only() {
+        let mut value = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-1",
+                "OPENAI_API_KEY": "sk-2",
+                "ANTHROPIC_BASE_URL": "https://api"
+            },
+            "auth": {"tokens": {"access_token": "a", "refresh_token": "r"}},
+            "apiKey": "sk-3",
+            "private_key": null,
+            "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "32000"
+        });
+        redact_secrets(&mut value);
+
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], json!(REDACTED));
+        assert_eq!(value["env"]["OPENAI_API_KEY"], json!(REDACTED));
+        assert_eq!(value["env"]["ANTHROPIC_BASE_URL"], json!("https://api"));
+        assert_eq!(value["auth"]["tokens"]["access_token"], json!(REDACTED));
+        assert_eq!(value["auth"]["tokens"]["refresh_token"], json!(REDACTED));
+        assert_eq!(value["apiKey"], json!(REDACTED));
+        assert_eq!(value["private_key"], Value::Null);
+        assert_eq!(value["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], json!("32000"));
+
+        // 密钥变化仍会出现在差异中，但不暴露具体值
+        let changes = diff_redacted(
+            &json!({"env": {"API_KEY": "old"}, "auth": {}}),
+            &json!({"env": {"API_KEY": "new"}, "auth": {"token": "t"}}),
+        );
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, "/auth/token");
+        assert_eq!(changes[0].after, Some(json!(REDACTED)));
+        assert_eq!(changes[1].path, "/env/API_KEY");
+        assert_eq!(changes[1].before, Some(json!(REDACTED)));
+        assert_eq!(changes[1].after, Some(json!(REDACTED)));
+    }
+
+    #[test]
+    fn redact_secrets_masks_secrets_in_codex_toml_config() {
+        let config = "model_provider = \"relay\"\n\n[model_providers.relay]\nbase_url = \"https://relay\"\nexperimental_bearer_token = \"sk-toml\" # 密钥\nhttp_headers = { Authorization = \"Bearer sk-header\" }\n";
+        let mut value = json!({ "auth": {}, "config": config });
+        redact_secrets(&mut value);
+
+        let redacted = value["config"].as_str().unwrap();
+        assert!(!redacted.contains("sk-toml"));
+        assert!(!redacted.contains("sk-header"));
+        assert!(redacted.contains("base_url = \"https://relay\""));
+        assert!(redacted.contains(&format!(
+            "experimental_bearer_token = \"{REDACTED}\" # 密钥"
+        )));
+
+        let changes = diff_redacted(
+            &json!({ "config": config }),
+            &json!({ "config": config.replace("sk-toml", "sk-new") }),
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/config");
+        assert_eq!(changes[0].before, changes[0].after);
+
+        let mut invalid = json!({ "config": "token = [sk-broken" });
+        redact_secrets(&mut invalid);
+        assert_eq!(invalid["config"], json!(REDACTED));
+    }
+}
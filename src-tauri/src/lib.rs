@@ -1,6 +1,7 @@
 mod app_config;
 mod app_store;
 mod auto_launch;
+mod claude_managed;
 mod claude_mcp;
 mod claude_plugin;
 mod codex_config;
@@ -9,12 +10,17 @@ mod config;
 mod database;
 mod deeplink;
 mod error;
+mod field_error;
 mod gemini_config;
 mod gemini_mcp;
+mod hotkeys;
 mod init_status;
+mod json_diff;
+mod log_filter;
 mod mcp;
 mod opencode_config;
 mod panic_hook;
+mod preflight;
 mod prompt;
 mod prompt_files;
 mod provider;
@@ -24,6 +30,7 @@ mod services;
 mod session_manager;
 mod settings;
 mod store;
+mod theme;
 mod tray;
 mod usage_script;
 
@@ -32,8 +39,12 @@ pub use codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_l
 pub use commands::open_provider_terminal;
 pub use commands::*;
 pub use config::{get_claude_mcp_path, get_claude_settings_path, read_json_file};
-pub use database::Database;
-pub use deeplink::{import_provider_from_deeplink, parse_deeplink_url, DeepLinkImportRequest};
+pub use database::{Database, ImportAction, ImportResolution, MergeStrategy};
+pub use deeplink::{
+    import_provider_batch_from_deeplink, import_provider_from_deeplink,
+    import_provider_from_deeplink_with_strategy, parse_deeplink_url, BatchImportResult,
+    DeepLinkImportRequest,
+};
 pub use error::AppError;
 pub use mcp::{
     import_from_claude, import_from_codex, import_from_gemini, remove_server_from_claude,
@@ -43,10 +54,10 @@ pub use mcp::{
 };
 pub use provider::{Provider, ProviderMeta};
 pub use services::{
-    ConfigService, EndpointLatency, McpService, PromptService, ProviderService, ProxyService,
-    SkillService, SpeedtestService,
+    ConfigService, EndpointLatency, ExternalFormat, McpService, PromptService, ProviderService,
+    ProxyService, SkillService, SpeedtestService,
 };
-pub use settings::{update_settings, AppSettings};
+pub use settings::{update_settings, AppSettings, ClaudeWriteMode};
 pub use store::AppState;
 use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
@@ -116,6 +127,9 @@ fn handle_deeplink_url(
                 request.app,
                 request.name
             );
+            if let Some(providers) = &request.providers {
+                log::info!("✓ Deep link contains {} providers", providers.len());
+            }
 
             if let Err(e) = app.emit("deeplink-import", &request) {
                 log::error!("✗ Failed to emit deeplink-import event: {e}");
@@ -135,13 +149,7 @@ fn handle_deeplink_url(
         Err(e) => {
             log::error!("✗ Failed to parse deep link URL: {e}");
 
-            if let Err(emit_err) = app.emit(
-                "deeplink-error",
-                serde_json::json!({
-                    "url": url_str,
-                    "error": e.to_string()
-                }),
-            ) {
+            if let Err(emit_err) = app.emit("deeplink-error", e.event_payload(url_str)) {
                 log::error!("✗ Failed to emit deeplink-error event: {emit_err}");
             }
         }
@@ -156,7 +164,8 @@ async fn update_tray_menu(
     app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
 ) -> Result<bool, String> {
-    match tray::create_tray_menu(&app, state.inner()) {
+    let proxy_running = state.proxy_service.is_running().await;
+    match tray::create_tray_menu(&app, state.inner(), proxy_running) {
         Ok(new_menu) => {
             if let Some(tray) = app.tray_by_id("main") {
                 tray.set_menu(Some(new_menu))
@@ -228,6 +237,9 @@ pub fn run() {
         .plugin(tauri_plugin_deep_link::init())
         // 拦截窗口关闭：根据设置决定是否最小化到托盘
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::ThemeChanged(_) = event {
+                theme::on_system_theme_changed(window.app_handle());
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let settings = crate::settings::get_settings();
 
@@ -252,8 +264,11 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
+            crate::init_status::mark_setup_started();
             // 预先刷新 Store 覆盖配置，确保后续路径读取正确（日志/数据库等）
             app_store::refresh_app_config_dir_override(app.handle());
+            // 界面语言需在 AppState 创建前可用（迁移/数据库错误对话框）
+            app_store::refresh_language_override(app.handle());
             panic_hook::init_app_config_dir(crate::config::get_app_config_dir());
 
             // 注册 Updater 插件（桌面端）
@@ -286,6 +301,8 @@ pub fn run() {
                     tauri_plugin_log::Builder::default()
                         // 初始化为 Trace，允许后续通过 log::set_max_level() 动态调整级别
                         .level(log::LevelFilter::Trace)
+                        // 按模块覆盖的级别由 log_filter 在运行时判断
+                        .filter(crate::log_filter::allows)
                         .targets([
                             Target::new(TargetKind::Stdout),
                             Target::new(TargetKind::Folder {
@@ -318,6 +335,21 @@ pub fn run() {
             let migration_config = if !has_db && has_json {
                 log::info!("检测到旧版配置文件，验证配置文件...");
 
+                // 磁盘已满或目录只读时迁移会中途失败；此时数据库尚未创建，提示后可重试
+                let required_bytes = std::fs::metadata(&json_path)
+                    .map(|m| m.len())
+                    .unwrap_or(0)
+                    .saturating_mul(4)
+                    .saturating_add(crate::preflight::MIN_FREE_BYTES);
+                while let Err(e) = crate::preflight::check_path(&app_config_dir, required_bytes) {
+                    log::error!("迁移前检查失败: {e}");
+                    if !show_migration_error_dialog(app.handle(), &e.localized_message()) {
+                        log::info!("用户选择退出程序");
+                        std::process::exit(1);
+                    }
+                    log::info!("用户选择重试迁移前检查");
+                }
+
                 // 循环：支持用户重试加载配置文件
                 loop {
                     match crate::app_config::MultiAppConfig::load() {
@@ -347,7 +379,7 @@ pub fn run() {
             // 说明：从 v3.8.* 升级的用户通常会走到这里的 SQLite schema 迁移，
             // 若迁移失败（数据库损坏/权限不足/user_version 过新等），需要给用户明确提示，
             // 否则表现可能只是“应用打不开/闪退”。
-            let db = loop {
+            let db = crate::init_status::time_startup_phase("database_init", false, || loop {
                 match crate::database::Database::init() {
                     Ok(db) => break Arc::new(db),
                     Err(e) => {
@@ -362,11 +394,23 @@ pub fn run() {
                         log::info!("用户选择重试初始化数据库");
                     }
                 }
-            };
+            });
+
+            // 检查供应商表中无法解析的行，结果通过启动状态提示用户修复
+            match db.validate_providers() {
+                Ok(invalid) => {
+                    if !invalid.is_empty() {
+                        log::warn!("发现 {} 个无法解析的供应商", invalid.len());
+                    }
+                    crate::init_status::set_invalid_providers(invalid);
+                }
+                Err(e) => log::warn!("校验供应商表失败: {e}"),
+            }
 
             // 如果有预加载的配置，执行迁移
             if let Some(config) = migration_config {
                 log::info!("开始执行数据迁移...");
+                let migration_started = std::time::Instant::now();
 
                 match db.migrate_from_json(&config) {
                     Ok(_) => {
@@ -386,6 +430,12 @@ pub fn run() {
                         log::error!("配置迁移失败: {e}，将从现有配置导入");
                     }
                 }
+                crate::init_status::record_startup_phase(
+                    "json_migration",
+                    migration_started,
+                    migration_started.elapsed(),
+                    false,
+                );
             }
 
             let app_state = AppState::new(db);
@@ -393,174 +443,8 @@ pub fn run() {
             // 设置 AppHandle 用于代理故障转移时的 UI 更新
             app_state.proxy_service.set_app_handle(app.handle().clone());
 
-            // ============================================================
-            // 按表独立判断的导入逻辑（各类数据独立检查，互不影响）
-            // ============================================================
-
-            // 1. 初始化默认 Skills 仓库（已有内置检查：表非空则跳过）
-            match app_state.db.init_default_skill_repos() {
-                Ok(count) if count > 0 => {
-                    log::info!("✓ Initialized {count} default skill repositories");
-                }
-                Ok(_) => {} // 表非空，静默跳过
-                Err(e) => log::warn!("✗ Failed to initialize default skill repos: {e}"),
-            }
-
-            // 1.1. Skills 统一管理迁移：当数据库迁移到 v3 结构后，自动从各应用目录导入到 SSOT
-            // 触发条件由 schema 迁移设置 settings.skills_ssot_migration_pending = true 控制。
-            match app_state.db.get_setting("skills_ssot_migration_pending") {
-                Ok(Some(flag)) if flag == "true" || flag == "1" => {
-                    // 安全保护：如果用户已经有 v3 结构的 Skills 数据，就不要自动清空重建。
-                    let has_existing = app_state
-                        .db
-                        .get_all_installed_skills()
-                        .map(|skills| !skills.is_empty())
-                        .unwrap_or(false);
-
-                    if has_existing {
-                        log::info!(
-                            "Detected skills_ssot_migration_pending but skills table not empty; skipping auto import."
-                        );
-                        let _ = app_state
-                            .db
-                            .set_setting("skills_ssot_migration_pending", "false");
-                    } else {
-                        match crate::services::skill::migrate_skills_to_ssot(&app_state.db) {
-                            Ok(count) => {
-                                log::info!("✓ Auto imported {count} skill(s) into SSOT");
-                                if count > 0 {
-                                    crate::init_status::set_skills_migration_result(count);
-                                }
-                                let _ = app_state
-                                    .db
-                                    .set_setting("skills_ssot_migration_pending", "false");
-                            }
-                            Err(e) => {
-                                log::warn!("✗ Failed to auto import legacy skills to SSOT: {e}");
-                                crate::init_status::set_skills_migration_error(e.to_string());
-                                // 保留 pending 标志，方便下次启动重试
-                            }
-                        }
-                    }
-                }
-                Ok(_) => {} // 未开启迁移标志，静默跳过
-                Err(e) => log::warn!("✗ Failed to read skills migration flag: {e}"),
-            }
-
-            // 2. 导入供应商配置（已有内置检查：该应用已有供应商则跳过）
-            for app in [
-                crate::app_config::AppType::Claude,
-                crate::app_config::AppType::Codex,
-                crate::app_config::AppType::Gemini,
-            ] {
-                match crate::services::provider::ProviderService::import_default_config(
-                    &app_state,
-                    app.clone(),
-                ) {
-                    Ok(true) => {
-                        log::info!("✓ Imported default provider for {}", app.as_str());
-
-                        // 首次运行：自动提取通用配置片段（仅当通用配置为空时）
-                        if app_state
-                            .db
-                            .get_config_snippet(app.as_str())
-                            .ok()
-                            .flatten()
-                            .is_none()
-                        {
-                            match crate::services::provider::ProviderService::extract_common_config_snippet(&app_state, app.clone()) {
-                                Ok(snippet) if !snippet.is_empty() && snippet != "{}" => {
-                                    if let Err(e) = app_state.db.set_config_snippet(app.as_str(), Some(snippet)) {
-                                        log::warn!("✗ Failed to save common config snippet for {}: {e}", app.as_str());
-                                    } else {
-                                        log::info!("✓ Extracted common config snippet for {}", app.as_str());
-                                    }
-                                }
-                                Ok(_) => log::debug!("○ No common config to extract for {}", app.as_str()),
-                                Err(e) => log::debug!("○ Failed to extract common config for {}: {e}", app.as_str()),
-                            }
-                        }
-                    }
-                    Ok(false) => {} // 已有供应商，静默跳过
-                    Err(e) => {
-                        log::debug!(
-                            "○ No default provider to import for {}: {}",
-                            app.as_str(),
-                            e
-                        );
-                    }
-                }
-            }
-
-            // 2.1 OpenCode 供应商导入（累加式模式，需特殊处理）
-            // OpenCode 与其他应用不同：配置文件中可同时存在多个供应商
-            // 需要遍历 provider 字段下的每个供应商并导入
-            match crate::services::provider::import_opencode_providers_from_live(&app_state) {
-                Ok(count) if count > 0 => {
-                    log::info!("✓ Imported {count} OpenCode provider(s) from live config");
-                }
-                Ok(_) => log::debug!("○ No OpenCode providers found to import"),
-                Err(e) => log::debug!("○ Failed to import OpenCode providers: {e}"),
-            }
-
-            // 3. 导入 MCP 服务器配置（表空时触发）
-            if app_state.db.is_mcp_table_empty().unwrap_or(false) {
-                log::info!("MCP table empty, importing from live configurations...");
-
-                match crate::services::mcp::McpService::import_from_claude(&app_state) {
-                    Ok(count) if count > 0 => {
-                        log::info!("✓ Imported {count} MCP server(s) from Claude");
-                    }
-                    Ok(_) => log::debug!("○ No Claude MCP servers found to import"),
-                    Err(e) => log::warn!("✗ Failed to import Claude MCP: {e}"),
-                }
-
-                match crate::services::mcp::McpService::import_from_codex(&app_state) {
-                    Ok(count) if count > 0 => {
-                        log::info!("✓ Imported {count} MCP server(s) from Codex");
-                    }
-                    Ok(_) => log::debug!("○ No Codex MCP servers found to import"),
-                    Err(e) => log::warn!("✗ Failed to import Codex MCP: {e}"),
-                }
-
-                match crate::services::mcp::McpService::import_from_gemini(&app_state) {
-                    Ok(count) if count > 0 => {
-                        log::info!("✓ Imported {count} MCP server(s) from Gemini");
-                    }
-                    Ok(_) => log::debug!("○ No Gemini MCP servers found to import"),
-                    Err(e) => log::warn!("✗ Failed to import Gemini MCP: {e}"),
-                }
-
-                match crate::services::mcp::McpService::import_from_opencode(&app_state) {
-                    Ok(count) if count > 0 => {
-                        log::info!("✓ Imported {count} MCP server(s) from OpenCode");
-                    }
-                    Ok(_) => log::debug!("○ No OpenCode MCP servers found to import"),
-                    Err(e) => log::warn!("✗ Failed to import OpenCode MCP: {e}"),
-                }
-            }
-
-            // 4. 导入提示词文件（表空时触发）
-            if app_state.db.is_prompts_table_empty().unwrap_or(false) {
-                log::info!("Prompts table empty, importing from live configurations...");
-
-                for app in [
-                    crate::app_config::AppType::Claude,
-                    crate::app_config::AppType::Codex,
-                    crate::app_config::AppType::Gemini,
-                ] {
-                    match crate::services::prompt::PromptService::import_from_file_on_first_launch(
-                        &app_state,
-                        app.clone(),
-                    ) {
-                        Ok(count) if count > 0 => {
-                            log::info!("✓ Imported {count} prompt(s) for {}", app.as_str());
-                        }
-                        Ok(_) => log::debug!("○ No prompt file found for {}", app.as_str()),
-                        Err(e) => log::warn!("✗ Failed to import prompt for {}: {e}", app.as_str()),
-                    }
-                }
-            }
+            // 默认数据初始化与供应商 / MCP / 提示词 / Skills 导入不影响首屏，
+            // 在窗口显示后由后台任务执行（见 run_deferred_startup_imports）
 
             // 迁移旧的 app_config_dir 配置到 Store
             if let Err(e) = app_store::migrate_app_config_dir_from_settings(app.handle()) {
@@ -628,7 +512,10 @@ pub fn run() {
             log::info!("✓ Deep-link URL handler registered");
 
             // 创建动态托盘菜单
-            let menu = tray::create_tray_menu(app.handle(), &app_state)?;
+            let tray_started = std::time::Instant::now();
+            let proxy_running =
+                tauri::async_runtime::block_on(app_state.proxy_service.is_running());
+            let menu = tray::create_tray_menu(app.handle(), &app_state, proxy_running)?;
 
             // 构建托盘
             let mut tray_builder = TrayIconBuilder::with_id("main")
@@ -666,6 +553,12 @@ pub fn run() {
             }
 
             let _tray = tray_builder.build(app)?;
+            crate::init_status::record_startup_phase(
+                "tray",
+                tray_started,
+                tray_started.elapsed(),
+                false,
+            );
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
 
@@ -673,7 +566,7 @@ pub fn run() {
             {
                 let db = &app.state::<AppState>().db;
                 if let Ok(log_config) = db.get_log_config() {
-                    log::set_max_level(log_config.to_level_filter());
+                    crate::log_filter::apply(&log_config);
                     log::info!(
                         "已加载日志配置: enabled={}, level={}",
                         log_config.enabled,
@@ -687,17 +580,37 @@ pub fn run() {
             app.manage(commands::skill::SkillServiceState(Arc::new(skill_service)));
 
             // 初始化全局出站代理 HTTP 客户端
-            {
+            crate::init_status::time_startup_phase("http_client", false, || {
                 let db = &app.state::<AppState>().db;
-                let proxy_url = db.get_global_proxy_url().ok().flatten();
+
+                // 先载入 DNS 覆盖 / IPv6 优先设置，init 构建客户端时使用
+                let network_config = db.get_global_network_config().unwrap_or_default();
+                if let Err(e) = crate::proxy::http_client::validate_network_config(&network_config)
+                    .and_then(|()| crate::proxy::http_client::apply_network_config(network_config))
+                {
+                    log::warn!("[GlobalProxy] Ignoring invalid network config: {e}");
+                }
+
+                // 按保存的代理模式计算实际代理 URL（system 模式在此检测系统代理）
+                let proxy_mode = db.get_proxy_mode().unwrap_or_default();
+                if let Err(e) = crate::proxy::http_client::set_current_proxy_mode(proxy_mode) {
+                    log::warn!("[GlobalProxy] Failed to set proxy mode: {e}");
+                }
+                let proxy_url = crate::proxy::system_proxy::resolve_proxy_url(
+                    proxy_mode,
+                    db.get_global_proxy_url().ok().flatten(),
+                    &crate::proxy::system_proxy::OsProxyDetector,
+                );
 
                 if let Err(e) = crate::proxy::http_client::init(proxy_url.as_deref()) {
                     log::error!(
                         "[GlobalProxy] [GP-005] Failed to initialize with saved config: {e}"
                     );
 
-                    // 清除无效的代理配置
-                    if proxy_url.is_some() {
+                    // 清除无效的代理配置（system 模式下的地址来自检测，无需清除）
+                    if proxy_url.is_some()
+                        && proxy_mode == crate::proxy::types::ProxyMode::Manual
+                    {
                         log::warn!(
                             "[GlobalProxy] [GP-006] Clearing invalid proxy config from database"
                         );
@@ -715,12 +628,36 @@ pub fn run() {
                         );
                     }
                 }
+            });
+
+            // 静默启动：根据设置决定是否显示主窗口
+            // 在任何后台任务（导入、网络请求）开始前完成，保证窗口尽早出现
+            let settings = crate::settings::get_settings();
+            if let Some(window) = app.get_webview_window("main") {
+                if settings.silent_startup {
+                    // 静默启动模式：保持窗口隐藏
+                    let _ = window.hide();
+                    #[cfg(target_os = "windows")]
+                    let _ = window.set_skip_taskbar(true);
+                    #[cfg(target_os = "macos")]
+                    tray::apply_tray_policy(app.handle(), false);
+                    log::info!("静默启动模式：主窗口已隐藏");
+                } else {
+                    // 正常启动模式：显示窗口
+                    let _ = window.show();
+                    log::info!("正常启动模式：主窗口已显示");
+                }
             }
 
-            // 异常退出恢复 + 代理状态自动恢复
+            // 只读模式在窗口标题上标注
+            commands::apply_read_only_window_title(app.handle());
+            crate::init_status::mark_window_shown();
+
+            // 异常退出恢复 → 延后导入 → 代理状态自动恢复（顺序执行）
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 let state = app_handle.state::<AppState>();
+                let recovery_started = std::time::Instant::now();
 
                 // 检查是否有 Live 备份（表示上次异常退出时可能处于接管状态）
                 let has_backups = match state.db.has_any_live_backup().await {
@@ -735,54 +672,165 @@ pub fn run() {
 
                 if has_backups || live_taken_over {
                     log::warn!("检测到上次异常退出（存在接管残留），正在恢复 Live 配置...");
-                    if let Err(e) = state.proxy_service.recover_from_crash().await {
-                        log::error!("恢复 Live 配置失败: {e}");
-                    } else {
+                    let report = state.proxy_service.emergency_restore_all().await;
+                    let failed: Vec<_> = report
+                        .apps
+                        .iter()
+                        .filter(|app| app.still_taken_over || !app.errors.is_empty())
+                        .map(|app| app.app_type.as_str())
+                        .collect();
+                    if failed.is_empty() {
                         log::info!("Live 配置已恢复");
+                    } else {
+                        log::error!("恢复 Live 配置未完全成功: {failed:?}");
                     }
                 }
+                crate::init_status::record_startup_phase(
+                    "crash_recovery",
+                    recovery_started,
+                    recovery_started.elapsed(),
+                    true,
+                );
+
+                // 导入在恢复之后执行，避免把接管残留的占位配置当作用户配置导入
+                let import_handle = app_handle.clone();
+                if let Err(e) = tauri::async_runtime::spawn_blocking(move || {
+                    run_deferred_startup_imports(&import_handle)
+                })
+                .await
+                {
+                    log::error!("延后导入任务异常结束: {e}");
+                }
+                crate::init_status::set_initial_import_done();
+                tray::refresh_tray_menu(&app_handle);
+                if let Err(e) = app_handle.emit(
+                    crate::init_status::INITIAL_IMPORT_FINISHED_EVENT,
+                    crate::init_status::get_startup_status(),
+                ) {
+                    log::error!("发射 initial-import-finished 事件失败: {e}");
+                }
+
+                // 首次运行引导报告依赖导入结果（包含 CLI 检测，后台生成）
+                crate::services::OnboardingService::spawn_report(app_handle.clone());
+
+                // 凭据体检（只读，覆盖刚导入的供应商；发现的问题通过 credentials-audit 事件推送给前端）
+                {
+                    let handle = app_handle.clone();
+                    tauri::async_runtime::spawn_blocking(move || {
+                        let Some(state) = handle.try_state::<AppState>() else {
+                            return;
+                        };
+                        match crate::services::ProviderService::audit_credentials(&state) {
+                            Ok(issues) => {
+                                if !issues.is_empty() {
+                                    log::warn!("凭据体检发现 {} 个问题", issues.len());
+                                }
+                                if let Err(e) = handle.emit("credentials-audit", &issues) {
+                                    log::error!("发射 credentials-audit 事件失败: {e}");
+                                }
+                            }
+                            Err(e) => log::warn!("凭据体检失败: {e}"),
+                        }
+                    });
+                }
+
+                // 启动延迟：等待网络等依赖就绪后再恢复代理（不阻塞窗口显示）
+                let delay = crate::settings::get_startup_delay_seconds();
+                if delay > 0 {
+                    log::info!("启动延迟 {delay} 秒后恢复代理状态");
+                    tokio::time::sleep(std::time::Duration::from_secs(u64::from(delay))).await;
+                }
 
                 // 检查 settings 表中的代理状态，自动恢复代理服务
+                let restore_started = std::time::Instant::now();
                 restore_proxy_state_on_startup(&state).await;
+                crate::init_status::record_startup_phase(
+                    "proxy_restore",
+                    restore_started,
+                    restore_started.elapsed(),
+                    true,
+                );
             });
 
-            // 静默启动：根据设置决定是否显示主窗口
-            let settings = crate::settings::get_settings();
-            if let Some(window) = app.get_webview_window("main") {
-                if settings.silent_startup {
-                    // 静默启动模式：保持窗口隐藏
-                    let _ = window.hide();
-                    #[cfg(target_os = "windows")]
-                    let _ = window.set_skip_taskbar(true);
-                    #[cfg(target_os = "macos")]
-                    tray::apply_tray_policy(app.handle(), false);
-                    log::info!("静默启动模式：主窗口已隐藏");
+            // 流式健康检查定时任务
+            {
+                use crate::services::stream_check_scheduler::StreamCheckScheduler;
+
+                match app.state::<AppState>().db.get_stream_check_config() {
+                    Ok(config) => StreamCheckScheduler::reschedule(app.handle(), &config),
+                    Err(e) => log::warn!("加载流式检查配置失败，定时检查未启动: {e}"),
+                }
+            }
+
+            // 窗口主题：按计划时间或系统外观自动切换
+            theme::spawn_watcher(app.handle().clone());
+
+            // 全局快捷键（切换供应商 / 启停代理）
+            #[cfg(desktop)]
+            {
+                if let Err(e) = app.handle().plugin(hotkeys::plugin()) {
+                    log::warn!("初始化全局快捷键插件失败，已跳过：{e}");
                 } else {
-                    // 正常启动模式：显示窗口
-                    let _ = window.show();
-                    log::info!("正常启动模式：主窗口已显示");
+                    // 注册失败的绑定已在 register_all 中记录日志
+                    hotkeys::register_all(app.handle());
                 }
             }
 
+            // Codex 额度后台刷新（仅在代理运行时请求远端）
+            crate::services::codex_quota::CodexQuotaService::spawn_background_refresh(
+                app.handle().clone(),
+            );
+
+            // 请求日志自动清理（每日一次）
+            crate::services::LogRetentionService::spawn_daily_task(
+                app.state::<AppState>().db.clone(),
+            );
+
+            // 远程预设目录：缓存过期时后台刷新，离线不影响启动
+            crate::services::PresetCatalogService::spawn_startup_refresh(
+                app.state::<AppState>().db.clone(),
+            );
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_providers,
+            commands::get_providers_summary,
+            commands::get_provider_settings,
+            commands::search_providers,
+            commands::validate_provider_config,
             commands::get_current_provider,
             commands::add_provider,
             commands::update_provider,
             commands::delete_provider,
             commands::remove_provider_from_live_config,
+            commands::restore_live_config,
+            commands::list_live_config_history,
+            commands::restore_live_config_version,
+            commands::get_provider_audit_log,
+            commands::audit_credentials,
+            commands::get_provider_audit_retention_days,
+            commands::set_provider_audit_retention_days,
+            commands::validate_providers,
+            commands::repair_providers,
             commands::switch_provider,
+            commands::get_backfill_setting,
+            commands::set_backfill_setting,
             commands::antigravity_import_current_session,
+            commands::create_antigravity_provider,
             commands::antigravity_start_login,
             commands::antigravity_get_quota,
             commands::gemini_oauth_init_login,
             commands::gemini_oauth_poll_token,
             commands::codex_oauth_init_device_flow,
             commands::codex_oauth_poll_token,
+            commands::list_background_tasks,
+            commands::cancel_background_task,
             commands::codex_get_quota,
+            commands::get_codex_quota_ttl,
+            commands::set_codex_quota_ttl,
             commands::import_default_config,
+            commands::import_provider_from_env,
             commands::get_claude_config_status,
             commands::get_config_status,
             commands::get_claude_code_config_path,
@@ -793,6 +841,12 @@ pub fn run() {
             commands::get_init_error,
             commands::get_migration_result,
             commands::get_skills_migration_result,
+            commands::get_startup_status,
+            commands::get_startup_timings,
+            commands::run_preflight_checks,
+            commands::get_onboarding_report,
+            commands::rerun_initial_import,
+            commands::get_status_snapshot,
             commands::get_app_config_path,
             commands::open_app_config_folder,
             commands::get_claude_common_config_snippet,
@@ -800,13 +854,25 @@ pub fn run() {
             commands::get_common_config_snippet,
             commands::set_common_config_snippet,
             commands::extract_common_config_snippet,
+            commands::list_config_snippets,
+            commands::save_config_snippet,
+            commands::delete_config_snippet,
+            commands::apply_snippet_to_provider,
+            commands::apply_common_config_to_all,
+            commands::format_codex_config,
             commands::read_live_provider_settings,
             commands::get_settings,
             commands::save_settings,
+            commands::validate_override_dir,
             commands::get_rectifier_config,
             commands::set_rectifier_config,
             commands::get_log_config,
             commands::set_log_config,
+            commands::export_settings,
+            commands::import_settings,
+            commands::get_crash_log,
+            commands::clear_crash_log,
+            commands::get_app_log_tail,
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
@@ -836,27 +902,59 @@ pub fn run() {
             commands::delete_mcp_server,
             commands::toggle_mcp_app,
             commands::import_mcp_from_apps,
+            commands::import_mcp_from_claude_desktop,
+            commands::export_mcp_to_claude_desktop,
+            commands::get_mcp_profiles,
+            commands::upsert_mcp_profile,
+            commands::delete_mcp_profile,
+            commands::apply_mcp_profile,
+            commands::get_mcp_managed_apps,
+            commands::set_mcp_managed_apps,
             // Prompt management
             commands::get_prompts,
             commands::upsert_prompt,
             commands::delete_prompt,
             commands::enable_prompt,
+            commands::reorder_prompts,
             commands::import_prompt_from_file,
             commands::get_current_prompt_file_content,
+            commands::import_prompt_markdown,
+            commands::export_prompt_markdown,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            commands::get_endpoint_latency_history,
+            commands::fetch_provider_metadata,
+            commands::refresh_provider_presets,
+            commands::get_provider_presets,
+            commands::resolve_provider_icon,
             commands::get_custom_endpoints,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
             commands::update_endpoint_last_used,
+            commands::dedupe_custom_endpoints,
             // app_config_dir override via Store
             commands::get_app_config_dir_override,
             commands::set_app_config_dir_override,
+            commands::relocate_config_dir,
             // provider sort order management
             commands::update_providers_sort_order,
+            commands::normalize_claude_models,
+            commands::get_provider_cost_multiplier,
+            commands::set_provider_cost_multiplier,
             // theirs: config import/export and dialogs
             commands::export_config_to_file,
+            commands::export_config_encrypted,
             commands::import_config_from_file,
+            commands::preview_import_config,
+            commands::apply_import_config,
+            commands::export_bundle,
+            commands::import_bundle,
+            commands::reimport_from_archived_json,
+            commands::import_from_external_format,
+            commands::get_sync_config,
+            commands::set_sync_config,
+            commands::sync_push,
+            commands::sync_pull,
             commands::save_file_dialog,
             commands::open_file_dialog,
             commands::open_zip_file_dialog,
@@ -864,6 +962,7 @@ pub fn run() {
             // Deep link import
             commands::parse_deeplink,
             commands::merge_deeplink_config,
+            commands::validate_deeplink,
             commands::import_from_deeplink,
             commands::import_from_deeplink_unified,
             update_tray_menu,
@@ -871,11 +970,15 @@ pub fn run() {
             commands::check_env_conflicts,
             commands::delete_env_vars,
             commands::restore_env_backup,
+            commands::auto_resolve_env_conflicts,
             // Skill management (v3.10.0+ unified)
             commands::get_installed_skills,
             commands::install_skill_unified,
+            commands::install_skills_from_repo,
             commands::uninstall_skill_unified,
             commands::toggle_skill_app,
+            commands::get_skill_enablement_matrix,
+            commands::set_skill_enablement,
             commands::scan_unmanaged_skills,
             commands::import_skills_from_apps,
             commands::discover_available_skills,
@@ -889,13 +992,21 @@ pub fn run() {
             commands::get_skill_repos,
             commands::add_skill_repo,
             commands::remove_skill_repo,
+            commands::set_skill_repo_token,
             commands::install_skills_from_zip,
             // Auto launch
             commands::set_auto_launch,
             commands::get_auto_launch_status,
+            commands::get_startup_delay,
+            commands::set_startup_delay,
+            commands::get_read_only_mode,
+            commands::set_read_only_mode,
+            commands::set_language,
             // Proxy server management
             commands::start_proxy_server,
             commands::stop_proxy_with_restore,
+            commands::stop_proxy_graceful,
+            commands::emergency_restore_all,
             commands::get_proxy_takeover_status,
             commands::set_proxy_takeover_for_app,
             commands::get_proxy_status,
@@ -913,12 +1024,20 @@ pub fn run() {
             commands::is_proxy_running,
             commands::is_live_takeover_active,
             commands::switch_proxy_provider,
+            commands::replay_proxy_request,
+            commands::get_request_as_curl,
+            commands::get_local_api_token,
+            commands::get_local_api_enabled,
+            commands::set_local_api_enabled,
             // Proxy failover commands
             commands::get_provider_health,
             commands::reset_circuit_breaker,
+            commands::set_provider_circuit_state,
             commands::get_circuit_breaker_config,
             commands::update_circuit_breaker_config,
             commands::get_circuit_breaker_stats,
+            commands::get_circuit_breaker_history,
+            commands::simulate_failover,
             // Failover queue management
             commands::get_failover_queue,
             commands::get_available_providers_for_failover,
@@ -930,23 +1049,32 @@ pub fn run() {
             commands::get_usage_summary,
             commands::get_usage_trends,
             commands::get_provider_stats,
+            commands::fetch_usd_exchange_rate,
             commands::get_model_stats,
+            commands::get_provider_cost_comparison,
             commands::get_request_logs,
             commands::get_request_detail,
             commands::get_model_pricing,
+            commands::estimate_request_cost,
             commands::update_model_pricing,
             commands::delete_model_pricing,
             commands::check_provider_limits,
+            commands::prune_request_logs,
+            commands::get_database_stats,
+            commands::get_log_retention_config,
+            commands::set_log_retention_config,
             // Stream health check
             commands::stream_check_provider,
             commands::stream_check_all_providers,
             commands::get_stream_check_config,
             commands::save_stream_check_config,
+            commands::get_stream_check_history,
             // Session manager
             commands::list_sessions,
             commands::get_session_messages,
             commands::launch_session_terminal,
             commands::get_tool_versions,
+            commands::update_tool,
             commands::restart_codex_cli,
             commands::restart_codex_app,
             // Provider terminal
@@ -957,6 +1085,8 @@ pub fn run() {
             commands::upsert_universal_provider,
             commands::delete_universal_provider,
             commands::sync_universal_provider,
+            commands::find_orphaned_universal_children,
+            commands::cleanup_orphaned_universal_children,
             // OpenCode specific
             commands::import_opencode_providers_from_live,
             commands::get_opencode_live_provider_ids,
@@ -964,10 +1094,19 @@ pub fn run() {
             commands::get_global_proxy_url,
             commands::set_global_proxy_url,
             commands::test_proxy_url,
+            commands::get_global_network_config,
+            commands::set_global_network_config,
             commands::get_upstream_proxy_status,
+            commands::get_proxy_mode,
+            commands::set_proxy_mode,
+            commands::refresh_system_proxy,
             commands::scan_local_proxies,
             // Window theme control
             commands::set_window_theme,
+            commands::get_theme_config,
+            commands::set_theme_config,
+            commands::get_hotkey_config,
+            commands::set_hotkey_config,
         ]);
 
     let app = builder
@@ -1040,13 +1179,9 @@ pub fn run() {
                                         "Failed to parse deep link URL from RunEvent::Opened: {e}"
                                     );
 
-                                    if let Err(emit_err) = app_handle.emit(
-                                        "deeplink-error",
-                                        serde_json::json!({
-                                            "url": url_str,
-                                            "error": e.to_string()
-                                        }),
-                                    ) {
+                                    if let Err(emit_err) = app_handle
+                                        .emit("deeplink-error", e.event_payload(&url_str))
+                                    {
                                         log::error!(
                                             "Failed to emit deep link error event from RunEvent::Opened: {emit_err}"
                                         );
@@ -1078,12 +1213,18 @@ pub fn run() {
 // 应用退出清理
 // ============================================================
 
+/// 退出时等待进行中请求完成的最长时间
+const EXIT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// 应用退出前的清理工作
 ///
 /// 在应用退出前检查代理服务器状态，如果正在运行则停止代理并恢复 Live 配置。
 /// 确保 Claude Code/Codex/Gemini 的配置不会处于损坏状态。
 /// 使用 stop_with_restore_keep_state 保留 settings 表中的代理状态，下次启动时自动恢复。
+/// 停止前给进行中的请求留出短暂的排空时间，避免直接截断流式响应。
 pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
+    crate::services::stream_check_scheduler::StreamCheckScheduler::shutdown();
+
     if let Some(state) = app_handle.try_state::<store::AppState>() {
         let proxy_service = &state.proxy_service;
 
@@ -1101,7 +1242,10 @@ pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
         if needs_restore {
             log::info!("检测到接管残留，开始恢复 Live 配置（保留代理状态）...");
             // 使用 keep_state 版本，保留 settings 表中的代理状态
-            if let Err(e) = proxy_service.stop_with_restore_keep_state().await {
+            if let Err(e) = proxy_service
+                .stop_with_restore_keep_state(Some(EXIT_DRAIN_TIMEOUT))
+                .await
+            {
                 log::error!("退出时恢复 Live 配置失败: {e}");
             } else {
                 log::info!("已恢复 Live 配置（代理状态已保留，下次启动将自动恢复）");
@@ -1112,7 +1256,10 @@ pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
         // 非接管模式：代理在运行则仅停止代理
         if proxy_service.is_running().await {
             log::info!("检测到代理服务器正在运行，开始停止...");
-            if let Err(e) = proxy_service.stop().await {
+            if let Err(e) = proxy_service
+                .stop_with_grace(Some(EXIT_DRAIN_TIMEOUT))
+                .await
+            {
                 log::error!("退出时停止代理失败: {e}");
             }
             log::info!("代理服务器清理完成");
@@ -1120,6 +1267,106 @@ pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
     }
 }
 
+// ============================================================
+// 启动时延后执行的导入
+// ============================================================
+
+/// 默认数据初始化与各类导入（在窗口显示后于后台执行）
+///
+/// 按表独立判断，各类数据独立检查、互不影响；每一步的耗时记录到启动耗时报告。
+fn run_deferred_startup_imports(app: &tauri::AppHandle) {
+    use crate::init_status::time_startup_phase;
+
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let state = state.inner();
+
+    // 1. 初始化默认 Skills 仓库（已有内置检查：表非空则跳过）
+    time_startup_phase("default_skill_repos", true, || {
+        match state.db.init_default_skill_repos() {
+            Ok(count) if count > 0 => {
+                log::info!("✓ Initialized {count} default skill repositories");
+            }
+            Ok(_) => {} // 表非空，静默跳过
+            Err(e) => log::warn!("✗ Failed to initialize default skill repos: {e}"),
+        }
+    });
+
+    // 1.1. Skills 统一管理迁移：当数据库迁移到 v3 结构后，自动从各应用目录导入到 SSOT
+    // 触发条件由 schema 迁移设置 settings.skills_ssot_migration_pending = true 控制。
+    time_startup_phase("skills_ssot_migration", true, || {
+        match state.db.get_setting("skills_ssot_migration_pending") {
+            Ok(Some(flag)) if flag == "true" || flag == "1" => {
+                // 安全保护：如果用户已经有 v3 结构的 Skills 数据，就不要自动清空重建。
+                let has_existing = state
+                    .db
+                    .get_all_installed_skills()
+                    .map(|skills| !skills.is_empty())
+                    .unwrap_or(false);
+
+                if has_existing {
+                    log::info!(
+                        "Detected skills_ssot_migration_pending but skills table not empty; skipping auto import."
+                    );
+                    let _ = state
+                        .db
+                        .set_setting("skills_ssot_migration_pending", "false");
+                } else {
+                    match crate::services::skill::migrate_skills_to_ssot(&state.db) {
+                        Ok(count) => {
+                            log::info!("✓ Auto imported {count} skill(s) into SSOT");
+                            if count > 0 {
+                                crate::init_status::set_skills_migration_result(count);
+                            }
+                            let _ = state
+                                .db
+                                .set_setting("skills_ssot_migration_pending", "false");
+                        }
+                        Err(e) => {
+                            log::warn!("✗ Failed to auto import legacy skills to SSOT: {e}");
+                            crate::init_status::set_skills_migration_error(e.to_string());
+                            // 保留 pending 标志，方便下次启动重试
+                        }
+                    }
+                }
+            }
+            Ok(_) => {} // 未开启迁移标志，静默跳过
+            Err(e) => log::warn!("✗ Failed to read skills migration flag: {e}"),
+        }
+    });
+
+    // 1.2. Claude 模型键迁移：旧版本保存的供应商可能仍使用 ANTHROPIC_SMALL_FAST_MODEL，
+    // 启动时统一迁移一次，完成后写入 settings.claude_models_normalized = true。
+    time_startup_phase("claude_models_normalize", true, || {
+        match state.db.get_setting("claude_models_normalized") {
+            Ok(Some(flag)) if flag == "true" || flag == "1" => {}
+            Ok(_) => {
+                match crate::services::provider::ProviderService::normalize_all_claude_providers(
+                    state,
+                ) {
+                    Ok(count) => {
+                        if count > 0 {
+                            log::info!("✓ Normalized model keys for {count} Claude provider(s)");
+                        }
+                        let _ = state.db.set_setting("claude_models_normalized", "true");
+                    }
+                    Err(e) => {
+                        log::warn!("✗ Failed to normalize Claude provider models: {e}");
+                        // 保留标志未设置，方便下次启动重试
+                    }
+                }
+            }
+            Err(e) => log::warn!("✗ Failed to read Claude models migration flag: {e}"),
+        }
+    });
+
+    // 2. 导入供应商 / MCP / 提示词（含 OpenCode Live 解析；结果记录到 init_status 供引导报告使用）
+    time_startup_phase("initial_imports", true, || {
+        crate::services::OnboardingService::run_initial_imports(state)
+    });
+}
+
 // ============================================================
 // 启动时恢复代理状态
 // ============================================================
@@ -1131,7 +1378,7 @@ pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
 async fn restore_proxy_state_on_startup(state: &store::AppState) {
     // 收集需要恢复接管的应用列表（从 proxy_config.enabled 读取）
     let mut apps_to_restore = Vec::new();
-    for app_type in ["claude", "codex", "gemini"] {
+    for app_type in ["claude", "codex", "gemini", "opencode"] {
         if let Ok(config) = state.db.get_proxy_config_for_app(app_type).await {
             if config.enabled {
                 apps_to_restore.push(app_type);
@@ -1141,12 +1388,14 @@ async fn restore_proxy_state_on_startup(state: &store::AppState) {
 
     if apps_to_restore.is_empty() {
         log::debug!("启动时无需恢复代理状态");
+        crate::init_status::set_proxy_restore_result(Vec::new());
         return;
     }
 
     log::info!("检测到上次代理状态需要恢复，应用列表: {apps_to_restore:?}");
 
     // 逐个恢复接管状态
+    let mut restored = Vec::new();
     for app_type in apps_to_restore {
         match state
             .proxy_service
@@ -1155,6 +1404,7 @@ async fn restore_proxy_state_on_startup(state: &store::AppState) {
         {
             Ok(()) => {
                 log::info!("✓ 已恢复 {app_type} 的代理接管状态");
+                restored.push(app_type.to_string());
             }
             Err(e) => {
                 log::error!("✗ 恢复 {app_type} 的代理接管状态失败: {e}");
@@ -1169,31 +1419,24 @@ async fn restore_proxy_state_on_startup(state: &store::AppState) {
             }
         }
     }
+
+    crate::init_status::set_proxy_restore_result(restored);
 }
 
 // ============================================================
 // 迁移错误对话框辅助函数
 // ============================================================
 
-/// 检测是否为中文环境
-fn is_chinese_locale() -> bool {
-    std::env::var("LANG")
-        .or_else(|_| std::env::var("LC_ALL"))
-        .or_else(|_| std::env::var("LC_MESSAGES"))
-        .map(|lang| lang.starts_with("zh"))
-        .unwrap_or(false)
-}
-
 /// 显示迁移错误对话框
 /// 返回 true 表示用户选择重试，false 表示用户选择退出
 fn show_migration_error_dialog(app: &tauri::AppHandle, error: &str) -> bool {
-    let title = if is_chinese_locale() {
+    let title = if crate::settings::is_chinese_ui() {
         "配置迁移失败"
     } else {
         "Migration Failed"
     };
 
-    let message = if is_chinese_locale() {
+    let message = if crate::settings::is_chinese_ui() {
         format!(
             "从旧版本迁移配置时发生错误：\n\n{error}\n\n\
             您的数据尚未丢失，旧配置文件仍然保留。\n\
@@ -1211,12 +1454,12 @@ fn show_migration_error_dialog(app: &tauri::AppHandle, error: &str) -> bool {
         )
     };
 
-    let retry_text = if is_chinese_locale() {
+    let retry_text = if crate::settings::is_chinese_ui() {
         "重试"
     } else {
         "Retry"
     };
-    let exit_text = if is_chinese_locale() {
+    let exit_text = if crate::settings::is_chinese_ui() {
         "退出"
     } else {
         "Exit"
@@ -1242,13 +1485,13 @@ fn show_database_init_error_dialog(
     db_path: &std::path::Path,
     error: &str,
 ) -> bool {
-    let title = if is_chinese_locale() {
+    let title = if crate::settings::is_chinese_ui() {
         "数据库初始化失败"
     } else {
         "Database Initialization Failed"
     };
 
-    let message = if is_chinese_locale() {
+    let message = if crate::settings::is_chinese_ui() {
         format!(
             "初始化数据库或迁移数据库结构时发生错误：\n\n{error}\n\n\
             数据库文件路径：\n{db}\n\n\
@@ -1278,12 +1521,12 @@ fn show_database_init_error_dialog(
         )
     };
 
-    let retry_text = if is_chinese_locale() {
+    let retry_text = if crate::settings::is_chinese_ui() {
         "重试"
     } else {
         "Retry"
     };
-    let exit_text = if is_chinese_locale() {
+    let exit_text = if crate::settings::is_chinese_ui() {
         "退出"
     } else {
         "Exit"
@@ -0,0 +1,180 @@
+//! 按模块的运行时日志级别过滤
+//!
+//! `log::set_max_level` 只能设置一个全局级别。为了支持 `proxy=debug, tray=warn` 这类
+//! 按模块覆盖，日志插件初始化时挂载 [`allows`] 作为过滤钩子：
+//! - 全局级别作为默认值；
+//! - 模块覆盖按 target 前缀匹配（最长前缀优先），优先于全局级别；
+//! - `log::max_level` 取全局级别与所有覆盖中的最大值，避免覆盖为 debug 的记录被提前丢弃。
+
+use crate::proxy::types::LogConfig;
+use log::{LevelFilter, Metadata};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+/// 本 crate 日志 target 的前缀（模块覆盖可省略该前缀，如 `proxy` 即 `cc_switch_lib::proxy`）
+const CRATE_TARGET_PREFIX: &str = "cc_switch_lib::";
+
+#[derive(Debug)]
+struct FilterState {
+    global: LevelFilter,
+    /// (target 前缀, 级别)，按前缀长度降序排列
+    modules: Vec<(String, LevelFilter)>,
+}
+
+static FILTER_STATE: Lazy<RwLock<FilterState>> = Lazy::new(|| {
+    RwLock::new(FilterState {
+        global: LevelFilter::Trace,
+        modules: Vec::new(),
+    })
+});
+
+/// 解析日志级别字符串
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.trim().to_lowercase().as_str() {
+        "off" => Some(LevelFilter::Off),
+        "error" => Some(LevelFilter::Error),
+        "warn" => Some(LevelFilter::Warn),
+        "info" => Some(LevelFilter::Info),
+        "debug" => Some(LevelFilter::Debug),
+        "trace" => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// 校验模块级别配置，返回第一个无效项的错误信息
+pub fn validate(config: &LogConfig) -> Result<(), String> {
+    for (module, level) in &config.module_levels {
+        if normalize_module(module).is_empty() {
+            return Err("日志模块名不能为空".to_string());
+        }
+        if parse_level(level).is_none() {
+            return Err(format!("模块 {module} 的日志级别无效: {level}"));
+        }
+    }
+    Ok(())
+}
+
+/// 应用日志配置（全局级别 + 模块覆盖）
+pub fn apply(config: &LogConfig) {
+    let global = config.to_level_filter();
+    let mut modules: Vec<(String, LevelFilter)> = if config.enabled {
+        config
+            .module_levels
+            .iter()
+            .filter_map(|(module, level)| {
+                let module = normalize_module(module);
+                if module.is_empty() {
+                    return None;
+                }
+                Some((module.to_string(), parse_level(level)?))
+            })
+            .collect()
+    } else {
+        // 总开关关闭时模块覆盖也不生效
+        Vec::new()
+    };
+    modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    let max = modules
+        .iter()
+        .map(|(_, level)| *level)
+        .fold(global, LevelFilter::max);
+
+    // 状态整体替换，锁毒化不影响一致性，直接恢复（此处不能记日志：日志本身经过该过滤器）
+    {
+        let mut state = FILTER_STATE.write().unwrap_or_else(|e| e.into_inner());
+        state.global = global;
+        state.modules = modules;
+    }
+    log::set_max_level(max);
+}
+
+/// 日志插件的过滤钩子
+pub fn allows(metadata: &Metadata) -> bool {
+    let state = FILTER_STATE.read().unwrap_or_else(|e| e.into_inner());
+    metadata.level() <= level_for_target(&state, metadata.target())
+}
+
+fn level_for_target(state: &FilterState, target: &str) -> LevelFilter {
+    let short = target.strip_prefix(CRATE_TARGET_PREFIX).unwrap_or(target);
+    state
+        .modules
+        .iter()
+        .find(|(prefix, _)| matches_prefix(short, prefix) || matches_prefix(target, prefix))
+        .map(|(_, level)| *level)
+        .unwrap_or(state.global)
+}
+
+/// target 等于前缀，或以 `前缀::` 开头
+fn matches_prefix(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+fn normalize_module(module: &str) -> &str {
+    module.trim().trim_end_matches("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn state(global: LevelFilter, modules: &[(&str, LevelFilter)]) -> FilterState {
+        let mut modules: Vec<(String, LevelFilter)> =
+            modules.iter().map(|(m, l)| (m.to_string(), *l)).collect();
+        modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        FilterState { global, modules }
+    }
+
+    #[test]
+    fn module_overrides_win_over_global_level() {
+        let state = state(
+            LevelFilter::Info,
+            &[
+                ("proxy", LevelFilter::Debug),
+                ("proxy::forwarder", LevelFilter::Trace),
+                ("tray", LevelFilter::Warn),
+                ("hyper", LevelFilter::Off),
+            ],
+        );
+
+        assert_eq!(
+            level_for_target(&state, "cc_switch_lib::proxy::server"),
+            LevelFilter::Debug
+        );
+        assert_eq!(
+            level_for_target(&state, "cc_switch_lib::proxy::forwarder"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            level_for_target(&state, "cc_switch_lib::tray"),
+            LevelFilter::Warn
+        );
+        assert_eq!(level_for_target(&state, "hyper::client"), LevelFilter::Off);
+        // 前缀需按模块边界匹配
+        assert_eq!(
+            level_for_target(&state, "cc_switch_lib::proxy_config"),
+            LevelFilter::Info
+        );
+        assert_eq!(
+            level_for_target(&state, "cc_switch_lib::deeplink"),
+            LevelFilter::Info
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_levels() {
+        let mut config = LogConfig {
+            module_levels: BTreeMap::from([("proxy".to_string(), "debug".to_string())]),
+            ..Default::default()
+        };
+        assert!(validate(&config).is_ok());
+
+        config
+            .module_levels
+            .insert("tray".to_string(), "verbose".to_string());
+        assert!(validate(&config).is_err());
+    }
+}
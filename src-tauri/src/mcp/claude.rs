@@ -3,7 +3,7 @@
 use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::app_config::{McpApps, McpConfig, McpServer, MultiAppConfig};
+use crate::app_config::{AppType, McpApps, McpConfig, McpServer, MultiAppConfig};
 use crate::error::AppError;
 
 use super::validation::{extract_server_spec, validate_server_spec};
@@ -11,7 +11,10 @@ use super::validation::{extract_server_spec, validate_server_spec};
 fn should_sync_claude_mcp() -> bool {
     // Claude 未安装/未初始化时：通常 ~/.claude 目录与 ~/.claude.json 都不存在。
     // 按用户偏好：此时跳过写入/删除，不创建任何文件或目录。
-    crate::config::get_claude_config_dir().exists() || crate::config::get_claude_mcp_path().exists()
+    // 用户关闭 Claude 的 MCP 管理时同样不读写其 MCP 配置。
+    crate::settings::is_mcp_managed(&AppType::Claude)
+        && (crate::config::get_claude_config_dir().exists()
+            || crate::config::get_claude_mcp_path().exists())
 }
 
 /// 返回已启用的 MCP 服务器（过滤 enabled==true）
@@ -42,6 +45,7 @@ pub fn sync_enabled_to_claude(config: &MultiAppConfig) -> Result<(), AppError> {
     if !should_sync_claude_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     let enabled = collect_enabled_servers(&config.mcp.claude);
     crate::claude_mcp::set_mcp_servers_map(&enabled)
 }
@@ -120,6 +124,7 @@ pub fn sync_single_server_to_claude(
     if !should_sync_claude_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     // 读取现有的 MCP 配置
     let current = crate::claude_mcp::read_mcp_servers_map()?;
 
@@ -136,6 +141,7 @@ pub fn remove_server_from_claude(id: &str) -> Result<(), AppError> {
     if !should_sync_claude_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     // 读取现有的 MCP 配置
     let mut current = crate::claude_mcp::read_mcp_servers_map()?;
 
@@ -0,0 +1,180 @@
+//! Claude Desktop `claude_desktop_config.json` 格式转换模块
+//!
+//! Claude Desktop 只支持 stdio 服务器，条目形如 `{ command, args, env }`：
+//! - 导入：保留 command/args/env/cwd，远程（sse/http）条目跳过并给出警告
+//! - 导出：仅输出 Desktop 认识的字段，远程服务器跳过并给出警告
+
+use serde_json::{json, Map, Value};
+use std::path::PathBuf;
+
+use crate::error::AppError;
+
+use super::validation::validate_server_spec;
+
+/// Claude Desktop 配置文件名
+const DESKTOP_CONFIG_FILE: &str = "claude_desktop_config.json";
+
+/// Desktop stdio 条目支持的字段
+const DESKTOP_STDIO_FIELDS: [&str; 4] = ["command", "args", "env", "cwd"];
+
+/// Claude Desktop 配置文件的默认路径
+///
+/// - Windows: `%APPDATA%\Claude\claude_desktop_config.json`
+/// - macOS: `~/Library/Application Support/Claude/claude_desktop_config.json`
+/// - Linux: `~/.config/Claude/claude_desktop_config.json`
+pub fn default_desktop_config_path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(appdata) = std::env::var_os("APPDATA").filter(|v| !v.is_empty()) {
+            return Some(
+                PathBuf::from(appdata)
+                    .join("Claude")
+                    .join(DESKTOP_CONFIG_FILE),
+            );
+        }
+    }
+    dirs::config_dir().map(|dir| dir.join("Claude").join(DESKTOP_CONFIG_FILE))
+}
+
+/// 判断条目是否为远程服务器（Desktop 不支持）
+fn is_remote_spec(spec: &Value) -> bool {
+    match spec.get("type").and_then(|t| t.as_str()) {
+        Some("sse") | Some("http") => true,
+        Some(_) => false,
+        None => spec.get("url").is_some() && spec.get("command").is_none(),
+    }
+}
+
+/// 解析 Desktop 配置中的 `mcpServers`，返回 (名称, 统一结构 spec) 列表与警告
+pub fn parse_desktop_servers(
+    config: &Value,
+) -> Result<(Vec<(String, Value)>, Vec<String>), AppError> {
+    let map = config
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| AppError::McpValidation("Claude Desktop 配置缺少 mcpServers 对象".into()))?;
+
+    let mut servers = Vec::new();
+    let mut warnings = Vec::new();
+    for (name, entry) in map {
+        if is_remote_spec(entry) {
+            warnings.push(format!(
+                "{name}: Claude Desktop 不支持远程（sse/http）服务器，已跳过"
+            ));
+            continue;
+        }
+
+        let Some(obj) = entry.as_object() else {
+            warnings.push(format!("{name}: 条目必须为 JSON 对象，已跳过"));
+            continue;
+        };
+        let mut spec = Map::new();
+        spec.insert("type".into(), json!("stdio"));
+        for field in DESKTOP_STDIO_FIELDS {
+            if let Some(value) = obj.get(field) {
+                spec.insert(field.into(), value.clone());
+            }
+        }
+        let spec = Value::Object(spec);
+
+        if let Err(e) = validate_server_spec(&spec) {
+            warnings.push(format!("{name}: {e}"));
+            continue;
+        }
+        servers.push((name.clone(), spec));
+    }
+
+    Ok((servers, warnings))
+}
+
+/// 将统一结构 spec 转换为 Desktop 条目（远程服务器返回错误说明）
+pub fn convert_to_desktop_entry(spec: &Value) -> Result<Value, String> {
+    if is_remote_spec(spec) {
+        return Err("Claude Desktop 不支持远程（sse/http）服务器".to_string());
+    }
+    validate_server_spec(spec).map_err(|e| e.to_string())?;
+
+    let mut entry = Map::new();
+    for field in DESKTOP_STDIO_FIELDS {
+        match spec.get(field) {
+            // 空的 args/env 省略，保持与手写配置一致
+            Some(Value::Array(a)) if a.is_empty() => {}
+            Some(Value::Object(o)) if o.is_empty() => {}
+            Some(value) => {
+                entry.insert(field.into(), value.clone());
+            }
+            None => {}
+        }
+    }
+    Ok(Value::Object(entry))
+}
+
+/// 生成不与已有名称冲突的新名称（`name-2`、`name-3`…）
+pub fn unique_name(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(base) {
+        return base.to_string();
+    }
+    let mut n = 2;
+    loop {
+        let candidate = format!("{base}-{n}");
+        if !taken(&candidate) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop_servers_skips_remote() {
+        let config = json!({
+            "mcpServers": {
+                "filesystem": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"],
+                    "env": { "DEBUG": "1" }
+                },
+                "remote": { "type": "sse", "url": "https://example.com/sse" },
+                "broken": { "args": ["x"] }
+            },
+            "globalShortcut": "Ctrl+Space"
+        });
+
+        let (servers, warnings) = parse_desktop_servers(&config).unwrap();
+        assert_eq!(servers.len(), 1);
+        let (name, spec) = &servers[0];
+        assert_eq!(name, "filesystem");
+        assert_eq!(spec["type"], "stdio");
+        assert_eq!(spec["args"][2], "/tmp");
+        assert_eq!(spec["env"]["DEBUG"], "1");
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_convert_to_desktop_entry() {
+        let spec = json!({
+            "type": "stdio",
+            "command": "uvx",
+            "args": ["mcp-server-git"],
+            "env": {}
+        });
+        let entry = convert_to_desktop_entry(&spec).unwrap();
+        assert_eq!(
+            entry,
+            json!({ "command": "uvx", "args": ["mcp-server-git"] })
+        );
+
+        let remote = json!({ "type": "http", "url": "https://example.com/mcp" });
+        assert!(convert_to_desktop_entry(&remote).is_err());
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let taken = ["fs", "fs-2"];
+        assert_eq!(unique_name("git", |n| taken.contains(&n)), "git");
+        assert_eq!(unique_name("fs", |n| taken.contains(&n)), "fs-3");
+    }
+}
@@ -8,7 +8,7 @@
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
-use crate::app_config::{McpApps, McpConfig, McpServer, MultiAppConfig};
+use crate::app_config::{AppType, McpApps, McpConfig, McpServer, MultiAppConfig};
 use crate::error::AppError;
 
 use super::validation::{extract_server_spec, validate_server_spec};
@@ -16,7 +16,9 @@ use super::validation::{extract_server_spec, validate_server_spec};
 fn should_sync_codex_mcp() -> bool {
     // Codex 未安装/未初始化时：~/.codex 目录不存在。
     // 按用户偏好：目录缺失时跳过写入/删除，不创建任何文件或目录。
-    crate::codex_config::get_codex_config_dir().exists()
+    // 用户关闭 Codex 的 MCP 管理时同样不读写其 MCP 配置。
+    crate::settings::is_mcp_managed(&AppType::Codex)
+        && crate::codex_config::get_codex_config_dir().exists()
 }
 
 /// 返回已启用的 MCP 服务器（过滤 enabled==true）
@@ -283,6 +285,7 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), AppError> {
     if !should_sync_codex_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     use toml_edit::{Item, Table};
 
     // 1) 收集启用项（Codex 维度）
@@ -352,6 +355,7 @@ pub fn sync_single_server_to_codex(
     if !should_sync_codex_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     use toml_edit::Item;
 
     // 读取现有的 config.toml
@@ -406,6 +410,7 @@ pub fn remove_server_from_codex(id: &str) -> Result<(), AppError> {
     if !should_sync_codex_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     let config_path = crate::codex_config::get_codex_config_path();
 
     if !config_path.exists() {
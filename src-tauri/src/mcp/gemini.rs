@@ -3,7 +3,7 @@
 use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::app_config::{McpApps, McpConfig, McpServer, MultiAppConfig};
+use crate::app_config::{AppType, McpApps, McpConfig, McpServer, MultiAppConfig};
 use crate::error::AppError;
 
 use super::validation::{extract_server_spec, validate_server_spec};
@@ -11,7 +11,9 @@ use super::validation::{extract_server_spec, validate_server_spec};
 fn should_sync_gemini_mcp() -> bool {
     // Gemini 未安装/未初始化时：~/.gemini 目录不存在。
     // 按用户偏好：目录缺失时跳过写入/删除，不创建任何文件或目录。
-    crate::gemini_config::get_gemini_dir().exists()
+    // 用户关闭 Gemini 的 MCP 管理时同样不读写其 MCP 配置。
+    crate::settings::is_mcp_managed(&AppType::Gemini)
+        && crate::gemini_config::get_gemini_dir().exists()
 }
 
 /// 返回已启用的 MCP 服务器（过滤 enabled==true）
@@ -42,6 +44,7 @@ pub fn sync_enabled_to_gemini(config: &MultiAppConfig) -> Result<(), AppError> {
     if !should_sync_gemini_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     let enabled = collect_enabled_servers(&config.mcp.gemini);
     crate::gemini_mcp::set_mcp_servers_map(&enabled)
 }
@@ -116,6 +119,7 @@ pub fn sync_single_server_to_gemini(
     if !should_sync_gemini_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     // 读取现有的 MCP 配置
     let mut current = crate::gemini_mcp::read_mcp_servers_map()?;
 
@@ -131,6 +135,7 @@ pub fn remove_server_from_gemini(id: &str) -> Result<(), AppError> {
     if !should_sync_gemini_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
     // 读取现有的 MCP 配置
     let mut current = crate::gemini_mcp::read_mcp_servers_map()?;
 
@@ -6,11 +6,13 @@
 //!
 //! - `validation` - 服务器配置验证
 //! - `claude` - Claude MCP 同步和导入
+//! - `claude_desktop` - Claude Desktop 配置格式的导入导出转换
 //! - `codex` - Codex MCP 同步和导入（含 TOML 转换）
 //! - `gemini` - Gemini MCP 同步和导入
 //! - `opencode` - OpenCode MCP 同步和导入（含 local/remote 格式转换）
 
 mod claude;
+mod claude_desktop;
 mod codex;
 mod gemini;
 mod opencode;
@@ -21,6 +23,9 @@ pub use claude::{
     import_from_claude, remove_server_from_claude, sync_enabled_to_claude,
     sync_single_server_to_claude,
 };
+pub use claude_desktop::{
+    convert_to_desktop_entry, default_desktop_config_path, parse_desktop_servers, unique_name,
+};
 pub use codex::{
     import_from_codex, remove_server_from_codex, sync_enabled_to_codex, sync_single_server_to_codex,
 };
@@ -15,7 +15,7 @@
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
-use crate::app_config::{McpApps, McpServer, MultiAppConfig};
+use crate::app_config::{AppType, McpApps, McpServer, MultiAppConfig};
 use crate::error::AppError;
 use crate::opencode_config;
 
@@ -27,8 +27,9 @@ use super::validation::validate_server_spec;
 
 /// Check if OpenCode MCP sync should proceed
 fn should_sync_opencode_mcp() -> bool {
-    // Skip if OpenCode config directory doesn't exist
-    opencode_config::get_opencode_dir().exists()
+    // Skip if OpenCode config directory doesn't exist, or the user opted out of MCP management
+    crate::settings::is_mcp_managed(&AppType::OpenCode)
+        && opencode_config::get_opencode_dir().exists()
 }
 
 // ============================================================================
@@ -189,6 +190,7 @@ pub fn sync_single_server_to_opencode(
     if !should_sync_opencode_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
 
     // Convert to OpenCode format
     let opencode_spec = convert_to_opencode_format(server_spec)?;
@@ -202,6 +204,7 @@ pub fn remove_server_from_opencode(id: &str) -> Result<(), AppError> {
     if !should_sync_opencode_mcp() {
         return Ok(());
     }
+    crate::settings::ensure_live_writable()?;
 
     opencode_config::remove_mcp_server(id)
 }
@@ -3,10 +3,11 @@
 //! 在应用崩溃时捕获 panic 信息并记录到 `<app_config_dir>/crash.log` 文件中（默认 `~/.cc-switch/crash.log`）。
 //! 便于用户和开发者诊断闪退问题。
 
-use std::fs::OpenOptions;
-use std::io::Write;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 /// 应用版本号（从 Cargo.toml 读取）
@@ -43,6 +44,96 @@ pub fn get_log_dir() -> PathBuf {
     get_app_config_dir().join("logs")
 }
 
+/// 应用日志按行读取尾部时最多回溯的字节数
+const MAX_APP_LOG_TAIL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// 日志文件尾部内容（供界面“查看日志”面板使用）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogTail {
+    /// 日志文件路径
+    pub path: String,
+    /// 文件是否存在
+    pub exists: bool,
+    /// 文件总大小（字节）
+    pub size: u64,
+    /// 是否只返回了部分内容
+    pub truncated: bool,
+    pub content: String,
+}
+
+/// 读取崩溃日志最后 `max_bytes` 字节
+pub fn read_crash_log(max_bytes: u64) -> std::io::Result<LogTail> {
+    read_tail_bytes(&get_crash_log_path(), max_bytes)
+}
+
+/// 清空崩溃日志（文件不存在时视为成功）
+pub fn clear_crash_log() -> std::io::Result<()> {
+    let path = get_crash_log_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    OpenOptions::new().write(true).truncate(true).open(&path)?;
+    Ok(())
+}
+
+/// 读取应用日志 `cc-switch.log` 的最后 `lines` 行
+pub fn read_app_log_tail(lines: usize) -> std::io::Result<LogTail> {
+    read_tail_lines(&get_log_dir().join("cc-switch.log"), lines)
+}
+
+fn missing_log(path: &Path) -> LogTail {
+    LogTail {
+        path: path.display().to_string(),
+        exists: false,
+        size: 0,
+        truncated: false,
+        content: String::new(),
+    }
+}
+
+/// 读取文件尾部字节；截断时丢弃开头不完整的一行
+fn read_tail_bytes(path: &Path, max_bytes: u64) -> std::io::Result<LogTail> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(missing_log(path)),
+        Err(e) => return Err(e),
+    };
+    let size = file.metadata()?.len();
+    let start = size.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut buf = Vec::new();
+    file.take(max_bytes).read_to_end(&mut buf)?;
+
+    let truncated = start > 0;
+    let mut content = String::from_utf8_lossy(&buf).into_owned();
+    if truncated {
+        if let Some(pos) = content.find('\n') {
+            content.drain(..=pos);
+        }
+    }
+
+    Ok(LogTail {
+        path: path.display().to_string(),
+        exists: true,
+        size,
+        truncated,
+        content,
+    })
+}
+
+/// 读取文件最后若干行（最多回溯 MAX_APP_LOG_TAIL_BYTES 字节）
+fn read_tail_lines(path: &Path, lines: usize) -> std::io::Result<LogTail> {
+    let mut tail = read_tail_bytes(path, MAX_APP_LOG_TAIL_BYTES)?;
+    let all: Vec<&str> = tail.content.lines().collect();
+    if all.len() > lines {
+        tail.content = all[all.len() - lines..].join("\n");
+        tail.truncated = true;
+    }
+    Ok(tail)
+}
+
 /// 安全获取环境信息（不会 panic）
 fn get_system_info() -> String {
     let os = std::env::consts::OS;
@@ -195,6 +286,36 @@ mod tests {
         assert!(path.to_string_lossy().contains(".cc-switch"));
     }
 
+    #[test]
+    fn test_read_tail_bytes_drops_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("crash.log");
+        std::fs::write(&path, "first line\nsecond line\nthird line\n").unwrap();
+
+        let tail = read_tail_bytes(&path, 15).unwrap();
+        assert!(tail.exists && tail.truncated);
+        assert_eq!(tail.content, "third line\n");
+
+        let full = read_tail_bytes(&path, 1024).unwrap();
+        assert!(!full.truncated);
+        assert_eq!(full.size, 34);
+
+        let missing = read_tail_bytes(&dir.path().join("none.log"), 1024).unwrap();
+        assert!(!missing.exists);
+    }
+
+    #[test]
+    fn test_read_tail_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cc-switch.log");
+        let content: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(&path, content).unwrap();
+
+        let tail = read_tail_lines(&path, 3).unwrap();
+        assert!(tail.truncated);
+        assert_eq!(tail.content, "line 8\nline 9\nline 10");
+    }
+
     #[test]
     fn test_system_info() {
         let info = get_system_info();
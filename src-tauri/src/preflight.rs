@@ -0,0 +1,317 @@
+//! 写入前的预检
+//!
+//! JSON→SQLite 迁移、代理接管改写 Live 配置、Skill 安装等操作若在磁盘已满或目录只读时
+//! 执行，往往会中途失败并留下半完成的状态。这些操作开始前先检查目标目录可写且剩余空间
+//! 足够，不满足时直接返回指明路径与缺口的错误，不再尝试写入。
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 任何写入操作至少需要保留的剩余空间
+pub const MIN_FREE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 单项预检结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    /// database / claude / codex / gemini / opencode / skills
+    pub name: String,
+    pub path: String,
+    pub writable: bool,
+    /// 无法查询剩余空间时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_bytes: Option<u64>,
+    pub required_bytes: u64,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 路径本身或最近的已存在上级目录（目标目录可能尚未创建）
+fn existing_dir(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.is_dir()).map(Path::to_path_buf)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// 查询路径所在磁盘的剩余空间（字节）；查询失败时返回 None
+pub fn available_space(path: &Path) -> Option<u64> {
+    let dir = existing_dir(path)?;
+    query_available_space(&dir)
+}
+
+/// `df -P` 输出第二行：Filesystem 1024-blocks Used Available Capacity Mounted-on。
+/// 文件系统名与挂载点都可能含空格，因此以 Capacity 列（以 % 结尾）定位 Available 列。
+#[cfg(not(target_os = "windows"))]
+fn query_available_space(dir: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+    let capacity = fields.iter().position(|f| f.ends_with('%'))?;
+    let kb: u64 = fields.get(capacity.checked_sub(1)?)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "windows")]
+fn query_available_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::process::CommandExt;
+    use std::path::Component;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // DriveInfo 只接受盘符根目录；UNC 路径不做检查
+    let Some(Component::Prefix(prefix)) = dir.components().next() else {
+        return None;
+    };
+    let root = format!("{}\\", prefix.as_os_str().to_string_lossy());
+    let script = format!(
+        "([System.IO.DriveInfo]::new('{}')).AvailableFreeSpace",
+        root.replace('\'', "''")
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+fn check_space_with(
+    path: &Path,
+    required_bytes: u64,
+    available: Option<u64>,
+) -> Result<(), AppError> {
+    let Some(available) = available else {
+        log::debug!("无法查询 {} 的剩余空间，跳过检查", path.display());
+        return Ok(());
+    };
+    if available >= required_bytes {
+        return Ok(());
+    }
+    let shortfall = format_bytes(required_bytes - available);
+    let (need, have) = (format_bytes(required_bytes), format_bytes(available));
+    Err(AppError::localized(
+        "preflight.disk_space",
+        format!(
+            "磁盘空间不足：{} 需要 {need}，仅剩 {have}（还差 {shortfall}）",
+            path.display()
+        ),
+        format!(
+            "Not enough disk space for {}: {need} required, {have} available ({shortfall} short)",
+            path.display()
+        ),
+    ))
+}
+
+/// 检查路径所在磁盘至少有 `required_bytes` 剩余空间
+///
+/// 无法查询剩余空间时（命令不可用、网络路径等）视为通过，不阻止操作。
+pub fn check_disk_space(path: &Path, required_bytes: u64) -> Result<(), AppError> {
+    check_space_with(path, required_bytes, available_space(path))
+}
+
+/// 检查路径可写：目录通过创建临时探测文件验证，已存在的文件还需不是只读
+///
+/// 路径尚不存在时检查最近的已存在上级目录（后续写入会在其中创建）。
+pub fn check_writable(path: &Path) -> Result<(), AppError> {
+    let not_writable = |reason: String| {
+        AppError::localized(
+            "preflight.not_writable",
+            format!("路径不可写：{}（{reason}）", path.display()),
+            format!("Path is not writable: {} ({reason})", path.display()),
+        )
+    };
+
+    if path.is_file() {
+        let readonly = std::fs::metadata(path)
+            .map(|m| m.permissions().readonly())
+            .unwrap_or(false);
+        if readonly {
+            return Err(not_writable("read-only file".to_string()));
+        }
+    }
+
+    let probe_dir = if path.is_file() {
+        path.parent().and_then(existing_dir)
+    } else {
+        existing_dir(path)
+    }
+    .ok_or_else(|| not_writable("no existing parent directory".to_string()))?;
+
+    tempfile::Builder::new()
+        .prefix(".cc-switch-preflight")
+        .tempfile_in(&probe_dir)
+        .map(drop)
+        .map_err(|e| not_writable(e.to_string()))
+}
+
+/// 依次检查可写与剩余空间
+pub fn check_path(path: &Path, required_bytes: u64) -> Result<(), AppError> {
+    check_writable(path)?;
+    check_disk_space(path, required_bytes)
+}
+
+/// 应用 Live 配置所在目录（已考虑目录覆盖）
+pub fn live_config_dir(app_type: &AppType) -> PathBuf {
+    match app_type {
+        AppType::Claude => crate::config::get_claude_config_dir(),
+        AppType::Codex => crate::codex_config::get_codex_config_dir(),
+        AppType::Gemini => crate::gemini_config::get_gemini_dir(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_dir(),
+    }
+}
+
+/// 目录下所有文件的总大小（用于估算复制所需空间）
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn run_check(name: &str, path: PathBuf, required_bytes: u64) -> PreflightCheck {
+    let available_bytes = available_space(&path);
+    let writable_err = check_writable(&path).err();
+    let writable = writable_err.is_none();
+    let error =
+        writable_err.or_else(|| check_space_with(&path, required_bytes, available_bytes).err());
+    PreflightCheck {
+        name: name.to_string(),
+        path: path.display().to_string(),
+        writable,
+        available_bytes,
+        required_bytes,
+        ok: error.is_none(),
+        error: error.map(|e| e.localized_message()),
+    }
+}
+
+/// 检查数据库目录、各应用 Live 配置目录与 Skills 目录，供界面显示健康提示
+pub fn run_preflight_checks() -> Vec<PreflightCheck> {
+    let app_config_dir = crate::config::get_app_config_dir();
+    let mut checks = vec![run_check(
+        "database",
+        app_config_dir.clone(),
+        MIN_FREE_BYTES,
+    )];
+    for app_type in [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::OpenCode,
+    ] {
+        checks.push(run_check(
+            app_type.as_str(),
+            live_config_dir(&app_type),
+            MIN_FREE_BYTES,
+        ));
+    }
+    checks.push(run_check(
+        "skills",
+        app_config_dir.join("skills"),
+        MIN_FREE_BYTES,
+    ));
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn missing_directory_is_checked_through_existing_parent() {
+        let dir = TempDir::new().expect("temp dir");
+        let nested = dir.path().join("a").join("b");
+        assert!(check_writable(&nested).is_ok());
+        assert!(!nested.exists());
+    }
+
+    #[test]
+    fn disk_space_shortfall_names_path_and_amount() {
+        let dir = TempDir::new().expect("temp dir");
+        assert!(check_space_with(dir.path(), 1024, Some(2048)).is_ok());
+        assert!(check_space_with(dir.path(), 1024, None).is_ok());
+
+        let err = check_space_with(dir.path(), 3 * 1024 * 1024, Some(1024 * 1024)).unwrap_err();
+        let AppError::Localized { key, en, .. } = err else {
+            panic!("expected localized error");
+        };
+        assert_eq!(key, "preflight.disk_space");
+        assert!(en.contains(&dir.path().display().to_string()));
+        assert!(en.contains("2.0 MB short"));
+    }
+
+    #[test]
+    fn reports_available_space_for_temp_dir() {
+        let dir = TempDir::new().expect("temp dir");
+        assert!(check_disk_space(dir.path(), 1).is_ok());
+        if available_space(dir.path()).is_some() {
+            assert!(check_disk_space(dir.path(), u64::MAX).is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_and_file_are_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().expect("temp dir");
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked).expect("create dir");
+        let file = dir.path().join("settings.json");
+        std::fs::write(&file, "{}").expect("write file");
+
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555))
+            .expect("chmod dir");
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o444))
+            .expect("chmod file");
+
+        // root 不受权限位限制，此时无法模拟只读目录
+        let bypassed = std::fs::write(locked.join("probe"), "").is_ok();
+        if !bypassed {
+            let err = check_writable(&locked).unwrap_err();
+            assert!(matches!(
+                err,
+                AppError::Localized {
+                    key: "preflight.not_writable",
+                    ..
+                }
+            ));
+            assert!(check_writable(&locked.join("new.json")).is_err());
+        }
+        assert!(check_writable(&file).is_err());
+
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755))
+            .expect("restore dir permissions");
+    }
+}
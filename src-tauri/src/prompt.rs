@@ -9,6 +9,9 @@ pub struct Prompt {
     pub description: Option<String>,
     #[serde(default)]
     pub enabled: bool,
+    /// 启用多个提示词时写入 live 文件的顺序（升序）
+    #[serde(rename = "orderIndex", default)]
+    pub order_index: i64,
     #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
@@ -40,6 +40,12 @@ pub struct Provider {
     #[serde(default)]
     #[serde(rename = "inFailoverQueue")]
     pub in_failover_queue: bool,
+    /// 远程图标（`icon` 为 https URL 时）的本地缓存，PNG data URL
+    ///
+    /// 仅为运行时字段：不参与序列化，避免进入数据库、导出文件与同步数据；
+    /// 前端通过 `get_providers_summary` 的摘要获取
+    #[serde(skip)]
+    pub icon_cached: Option<String>,
 }
 
 impl Provider {
@@ -63,10 +69,45 @@ impl Provider {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 }
 
+/// 供应商列表摘要（不含 settingsConfig，用于列表视图）
+///
+/// 完整配置在打开编辑对话框时通过 `get_provider_settings` 按需获取。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_color: Option<String>,
+    /// 远程图标的本地缓存（PNG data URL）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_cached: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    pub is_current: bool,
+    pub in_failover_queue: bool,
+    /// 合作伙伴标记（meta.isPartner）
+    pub is_partner: bool,
+    /// 是否启用了用量查询脚本（meta.usageScript.enabled）
+    pub usage_script_enabled: bool,
+    /// 从配置中提取的请求地址（Claude/Gemini 读 env，Codex 读当前 model_provider 的 base_url）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
 /// 供应商管理器
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderManager {
@@ -106,6 +147,10 @@ pub struct UsageScript {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "autoQueryInterval")]
     pub auto_query_interval: Option<u64>,
+    /// 网络白名单：脚本只能请求匹配的域名（支持 `*.example.com`，为空时不限制）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "allowedHosts")]
+    pub allowed_hosts: Option<Vec<String>>,
 }
 
 /// 用量数据
@@ -191,6 +236,46 @@ pub struct ProviderProxyConfig {
     pub proxy_password: Option<String>,
 }
 
+/// 重试退避策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryBackoff {
+    /// 每次重试等待相同时间
+    #[default]
+    Fixed,
+    /// 每次重试等待时间翻倍
+    Exponential,
+}
+
+/// 供应商单独的请求重试配置
+///
+/// 优先级：供应商 `retryConfig` > 应用级 `proxy_config.max_retries`；
+/// 未设置的字段回退到应用级配置 / 默认退避（固定 500ms）。
+/// 未配置 `retryConfig` 的供应商不在自身重试，失败后直接切换到下一个供应商。
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProviderRetryConfig {
+    /// 同一供应商的最大重试次数（0 表示失败后立即切换到下一个供应商）
+    #[serde(rename = "maxRetries", skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// 退避策略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<RetryBackoff>,
+    /// 退避基础间隔（毫秒）
+    #[serde(rename = "backoffMs", skip_serializing_if = "Option::is_none")]
+    pub backoff_ms: Option<u64>,
+}
+
+/// 超出速率限制时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitOverflow {
+    /// 排队等待令牌
+    #[default]
+    Queue,
+    /// 立即切换到下一个供应商
+    Failover,
+}
+
 /// 供应商元数据
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderMeta {
@@ -230,11 +315,36 @@ pub struct ProviderMeta {
     /// 供应商单独的代理配置
     #[serde(rename = "proxyConfig", skip_serializing_if = "Option::is_none")]
     pub proxy_config: Option<ProviderProxyConfig>,
+    /// 供应商单独的请求重试配置（优先于应用级配置）
+    #[serde(rename = "retryConfig", skip_serializing_if = "Option::is_none")]
+    pub retry_config: Option<ProviderRetryConfig>,
+    /// 代理转发的每分钟请求数上限（未设置或 0 表示不限流）
+    #[serde(rename = "rateLimitRpm", skip_serializing_if = "Option::is_none")]
+    pub rate_limit_rpm: Option<u32>,
+    /// 超出速率限制时的处理方式（默认排队）
+    #[serde(rename = "rateLimitOverflow", skip_serializing_if = "Option::is_none")]
+    pub rate_limit_overflow: Option<RateLimitOverflow>,
     /// Claude API 格式（仅 Claude 供应商使用）
     /// - "anthropic": 原生 Anthropic Messages API，直接透传
     /// - "openai_chat": OpenAI Chat Completions 格式，需要转换
     #[serde(rename = "apiFormat", skip_serializing_if = "Option::is_none")]
     pub api_format: Option<String>,
+    /// 模型别名（规范模型名 → 供应商实际模型名），代理转发前改写请求体的 model 字段
+    #[serde(
+        rename = "modelAliases",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub model_aliases: HashMap<String, String>,
+    /// 默认模型（角色 → 模型名），写入 Live 配置时覆盖 settings_config 中的对应字段
+    /// - Claude：model / haiku / sonnet / opus（对应 ANTHROPIC_MODEL 与 ANTHROPIC_DEFAULT_*_MODEL）
+    /// - Codex：model（config.toml 顶层 model）
+    #[serde(
+        rename = "defaultModels",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub default_models: HashMap<String, String>,
 }
 
 impl ProviderManager {
@@ -429,6 +539,7 @@ impl UniversalProvider {
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            icon_cached: None,
         })
     }
 
@@ -494,6 +605,7 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            icon_cached: None,
         })
     }
 
@@ -529,6 +641,7 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            icon_cached: None,
         })
     }
 }
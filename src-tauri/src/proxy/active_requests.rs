@@ -0,0 +1,200 @@
+//! 进行中请求跟踪
+//!
+//! 每个代理请求在处理期间（流式响应直到响应体发送完毕）登记在 [`ActiveRequests`] 中，
+//! 供优雅停止等待排空；超时后通过 [`ActiveRequests::abort_all`] 中断剩余请求。
+//! 状态随 ProxyServer 创建，代理重启后重置。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use tokio::sync::{watch, Notify};
+
+use super::server::ProxyState;
+
+/// 进行中的请求
+#[derive(Debug, Clone)]
+pub struct ActiveRequest {
+    pub method: String,
+    pub path: String,
+    pub started_at: Instant,
+}
+
+/// 进行中请求登记表
+#[derive(Debug)]
+pub struct ActiveRequests {
+    requests: Mutex<HashMap<u64, ActiveRequest>>,
+    next_id: AtomicU64,
+    changed: Notify,
+    abort_tx: watch::Sender<bool>,
+}
+
+impl Default for ActiveRequests {
+    fn default() -> Self {
+        Self {
+            requests: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            changed: Notify::new(),
+            abort_tx: watch::channel(false).0,
+        }
+    }
+}
+
+/// 登记凭证，Drop 时移除对应请求
+pub struct ActiveRequestGuard {
+    registry: Arc<ActiveRequests>,
+    id: u64,
+}
+
+impl Drop for ActiveRequestGuard {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.id);
+        self.registry.changed.notify_waiters();
+    }
+}
+
+impl ActiveRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, ActiveRequest>> {
+        match self.requests.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// 登记一个请求
+    pub fn begin(self: &Arc<Self>, method: &str, path: &str) -> ActiveRequestGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(
+            id,
+            ActiveRequest {
+                method: method.to_string(),
+                path: path.to_string(),
+                started_at: Instant::now(),
+            },
+        );
+        ActiveRequestGuard {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 当前进行中的请求（按开始时间排序）
+    pub fn snapshot(&self) -> Vec<ActiveRequest> {
+        let mut requests: Vec<_> = self.lock().values().cloned().collect();
+        requests.sort_by_key(|r| r.started_at);
+        requests
+    }
+
+    /// 等待所有请求结束，超时返回 false
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let changed = self.changed.notified();
+                if self.is_empty() {
+                    return;
+                }
+                changed.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+
+    /// 中断所有进行中的请求（流式响应提前结束，未返回的请求响应 503）
+    pub fn abort_all(&self) {
+        self.abort_tx.send_replace(true);
+    }
+
+    /// 中断信号：`abort_all` 调用后完成
+    fn aborted(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut rx = self.abort_tx.subscribe();
+        async move {
+            let _ = rx.wait_for(|aborted| *aborted).await;
+        }
+    }
+}
+
+/// 请求跟踪中间件：登记凭证随响应体一起释放，流式响应在发送完毕前保持登记
+pub async fn track_active_requests(
+    State(state): State<ProxyState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let registry = state.active_requests.clone();
+    let guard = registry.begin(request.method().as_str(), request.uri().path());
+
+    let response = tokio::select! {
+        response = next.run(request) => response,
+        _ = registry.aborted() => {
+            return (StatusCode::SERVICE_UNAVAILABLE, "proxy is shutting down").into_response();
+        }
+    };
+
+    let (parts, body) = response.into_parts();
+    let stream = body
+        .into_data_stream()
+        .take_until(registry.aborted())
+        .map(move |chunk| {
+            let _ = &guard;
+            chunk
+        });
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_idle_returns_when_last_request_finishes() {
+        let registry = Arc::new(ActiveRequests::new());
+        let first = registry.begin("POST", "/v1/messages");
+        let second = registry.begin("POST", "/v1/responses");
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.snapshot()[0].path, "/v1/messages");
+
+        assert!(!registry.wait_idle(Duration::from_millis(20)).await);
+
+        drop(first);
+        let waiter = {
+            let registry = registry.clone();
+            tokio::spawn(async move { registry.wait_idle(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(second);
+        assert!(waiter.await.expect("join waiter"));
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn abort_all_resolves_pending_abort_signals() {
+        let registry = Arc::new(ActiveRequests::new());
+        let signal = registry.aborted();
+        registry.abort_all();
+        tokio::time::timeout(Duration::from_secs(1), signal)
+            .await
+            .expect("abort signal should fire");
+        // 中断之后新订阅的信号立即完成
+        tokio::time::timeout(Duration::from_secs(1), registry.aborted())
+            .await
+            .expect("late abort signal should fire");
+    }
+}
@@ -0,0 +1,296 @@
+//! 请求/响应体捕获模块
+//!
+//! 在 `proxy_config.capture_bodies` 开启时，为失败请求保存截断后的请求头、请求体与
+//! 上游响应体，便于排查供应商返回的异常错误。
+//!
+//! ## 安全规则
+//! - `Authorization`、`x-api-key` 等认证相关请求头的值会被替换为 `[REDACTED]`
+//! - 请求体中同名的认证字段同样会被递归脱敏
+//! - 端点查询参数中的密钥（如 Gemini 的 `?key=`）在保存前移除
+//! - 只捕获失败请求，成功请求（包括流式响应）永远不会被捕获
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::proxy::types::AppProxyConfig;
+use axum::http::HeaderMap;
+use serde_json::{Map, Value};
+
+/// 脱敏后的占位值
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// 需要脱敏的请求头 / JSON 字段名（小写比较）
+const SENSITIVE_KEYS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "x-goog-api-key",
+    "api-key",
+    "api_key",
+    "apikey",
+    "cookie",
+    "set-cookie",
+];
+
+/// 待写入数据库的捕获内容
+#[derive(Debug, Clone, Default)]
+pub struct CapturedBodies {
+    /// 上游端点（含查询参数），用于重放请求
+    pub endpoint: Option<String>,
+    pub request_headers: Option<String>,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    /// 是否有任一部分被截断
+    pub truncated: bool,
+}
+
+impl CapturedBodies {
+    /// 按配置构造失败请求的捕获内容
+    ///
+    /// 未开启捕获时返回 None。
+    pub fn for_failed_request(
+        config: &AppProxyConfig,
+        endpoint: &str,
+        request_headers: Option<&Value>,
+        request_body: &Value,
+        response_body: Option<&str>,
+    ) -> Option<Self> {
+        if !config.capture_bodies {
+            return None;
+        }
+
+        let max_bytes = config.capture_max_bytes as usize;
+        let mut captured = Self {
+            endpoint: Some(strip_endpoint_credentials(endpoint)),
+            ..Self::default()
+        };
+
+        if let Some(headers) = request_headers {
+            let (text, truncated) = truncate_utf8(&headers.to_string(), max_bytes);
+            captured.request_headers = Some(text);
+            captured.truncated |= truncated;
+        }
+
+        let mut body = request_body.clone();
+        redact_json(&mut body);
+        let (text, truncated) = truncate_utf8(&body.to_string(), max_bytes);
+        captured.request_body = Some(text);
+        captured.truncated |= truncated;
+
+        if let Some(response) = response_body {
+            let (text, truncated) = truncate_utf8(response, max_bytes);
+            captured.response_body = Some(text);
+            captured.truncated |= truncated;
+        }
+
+        Some(captured)
+    }
+
+    /// 保存到 proxy_request_bodies 表
+    pub fn save(&self, db: &Database, request_id: &str) -> Result<(), AppError> {
+        db.save_request_bodies(
+            request_id,
+            self.endpoint.as_deref(),
+            self.request_headers.as_deref(),
+            self.request_body.as_deref(),
+            self.response_body.as_deref(),
+            self.truncated,
+        )
+    }
+}
+
+/// 将请求头转换为 JSON 对象，并对认证相关请求头脱敏
+pub fn redact_headers(headers: &HeaderMap) -> Value {
+    let mut map = Map::new();
+    for (name, value) in headers {
+        let key = name.as_str().to_string();
+        let value = if is_sensitive_key(&key) {
+            REDACTED.to_string()
+        } else {
+            String::from_utf8_lossy(value.as_bytes()).into_owned()
+        };
+        map.insert(key, Value::String(value));
+    }
+    Value::Object(map)
+}
+
+/// 递归脱敏 JSON 中的认证字段
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_sensitive_key(key) {
+                    *v = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// 移除端点查询参数中的密钥（如 Gemini 的 `?key=`），其余参数保持原样
+///
+/// 重放时由目标供应商重新附加认证，因此直接移除而非替换为占位值。
+pub fn strip_endpoint_credentials(endpoint: &str) -> String {
+    let Some((path, query)) = endpoint.split_once('?') else {
+        return endpoint.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+            !(name.eq_ignore_ascii_case("key") || is_sensitive_key(name))
+        })
+        .collect();
+    if kept.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", kept.join("&"))
+    }
+}
+
+/// 按字节数截断字符串（保证不截断在 UTF-8 字符中间）
+///
+/// 返回截断后的字符串以及是否发生了截断。
+pub fn truncate_utf8(text: &str, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+    let mut end = max_bytes;
+    while end > 0 && !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+pub(crate) fn is_sensitive_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    SENSITIVE_KEYS.contains(&lower.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    fn capture_config(max_bytes: u32) -> AppProxyConfig {
+        AppProxyConfig {
+            app_type: "claude".to_string(),
+            enabled: true,
+            auto_failover_enabled: false,
+            max_retries: 3,
+            streaming_first_byte_timeout: 60,
+            streaming_idle_timeout: 120,
+            non_streaming_timeout: 600,
+            circuit_failure_threshold: 4,
+            circuit_success_threshold: 2,
+            circuit_timeout_seconds: 60,
+            circuit_error_rate_threshold: 0.6,
+            circuit_min_requests: 10,
+            capture_bodies: true,
+            capture_max_bytes: max_bytes,
+        }
+    }
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_static("Bearer sk-secret"),
+        );
+        headers.insert("x-api-key", HeaderValue::from_static("sk-secret"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        let value = redact_headers(&headers);
+        assert_eq!(value["authorization"], REDACTED);
+        assert_eq!(value["x-api-key"], REDACTED);
+        assert_eq!(value["content-type"], "application/json");
+        assert!(!value.to_string().contains("sk-secret"));
+    }
+
+    #[test]
+    fn test_strip_endpoint_credentials() {
+        assert_eq!(
+            strip_endpoint_credentials(
+                "/v1beta/models/g:streamGenerateContent?alt=sse&key=AIza-secret"
+            ),
+            "/v1beta/models/g:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(
+            strip_endpoint_credentials("/v1beta/models/g:generateContent?key=AIza-secret"),
+            "/v1beta/models/g:generateContent"
+        );
+        assert_eq!(
+            strip_endpoint_credentials("/v1/messages?beta=true"),
+            "/v1/messages?beta=true"
+        );
+
+        let captured = CapturedBodies::for_failed_request(
+            &capture_config(1024),
+            "/v1beta/models/g:generateContent?key=AIza-secret",
+            None,
+            &json!({}),
+            None,
+        )
+        .expect("capture enabled");
+        assert!(!captured.endpoint.unwrap().contains("AIza-secret"));
+    }
+
+    #[test]
+    fn test_redact_json_nested() {
+        let mut body = json!({
+            "model": "claude-3",
+            "metadata": {"Authorization": "Bearer sk-secret"},
+            "tools": [{"api_key": "sk-secret"}]
+        });
+        redact_json(&mut body);
+        assert_eq!(body["model"], "claude-3");
+        assert_eq!(body["metadata"]["Authorization"], REDACTED);
+        assert_eq!(body["tools"][0]["api_key"], REDACTED);
+    }
+
+    #[test]
+    fn test_truncate_utf8_respects_char_boundary() {
+        let (text, truncated) = truncate_utf8("你好世界", 4);
+        assert_eq!(text, "你");
+        assert!(truncated);
+
+        let (text, truncated) = truncate_utf8("abc", 10);
+        assert_eq!(text, "abc");
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_for_failed_request_disabled_returns_none() {
+        let mut config = capture_config(1024);
+        config.capture_bodies = false;
+        assert!(CapturedBodies::for_failed_request(
+            &config,
+            "/v1/messages",
+            None,
+            &json!({}),
+            None
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_for_failed_request_truncates_response() {
+        let config = capture_config(8);
+        let captured = CapturedBodies::for_failed_request(
+            &config,
+            "/v1/messages",
+            None,
+            &json!({"a": 1}),
+            Some("upstream error body"),
+        )
+        .expect("capture enabled");
+        assert_eq!(captured.endpoint.as_deref(), Some("/v1/messages"));
+        assert_eq!(captured.response_body.as_deref(), Some("upstream"));
+        assert!(captured.truncated);
+    }
+}
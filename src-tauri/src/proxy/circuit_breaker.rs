@@ -31,6 +31,29 @@ impl std::fmt::Display for CircuitState {
     }
 }
 
+impl std::str::FromStr for CircuitState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "closed" => Ok(CircuitState::Closed),
+            "open" => Ok(CircuitState::Open),
+            "half_open" => Ok(CircuitState::HalfOpen),
+            other => Err(format!("未知的熔断器状态: {other}")),
+        }
+    }
+}
+
+/// 熔断器状态转换（计数为转换发生前的快照）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitTransition {
+    pub from: CircuitState,
+    pub to: CircuitState,
+    pub consecutive_failures: u32,
+    pub failed_requests: u32,
+    pub total_requests: u32,
+}
+
 /// 熔断器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -87,6 +110,8 @@ pub struct CircuitBreaker {
 pub struct AllowResult {
     pub allowed: bool,
     pub used_half_open_permit: bool,
+    /// 本次检查触发的状态转换（Open → HalfOpen）
+    pub transition: Option<CircuitTransition>,
 }
 
 impl CircuitBreaker {
@@ -118,11 +143,16 @@ impl CircuitBreaker {
     /// 注意：真正发起请求前仍需调用 `allow_request()` 来获取 HalfOpen 探测名额，
     /// 并在请求结束后通过 `record_success()` / `record_failure()` 释放。
     pub async fn is_available(&self) -> bool {
+        self.check_available().await.0
+    }
+
+    /// 同 `is_available`，并返回检查过程中触发的状态转换
+    pub async fn check_available(&self) -> (bool, Option<CircuitTransition>) {
         let state = *self.state.read().await;
         let config = self.config.read().await;
 
         match state {
-            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Closed | CircuitState::HalfOpen => (true, None),
             CircuitState::Open => {
                 if let Some(opened_at) = *self.last_opened_at.read().await {
                     if opened_at.elapsed().as_secs() >= config.timeout_seconds {
@@ -131,11 +161,24 @@ impl CircuitBreaker {
                             "[{}] 熔断器 Open → HalfOpen (超时恢复)",
                             log_cb::OPEN_TO_HALF_OPEN
                         );
-                        self.transition_to_half_open().await;
-                        return true;
+                        return (true, self.transition_to_half_open().await);
                     }
                 }
-                false
+                (false, None)
+            }
+        }
+    }
+
+    /// 同 `is_available`，但不触发 Open → HalfOpen 转换（用于故障转移模拟）
+    pub async fn peek_available(&self) -> bool {
+        match *self.state.read().await {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                let timeout_seconds = self.config.read().await.timeout_seconds;
+                self.last_opened_at
+                    .read()
+                    .await
+                    .is_some_and(|opened_at| opened_at.elapsed().as_secs() >= timeout_seconds)
             }
         }
     }
@@ -148,6 +191,7 @@ impl CircuitBreaker {
             CircuitState::Closed => AllowResult {
                 allowed: true,
                 used_half_open_permit: false,
+                transition: None,
             },
             CircuitState::Open => {
                 let config = self.config.read().await;
@@ -159,35 +203,42 @@ impl CircuitBreaker {
                             "[{}] 熔断器 Open → HalfOpen (超时恢复)",
                             log_cb::OPEN_TO_HALF_OPEN
                         );
-                        self.transition_to_half_open().await;
+                        let transition = self.transition_to_half_open().await;
 
                         // 转换后按当前状态决定是否需要获取 HalfOpen 探测名额
                         let current_state = *self.state.read().await;
-                        return match current_state {
+                        let result = match current_state {
                             CircuitState::Closed => AllowResult {
                                 allowed: true,
                                 used_half_open_permit: false,
+                                transition: None,
                             },
                             CircuitState::HalfOpen => self.allow_half_open_probe(),
                             CircuitState::Open => AllowResult {
                                 allowed: false,
                                 used_half_open_permit: false,
+                                transition: None,
                             },
                         };
+                        return AllowResult {
+                            transition,
+                            ..result
+                        };
                     }
                 }
 
                 AllowResult {
                     allowed: false,
                     used_half_open_permit: false,
+                    transition: None,
                 }
             }
             CircuitState::HalfOpen => self.allow_half_open_probe(),
         }
     }
 
-    /// 记录成功
-    pub async fn record_success(&self, used_half_open_permit: bool) {
+    /// 记录成功，返回触发的状态转换（HalfOpen → Closed）
+    pub async fn record_success(&self, used_half_open_permit: bool) -> Option<CircuitTransition> {
         let state = *self.state.read().await;
         let config = self.config.read().await;
 
@@ -208,13 +259,14 @@ impl CircuitBreaker {
                     "[{}] 熔断器 HalfOpen → Closed (恢复正常)",
                     log_cb::HALF_OPEN_TO_CLOSED
                 );
-                self.transition_to_closed().await;
+                return self.transition_to_closed().await;
             }
         }
+        None
     }
 
-    /// 记录失败
-    pub async fn record_failure(&self, used_half_open_permit: bool) {
+    /// 记录失败，返回触发的状态转换（→ Open）
+    pub async fn record_failure(&self, used_half_open_permit: bool) -> Option<CircuitTransition> {
         let state = *self.state.read().await;
         let config = self.config.read().await;
 
@@ -239,7 +291,7 @@ impl CircuitBreaker {
                     log_cb::HALF_OPEN_PROBE_FAILED
                 );
                 drop(config);
-                self.transition_to_open().await;
+                self.transition_to_open().await
             }
             CircuitState::Closed => {
                 // 检查连续失败次数
@@ -249,7 +301,7 @@ impl CircuitBreaker {
                         log_cb::TRIGGERED_FAILURES
                     );
                     drop(config); // 释放读锁再转换状态
-                    self.transition_to_open().await;
+                    self.transition_to_open().await
                 } else {
                     // 检查错误率
                     let total = self.total_requests.load(Ordering::SeqCst);
@@ -265,12 +317,13 @@ impl CircuitBreaker {
                                 error_rate * 100.0
                             );
                             drop(config); // 释放读锁再转换状态
-                            self.transition_to_open().await;
+                            return self.transition_to_open().await;
                         }
                     }
+                    None
                 }
             }
-            _ => {}
+            CircuitState::Open => None,
         }
     }
 
@@ -294,9 +347,9 @@ impl CircuitBreaker {
 
     /// 重置熔断器（手动恢复）
     #[allow(dead_code)]
-    pub async fn reset(&self) {
+    pub async fn reset(&self) -> Option<CircuitTransition> {
         log::info!("[{}] 熔断器手动重置 → Closed", log_cb::MANUAL_RESET);
-        self.transition_to_closed().await;
+        self.transition_to_closed().await
     }
 
     fn allow_half_open_probe(&self) -> AllowResult {
@@ -308,6 +361,7 @@ impl CircuitBreaker {
             AllowResult {
                 allowed: true,
                 used_half_open_permit: true,
+                transition: None,
             }
         } else {
             // 超过限额，回退计数，拒绝请求
@@ -315,6 +369,7 @@ impl CircuitBreaker {
             AllowResult {
                 allowed: false,
                 used_half_open_permit: false,
+                transition: None,
             }
         }
     }
@@ -343,34 +398,53 @@ impl CircuitBreaker {
     }
 
     /// 转换到打开状态
-    async fn transition_to_open(&self) {
-        *self.state.write().await = CircuitState::Open;
+    async fn transition_to_open(&self) -> Option<CircuitTransition> {
+        let mut state = self.state.write().await;
+        let transition = self.transition_from(*state, CircuitState::Open);
+        *state = CircuitState::Open;
         *self.last_opened_at.write().await = Some(Instant::now());
         self.consecutive_failures.store(0, Ordering::SeqCst);
         self.consecutive_successes.store(0, Ordering::SeqCst);
+        transition
     }
 
     /// 转换到半开状态
-    async fn transition_to_half_open(&self) {
+    async fn transition_to_half_open(&self) -> Option<CircuitTransition> {
         let mut state = self.state.write().await;
         if *state != CircuitState::Open {
-            return;
+            return None;
         }
 
+        let transition = self.transition_from(*state, CircuitState::HalfOpen);
         *state = CircuitState::HalfOpen;
         self.consecutive_successes.store(0, Ordering::SeqCst);
         // 重置半开状态的请求限流计数
         self.half_open_requests.store(0, Ordering::SeqCst);
+        transition
     }
 
     /// 转换到关闭状态
-    async fn transition_to_closed(&self) {
-        *self.state.write().await = CircuitState::Closed;
+    async fn transition_to_closed(&self) -> Option<CircuitTransition> {
+        let mut state = self.state.write().await;
+        let transition = self.transition_from(*state, CircuitState::Closed);
+        *state = CircuitState::Closed;
         self.consecutive_failures.store(0, Ordering::SeqCst);
         self.consecutive_successes.store(0, Ordering::SeqCst);
         // 重置计数器
         self.total_requests.store(0, Ordering::SeqCst);
         self.failed_requests.store(0, Ordering::SeqCst);
+        transition
+    }
+
+    /// 在状态写锁内生成转换记录（状态未变化时返回 None）
+    fn transition_from(&self, from: CircuitState, to: CircuitState) -> Option<CircuitTransition> {
+        (from != to).then(|| CircuitTransition {
+            from,
+            to,
+            consecutive_failures: self.consecutive_failures.load(Ordering::SeqCst),
+            failed_requests: self.failed_requests.load(Ordering::SeqCst),
+            total_requests: self.total_requests.load(Ordering::SeqCst),
+        })
     }
 }
 
@@ -385,6 +459,48 @@ pub struct CircuitBreakerStats {
     pub failed_requests: u32,
 }
 
+/// 熔断器状态转换事件（持久化到 circuit_breaker_events 表，并作为前端事件载荷）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreakerEvent {
+    /// 数据库自增 ID（尚未持久化时为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub app_type: String,
+    pub provider_id: String,
+    pub from_state: CircuitState,
+    pub to_state: CircuitState,
+    pub consecutive_failures: u32,
+    pub failed_requests: u32,
+    pub total_requests: u32,
+    /// 触发本次转换的错误信息
+    pub error: Option<String>,
+    /// 发生时间（Unix 秒）
+    pub created_at: i64,
+}
+
+impl CircuitBreakerEvent {
+    pub fn new(
+        app_type: &str,
+        provider_id: &str,
+        transition: CircuitTransition,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            id: None,
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+            from_state: transition.from,
+            to_state: transition.to,
+            consecutive_failures: transition.consecutive_failures,
+            failed_requests: transition.failed_requests,
+            total_requests: transition.total_requests,
+            error,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,4 +595,42 @@ mod tests {
         assert_eq!(breaker.get_state().await, CircuitState::Closed);
         assert!(breaker.allow_request().await.allowed);
     }
+
+    #[tokio::test]
+    async fn test_state_transitions_are_reported_once() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            success_threshold: 1,
+            timeout_seconds: 0,
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::new(config);
+
+        assert!(breaker.record_failure(false).await.is_none());
+        let opened = breaker
+            .record_failure(false)
+            .await
+            .expect("closed -> open transition");
+        assert_eq!(opened.from, CircuitState::Closed);
+        assert_eq!(opened.to, CircuitState::Open);
+        assert_eq!(opened.consecutive_failures, 2);
+        assert_eq!(opened.failed_requests, 2);
+
+        // 已处于 Open 时重复转换不应再次上报
+        assert!(breaker.transition_to_open().await.is_none());
+
+        let probe = breaker.allow_request().await;
+        assert!(probe.used_half_open_permit);
+        let half_open = probe.transition.expect("open -> half-open transition");
+        assert_eq!(half_open.from, CircuitState::Open);
+        assert_eq!(half_open.to, CircuitState::HalfOpen);
+
+        let closed = breaker
+            .record_success(true)
+            .await
+            .expect("half-open -> closed transition");
+        assert_eq!(closed.from, CircuitState::HalfOpen);
+        assert_eq!(closed.to, CircuitState::Closed);
+        assert!(breaker.reset().await.is_none());
+    }
 }
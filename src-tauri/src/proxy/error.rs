@@ -45,6 +45,10 @@ pub enum ProxyError {
     #[error("超过最大重试次数")]
     MaxRetriesExceeded,
 
+    /// 供应商超出本地配置的每分钟请求数限制
+    #[error("供应商请求速率超出限制: {0}")]
+    RateLimited(String),
+
     #[error("数据库错误: {0}")]
     DatabaseError(String),
 
@@ -141,6 +145,10 @@ impl IntoResponse for ProxyError {
                     ProxyError::MaxRetriesExceeded => {
                         (StatusCode::SERVICE_UNAVAILABLE, self.to_string())
                     }
+                    // 本地限流不使用 429，避免客户端与日志把它当作上游的限流响应
+                    ProxyError::RateLimited(_) => {
+                        (StatusCode::SERVICE_UNAVAILABLE, self.to_string())
+                    }
                     ProxyError::DatabaseError(_) => {
                         (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
                     }
@@ -160,10 +168,14 @@ impl IntoResponse for ProxyError {
                     ProxyError::UpstreamError { .. } => unreachable!(),
                 };
 
+                let error_type = match &self {
+                    ProxyError::RateLimited(_) => "local_rate_limit_error",
+                    _ => "proxy_error",
+                };
                 let error_body = json!({
                     "error": {
                         "message": message,
-                        "type": "proxy_error",
+                        "type": error_type,
                     }
                 });
 
@@ -36,6 +36,9 @@ pub fn map_proxy_error_to_status(error: &ProxyError) -> u16 {
         // 重试耗尽：503 Service Unavailable
         ProxyError::MaxRetriesExceeded => 503,
 
+        // 本地限流：503，与上游返回的 429 区分
+        ProxyError::RateLimited(_) => 503,
+
         // Provider 不健康：503 Service Unavailable
         ProxyError::ProviderUnhealthy(_) => 503,
 
@@ -66,6 +69,9 @@ pub fn get_error_message(error: &ProxyError) -> String {
         ProxyError::AllProvidersCircuitOpen => "所有供应商已熔断，无可用渠道".to_string(),
         ProxyError::NoProvidersConfigured => "未配置供应商".to_string(),
         ProxyError::MaxRetriesExceeded => "所有 Provider 都失败，重试耗尽".to_string(),
+        ProxyError::RateLimited(name) => {
+            format!("[本地限流] 供应商 {name} 超出每分钟请求数限制，未转发到上游")
+        }
         ProxyError::ProviderUnhealthy(msg) => format!("Provider 不健康: {msg}"),
         ProxyError::DatabaseError(msg) => format!("数据库错误: {msg}"),
         ProxyError::TransformError(msg) => format!("请求/响应转换错误: {msg}"),
@@ -104,6 +110,13 @@ mod tests {
         assert_eq!(map_proxy_error_to_status(&error), 503);
     }
 
+    #[test]
+    fn test_rate_limited_is_distinct_from_upstream_429() {
+        let error = ProxyError::RateLimited("Budget".to_string());
+        assert_eq!(map_proxy_error_to_status(&error), 503);
+        assert!(get_error_message(&error).starts_with("[本地限流]"));
+    }
+
     #[test]
     fn test_get_error_message() {
         let error = ProxyError::UpstreamError {
@@ -0,0 +1,238 @@
+//! 故障转移模拟
+//!
+//! 给定一组假设故障的供应商，按与 `ProviderRouter::select_providers` 及转发器相同的规则
+//! 推演代理会依次尝试哪些供应商、最终落在哪一个上，不发送任何真实请求，也不改变熔断器状态。
+//! 开启消费限额强制时，已超出限额的供应商同样会被跳过。
+//! 限流与 HalfOpen 探测名额属于运行时因素，不在模拟范围内。
+
+use crate::proxy::circuit_breaker::CircuitState;
+use crate::proxy::types::ForcedCircuitState;
+use serde::Serialize;
+
+/// 参与模拟的供应商及其当前健康/熔断状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationCandidate {
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 手动熔断状态（来自 provider_health）
+    pub forced_state: Option<ForcedCircuitState>,
+    pub is_healthy: bool,
+    pub consecutive_failures: u32,
+    /// 熔断器状态；代理未运行或尚未创建熔断器时为 Closed
+    pub circuit_state: CircuitState,
+    /// 路由时是否会纳入候选（Open 且已超过恢复时间时为 true）
+    pub circuit_available: bool,
+    /// 开启消费限额强制且已超出限额（路由时视同熔断）
+    pub limit_tripped: bool,
+}
+
+/// 单个供应商在模拟中的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulatedOutcome {
+    /// 手动强制熔断，不参与路由
+    ForcedOpen,
+    /// 熔断器打开，路由时跳过
+    CircuitOpen,
+    /// 超出消费限额，路由时跳过
+    LimitTripped,
+    /// 被尝试，但按假设故障，转向下一个
+    Failed,
+    /// 最终选中
+    Selected,
+    /// 已选中前面的供应商，不会被尝试
+    NotReached,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedProvider {
+    #[serde(flatten)]
+    pub candidate: SimulationCandidate,
+    pub outcome: SimulatedOutcome,
+}
+
+/// 模拟结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverSimulation {
+    pub app_type: String,
+    pub auto_failover_enabled: bool,
+    /// 按路由顺序排列的所有候选及其结果
+    pub providers: Vec<SimulatedProvider>,
+    /// 实际会发起请求的供应商 ID（按尝试顺序）
+    pub tried: Vec<String>,
+    /// 最终选中的供应商；所有候选均故障或被熔断时为 None
+    pub selected: Option<String>,
+}
+
+/// 推演故障转移过程
+///
+/// `candidates` 为故障转移队列（开启时）或仅含当前供应商（关闭时）。
+/// 故障转移关闭时只有一个候选，转发器会跳过熔断器检查，这里同样忽略熔断状态。
+pub fn simulate_failover(
+    app_type: &str,
+    auto_failover_enabled: bool,
+    candidates: Vec<SimulationCandidate>,
+    failing_provider_ids: &[String],
+) -> FailoverSimulation {
+    let mut providers = Vec::with_capacity(candidates.len());
+    let mut tried = Vec::new();
+    let mut selected: Option<String> = None;
+
+    for candidate in candidates {
+        let skipped = if !auto_failover_enabled {
+            None
+        } else {
+            match candidate.forced_state {
+                Some(ForcedCircuitState::Open) => Some(SimulatedOutcome::ForcedOpen),
+                Some(ForcedCircuitState::Closed) => None,
+                None if candidate.limit_tripped => Some(SimulatedOutcome::LimitTripped),
+                None if !candidate.circuit_available => Some(SimulatedOutcome::CircuitOpen),
+                None => None,
+            }
+        };
+
+        let outcome = match skipped {
+            Some(outcome) => outcome,
+            None if selected.is_some() => SimulatedOutcome::NotReached,
+            None => {
+                tried.push(candidate.provider_id.clone());
+                if failing_provider_ids.contains(&candidate.provider_id) {
+                    SimulatedOutcome::Failed
+                } else {
+                    selected = Some(candidate.provider_id.clone());
+                    SimulatedOutcome::Selected
+                }
+            }
+        };
+        providers.push(SimulatedProvider { candidate, outcome });
+    }
+
+    FailoverSimulation {
+        app_type: app_type.to_string(),
+        auto_failover_enabled,
+        providers,
+        tried,
+        selected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str) -> SimulationCandidate {
+        SimulationCandidate {
+            provider_id: id.to_string(),
+            provider_name: id.to_uppercase(),
+            forced_state: None,
+            is_healthy: true,
+            consecutive_failures: 0,
+            circuit_state: CircuitState::Closed,
+            circuit_available: true,
+            limit_tripped: false,
+        }
+    }
+
+    fn outcomes(sim: &FailoverSimulation) -> Vec<SimulatedOutcome> {
+        sim.providers.iter().map(|p| p.outcome).collect()
+    }
+
+    #[test]
+    fn queue_order_skips_failing_providers() {
+        let failing = vec!["b".to_string()];
+        let sim = simulate_failover(
+            "claude",
+            true,
+            vec![candidate("b"), candidate("a"), candidate("c")],
+            &failing,
+        );
+
+        assert_eq!(sim.tried, vec!["b", "a"]);
+        assert_eq!(sim.selected.as_deref(), Some("a"));
+        assert_eq!(
+            outcomes(&sim),
+            vec![
+                SimulatedOutcome::Failed,
+                SimulatedOutcome::Selected,
+                SimulatedOutcome::NotReached,
+            ]
+        );
+    }
+
+    #[test]
+    fn circuit_and_forced_states_are_honored() {
+        let mut forced_open = candidate("a");
+        forced_open.forced_state = Some(ForcedCircuitState::Open);
+        let mut tripped = candidate("b");
+        tripped.circuit_state = CircuitState::Open;
+        tripped.circuit_available = false;
+        let mut forced_closed = candidate("c");
+        forced_closed.circuit_state = CircuitState::Open;
+        forced_closed.circuit_available = false;
+        forced_closed.forced_state = Some(ForcedCircuitState::Closed);
+
+        let sim = simulate_failover(
+            "codex",
+            true,
+            vec![forced_open, tripped, forced_closed, candidate("d")],
+            &["c".to_string(), "d".to_string()],
+        );
+
+        assert_eq!(sim.tried, vec!["c", "d"]);
+        assert_eq!(sim.selected, None);
+        assert_eq!(
+            outcomes(&sim),
+            vec![
+                SimulatedOutcome::ForcedOpen,
+                SimulatedOutcome::CircuitOpen,
+                SimulatedOutcome::Failed,
+                SimulatedOutcome::Failed,
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_tripped_providers_are_skipped_unless_forced_closed() {
+        let mut over_limit = candidate("a");
+        over_limit.limit_tripped = true;
+        let mut forced_closed = candidate("b");
+        forced_closed.limit_tripped = true;
+        forced_closed.forced_state = Some(ForcedCircuitState::Closed);
+
+        let sim = simulate_failover(
+            "claude",
+            true,
+            vec![over_limit, forced_closed, candidate("c")],
+            &["b".to_string()],
+        );
+
+        assert_eq!(sim.tried, vec!["b", "c"]);
+        assert_eq!(sim.selected.as_deref(), Some("c"));
+        assert_eq!(
+            outcomes(&sim),
+            vec![
+                SimulatedOutcome::LimitTripped,
+                SimulatedOutcome::Failed,
+                SimulatedOutcome::Selected,
+            ]
+        );
+    }
+
+    #[test]
+    fn failover_disabled_uses_current_provider_regardless_of_circuit() {
+        let mut current = candidate("a");
+        current.circuit_state = CircuitState::Open;
+        current.circuit_available = false;
+
+        let sim = simulate_failover("gemini", false, vec![current.clone()], &[]);
+        assert_eq!(sim.selected.as_deref(), Some("a"));
+
+        let sim = simulate_failover("gemini", false, vec![current], &["a".to_string()]);
+        assert_eq!(sim.tried, vec!["a"]);
+        assert_eq!(sim.selected, None);
+        assert_eq!(outcomes(&sim), vec![SimulatedOutcome::Failed]);
+    }
+}
@@ -9,6 +9,7 @@
 
 use crate::database::Database;
 use crate::error::AppError;
+use crate::services::provider::{AuditAction, AuditSource};
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -100,6 +101,13 @@ impl FailoverSwitchManager {
 
         // 1. 更新数据库 is_current
         self.db.set_current_provider(app_type, provider_id)?;
+        crate::services::provider::record_provider_audit(
+            &self.db,
+            app_type,
+            provider_id,
+            AuditAction::Switch,
+            AuditSource::Failover,
+        );
 
         // 2. 更新本地 settings（设备级）
         let app_type_enum = crate::app_config::AppType::from_str(app_type)
@@ -122,7 +130,10 @@ impl FailoverSwitchManager {
                 }
 
                 // 重建托盘菜单
-                if let Ok(new_menu) = crate::tray::create_tray_menu(app, app_state.inner()) {
+                let proxy_running = app_state.proxy_service.is_running().await;
+                if let Ok(new_menu) =
+                    crate::tray::create_tray_menu(app, app_state.inner(), proxy_running)
+                {
                     if let Some(tray) = app.tray_by_id("main") {
                         if let Err(e) = tray.set_menu(Some(new_menu)) {
                             log::error!("[Failover] 更新托盘菜单失败: {e}");
@@ -8,14 +8,19 @@ use super::{
     failover_switch::FailoverSwitchManager,
     provider_router::ProviderRouter,
     providers::{get_adapter, ProviderAdapter, ProviderType},
+    rate_limiter::RateLimiter,
     thinking_rectifier::{rectify_anthropic_request, should_rectify_thinking_signature},
-    types::{ProxyStatus, RectifierConfig},
+    types::{AttemptKind, ForwardAttempt, ProxyStatus, RectifierConfig},
     ProxyError,
 };
-use crate::{app_config::AppType, provider::Provider};
+use crate::{
+    app_config::AppType,
+    provider::{Provider, RateLimitOverflow, RetryBackoff},
+};
 use reqwest::Response;
 use serde_json::Value;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Headers 黑名单 - 不透传到上游的 Headers
@@ -71,6 +76,56 @@ const HEADER_BLACKLIST: &[&str] = &[
     "x-real-ip",
 ];
 
+/// 默认退避基础间隔
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// 指数退避的单次等待上限
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+/// 写入尝试时间线的错误信息最大长度（字符）
+const MAX_ATTEMPT_ERROR_CHARS: usize = 200;
+
+/// 单个供应商的重试策略
+///
+/// 优先级：供应商 `meta.retryConfig` > 应用级 `proxy_config.max_retries` > 默认退避（固定 500ms）。
+/// 供应商未配置 `retryConfig` 时不在同一供应商上重试，失败后直接切换（原有行为）。
+/// 只有瞬时错误（超时、连接失败、429、5xx）才会在同一供应商上重试，
+/// 重试次数用完后再切换到故障转移队列中的下一个供应商。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff: RetryBackoff,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// 合并供应商配置与应用级重试次数
+    ///
+    /// 应用级 `max_retries` 仅在供应商显式配置了 `retryConfig`（但未设置次数）时生效。
+    pub fn resolve(provider: &Provider, app_max_retries: u32) -> Self {
+        let config = provider.meta.as_ref().and_then(|m| m.retry_config.as_ref());
+        Self {
+            max_retries: config.map_or(0, |c| c.max_retries.unwrap_or(app_max_retries)),
+            backoff: config.and_then(|c| c.backoff).unwrap_or_default(),
+            base_delay: config
+                .and_then(|c| c.backoff_ms)
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_RETRY_BACKOFF),
+        }
+    }
+
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match self.backoff {
+            RetryBackoff::Fixed => self.base_delay,
+            RetryBackoff::Exponential => {
+                let factor = 1u32 << attempt.saturating_sub(1).min(16);
+                self.base_delay
+                    .saturating_mul(factor)
+                    .min(MAX_RETRY_BACKOFF)
+            }
+        }
+    }
+}
+
 pub struct ForwardResult {
     pub response: Response,
     pub provider: Provider,
@@ -81,6 +136,115 @@ pub struct ForwardError {
     pub provider: Option<Provider>,
 }
 
+/// 单个请求的上游尝试时间线
+///
+/// 由 RequestContext 创建并与 RequestForwarder 共享，记录日志时序列化到 `attempts` 列。
+#[derive(Debug)]
+pub struct AttemptTimeline {
+    started: Instant,
+    attempts: Mutex<Vec<ForwardAttempt>>,
+}
+
+impl AttemptTimeline {
+    pub fn new(started: Instant) -> Self {
+        Self {
+            started,
+            attempts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 新 Provider 的首个请求：此前已发出过请求则视为故障转移
+    fn next_kind(&self) -> AttemptKind {
+        let attempted = self.snapshot().iter().any(|a| a.kind.is_request());
+        if attempted {
+            AttemptKind::Failover
+        } else {
+            AttemptKind::Initial
+        }
+    }
+
+    fn record(
+        &self,
+        provider_id: &str,
+        kind: AttemptKind,
+        attempt_start: Instant,
+        outcome: &Result<Response, ProxyError>,
+    ) {
+        let (status_code, error) = match outcome {
+            Ok(response) => (Some(response.status().as_u16()), None),
+            Err(e) => {
+                let status = match e {
+                    ProxyError::UpstreamError { status, .. } => Some(*status),
+                    _ => None,
+                };
+                (
+                    status,
+                    Some(
+                        e.to_string()
+                            .chars()
+                            .take(MAX_ATTEMPT_ERROR_CHARS)
+                            .collect(),
+                    ),
+                )
+            }
+        };
+        self.push(ForwardAttempt {
+            provider_id: provider_id.to_string(),
+            kind,
+            start_offset_ms: attempt_start.duration_since(self.started).as_millis() as u64,
+            duration_ms: attempt_start.elapsed().as_millis() as u64,
+            status_code,
+            error,
+        });
+    }
+
+    fn skip(&self, provider_id: &str) {
+        self.push(ForwardAttempt {
+            provider_id: provider_id.to_string(),
+            kind: AttemptKind::CircuitSkip,
+            start_offset_ms: self.started.elapsed().as_millis() as u64,
+            duration_ms: 0,
+            status_code: None,
+            error: None,
+        });
+    }
+
+    /// 记录一次本地限流：`waited` 为排队等待时长（快速失败时为 0）
+    fn rate_limited(&self, provider_id: &str, waited: Duration) {
+        let elapsed = self.started.elapsed();
+        self.push(ForwardAttempt {
+            provider_id: provider_id.to_string(),
+            kind: AttemptKind::RateLimited,
+            start_offset_ms: elapsed.saturating_sub(waited).as_millis() as u64,
+            duration_ms: waited.as_millis() as u64,
+            status_code: None,
+            error: None,
+        });
+    }
+
+    fn push(&self, attempt: ForwardAttempt) {
+        if let Ok(mut attempts) = self.attempts.lock() {
+            attempts.push(attempt);
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ForwardAttempt> {
+        self.attempts
+            .lock()
+            .map(|attempts| attempts.clone())
+            .unwrap_or_default()
+    }
+
+    /// 序列化为 JSON（无记录时返回 None）
+    pub fn to_json(&self) -> Option<String> {
+        let attempts = self.snapshot();
+        if attempts.is_empty() {
+            return None;
+        }
+        serde_json::to_string(&attempts).ok()
+    }
+}
+
 pub struct RequestForwarder {
     /// 共享的 ProviderRouter（持有熔断器状态）
     router: Arc<ProviderRouter>,
@@ -96,6 +260,12 @@ pub struct RequestForwarder {
     rectifier_config: RectifierConfig,
     /// 非流式请求超时（秒）
     non_streaming_timeout: std::time::Duration,
+    /// 应用级最大重试次数（供应商未单独配置时使用）
+    max_retries: u32,
+    /// 供应商速率限制器（与 ProxyState 共享）
+    rate_limiter: Arc<RateLimiter>,
+    /// 上游尝试时间线（与 RequestContext 共享）
+    timeline: Arc<AttemptTimeline>,
 }
 
 impl RequestForwarder {
@@ -111,6 +281,9 @@ impl RequestForwarder {
         _streaming_first_byte_timeout: u64,
         _streaming_idle_timeout: u64,
         rectifier_config: RectifierConfig,
+        max_retries: u32,
+        rate_limiter: Arc<RateLimiter>,
+        timeline: Arc<AttemptTimeline>,
     ) -> Self {
         Self {
             router,
@@ -121,6 +294,45 @@ impl RequestForwarder {
             current_provider_id_at_start,
             rectifier_config,
             non_streaming_timeout: std::time::Duration::from_secs(non_streaming_timeout),
+            max_retries,
+            rate_limiter,
+            timeline,
+        }
+    }
+
+    /// 按供应商的 `rate_limit_rpm` 获取转发令牌
+    ///
+    /// 返回 false 表示超出限制且配置为 failover，应跳过该供应商
+    async fn acquire_rate_limit(&self, provider: &Provider, app_type_str: &str) -> bool {
+        let meta = provider.meta.as_ref();
+        let rpm = meta.and_then(|m| m.rate_limit_rpm).unwrap_or(0);
+        if rpm == 0 || self.rate_limiter.try_acquire(&provider.id, rpm).is_ok() {
+            return true;
+        }
+
+        match meta.and_then(|m| m.rate_limit_overflow).unwrap_or_default() {
+            RateLimitOverflow::Failover => {
+                log::warn!(
+                    "[{}] [FWD-004] Provider {} 超出速率限制 ({} rpm)，跳过",
+                    app_type_str,
+                    provider.name,
+                    rpm
+                );
+                self.timeline.rate_limited(&provider.id, Duration::ZERO);
+                false
+            }
+            RateLimitOverflow::Queue => {
+                let waited = self.rate_limiter.acquire(&provider.id, rpm).await;
+                log::info!(
+                    "[{}] [FWD-004] Provider {} 超出速率限制 ({} rpm)，排队 {}ms",
+                    app_type_str,
+                    provider.name,
+                    rpm,
+                    waited.as_millis()
+                );
+                self.timeline.rate_limited(&provider.id, waited);
+                true
+            }
         }
     }
 
@@ -154,6 +366,7 @@ impl RequestForwarder {
         let mut last_error = None;
         let mut last_provider = None;
         let mut attempted_providers = 0usize;
+        let mut rate_limited_provider: Option<&Provider> = None;
 
         // 整流器重试标记：确保整流最多触发一次
         let mut rectifier_retried = false;
@@ -162,10 +375,10 @@ impl RequestForwarder {
         let bypass_circuit_breaker = providers.len() == 1;
 
         // 依次尝试每个供应商
-        for provider in providers.iter() {
+        'providers: for provider in providers.iter() {
             // 发起请求前先获取熔断器放行许可（HalfOpen 会占用探测名额）
             // 单 Provider 场景下跳过此检查，避免熔断器阻塞所有请求
-            let (allowed, used_half_open_permit) = if bypass_circuit_breaker {
+            let (allowed, mut used_half_open_permit) = if bypass_circuit_breaker {
                 (true, false)
             } else {
                 let permit = self
@@ -176,6 +389,15 @@ impl RequestForwarder {
             };
 
             if !allowed {
+                self.timeline.skip(&provider.id);
+                continue;
+            }
+
+            if !self.acquire_rate_limit(provider, app_type_str).await {
+                self.router
+                    .release_permit_neutral(&provider.id, app_type_str, used_half_open_permit)
+                    .await;
+                rate_limited_provider = Some(provider);
                 continue;
             }
 
@@ -190,11 +412,91 @@ impl RequestForwarder {
                 status.last_request_at = Some(chrono::Utc::now().to_rfc3339());
             }
 
-            // 转发请求（每个 Provider 只尝试一次，重试由客户端控制）
-            match self
-                .forward(provider, endpoint, &body, &headers, adapter.as_ref())
-                .await
-            {
+            // 转发请求：瞬时错误按重试策略在同一 Provider 上重试，每次失败都计入熔断器
+            let policy = RetryPolicy::resolve(provider, self.max_retries);
+            let mut retries = 0u32;
+            let result = loop {
+                let kind = if retries > 0 {
+                    AttemptKind::Retry
+                } else {
+                    self.timeline.next_kind()
+                };
+                let attempt_start = Instant::now();
+                let outcome = self
+                    .forward(provider, endpoint, &body, &headers, adapter.as_ref())
+                    .await;
+                self.timeline
+                    .record(&provider.id, kind, attempt_start, &outcome);
+                match outcome {
+                    Err(e) if retries < policy.max_retries && is_transient_error(&e) => {
+                        let _ = self
+                            .router
+                            .record_result(
+                                &provider.id,
+                                app_type_str,
+                                used_half_open_permit,
+                                false,
+                                Some(e.to_string()),
+                            )
+                            .await;
+
+                        retries += 1;
+                        let delay = policy.delay(retries);
+                        log::warn!(
+                            "[{}] [FWD-003] Provider {} 失败，{}ms 后重试 ({}/{}): {}",
+                            app_type_str,
+                            provider.name,
+                            delay.as_millis(),
+                            retries,
+                            policy.max_retries,
+                            e
+                        );
+                        tokio::time::sleep(delay).await;
+
+                        // 重试前重新获取熔断器许可：熔断已打开时直接切换下一个供应商
+                        if !bypass_circuit_breaker {
+                            let permit = self
+                                .router
+                                .allow_provider_request(&provider.id, app_type_str)
+                                .await;
+                            if !permit.allowed {
+                                self.timeline.skip(&provider.id);
+                                {
+                                    let mut status = self.status.write().await;
+                                    status.last_error =
+                                        Some(format!("Provider {} 失败: {}", provider.name, e));
+                                }
+                                last_error = Some(e);
+                                last_provider = Some(provider.clone());
+                                continue 'providers;
+                            }
+                            used_half_open_permit = permit.used_half_open_permit;
+                        }
+
+                        // 重试同样占用速率配额：超限且配置为 failover 时切换下一个供应商
+                        if !self.acquire_rate_limit(provider, app_type_str).await {
+                            self.router
+                                .release_permit_neutral(
+                                    &provider.id,
+                                    app_type_str,
+                                    used_half_open_permit,
+                                )
+                                .await;
+                            {
+                                let mut status = self.status.write().await;
+                                status.last_error =
+                                    Some(format!("Provider {} 失败: {}", provider.name, e));
+                            }
+                            last_error = Some(e);
+                            last_provider = Some(provider.clone());
+                            continue 'providers;
+                        }
+                    }
+                    result => break result,
+                }
+            };
+
+            match result {
                 Ok(response) => {
                     // 成功：记录成功并更新熔断器
                     let _ = self
@@ -332,10 +634,17 @@ impl RequestForwarder {
                             let _ = std::mem::replace(&mut rectifier_retried, true);
 
                             // 使用同一供应商重试（不计入熔断器）
-                            match self
+                            let attempt_start = Instant::now();
+                            let outcome = self
                                 .forward(provider, endpoint, &body, &headers, adapter.as_ref())
-                                .await
-                            {
+                                .await;
+                            self.timeline.record(
+                                &provider.id,
+                                AttemptKind::Retry,
+                                attempt_start,
+                                &outcome,
+                            );
+                            match outcome {
                                 Ok(response) => {
                                     log::info!("[{app_type_str}] [RECT-002] 整流重试成功");
                                     // 记录成功
@@ -511,6 +820,23 @@ impl RequestForwarder {
         }
 
         if attempted_providers == 0 {
+            // providers 列表非空，但全部被本地限流跳过
+            if let Some(provider) = rate_limited_provider {
+                {
+                    let mut status = self.status.write().await;
+                    status.failed_requests += 1;
+                    status.last_error = Some(format!("Provider {} 超出速率限制", provider.name));
+                    if status.total_requests > 0 {
+                        status.success_rate =
+                            (status.success_requests as f32 / status.total_requests as f32) * 100.0;
+                    }
+                }
+                return Err(ForwardError {
+                    error: ProxyError::RateLimited(provider.name.clone()),
+                    provider: Some(provider.clone()),
+                });
+            }
+
             // providers 列表非空，但全部被熔断器拒绝（典型：HalfOpen 探测名额被占用）
             {
                 let mut status = self.status.write().await;
@@ -555,114 +881,14 @@ impl RequestForwarder {
         headers: &axum::http::HeaderMap,
         adapter: &dyn ProviderAdapter,
     ) -> Result<Response, ProxyError> {
-        // 使用适配器提取 base_url
-        let base_url = adapter.extract_base_url(provider)?;
-
-        // 检查是否需要格式转换
-        let needs_transform = adapter.needs_transform(provider);
-
-        let effective_endpoint =
-            if needs_transform && adapter.name() == "Claude" && endpoint == "/v1/messages" {
-                "/v1/chat/completions"
-            } else {
-                endpoint
-            };
-
-        // 使用适配器构建 URL
-        let url = adapter.build_url(&base_url, effective_endpoint);
-
-        // 应用模型映射（独立于格式转换）
-        let (mapped_body, _original_model, _mapped_model) =
-            super::model_mapper::apply_model_mapping(body.clone(), provider);
-
-        // 转换请求体（如果需要）
-        let request_body = if needs_transform {
-            adapter.transform_request(mapped_body, provider)?
-        } else {
-            mapped_body
-        };
-
-        // 过滤私有参数（以 `_` 开头的字段），防止内部信息泄露到上游
-        // 默认使用空白名单，过滤所有 _ 前缀字段
-        let filtered_body = filter_private_params_with_whitelist(request_body, &[]);
-
-        // 获取 HTTP 客户端：优先使用供应商单独代理配置，否则使用全局客户端
-        let proxy_config = provider.meta.as_ref().and_then(|m| m.proxy_config.as_ref());
-        let client = super::http_client::get_for_provider(proxy_config);
-        let mut request = client.post(&url);
-
-        // 只有当 timeout > 0 时才设置请求超时
-        // Duration::ZERO 在 reqwest 中表示"立刻超时"而不是"禁用超时"
-        // 故障转移关闭时会传入 0，此时应该使用 client 的默认超时（600秒）
-        if !self.non_streaming_timeout.is_zero() {
-            request = request.timeout(self.non_streaming_timeout);
-        }
-
-        // 过滤黑名单 Headers，保护隐私并避免冲突
-        for (key, value) in headers {
-            if HEADER_BLACKLIST
-                .iter()
-                .any(|h| key.as_str().eq_ignore_ascii_case(h))
-            {
-                continue;
-            }
-            request = request.header(key, value);
-        }
-
-        // 处理 anthropic-beta Header（仅 Claude）
-        // 关键：确保包含 claude-code-20250219 标记，这是上游服务验证请求来源的依据
-        // 如果客户端发送的 beta 标记中没有包含 claude-code-20250219，需要补充
-        if adapter.name() == "Claude" {
-            const CLAUDE_CODE_BETA: &str = "claude-code-20250219";
-            let beta_value = if let Some(beta) = headers.get("anthropic-beta") {
-                if let Ok(beta_str) = beta.to_str() {
-                    // 检查是否已包含 claude-code-20250219
-                    if beta_str.contains(CLAUDE_CODE_BETA) {
-                        beta_str.to_string()
-                    } else {
-                        // 补充 claude-code-20250219
-                        format!("{CLAUDE_CODE_BETA},{beta_str}")
-                    }
-                } else {
-                    CLAUDE_CODE_BETA.to_string()
-                }
-            } else {
-                // 如果客户端没有发送，使用默认值
-                CLAUDE_CODE_BETA.to_string()
-            };
-            request = request.header("anthropic-beta", &beta_value);
-        }
-
-        // 客户端 IP 透传（默认开启）
-        if let Some(xff) = headers.get("x-forwarded-for") {
-            if let Ok(xff_str) = xff.to_str() {
-                request = request.header("x-forwarded-for", xff_str);
-            }
-        }
-        if let Some(real_ip) = headers.get("x-real-ip") {
-            if let Ok(real_ip_str) = real_ip.to_str() {
-                request = request.header("x-real-ip", real_ip_str);
-            }
-        }
-
-        // 禁用压缩，避免 gzip 流式响应解析错误
-        // 参考 CCH: undici 在连接提前关闭时会对不完整的 gzip 流抛出错误
-        request = request.header("accept-encoding", "identity");
-
-        // 使用适配器添加认证头
-        if let Some(auth) = adapter.extract_auth(provider) {
-            request = adapter.add_auth_headers(request, &auth);
-        }
-
-        // anthropic-version 统一处理（仅 Claude）：优先使用客户端的版本号，否则使用默认值
-        // 注意：只设置一次，避免重复
-        if adapter.name() == "Claude" {
-            let version_str = headers
-                .get("anthropic-version")
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("2023-06-01");
-            request = request.header("anthropic-version", version_str);
-        }
+        let (request, url, filtered_body) = build_upstream_request(
+            provider,
+            endpoint,
+            body,
+            headers,
+            adapter,
+            self.non_streaming_timeout,
+        )?;
 
         // 输出请求信息日志
         let tag = adapter.name();
@@ -729,6 +955,142 @@ impl RequestForwarder {
     }
 }
 
+/// 构建发往上游的请求：URL、模型映射、格式转换、请求头与认证
+///
+/// 正常转发与手动重放共用；返回尚未附带请求体的请求、目标 URL 与最终请求体。
+pub(crate) fn build_upstream_request(
+    provider: &Provider,
+    endpoint: &str,
+    body: &Value,
+    headers: &axum::http::HeaderMap,
+    adapter: &dyn ProviderAdapter,
+    timeout: Duration,
+) -> Result<(reqwest::RequestBuilder, String, Value), ProxyError> {
+    // 使用适配器提取 base_url
+    let base_url = adapter.extract_base_url(provider)?;
+
+    // 检查是否需要格式转换
+    let needs_transform = adapter.needs_transform(provider);
+
+    let effective_endpoint =
+        if needs_transform && adapter.name() == "Claude" && endpoint == "/v1/messages" {
+            "/v1/chat/completions"
+        } else {
+            endpoint
+        };
+
+    // 使用适配器构建 URL
+    let url = adapter.build_url(&base_url, effective_endpoint);
+
+    // 应用模型映射（独立于格式转换）
+    let (mapped_body, _original_model, _mapped_model) =
+        super::model_mapper::apply_model_mapping(body.clone(), provider);
+
+    // 应用供应商模型别名（规范模型名 → 供应商实际模型名）
+    let (mapped_body, _aliased_model) =
+        super::model_mapper::apply_model_aliases(mapped_body, provider);
+
+    // 转换请求体（如果需要）
+    let request_body = if needs_transform {
+        adapter.transform_request(mapped_body, provider)?
+    } else {
+        mapped_body
+    };
+
+    // 过滤私有参数（以 `_` 开头的字段），防止内部信息泄露到上游
+    // 默认使用空白名单，过滤所有 _ 前缀字段
+    let filtered_body = filter_private_params_with_whitelist(request_body, &[]);
+
+    // 获取 HTTP 客户端：优先使用供应商单独代理配置，否则使用全局客户端
+    let proxy_config = provider.meta.as_ref().and_then(|m| m.proxy_config.as_ref());
+    let client = super::http_client::get_for_provider(proxy_config);
+    let mut request = client.post(&url);
+
+    // 只有当 timeout > 0 时才设置请求超时
+    // Duration::ZERO 在 reqwest 中表示"立刻超时"而不是"禁用超时"
+    // 故障转移关闭时会传入 0，此时应该使用 client 的默认超时（600秒）
+    if !timeout.is_zero() {
+        request = request.timeout(timeout);
+    }
+
+    // 过滤黑名单 Headers，保护隐私并避免冲突
+    for (key, value) in headers {
+        if HEADER_BLACKLIST
+            .iter()
+            .any(|h| key.as_str().eq_ignore_ascii_case(h))
+        {
+            continue;
+        }
+        request = request.header(key, value);
+    }
+
+    // 处理 anthropic-beta Header（仅 Claude）
+    // 关键：确保包含 claude-code-20250219 标记，这是上游服务验证请求来源的依据
+    // 如果客户端发送的 beta 标记中没有包含 claude-code-20250219，需要补充
+    if adapter.name() == "Claude" {
+        const CLAUDE_CODE_BETA: &str = "claude-code-20250219";
+        let beta_value = if let Some(beta) = headers.get("anthropic-beta") {
+            if let Ok(beta_str) = beta.to_str() {
+                // 检查是否已包含 claude-code-20250219
+                if beta_str.contains(CLAUDE_CODE_BETA) {
+                    beta_str.to_string()
+                } else {
+                    // 补充 claude-code-20250219
+                    format!("{CLAUDE_CODE_BETA},{beta_str}")
+                }
+            } else {
+                CLAUDE_CODE_BETA.to_string()
+            }
+        } else {
+            // 如果客户端没有发送，使用默认值
+            CLAUDE_CODE_BETA.to_string()
+        };
+        request = request.header("anthropic-beta", &beta_value);
+    }
+
+    // 客户端 IP 透传（默认开启）
+    if let Some(xff) = headers.get("x-forwarded-for") {
+        if let Ok(xff_str) = xff.to_str() {
+            request = request.header("x-forwarded-for", xff_str);
+        }
+    }
+    if let Some(real_ip) = headers.get("x-real-ip") {
+        if let Ok(real_ip_str) = real_ip.to_str() {
+            request = request.header("x-real-ip", real_ip_str);
+        }
+    }
+
+    // 禁用压缩，避免 gzip 流式响应解析错误
+    // 参考 CCH: undici 在连接提前关闭时会对不完整的 gzip 流抛出错误
+    request = request.header("accept-encoding", "identity");
+
+    // 使用适配器添加认证头
+    if let Some(auth) = adapter.extract_auth(provider) {
+        request = adapter.add_auth_headers(request, &auth);
+    }
+
+    // anthropic-version 统一处理（仅 Claude）：优先使用客户端的版本号，否则使用默认值
+    // 注意：只设置一次，避免重复
+    if adapter.name() == "Claude" {
+        let version_str = headers
+            .get("anthropic-version")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("2023-06-01");
+        request = request.header("anthropic-version", version_str);
+    }
+
+    // OpenCode 使用 @ai-sdk/anthropic 时，透传客户端的 anthropic-* 头（不补默认值）
+    if adapter.name() == "OpenCode" {
+        for key in ["anthropic-version", "anthropic-beta"] {
+            if let Some(value) = headers.get(key) {
+                request = request.header(key, value);
+            }
+        }
+    }
+
+    Ok((request, url, filtered_body))
+}
+
 /// 从 ProxyError 中提取错误消息
 fn extract_error_message(error: &ProxyError) -> Option<String> {
     match error {
@@ -736,3 +1098,236 @@ fn extract_error_message(error: &ProxyError) -> Option<String> {
         _ => Some(error.to_string()),
     }
 }
+
+/// 是否为值得在同一供应商上重试的瞬时错误（超时、连接失败、429、5xx）
+fn is_transient_error(error: &ProxyError) -> bool {
+    match error {
+        ProxyError::Timeout(_) | ProxyError::ForwardFailed(_) => true,
+        ProxyError::UpstreamError { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::provider::{ProviderMeta, ProviderRetryConfig};
+    use axum::{http::StatusCode, routing::post, Json, Router};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn claude_provider(id: &str, base_url: &str) -> Provider {
+        Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": base_url, "ANTHROPIC_AUTH_TOKEN": "sk-test" } }),
+            None,
+        )
+    }
+
+    #[test]
+    fn retry_policy_prefers_provider_config() {
+        let mut provider = claude_provider("a", "https://a.example.com");
+        assert_eq!(
+            RetryPolicy::resolve(&provider, 3),
+            RetryPolicy {
+                max_retries: 0,
+                backoff: RetryBackoff::Fixed,
+                base_delay: DEFAULT_RETRY_BACKOFF,
+            }
+        );
+
+        provider.meta = Some(ProviderMeta {
+            retry_config: Some(ProviderRetryConfig {
+                max_retries: Some(5),
+                backoff: Some(RetryBackoff::Exponential),
+                backoff_ms: Some(100),
+            }),
+            ..Default::default()
+        });
+        let policy = RetryPolicy::resolve(&provider, 3);
+        assert_eq!(policy.max_retries, 5);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(40), MAX_RETRY_BACKOFF);
+
+        // 显式配置了 retryConfig 但未设置次数时使用应用级重试次数
+        provider.meta = Some(ProviderMeta {
+            retry_config: Some(ProviderRetryConfig::default()),
+            ..Default::default()
+        });
+        assert_eq!(RetryPolicy::resolve(&provider, 3).max_retries, 3);
+    }
+
+    #[tokio::test]
+    async fn provider_with_zero_retries_fails_over_immediately() {
+        let flaky_hits = Arc::new(AtomicUsize::new(0));
+        let hits = flaky_hits.clone();
+        let app = Router::new()
+            .route(
+                "/flaky/v1/messages",
+                post(move || {
+                    let hits = hits.clone();
+                    async move {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        (StatusCode::SERVICE_UNAVAILABLE, "overloaded")
+                    }
+                }),
+            )
+            .route(
+                "/stable/v1/messages",
+                post(|| async { Json(json!({ "type": "message" })) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let mut flaky = claude_provider("flaky", &format!("http://{addr}/flaky"));
+        flaky.meta = Some(ProviderMeta {
+            retry_config: Some(ProviderRetryConfig {
+                max_retries: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let stable = claude_provider("stable", &format!("http://{addr}/stable"));
+
+        let db = Arc::new(Database::memory().expect("db"));
+        let timeline = Arc::new(AttemptTimeline::new(Instant::now()));
+        let forwarder = RequestForwarder::new(
+            Arc::new(ProviderRouter::new(db.clone())),
+            0,
+            Arc::new(RwLock::new(ProxyStatus::default())),
+            Arc::new(RwLock::new(std::collections::HashMap::new())),
+            Arc::new(FailoverSwitchManager::new(db)),
+            None,
+            "stable".to_string(),
+            0,
+            0,
+            RectifierConfig::default(),
+            // 应用级允许重试 3 次，供应商配置应优先生效
+            3,
+            Arc::new(RateLimiter::new()),
+            timeline.clone(),
+        );
+
+        let result = forwarder
+            .forward_with_retry(
+                &AppType::Claude,
+                "/v1/messages",
+                json!({ "model": "claude-sonnet-4", "messages": [] }),
+                axum::http::HeaderMap::new(),
+                vec![flaky, stable],
+            )
+            .await;
+
+        let Ok(result) = result else {
+            panic!("expected failover to the stable provider");
+        };
+        assert_eq!(result.provider.id, "stable");
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 1);
+
+        let attempts = timeline.snapshot();
+        let hops: Vec<_> = attempts
+            .iter()
+            .map(|a| (a.provider_id.as_str(), a.kind, a.status_code))
+            .collect();
+        assert_eq!(
+            hops,
+            vec![
+                ("flaky", AttemptKind::Initial, Some(503)),
+                ("stable", AttemptKind::Failover, Some(200)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rate_limited_provider_fails_over_when_configured() {
+        let app = Router::new()
+            .route(
+                "/limited/v1/messages",
+                post(|| async { Json(json!({ "type": "message" })) }),
+            )
+            .route(
+                "/stable/v1/messages",
+                post(|| async { Json(json!({ "type": "message" })) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let mut limited = claude_provider("limited", &format!("http://{addr}/limited"));
+        limited.meta = Some(ProviderMeta {
+            rate_limit_rpm: Some(1),
+            rate_limit_overflow: Some(RateLimitOverflow::Failover),
+            ..Default::default()
+        });
+        let stable = claude_provider("stable", &format!("http://{addr}/stable"));
+
+        // 先耗尽 limited 的令牌桶
+        let rate_limiter = Arc::new(RateLimiter::new());
+        assert!(rate_limiter.try_acquire("limited", 1).is_ok());
+
+        let db = Arc::new(Database::memory().expect("db"));
+        let timeline = Arc::new(AttemptTimeline::new(Instant::now()));
+        let forwarder = RequestForwarder::new(
+            Arc::new(ProviderRouter::new(db.clone())),
+            0,
+            Arc::new(RwLock::new(ProxyStatus::default())),
+            Arc::new(RwLock::new(std::collections::HashMap::new())),
+            Arc::new(FailoverSwitchManager::new(db)),
+            None,
+            "limited".to_string(),
+            0,
+            0,
+            RectifierConfig::default(),
+            0,
+            rate_limiter,
+            timeline.clone(),
+        );
+
+        let forward = |providers: Vec<Provider>| {
+            forwarder.forward_with_retry(
+                &AppType::Claude,
+                "/v1/messages",
+                json!({ "model": "claude-sonnet-4", "messages": [] }),
+                axum::http::HeaderMap::new(),
+                providers,
+            )
+        };
+
+        let Ok(result) = forward(vec![limited.clone(), stable]).await else {
+            panic!("expected failover to the stable provider");
+        };
+        assert_eq!(result.provider.id, "stable");
+
+        let hops: Vec<_> = timeline
+            .snapshot()
+            .iter()
+            .map(|a| (a.provider_id.clone(), a.kind))
+            .collect();
+        assert_eq!(
+            hops,
+            vec![
+                ("limited".to_string(), AttemptKind::RateLimited),
+                ("stable".to_string(), AttemptKind::Initial),
+            ]
+        );
+
+        // 没有其它供应商可切换时返回本地限流错误
+        let Err(err) = forward(vec![limited]).await else {
+            panic!("expected local rate limit error");
+        };
+        assert!(matches!(err.error, ProxyError::RateLimited(_)));
+        assert_eq!(err.provider.map(|p| p.id).as_deref(), Some("limited"));
+    }
+}
@@ -5,14 +5,15 @@
 use crate::app_config::AppType;
 use crate::provider::Provider;
 use crate::proxy::{
-    extract_session_id,
-    forwarder::RequestForwarder,
+    body_capture, extract_session_id,
+    forwarder::{AttemptTimeline, RequestForwarder},
     server::ProxyState,
     types::{AppProxyConfig, RectifierConfig},
     ProxyError,
 };
 use axum::http::HeaderMap;
 use serde_json::Value;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// 流式超时配置
@@ -62,6 +63,10 @@ pub struct RequestContext {
     pub request_body: Value,
     /// 整流器配置
     pub rectifier_config: RectifierConfig,
+    /// 脱敏后的请求头（仅在开启 capture_bodies 时记录，用于失败请求调试）
+    pub captured_headers: Option<Value>,
+    /// 上游尝试时间线（重试、故障转移、熔断跳过）
+    pub attempts: Arc<AttemptTimeline>,
 }
 
 impl RequestContext {
@@ -84,6 +89,40 @@ impl RequestContext {
         app_type: AppType,
         tag: &'static str,
         app_type_str: &'static str,
+    ) -> Result<Self, ProxyError> {
+        Self::build(state, body, headers, app_type, tag, app_type_str, None).await
+    }
+
+    /// 创建固定使用指定 Provider 的请求上下文（不参与故障转移，如 OpenCode 按供应商路由）
+    pub async fn for_provider(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        app_type: AppType,
+        tag: &'static str,
+        app_type_str: &'static str,
+        provider: Provider,
+    ) -> Result<Self, ProxyError> {
+        Self::build(
+            state,
+            body,
+            headers,
+            app_type,
+            tag,
+            app_type_str,
+            Some(provider),
+        )
+        .await
+    }
+
+    async fn build(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        app_type: AppType,
+        tag: &'static str,
+        app_type_str: &'static str,
+        pinned: Option<Provider>,
     ) -> Result<Self, ProxyError> {
         let start_time = Instant::now();
 
@@ -94,11 +133,18 @@ impl RequestContext {
             .await
             .map_err(|e| ProxyError::DatabaseError(e.to_string()))?;
 
+        let captured_headers = app_config
+            .capture_bodies
+            .then(|| body_capture::redact_headers(headers));
+
         // 从数据库读取整流器配置
         let rectifier_config = state.db.get_rectifier_config().unwrap_or_default();
 
-        let current_provider_id =
-            crate::settings::get_current_provider(&app_type).unwrap_or_default();
+        // 固定 Provider 时以其为“当前供应商”，避免转发成功后触发切换
+        let current_provider_id = match &pinned {
+            Some(provider) => provider.id.clone(),
+            None => crate::settings::get_current_provider(&app_type).unwrap_or_default(),
+        };
 
         // 从请求体提取模型名称
         let request_model = body
@@ -121,17 +167,22 @@ impl RequestContext {
 
         // 使用共享的 ProviderRouter 选择 Provider（熔断器状态跨请求保持）
         // 注意：只在这里调用一次，结果传递给 forwarder，避免重复消耗 HalfOpen 名额
-        let providers = state
-            .provider_router
-            .select_providers(app_type_str)
-            .await
-            .map_err(|e| match e {
-                crate::error::AppError::AllProvidersCircuitOpen => {
-                    ProxyError::AllProvidersCircuitOpen
-                }
-                crate::error::AppError::NoProvidersConfigured => ProxyError::NoProvidersConfigured,
-                _ => ProxyError::DatabaseError(e.to_string()),
-            })?;
+        let providers = match pinned {
+            Some(provider) => vec![provider],
+            None => state
+                .provider_router
+                .select_providers(app_type_str)
+                .await
+                .map_err(|e| match e {
+                    crate::error::AppError::AllProvidersCircuitOpen => {
+                        ProxyError::AllProvidersCircuitOpen
+                    }
+                    crate::error::AppError::NoProvidersConfigured => {
+                        ProxyError::NoProvidersConfigured
+                    }
+                    _ => ProxyError::DatabaseError(e.to_string()),
+                })?,
+        };
 
         let provider = providers
             .first()
@@ -160,6 +211,8 @@ impl RequestContext {
             session_id,
             request_body: body.clone(),
             rectifier_config,
+            captured_headers,
+            attempts: Arc::new(AttemptTimeline::new(start_time)),
         })
     }
 
@@ -220,6 +273,9 @@ impl RequestContext {
             first_byte_timeout,
             idle_timeout,
             self.rectifier_config.clone(),
+            self.app_config.max_retries,
+            state.rate_limiter.clone(),
+            self.attempts.clone(),
         )
     }
 
@@ -230,6 +286,11 @@ impl RequestContext {
         self.providers.clone()
     }
 
+    /// 上游尝试时间线的 JSON（无尝试时返回 None）
+    pub fn attempts_json(&self) -> Option<String> {
+        self.attempts.to_json()
+    }
+
     /// 计算请求延迟（毫秒）
     #[inline]
     pub fn latency_ms(&self) -> u64 {
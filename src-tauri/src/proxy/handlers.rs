@@ -8,11 +8,13 @@
 //! - Claude 的格式转换逻辑保留在此文件（用于 OpenRouter 旧接口回退）
 
 use super::{
+    body_capture::CapturedBodies,
     error_mapper::{get_error_message, map_proxy_error_to_status},
     handler_config::{
         CLAUDE_PARSER_CONFIG, CODEX_PARSER_CONFIG, GEMINI_PARSER_CONFIG, OPENAI_PARSER_CONFIG,
     },
     handler_context::RequestContext,
+    model_mapper,
     providers::{get_adapter, streaming::create_anthropic_sse_stream, transform},
     response_processor::{create_logged_passthrough_stream, process_response, SseUsageCollector},
     server::ProxyState,
@@ -21,7 +23,12 @@ use super::{
     ProxyError,
 };
 use crate::app_config::AppType;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use serde_json::{json, Value};
 
 // ============================================================================
@@ -84,7 +91,7 @@ pub async fn handle_messages(
             if let Some(provider) = err.provider.take() {
                 ctx.provider = provider;
             }
-            log_forward_error(&state, &ctx, is_stream, &err.error);
+            log_forward_error(&state, &ctx, "/v1/messages", is_stream, &err.error);
             return Err(err.error);
         }
     };
@@ -129,6 +136,7 @@ async fn handle_claude_transform(
             let model = ctx.request_model.clone();
             let status_code = status.as_u16();
             let start_time = ctx.start_time;
+            let attempts = ctx.attempts_json();
 
             SseUsageCollector::new(start_time, move |events, first_token_ms| {
                 if let Some(usage) = TokenUsage::from_claude_stream_events(&events) {
@@ -136,6 +144,7 @@ async fn handle_claude_transform(
                     let state = state.clone();
                     let provider_id = provider_id.clone();
                     let model = model.clone();
+                    let attempts = attempts.clone();
 
                     tokio::spawn(async move {
                         log_usage(
@@ -149,6 +158,7 @@ async fn handle_claude_transform(
                             first_token_ms,
                             true,
                             status_code,
+                            attempts,
                         )
                         .await;
                     });
@@ -201,10 +211,11 @@ async fn handle_claude_transform(
         ProxyError::TransformError(format!("Failed to parse OpenAI response: {e}"))
     })?;
 
-    let anthropic_response = transform::openai_to_anthropic(openai_response).map_err(|e| {
+    let mut anthropic_response = transform::openai_to_anthropic(openai_response).map_err(|e| {
         log::error!("[Claude] 转换响应失败: {e}");
         e
     })?;
+    model_mapper::restore_canonical_model(&mut anthropic_response, &ctx.provider);
 
     // 记录使用量
     if let Some(usage) = TokenUsage::from_claude_response(&anthropic_response) {
@@ -219,6 +230,7 @@ async fn handle_claude_transform(
             let state = state.clone();
             let provider_id = ctx.provider.id.clone();
             let model = model.to_string();
+            let attempts = ctx.attempts_json();
             async move {
                 log_usage(
                     &state,
@@ -231,6 +243,7 @@ async fn handle_claude_transform(
                     None,
                     false,
                     status.as_u16(),
+                    attempts,
                 )
                 .await;
             }
@@ -299,7 +312,7 @@ pub async fn handle_chat_completions(
             if let Some(provider) = err.provider.take() {
                 ctx.provider = provider;
             }
-            log_forward_error(&state, &ctx, is_stream, &err.error);
+            log_forward_error(&state, &ctx, "/chat/completions", is_stream, &err.error);
             return Err(err.error);
         }
     };
@@ -343,7 +356,7 @@ pub async fn handle_responses(
             if let Some(provider) = err.provider.take() {
                 ctx.provider = provider;
             }
-            log_forward_error(&state, &ctx, is_stream, &err.error);
+            log_forward_error(&state, &ctx, "/responses", is_stream, &err.error);
             return Err(err.error);
         }
     };
@@ -429,7 +442,7 @@ pub async fn handle_gemini(
             if let Some(provider) = err.provider.take() {
                 ctx.provider = provider;
             }
-            log_forward_error(&state, &ctx, is_stream, &err.error);
+            log_forward_error(&state, &ctx, endpoint, is_stream, &err.error);
             return Err(err.error);
         }
     };
@@ -440,6 +453,94 @@ pub async fn handle_gemini(
     process_response(response, &ctx, &state, &GEMINI_PARSER_CONFIG).await
 }
 
+// ============================================================================
+// OpenCode 处理器
+// ============================================================================
+
+/// 处理 /opencode/:provider_id/*path 请求
+///
+/// OpenCode 为累加模式，接管时每个供应商的 baseURL 指向各自的路由，
+/// 因此直接使用路径中的供应商，不参与故障转移。
+pub async fn handle_opencode(
+    State(state): State<ProxyState>,
+    Path((provider_id, path)): Path<(String, String)>,
+    uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
+    Json(body): Json<Value>,
+) -> Result<axum::response::Response, ProxyError> {
+    let provider = state
+        .db
+        .get_provider_by_id(&provider_id, "opencode")
+        .map_err(|e| ProxyError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| {
+            ProxyError::InvalidRequest(format!("OpenCode 供应商不存在: {provider_id}"))
+        })?;
+
+    let npm = provider
+        .settings_config
+        .get("npm")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let mut ctx = RequestContext::for_provider(
+        &state,
+        &body,
+        &headers,
+        AppType::OpenCode,
+        "OpenCode",
+        "opencode",
+        provider,
+    )
+    .await?;
+    if npm == "@ai-sdk/google" {
+        ctx = ctx.with_model_from_uri(&uri);
+    }
+
+    // 保留查询参数（Gemini 流式请求使用 ?alt=sse）
+    let endpoint = match uri.query() {
+        Some(query) => format!("/{path}?{query}"),
+        None => format!("/{path}"),
+    };
+
+    let is_stream = body
+        .get("stream")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let forwarder = ctx.create_forwarder(&state);
+    let result = match forwarder
+        .forward_with_retry(
+            &AppType::OpenCode,
+            &endpoint,
+            body,
+            headers,
+            ctx.get_providers(),
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(mut err) => {
+            if let Some(provider) = err.provider.take() {
+                ctx.provider = provider;
+            }
+            log_forward_error(&state, &ctx, &endpoint, is_stream, &err.error);
+            return Err(err.error);
+        }
+    };
+
+    ctx.provider = result.provider;
+    let response = result.response;
+
+    let parser_config = match npm.as_str() {
+        "@ai-sdk/anthropic" => &CLAUDE_PARSER_CONFIG,
+        "@ai-sdk/google" => &GEMINI_PARSER_CONFIG,
+        _ if path.ends_with("responses") => &CODEX_PARSER_CONFIG,
+        _ => &OPENAI_PARSER_CONFIG,
+    };
+    process_response(response, &ctx, &state, parser_config).await
+}
+
 // ============================================================================
 // 使用量记录（保留用于 Claude 转换逻辑）
 // ============================================================================
@@ -447,6 +548,7 @@ pub async fn handle_gemini(
 fn log_forward_error(
     state: &ProxyState,
     ctx: &RequestContext,
+    endpoint: &str,
     is_streaming: bool,
     error: &ProxyError,
 ) {
@@ -458,7 +560,7 @@ fn log_forward_error(
     let request_id = uuid::Uuid::new_v4().to_string();
 
     if let Err(e) = logger.log_error_with_context(
-        request_id,
+        request_id.clone(),
         ctx.provider.id.clone(),
         ctx.app_type_str.to_string(),
         ctx.request_model.clone(),
@@ -468,8 +570,26 @@ fn log_forward_error(
         is_streaming,
         Some(ctx.session_id.clone()),
         None,
+        ctx.attempts_json(),
     ) {
         log::warn!("记录失败请求日志失败: {e}");
+        return;
+    }
+
+    let response_body = match error {
+        ProxyError::UpstreamError { body, .. } => body.as_deref(),
+        _ => None,
+    };
+    if let Some(captured) = CapturedBodies::for_failed_request(
+        &ctx.app_config,
+        endpoint,
+        ctx.captured_headers.as_ref(),
+        &ctx.request_body,
+        response_body,
+    ) {
+        if let Err(e) = captured.save(&state.db, &request_id) {
+            log::warn!("保存失败请求的请求/响应体失败: {e}");
+        }
     }
 }
 
@@ -486,6 +606,7 @@ async fn log_usage(
     first_token_ms: Option<u64>,
     is_streaming: bool,
     status_code: u16,
+    attempts: Option<String>,
 ) {
     use super::usage::logger::UsageLogger;
 
@@ -516,6 +637,7 @@ async fn log_usage(
         None,
         None, // provider_type
         is_streaming,
+        attempts,
     ) {
         log::warn!("[USG-001] 记录使用量失败: {e}");
     }
@@ -3,12 +3,14 @@
 //! 提供支持全局代理配置的 HTTP 客户端。
 //! 所有需要发送 HTTP 请求的模块都应使用此模块提供的客户端。
 
+use super::types::{GlobalNetworkConfig, ProxyMode};
 use crate::provider::ProviderProxyConfig;
 use once_cell::sync::OnceCell;
-use reqwest::Client;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{Client, ClientBuilder};
 use std::env;
-use std::net::IpAddr;
-use std::sync::RwLock;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 /// 全局 HTTP 客户端实例
@@ -17,6 +19,12 @@ static GLOBAL_CLIENT: OnceCell<RwLock<Client>> = OnceCell::new();
 /// 当前代理 URL（用于日志和状态查询）
 static CURRENT_PROXY_URL: OnceCell<RwLock<Option<String>>> = OnceCell::new();
 
+/// 当前出站代理模式
+static CURRENT_PROXY_MODE: OnceCell<RwLock<ProxyMode>> = OnceCell::new();
+
+/// 当前全局网络配置（DNS 覆盖 / IPv6 优先）
+static CURRENT_NETWORK_CONFIG: OnceCell<RwLock<GlobalNetworkConfig>> = OnceCell::new();
+
 /// CC Switch 代理服务器当前监听的端口
 static CC_SWITCH_PROXY_PORT: OnceCell<RwLock<u16>> = OnceCell::new();
 
@@ -36,7 +44,7 @@ pub fn set_proxy_port(port: u16) {
 }
 
 /// 获取 CC Switch 代理服务器的监听端口
-fn get_proxy_port() -> u16 {
+pub(crate) fn get_proxy_port() -> u16 {
     CC_SWITCH_PROXY_PORT
         .get()
         .and_then(|lock| lock.read().ok())
@@ -53,7 +61,11 @@ fn get_proxy_port() -> u16 {
 ///   传入 None 或空字符串表示直连
 pub fn init(proxy_url: Option<&str>) -> Result<(), String> {
     let effective_url = proxy_url.filter(|s| !s.trim().is_empty());
-    let client = build_client(effective_url)?;
+    let client = build_client_with_mode(
+        current_proxy_mode(),
+        effective_url,
+        &current_network_config(),
+    )?;
 
     // 尝试初始化全局客户端，如果已存在则记录警告并使用 apply_proxy 更新
     if GLOBAL_CLIENT.set(RwLock::new(client.clone())).is_err() {
@@ -93,7 +105,11 @@ pub fn init(proxy_url: Option<&str>) -> Result<(), String> {
 pub fn validate_proxy(proxy_url: Option<&str>) -> Result<(), String> {
     let effective_url = proxy_url.filter(|s| !s.trim().is_empty());
     // 只调用 build_client 来验证，但不应用
-    build_client(effective_url)?;
+    build_client_with_mode(
+        current_proxy_mode(),
+        effective_url,
+        &current_network_config(),
+    )?;
     Ok(())
 }
 
@@ -106,7 +122,11 @@ pub fn validate_proxy(proxy_url: Option<&str>) -> Result<(), String> {
 /// * `proxy_url` - 代理 URL，None 或空字符串表示直连
 pub fn apply_proxy(proxy_url: Option<&str>) -> Result<(), String> {
     let effective_url = proxy_url.filter(|s| !s.trim().is_empty());
-    let new_client = build_client(effective_url)?;
+    let new_client = build_client_with_mode(
+        current_proxy_mode(),
+        effective_url,
+        &current_network_config(),
+    )?;
 
     // 更新客户端
     if let Some(lock) = GLOBAL_CLIENT.get() {
@@ -139,6 +159,58 @@ pub fn apply_proxy(proxy_url: Option<&str>) -> Result<(), String> {
     Ok(())
 }
 
+/// 切换代理模式并应用对应的代理 URL
+///
+/// None 模式忽略传入的 URL 并强制直连；Manual / System 模式使用传入的 URL，
+/// URL 为空时沿用环境变量代理。先以新模式验证，成功后才更新模式记录和客户端。
+pub fn apply_proxy_mode(mode: ProxyMode, proxy_url: Option<&str>) -> Result<(), String> {
+    validate_proxy_mode(mode, proxy_url)?;
+    set_current_proxy_mode(mode)?;
+    apply_proxy(mode_effective_url(mode, proxy_url))
+}
+
+/// 验证代理模式与代理 URL 的组合（不应用）
+pub fn validate_proxy_mode(mode: ProxyMode, proxy_url: Option<&str>) -> Result<(), String> {
+    build_client_with_mode(
+        mode,
+        mode_effective_url(mode, proxy_url),
+        &current_network_config(),
+    )?;
+    Ok(())
+}
+
+fn mode_effective_url(mode: ProxyMode, proxy_url: Option<&str>) -> Option<&str> {
+    match mode {
+        ProxyMode::None => None,
+        ProxyMode::Manual | ProxyMode::System => proxy_url.filter(|s| !s.trim().is_empty()),
+    }
+}
+
+/// 记录当前代理模式（不重建客户端）
+///
+/// 启动时应在 init 之前调用，使初始客户端按保存的模式构建。
+pub fn set_current_proxy_mode(mode: ProxyMode) -> Result<(), String> {
+    if let Some(lock) = CURRENT_PROXY_MODE.get() {
+        let mut current = lock.write().map_err(|e| {
+            log::error!("[GlobalProxy] [GP-013] Failed to acquire proxy mode write lock: {e}");
+            "Failed to update proxy mode: lock poisoned".to_string()
+        })?;
+        *current = mode;
+    } else {
+        let _ = CURRENT_PROXY_MODE.set(RwLock::new(mode));
+    }
+    Ok(())
+}
+
+/// 获取当前代理模式
+pub fn current_proxy_mode() -> ProxyMode {
+    CURRENT_PROXY_MODE
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|mode| *mode)
+        .unwrap_or_default()
+}
+
 /// 更新代理配置（热更新）
 ///
 /// 可在运行时调用以更改代理设置，无需重启应用。
@@ -150,7 +222,11 @@ pub fn apply_proxy(proxy_url: Option<&str>) -> Result<(), String> {
 #[allow(dead_code)]
 pub fn update_proxy(proxy_url: Option<&str>) -> Result<(), String> {
     let effective_url = proxy_url.filter(|s| !s.trim().is_empty());
-    let new_client = build_client(effective_url)?;
+    let new_client = build_client_with_mode(
+        current_proxy_mode(),
+        effective_url,
+        &current_network_config(),
+    )?;
 
     // 更新客户端
     if let Some(lock) = GLOBAL_CLIENT.get() {
@@ -193,7 +269,8 @@ pub fn get() -> Client {
         .map(|c| c.clone())
         .unwrap_or_else(|| {
             log::warn!("[GlobalProxy] [GP-004] Client not initialized, using fallback");
-            build_client(None).unwrap_or_default()
+            build_client_with_mode(current_proxy_mode(), None, &current_network_config())
+                .unwrap_or_default()
         })
 }
 
@@ -213,8 +290,168 @@ pub fn is_proxy_enabled() -> bool {
     get_current_proxy_url().is_some()
 }
 
-/// 构建 HTTP 客户端
-fn build_client(proxy_url: Option<&str>) -> Result<Client, String> {
+/// 获取当前全局网络配置
+pub fn current_network_config() -> GlobalNetworkConfig {
+    CURRENT_NETWORK_CONFIG
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// 验证全局网络配置（不应用）
+///
+/// 使用当前代理 URL 和新配置尝试构建客户端，用于在持久化之前校验 DNS 覆盖是否有效。
+pub fn validate_network_config(config: &GlobalNetworkConfig) -> Result<(), String> {
+    build_client_with_mode(
+        current_proxy_mode(),
+        get_current_proxy_url().as_deref(),
+        config,
+    )?;
+    Ok(())
+}
+
+/// 应用全局网络配置（假设已验证）
+///
+/// 与 apply_proxy 相同，以当前代理 URL 重建客户端后整体替换；
+/// 客户端尚未初始化时只记录配置，由 init 使用。
+pub fn apply_network_config(config: GlobalNetworkConfig) -> Result<(), String> {
+    if let Some(lock) = GLOBAL_CLIENT.get() {
+        let new_client = build_client_with_mode(
+            current_proxy_mode(),
+            get_current_proxy_url().as_deref(),
+            &config,
+        )?;
+        let mut client = lock.write().map_err(|e| {
+            log::error!("[GlobalProxy] [GP-001] Failed to acquire write lock: {e}");
+            "Failed to update network config: lock poisoned".to_string()
+        })?;
+        *client = new_client;
+    }
+
+    log::info!(
+        "[GlobalProxy] Network config applied: {} DNS override(s), prefer_ipv6={}",
+        config.dns_overrides.len(),
+        config.prefer_ipv6
+    );
+
+    if let Some(lock) = CURRENT_NETWORK_CONFIG.get() {
+        let mut current = lock.write().map_err(|e| {
+            log::error!("[GlobalProxy] [GP-012] Failed to acquire network config write lock: {e}");
+            "Failed to update network config record: lock poisoned".to_string()
+        })?;
+        *current = config;
+    } else {
+        let _ = CURRENT_NETWORK_CONFIG.set(RwLock::new(config));
+    }
+
+    Ok(())
+}
+
+/// 构建用于测试代理的临时客户端
+///
+/// 应用当前的 DNS 覆盖与 IPv6 优先设置，使测试结果与实际请求一致。
+pub fn build_test_client(proxy_url: &str, timeout: Duration) -> Result<Client, String> {
+    let proxy = reqwest::Proxy::all(proxy_url).map_err(|e| format!("Invalid proxy URL: {e}"))?;
+    let builder = Client::builder()
+        .proxy(proxy)
+        .timeout(timeout)
+        .connect_timeout(timeout);
+
+    apply_network_options(builder, &current_network_config())?
+        .build()
+        .map_err(|e| format!("Failed to build client: {e}"))
+}
+
+/// 将 DNS 覆盖与 IPv6 优先设置应用到客户端构建器
+///
+/// 注意：经 HTTP 代理或 socks5h 代理转发时由代理端解析域名，此时 DNS 覆盖不生效。
+fn apply_network_options(
+    mut builder: ClientBuilder,
+    config: &GlobalNetworkConfig,
+) -> Result<ClientBuilder, String> {
+    for (host, ip) in &config.dns_overrides {
+        let host = host.trim().to_ascii_lowercase();
+        if host.is_empty() {
+            return Err("DNS override hostname is empty".to_string());
+        }
+        let addr: IpAddr = ip
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid IP address '{ip}' for host '{host}': {e}"))?;
+        // 端口以请求 URL 为准，这里的端口会被 reqwest 忽略
+        builder = builder.resolve(&host, SocketAddr::new(addr, 0));
+    }
+
+    if config.prefer_ipv6 {
+        builder = builder.dns_resolver(Arc::new(PreferIpv6Resolver));
+    }
+
+    Ok(builder)
+}
+
+/// 优先返回 IPv6 地址的 DNS 解析器
+///
+/// 连接时按地址族分组尝试（Happy Eyeballs），首个地址的地址族优先，
+/// 因此把 IPv6 排在前面即可优先走 IPv6，失败时仍会回退到 IPv4。
+struct PreferIpv6Resolver;
+
+impl Resolve for PreferIpv6Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let mut addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            sort_ipv6_first(&mut addrs);
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 稳定排序：IPv6 在前，同一地址族内保持系统解析顺序
+fn sort_ipv6_first(addrs: &mut [SocketAddr]) {
+    addrs.sort_by_key(|addr| !addr.is_ipv6());
+}
+
+/// 构建 HTTP 客户端（Manual 模式）
+#[cfg(test)]
+fn build_client(proxy_url: Option<&str>, network: &GlobalNetworkConfig) -> Result<Client, String> {
+    build_client_with_mode(ProxyMode::Manual, proxy_url, network)
+}
+
+/// 构建使用指定重定向策略的客户端，代理与网络设置沿用全局配置
+///
+/// 全局客户端会自动跟随重定向；需要逐跳校验目标地址的调用方使用此函数。
+pub fn build_client_with_redirect_policy(
+    policy: reqwest::redirect::Policy,
+) -> Result<Client, String> {
+    let proxy_url = get_current_proxy_url();
+    client_builder_with_mode(
+        current_proxy_mode(),
+        proxy_url.as_deref(),
+        &current_network_config(),
+    )?
+    .redirect(policy)
+    .build()
+    .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// 按代理模式构建 HTTP 客户端
+fn build_client_with_mode(
+    mode: ProxyMode,
+    proxy_url: Option<&str>,
+    network: &GlobalNetworkConfig,
+) -> Result<Client, String> {
+    client_builder_with_mode(mode, proxy_url, network)?
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// 按代理模式准备客户端构建器
+fn client_builder_with_mode(
+    mode: ProxyMode,
+    proxy_url: Option<&str>,
+    network: &GlobalNetworkConfig,
+) -> Result<ClientBuilder, String> {
     let mut builder = Client::builder()
         .timeout(Duration::from_secs(600))
         .connect_timeout(Duration::from_secs(30))
@@ -240,6 +477,9 @@ fn build_client(proxy_url: Option<&str>) -> Result<Client, String> {
             .map_err(|e| format!("Invalid proxy URL '{}': {}", mask_url(url), e))?;
         builder = builder.proxy(proxy);
         log::debug!("[GlobalProxy] Proxy configured: {}", mask_url(url));
+    } else if mode == ProxyMode::None {
+        builder = builder.no_proxy();
+        log::debug!("[GlobalProxy] Proxy mode 'none': direct connection");
     } else {
         // 未设置全局代理时，让 reqwest 自动检测系统代理（环境变量）
         // 若系统代理指向本机，禁用系统代理避免自环
@@ -253,9 +493,7 @@ fn build_client(proxy_url: Option<&str>) -> Result<Client, String> {
         }
     }
 
-    builder
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+    apply_network_options(builder, network)
 }
 
 fn system_proxy_points_to_loopback() -> bool {
@@ -275,7 +513,7 @@ fn system_proxy_points_to_loopback() -> bool {
         .any(|value| proxy_points_to_loopback(&value))
 }
 
-fn proxy_points_to_loopback(value: &str) -> bool {
+pub(crate) fn proxy_points_to_loopback(value: &str) -> bool {
     fn host_is_loopback(host: &str) -> bool {
         if host.eq_ignore_ascii_case("localhost") {
             return true;
@@ -457,19 +695,25 @@ mod tests {
 
     #[test]
     fn test_build_client_direct() {
-        let result = build_client(None);
+        let result = build_client(None, &GlobalNetworkConfig::default());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_build_client_with_http_proxy() {
-        let result = build_client(Some("http://127.0.0.1:7890"));
+        let result = build_client(
+            Some("http://127.0.0.1:7890"),
+            &GlobalNetworkConfig::default(),
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_build_client_with_socks5_proxy() {
-        let result = build_client(Some("socks5://127.0.0.1:1080"));
+        let result = build_client(
+            Some("socks5://127.0.0.1:1080"),
+            &GlobalNetworkConfig::default(),
+        );
         assert!(result.is_ok());
     }
 
@@ -477,10 +721,46 @@ mod tests {
     fn test_build_client_invalid_url() {
         // reqwest::Proxy::all 对某些无效 URL 不会立即报错
         // 使用明确无效的 scheme 来触发错误
-        let result = build_client(Some("invalid-scheme://127.0.0.1:7890"));
+        let result = build_client(
+            Some("invalid-scheme://127.0.0.1:7890"),
+            &GlobalNetworkConfig::default(),
+        );
         assert!(result.is_err(), "Should reject invalid proxy scheme");
     }
 
+    #[test]
+    fn test_build_client_with_network_config() {
+        let mut config = GlobalNetworkConfig {
+            prefer_ipv6: true,
+            ..Default::default()
+        };
+        config
+            .dns_overrides
+            .insert("relay.example.com".to_string(), "2001:db8::1".to_string());
+        assert!(build_client(None, &config).is_ok());
+
+        config
+            .dns_overrides
+            .insert("bad.example.com".to_string(), "not-an-ip".to_string());
+        assert!(build_client(None, &config).is_err());
+    }
+
+    #[test]
+    fn test_sort_ipv6_first() {
+        let mut addrs: Vec<SocketAddr> = vec![
+            "1.1.1.1:0".parse().unwrap(),
+            "[2001:db8::1]:0".parse().unwrap(),
+            "8.8.8.8:0".parse().unwrap(),
+            "[2001:db8::2]:0".parse().unwrap(),
+        ];
+        sort_ipv6_first(&mut addrs);
+        let ordered: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+        assert_eq!(
+            ordered,
+            vec!["2001:db8::1", "2001:db8::2", "1.1.1.1", "8.8.8.8"]
+        );
+    }
+
     #[test]
     fn test_proxy_points_to_loopback() {
         // 设置 CC Switch 代理端口为 15721（默认值）
@@ -0,0 +1,265 @@
+//! 本地 HTTP API（供外部工具使用）
+//!
+//! 与代理共用监听端口，挂载在 `/__ccswitch/` 下：
+//! - `GET /__ccswitch/status`：各应用当前供应商、代理运行状态、熔断器状态
+//! - `POST /__ccswitch/switch`：`{ "app": "claude", "provider_id": "..." }`，
+//!   与界面切换走同一条 `ProviderService::switch` 路径
+//!
+//! 所有请求需携带 `Authorization: Bearer <token>`，token 在进程启动时随机生成，
+//! 可通过 `get_local_api_token` 命令获取。`local_api_enabled` 设置关闭时接口整体返回 404。
+
+use super::{server::ProxyState, types::ActiveTarget};
+use crate::app_config::AppType;
+use crate::services::provider::AuditSource;
+use crate::services::ProviderService;
+use crate::store::AppState;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use tauri::{Emitter, Manager};
+
+/// 本地 API 路由前缀
+pub const LOCAL_API_PREFIX: &str = "/__ccswitch";
+
+/// 本次进程的访问 token（重启后变化）
+static LOCAL_API_TOKEN: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().simple().to_string());
+
+/// 获取本地 API 访问 token
+pub fn token() -> &'static str {
+    LOCAL_API_TOKEN.as_str()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwitchRequest {
+    pub app: String,
+    #[serde(alias = "providerId")]
+    pub provider_id: String,
+}
+
+/// GET /__ccswitch/status
+pub async fn status(State(state): State<ProxyState>, headers: HeaderMap) -> Response {
+    if let Err(resp) = authorize(&state, &headers) {
+        return resp;
+    }
+
+    let mut proxy = state.status.read().await.clone();
+    if let Some(start) = *state.start_time.read().await {
+        proxy.uptime_seconds = start.elapsed().as_secs();
+    }
+    proxy.active_targets = state
+        .current_providers
+        .read()
+        .await
+        .iter()
+        .map(|(app_type, (provider_id, provider_name))| ActiveTarget {
+            app_type: app_type.clone(),
+            provider_id: provider_id.clone(),
+            provider_name: provider_name.clone(),
+        })
+        .collect();
+
+    let mut current_providers = Vec::new();
+    for app_type in AppType::all() {
+        let provider = match state.db.get_current_provider(app_type.as_str()) {
+            Ok(Some(id)) => state
+                .db
+                .get_provider_by_id(&id, app_type.as_str())
+                .ok()
+                .flatten()
+                .map(|p| json!({ "id": p.id, "name": p.name }))
+                .unwrap_or_else(|| json!({ "id": id, "name": Value::Null })),
+            Ok(None) => Value::Null,
+            Err(e) => {
+                log::warn!("[LocalApi] 读取 {} 当前供应商失败: {e}", app_type.as_str());
+                Value::Null
+            }
+        };
+        current_providers.push(json!({
+            "app": app_type.as_str(),
+            "provider": provider,
+        }));
+    }
+
+    let circuits: Vec<Value> = state
+        .provider_router
+        .get_all_circuit_breaker_stats()
+        .await
+        .into_iter()
+        .map(|(app_type, provider_id, stats)| {
+            json!({
+                "app": app_type,
+                "providerId": provider_id,
+                "stats": stats,
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "currentProviders": current_providers,
+            "proxy": proxy,
+            "circuits": circuits,
+        })),
+    )
+        .into_response()
+}
+
+/// POST /__ccswitch/switch
+pub async fn switch(State(state): State<ProxyState>, headers: HeaderMap, body: Bytes) -> Response {
+    // 先鉴权再解析请求体，避免未授权请求探测到接口细节
+    if let Err(resp) = authorize(&state, &headers) {
+        return resp;
+    }
+
+    let req: SwitchRequest = match serde_json::from_slice(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, format!("请求体无效: {e}")),
+    };
+
+    let app_type = match AppType::from_str(&req.app) {
+        Ok(app_type) => app_type,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let Some(app_handle) = state.app_handle.clone() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "应用尚未就绪，无法切换供应商".to_string(),
+        );
+    };
+
+    let provider_id = req.provider_id.clone();
+    let app_type_for_task = app_type.clone();
+    // ProviderService::switch 为同步实现（可能包含切换前健康检查），放到阻塞线程执行
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let app_state = app_handle
+            .try_state::<AppState>()
+            .ok_or_else(|| "应用状态不可用".to_string())?;
+        ProviderService::switch_with_source(
+            &app_state,
+            app_type_for_task.clone(),
+            &provider_id,
+            false,
+            AuditSource::LocalApi,
+        )
+        .map_err(|e| e.to_string())?;
+        notify_switched(&app_handle, &app_type_for_task, &provider_id);
+        Ok::<(), String>(())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {
+            log::info!(
+                "[LocalApi] 已通过本地 API 切换 {} 供应商为 {}",
+                app_type.as_str(),
+                req.provider_id
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "success": true,
+                    "app": app_type.as_str(),
+                    "providerId": req.provider_id,
+                })),
+            )
+                .into_response()
+        }
+        Ok(Err(e)) => error_response(StatusCode::BAD_REQUEST, e),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// 更新托盘菜单并通知前端刷新
+fn notify_switched(app: &tauri::AppHandle, app_type: &AppType, provider_id: &str) {
+    crate::tray::refresh_tray_menu(app);
+
+    let event_data = json!({
+        "appType": app_type.as_str(),
+        "providerId": provider_id,
+        "source": "localApi",
+    });
+    if let Err(e) = app.emit("provider-switched", event_data) {
+        log::error!("[LocalApi] 发射 provider-switched 事件失败: {e}");
+    }
+}
+
+/// 校验开关与 token；失败时返回应直接响应的结果
+fn authorize(state: &ProxyState, headers: &HeaderMap) -> Result<(), Response> {
+    let enabled = state.db.get_local_api_enabled().unwrap_or_else(|e| {
+        log::warn!("[LocalApi] 读取本地 API 开关失败: {e}");
+        false
+    });
+    if !enabled {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    match provided {
+        Some(provided) if constant_time_eq(provided.as_bytes(), token().as_bytes()) => Ok(()),
+        _ => Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "缺少或无效的访问 token".to_string(),
+        )),
+    }
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (
+        status,
+        Json(json!({
+            "success": false,
+            "error": message,
+        })),
+    )
+        .into_response()
+}
+
+/// 逐字节比较，耗时与首个不同字节的位置无关
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_stable_within_process() {
+        assert_eq!(token(), token());
+        assert_eq!(token().len(), 32);
+    }
+
+    #[test]
+    fn constant_time_eq_compares_content_and_length() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+
+    #[test]
+    fn switch_request_accepts_snake_and_camel_case() {
+        let req: SwitchRequest =
+            serde_json::from_str(r#"{"app":"claude","provider_id":"p1"}"#).unwrap();
+        assert_eq!(req.provider_id, "p1");
+        let req: SwitchRequest =
+            serde_json::from_str(r#"{"app":"codex","providerId":"p2"}"#).unwrap();
+        assert_eq!(req.app, "codex");
+        assert_eq!(req.provider_id, "p2");
+    }
+}
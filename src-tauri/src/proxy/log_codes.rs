@@ -26,12 +26,16 @@ pub mod srv {
     pub const STOPPED: &str = "SRV-002";
     pub const STOP_TIMEOUT: &str = "SRV-003";
     pub const TASK_ERROR: &str = "SRV-004";
+    pub const DRAINING: &str = "SRV-005";
+    pub const DRAIN_TIMEOUT: &str = "SRV-006";
 }
 
 /// 转发器日志码
 pub mod fwd {
     pub const PROVIDER_FAILED_RETRY: &str = "FWD-001";
     pub const ALL_PROVIDERS_FAILED: &str = "FWD-002";
+    pub const SAME_PROVIDER_RETRY: &str = "FWD-003";
+    pub const RATE_LIMITED: &str = "FWD-004";
 }
 
 /// 故障转移日志码
@@ -41,6 +45,7 @@ pub mod fo {
     pub const LIVE_BACKUP_ERROR: &str = "FO-003";
     pub const ALL_CIRCUIT_OPEN: &str = "FO-004";
     pub const NO_PROVIDERS: &str = "FO-005";
+    pub const LIMIT_TRIPPED: &str = "FO-006";
 }
 
 /// 响应处理日志码
@@ -2,10 +2,13 @@
 //!
 //! 提供本地HTTP代理服务，支持多Provider故障转移和请求透传
 
+pub mod active_requests;
+pub mod body_capture;
 pub mod body_filter;
 pub mod circuit_breaker;
 pub mod error;
 pub mod error_mapper;
+pub mod failover_simulation;
 pub(crate) mod failover_switch;
 mod forwarder;
 pub mod handler_config;
@@ -13,14 +16,19 @@ pub mod handler_context;
 mod handlers;
 mod health;
 pub mod http_client;
+pub mod local_api;
 pub mod log_codes;
 pub mod model_mapper;
+pub mod port;
 pub mod provider_router;
 pub mod providers;
+pub mod rate_limiter;
+pub mod replay;
 pub mod response_handler;
 pub mod response_processor;
 pub(crate) mod server;
 pub mod session;
+pub mod system_proxy;
 pub mod thinking_rectifier;
 pub(crate) mod types;
 pub mod usage;
@@ -28,7 +36,7 @@ pub mod usage;
 // 公开导出给外部使用（commands, services等模块需要）
 #[allow(unused_imports)]
 pub use circuit_breaker::{
-    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats, CircuitState,
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerEvent, CircuitBreakerStats, CircuitState,
 };
 #[allow(unused_imports)]
 pub use error::ProxyError;
@@ -4,6 +4,7 @@
 
 use crate::provider::Provider;
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// 模型映射配置
 pub struct ModelMapping {
@@ -136,6 +137,74 @@ pub fn apply_model_mapping(
     (body, original_model, None)
 }
 
+/// 获取供应商配置的模型别名（未配置时返回 None）
+fn model_aliases(provider: &Provider) -> Option<&HashMap<String, String>> {
+    provider
+        .meta
+        .as_ref()
+        .map(|m| &m.model_aliases)
+        .filter(|aliases| !aliases.is_empty())
+}
+
+/// 按供应商的模型别名改写请求体的 `model` 字段
+///
+/// Anthropic Messages 与 OpenAI Chat/Responses 请求的模型名都位于顶层 `model` 字段，
+/// 其余字段（包括 `stream`）保持不变。
+///
+/// 返回 (改写后的请求体, 改写后的模型名)
+pub fn apply_model_aliases(mut body: Value, provider: &Provider) -> (Value, Option<String>) {
+    let Some(aliases) = model_aliases(provider) else {
+        return (body, None);
+    };
+
+    let alias = body
+        .get("model")
+        .and_then(|m| m.as_str())
+        .and_then(|model| aliases.get(model))
+        .cloned();
+
+    if let Some(ref alias) = alias {
+        log::debug!(
+            "[ModelMapper] 模型别名: {} → {alias}",
+            body["model"].as_str().unwrap_or_default()
+        );
+        body["model"] = serde_json::json!(alias);
+    }
+
+    (body, alias)
+}
+
+/// 将上游返回的模型名反向映射为规范模型名，未命中别名时原样返回
+///
+/// 多个规范名指向同一别名时取字典序最小者，保证统计结果稳定。
+pub fn canonical_model_name(provider: &Provider, model: &str) -> String {
+    model_aliases(provider)
+        .and_then(|aliases| {
+            aliases
+                .iter()
+                .filter(|(_, alias)| alias.as_str() == model)
+                .map(|(canonical, _)| canonical)
+                .min()
+        })
+        .cloned()
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// 将 JSON 响应体顶层的 `model` 字段还原为规范模型名，返回是否发生改写
+pub fn restore_canonical_model(body: &mut Value, provider: &Provider) -> bool {
+    let Some(model) = body.get("model").and_then(|m| m.as_str()) else {
+        return false;
+    };
+
+    let canonical = canonical_model_name(provider, model);
+    if canonical == model {
+        return false;
+    }
+
+    body["model"] = serde_json::json!(canonical);
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,6 +232,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -180,6 +250,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -201,6 +272,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -308,4 +380,94 @@ mod tests {
         assert_eq!(result["model"], "sonnet-mapped");
         assert_eq!(mapped, Some("sonnet-mapped".to_string()));
     }
+
+    fn create_provider_with_aliases() -> Provider {
+        let mut provider = create_provider_without_mapping();
+        let mut meta = crate::provider::ProviderMeta::default();
+        meta.model_aliases.insert(
+            "claude-sonnet-4-5".to_string(),
+            "vendor/sonnet-4.5".to_string(),
+        );
+        meta.model_aliases
+            .insert("gpt-5".to_string(), "vendor/gpt-5".to_string());
+        provider.meta = Some(meta);
+        provider
+    }
+
+    #[test]
+    fn test_alias_rewrites_anthropic_body() {
+        let provider = create_provider_with_aliases();
+        let body = json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 1024,
+            "system": "You are helpful",
+            "messages": [{"role": "user", "content": [{"type": "text", "text": "hi"}]}],
+            "stream": true
+        });
+        let (result, alias) = apply_model_aliases(body.clone(), &provider);
+        assert_eq!(result["model"], "vendor/sonnet-4.5");
+        assert_eq!(alias, Some("vendor/sonnet-4.5".to_string()));
+        // 除 model 外的字段保持不变（包括 stream）
+        assert_eq!(result["stream"], true);
+        assert_eq!(result["messages"], body["messages"]);
+        assert_eq!(result["system"], body["system"]);
+    }
+
+    #[test]
+    fn test_alias_rewrites_openai_body() {
+        let provider = create_provider_with_aliases();
+        let body = json!({
+            "model": "gpt-5",
+            "messages": [
+                {"role": "system", "content": "You are helpful"},
+                {"role": "user", "content": "hi"}
+            ],
+            "stream": true,
+            "stream_options": {"include_usage": true}
+        });
+        let (result, alias) = apply_model_aliases(body.clone(), &provider);
+        assert_eq!(result["model"], "vendor/gpt-5");
+        assert_eq!(alias, Some("vendor/gpt-5".to_string()));
+        assert_eq!(result["stream_options"], body["stream_options"]);
+    }
+
+    #[test]
+    fn test_alias_leaves_unmapped_model() {
+        let provider = create_provider_with_aliases();
+        let body = json!({"model": "claude-haiku-4-5", "messages": []});
+        let (result, alias) = apply_model_aliases(body, &provider);
+        assert_eq!(result["model"], "claude-haiku-4-5");
+        assert!(alias.is_none());
+
+        let (result, alias) = apply_model_aliases(
+            json!({"model": "gpt-5"}),
+            &create_provider_without_mapping(),
+        );
+        assert_eq!(result["model"], "gpt-5");
+        assert!(alias.is_none());
+    }
+
+    #[test]
+    fn test_alias_reverse_mapping_for_responses() {
+        let provider = create_provider_with_aliases();
+        assert_eq!(
+            canonical_model_name(&provider, "vendor/sonnet-4.5"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(canonical_model_name(&provider, "other"), "other");
+
+        // Anthropic 响应
+        let mut anthropic = json!({"id": "msg_1", "type": "message", "model": "vendor/sonnet-4.5"});
+        assert!(restore_canonical_model(&mut anthropic, &provider));
+        assert_eq!(anthropic["model"], "claude-sonnet-4-5");
+
+        // OpenAI 响应
+        let mut openai =
+            json!({"id": "chatcmpl-1", "object": "chat.completion", "model": "vendor/gpt-5"});
+        assert!(restore_canonical_model(&mut openai, &provider));
+        assert_eq!(openai["model"], "gpt-5");
+
+        let mut untouched = json!({"model": "gpt-5"});
+        assert!(!restore_canonical_model(&mut untouched, &provider));
+    }
 }
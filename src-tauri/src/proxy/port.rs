@@ -0,0 +1,171 @@
+//! 监听端口占用检测
+//!
+//! 启动代理前先尝试绑定配置的端口：被占用时尽力找出占用进程，
+//! 或在开启自动换端口时向上查找可用端口。
+
+use std::io::ErrorKind;
+use std::net::TcpListener;
+
+/// 自动换端口时最多向上尝试的端口数
+pub const MAX_PORT_SCAN: u16 = 100;
+
+/// 占用端口的进程
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub name: Option<String>,
+}
+
+/// 端口是否已被其他程序监听
+///
+/// 仅把"地址已被使用"视为占用；地址无效、权限不足等其他绑定错误留给代理启动时报告。
+pub fn is_port_in_use(address: &str, port: u16) -> bool {
+    matches!(TcpListener::bind((address, port)), Err(e) if e.kind() == ErrorKind::AddrInUse)
+}
+
+/// 从 `start` 的下一个端口开始向上查找可以绑定的端口（绑定成功后立即释放）
+pub fn find_free_port(address: &str, start: u16) -> Option<u16> {
+    (1..=MAX_PORT_SCAN)
+        .filter_map(|offset| start.checked_add(offset))
+        .find(|port| TcpListener::bind((address, *port)).is_ok())
+}
+
+/// 端口被占用时的错误信息，尽量附带占用进程
+pub fn port_in_use_message(port: u16) -> String {
+    match find_port_owner(port) {
+        Some(PortOwner {
+            pid,
+            name: Some(name),
+        }) => format!("端口 {port} 已被占用（PID {pid}，{name}）"),
+        Some(PortOwner { pid, name: None }) => format!("端口 {port} 已被占用（PID {pid}）"),
+        None => format!("端口 {port} 已被占用"),
+    }
+}
+
+/// 异步版本的 [`port_in_use_message`]：查找占用进程会启动 lsof / netstat 子进程，
+/// 因此放到阻塞线程池中执行
+pub async fn port_in_use_message_async(port: u16) -> String {
+    tauri::async_runtime::spawn_blocking(move || port_in_use_message(port))
+        .await
+        .unwrap_or_else(|_| format!("端口 {port} 已被占用"))
+}
+
+/// 尽力查找监听指定端口的进程；命令不存在或解析失败时返回 None
+///
+/// - macOS / Linux：`lsof -nP -iTCP:<port> -sTCP:LISTEN`
+/// - Windows：`netstat -ano -p TCP` + `tasklist`
+pub fn find_port_owner(port: u16) -> Option<PortOwner> {
+    #[cfg(unix)]
+    {
+        let output = std::process::Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN"])
+            .output()
+            .ok()?;
+        parse_lsof_owner(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let netstat = std::process::Command::new("netstat")
+            .args(["-ano", "-p", "TCP"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+        let pid = parse_netstat_owner(&String::from_utf8_lossy(&netstat.stdout), port)?;
+        let name = std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()
+            .and_then(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .next()
+                    .and_then(|line| line.split("\",\"").next())
+                    .map(|name| name.trim_start_matches('"').to_string())
+            })
+            .filter(|name| !name.is_empty() && !name.starts_with("INFO:"));
+        Some(PortOwner { pid, name })
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = port;
+        None
+    }
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_lsof_owner(output: &str) -> Option<PortOwner> {
+    // COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME (LISTEN)
+    let line = output.lines().nth(1)?;
+    let mut columns = line.split_whitespace();
+    // lsof 会将进程名中的空格转义为 \x20
+    let name = columns.next()?.replace("\\x20", " ");
+    let pid = columns.next()?.parse().ok()?;
+    Some(PortOwner {
+        pid,
+        name: Some(name),
+    })
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netstat_owner(output: &str, port: u16) -> Option<u32> {
+    let suffix = format!(":{port}");
+    output.lines().find_map(|line| {
+        // TCP    127.0.0.1:15721    0.0.0.0:0    LISTENING    1234
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 5
+            || !columns[0].eq_ignore_ascii_case("TCP")
+            || !columns[3].eq_ignore_ascii_case("LISTENING")
+            || !columns[1].ends_with(&suffix)
+        {
+            return None;
+        }
+        columns[4].parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_port_is_detected_and_next_free_port_found() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind ephemeral port");
+        let port = listener.local_addr().expect("local addr").port();
+
+        assert!(is_port_in_use("127.0.0.1", port));
+        let next = find_free_port("127.0.0.1", port).expect("free port above busy one");
+        assert!(next > port && next <= port.saturating_add(MAX_PORT_SCAN));
+        assert!(find_free_port("127.0.0.1", u16::MAX).is_none());
+    }
+
+    #[test]
+    fn parses_port_owner_from_lsof_and_netstat() {
+        let lsof = "\
+COMMAND     PID USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+Google\\x20  4321 me    10u  IPv4 0x1234567890abcdef      0t0  TCP 127.0.0.1:15721 (LISTEN)
+";
+        assert_eq!(
+            parse_lsof_owner(lsof),
+            Some(PortOwner {
+                pid: 4321,
+                name: Some("Google ".to_string()),
+            })
+        );
+        assert_eq!(parse_lsof_owner(""), None);
+
+        let netstat = "\
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    127.0.0.1:157210       0.0.0.0:0              LISTENING       1
+  TCP    127.0.0.1:15721        127.0.0.1:51000        ESTABLISHED     2
+  TCP    0.0.0.0:15721          0.0.0.0:0              LISTENING       3
+";
+        assert_eq!(parse_netstat_owner(netstat, 15721), Some(3));
+        assert_eq!(parse_netstat_owner(netstat, 8080), None);
+    }
+}
@@ -6,26 +6,44 @@ use crate::app_config::AppType;
 use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
-use crate::proxy::circuit_breaker::{AllowResult, CircuitBreaker, CircuitBreakerConfig};
+use crate::proxy::circuit_breaker::{
+    AllowResult, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerEvent, CircuitBreakerStats,
+    CircuitState, CircuitTransition,
+};
+use crate::proxy::types::ForcedCircuitState;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// 手动熔断状态：key 格式同熔断器（"app_type:provider_id"），value 为 (状态, 失效时间毫秒)
+type CircuitOverrides = HashMap<String, (ForcedCircuitState, Option<i64>)>;
+
 /// 供应商路由器
 pub struct ProviderRouter {
     /// 数据库连接
     db: Arc<Database>,
     /// 熔断器管理器 - key 格式: "app_type:provider_id"
     circuit_breakers: Arc<RwLock<HashMap<String, Arc<CircuitBreaker>>>>,
+    /// 手动熔断状态缓存（首次路由时从数据库加载一次，之后随设置命令更新）
+    circuit_overrides: Arc<RwLock<Option<CircuitOverrides>>>,
+    /// AppHandle，用于发射熔断器状态变化事件
+    app_handle: Option<tauri::AppHandle>,
 }
 
 impl ProviderRouter {
     /// 创建新的供应商路由器
     pub fn new(db: Arc<Database>) -> Self {
+        Self::with_app_handle(db, None)
+    }
+
+    /// 创建带 AppHandle 的供应商路由器（熔断器状态变化时通知前端）
+    pub fn with_app_handle(db: Arc<Database>, app_handle: Option<tauri::AppHandle>) -> Self {
         Self {
             db,
             circuit_breakers: Arc::new(RwLock::new(HashMap::new())),
+            circuit_overrides: Arc::new(RwLock::new(None)),
+            app_handle,
         }
     }
 
@@ -61,16 +79,39 @@ impl ProviderRouter {
                 .collect();
 
             total_providers = ordered_ids.len();
+            let enforce_limits = crate::settings::enforce_provider_limits();
 
             for provider_id in ordered_ids {
                 let Some(provider) = all_providers.get(&provider_id).cloned() else {
                     continue;
                 };
 
+                // 手动熔断状态优先于熔断器判断
+                match self.circuit_override(&provider.id, app_type).await {
+                    Some(ForcedCircuitState::Open) => {
+                        circuit_open_count += 1;
+                        continue;
+                    }
+                    Some(ForcedCircuitState::Closed) => {
+                        result.push(provider);
+                        continue;
+                    }
+                    None => {}
+                }
+
+                // 超出消费限额的供应商视同熔断，直到限额窗口重置
+                if enforce_limits && self.limit_tripped(&provider, app_type) {
+                    circuit_open_count += 1;
+                    continue;
+                }
+
                 let circuit_key = format!("{app_type}:{}", provider.id);
                 let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
 
-                if breaker.is_available().await {
+                let (available, transition) = breaker.check_available().await;
+                self.record_transition(app_type, &provider.id, transition, None);
+
+                if available {
                     result.push(provider);
                 } else {
                     circuit_open_count += 1;
@@ -78,16 +119,7 @@ impl ProviderRouter {
             }
         } else {
             // 故障转移关闭：仅使用当前供应商，跳过熔断器检查
-            let current_id = AppType::from_str(app_type)
-                .ok()
-                .and_then(|app_enum| {
-                    crate::settings::get_effective_current_provider(&self.db, &app_enum)
-                        .ok()
-                        .flatten()
-                })
-                .or_else(|| self.db.get_current_provider(app_type).ok().flatten());
-
-            if let Some(current_id) = current_id {
+            if let Some(current_id) = current_provider_id(&self.db, app_type) {
                 if let Some(current) = self.db.get_provider_by_id(&current_id, app_type)? {
                     total_providers = 1;
                     result.push(current);
@@ -113,13 +145,24 @@ impl ProviderRouter {
     /// - Closed：直接放行
     /// - Open：超时到达后切到 HalfOpen 并放行一次探测
     /// - HalfOpen：按限流规则放行探测
+    /// - 手动强制打开/关闭时直接拒绝/放行，不占用探测名额
     ///
     /// 注意：调用方必须在请求结束后通过 `record_result()` 释放 HalfOpen 名额，
     /// 否则会导致该 Provider 长时间无法进入探测状态。
     pub async fn allow_provider_request(&self, provider_id: &str, app_type: &str) -> AllowResult {
+        if let Some(forced) = self.circuit_override(provider_id, app_type).await {
+            return AllowResult {
+                allowed: forced == ForcedCircuitState::Closed,
+                used_half_open_permit: false,
+                transition: None,
+            };
+        }
+
         let circuit_key = format!("{app_type}:{provider_id}");
         let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
-        breaker.allow_request().await
+        let permit = breaker.allow_request().await;
+        self.record_transition(app_type, provider_id, permit.transition, None);
+        permit
     }
 
     /// 记录供应商请求结果
@@ -141,11 +184,13 @@ impl ProviderRouter {
         let circuit_key = format!("{app_type}:{provider_id}");
         let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
 
-        if success {
-            breaker.record_success(used_half_open_permit).await;
+        let transition = if success {
+            breaker.record_success(used_half_open_permit).await
         } else {
-            breaker.record_failure(used_half_open_permit).await;
-        }
+            breaker.record_failure(used_half_open_permit).await
+        };
+        let trigger_error = if success { None } else { error_msg.clone() };
+        self.record_transition(app_type, provider_id, transition, trigger_error);
 
         // 3. 更新数据库健康状态（使用配置的阈值）
         self.db
@@ -163,9 +208,12 @@ impl ProviderRouter {
 
     /// 重置熔断器（手动恢复）
     pub async fn reset_circuit_breaker(&self, circuit_key: &str) {
-        let breakers = self.circuit_breakers.read().await;
-        if let Some(breaker) = breakers.get(circuit_key) {
-            breaker.reset().await;
+        let breaker = self.circuit_breakers.read().await.get(circuit_key).cloned();
+        if let Some(breaker) = breaker {
+            let transition = breaker.reset().await;
+            if let Some((app_type, provider_id)) = circuit_key.split_once(':') {
+                self.record_transition(app_type, provider_id, transition, None);
+            }
         }
     }
 
@@ -202,7 +250,6 @@ impl ProviderRouter {
     }
 
     /// 获取熔断器状态
-    #[allow(dead_code)]
     pub async fn get_circuit_breaker_stats(
         &self,
         provider_id: &str,
@@ -218,6 +265,150 @@ impl ProviderRouter {
         }
     }
 
+    /// 获取所有已创建熔断器的状态，返回 (app_type, provider_id, stats)
+    pub async fn get_all_circuit_breaker_stats(
+        &self,
+    ) -> Vec<(String, String, CircuitBreakerStats)> {
+        let breakers: Vec<(String, Arc<CircuitBreaker>)> = self
+            .circuit_breakers
+            .read()
+            .await
+            .iter()
+            .map(|(key, breaker)| (key.clone(), breaker.clone()))
+            .collect();
+
+        let mut stats = Vec::with_capacity(breakers.len());
+        for (key, breaker) in breakers {
+            let Some((app_type, provider_id)) = key.split_once(':') else {
+                continue;
+            };
+            stats.push((
+                app_type.to_string(),
+                provider_id.to_string(),
+                breaker.get_stats().await,
+            ));
+        }
+        stats.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        stats
+    }
+
+    /// 供应商是否已超出消费限额（未设置限额时跳过；消费取自内存缓存）
+    fn limit_tripped(&self, provider: &Provider, app_type: &str) -> bool {
+        if !has_spending_limits(provider) {
+            return false;
+        }
+        match self.db.check_provider_limits_cached(provider, app_type) {
+            Ok(status) if status.limit_tripped => {
+                log::info!(
+                    "[{app_type}] [{}] 供应商 {} 已超出消费限额，跳过至 {:?}",
+                    crate::proxy::log_codes::fo::LIMIT_TRIPPED,
+                    provider.name,
+                    status.tripped_until
+                );
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::warn!("[{app_type}] 检查供应商 {} 消费限额失败: {e}", provider.id);
+                false
+            }
+        }
+    }
+
+    /// 读取已有熔断器的状态与路由可用性（不创建熔断器，也不触发状态转换）
+    pub async fn peek_circuit(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Option<(CircuitState, bool)> {
+        let key = format!("{app_type}:{provider_id}");
+        let breaker = self.circuit_breakers.read().await.get(&key).cloned()?;
+        Some((breaker.get_state().await, breaker.peek_available().await))
+    }
+
+    /// 持久化熔断器状态转换并通知前端
+    fn record_transition(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        transition: Option<CircuitTransition>,
+        error: Option<String>,
+    ) {
+        let Some(transition) = transition else {
+            return;
+        };
+
+        let mut event = CircuitBreakerEvent::new(app_type, provider_id, transition, error);
+        match self.db.save_circuit_breaker_event(&event) {
+            Ok(id) => event.id = Some(id),
+            Err(e) => log::warn!("[{app_type}] 保存熔断器事件失败: {e}"),
+        }
+
+        if let Some(app_handle) = self.app_handle.as_ref() {
+            crate::services::proxy::ProxyService::emit_circuit_breaker_event(app_handle, &event);
+        }
+    }
+
+    /// 读取手动熔断状态（已过期视为自动），缓存未加载且加载失败时按自动处理
+    async fn circuit_override(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Option<ForcedCircuitState> {
+        let key = format!("{app_type}:{provider_id}");
+        let active = |overrides: &CircuitOverrides| {
+            overrides
+                .get(&key)
+                .filter(|(_, until)| !Database::circuit_override_expired(*until))
+                .map(|(state, _)| *state)
+        };
+
+        if let Some(overrides) = self.circuit_overrides.read().await.as_ref() {
+            return active(overrides);
+        }
+
+        let mut cached = self.circuit_overrides.write().await;
+        if cached.is_none() {
+            match self.db.list_provider_circuit_overrides().await {
+                Ok(rows) => {
+                    *cached = Some(
+                        rows.into_iter()
+                            .map(|(id, app, state, until)| (format!("{app}:{id}"), (state, until)))
+                            .collect(),
+                    );
+                }
+                Err(e) => {
+                    log::warn!("[{app_type}] 读取手动熔断状态失败: {e}");
+                    return None;
+                }
+            }
+        }
+        cached.as_ref().and_then(active)
+    }
+
+    /// 更新内存中的手动熔断状态（数据库已由调用方写入）
+    ///
+    /// 缓存尚未加载时无需处理，首次路由时会从数据库读取最新状态。
+    pub async fn set_circuit_override(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        forced_state: Option<ForcedCircuitState>,
+        until: Option<i64>,
+    ) {
+        let key = format!("{app_type}:{provider_id}");
+        if let Some(overrides) = self.circuit_overrides.write().await.as_mut() {
+            match forced_state {
+                Some(state) => {
+                    overrides.insert(key, (state, until));
+                }
+                None => {
+                    overrides.remove(&key);
+                }
+            }
+        }
+    }
+
     /// 获取或创建熔断器
     async fn get_or_create_circuit_breaker(&self, key: &str) -> Arc<CircuitBreaker> {
         // 先尝试读锁获取
@@ -258,6 +449,26 @@ impl ProviderRouter {
     }
 }
 
+/// 供应商是否设置了每日或每月消费限额
+pub(crate) fn has_spending_limits(provider: &Provider) -> bool {
+    provider
+        .meta
+        .as_ref()
+        .is_some_and(|meta| meta.limit_daily_usd.is_some() || meta.limit_monthly_usd.is_some())
+}
+
+/// 故障转移关闭时代理使用的供应商：优先本地设置中的当前供应商，其次数据库记录
+pub(crate) fn current_provider_id(db: &Database, app_type: &str) -> Option<String> {
+    AppType::from_str(app_type)
+        .ok()
+        .and_then(|app_enum| {
+            crate::settings::get_effective_current_provider(db, &app_enum)
+                .ok()
+                .flatten()
+        })
+        .or_else(|| db.get_current_provider(app_type).ok().flatten())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -500,4 +711,76 @@ mod tests {
         assert!(third.allowed);
         assert!(third.used_half_open_permit);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_forced_circuit_state_overrides_breaker() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        let provider_a =
+            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
+        let provider_b =
+            Provider::with_id("b".to_string(), "Provider B".to_string(), json!({}), None);
+        db.save_provider("claude", &provider_a).unwrap();
+        db.save_provider("claude", &provider_b).unwrap();
+        db.add_to_failover_queue("claude", "a").unwrap();
+        db.add_to_failover_queue("claude", "b").unwrap();
+
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        config.circuit_failure_threshold = 1;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        // 与 ProxyService 相同：先写数据库，再同步到运行中的路由器
+        let (db_ref, router_ref) = (&db, &router);
+        let force = move |id: &'static str, state, until: Option<i64>| async move {
+            db_ref
+                .set_provider_circuit_override(id, "claude", Some(state), until)
+                .await
+                .unwrap();
+            router_ref
+                .set_circuit_override(id, "claude", Some(state), until)
+                .await;
+        };
+
+        // 强制打开：不参与路由，也不放行请求（首次路由时从数据库加载）
+        db.set_provider_circuit_override("a", "claude", Some(ForcedCircuitState::Open), None)
+            .await
+            .unwrap();
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].id, "b");
+        assert!(!router.allow_provider_request("a", "claude").await.allowed);
+
+        // 强制关闭：即使熔断器已打开也放行
+        router
+            .record_result("b", "claude", false, false, Some("fail".to_string()))
+            .await
+            .unwrap();
+        force("b", ForcedCircuitState::Closed, None).await;
+        let permit = router.allow_provider_request("b", "claude").await;
+        assert!(permit.allowed);
+        assert!(!permit.used_half_open_permit);
+
+        // 全部强制打开时报告全部熔断
+        force("b", ForcedCircuitState::Open, None).await;
+        assert!(matches!(
+            router.select_providers("claude").await,
+            Err(AppError::AllProvidersCircuitOpen)
+        ));
+
+        // 已过期的手动状态按自动处理
+        let expired = chrono::Utc::now().timestamp_millis() - 1;
+        force("a", ForcedCircuitState::Open, Some(expired)).await;
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(providers[0].id, "a");
+
+        // 加载后只读内存：数据库仍为强制打开，内存中的强制关闭生效
+        router
+            .set_circuit_override("b", "claude", Some(ForcedCircuitState::Closed), None)
+            .await;
+        assert!(router.allow_provider_request("b", "claude").await.allowed);
+    }
 }
@@ -335,6 +335,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -352,6 +353,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
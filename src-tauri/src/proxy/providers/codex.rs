@@ -110,19 +110,10 @@ impl ProviderAdapter for CodexAdapter {
                 return Ok(url.trim_end_matches('/').to_string());
             }
 
-            // 尝试解析 TOML 字符串格式
+            // 尝试解析 TOML 字符串格式（取当前 model_provider 对应表的 base_url）
             if let Some(config_str) = config.as_str() {
-                if let Some(start) = config_str.find("base_url = \"") {
-                    let rest = &config_str[start + 12..];
-                    if let Some(end) = rest.find('"') {
-                        return Ok(rest[..end].trim_end_matches('/').to_string());
-                    }
-                }
-                if let Some(start) = config_str.find("base_url = '") {
-                    let rest = &config_str[start + 12..];
-                    if let Some(end) = rest.find('\'') {
-                        return Ok(rest[..end].trim_end_matches('/').to_string());
-                    }
+                if let Ok(Some(url)) = crate::codex_config::get_active_base_url(config_str) {
+                    return Ok(url.trim_end_matches('/').to_string());
                 }
             }
         }
@@ -198,6 +189,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -251,6 +251,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
@@ -8,6 +8,7 @@
 //! - `claude`: Claude (Anthropic) 适配器
 //! - `codex`: Codex (OpenAI) 适配器
 //! - `gemini`: Gemini (Google) 适配器
+//! - `opencode`: OpenCode 适配器（按 npm 包区分上游协议）
 //! - `models`: API 数据模型
 //! - `transform`: 格式转换
 
@@ -17,6 +18,7 @@ mod claude;
 mod codex;
 mod gemini;
 pub mod models;
+mod opencode;
 pub mod streaming;
 pub mod transform;
 
@@ -30,6 +32,7 @@ pub use auth::{AuthInfo, AuthStrategy};
 pub use claude::ClaudeAdapter;
 pub use codex::CodexAdapter;
 pub use gemini::GeminiAdapter;
+pub use opencode::OpenCodeAdapter;
 
 /// 供应商类型枚举
 ///
@@ -133,8 +136,12 @@ impl ProviderType {
                 ProviderType::Gemini
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy, but return a default type for completeness
-                ProviderType::Codex // Fallback to Codex-like type
+                // OpenCode 按 npm 包区分上游协议
+                match provider.settings_config.get("npm").and_then(|v| v.as_str()) {
+                    Some("@ai-sdk/anthropic") => ProviderType::Claude,
+                    Some("@ai-sdk/google") => ProviderType::Gemini,
+                    _ => ProviderType::Codex,
+                }
             }
         }
     }
@@ -180,10 +187,7 @@ pub fn get_adapter(app_type: &AppType) -> Box<dyn ProviderAdapter> {
         AppType::Claude => Box::new(ClaudeAdapter::new()),
         AppType::Codex => Box::new(CodexAdapter::new()),
         AppType::Gemini => Box::new(GeminiAdapter::new()),
-        AppType::OpenCode => {
-            // OpenCode doesn't support proxy, fallback to Codex adapter
-            Box::new(CodexAdapter::new())
-        }
+        AppType::OpenCode => Box::new(OpenCodeAdapter::new()),
     }
 }
 
@@ -218,6 +222,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            icon_cached: None,
         }
     }
 
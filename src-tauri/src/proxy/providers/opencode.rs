@@ -0,0 +1,168 @@
+//! OpenCode Provider Adapter
+//!
+//! OpenCode 的供应商配置位于 opencode.json 的 `provider.<id>`：
+//! `{ "npm": "@ai-sdk/...", "options": { "baseURL": ..., "apiKey": ... }, "models": {...} }`
+//!
+//! ## 认证模式（按 npm 包区分）
+//! - `@ai-sdk/anthropic`: x-api-key
+//! - `@ai-sdk/google`: x-goog-api-key
+//! - 其它（OpenAI 兼容）: Authorization Bearer
+
+use super::{AuthInfo, AuthStrategy, ProviderAdapter};
+use crate::provider::Provider;
+use crate::proxy::error::ProxyError;
+use reqwest::RequestBuilder;
+
+/// OpenCode 适配器
+pub struct OpenCodeAdapter;
+
+impl OpenCodeAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn npm<'a>(&self, provider: &'a Provider) -> &'a str {
+        provider
+            .settings_config
+            .get("npm")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+    }
+
+    /// 未配置 baseURL 时使用 SDK 的默认地址
+    fn default_base_url(npm: &str) -> Option<&'static str> {
+        match npm {
+            "@ai-sdk/anthropic" => Some("https://api.anthropic.com/v1"),
+            "@ai-sdk/openai" => Some("https://api.openai.com/v1"),
+            "@ai-sdk/google" => Some("https://generativelanguage.googleapis.com/v1beta"),
+            _ => None,
+        }
+    }
+
+    /// 根据 npm 包检测认证方式
+    pub fn detect_auth_type(&self, provider: &Provider) -> AuthStrategy {
+        match self.npm(provider) {
+            "@ai-sdk/anthropic" => AuthStrategy::Anthropic,
+            "@ai-sdk/google" => AuthStrategy::Google,
+            _ => AuthStrategy::Bearer,
+        }
+    }
+}
+
+impl Default for OpenCodeAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderAdapter for OpenCodeAdapter {
+    fn name(&self) -> &'static str {
+        "OpenCode"
+    }
+
+    fn extract_base_url(&self, provider: &Provider) -> Result<String, ProxyError> {
+        if let Some(url) = provider
+            .settings_config
+            .get("options")
+            .and_then(|v| v.get("baseURL"))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            return Ok(url.trim_end_matches('/').to_string());
+        }
+
+        Self::default_base_url(self.npm(provider))
+            .map(str::to_string)
+            .ok_or_else(|| {
+                ProxyError::ConfigError("OpenCode Provider 缺少 options.baseURL 配置".to_string())
+            })
+    }
+
+    fn extract_auth(&self, provider: &Provider) -> Option<AuthInfo> {
+        let key = provider
+            .settings_config
+            .get("options")
+            .and_then(|v| v.get("apiKey"))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())?;
+        Some(AuthInfo::new(
+            key.to_string(),
+            self.detect_auth_type(provider),
+        ))
+    }
+
+    fn build_url(&self, base_url: &str, endpoint: &str) -> String {
+        // baseURL 已包含版本前缀（如 /v1），endpoint 为 SDK 拼接在其后的相对路径
+        format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            endpoint.trim_start_matches('/')
+        )
+    }
+
+    fn add_auth_headers(&self, request: RequestBuilder, auth: &AuthInfo) -> RequestBuilder {
+        match auth.strategy {
+            AuthStrategy::Anthropic => request.header("x-api-key", &auth.api_key),
+            AuthStrategy::Google => request.header("x-goog-api-key", &auth.api_key),
+            _ => request.header("Authorization", format!("Bearer {}", auth.api_key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_provider(config: serde_json::Value) -> Provider {
+        Provider::with_id("relay".to_string(), "Relay".to_string(), config, None)
+    }
+
+    #[test]
+    fn test_extract_base_url_and_auth_by_npm() {
+        let adapter = OpenCodeAdapter::new();
+        let provider = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": { "baseURL": "https://relay.example.com/v1/", "apiKey": "sk-relay" }
+        }));
+
+        assert_eq!(
+            adapter.extract_base_url(&provider).unwrap(),
+            "https://relay.example.com/v1"
+        );
+        let auth = adapter.extract_auth(&provider).unwrap();
+        assert_eq!(auth.api_key, "sk-relay");
+        assert_eq!(auth.strategy, AuthStrategy::Bearer);
+        assert_eq!(
+            adapter.build_url("https://relay.example.com/v1", "/chat/completions"),
+            "https://relay.example.com/v1/chat/completions"
+        );
+
+        let anthropic = create_provider(json!({
+            "npm": "@ai-sdk/anthropic",
+            "options": { "apiKey": "sk-ant" }
+        }));
+        assert_eq!(
+            adapter.extract_base_url(&anthropic).unwrap(),
+            "https://api.anthropic.com/v1"
+        );
+        assert_eq!(
+            adapter.extract_auth(&anthropic).unwrap().strategy,
+            AuthStrategy::Anthropic
+        );
+    }
+
+    #[test]
+    fn test_missing_base_url_for_unknown_npm() {
+        let adapter = OpenCodeAdapter::new();
+        let provider = create_provider(json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": {}
+        }));
+
+        assert!(adapter.extract_base_url(&provider).is_err());
+        assert!(adapter.extract_auth(&provider).is_none());
+    }
+}
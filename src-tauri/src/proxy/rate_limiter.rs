@@ -0,0 +1,138 @@
+//! 供应商级请求速率限制
+//!
+//! 每个供应商一个令牌桶：容量为 `rate_limit_rpm`，按 rpm/60 每秒匀速补充。
+//! 状态仅保存在内存中（随 ProxyServer 创建），代理重启后全部重置。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 令牌桶
+#[derive(Debug)]
+struct TokenBucket {
+    rpm: u32,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rpm: u32, now: Instant) -> Self {
+        Self {
+            rpm,
+            tokens: rpm as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        let per_second = self.rpm as f64 / 60.0;
+        self.tokens = (self.tokens + elapsed * per_second).min(self.rpm as f64);
+        self.last_refill = now;
+    }
+
+    /// 取一个令牌；桶为空时返回距下一个令牌可用的等待时间
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let per_second = self.rpm as f64 / 60.0;
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / per_second))
+    }
+}
+
+/// 按供应商 ID 维护令牌桶的限流器
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 尝试获取令牌（不等待）
+    ///
+    /// `rpm` 变化（用户编辑了供应商）时按新容量重建令牌桶，已有令牌不超过新容量。
+    pub fn try_acquire(&self, provider_id: &str, rpm: u32) -> Result<(), Duration> {
+        self.try_acquire_at(provider_id, rpm, Instant::now())
+    }
+
+    fn try_acquire_at(&self, provider_id: &str, rpm: u32, now: Instant) -> Result<(), Duration> {
+        if rpm == 0 {
+            return Ok(());
+        }
+        let mut buckets = match self.buckets.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let bucket = buckets
+            .entry(provider_id.to_string())
+            .or_insert_with(|| TokenBucket::new(rpm, now));
+        if bucket.rpm != rpm {
+            bucket.refill(now);
+            bucket.rpm = rpm;
+            bucket.tokens = bucket.tokens.min(rpm as f64);
+        }
+        bucket.try_take(now)
+    }
+
+    /// 排队等待直到获取到令牌，返回实际等待时长
+    pub async fn acquire(&self, provider_id: &str, rpm: u32) -> Duration {
+        let started = Instant::now();
+        while let Err(wait) = self.try_acquire(provider_id, rpm) {
+            tokio::time::sleep(wait).await;
+        }
+        started.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_up_to_rpm_then_refills() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("p1", 3, start).is_ok());
+        }
+        let wait = limiter
+            .try_acquire_at("p1", 3, start)
+            .expect_err("bucket should be empty");
+        assert!(wait > Duration::from_secs(19) && wait <= Duration::from_secs(20));
+
+        // 其它供应商互不影响
+        assert!(limiter.try_acquire_at("p2", 3, start).is_ok());
+
+        // 约 20 秒后补充一个令牌
+        let later = start + Duration::from_secs(21);
+        assert!(limiter.try_acquire_at("p1", 3, later).is_ok());
+        assert!(limiter.try_acquire_at("p1", 3, later).is_err());
+    }
+
+    #[test]
+    fn changing_rpm_rebuilds_bucket_capacity() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+
+        for _ in 0..10 {
+            assert!(limiter.try_acquire_at("p1", 60, start).is_ok());
+        }
+        // 降低到 5 rpm：剩余 50 个令牌被截断为 5
+        for _ in 0..5 {
+            assert!(limiter.try_acquire_at("p1", 5, start).is_ok());
+        }
+        assert!(limiter.try_acquire_at("p1", 5, start).is_err());
+
+        // rpm 为 0 表示不限流
+        assert!(limiter.try_acquire_at("p1", 0, start).is_ok());
+    }
+}
@@ -0,0 +1,521 @@
+//! 代理请求重放
+//!
+//! 根据请求日志与捕获的请求体重建一次请求，发送到指定供应商，便于对比不同供应商的表现。
+//! 重放直接发往目标供应商，不经过熔断器、故障转移与速率限制；结果写入
+//! `proxy_request_logs` 并以 `replay_of` 标记来源请求，不计入使用统计。
+//!
+//! 同样的重建逻辑也用于"复制为 curl"，便于在 cc-switch 之外复现失败的请求。
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::body_capture::{is_sensitive_key, truncate_utf8, REDACTED};
+use super::forwarder::build_upstream_request;
+use super::providers::get_adapter;
+use super::usage::{RequestLog, TokenUsage, UsageLogger};
+use crate::app_config::AppType;
+use crate::database::{Database, ReplaySource};
+use crate::error::AppError;
+use crate::provider::Provider;
+
+/// 重放结果中保留的响应体最大字节数
+const MAX_REPLAY_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// 重放结果摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    /// 重放请求在 `proxy_request_logs` 中的 ID
+    pub request_id: String,
+    /// 被重放的原始请求 ID
+    pub replay_of: String,
+    pub provider_id: String,
+    pub url: String,
+    pub status_code: u16,
+    pub success: bool,
+    pub latency_ms: u64,
+    /// 响应体（流式响应为原始 SSE 文本，超过上限时截断）
+    pub response_body: Option<String>,
+    pub truncated: bool,
+    pub error_message: Option<String>,
+}
+
+/// 将 `request_id` 对应的请求重放到 `target_provider_id`
+///
+/// 需要原请求已捕获请求体（`capture_bodies`），否则返回 `proxy.replay.unavailable`。
+pub async fn replay_request(
+    db: &Database,
+    request_id: &str,
+    target_provider_id: &str,
+) -> Result<ReplayResult, AppError> {
+    let source = load_source(db, request_id)?;
+    let body_text = source.request_body.as_deref().ok_or_else(|| {
+        AppError::localized(
+            "proxy.replay.unavailable",
+            "该请求未捕获请求体，无法重放。请在代理设置中开启请求体捕获",
+            "Replay unavailable: the request body was not captured. Enable body capture in proxy settings",
+        )
+    })?;
+    let body: Value = serde_json::from_str(body_text).map_err(|_| {
+        AppError::localized(
+            "proxy.replay.body_truncated",
+            "捕获的请求体已被截断，无法重放。可调大捕获上限后重试",
+            "The captured request body was truncated and cannot be replayed. Increase the capture limit and retry",
+        )
+    })?;
+
+    let app_type = AppType::from_str(&source.app_type)?;
+    let endpoint = resolve_endpoint(&source, &app_type, &body)?;
+    let provider = load_provider(db, &app_type, target_provider_id)?;
+
+    let app_config = db.get_proxy_config_for_app(app_type.as_str()).await?;
+    let headers = restore_headers(source.request_headers.as_deref());
+    let adapter = get_adapter(&app_type);
+    let (request, url, filtered_body) = build_upstream_request(
+        &provider,
+        &endpoint,
+        &body,
+        &headers,
+        adapter.as_ref(),
+        Duration::from_secs(app_config.non_streaming_timeout),
+    )
+    .map_err(|e| AppError::Message(e.to_string()))?;
+
+    log::info!("[Replay] 重放请求 {request_id} -> {target_provider_id}: {url}");
+    let started = Instant::now();
+    let (status_code, response_text, error_message) =
+        match request.json(&filtered_body).send().await {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let text = response.text().await.ok();
+                let error = (!(200..300).contains(&status)).then(|| format!("HTTP {status}"));
+                (status, text, error)
+            }
+            Err(e) => {
+                let status = if e.is_timeout() { 504 } else { 502 };
+                (status, None, Some(e.to_string()))
+            }
+        };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (response_body, truncated) = match response_text {
+        Some(text) => {
+            let (text, truncated) = truncate_utf8(&text, MAX_REPLAY_RESPONSE_BYTES);
+            (Some(text), truncated)
+        }
+        None => (None, false),
+    };
+
+    let replay_id = uuid::Uuid::new_v4().to_string();
+    let log = RequestLog {
+        request_id: replay_id.clone(),
+        provider_id: provider.id.clone(),
+        app_type: app_type.as_str().to_string(),
+        model: source.model.clone(),
+        request_model: source.model,
+        usage: TokenUsage::default(),
+        cost: None,
+        latency_ms,
+        first_token_ms: None,
+        status_code,
+        error_message: error_message.clone(),
+        session_id: None,
+        provider_type: None,
+        is_streaming: source.is_streaming,
+        cost_multiplier: "1.0".to_string(),
+        attempts: None,
+        replay_of: Some(request_id.to_string()),
+    };
+    if let Err(e) = UsageLogger::new(db).log_request(&log) {
+        log::warn!("[Replay] 记录重放请求日志失败: {e}");
+    }
+
+    Ok(ReplayResult {
+        request_id: replay_id,
+        replay_of: request_id.to_string(),
+        provider_id: provider.id,
+        url,
+        status_code,
+        success: error_message.is_none(),
+        latency_ms,
+        response_body,
+        truncated,
+        error_message,
+    })
+}
+
+/// "复制为 curl" 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurlExport {
+    /// 可直接粘贴到终端执行的命令；请求体缺失或被截断时首行为 `#` 注释说明
+    pub command: String,
+    /// 是否附带了请求体（未开启请求体捕获时为 false）
+    pub body_included: bool,
+    /// 捕获的请求体是否被截断（截断时按原文附带，不是完整的 JSON）
+    pub body_truncated: bool,
+    pub auth_included: bool,
+}
+
+/// 把 `request_id` 对应的请求还原为 curl 命令，发往原供应商的上游地址
+///
+/// 认证信息默认以 `[REDACTED]` 占位，只有显式传入 `include_auth` 时才写入真实密钥。
+/// 未捕获请求体时只还原 URL 与请求头；流式请求附带 `-N`。
+pub fn request_as_curl(
+    db: &Database,
+    request_id: &str,
+    include_auth: bool,
+) -> Result<CurlExport, AppError> {
+    let source = load_source(db, request_id)?;
+    let app_type = AppType::from_str(&source.app_type)?;
+    let provider = load_provider(db, &app_type, &source.provider_id)?;
+
+    // 截断后的请求体不是合法 JSON，无法经过模型映射与格式转换，只能按原文附带。
+    // 截断标记覆盖捕获的所有部分，请求体仍能解析时说明被截断的是其他部分
+    let parsed_body = source
+        .request_body
+        .as_deref()
+        .and_then(|text| serde_json::from_str::<Value>(text).ok());
+    let body_truncated = source.truncated && source.request_body.is_some() && parsed_body.is_none();
+    let body = parsed_body
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({ "model": source.model }));
+
+    let endpoint = resolve_endpoint(&source, &app_type, &body)?;
+    let headers = restore_headers(source.request_headers.as_deref());
+    let adapter = get_adapter(&app_type);
+    let (request, _, filtered_body) = build_upstream_request(
+        &provider,
+        &endpoint,
+        &body,
+        &headers,
+        adapter.as_ref(),
+        Duration::ZERO,
+    )
+    .map_err(|e| AppError::Message(e.to_string()))?;
+    let request = request
+        .build()
+        .map_err(|e| AppError::Message(e.to_string()))?;
+
+    let body_text = match (&parsed_body, &source.request_body) {
+        (Some(_), _) => Some(filtered_body.to_string()),
+        (None, Some(raw)) => Some(raw.clone()),
+        (None, None) => None,
+    };
+
+    let mut url = request.url().clone();
+    if !include_auth {
+        redact_url_credentials(&mut url);
+    }
+    let mut parts = vec!["curl".to_string()];
+    if source.is_streaming {
+        parts.push("-N".to_string());
+    }
+    parts.push(format!("-X POST {}", shell_quote(url.as_str())));
+    for (name, value) in request.headers() {
+        let value = if !include_auth && is_sensitive_key(name.as_str()) {
+            REDACTED
+        } else {
+            value.to_str().unwrap_or_default()
+        };
+        parts.push(format!("-H {}", shell_quote(&format!("{name}: {value}"))));
+    }
+    if let Some(body_text) = &body_text {
+        if !request.headers().contains_key("content-type") {
+            parts.push(format!(
+                "-H {}",
+                shell_quote("content-type: application/json")
+            ));
+        }
+        parts.push(format!("--data-raw {}", shell_quote(body_text)));
+    }
+
+    let mut command = parts.join(" \\\n  ");
+    if body_truncated {
+        command = format!(
+            "# 注意：捕获的请求体已被截断 / NOTE: the captured request body was truncated\n{command}"
+        );
+    } else if body_text.is_none() {
+        command = format!(
+            "# 注意：该请求未捕获请求体 / NOTE: the request body was not captured\n{command}"
+        );
+    }
+
+    Ok(CurlExport {
+        command,
+        body_included: body_text.is_some(),
+        body_truncated,
+        auth_included: include_auth,
+    })
+}
+
+fn load_source(db: &Database, request_id: &str) -> Result<ReplaySource, AppError> {
+    db.get_replay_source(request_id)?.ok_or_else(|| {
+        AppError::localized(
+            "proxy.replay.not_found",
+            format!("请求日志不存在: {request_id}"),
+            format!("Request log not found: {request_id}"),
+        )
+    })
+}
+
+fn load_provider(
+    db: &Database,
+    app_type: &AppType,
+    provider_id: &str,
+) -> Result<Provider, AppError> {
+    db.get_provider_by_id(provider_id, app_type.as_str())?
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商不存在: {provider_id}"),
+                format!("Provider not found: {provider_id}"),
+            )
+        })
+}
+
+fn resolve_endpoint(
+    source: &ReplaySource,
+    app_type: &AppType,
+    body: &Value,
+) -> Result<String, AppError> {
+    source
+        .endpoint
+        .clone()
+        .or_else(|| default_endpoint(app_type, body))
+        .ok_or_else(|| {
+            AppError::localized(
+                "proxy.replay.endpoint_unknown",
+                "该请求缺少端点信息，无法重放",
+                "Replay unavailable: the request endpoint was not recorded",
+            )
+        })
+}
+
+/// 脱敏 URL 查询参数中的密钥（如 Gemini 的 `?key=`）
+fn redact_url_credentials(url: &mut url::Url) {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if !pairs.iter().any(|(k, _)| k == "key" || is_sensitive_key(k)) {
+        return;
+    }
+    let mut query = url.query_pairs_mut();
+    query.clear();
+    for (k, v) in &pairs {
+        if k == "key" || is_sensitive_key(k) {
+            query.append_pair(k, REDACTED);
+        } else {
+            query.append_pair(k, v);
+        }
+    }
+}
+
+/// POSIX shell 单引号转义
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 旧版本捕获的记录没有端点信息时，按应用与请求体推断
+fn default_endpoint(app_type: &AppType, body: &Value) -> Option<String> {
+    match app_type {
+        AppType::Claude => Some("/v1/messages".to_string()),
+        AppType::Codex if body.get("input").is_some() => Some("/responses".to_string()),
+        AppType::Codex => Some("/chat/completions".to_string()),
+        // Gemini 的模型名在路径中，OpenCode 的路径取决于供应商 SDK，无法推断
+        AppType::Gemini | AppType::OpenCode => None,
+    }
+}
+
+/// 从捕获的请求头 JSON 重建请求头，跳过已脱敏的认证头（由目标供应商的认证替代）
+fn restore_headers(raw: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let parsed = raw.and_then(|raw| serde_json::from_str::<Value>(raw).ok());
+    let Some(Value::Object(map)) = parsed else {
+        return headers;
+    };
+    for (name, value) in map {
+        let Some(value) = value.as_str().filter(|v| *v != REDACTED) else {
+            continue;
+        };
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn restore_headers_skips_redacted_values() {
+        let raw = json!({
+            "authorization": REDACTED,
+            "anthropic-version": "2023-06-01",
+            "anthropic-beta": "tools-2024",
+        })
+        .to_string();
+        let headers = restore_headers(Some(&raw));
+        assert!(headers.get("authorization").is_none());
+        assert_eq!(headers["anthropic-version"], "2023-06-01");
+        assert_eq!(headers["anthropic-beta"], "tools-2024");
+        assert!(restore_headers(Some("not json")).is_empty());
+    }
+
+    #[test]
+    fn default_endpoint_infers_from_app_and_body() {
+        assert_eq!(
+            default_endpoint(&AppType::Codex, &json!({"input": []})).as_deref(),
+            Some("/responses")
+        );
+        assert_eq!(
+            default_endpoint(&AppType::Codex, &json!({"messages": []})).as_deref(),
+            Some("/chat/completions")
+        );
+        assert!(default_endpoint(&AppType::Gemini, &json!({})).is_none());
+    }
+
+    #[tokio::test]
+    async fn replay_without_captured_body_is_unavailable() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = crate::database::lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, latency_ms, status_code, created_at
+                ) VALUES ('req-1', 'p1', 'claude', 'claude-3', 100, 500, 1000)",
+                [],
+            )?;
+        }
+
+        let err = replay_request(&db, "req-1", "p1").await.unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "proxy.replay.unavailable",
+                ..
+            }
+        ));
+        let err = replay_request(&db, "missing", "p1").await.unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "proxy.replay.not_found",
+                ..
+            }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn curl_export_redacts_auth_unless_requested() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let provider = Provider::with_id(
+            "p1".into(),
+            "Relay".into(),
+            json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": "https://relay.example.com",
+                    "ANTHROPIC_AUTH_TOKEN": "sk-secret"
+                }
+            }),
+            None,
+        );
+        db.save_provider("claude", &provider)?;
+        {
+            let conn = crate::database::lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, latency_ms, status_code,
+                    is_streaming, created_at
+                ) VALUES ('req-1', 'p1', 'claude', 'claude-3', 100, 500, 1, 1000)",
+                [],
+            )?;
+        }
+        db.save_request_bodies(
+            "req-1",
+            Some("/v1/messages"),
+            Some(&json!({ "authorization": REDACTED }).to_string()),
+            Some(r#"{"model":"claude-3","stream":true}"#),
+            None,
+            false,
+        )?;
+
+        let export = request_as_curl(&db, "req-1", false)?;
+        assert!(export.command.starts_with("curl \\\n  -N"));
+        assert!(export
+            .command
+            .contains("https://relay.example.com/v1/messages"));
+        assert!(export.command.contains("--data-raw"));
+        assert!(!export.command.contains("sk-secret"));
+        assert!(export.body_included && !export.body_truncated);
+
+        let export = request_as_curl(&db, "req-1", true)?;
+        assert!(export.command.contains("sk-secret"));
+        Ok(())
+    }
+
+    #[test]
+    fn curl_export_notes_truncated_body() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let provider = Provider::with_id(
+            "p1".into(),
+            "Relay".into(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://relay.example.com" } }),
+            None,
+        );
+        db.save_provider("claude", &provider)?;
+        {
+            let conn = crate::database::lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, latency_ms, status_code, created_at
+                ) VALUES ('req-2', 'p1', 'claude', 'claude-3', 100, 500, 1000)",
+                [],
+            )?;
+        }
+        db.save_request_bodies(
+            "req-2",
+            Some("/v1/messages"),
+            None,
+            Some(r#"{"model":"claude-3","messages":[{"ro"#),
+            None,
+            true,
+        )?;
+
+        let export = request_as_curl(&db, "req-2", false)?;
+        assert!(export.body_truncated);
+        assert!(export.command.starts_with("# "));
+        assert!(!export.command.contains(" -N"));
+
+        // 未标记截断的请求体即使无法解析，也按原文附带而不提示截断
+        db.save_request_bodies(
+            "req-2",
+            Some("/v1/messages"),
+            None,
+            Some("not json"),
+            None,
+            false,
+        )?;
+        let export = request_as_curl(&db, "req-2", false)?;
+        assert!(export.body_included && !export.body_truncated);
+        assert!(export.command.starts_with("curl"));
+        Ok(())
+    }
+}
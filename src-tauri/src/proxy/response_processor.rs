@@ -5,6 +5,7 @@
 use super::{
     handler_config::UsageParserConfig,
     handler_context::{RequestContext, StreamingTimeoutConfig},
+    model_mapper,
     server::ProxyState,
     usage::parser::TokenUsage,
     ProxyError,
@@ -114,7 +115,7 @@ pub async fn handle_non_streaming(
         String::from_utf8_lossy(&body_bytes)
     );
 
-    let parsed_json = serde_json::from_slice::<Value>(&body_bytes).ok();
+    let mut parsed_json = serde_json::from_slice::<Value>(&body_bytes).ok();
 
     // 解析并记录使用量
     if let Some(json_value) = parsed_json.as_ref() {
@@ -177,9 +178,23 @@ pub async fn handle_non_streaming(
 
     spawn_thread_memory_write_from_json(state, ctx, status.as_u16(), parsed_json.as_ref());
 
+    // 将响应中的供应商模型别名还原为规范模型名（仅非流式 JSON 响应，SSE 原样透传）
+    let body_bytes = match parsed_json.as_mut() {
+        Some(json_value) if model_mapper::restore_canonical_model(json_value, &ctx.provider) => {
+            serde_json::to_vec(json_value)
+                .map(Bytes::from)
+                .unwrap_or(body_bytes)
+        }
+        _ => body_bytes,
+    };
+
     // 构建响应
     let mut builder = axum::response::Response::builder().status(status);
     for (key, value) in response_headers.iter() {
+        // 响应体可能已被改写，由 axum 重新计算长度
+        if key == reqwest::header::CONTENT_LENGTH {
+            continue;
+        }
         builder = builder.header(key, value);
     }
 
@@ -289,6 +304,7 @@ fn create_usage_collector(
     parser_config: &UsageParserConfig,
 ) -> SseUsageCollector {
     let state = state.clone();
+    let provider = ctx.provider.clone();
     let provider_id = ctx.provider.id.clone();
     let request_model = ctx.request_model.clone();
     let app_type_str = parser_config.app_type_str;
@@ -302,16 +318,21 @@ fn create_usage_collector(
     let provider_id_for_memory = provider_id.clone();
     let request_text_for_memory =
         ThreadMemoryService::extract_user_text_from_request(app_type_str, &ctx.request_body);
+    let attempts = ctx.attempts_json();
 
     SseUsageCollector::new(start_time, move |events, first_token_ms| {
         if let Some(usage) = stream_parser(&events) {
-            let model = model_extractor(&events, &request_model);
+            let model = model_mapper::canonical_model_name(
+                &provider,
+                &model_extractor(&events, &request_model),
+            );
             let latency_ms = start_time.elapsed().as_millis() as u64;
 
             let state = state.clone();
             let provider_id = provider_id.clone();
             let session_id = session_id.clone();
             let request_model = request_model.clone();
+            let attempts = attempts.clone();
 
             tokio::spawn(async move {
                 log_usage_internal(
@@ -326,16 +347,21 @@ fn create_usage_collector(
                     true, // is_streaming
                     status_code,
                     Some(session_id),
+                    attempts,
                 )
                 .await;
             });
         } else {
-            let model = model_extractor(&events, &request_model);
+            let model = model_mapper::canonical_model_name(
+                &provider,
+                &model_extractor(&events, &request_model),
+            );
             let latency_ms = start_time.elapsed().as_millis() as u64;
             let state = state.clone();
             let provider_id = provider_id.clone();
             let session_id = session_id.clone();
             let request_model = request_model.clone();
+            let attempts = attempts.clone();
 
             tokio::spawn(async move {
                 log_usage_internal(
@@ -350,6 +376,7 @@ fn create_usage_collector(
                     true, // is_streaming
                     status_code,
                     Some(session_id),
+                    attempts,
                 )
                 .await;
             });
@@ -399,10 +426,11 @@ fn spawn_log_usage(
     let state = state.clone();
     let provider_id = ctx.provider.id.clone();
     let app_type_str = ctx.app_type_str.to_string();
-    let model = model.to_string();
+    let model = model_mapper::canonical_model_name(&ctx.provider, model);
     let request_model = request_model.to_string();
     let latency_ms = ctx.latency_ms();
     let session_id = ctx.session_id.clone();
+    let attempts = ctx.attempts_json();
 
     tokio::spawn(async move {
         log_usage_internal(
@@ -417,6 +445,7 @@ fn spawn_log_usage(
             is_streaming,
             status_code,
             Some(session_id),
+            attempts,
         )
         .await;
     });
@@ -436,6 +465,7 @@ async fn log_usage_internal(
     is_streaming: bool,
     status_code: u16,
     session_id: Option<String>,
+    attempts: Option<String>,
 ) {
     use super::usage::logger::UsageLogger;
 
@@ -474,6 +504,7 @@ async fn log_usage_internal(
         session_id,
         None, // provider_type
         is_streaming,
+        attempts,
     ) {
         log::warn!("[USG-001] 记录使用量失败: {e}");
     }
@@ -649,8 +680,10 @@ mod tests {
     use crate::database::Database;
     use crate::error::AppError;
     use crate::provider::ProviderMeta;
+    use crate::proxy::active_requests::ActiveRequests;
     use crate::proxy::failover_switch::FailoverSwitchManager;
     use crate::proxy::provider_router::ProviderRouter;
+    use crate::proxy::rate_limiter::RateLimiter;
     use crate::proxy::types::{ProxyConfig, ProxyStatus};
     use rust_decimal::Decimal;
     use std::collections::HashMap;
@@ -669,6 +702,8 @@ mod tests {
             app_handle: None,
             failover_manager: Arc::new(FailoverSwitchManager::new(db)),
             thread_memory: None,
+            rate_limiter: Arc::new(RateLimiter::new()),
+            active_requests: Arc::new(ActiveRequests::new()),
         }
     }
 
@@ -742,6 +777,7 @@ mod tests {
             false,
             200,
             None,
+            None,
         )
         .await;
 
@@ -801,6 +837,7 @@ mod tests {
             false,
             200,
             None,
+            None,
         )
         .await;
 
@@ -3,8 +3,15 @@
 //! 基于Axum的HTTP服务器，处理代理请求
 
 use super::{
-    failover_switch::FailoverSwitchManager, handlers, log_codes::srv as log_srv,
-    provider_router::ProviderRouter, types::*, ProxyError,
+    active_requests::{track_active_requests, ActiveRequests},
+    failover_switch::FailoverSwitchManager,
+    handlers,
+    local_api::{self, LOCAL_API_PREFIX},
+    log_codes::srv as log_srv,
+    provider_router::ProviderRouter,
+    rate_limiter::RateLimiter,
+    types::*,
+    ProxyError,
 };
 use crate::database::Database;
 use crate::services::thread_memory::ThreadMemoryService;
@@ -15,6 +22,7 @@ use axum::{
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{oneshot, RwLock};
 use tokio::task::JoinHandle;
 use tower_http::cors::{Any, CorsLayer};
@@ -36,6 +44,10 @@ pub struct ProxyState {
     pub failover_manager: Arc<FailoverSwitchManager>,
     /// 本地线程记忆（Neo4j，可选）
     pub thread_memory: Option<Arc<ThreadMemoryService>>,
+    /// 供应商速率限制器（内存状态，代理重启后重置）
+    pub rate_limiter: Arc<RateLimiter>,
+    /// 进行中的请求（用于优雅停止）
+    pub active_requests: Arc<ActiveRequests>,
 }
 
 /// 代理HTTP服务器
@@ -54,7 +66,10 @@ impl ProxyServer {
         app_handle: Option<tauri::AppHandle>,
     ) -> Self {
         // 创建共享的 ProviderRouter（熔断器状态将跨所有请求保持）
-        let provider_router = Arc::new(ProviderRouter::new(db.clone()));
+        let provider_router = Arc::new(ProviderRouter::with_app_handle(
+            db.clone(),
+            app_handle.clone(),
+        ));
         // 创建故障转移切换管理器
         let failover_manager = Arc::new(FailoverSwitchManager::new(db.clone()));
         let thread_memory = ThreadMemoryService::from_env().map(Arc::new);
@@ -69,6 +84,8 @@ impl ProxyServer {
             app_handle,
             failover_manager,
             thread_memory,
+            rate_limiter: Arc::new(RateLimiter::new()),
+            active_requests: Arc::new(ActiveRequests::new()),
         };
 
         Self {
@@ -152,7 +169,12 @@ impl ProxyServer {
             return Err(ProxyError::NotRunning);
         }
 
-        // 2. 等待服务器任务结束（带 5 秒超时保护）
+        // 2. 等待服务器任务结束
+        self.wait_server_stopped().await
+    }
+
+    /// 等待服务器任务结束（带 5 秒超时保护）
+    async fn wait_server_stopped(&self) -> Result<(), ProxyError> {
         if let Some(handle) = self.server_handle.write().await.take() {
             match tokio::time::timeout(std::time::Duration::from_secs(5), handle).await {
                 Ok(Ok(())) => {
@@ -176,9 +198,45 @@ impl ProxyServer {
         }
     }
 
+    /// 优雅停止：立即停止接受新连接，最多等待 `timeout` 让进行中的请求（含流式响应）完成
+    ///
+    /// 超时后中断剩余请求并记录被中断的请求，再按普通停止流程等待服务器退出。
+    pub async fn stop_graceful(&self, timeout: Duration) -> Result<(), ProxyError> {
+        let Some(tx) = self.shutdown_tx.write().await.take() else {
+            return Err(ProxyError::NotRunning);
+        };
+        let _ = tx.send(());
+
+        let active = &self.state.active_requests;
+        if !active.is_empty() {
+            log::info!(
+                "[{}] 已停止接受新连接，等待 {} 个进行中的请求完成（最多 {} 秒）",
+                log_srv::DRAINING,
+                active.len(),
+                timeout.as_secs()
+            );
+        }
+        if !active.wait_idle(timeout).await {
+            for request in active.snapshot() {
+                log::warn!(
+                    "[{}] 优雅停止超时，中断请求: {} {}（已进行 {} 秒）",
+                    log_srv::DRAIN_TIMEOUT,
+                    request.method,
+                    request.path,
+                    request.started_at.elapsed().as_secs()
+                );
+            }
+            active.abort_all();
+        }
+
+        self.wait_server_stopped().await
+    }
+
     pub async fn get_status(&self) -> ProxyStatus {
         let mut status = self.state.status.read().await.clone();
 
+        status.active_connections = self.state.active_requests.len();
+
         // 计算运行时间
         if let Some(start) = *self.state.start_time.read().await {
             status.uptime_seconds = start.elapsed().as_secs();
@@ -220,6 +278,15 @@ impl ProxyServer {
             // 健康检查
             .route("/health", get(handlers::health_check))
             .route("/status", get(handlers::get_status))
+            // 本地 API（供外部工具查询状态/切换供应商，需 token，可整体关闭）
+            .route(
+                &format!("{LOCAL_API_PREFIX}/status"),
+                get(local_api::status),
+            )
+            .route(
+                &format!("{LOCAL_API_PREFIX}/switch"),
+                post(local_api::switch),
+            )
             // Claude API (支持带前缀和不带前缀两种格式)
             .route("/v1/messages", post(handlers::handle_messages))
             .route("/claude/v1/messages", post(handlers::handle_messages))
@@ -245,6 +312,16 @@ impl ProxyServer {
             // Gemini API (支持带前缀和不带前缀)
             .route("/v1beta/*path", post(handlers::handle_gemini))
             .route("/gemini/v1beta/*path", post(handlers::handle_gemini))
+            // OpenCode（按供应商路由，接管时写入各供应商的 baseURL）
+            .route(
+                "/opencode/:provider_id/*path",
+                post(handlers::handle_opencode),
+            )
+            // 跟踪进行中的请求，供优雅停止等待排空
+            .layer(axum::middleware::from_fn_with_state(
+                self.state.clone(),
+                track_active_requests,
+            ))
             // 提高默认请求体大小限制（避免 413 Payload Too Large）
             .layer(DefaultBodyLimit::max(200 * 1024 * 1024))
             .layer(cors)
@@ -266,6 +343,40 @@ impl ProxyServer {
         self.state.provider_router.update_all_configs(config).await;
     }
 
+    /// 读取熔断器状态与路由可用性，不触发状态转换（尚未创建熔断器时返回 None）
+    pub async fn peek_circuit(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Option<(super::circuit_breaker::CircuitState, bool)> {
+        self.state
+            .provider_router
+            .peek_circuit(provider_id, app_type)
+            .await
+    }
+
+    /// 获取指定 Provider 的熔断器状态（尚未创建熔断器时返回 None）
+    pub async fn get_circuit_breaker_stats(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Option<super::circuit_breaker::CircuitBreakerStats> {
+        self.state
+            .provider_router
+            .get_circuit_breaker_stats(provider_id, app_type)
+            .await
+    }
+
+    /// 获取所有熔断器状态，返回 (app_type, provider_id, stats)
+    pub async fn get_all_circuit_breaker_stats(
+        &self,
+    ) -> Vec<(String, String, super::circuit_breaker::CircuitBreakerStats)> {
+        self.state
+            .provider_router
+            .get_all_circuit_breaker_stats()
+            .await
+    }
+
     /// 重置指定 Provider 的熔断器
     pub async fn reset_provider_circuit_breaker(&self, provider_id: &str, app_type: &str) {
         self.state
@@ -273,4 +384,18 @@ impl ProxyServer {
             .reset_provider_breaker(provider_id, app_type)
             .await;
     }
+
+    /// 同步指定 Provider 的手动熔断状态到路由器
+    pub async fn set_circuit_override(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        forced_state: Option<super::types::ForcedCircuitState>,
+        until: Option<i64>,
+    ) {
+        self.state
+            .provider_router
+            .set_circuit_override(provider_id, app_type, forced_state, until)
+            .await;
+    }
 }
@@ -0,0 +1,364 @@
+//! 操作系统代理设置检测
+//!
+//! 用于 "system" 代理模式：读取操作系统当前的代理配置并转换为代理 URL。
+//!
+//! - macOS：`scutil --proxy`（SystemConfiguration 动态存储）
+//! - Windows：`reg query` 读取 WinINet 的 Internet Settings
+//! - Linux 等其他平台：HTTPS_PROXY / HTTP_PROXY / ALL_PROXY 环境变量
+
+use super::http_client;
+use super::types::ProxyMode;
+use std::collections::HashMap;
+
+/// 系统代理检测器
+///
+/// 返回 Ok(None) 表示系统未配置代理，Err 表示检测本身失败。
+pub trait SystemProxyDetector {
+    fn detect(&self) -> Result<Option<String>, String>;
+}
+
+/// 基于当前操作系统的检测器
+pub struct OsProxyDetector;
+
+impl SystemProxyDetector for OsProxyDetector {
+    #[cfg(target_os = "macos")]
+    fn detect(&self) -> Result<Option<String>, String> {
+        let output = std::process::Command::new("scutil")
+            .arg("--proxy")
+            .output()
+            .map_err(|e| format!("Failed to run scutil: {e}"))?;
+        if !output.status.success() {
+            return Err(format!("scutil exited with {}", output.status));
+        }
+        Ok(parse_scutil_proxy(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    #[cfg(windows)]
+    fn detect(&self) -> Result<Option<String>, String> {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let output = std::process::Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings",
+            ])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("Failed to run reg query: {e}"))?;
+        if !output.status.success() {
+            return Err(format!("reg query exited with {}", output.status));
+        }
+        Ok(parse_windows_internet_settings(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn detect(&self) -> Result<Option<String>, String> {
+        Ok(proxy_from_env(|key| std::env::var(key).ok()))
+    }
+}
+
+/// 根据代理模式计算实际使用的代理 URL
+///
+/// - Manual：使用手动配置的 URL
+/// - System：使用检测到的系统代理；检测失败、未配置或指向 CC Switch 自身时返回 None，
+///   此时客户端回退为跟随环境变量代理（与未配置手动代理时一致）
+/// - None：始终返回 None（直连）
+pub fn resolve_proxy_url(
+    mode: ProxyMode,
+    manual_url: Option<String>,
+    detector: &dyn SystemProxyDetector,
+) -> Option<String> {
+    match mode {
+        ProxyMode::Manual => manual_url.filter(|url| !url.trim().is_empty()),
+        ProxyMode::None => None,
+        ProxyMode::System => match detector.detect() {
+            Ok(Some(url)) if http_client::proxy_points_to_loopback(&url) => {
+                log::warn!(
+                    "[GlobalProxy] System proxy {} points to CC Switch itself, ignoring",
+                    http_client::mask_url(&url)
+                );
+                None
+            }
+            Ok(Some(url)) => {
+                log::info!(
+                    "[GlobalProxy] Detected system proxy: {}",
+                    http_client::mask_url(&url)
+                );
+                Some(url)
+            }
+            Ok(None) => {
+                log::info!("[GlobalProxy] No system proxy configured");
+                None
+            }
+            Err(e) => {
+                log::warn!("[GlobalProxy] System proxy detection failed, falling back: {e}");
+                None
+            }
+        },
+    }
+}
+
+/// 解析 `scutil --proxy` 输出
+///
+/// 优先级：HTTPS > HTTP > SOCKS，仅取对应 *Enable 为 1 的条目。
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_scutil_proxy(output: &str) -> Option<String> {
+    let mut values: HashMap<&str, &str> = HashMap::new();
+    let mut depth = 0usize;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.ends_with('{') {
+            depth += 1;
+            continue;
+        }
+        if line == "}" {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        // 只读取顶层字典的键值（跳过 ExceptionsList 等嵌套数组）
+        if depth != 1 {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(" : ") {
+            values.insert(key.trim(), value.trim());
+        }
+    }
+
+    [("HTTPS", "http"), ("HTTP", "http"), ("SOCKS", "socks5")]
+        .into_iter()
+        .find_map(|(prefix, scheme)| {
+            if values.get(format!("{prefix}Enable").as_str()) != Some(&"1") {
+                return None;
+            }
+            let host = values.get(format!("{prefix}Proxy").as_str())?;
+            let port: u16 = values.get(format!("{prefix}Port").as_str())?.parse().ok()?;
+            Some(format_proxy_url(scheme, host, port))
+        })
+}
+
+/// 解析 `reg query ...\Internet Settings` 输出
+///
+/// ProxyEnable 为 0x1 时读取 ProxyServer 并交给 [`parse_windows_proxy_server`]。
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_windows_internet_settings(output: &str) -> Option<String> {
+    let mut enabled = false;
+    let mut server = None;
+    for line in output.lines() {
+        // "    ProxyEnable    REG_DWORD    0x1"
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(_kind)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let value = parts.collect::<Vec<_>>().join(" ");
+        match name {
+            "ProxyEnable" => {
+                enabled = u32::from_str_radix(value.trim_start_matches("0x"), 16) == Ok(1);
+            }
+            "ProxyServer" => server = Some(value),
+            _ => {}
+        }
+    }
+    if !enabled {
+        return None;
+    }
+    parse_windows_proxy_server(&server?)
+}
+
+/// 解析 WinINet ProxyServer 值
+///
+/// 支持两种格式：
+/// - 单一代理：`127.0.0.1:7890` 或 `http://127.0.0.1:7890`
+/// - 按协议分配：`http=127.0.0.1:7890;https=127.0.0.1:7890;socks=127.0.0.1:7891`
+///
+/// 按协议分配时优先级为 https > http > socks。
+fn parse_windows_proxy_server(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if !value.contains('=') {
+        let first = value.split(';').map(str::trim).find(|s| !s.is_empty())?;
+        return Some(with_default_scheme(first, "http"));
+    }
+
+    let entries: HashMap<String, &str> = value
+        .split(';')
+        .filter_map(|entry| {
+            let (protocol, address) = entry.split_once('=')?;
+            let address = address.trim();
+            (!address.is_empty()).then(|| (protocol.trim().to_ascii_lowercase(), address))
+        })
+        .collect();
+
+    [("https", "http"), ("http", "http"), ("socks", "socks5")]
+        .into_iter()
+        .find_map(|(protocol, scheme)| {
+            entries
+                .get(protocol)
+                .map(|address| with_default_scheme(address, scheme))
+        })
+}
+
+/// 从环境变量读取代理（HTTPS > HTTP > ALL，大写优先）
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+fn proxy_from_env(lookup: impl Fn(&str) -> Option<String>) -> Option<String> {
+    const KEYS: [&str; 6] = [
+        "HTTPS_PROXY",
+        "https_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+    ];
+    KEYS.iter()
+        .filter_map(|key| lookup(key))
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+        .map(|value| with_default_scheme(&value, "http"))
+}
+
+fn with_default_scheme(address: &str, scheme: &str) -> String {
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("{scheme}://{address}")
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn format_proxy_url(scheme: &str, host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("{scheme}://[{host}]:{port}")
+    } else {
+        format!("{scheme}://{host}:{port}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedDetector(Result<Option<String>, String>);
+
+    impl SystemProxyDetector for FixedDetector {
+        fn detect(&self) -> Result<Option<String>, String> {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn parse_scutil_proxy_prefers_https_and_skips_nested_arrays() {
+        let output = "\
+<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  FTPPassive : 1
+  HTTPEnable : 1
+  HTTPPort : 8080
+  HTTPProxy : proxy.corp.example
+  HTTPSEnable : 1
+  HTTPSPort : 7890
+  HTTPSProxy : 127.0.0.1
+  SOCKSEnable : 0
+}
+";
+        assert_eq!(
+            parse_scutil_proxy(output).as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+
+        let socks_only = "\
+<dictionary> {
+  HTTPEnable : 0
+  SOCKSEnable : 1
+  SOCKSPort : 1080
+  SOCKSProxy : ::1
+}
+";
+        assert_eq!(
+            parse_scutil_proxy(socks_only).as_deref(),
+            Some("socks5://[::1]:1080")
+        );
+
+        assert_eq!(
+            parse_scutil_proxy("<dictionary> {\n  HTTPEnable : 0\n}\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_windows_internet_settings_requires_proxy_enable() {
+        let enabled = "\
+HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings
+    ProxyEnable    REG_DWORD    0x1
+    ProxyServer    REG_SZ    127.0.0.1:7890
+    ProxyOverride    REG_SZ    localhost;127.*;<local>
+";
+        assert_eq!(
+            parse_windows_internet_settings(enabled).as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+
+        let disabled = enabled.replace("0x1", "0x0");
+        assert_eq!(parse_windows_internet_settings(&disabled), None);
+    }
+
+    #[test]
+    fn parse_windows_proxy_server_handles_per_protocol_lists() {
+        assert_eq!(
+            parse_windows_proxy_server("http=10.0.0.2:3128;https=10.0.0.2:3129").as_deref(),
+            Some("http://10.0.0.2:3129")
+        );
+        assert_eq!(
+            parse_windows_proxy_server("socks=127.0.0.1:1080").as_deref(),
+            Some("socks5://127.0.0.1:1080")
+        );
+        assert_eq!(
+            parse_windows_proxy_server("socks5://127.0.0.1:7891").as_deref(),
+            Some("socks5://127.0.0.1:7891")
+        );
+        assert_eq!(parse_windows_proxy_server("  "), None);
+    }
+
+    #[test]
+    fn proxy_from_env_prefers_https_and_adds_scheme() {
+        let env: HashMap<&str, &str> = [
+            ("http_proxy", "http://10.0.0.2:3128"),
+            ("HTTPS_PROXY", "127.0.0.1:7890"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            proxy_from_env(|key| env.get(key).map(|v| v.to_string())).as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+        assert_eq!(proxy_from_env(|_| None), None);
+    }
+
+    #[test]
+    fn resolve_proxy_url_falls_back_when_detection_fails() {
+        let manual = Some("http://10.0.0.2:3128".to_string());
+        let detected = FixedDetector(Ok(Some("http://10.0.0.9:8080".to_string())));
+        let failing = FixedDetector(Err("scutil missing".to_string()));
+
+        assert_eq!(
+            resolve_proxy_url(ProxyMode::Manual, manual.clone(), &failing),
+            manual
+        );
+        assert_eq!(
+            resolve_proxy_url(ProxyMode::System, manual.clone(), &detected).as_deref(),
+            Some("http://10.0.0.9:8080")
+        );
+        assert_eq!(
+            resolve_proxy_url(ProxyMode::System, manual.clone(), &failing),
+            None
+        );
+        assert_eq!(resolve_proxy_url(ProxyMode::None, manual, &detected), None);
+    }
+}
@@ -113,6 +113,8 @@ pub struct ProxyTakeoverStatus {
     pub claude: bool,
     pub codex: bool,
     pub gemini: bool,
+    #[serde(default)]
+    pub opencode: bool,
 }
 
 /// API 格式类型（预留，当前不需要格式转换）
@@ -135,6 +137,105 @@ pub struct ProviderHealth {
     pub last_failure_at: Option<String>,
     pub last_error: Option<String>,
     pub updated_at: String,
+    /// 手动指定的熔断器状态（None 表示自动）
+    #[serde(default)]
+    pub forced_state: Option<ForcedCircuitState>,
+    /// 手动状态的失效时间（毫秒时间戳，None 表示直到手动清除）
+    #[serde(default)]
+    pub forced_until: Option<i64>,
+}
+
+/// 手动指定的熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForcedCircuitState {
+    /// 强制熔断：该供应商不参与路由
+    Open,
+    /// 强制放行：忽略熔断器判断
+    Closed,
+}
+
+impl ForcedCircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(Self::Open),
+            "closed" => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+
+/// 上游尝试类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptKind {
+    /// 首次请求
+    Initial,
+    /// 同一 Provider 上的重试（瞬时错误重试或整流重试）
+    Retry,
+    /// 切换到下一个 Provider
+    Failover,
+    /// 熔断器拒绝，未发出请求
+    CircuitSkip,
+    /// 超出供应商速率限制（排队等待或直接跳过）
+    RateLimited,
+}
+
+impl AttemptKind {
+    /// 是否实际向上游发出了请求
+    pub fn is_request(self) -> bool {
+        !matches!(self, Self::CircuitSkip | Self::RateLimited)
+    }
+}
+
+/// 单次上游尝试记录（存入 proxy_request_logs.attempts）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardAttempt {
+    pub provider_id: String,
+    pub kind: AttemptKind,
+    /// 相对请求开始的偏移（毫秒）
+    pub start_offset_ms: u64,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 紧急恢复中单个应用的处理结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmergencyRestoreAppReport {
+    pub app_type: String,
+    /// 已从 Live 备份恢复
+    pub backup_restored: bool,
+    /// 已清理残留的接管占位符
+    pub placeholders_removed: bool,
+    /// 已从当前供应商重写 Live 配置（最后兜底）
+    pub rewritten_from_provider: bool,
+    /// 已清除 proxy_config.enabled，下次启动不再自动接管
+    pub takeover_disabled: bool,
+    /// 恢复后 Live 配置是否仍处于接管状态
+    pub still_taken_over: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// 紧急恢复结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmergencyRestoreReport {
+    /// 本次是否停止了正在运行的代理
+    pub proxy_stopped: bool,
+    pub apps: Vec<EmergencyRestoreAppReport>,
 }
 
 /// Live 配置备份记录
@@ -160,6 +261,9 @@ pub struct GlobalProxyConfig {
     pub listen_port: u16,
     /// 是否启用日志
     pub enable_logging: bool,
+    /// 端口被占用时自动向上查找可用端口并写回配置
+    #[serde(default)]
+    pub auto_port: bool,
 }
 
 /// 应用级代理配置（每个 app 独立）
@@ -190,6 +294,16 @@ pub struct AppProxyConfig {
     pub circuit_error_rate_threshold: f64,
     /// 计算错误率的最小请求数
     pub circuit_min_requests: u32,
+    /// 是否为失败请求捕获请求/响应体（调试用，默认关闭）
+    #[serde(default)]
+    pub capture_bodies: bool,
+    /// 捕获请求/响应体的最大字节数（超出部分截断）
+    #[serde(default = "default_capture_max_bytes")]
+    pub capture_max_bytes: u32,
+}
+
+pub(crate) fn default_capture_max_bytes() -> u32 {
+    16 * 1024
 }
 
 /// 整流器配置
@@ -216,6 +330,59 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
+/// 全局出站网络配置（DNS 覆盖 + IPv6 优先）
+///
+/// 存储在 settings 表的 global_network_config 字段中（JSON 格式），
+/// 与全局代理 URL 一起用于构建全局 HTTP 客户端。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalNetworkConfig {
+    /// 主机名 → IP 地址的固定解析（优先于系统 DNS）
+    #[serde(default)]
+    pub dns_overrides: std::collections::BTreeMap<String, String>,
+    /// 解析结果中优先尝试 IPv6 地址
+    #[serde(default)]
+    pub prefer_ipv6: bool,
+}
+
+/// 全局出站代理模式
+///
+/// 存储在 settings 表的 proxy_mode 字段中。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// 使用手动配置的 global_proxy_url（未配置时沿用环境变量代理）
+    #[default]
+    Manual,
+    /// 跟随操作系统代理设置
+    System,
+    /// 强制直连，忽略所有代理设置
+    None,
+}
+
+impl ProxyMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyMode::Manual => "manual",
+            ProxyMode::System => "system",
+            ProxyMode::None => "none",
+        }
+    }
+}
+
+impl std::str::FromStr for ProxyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "manual" => Ok(ProxyMode::Manual),
+            "system" => Ok(ProxyMode::System),
+            "none" => Ok(ProxyMode::None),
+            other => Err(format!("Unknown proxy mode: {other}")),
+        }
+    }
+}
+
 /// 日志配置
 ///
 /// 存储在 settings 表的 log_config 字段中（JSON 格式）
@@ -228,6 +395,9 @@ pub struct LogConfig {
     /// 日志级别: error, warn, info, debug, trace
     #[serde(default = "default_log_level")]
     pub level: String,
+    /// 按模块覆盖的日志级别（如 `proxy` → `debug`），优先于全局级别
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub module_levels: std::collections::BTreeMap<String, String>,
 }
 
 impl Default for LogConfig {
@@ -235,6 +405,7 @@ impl Default for LogConfig {
         Self {
             enabled: true,
             level: "info".to_string(),
+            module_levels: std::collections::BTreeMap::new(),
         }
     }
 }
@@ -256,10 +427,54 @@ impl LogConfig {
     }
 }
 
+fn default_log_retention_days() -> u32 {
+    90
+}
+
+/// 请求日志保留策略
+///
+/// 存储在 settings 表的 log_retention_config 字段中（JSON 格式）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRetentionConfig {
+    /// 保留天数，早于该天数的 proxy_request_logs 会被清理；填 0 表示不按时间清理
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+    /// 最多保留的日志行数，超出部分从最旧的开始清理；None 表示不限制
+    #[serde(default)]
+    pub max_log_rows: Option<u64>,
+    /// 清理前是否将被删除的行归档为 gzip 压缩的 JSONL 文件
+    #[serde(default)]
+    pub archive_before_prune: bool,
+    /// 清理后是否执行 PRAGMA incremental_vacuum 回收空间
+    #[serde(default = "default_true")]
+    pub incremental_vacuum: bool,
+}
+
+impl Default for LogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            log_retention_days: default_log_retention_days(),
+            max_log_rows: None,
+            archive_before_prune: false,
+            incremental_vacuum: true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_log_retention_config_serde_default() {
+        let config: LogRetentionConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.log_retention_days, 90);
+        assert_eq!(config.max_log_rows, None);
+        assert!(!config.archive_before_prune);
+        assert!(config.incremental_vacuum);
+    }
+
     #[test]
     fn test_rectifier_config_default_disabled() {
         // 验证 RectifierConfig::default() 返回全禁用状态
@@ -347,6 +562,7 @@ mod tests {
         let config = LogConfig {
             enabled: false,
             level: "debug".to_string(),
+            ..Default::default()
         };
         assert_eq!(config.to_level_filter(), log::LevelFilter::Off);
     }
@@ -356,10 +572,16 @@ mod tests {
         let config = LogConfig {
             enabled: true,
             level: "debug".to_string(),
+            module_levels: std::collections::BTreeMap::from([(
+                "proxy".to_string(),
+                "trace".to_string(),
+            )]),
         };
         let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("moduleLevels"));
         let parsed: LogConfig = serde_json::from_str(&json).unwrap();
         assert!(parsed.enabled);
         assert_eq!(parsed.level, "debug");
+        assert_eq!(parsed.module_levels["proxy"], "trace");
     }
 }
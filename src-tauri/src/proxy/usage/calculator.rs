@@ -45,20 +45,40 @@ impl CostCalculator {
         usage: &TokenUsage,
         pricing: &ModelPricing,
         cost_multiplier: Decimal,
+    ) -> CostBreakdown {
+        Self::calculate_tokens(
+            u64::from(usage.input_tokens),
+            u64::from(usage.output_tokens),
+            u64::from(usage.cache_read_tokens),
+            u64::from(usage.cache_creation_tokens),
+            pricing,
+            cost_multiplier,
+        )
+    }
+
+    /// 按原始 token 数计算成本
+    ///
+    /// 代理记录成本、历史成本回填与成本预估共用此计算，保证结果一致。
+    pub fn calculate_tokens(
+        input_tokens: u64,
+        output_tokens: u64,
+        cache_read_tokens: u64,
+        cache_creation_tokens: u64,
+        pricing: &ModelPricing,
+        cost_multiplier: Decimal,
     ) -> CostBreakdown {
         let million = Decimal::from(1_000_000);
 
         // 计算实际需要按输入价格计费的 token 数（减去缓存命中部分）
-        let billable_input_tokens = usage.input_tokens.saturating_sub(usage.cache_read_tokens);
+        let billable_input_tokens = input_tokens.saturating_sub(cache_read_tokens);
 
         // 各项基础成本（不含倍率）
         let input_cost =
             Decimal::from(billable_input_tokens) * pricing.input_cost_per_million / million;
-        let output_cost =
-            Decimal::from(usage.output_tokens) * pricing.output_cost_per_million / million;
+        let output_cost = Decimal::from(output_tokens) * pricing.output_cost_per_million / million;
         let cache_read_cost =
-            Decimal::from(usage.cache_read_tokens) * pricing.cache_read_cost_per_million / million;
-        let cache_creation_cost = Decimal::from(usage.cache_creation_tokens)
+            Decimal::from(cache_read_tokens) * pricing.cache_read_cost_per_million / million;
+        let cache_creation_cost = Decimal::from(cache_creation_tokens)
             * pricing.cache_creation_cost_per_million
             / million;
 
@@ -4,7 +4,7 @@ use super::calculator::{CostBreakdown, CostCalculator, ModelPricing};
 use super::parser::TokenUsage;
 use crate::database::Database;
 use crate::error::AppError;
-use crate::services::usage_stats::find_model_pricing_row;
+use crate::services::usage_stats::load_model_pricing;
 use rust_decimal::Decimal;
 use std::{str::FromStr, time::SystemTime};
 
@@ -29,6 +29,10 @@ pub struct RequestLog {
     pub is_streaming: bool,
     /// 成本倍数
     pub cost_multiplier: String,
+    /// 上游尝试时间线（JSON 数组，仅发生重试/故障转移/熔断跳过时有意义）
+    pub attempts: Option<String>,
+    /// 重放来源请求 ID（手动重放的请求不计入使用统计）
+    pub replay_of: Option<String>,
 }
 
 /// 使用量记录器
@@ -78,8 +82,8 @@ impl<'a> UsageLogger<'a> {
                 input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens,
                 input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd, total_cost_usd,
                 latency_ms, first_token_ms, status_code, error_message, session_id,
-                provider_type, is_streaming, cost_multiplier, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+                provider_type, is_streaming, cost_multiplier, created_at, attempts, replay_of
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
             rusqlite::params![
                 log.request_id,
                 log.provider_id,
@@ -104,10 +108,20 @@ impl<'a> UsageLogger<'a> {
                 log.is_streaming as i64,
                 log.cost_multiplier,
                 created_at,
+                log.attempts,
+                log.replay_of,
             ],
         )
         .map_err(|e| AppError::Database(format!("记录请求日志失败: {e}")))?;
 
+        // 与 SUM(CAST(total_cost_usd AS REAL)) 口径一致，累加到限额窗口缓存
+        self.db.record_provider_spend(
+            &log.provider_id,
+            &log.app_type,
+            created_at,
+            total_cost.parse::<f64>().unwrap_or(0.0),
+        );
+
         Ok(())
     }
 
@@ -142,6 +156,8 @@ impl<'a> UsageLogger<'a> {
             provider_type: None,
             is_streaming: false,
             cost_multiplier: "1.0".to_string(),
+            attempts: None,
+            replay_of: None,
         };
 
         self.log_request(&log)
@@ -163,6 +179,7 @@ impl<'a> UsageLogger<'a> {
         is_streaming: bool,
         session_id: Option<String>,
         provider_type: Option<String>,
+        attempts: Option<String>,
     ) -> Result<(), AppError> {
         let request_model = model.clone();
         let log = RequestLog {
@@ -181,6 +198,8 @@ impl<'a> UsageLogger<'a> {
             provider_type,
             is_streaming,
             cost_multiplier: "1.0".to_string(),
+            attempts,
+            replay_of: None,
         };
 
         self.log_request(&log)
@@ -189,15 +208,7 @@ impl<'a> UsageLogger<'a> {
     /// 获取模型定价
     pub fn get_model_pricing(&self, model_id: &str) -> Result<Option<ModelPricing>, AppError> {
         let conn = crate::database::lock_conn!(self.db.conn);
-        let row = find_model_pricing_row(&conn, model_id)?;
-        match row {
-            Some((input, output, cache_read, cache_creation)) => {
-                ModelPricing::from_strings(&input, &output, &cache_read, &cache_creation)
-                    .map(Some)
-                    .map_err(|e| AppError::Database(format!("解析定价数据失败: {e}")))
-            }
-            None => Ok(None),
-        }
+        load_model_pricing(&conn, model_id)
     }
 
     /// 获取有效的倍率与计费模式来源（供应商优先，未配置则回退全局默认）
@@ -300,6 +311,7 @@ impl<'a> UsageLogger<'a> {
         session_id: Option<String>,
         provider_type: Option<String>,
         is_streaming: bool,
+        attempts: Option<String>,
     ) -> Result<(), AppError> {
         let pricing = self.get_model_pricing(&pricing_model)?;
 
@@ -325,6 +337,8 @@ impl<'a> UsageLogger<'a> {
             provider_type,
             is_streaming,
             cost_multiplier: cost_multiplier.to_string(),
+            attempts,
+            replay_of: None,
         };
 
         self.log_request(&log)
@@ -375,6 +389,7 @@ mod tests {
             None,
             Some("claude".to_string()),
             false,
+            None,
         )?;
 
         // 验证记录已插入
@@ -9,9 +9,13 @@ use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::app_config::AppType;
 use crate::config::get_home_dir;
 use crate::error::AppError;
-use crate::provider::{Provider, UsageData, UsageResult};
+use crate::gemini_config::validate_gemini_settings;
+use crate::provider::{Provider, ProviderMeta, UsageData, UsageResult};
+use crate::services::ProviderService;
+use crate::store::AppState;
 
 pub const ANTIGRAVITY_ACCESS_TOKEN_KEY: &str = "ANTIGRAVITY_ACCESS_TOKEN";
 pub const ANTIGRAVITY_REFRESH_TOKEN_KEY: &str = "ANTIGRAVITY_REFRESH_TOKEN";
@@ -23,6 +27,8 @@ pub const GOOGLE_OAUTH_EMAIL_KEY: &str = "GOOGLE_OAUTH_EMAIL";
 pub const GOOGLE_OAUTH_PROJECT_ID_KEY: &str = "GOOGLE_OAUTH_PROJECT_ID";
 
 const CLOUD_CODE_BASE_URL: &str = "https://daily-cloudcode-pa.sandbox.googleapis.com";
+const ANTIGRAVITY_WEBSITE_URL: &str = "https://antigravity.dev";
+const DEFAULT_GEMINI_MODEL: &str = "gemini-2.5-pro";
 const QUOTA_API_URL: &str =
     "https://daily-cloudcode-pa.sandbox.googleapis.com/v1internal:fetchAvailableModels";
 const USERINFO_URL: &str = "https://www.googleapis.com/oauth2/v2/userinfo";
@@ -710,12 +716,173 @@ pub fn has_official_credentials(provider: &Provider) -> bool {
         .unwrap_or(false)
 }
 
+/// Antigravity 访问令牌的过期时间（秒级时间戳），未记录时返回 None
+pub fn antigravity_expires_at(provider: &Provider) -> Option<i64> {
+    extract_env_map_from_provider(provider)
+        .ok()?
+        .get(ANTIGRAVITY_EXPIRES_AT_KEY)?
+        .trim()
+        .parse::<i64>()
+        .ok()
+}
+
 pub fn has_google_oauth_access_token(provider: &Provider) -> bool {
     extract_env_map_from_provider(provider)
         .map(|env| extract_quota_access_token(&env).is_some())
         .unwrap_or(false)
 }
 
+/// 一键创建 Antigravity 供应商时执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AntigravityProviderAction {
+    /// 新建了供应商
+    Created,
+    /// 已存在同邮箱供应商，仅原地更新 Token
+    Updated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AntigravityProviderResult {
+    pub action: AntigravityProviderAction,
+    pub provider: Provider,
+}
+
+/// 导入本机 Antigravity 当前会话并保存为 Gemini 供应商
+///
+/// 若已有同邮箱的 Antigravity 供应商，则原地更新其 Token，避免重复创建。
+pub fn create_provider_from_current_session(
+    state: &AppState,
+    name: &str,
+) -> Result<AntigravityProviderResult, AppError> {
+    let session = import_current_session_from_local_db()?;
+    let providers = state.db.get_all_providers(AppType::Gemini.as_str())?;
+
+    if let Some(existing) = find_provider_by_email(providers.values(), &session.email) {
+        let mut provider = existing.clone();
+        apply_session_to_provider(&mut provider, &session)?;
+        validate_gemini_settings(&provider.settings_config)?;
+        ProviderService::update(state, AppType::Gemini, provider.clone())?;
+        log::info!("已更新 Antigravity 供应商 Token: {}", provider.id);
+        return Ok(AntigravityProviderResult {
+            action: AntigravityProviderAction::Updated,
+            provider,
+        });
+    }
+
+    let provider = build_provider_from_session(name, &session);
+    validate_gemini_settings(&provider.settings_config)?;
+    ProviderService::add(state, AppType::Gemini, provider.clone())?;
+    log::info!("已创建 Antigravity 供应商: {}", provider.id);
+    Ok(AntigravityProviderResult {
+        action: AntigravityProviderAction::Created,
+        provider,
+    })
+}
+
+/// 根据导入的会话构建 Antigravity 官方账号供应商（与前端预设保持一致）
+fn build_provider_from_session(name: &str, session: &AntigravityImportedSession) -> Provider {
+    let name = match name.trim() {
+        "" => format!("Antigravity ({})", session.email),
+        trimmed => trimmed.to_string(),
+    };
+    let mut provider = Provider::with_id(
+        uuid::Uuid::new_v4().to_string(),
+        name,
+        json!({
+            "env": {
+                "GOOGLE_GEMINI_BASE_URL": CLOUD_CODE_BASE_URL,
+                "GEMINI_MODEL": DEFAULT_GEMINI_MODEL,
+            }
+        }),
+        Some(ANTIGRAVITY_WEBSITE_URL.to_string()),
+    );
+    provider.category = Some("official".to_string());
+    provider.created_at = Some(Utc::now().timestamp_millis());
+    provider.icon = Some("gemini".to_string());
+    provider.icon_color = Some("#0EA5E9".to_string());
+    provider.meta = Some(ProviderMeta {
+        is_partner: Some(true),
+        partner_promotion_key: Some("antigravity".to_string()),
+        ..Default::default()
+    });
+    write_session_env(&mut provider.settings_config, session);
+    provider
+}
+
+/// 用新会话覆盖供应商中的 ANTIGRAVITY_* Token，其余配置保持不变
+fn apply_session_to_provider(
+    provider: &mut Provider,
+    session: &AntigravityImportedSession,
+) -> Result<(), AppError> {
+    // 兼容历史异常数据：settingsConfig 被存成 JSON 字符串时先还原为对象
+    if let Some(raw) = provider.settings_config.as_str() {
+        if let Ok(parsed) = serde_json::from_str::<Value>(raw) {
+            provider.settings_config = parsed;
+        }
+    }
+    if !provider.settings_config.is_object() {
+        return Err(AppError::localized(
+            "antigravity.provider.invalid_env",
+            "Gemini 配置格式错误：缺少 env 配置",
+            "Invalid Gemini settings: missing env object",
+        ));
+    }
+    write_session_env(&mut provider.settings_config, session);
+    Ok(())
+}
+
+fn write_session_env(settings: &mut Value, session: &AntigravityImportedSession) {
+    let Some(root) = settings.as_object_mut() else {
+        return;
+    };
+    let env = root.entry("env").or_insert_with(|| json!({}));
+    if !env.is_object() {
+        *env = json!({});
+    }
+    let Some(env) = env.as_object_mut() else {
+        return;
+    };
+    env.insert(
+        ANTIGRAVITY_ACCESS_TOKEN_KEY.to_string(),
+        json!(session.access_token),
+    );
+    env.insert(
+        ANTIGRAVITY_REFRESH_TOKEN_KEY.to_string(),
+        json!(session.refresh_token),
+    );
+    env.insert(ANTIGRAVITY_EMAIL_KEY.to_string(), json!(session.email));
+    env.insert(
+        ANTIGRAVITY_EXPIRES_AT_KEY.to_string(),
+        json!(session.expires_at.to_string()),
+    );
+    match session.project_id.as_deref() {
+        Some(project_id) => {
+            env.insert(ANTIGRAVITY_PROJECT_ID_KEY.to_string(), json!(project_id));
+        }
+        None => {
+            env.entry(ANTIGRAVITY_PROJECT_ID_KEY.to_string())
+                .or_insert_with(|| json!(""));
+        }
+    }
+}
+
+/// 按账号邮箱（忽略大小写）查找已存在的 Antigravity 供应商
+fn find_provider_by_email<'a>(
+    providers: impl IntoIterator<Item = &'a Provider>,
+    email: &str,
+) -> Option<&'a Provider> {
+    let email = email.trim();
+    providers.into_iter().find(|provider| {
+        is_antigravity_provider(provider)
+            && extract_env_map_from_provider(provider)
+                .ok()
+                .and_then(|env| env.get(ANTIGRAVITY_EMAIL_KEY).cloned())
+                .map(|v| v.trim().eq_ignore_ascii_case(email))
+                .unwrap_or(false)
+    })
+}
+
 fn extract_token_bundle_new_format(conn: &Connection) -> Option<TokenBundle> {
     let value: String = conn
         .query_row(
@@ -1284,4 +1451,58 @@ mod tests {
         );
         assert_eq!(has_google_oauth_access_token(&provider), true);
     }
+
+    fn mock_session(email: &str, access_token: &str) -> AntigravityImportedSession {
+        AntigravityImportedSession {
+            email: email.to_string(),
+            access_token: access_token.to_string(),
+            refresh_token: "1//refresh".to_string(),
+            expires_at: 1_700_000_000,
+            project_id: Some("proj-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn should_build_recognizable_provider_from_session() {
+        let provider = build_provider_from_session("  ", &mock_session("a@example.com", "ya29.a"));
+
+        assert!(is_antigravity_provider(&provider));
+        assert!(has_official_credentials(&provider));
+        assert!(validate_gemini_settings(&provider.settings_config).is_ok());
+        assert_eq!(provider.name, "Antigravity (a@example.com)");
+        assert_eq!(
+            provider.settings_config["env"]["GOOGLE_GEMINI_BASE_URL"],
+            json!(CLOUD_CODE_BASE_URL)
+        );
+        assert_eq!(
+            provider.settings_config["env"][ANTIGRAVITY_EXPIRES_AT_KEY],
+            json!("1700000000")
+        );
+    }
+
+    #[test]
+    fn should_update_existing_provider_tokens_in_place() {
+        let mut existing =
+            build_provider_from_session("Work", &mock_session("A@Example.com", "old"));
+        existing.settings_config["env"]["GEMINI_MODEL"] = json!("gemini-2.5-flash");
+        let other = build_provider_from_session("Other", &mock_session("b@example.com", "b"));
+        let providers = vec![other, existing];
+
+        let found = find_provider_by_email(&providers, " a@example.com ")
+            .expect("should match email case-insensitively");
+        assert_eq!(found.name, "Work");
+        assert!(find_provider_by_email(&providers, "c@example.com").is_none());
+
+        let mut updated = found.clone();
+        apply_session_to_provider(&mut updated, &mock_session("a@example.com", "new")).unwrap();
+        assert_eq!(updated.id, found.id);
+        assert_eq!(
+            updated.settings_config["env"][ANTIGRAVITY_ACCESS_TOKEN_KEY],
+            json!("new")
+        );
+        assert_eq!(
+            updated.settings_config["env"]["GEMINI_MODEL"],
+            json!("gemini-2.5-flash")
+        );
+    }
 }
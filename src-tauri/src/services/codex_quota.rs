@@ -0,0 +1,189 @@
+//! Codex 官方额度缓存与后台刷新
+//!
+//! 每个供应商最近一次成功查询的额度保存在 settings 表中（带 `fetched_at`）：
+//! - 前端查询时缓存未过期则直接返回，避免频繁请求 ChatGPT 后端触发限流；
+//! - 代理运行期间，后台按缓存有效期定时刷新故障转移队列中的 Codex 供应商，
+//!   刷新成功后发送 [`CODEX_QUOTA_UPDATED_EVENT`]；失败时保留上一次的缓存值。
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 后台刷新某个供应商额度成功后发送的事件
+pub const CODEX_QUOTA_UPDATED_EVENT: &str = "codex-quota-updated";
+/// 默认缓存有效期（5 分钟）
+pub const DEFAULT_CODEX_QUOTA_TTL_SECS: u64 = 5 * 60;
+/// 缓存有效期下限，避免后台刷新过于频繁
+pub const MIN_CODEX_QUOTA_TTL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexQuotaWindow {
+    pub used_percent: i64,
+    pub limit_window_seconds: i64,
+    pub reset_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexQuotaUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub five_hour: Option<CodexQuotaWindow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekly: Option<CodexQuotaWindow>,
+    pub fetched_at: i64,
+}
+
+/// [`CODEX_QUOTA_UPDATED_EVENT`] 的 payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexQuotaUpdate {
+    pub provider_id: String,
+    pub quota: CodexQuotaUsage,
+}
+
+pub struct CodexQuotaService;
+
+impl CodexQuotaService {
+    /// 获取供应商额度：缓存未过期且未要求强制刷新时返回缓存，否则请求远端并更新缓存
+    pub async fn get_quota(
+        db: &Database,
+        provider: &Provider,
+        force_refresh: bool,
+    ) -> Result<CodexQuotaUsage, AppError> {
+        if !force_refresh {
+            let ttl = db.get_codex_quota_ttl_secs()?;
+            match db.get_codex_quota_cache(&provider.id) {
+                Ok(Some(cached)) if Self::is_fresh(&cached, ttl, Utc::now().timestamp()) => {
+                    return Ok(cached);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("[CodexQuota] 读取额度缓存失败，将重新查询: {e}"),
+            }
+        }
+
+        let usage = crate::commands::fetch_codex_quota(provider)
+            .await
+            .map_err(AppError::Message)?;
+        // 只在查询成功时写入，失败不会覆盖上一次的有效缓存
+        if let Err(e) = db.set_codex_quota_cache(&provider.id, &usage) {
+            log::warn!("[CodexQuota] 保存额度缓存失败: {e}");
+        }
+        Ok(usage)
+    }
+
+    /// 启动后台刷新任务：代理运行时每个有效期刷新一次故障转移队列中的供应商
+    pub fn spawn_background_refresh(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let ttl = app
+                    .try_state::<AppState>()
+                    .and_then(|state| state.db.get_codex_quota_ttl_secs().ok())
+                    .unwrap_or(DEFAULT_CODEX_QUOTA_TTL_SECS);
+                tokio::time::sleep(Duration::from_secs(ttl)).await;
+
+                let Some(state) = app.try_state::<AppState>() else {
+                    continue;
+                };
+                if !state.proxy_service.is_running().await {
+                    continue;
+                }
+                Self::refresh_failover_queue(&app, &state.db).await;
+            }
+        });
+    }
+
+    async fn refresh_failover_queue(app: &AppHandle, db: &Database) {
+        let providers = match db.get_failover_providers(AppType::Codex.as_str()) {
+            Ok(providers) => providers,
+            Err(e) => {
+                log::warn!("[CodexQuota] 读取故障转移队列失败: {e}");
+                return;
+            }
+        };
+
+        for provider in providers {
+            match Self::get_quota(db, &provider, true).await {
+                Ok(quota) => {
+                    let update = CodexQuotaUpdate {
+                        provider_id: provider.id.clone(),
+                        quota,
+                    };
+                    if let Err(e) = app.emit(CODEX_QUOTA_UPDATED_EVENT, &update) {
+                        log::error!("[CodexQuota] 发射额度更新事件失败: {e}");
+                    }
+                }
+                Err(e) => log::debug!("[CodexQuota] 后台刷新 {} 额度失败: {e}", provider.id),
+            }
+        }
+    }
+
+    fn is_fresh(cached: &CodexQuotaUsage, ttl_secs: u64, now: i64) -> bool {
+        let age = now - cached.fetched_at;
+        age >= 0 && (age as u64) < ttl_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_freshness_respects_ttl() {
+        let cached = CodexQuotaUsage {
+            fetched_at: 1_000,
+            ..Default::default()
+        };
+        assert!(CodexQuotaService::is_fresh(&cached, 300, 1_000));
+        assert!(CodexQuotaService::is_fresh(&cached, 300, 1_299));
+        assert!(!CodexQuotaService::is_fresh(&cached, 300, 1_300));
+        // 时钟回拨时视为过期，重新查询
+        assert!(!CodexQuotaService::is_fresh(&cached, 300, 999));
+    }
+
+    #[test]
+    fn quota_cache_round_trips_and_ttl_has_floor() {
+        let db = Database::memory().expect("create memory db");
+        assert_eq!(
+            db.get_codex_quota_ttl_secs().unwrap(),
+            DEFAULT_CODEX_QUOTA_TTL_SECS
+        );
+        assert_eq!(
+            db.set_codex_quota_ttl_secs(5).unwrap(),
+            MIN_CODEX_QUOTA_TTL_SECS
+        );
+        assert_eq!(
+            db.get_codex_quota_ttl_secs().unwrap(),
+            MIN_CODEX_QUOTA_TTL_SECS
+        );
+
+        assert!(db.get_codex_quota_cache("p1").unwrap().is_none());
+        let usage = CodexQuotaUsage {
+            plan_type: Some("plus".to_string()),
+            five_hour: Some(CodexQuotaWindow {
+                used_percent: 42,
+                limit_window_seconds: 18_000,
+                reset_at: 2_000,
+            }),
+            weekly: None,
+            fetched_at: 1_000,
+        };
+        db.set_codex_quota_cache("p1", &usage).unwrap();
+        let cached = db.get_codex_quota_cache("p1").unwrap().unwrap();
+        assert_eq!(cached.plan_type.as_deref(), Some("plus"));
+        assert_eq!(cached.five_hour.unwrap().used_percent, 42);
+        assert!(db.get_codex_quota_cache("p2").unwrap().is_none());
+
+        db.delete_provider("codex", "p1").unwrap();
+        assert!(db.get_codex_quota_cache("p1").unwrap().is_none());
+    }
+}
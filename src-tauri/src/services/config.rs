@@ -1,14 +1,32 @@
-use super::provider::{sanitize_claude_settings_for_live, ProviderService};
+use super::provider::ProviderService;
 use crate::app_config::{AppType, MultiAppConfig};
+use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
 use chrono::Utc;
+use serde::Serialize;
 use serde_json::Value;
 use std::fs;
 use std::path::Path;
 
 const MAX_BACKUPS: usize = 10;
 
+/// 数据库文件名及其 SQLite 附属文件（迁移时由快照替代，不直接复制）
+const DB_FILE_NAME: &str = "cc-switch.db";
+const DB_SIDECAR_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+/// 配置目录迁移结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDirRelocation {
+    pub old_path: String,
+    pub new_path: String,
+    /// 复制的文件数（含数据库快照）
+    pub copied_files: usize,
+    /// 需要重启应用后才会使用新目录
+    pub restart_required: bool,
+}
+
 /// 配置导入导出相关业务逻辑
 pub struct ConfigService;
 
@@ -38,6 +56,100 @@ impl ConfigService {
         Ok(backup_id)
     }
 
+    /// 将配置目录（数据库、日志、备份、Skills 等）复制到新目录
+    ///
+    /// 数据库通过 SQLite 在线备份生成一致性快照，其余文件原样复制；旧目录保持不变，
+    /// 调用方负责更新目录覆盖配置并提示重启。
+    pub fn relocate_config_dir(
+        db: &Database,
+        current_dir: &Path,
+        target_dir: &Path,
+    ) -> Result<ConfigDirRelocation, AppError> {
+        let current_abs = fs::canonicalize(current_dir).unwrap_or_else(|_| current_dir.into());
+        let target_abs = fs::canonicalize(target_dir).unwrap_or_else(|_| target_dir.into());
+        if target_abs == current_abs {
+            return Err(AppError::localized(
+                "config.relocate.same_dir",
+                "新目录与当前配置目录相同",
+                "The new directory is the same as the current config directory",
+            ));
+        }
+        if target_abs.starts_with(&current_abs) || target_dir.starts_with(current_dir) {
+            return Err(AppError::localized(
+                "config.relocate.nested_dir",
+                "新目录不能位于当前配置目录内",
+                "The new directory cannot be inside the current config directory",
+            ));
+        }
+
+        if target_dir.join(DB_FILE_NAME).exists() {
+            return Err(AppError::localized(
+                "config.relocate.target_has_db",
+                "新目录中已存在 cc-switch.db，为避免覆盖已中止迁移",
+                "cc-switch.db already exists in the new directory; aborted to avoid overwriting it",
+            ));
+        }
+
+        fs::create_dir_all(target_dir).map_err(|e| AppError::io(target_dir, e))?;
+        Self::ensure_writable(target_dir)?;
+
+        db.snapshot_to(&target_dir.join(DB_FILE_NAME))?;
+        let copied = Self::copy_config_entries(current_dir, target_dir)?;
+
+        log::info!(
+            "配置目录已复制: {} -> {}（{} 个文件）",
+            current_dir.display(),
+            target_dir.display(),
+            copied + 1
+        );
+
+        Ok(ConfigDirRelocation {
+            old_path: current_dir.to_string_lossy().to_string(),
+            new_path: target_dir.to_string_lossy().to_string(),
+            copied_files: copied + 1,
+            restart_required: true,
+        })
+    }
+
+    /// 写入并删除探测文件，确认目录可写
+    fn ensure_writable(dir: &Path) -> Result<(), AppError> {
+        let probe = dir.join(".cc-switch-write-test");
+        fs::write(&probe, b"ok").map_err(|e| AppError::IoContext {
+            context: format!("新目录不可写: {}", dir.display()),
+            source: e,
+        })?;
+        fs::remove_file(&probe).map_err(|e| AppError::io(&probe, e))?;
+        Ok(())
+    }
+
+    /// 递归复制目录内容（跳过数据库及其附属文件），返回复制的文件数
+    fn copy_config_entries(src: &Path, dest: &Path) -> Result<usize, AppError> {
+        let mut copied = 0;
+        for entry in fs::read_dir(src).map_err(|e| AppError::io(src, e))? {
+            let entry = entry.map_err(|e| AppError::io(src, e))?;
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if name_str == DB_FILE_NAME
+                || DB_SIDECAR_SUFFIXES
+                    .iter()
+                    .any(|suffix| name_str == format!("{DB_FILE_NAME}{suffix}"))
+            {
+                continue;
+            }
+
+            let path = entry.path();
+            let dest_path = dest.join(&name);
+            if path.is_dir() {
+                fs::create_dir_all(&dest_path).map_err(|e| AppError::io(&dest_path, e))?;
+                copied += Self::copy_config_entries(&path, &dest_path)?;
+            } else {
+                crate::config::copy_file(&path, &dest_path)?;
+                copied += 1;
+            }
+        }
+        Ok(copied)
+    }
+
     fn cleanup_old_backups(backup_dir: &Path, retain: usize) -> Result<(), AppError> {
         if retain == 0 {
             return Ok(());
@@ -147,9 +259,13 @@ impl ConfigService {
                 "供应商 {provider_id} 的 Codex auth 配置必须是 JSON 对象"
             )));
         }
-        let cfg_text = settings.get("config").and_then(Value::as_str);
+        let cfg_text = settings
+            .get("config")
+            .and_then(Value::as_str)
+            .map(crate::codex_config::merge_with_live_config)
+            .transpose()?;
 
-        crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
+        crate::codex_config::write_codex_live_atomic(auth, cfg_text.as_deref())?;
         // 注意：MCP 同步在 v3.7.0 中已通过 McpService 进行，不再在此调用
         // sync_enabled_to_codex 使用旧的 config.mcp.codex 结构，在新架构中为空
         // MCP 的启用/禁用应通过 McpService::toggle_app 进行
@@ -174,17 +290,19 @@ impl ConfigService {
         provider_id: &str,
         provider: &Provider,
     ) -> Result<(), AppError> {
-        use crate::config::{read_json_file, write_json_file};
+        use crate::config::read_json_file;
+        use crate::services::provider::{get_claude_live_path_for, write_claude_live};
 
         let settings_path = crate::config::get_claude_settings_path();
         if let Some(parent) = settings_path.parent() {
             fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
         }
 
-        let settings = sanitize_claude_settings_for_live(&provider.settings_config);
-        write_json_file(&settings_path, &settings)?;
+        // 按写入模式同步（profiles 模式下写入该供应商的 profile）
+        write_claude_live(Some(provider_id), &provider.settings_config)?;
 
-        let live_after = read_json_file::<serde_json::Value>(&settings_path)?;
+        let live_after =
+            read_json_file::<serde_json::Value>(&get_claude_live_path_for(provider_id))?;
         if let Some(manager) = config.get_manager_mut(&AppType::Claude) {
             if let Some(target) = manager.providers.get_mut(provider_id) {
                 target.settings_config = live_after;
@@ -225,3 +343,60 @@ impl ConfigService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn relocate_copies_snapshot_and_files() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let root = tempdir().expect("tempdir");
+        let current = root.path().join("current");
+        fs::create_dir_all(current.join("logs")).expect("create logs");
+        fs::write(current.join(DB_FILE_NAME), b"stale").expect("write db");
+        fs::write(current.join("cc-switch.db-wal"), b"wal").expect("write wal");
+        fs::write(current.join("logs").join("cc-switch.log"), b"log").expect("write log");
+        fs::write(current.join("crash.log"), b"crash").expect("write crash log");
+
+        let target = root.path().join("moved");
+        let result = ConfigService::relocate_config_dir(&db, &current, &target)?;
+
+        assert_eq!(result.copied_files, 3);
+        assert!(result.restart_required);
+        assert!(target.join(DB_FILE_NAME).exists());
+        assert!(!target.join("cc-switch.db-wal").exists());
+        assert_eq!(
+            fs::read(target.join("logs").join("cc-switch.log")).expect("read log"),
+            b"log"
+        );
+        // 数据库为快照而非原文件
+        assert_ne!(
+            fs::read(target.join(DB_FILE_NAME)).expect("read db"),
+            b"stale"
+        );
+        // 旧目录保持不变
+        assert!(current.join("crash.log").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn relocate_rejects_same_nested_or_occupied_dir() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let root = tempdir().expect("tempdir");
+        let current = root.path().join("current");
+        fs::create_dir_all(&current).expect("create current");
+
+        assert!(ConfigService::relocate_config_dir(&db, &current, &current).is_err());
+        assert!(
+            ConfigService::relocate_config_dir(&db, &current, &current.join("nested")).is_err()
+        );
+
+        let occupied = root.path().join("occupied");
+        fs::create_dir_all(&occupied).expect("create occupied");
+        fs::write(occupied.join(DB_FILE_NAME), b"db").expect("write db");
+        assert!(ConfigService::relocate_config_dir(&db, &current, &occupied).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! 用量展示货币换算
+//!
+//! 数据库中的成本始终以 USD 十进制字符串存储；这里只负责把返回给前端的金额
+//! 额外换算为展示货币，不修改任何已存储的值。
+
+use crate::error::AppError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 公共汇率接口（以 USD 为基准）
+const EXCHANGE_RATE_URL: &str = "https://open.er-api.com/v6/latest/USD";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 换算后的展示金额（原始 USD 金额仍保留在原字段中）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayCost {
+    /// ISO 4217 货币代码，如 "EUR"
+    pub currency: String,
+    pub amount: String,
+}
+
+/// 展示货币换算规则：金额 × 汇率（1 USD 可兑换的展示货币数量）
+#[derive(Debug, Clone)]
+pub struct CurrencyConversion {
+    currency: String,
+    rate: Decimal,
+}
+
+impl CurrencyConversion {
+    /// 货币代码无效、为 USD 或汇率不是正数时返回 None（只展示 USD）
+    pub fn new(currency: &str, rate: &str) -> Option<Self> {
+        let currency = normalize_currency_code(currency)?;
+        if currency == "USD" {
+            return None;
+        }
+        let rate = Decimal::from_str(rate.trim()).ok()?;
+        if rate <= Decimal::ZERO {
+            return None;
+        }
+        Some(Self { currency, rate })
+    }
+
+    /// 根据 display_currency / usd_exchange_rate 设置构建
+    pub fn from_settings() -> Option<Self> {
+        let settings = crate::settings::get_settings();
+        Self::new(
+            settings.display_currency.as_deref()?,
+            settings.usd_exchange_rate.as_deref()?,
+        )
+    }
+
+    /// 换算 USD 金额字符串；无法解析时返回 None
+    pub fn convert(&self, usd: &str) -> Option<DisplayCost> {
+        let usd = Decimal::from_str(usd.trim()).ok()?;
+        Some(DisplayCost {
+            currency: self.currency.clone(),
+            amount: format!("{:.6}", (usd * self.rate).round_dp(6)),
+        })
+    }
+}
+
+/// 规范化货币代码：去空白、转大写，必须为 3 位字母
+pub fn normalize_currency_code(raw: &str) -> Option<String> {
+    let code = raw.trim().to_ascii_uppercase();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())).then_some(code)
+}
+
+pub struct CurrencyService;
+
+impl CurrencyService {
+    /// 从公共汇率接口获取 1 USD 兑换指定货币的汇率
+    ///
+    /// 只返回汇率，不写入设置；是否采用由调用方决定（用户也可手动填写汇率）。
+    pub async fn fetch_usd_rate(currency: &str) -> Result<String, AppError> {
+        let code = normalize_currency_code(currency).ok_or_else(|| {
+            AppError::localized(
+                "currency.invalid_code",
+                format!("无效的货币代码: {currency}"),
+                format!("Invalid currency code: {currency}"),
+            )
+        })?;
+
+        let response = crate::proxy::http_client::get()
+            .get(EXCHANGE_RATE_URL)
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("获取汇率失败: {e}")))?;
+        if !response.status().is_success() {
+            return Err(AppError::Message(format!(
+                "获取汇率失败: HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Message(format!("获取汇率失败: {e}")))?;
+
+        let rate = Self::parse_rate_response(&body, &code)?;
+        log::info!("已获取汇率: 1 USD = {rate} {code}");
+        Ok(rate.normalize().to_string())
+    }
+
+    /// 解析 `{"rates": {"EUR": 0.92, ...}}` 形式的响应
+    fn parse_rate_response(body: &[u8], code: &str) -> Result<Decimal, AppError> {
+        let not_found = || {
+            AppError::localized(
+                "currency.rate_not_found",
+                format!("汇率接口未返回 {code} 的汇率"),
+                format!("Exchange rate for {code} not found in response"),
+            )
+        };
+        let json: Value = serde_json::from_slice(body)
+            .map_err(|e| AppError::Message(format!("解析汇率响应失败: {e}")))?;
+        let rate = json
+            .get("rates")
+            .and_then(|rates| rates.get(code))
+            .filter(|value| value.is_number())
+            .ok_or_else(not_found)?;
+        Decimal::from_str(&rate.to_string())
+            .or_else(|_| Decimal::from_scientific(&rate.to_string()))
+            .ok()
+            .filter(|rate| *rate > Decimal::ZERO)
+            .ok_or_else(not_found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_keeps_precision_and_rejects_invalid_settings() {
+        let conversion = CurrencyConversion::new(" eur ", "0.92").unwrap();
+        assert_eq!(
+            conversion.convert("1.234567"),
+            Some(DisplayCost {
+                currency: "EUR".to_string(),
+                amount: "1.135802".to_string(),
+            })
+        );
+        assert!(conversion.convert("not-a-number").is_none());
+
+        assert!(CurrencyConversion::new("USD", "1").is_none());
+        assert!(CurrencyConversion::new("EURO", "0.92").is_none());
+        assert!(CurrencyConversion::new("EUR", "0").is_none());
+        assert!(CurrencyConversion::new("EUR", "abc").is_none());
+    }
+
+    #[test]
+    fn parse_rate_response_reads_requested_currency() {
+        let body =
+            br#"{"result":"success","base_code":"USD","rates":{"USD":1,"EUR":0.9215,"JPY":151.3}}"#;
+        assert_eq!(
+            CurrencyService::parse_rate_response(body, "EUR").unwrap(),
+            Decimal::from_str("0.9215").unwrap()
+        );
+        assert_eq!(
+            CurrencyService::parse_rate_response(body, "JPY").unwrap(),
+            Decimal::from_str("151.3").unwrap()
+        );
+        assert!(CurrencyService::parse_rate_response(body, "GBP").is_err());
+        assert!(CurrencyService::parse_rate_response(b"<html>", "EUR").is_err());
+    }
+}
@@ -1,8 +1,9 @@
-use super::env_checker::EnvConflict;
+use super::env_checker::{check_env_conflicts, EnvConflict};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(target_os = "windows")]
 use winreg::enums::*;
@@ -17,6 +18,125 @@ pub struct BackupInfo {
     pub conflicts: Vec<EnvConflict>,
 }
 
+/// 自动注释掉的行使用的前缀，`restore_from_backup` 据此取消注释
+const DISABLED_MARKER: &str = "# [cc-switch] ";
+
+/// Result of `auto_resolve_env_conflicts`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvAutoResolveReport {
+    /// 已在 shell 配置文件中注释掉的变量
+    pub resolved: Vec<EnvConflict>,
+    /// 无法自动处理的变量（进程/系统环境变量、主目录以外的配置文件）
+    pub skipped: Vec<EnvConflict>,
+    /// 本次修改前的备份（无修改时为空），可通过 `restore_env_backup` 撤销
+    pub backup: Option<BackupInfo>,
+}
+
+/// Comment out shell-profile exports that would override the app's managed config
+///
+/// 只处理用户主目录下的 shell 配置文件；修改前先备份。已注释的行不会再被检测到，
+/// 因此重复执行不会产生新的修改。
+pub fn auto_resolve_env_conflicts(app: &str) -> Result<EnvAutoResolveReport, String> {
+    let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
+    let (resolvable, skipped): (Vec<EnvConflict>, Vec<EnvConflict>) =
+        check_env_conflicts(app)?.into_iter().partition(|conflict| {
+            conflict.source_type == "file"
+                && parse_file_source(&conflict.source_path)
+                    .is_some_and(|(path, _)| Path::new(path).starts_with(&home))
+        });
+
+    if resolvable.is_empty() {
+        return Ok(EnvAutoResolveReport {
+            resolved: Vec::new(),
+            skipped,
+            backup: None,
+        });
+    }
+
+    let backup_info = create_backup(&resolvable)?;
+
+    let mut by_file: BTreeMap<&str, Vec<(usize, &str)>> = BTreeMap::new();
+    for conflict in &resolvable {
+        if let Some((path, line)) = parse_file_source(&conflict.source_path) {
+            by_file
+                .entry(path)
+                .or_default()
+                .push((line, conflict.var_name.as_str()));
+        }
+    }
+    for (file_path, targets) in by_file {
+        let content =
+            fs::read_to_string(file_path).map_err(|e| format!("读取文件失败 {file_path}: {e}"))?;
+        fs::write(file_path, comment_out_lines(&content, &targets)).map_err(|e| {
+            format!(
+                "写入文件失败 {file_path}: {e}. 备份已保存到: {}",
+                backup_info.backup_path
+            )
+        })?;
+    }
+
+    log::info!(
+        "已注释 {} 个与 {app} 冲突的环境变量，备份: {}",
+        resolvable.len(),
+        backup_info.backup_path
+    );
+    Ok(EnvAutoResolveReport {
+        resolved: resolvable,
+        skipped,
+        backup: Some(backup_info),
+    })
+}
+
+/// 解析 `path:line` 格式的来源（行号从 1 开始）
+fn parse_file_source(source_path: &str) -> Option<(&str, usize)> {
+    let (path, line) = source_path.rsplit_once(':')?;
+    Some((path, line.parse().ok()?))
+}
+
+/// 判断一行是否为 `VAR=...` 或 `export VAR=...`
+fn line_sets_var(line: &str, var_name: &str) -> bool {
+    let trimmed = line.trim();
+    let assignment = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+    assignment
+        .split_once('=')
+        .is_some_and(|(name, _)| name.trim() == var_name)
+}
+
+/// 在指定行前加上 [`DISABLED_MARKER`]；行内容已不再设置该变量时跳过（文件在检测后被修改）
+fn comment_out_lines(content: &str, targets: &[(usize, &str)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    for &(line_num, var_name) in targets {
+        let Some(line) = line_num.checked_sub(1).and_then(|i| lines.get_mut(i)) else {
+            continue;
+        };
+        if line_sets_var(line, var_name) {
+            *line = format!("{DISABLED_MARKER}{line}");
+        }
+    }
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// 取消第一处被自动注释的该变量设置，未找到时返回 None
+#[cfg(not(target_os = "windows"))]
+fn uncomment_disabled_line(content: &str, var_name: &str) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let index = lines.iter().position(|line| {
+        line.strip_prefix(DISABLED_MARKER)
+            .is_some_and(|rest| line_sets_var(rest, var_name))
+    })?;
+    lines[index] = &lines[index][DISABLED_MARKER.len()..];
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
 /// Delete environment variables with automatic backup
 pub fn delete_env_vars(conflicts: Vec<EnvConflict>) -> Result<BackupInfo, String> {
     // Step 1: Create backup
@@ -212,6 +332,13 @@ fn restore_single_env(conflict: &EnvConflict) -> Result<(), String> {
             let mut content = fs::read_to_string(file_path)
                 .map_err(|e| format!("读取文件失败 {file_path}: {e}"))?;
 
+            // Lines disabled by auto-resolve are simply uncommented in place
+            if let Some(restored) = uncomment_disabled_line(&content, &conflict.var_name) {
+                fs::write(file_path, restored)
+                    .map_err(|e| format!("写入文件失败 {file_path}: {e}"))?;
+                return Ok(());
+            }
+
             // Append the environment variable line
             let export_line = format!("\nexport {}={}", conflict.var_name, conflict.var_value);
             content.push_str(&export_line);
@@ -237,4 +364,37 @@ mod tests {
         let backup_dir = get_backup_dir();
         assert!(backup_dir.is_ok());
     }
+
+    #[test]
+    fn test_comment_out_lines_only_touches_matching_vars() {
+        let content =
+            "export PATH=/bin\nexport ANTHROPIC_BASE_URL=https://x\n  ANTHROPIC_MODEL=m\n";
+        let updated = comment_out_lines(
+            content,
+            &[
+                (2, "ANTHROPIC_BASE_URL"),
+                (3, "ANTHROPIC_MODEL"),
+                (1, "ANTHROPIC_API_KEY"),
+            ],
+        );
+        assert_eq!(
+            updated,
+            "export PATH=/bin\n# [cc-switch] export ANTHROPIC_BASE_URL=https://x\n# [cc-switch]   ANTHROPIC_MODEL=m\n"
+        );
+        // 行号越界时忽略
+        assert_eq!(comment_out_lines(content, &[(10, "PATH")]), content);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_uncomment_restores_original_line() {
+        let original = "export ANTHROPIC_BASE_URL=https://x\nexport PATH=/bin";
+        let disabled = comment_out_lines(original, &[(1, "ANTHROPIC_BASE_URL")]);
+        assert_eq!(
+            uncomment_disabled_line(&disabled, "ANTHROPIC_BASE_URL").as_deref(),
+            Some(original)
+        );
+        assert!(uncomment_disabled_line(&disabled, "ANTHROPIC_MODEL").is_none());
+        assert!(uncomment_disabled_line(original, "ANTHROPIC_BASE_URL").is_none());
+    }
 }
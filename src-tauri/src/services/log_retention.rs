@@ -0,0 +1,246 @@
+//! 请求日志保留策略
+//!
+//! 按 `LogRetentionConfig` 定期清理 proxy_request_logs，避免数据库无限增长。
+//! 清理按批次进行（每批一个事务），可选在删除前归档为 gzip 压缩的 JSONL 文件。
+
+use crate::config::get_app_config_dir;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::proxy::types::LogRetentionConfig;
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 每个事务删除的最大行数
+const PRUNE_BATCH_SIZE: usize = 10_000;
+
+/// 自动清理间隔
+const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 启动后首次自动清理的延迟，避免与启动阶段的导入/迁移争用数据库
+const PRUNE_STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// 单次清理结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneResult {
+    /// 删除的行数
+    pub deleted_rows: u64,
+    /// 归档的行数
+    pub archived_rows: u64,
+    /// 归档文件路径（未归档时为 None）
+    pub archive_path: Option<String>,
+    /// 是否因导出进行中而跳过
+    pub skipped: bool,
+}
+
+pub struct LogRetentionService;
+
+impl LogRetentionService {
+    /// 按当前保留策略清理请求日志
+    pub fn prune(db: &Database) -> Result<PruneResult, AppError> {
+        let config = db.get_log_retention_config()?;
+        Self::prune_with_config(db, &config)
+    }
+
+    /// 按指定保留策略清理请求日志
+    pub fn prune_with_config(
+        db: &Database,
+        config: &LogRetentionConfig,
+    ) -> Result<PruneResult, AppError> {
+        if crate::database::is_export_in_progress() {
+            log::info!("[LogRetention] 导出进行中，跳过本次请求日志清理");
+            return Ok(PruneResult {
+                skipped: true,
+                ..Default::default()
+            });
+        }
+
+        let Some(cutoff) = Self::resolve_cutoff(db, config)? else {
+            log::debug!("[LogRetention] 未配置保留策略或无需清理");
+            return Ok(PruneResult::default());
+        };
+
+        let mut result = PruneResult::default();
+        let mut archive: Option<(PathBuf, GzEncoder<fs::File>)> = None;
+
+        loop {
+            // 每批之间重新检查，导出开始后立即停止
+            if crate::database::is_export_in_progress() {
+                log::info!("[LogRetention] 检测到导出开始，提前结束清理");
+                result.skipped = true;
+                break;
+            }
+
+            // 归档在删除事务提交前写入并落盘，归档失败时本批不删除
+            let (deleted, rows) = db.delete_request_logs_batch_with(
+                cutoff,
+                PRUNE_BATCH_SIZE,
+                config.archive_before_prune,
+                |rows| {
+                    if rows.is_empty() {
+                        return Ok(());
+                    }
+                    if archive.is_none() {
+                        archive = Some(Self::open_archive()?);
+                    }
+                    match archive.as_mut() {
+                        Some((path, encoder)) => Self::append_archive(path, encoder, rows),
+                        None => Ok(()),
+                    }
+                },
+            )?;
+            result.archived_rows += rows.len() as u64;
+
+            result.deleted_rows += deleted as u64;
+            if deleted < PRUNE_BATCH_SIZE {
+                break;
+            }
+        }
+
+        if let Some((path, encoder)) = archive {
+            encoder
+                .finish()
+                .and_then(|file| file.sync_all())
+                .map_err(|e| AppError::io(&path, e))?;
+            result.archive_path = Some(path.to_string_lossy().to_string());
+        }
+
+        if result.deleted_rows > 0 && config.incremental_vacuum {
+            if let Err(e) = db.incremental_vacuum() {
+                log::warn!("[LogRetention] {e}");
+            }
+        }
+
+        log::info!(
+            "[LogRetention] 清理完成: deleted={}, archived={}",
+            result.deleted_rows,
+            result.archived_rows
+        );
+        Ok(result)
+    }
+
+    /// 启动后台每日清理任务
+    pub fn spawn_daily_task(db: Arc<Database>) {
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(PRUNE_STARTUP_DELAY).await;
+            loop {
+                let db_for_task = db.clone();
+                match tauri::async_runtime::spawn_blocking(move || Self::prune(&db_for_task)).await
+                {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => log::warn!("[LogRetention] 自动清理请求日志失败: {e}"),
+                    Err(e) => log::warn!("[LogRetention] 自动清理任务异常: {e}"),
+                }
+                tokio::time::sleep(PRUNE_INTERVAL).await;
+            }
+        });
+    }
+
+    /// 综合保留天数与行数上限，计算清理截止时间（取两者中较晚的一个）
+    fn resolve_cutoff(db: &Database, config: &LogRetentionConfig) -> Result<Option<i64>, AppError> {
+        let age_cutoff = (config.log_retention_days > 0)
+            .then(|| Utc::now().timestamp() - i64::from(config.log_retention_days) * 24 * 60 * 60);
+        let row_cutoff = match config.max_log_rows {
+            Some(max_rows) => db.request_log_row_cutoff(max_rows)?,
+            None => None,
+        };
+
+        Ok(match (age_cutoff, row_cutoff) {
+            (Some(a), Some(r)) => Some(a.max(r)),
+            (a, r) => a.or(r),
+        })
+    }
+
+    /// 追加一批行到归档，并刷新压缩流、fsync 文件，确保删除前数据已落盘
+    fn append_archive(
+        path: &Path,
+        encoder: &mut GzEncoder<fs::File>,
+        rows: &[Value],
+    ) -> Result<(), AppError> {
+        for row in rows {
+            let line =
+                serde_json::to_string(row).map_err(|source| AppError::JsonSerialize { source })?;
+            encoder
+                .write_all(line.as_bytes())
+                .and_then(|_| encoder.write_all(b"\n"))
+                .map_err(|e| AppError::io(path, e))?;
+        }
+        encoder
+            .flush()
+            .and_then(|_| encoder.get_ref().sync_data())
+            .map_err(|e| AppError::io(path, e))
+    }
+
+    fn open_archive() -> Result<(PathBuf, GzEncoder<fs::File>), AppError> {
+        let dir = get_app_config_dir().join("archive");
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+
+        let base = format!("request_logs_{}", Utc::now().format("%Y%m%d_%H%M%S"));
+        let mut path = dir.join(format!("{base}.jsonl.gz"));
+        let mut counter = 1;
+        while path.exists() {
+            path = dir.join(format!("{base}_{counter}.jsonl.gz"));
+            counter += 1;
+        }
+
+        let file = fs::File::create(&path).map_err(|e| AppError::io(&path, e))?;
+        Ok((path, GzEncoder::new(file, Compression::default())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::lock_conn;
+    use rusqlite::params;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_prune_by_age_and_max_rows() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let now = Utc::now().timestamp();
+        {
+            let conn = lock_conn!(db.conn);
+            for (i, age_days) in [200i64, 100, 10, 5, 1].iter().enumerate() {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model, latency_ms, status_code, created_at
+                    ) VALUES (?1, 'p1', 'claude', 'm', 1, 200, ?2)",
+                    params![format!("r{i}"), now - age_days * 86_400],
+                )?;
+            }
+        }
+
+        let config = LogRetentionConfig {
+            log_retention_days: 90,
+            max_log_rows: Some(2),
+            archive_before_prune: false,
+            incremental_vacuum: true,
+        };
+        let result = LogRetentionService::prune_with_config(&db, &config)?;
+        assert_eq!(result.deleted_rows, 3);
+        assert!(!result.skipped);
+        assert_eq!(db.count_request_logs()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_prune_skipped_during_export() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let _guard = crate::database::ExportGuard::acquire();
+        let result = LogRetentionService::prune_with_config(&db, &LogRetentionConfig::default())?;
+        assert!(result.skipped);
+        Ok(())
+    }
+}
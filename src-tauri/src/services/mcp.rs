@@ -1,11 +1,53 @@
 use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::path::Path;
 
-use crate::app_config::{AppType, McpServer};
+use crate::app_config::{AppType, McpApps, McpProfile, McpServer};
 use crate::error::AppError;
 use crate::mcp;
 use crate::store::AppState;
 
+/// 导入时因重名而改名的服务器
+#[derive(Debug, Clone, Serialize)]
+pub struct McpRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// 从 Claude Desktop 配置导入的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClaudeDesktopImportResult {
+    /// 新增的服务器 ID
+    pub imported: Vec<String>,
+    /// 同名但配置不同、改名后导入的服务器
+    pub renamed: Vec<McpRename>,
+    /// 已存在且配置相同、未做改动的服务器
+    pub unchanged: Vec<String>,
+    /// 跳过的条目及原因（如 Desktop 不支持的远程服务器）
+    pub warnings: Vec<String>,
+}
+
+/// 导出到 Claude Desktop 配置的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClaudeDesktopExportResult {
+    pub path: String,
+    pub exported: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// 应用 MCP Profile 的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProfileApplyReport {
+    pub profile: String,
+    /// 启用状态发生变化的服务器 ID
+    pub changed: Vec<String>,
+    /// 同步失败的应用 -> 错误信息（数据库已更新，可稍后手动同步）
+    pub sync_errors: HashMap<String, Vec<String>>,
+}
+
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
 pub struct McpService;
 
@@ -163,6 +205,82 @@ impl McpService {
         Ok(())
     }
 
+    /// 获取所有 MCP Profile
+    pub fn get_profiles(state: &AppState) -> Result<Vec<McpProfile>, AppError> {
+        state.db.get_mcp_profiles()
+    }
+
+    /// 添加或更新 MCP Profile
+    pub fn upsert_profile(state: &AppState, mut profile: McpProfile) -> Result<(), AppError> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err(AppError::localized(
+                "mcp.profile.name_empty",
+                "MCP Profile 名称不能为空",
+                "MCP profile name cannot be empty",
+            ));
+        }
+        profile.pinned.sort();
+        profile.pinned.dedup();
+        state.db.save_mcp_profile(&profile)
+    }
+
+    /// 删除 MCP Profile
+    pub fn delete_profile(state: &AppState, name: &str) -> Result<bool, AppError> {
+        state.db.delete_mcp_profile(name)
+    }
+
+    /// 一键应用 MCP Profile
+    ///
+    /// 数据库中的启用状态在单个事务中更新；随后逐个应用同步 live 配置，
+    /// 某个应用同步失败时记录错误并继续处理其余应用。
+    pub fn apply_profile(state: &AppState, name: &str) -> Result<McpProfileApplyReport, AppError> {
+        let profile = state.db.get_mcp_profile(name)?.ok_or_else(|| {
+            AppError::localized(
+                "mcp.profile.not_found",
+                format!("MCP Profile 不存在: {name}"),
+                format!("MCP profile not found: {name}"),
+            )
+        })?;
+
+        let changed = state.db.apply_mcp_profile_flags(&profile)?;
+        let mut report = McpProfileApplyReport {
+            profile: profile.name.clone(),
+            changed: changed
+                .iter()
+                .map(|(_, server)| server.id.clone())
+                .collect(),
+            ..Default::default()
+        };
+
+        for app in AppType::all() {
+            let mut errors = Vec::new();
+            for (previous, server) in &changed {
+                let was_enabled = previous.is_enabled_for(&app);
+                let enabled = server.apps.is_enabled_for(&app);
+                let result = match (was_enabled, enabled) {
+                    (false, true) => Self::sync_server_to_app(state, server, &app),
+                    (true, false) => Self::remove_server_from_app(state, &server.id, &app),
+                    _ => continue,
+                };
+                if let Err(e) = result {
+                    log::warn!(
+                        "应用 MCP Profile '{}' 时同步 {} 到 {} 失败: {e}",
+                        profile.name,
+                        server.id,
+                        app.as_str()
+                    );
+                    errors.push(format!("{}: {e}", server.id));
+                }
+            }
+            if !errors.is_empty() {
+                report.sync_errors.insert(app.as_str().to_string(), errors);
+            }
+        }
+
+        Ok(report)
+    }
+
     // ========================================================================
     // 兼容层：支持旧的 v3.6.x 命令（已废弃，将在 v4.0 移除）
     // ========================================================================
@@ -362,4 +480,132 @@ impl McpService {
 
         Ok(new_count)
     }
+
+    /// 从 Claude Desktop 配置（`mcpServers` 对象）导入 MCP 服务器
+    ///
+    /// 同名且配置相同的条目视为已存在；同名但配置不同的条目改名为 `name-2` 等后导入。
+    /// 新服务器默认仅启用 Claude。
+    pub fn import_from_claude_desktop(
+        state: &AppState,
+        path: &Path,
+    ) -> Result<ClaudeDesktopImportResult, AppError> {
+        let config: Value = crate::config::read_json_file(path)?;
+        let (servers, warnings) = mcp::parse_desktop_servers(&config)?;
+
+        let mut result = ClaudeDesktopImportResult {
+            warnings,
+            ..Default::default()
+        };
+        let mut existing = state.db.get_all_mcp_servers()?;
+
+        for (name, spec) in servers {
+            let same_name = existing.values().find(|s| s.id == name || s.name == name);
+            if let Some(current) = same_name {
+                if mcp::convert_to_desktop_entry(&current.server).ok()
+                    == mcp::convert_to_desktop_entry(&spec).ok()
+                {
+                    result.unchanged.push(current.id.clone());
+                    continue;
+                }
+            }
+
+            let id = mcp::unique_name(&name, |candidate| {
+                existing
+                    .values()
+                    .any(|s| s.id == candidate || s.name == candidate)
+            });
+            if id != name {
+                result.renamed.push(McpRename {
+                    from: name.clone(),
+                    to: id.clone(),
+                });
+            }
+
+            let server = McpServer {
+                id: id.clone(),
+                name: id.clone(),
+                server: spec,
+                apps: McpApps {
+                    claude: true,
+                    codex: false,
+                    gemini: false,
+                    opencode: false,
+                },
+                description: None,
+                homepage: None,
+                docs: None,
+                tags: Vec::new(),
+            };
+            Self::upsert_server(state, server.clone())?;
+            existing.insert(id.clone(), server);
+            result.imported.push(id);
+        }
+
+        for warning in &result.warnings {
+            log::warn!("[MCP] Claude Desktop 导入跳过: {warning}");
+        }
+        Ok(result)
+    }
+
+    /// 导出 MCP 服务器到 Claude Desktop 配置文件
+    ///
+    /// 保留文件中的其它字段与未导出的服务器；`server_ids` 为 None 时导出全部服务器。
+    /// 远程（sse/http）服务器 Desktop 不支持，跳过并记录警告。
+    pub fn export_to_claude_desktop(
+        state: &AppState,
+        path: &Path,
+        server_ids: Option<&[String]>,
+    ) -> Result<ClaudeDesktopExportResult, AppError> {
+        crate::settings::ensure_live_writable()?;
+
+        let all = state.db.get_all_mcp_servers()?;
+        let selected: Vec<&McpServer> = match server_ids {
+            Some(ids) => ids.iter().filter_map(|id| all.get(id)).collect(),
+            None => all.values().collect(),
+        };
+
+        let mut result = ClaudeDesktopExportResult {
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        if let Some(ids) = server_ids {
+            for id in ids.iter().filter(|id| !all.contains_key(*id)) {
+                result
+                    .warnings
+                    .push(format!("{id}: MCP 服务器不存在，已跳过"));
+            }
+        }
+
+        let mut config: Value = if path.exists() {
+            crate::config::read_json_file(path)?
+        } else {
+            json!({})
+        };
+        let root = config.as_object_mut().ok_or_else(|| {
+            AppError::McpValidation("Claude Desktop 配置文件根节点必须为 JSON 对象".into())
+        })?;
+        let servers = root
+            .entry("mcpServers")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| AppError::McpValidation("mcpServers 必须为 JSON 对象".into()))?;
+
+        for server in selected {
+            match mcp::convert_to_desktop_entry(&server.server) {
+                Ok(entry) => {
+                    servers.insert(server.id.clone(), entry);
+                    result.exported.push(server.id.clone());
+                }
+                Err(e) => result.warnings.push(format!("{}: {e}，已跳过", server.id)),
+            }
+        }
+
+        crate::config::write_json_file(path, &config)?;
+        log::info!(
+            "[MCP] 已导出 {} 个服务器到 Claude Desktop 配置: {}",
+            result.exported.len(),
+            path.display()
+        );
+        Ok(result)
+    }
 }
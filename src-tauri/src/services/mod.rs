@@ -1,26 +1,46 @@
 pub mod antigravity;
 pub mod codex_cache;
+pub mod codex_quota;
 pub mod config;
+pub mod currency;
 pub mod env_checker;
 pub mod env_manager;
+pub mod log_retention;
 pub mod mcp;
+pub mod onboarding;
+pub mod preset_catalog;
 pub mod prompt;
 pub mod provider;
+pub mod provider_icon;
+pub mod provider_metadata;
 pub mod proxy;
+pub mod settings_transfer;
 pub mod skill;
 pub mod speedtest;
 pub mod stream_check;
+pub mod stream_check_scheduler;
+pub mod sync;
 pub mod thread_memory;
 pub mod usage_stats;
 
 pub use config::ConfigService;
+pub use log_retention::LogRetentionService;
 pub use mcp::McpService;
+pub use onboarding::OnboardingService;
+pub use preset_catalog::{PresetCatalogService, PresetCatalogStatus};
 pub use prompt::PromptService;
-pub use provider::{ProviderService, ProviderSortUpdate};
+pub use provider::{
+    CredentialIssue, EnvImportResult, ExternalFormat, ExternalImportResult, OrphanedUniversalChild,
+    ProviderService, ProviderSortUpdate,
+};
+pub use provider_icon::{ProviderIconService, ResolvedProviderIcon};
+pub use provider_metadata::{ProviderMetadata, ProviderMetadataService};
 pub use proxy::ProxyService;
+pub use settings_transfer::{SettingsExport, SettingsTransferService};
 #[allow(unused_imports)]
 pub use skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
 pub use speedtest::{EndpointLatency, SpeedtestService};
+pub use sync::SyncService;
 #[allow(unused_imports)]
 pub use usage_stats::{
     DailyStats, LogFilters, ModelStats, PaginatedLogs, ProviderLimitStatus, ProviderStats,
@@ -0,0 +1,278 @@
+//! 首次运行引导
+//!
+//! 启动时从各应用已有的 Live 配置自动导入供应商、MCP 服务器与提示词，并将结果记录到
+//! `init_status`；随后在后台检测 CLI 安装情况生成引导报告，供前端展示 cc-switch 找到了什么。
+//! 用户安装缺失的 CLI 后可针对单个应用重新执行导入。
+
+use std::path::PathBuf;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::init_status::{self, AppImportOutcome, AppOnboardingStatus, OnboardingReport};
+use crate::services::mcp::McpService;
+use crate::services::prompt::PromptService;
+use crate::services::provider::{import_opencode_providers_from_live, ProviderService};
+use crate::services::SkillService;
+use crate::store::AppState;
+
+pub struct OnboardingService;
+
+impl OnboardingService {
+    /// 启动阶段的自动导入（各类数据独立判断，互不影响）
+    ///
+    /// - 供应商：该应用尚无供应商时从 Live 配置导入；
+    /// - MCP / 提示词：仅在对应表为空时导入。
+    pub fn run_initial_imports(state: &AppState) {
+        let import_mcp = state.db.is_mcp_table_empty().unwrap_or(false);
+        let import_prompts = state.db.is_prompts_table_empty().unwrap_or(false);
+        if import_mcp {
+            log::info!("MCP table empty, importing from live configurations...");
+        }
+        if import_prompts {
+            log::info!("Prompts table empty, importing from live configurations...");
+        }
+
+        for app_type in AppType::all() {
+            // OpenCode 的提示词文件不在首次导入范围内
+            let prompts = import_prompts && !matches!(app_type, AppType::OpenCode);
+            let outcome = Self::import_app(state, &app_type, import_mcp, prompts);
+            init_status::set_import_outcome(app_type.as_str(), outcome);
+        }
+    }
+
+    /// 针对单个应用重新执行导入（用户安装 CLI 后手动触发），返回更新后的检测结果
+    pub fn rerun_initial_import(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<AppOnboardingStatus, AppError> {
+        log::info!("重新执行 {} 的初始导入", app_type.as_str());
+        let outcome = Self::import_app(state, &app_type, true, true);
+        init_status::set_import_outcome(app_type.as_str(), outcome);
+
+        let status = Self::app_status(state, &app_type)?;
+        init_status::update_onboarding_app(status.clone());
+        Ok(status)
+    }
+
+    /// 获取引导报告：优先返回启动阶段生成的结果，尚未生成时立即生成
+    pub fn get_report(state: &AppState) -> Result<OnboardingReport, AppError> {
+        if let Some(report) = init_status::get_onboarding_report() {
+            return Ok(report);
+        }
+        Self::refresh_report(state)
+    }
+
+    /// 重新检测所有应用并保存报告（包含 CLI 检测，耗时较长，应在后台线程调用）
+    pub fn refresh_report(state: &AppState) -> Result<OnboardingReport, AppError> {
+        let apps = AppType::all()
+            .map(|app_type| Self::app_status(state, &app_type))
+            .collect::<Result<Vec<_>, _>>()?;
+        let report = OnboardingReport {
+            generated_at: chrono::Utc::now().timestamp_millis(),
+            apps,
+        };
+        init_status::set_onboarding_report(report.clone());
+        Ok(report)
+    }
+
+    /// 启动后在后台生成引导报告
+    pub fn spawn_report(app: tauri::AppHandle) {
+        tauri::async_runtime::spawn_blocking(move || {
+            use tauri::Manager;
+
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            match Self::refresh_report(&state) {
+                Ok(_) => log::debug!("首次运行引导报告已生成"),
+                Err(e) => log::warn!("生成首次运行引导报告失败: {e}"),
+            }
+        });
+    }
+
+    fn import_app(
+        state: &AppState,
+        app_type: &AppType,
+        import_mcp: bool,
+        import_prompts: bool,
+    ) -> AppImportOutcome {
+        let app = app_type.as_str();
+        let mut outcome = AppImportOutcome::default();
+
+        match Self::import_providers(state, app_type) {
+            Ok(count) => outcome.providers_imported = count,
+            Err(e) => {
+                log::debug!("○ No default provider to import for {app}: {e}");
+                outcome.provider_error = Some(e.to_string());
+            }
+        }
+
+        if import_mcp {
+            let result = match app_type {
+                AppType::Claude => McpService::import_from_claude(state),
+                AppType::Codex => McpService::import_from_codex(state),
+                AppType::Gemini => McpService::import_from_gemini(state),
+                AppType::OpenCode => McpService::import_from_opencode(state),
+            };
+            match result {
+                Ok(count) if count > 0 => {
+                    log::info!("✓ Imported {count} MCP server(s) from {app}");
+                    outcome.mcp_imported = count;
+                }
+                Ok(_) => log::debug!("○ No {app} MCP servers found to import"),
+                Err(e) => {
+                    log::warn!("✗ Failed to import {app} MCP: {e}");
+                    outcome.mcp_error = Some(e.to_string());
+                }
+            }
+        }
+
+        if import_prompts {
+            match PromptService::import_from_file_on_first_launch(state, app_type.clone()) {
+                Ok(count) if count > 0 => {
+                    log::info!("✓ Imported {count} prompt(s) for {app}");
+                    outcome.prompts_imported = count;
+                }
+                Ok(_) => log::debug!("○ No prompt file found for {app}"),
+                Err(e) => {
+                    log::warn!("✗ Failed to import prompt for {app}: {e}");
+                    outcome.prompts_error = Some(e.to_string());
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// 导入供应商，返回导入数量
+    fn import_providers(state: &AppState, app_type: &AppType) -> Result<usize, AppError> {
+        // OpenCode 为累加模式：配置文件中可同时存在多个供应商，逐个导入
+        if matches!(app_type, AppType::OpenCode) {
+            let count = import_opencode_providers_from_live(state)?;
+            if count > 0 {
+                log::info!("✓ Imported {count} OpenCode provider(s) from live config");
+            }
+            return Ok(count);
+        }
+
+        if !ProviderService::import_default_config(state, app_type.clone())? {
+            return Ok(0); // 已有供应商，跳过
+        }
+        log::info!("✓ Imported default provider for {}", app_type.as_str());
+        Self::extract_common_snippet_if_empty(state, app_type);
+        Ok(1)
+    }
+
+    /// 首次导入后自动提取通用配置片段（仅当通用配置为空时）
+    fn extract_common_snippet_if_empty(state: &AppState, app_type: &AppType) {
+        let app = app_type.as_str();
+        if state.db.get_config_snippet(app).ok().flatten().is_some() {
+            return;
+        }
+        match ProviderService::extract_common_config_snippet(state, app_type.clone()) {
+            Ok(snippet) if !snippet.is_empty() && snippet != "{}" => {
+                if let Err(e) = state.db.set_config_snippet(app, Some(snippet)) {
+                    log::warn!("✗ Failed to save common config snippet for {app}: {e}");
+                } else {
+                    log::info!("✓ Extracted common config snippet for {app}");
+                }
+            }
+            Ok(_) => log::debug!("○ No common config to extract for {app}"),
+            Err(e) => log::debug!("○ Failed to extract common config for {app}: {e}"),
+        }
+    }
+
+    fn app_status(state: &AppState, app_type: &AppType) -> Result<AppOnboardingStatus, AppError> {
+        let app = app_type.as_str();
+        let (cli_version, _) = crate::commands::detect_cli_version(app);
+        let live_config_path = live_config_path(app_type);
+        let live_config_exists = live_config_path.exists();
+
+        let mcp_servers = state
+            .db
+            .get_all_mcp_servers()?
+            .values()
+            .filter(|server| server.apps.is_enabled_for(app_type))
+            .count();
+        let prompt_file_exists = crate::prompt_files::prompt_file_path(app_type)
+            .map(|path| path.exists())
+            .unwrap_or(false);
+
+        let import = init_status::get_import_outcome(app);
+        Ok(AppOnboardingStatus {
+            app: app.to_string(),
+            cli_found: cli_version.is_some(),
+            cli_version,
+            live_config_path: live_config_path.to_string_lossy().to_string(),
+            live_config_exists,
+            provider_count: state.db.get_all_providers(app)?.len(),
+            mcp_servers,
+            prompt_file_exists,
+            skills_detected: count_skill_dirs(app_type),
+            errors: import_errors(&import, live_config_exists),
+            import,
+        })
+    }
+}
+
+/// 各应用的主 Live 配置文件
+fn live_config_path(app_type: &AppType) -> PathBuf {
+    match app_type {
+        AppType::Claude => crate::config::get_claude_settings_path(),
+        AppType::Codex => crate::codex_config::get_codex_auth_path(),
+        AppType::Gemini => crate::gemini_config::get_gemini_env_path(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_config_path(),
+    }
+}
+
+/// 应用 Skills 目录下的 Skill 数量（非隐藏子目录）
+fn count_skill_dirs(app_type: &AppType) -> usize {
+    let Ok(dir) = SkillService::get_app_skills_dir(app_type) else {
+        return 0;
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .count()
+}
+
+/// 汇总需要提示的导入错误：Live 配置不存在时供应商导入失败属于预期情况，不计入
+fn import_errors(outcome: &AppImportOutcome, live_config_exists: bool) -> Vec<String> {
+    let provider_error = outcome
+        .provider_error
+        .as_ref()
+        .filter(|_| live_config_exists);
+    [
+        provider_error,
+        outcome.mcp_error.as_ref(),
+        outcome.prompts_error.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .cloned()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_errors_ignore_missing_live_config() {
+        let outcome = AppImportOutcome {
+            provider_error: Some("Claude settings file is missing".to_string()),
+            mcp_error: Some("invalid mcp".to_string()),
+            ..AppImportOutcome::default()
+        };
+        assert_eq!(import_errors(&outcome, false), vec!["invalid mcp"]);
+        assert_eq!(
+            import_errors(&outcome, true),
+            vec!["Claude settings file is missing", "invalid mcp"]
+        );
+        assert!(import_errors(&AppImportOutcome::default(), true).is_empty());
+    }
+}
@@ -0,0 +1,351 @@
+//! 远程供应商预设目录
+//!
+//! 内置预设随版本发布，更新不及时。用户在设置中配置目录地址后（默认不配置，不发起任何请求），
+//! 本服务下载预设目录 JSON 及其 `.sig` 签名文件（base64 编码的 Ed25519 签名，对目录原始字节签名），
+//! 使用内置公钥校验后连同原始字节与签名缓存到 settings 表。读取缓存时重新校验签名并确认地址
+//! 未变更；签名或结构校验失败时拒绝该目录，继续使用已有缓存或内置预设。
+//! 前端按名称将远程预设覆盖到内置预设之上。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::provider_metadata::ProviderMetadataService;
+
+/// 预设目录签名公钥（Ed25519，base64）
+const PRESET_CATALOG_PUBLIC_KEY: &str = "oajO4WfrFysherWuUis3Ty4++9p56AY1cL3Apene0mQ=";
+/// 支持的目录结构版本
+const SUPPORTED_SCHEMA_VERSION: u32 = 1;
+/// 请求超时
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// 目录大小上限（2MB）
+const MAX_CATALOG_BYTES: usize = 2 * 1024 * 1024;
+/// 签名文件大小上限
+const MAX_SIGNATURE_BYTES: usize = 1024;
+/// 启动时自动刷新的缓存有效期（24 小时）
+const STARTUP_REFRESH_TTL_SECS: i64 = 24 * 60 * 60;
+/// settings 表缓存键
+const CACHE_KEY: &str = "preset_catalog_cache";
+
+/// 预设目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetCatalog {
+    pub schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// 应用 -> 预设列表（预设结构与前端 ProviderPreset 一致）
+    #[serde(default)]
+    pub presets: HashMap<String, Vec<Value>>,
+}
+
+/// 刷新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetCatalogStatus {
+    pub fetched_at: i64,
+    pub updated_at: Option<String>,
+    pub preset_count: usize,
+}
+
+/// 缓存内容：保存原始字节与签名，读取时重新校验，避免 settings 表被篡改后直接生效
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedCatalog {
+    fetched_at: i64,
+    url: String,
+    body: String,
+    signature: String,
+}
+
+pub struct PresetCatalogService;
+
+impl PresetCatalogService {
+    /// 下载、校验并缓存远程预设目录
+    ///
+    /// 未配置目录地址时返回错误；任一步骤失败都返回错误且不修改已有缓存。
+    pub async fn refresh(db: &Database) -> Result<PresetCatalogStatus, AppError> {
+        let url = crate::settings::get_preset_catalog_url().ok_or_else(|| {
+            AppError::localized(
+                "preset_catalog.not_configured",
+                "未配置远程预设目录地址",
+                "Remote preset catalog URL is not configured",
+            )
+        })?;
+        let client = crate::proxy::http_client::get();
+        let body = Self::download(&client, &url, MAX_CATALOG_BYTES).await?;
+        let signature = Self::download(&client, &format!("{url}.sig"), MAX_SIGNATURE_BYTES).await?;
+
+        Self::verify_signature(&body, &signature, &Self::public_key()?)?;
+        let catalog = Self::parse_catalog(&body)?;
+        let body = String::from_utf8(body).map_err(|e| {
+            AppError::localized(
+                "preset_catalog.invalid_schema",
+                format!("预设目录格式无效（{e}），已保留当前预设"),
+                format!("Invalid preset catalog ({e}); keeping current presets"),
+            )
+        })?;
+        let signature = String::from_utf8_lossy(&signature).trim().to_string();
+
+        let fetched_at = chrono::Utc::now().timestamp();
+        let status = PresetCatalogStatus {
+            fetched_at,
+            updated_at: catalog.updated_at.clone(),
+            preset_count: catalog.presets.values().map(Vec::len).sum(),
+        };
+        let cached = serde_json::to_string(&CachedCatalog {
+            fetched_at,
+            url: url.clone(),
+            body,
+            signature,
+        })
+        .map_err(|e| AppError::JsonSerialize { source: e })?;
+        db.set_setting(CACHE_KEY, &cached)?;
+
+        log::info!("已刷新远程预设目录: {} 个预设 ({url})", status.preset_count);
+        Ok(status)
+    }
+
+    /// 获取指定应用的远程预设
+    ///
+    /// 未配置地址、无缓存、缓存来自其他地址或签名校验失败时返回空列表，前端回退到内置预设。
+    pub fn get_presets(db: &Database, app_type: &AppType) -> Result<Vec<Value>, AppError> {
+        let Some(url) = crate::settings::get_preset_catalog_url() else {
+            return Ok(Vec::new());
+        };
+        Ok(Self::load_cache(db, &url, &Self::public_key()?)?
+            .and_then(|(_, catalog)| catalog.presets.get(app_type.as_str()).cloned())
+            .unwrap_or_default())
+    }
+
+    /// 启动时在后台刷新过期的目录，失败只记录日志，不阻塞启动；未配置地址时不发起请求
+    pub fn spawn_startup_refresh(db: Arc<Database>) {
+        let Some(url) = crate::settings::get_preset_catalog_url() else {
+            return;
+        };
+        tauri::async_runtime::spawn(async move {
+            let fresh = Self::public_key()
+                .and_then(|key| Self::load_cache(&db, &url, &key))
+                .ok()
+                .flatten()
+                .is_some_and(|(fetched_at, _)| {
+                    chrono::Utc::now().timestamp() - fetched_at < STARTUP_REFRESH_TTL_SECS
+                });
+            if fresh {
+                return;
+            }
+            if let Err(e) = Self::refresh(&db).await {
+                log::warn!("刷新远程预设目录失败，继续使用缓存或内置预设: {e}");
+            }
+        });
+    }
+
+    fn public_key() -> Result<Vec<u8>, AppError> {
+        base64::engine::general_purpose::STANDARD
+            .decode(PRESET_CATALOG_PUBLIC_KEY)
+            .map_err(|e| AppError::Message(format!("内置预设目录公钥无效: {e}")))
+    }
+
+    /// 读取缓存并重新校验：地址必须与当前设置一致，签名与结构必须有效，否则视为无缓存
+    fn load_cache(
+        db: &Database,
+        url: &str,
+        public_key: &[u8],
+    ) -> Result<Option<(i64, PresetCatalog)>, AppError> {
+        let Some(raw) = db.get_setting(CACHE_KEY)? else {
+            return Ok(None);
+        };
+        let cached = match serde_json::from_str::<CachedCatalog>(&raw) {
+            Ok(cached) => cached,
+            Err(e) => {
+                log::warn!("预设目录缓存损坏，已忽略: {e}");
+                return Ok(None);
+            }
+        };
+        if cached.url != url {
+            return Ok(None);
+        }
+        let verified = Self::verify_signature(
+            cached.body.as_bytes(),
+            cached.signature.as_bytes(),
+            public_key,
+        )
+        .and_then(|()| Self::parse_catalog(cached.body.as_bytes()));
+        match verified {
+            Ok(catalog) => Ok(Some((cached.fetched_at, catalog))),
+            Err(e) => {
+                log::warn!("预设目录缓存校验失败，已忽略: {e}");
+                Ok(None)
+            }
+        }
+    }
+
+    async fn download(
+        client: &reqwest::Client,
+        url: &str,
+        max_bytes: usize,
+    ) -> Result<Vec<u8>, AppError> {
+        let response = client
+            .get(url)
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("下载预设目录失败: {e}")))?;
+        if !response.status().is_success() {
+            return Err(AppError::Message(format!(
+                "下载预设目录失败: HTTP {} ({url})",
+                response.status().as_u16()
+            )));
+        }
+        ProviderMetadataService::read_limited(response, max_bytes).await
+    }
+
+    /// 校验目录原始字节的 Ed25519 签名（签名文件为 base64 文本）
+    fn verify_signature(body: &[u8], signature: &[u8], public_key: &[u8]) -> Result<(), AppError> {
+        let invalid = || {
+            AppError::localized(
+                "preset_catalog.invalid_signature",
+                "预设目录签名校验失败，已保留当前预设",
+                "Preset catalog signature verification failed; keeping current presets",
+            )
+        };
+        let signature = std::str::from_utf8(signature).map_err(|_| invalid())?;
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(signature.trim())
+            .map_err(|_| invalid())?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(body, &signature)
+            .map_err(|_| invalid())
+    }
+
+    /// 解析并校验目录结构：版本必须匹配，每个预设必须包含名称与 settingsConfig 对象
+    fn parse_catalog(body: &[u8]) -> Result<PresetCatalog, AppError> {
+        let invalid = |detail: String| {
+            AppError::localized(
+                "preset_catalog.invalid_schema",
+                format!("预设目录格式无效（{detail}），已保留当前预设"),
+                format!("Invalid preset catalog ({detail}); keeping current presets"),
+            )
+        };
+        let catalog: PresetCatalog =
+            serde_json::from_slice(body).map_err(|e| invalid(e.to_string()))?;
+        if catalog.schema_version != SUPPORTED_SCHEMA_VERSION {
+            return Err(invalid(format!(
+                "unsupported schemaVersion {}",
+                catalog.schema_version
+            )));
+        }
+        for (app, presets) in &catalog.presets {
+            for (index, preset) in presets.iter().enumerate() {
+                let has_name = preset
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .is_some_and(|name| !name.trim().is_empty());
+                let has_settings = preset.get("settingsConfig").is_some_and(Value::is_object);
+                if !has_name || !has_settings {
+                    return Err(invalid(format!("{app}[{index}]")));
+                }
+            }
+        }
+        Ok(catalog)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn sign(body: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).expect("generate key");
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).expect("parse key");
+        let signature =
+            base64::engine::general_purpose::STANDARD.encode(key_pair.sign(body).as_ref());
+        (
+            key_pair.public_key().as_ref().to_vec(),
+            signature.into_bytes(),
+        )
+    }
+
+    #[test]
+    fn signature_must_match_catalog_bytes() {
+        let body = br#"{"schemaVersion":1,"presets":{}}"#;
+        let (public_key, signature) = sign(body);
+
+        assert!(PresetCatalogService::verify_signature(body, &signature, &public_key).is_ok());
+
+        let tampered = br#"{"schemaVersion":1,"presets":{"claude":[]}}"#;
+        assert!(PresetCatalogService::verify_signature(tampered, &signature, &public_key).is_err());
+        assert!(PresetCatalogService::verify_signature(body, b"not base64!", &public_key).is_err());
+    }
+
+    #[test]
+    fn parse_catalog_rejects_schema_mismatch() {
+        let catalog = PresetCatalogService::parse_catalog(
+            br#"{"schemaVersion":1,"presets":{"claude":[{"name":"Relay","settingsConfig":{"env":{}}}]}}"#,
+        )
+        .expect("valid catalog");
+        assert_eq!(catalog.presets["claude"].len(), 1);
+
+        for body in [
+            br#"{"schemaVersion":2,"presets":{}}"#.as_slice(),
+            br#"{"schemaVersion":1,"presets":{"claude":[{"name":"Relay"}]}}"#,
+            br#"{"schemaVersion":1,"presets":{"codex":[{"name":" ","settingsConfig":{}}]}}"#,
+            br#"{"presets":{}}"#,
+        ] {
+            assert!(PresetCatalogService::parse_catalog(body).is_err());
+        }
+    }
+
+    #[test]
+    fn get_presets_falls_back_to_empty_without_cache() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        assert!(PresetCatalogService::get_presets(&db, &AppType::Claude)?.is_empty());
+
+        db.set_setting(CACHE_KEY, "not json")?;
+        assert!(PresetCatalogService::get_presets(&db, &AppType::Claude)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn load_cache_reverifies_signature_and_url() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let url = "https://example.com/catalog.json";
+        let body = r#"{"schemaVersion":1,"presets":{"claude":[{"name":"Relay","settingsConfig":{"env":{}}}]}}"#;
+        let (public_key, signature) = sign(body.as_bytes());
+        let store = |body: &str| {
+            let cached = serde_json::to_string(&CachedCatalog {
+                fetched_at: 1,
+                url: url.to_string(),
+                body: body.to_string(),
+                signature: String::from_utf8(signature.clone()).expect("utf8 signature"),
+            })
+            .expect("serialize cache");
+            db.set_setting(CACHE_KEY, &cached)
+        };
+
+        store(body)?;
+        let (_, catalog) =
+            PresetCatalogService::load_cache(&db, url, &public_key)?.expect("verified cache");
+        assert_eq!(catalog.presets["claude"].len(), 1);
+        assert!(PresetCatalogService::load_cache(
+            &db,
+            "https://other.example/catalog.json",
+            &public_key
+        )?
+        .is_none());
+
+        store(&body.replace("Relay", "Evil"))?;
+        assert!(PresetCatalogService::load_cache(&db, url, &public_key)?.is_none());
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+use std::path::Path;
+
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 use crate::app_config::AppType;
 use crate::config::write_text_file;
@@ -15,6 +18,206 @@ fn get_unix_timestamp() -> Result<i64, AppError> {
         .map_err(|e| AppError::Message(format!("Failed to get system time: {e}")))
 }
 
+/// Markdown 提示词的 YAML front-matter
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptFrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default)]
+    enabled: bool,
+}
+
+/// 拆分 front-matter 与正文；没有 front-matter 或格式错误时返回 None
+fn parse_prompt_markdown(text: &str) -> Option<(PromptFrontMatter, String)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            let body = body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body);
+            let front_matter = if yaml.trim().is_empty() {
+                PromptFrontMatter::default()
+            } else {
+                serde_yaml::from_str(yaml).ok()?
+            };
+            return Some((front_matter, body.to_string()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 生成带 front-matter 的 Markdown（与 `parse_prompt_markdown` 互逆）
+fn render_prompt_markdown(prompt: &Prompt) -> Result<String, AppError> {
+    let front_matter = PromptFrontMatter {
+        name: Some(prompt.name.clone()),
+        description: prompt.description.clone(),
+        enabled: prompt.enabled,
+    };
+    let yaml = serde_yaml::to_string(&front_matter)
+        .map_err(|e| AppError::Message(format!("生成提示词 front-matter 失败: {e}")))?;
+    Ok(format!("---\n{yaml}---\n\n{}", prompt.content))
+}
+
+/// live 文件中由 CC Switch 管理的区块起止标记，区块外的用户内容保持不变
+const MANAGED_BEGIN: &str = "<!-- cc-switch:managed-begin -->";
+const MANAGED_END: &str = "<!-- cc-switch:managed-end -->";
+/// 每个提示词分段的标题注释，用于将分段映射回提示词 ID
+const SEGMENT_HEADER_PREFIX: &str = "<!-- cc-switch:prompt id=\"";
+const SEGMENT_HEADER_SUFFIX: &str = "\" -->";
+/// 记录渲染区块时使用的分隔内容（JSON 字符串），分隔设置修改后仍能正确拆分旧区块
+const DELIMITER_HEADER_PREFIX: &str = "<!-- cc-switch:delimiter ";
+const DELIMITER_HEADER_SUFFIX: &str = " -->";
+
+/// live 文件中与某个提示词对应的分段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSegment {
+    pub prompt_id: String,
+    pub content: String,
+}
+
+/// 当前 live 提示词文件内容及受管分段
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFileContent {
+    pub content: String,
+    /// 受管区块中的分段（按文件中的顺序）；没有受管区块时为空
+    pub segments: Vec<PromptSegment>,
+}
+
+/// 按顺序将启用的提示词渲染为受管区块；没有启用的提示词时返回 None
+fn render_managed_block<'a>(
+    prompts: impl IntoIterator<Item = &'a Prompt>,
+    delimiter: &str,
+) -> Option<String> {
+    let segments: Vec<String> = prompts
+        .into_iter()
+        .map(|p| {
+            format!(
+                "{SEGMENT_HEADER_PREFIX}{}{SEGMENT_HEADER_SUFFIX}\n{}\n",
+                p.id,
+                p.content.trim_end()
+            )
+        })
+        .collect();
+    if segments.is_empty() {
+        return None;
+    }
+    // `>` 转义为 \u003e，避免分隔内容中的 `-->` 提前结束注释
+    let encoded = serde_json::to_string(delimiter)
+        .unwrap_or_default()
+        .replace('>', "\\u003e");
+    Some(format!(
+        "{MANAGED_BEGIN}\n{DELIMITER_HEADER_PREFIX}{encoded}{DELIMITER_HEADER_SUFFIX}\n{}{MANAGED_END}",
+        segments.join(delimiter)
+    ))
+}
+
+/// 定位受管区块，返回 (起始偏移, 结束偏移)，结束偏移位于结束标记之后
+fn find_managed_block(text: &str) -> Option<(usize, usize)> {
+    let begin = text.find(MANAGED_BEGIN)?;
+    let end = begin + text[begin..].find(MANAGED_END)? + MANAGED_END.len();
+    Some((begin, end))
+}
+
+/// 用新的受管区块替换 live 文件中的旧区块，区块外的内容保持不变
+///
+/// 没有区块标记时：若文件为空或内容与某个已知提示词一致（旧版单选模式写入的），
+/// 整体替换；否则视为用户内容，将区块追加到末尾。
+fn splice_managed_block(existing: &str, block: Option<&str>, known_contents: &[&str]) -> String {
+    if let Some((begin, end)) = find_managed_block(existing) {
+        let before = &existing[..begin];
+        let after = &existing[end..];
+        return match block {
+            Some(block) => format!("{before}{block}{after}"),
+            None => {
+                let after = after
+                    .strip_prefix("\r\n")
+                    .or_else(|| after.strip_prefix('\n'))
+                    .unwrap_or(after);
+                let rest = format!("{}{after}", before.trim_end_matches(['\r', '\n']));
+                if rest.trim().is_empty() {
+                    String::new()
+                } else if after.is_empty() {
+                    format!("{rest}\n")
+                } else {
+                    rest
+                }
+            }
+        };
+    }
+
+    let trimmed = existing.trim();
+    let legacy = trimmed.is_empty() || known_contents.iter().any(|c| c.trim() == trimmed);
+    match (block, legacy) {
+        (Some(block), true) => format!("{block}\n"),
+        (Some(block), false) => format!("{}\n\n{block}\n", existing.trim_end()),
+        (None, true) => String::new(),
+        (None, false) => existing.to_string(),
+    }
+}
+
+/// 去掉受管区块，只保留用户自己维护的内容
+fn strip_managed_block(text: &str) -> String {
+    splice_managed_block(text, None, &[])
+}
+
+/// 解析受管区块中的分段
+///
+/// 分隔内容优先取区块中记录的值（渲染时实际使用的分隔），旧版区块没有记录时使用 `delimiter`。
+fn parse_managed_segments(text: &str, delimiter: &str) -> Vec<PromptSegment> {
+    let Some((begin, end)) = find_managed_block(text) else {
+        return Vec::new();
+    };
+    let inner = &text[begin + MANAGED_BEGIN.len()..end - MANAGED_END.len()];
+
+    let mut recorded_delimiter: Option<String> = None;
+    let mut segments: Vec<PromptSegment> = Vec::new();
+    for line in inner.split_inclusive('\n') {
+        if segments.is_empty() && recorded_delimiter.is_none() {
+            recorded_delimiter = line
+                .trim_end()
+                .strip_prefix(DELIMITER_HEADER_PREFIX)
+                .and_then(|rest| rest.strip_suffix(DELIMITER_HEADER_SUFFIX))
+                .and_then(|encoded| serde_json::from_str(encoded).ok());
+        }
+        let header = line
+            .trim_end()
+            .strip_prefix(SEGMENT_HEADER_PREFIX)
+            .and_then(|rest| rest.strip_suffix(SEGMENT_HEADER_SUFFIX));
+        match (header, segments.last_mut()) {
+            (Some(id), _) => segments.push(PromptSegment {
+                prompt_id: id.to_string(),
+                content: String::new(),
+            }),
+            (None, Some(segment)) => segment.content.push_str(line),
+            (None, None) => {}
+        }
+    }
+
+    let delimiter = recorded_delimiter.as_deref().unwrap_or(delimiter);
+    for segment in &mut segments {
+        let content = segment
+            .content
+            .strip_suffix(delimiter)
+            .filter(|_| !delimiter.is_empty())
+            .unwrap_or(&segment.content);
+        segment.content = content.trim_end_matches(['\r', '\n']).to_string();
+    }
+    segments
+}
+
 pub struct PromptService;
 
 impl PromptService {
@@ -29,31 +232,70 @@ impl PromptService {
         state: &AppState,
         app: AppType,
         _id: &str,
-        prompt: Prompt,
+        mut prompt: Prompt,
     ) -> Result<(), AppError> {
-        // 检查是否为已启用的提示词
-        let is_enabled = prompt.enabled;
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let previous = prompts.get(&prompt.id);
+        if previous.is_none() {
+            // 新提示词排在最后
+            prompt.order_index = Self::next_order_index(&prompts);
+        }
+        let affects_live = prompt.enabled || previous.is_some_and(|p| p.enabled);
 
         state.db.save_prompt(app.as_str(), &prompt)?;
 
-        if is_enabled {
-            // 启用提示词：写入内容到文件
-            let target_path = prompt_file_path(&app)?;
-            write_text_file(&target_path, &prompt.content)?;
-        } else {
-            // 禁用提示词：检查是否还有其他已启用的提示词
-            let prompts = state.db.get_prompts(app.as_str())?;
-            let any_enabled = prompts.values().any(|p| p.enabled);
-
-            if !any_enabled {
-                // 所有提示词都已禁用，清空文件
-                let target_path = prompt_file_path(&app)?;
-                if target_path.exists() {
-                    write_text_file(&target_path, "")?;
-                }
-            }
+        if affects_live {
+            // 旧版文件可能仍是修改前的内容，同样视为由 CC Switch 写入
+            let previous_content = previous.map(|p| p.content.as_str());
+            Self::sync_live_file(state, &app, previous_content)?;
         }
+        Ok(())
+    }
+
+    /// 调整提示词顺序并重新渲染受管区块
+    pub fn reorder_prompts(state: &AppState, app: AppType, ids: &[String]) -> Result<(), AppError> {
+        state.db.reorder_prompts(app.as_str(), ids)?;
+        Self::sync_live_file(state, &app, None)
+    }
+
+    /// 下一个可用的排序序号（排在所有已有提示词之后）
+    fn next_order_index(prompts: &IndexMap<String, Prompt>) -> i64 {
+        prompts
+            .values()
+            .map(|p| p.order_index + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 将所有启用的提示词按顺序渲染到 live 文件的受管区块
+    ///
+    /// 只替换受管区块，区块外的用户内容保持不变；内容没有变化时不写文件。
+    /// `legacy_content` 为旧版（无区块标记）文件中可能残留的提示词内容。
+    fn sync_live_file(
+        state: &AppState,
+        app: &AppType,
+        legacy_content: Option<&str>,
+    ) -> Result<(), AppError> {
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let target_path = prompt_file_path(app)?;
+        let existing = if target_path.exists() {
+            std::fs::read_to_string(&target_path).map_err(|e| AppError::io(&target_path, e))?
+        } else {
+            String::new()
+        };
 
+        let delimiter = crate::settings::get_prompt_segment_delimiter();
+        let block = render_managed_block(prompts.values().filter(|p| p.enabled), &delimiter);
+        let known: Vec<&str> = prompts
+            .values()
+            .map(|p| p.content.as_str())
+            .chain(legacy_content)
+            .collect();
+        let updated = splice_managed_block(&existing, block.as_deref(), &known);
+
+        if updated != existing && (target_path.exists() || !updated.is_empty()) {
+            write_text_file(&target_path, &updated)?;
+        }
         Ok(())
     }
 
@@ -70,77 +312,90 @@ impl PromptService {
         Ok(())
     }
 
+    /// 启用提示词（可同时启用多个，按 order_index 顺序写入 live 文件）
+    ///
+    /// 写入前先回填 live 文件中被手动修改的内容：受管分段回填到对应的提示词；
+    /// 旧版（无受管区块）文件回填到唯一启用的提示词，或创建一次备份。
     pub fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
-        // 回填当前 live 文件内容到已启用的提示词，或创建备份
         let target_path = prompt_file_path(&app)?;
         if target_path.exists() {
             if let Ok(live_content) = std::fs::read_to_string(&target_path) {
-                if !live_content.trim().is_empty() {
-                    let mut prompts = state.db.get_prompts(app.as_str())?;
-
-                    // 尝试回填到当前已启用的提示词
-                    if let Some((enabled_id, enabled_prompt)) = prompts
-                        .iter_mut()
-                        .find(|(_, p)| p.enabled)
-                        .map(|(id, p)| (id.clone(), p))
-                    {
-                        let timestamp = get_unix_timestamp()?;
-                        enabled_prompt.content = live_content.clone();
-                        enabled_prompt.updated_at = Some(timestamp);
-                        log::info!("回填 live 提示词内容到已启用项: {enabled_id}");
-                        state.db.save_prompt(app.as_str(), enabled_prompt)?;
-                    } else {
-                        // 没有已启用的提示词，则创建一次备份（避免重复备份）
-                        let content_exists = prompts
-                            .values()
-                            .any(|p| p.content.trim() == live_content.trim());
-                        if !content_exists {
-                            let timestamp = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs() as i64;
-                            let backup_id = format!("backup-{timestamp}");
-                            let backup_prompt = Prompt {
-                                id: backup_id.clone(),
-                                name: format!(
-                                    "原始提示词 {}",
-                                    chrono::Local::now().format("%Y-%m-%d %H:%M")
-                                ),
-                                content: live_content,
-                                description: Some("自动备份的原始提示词".to_string()),
-                                enabled: false,
-                                created_at: Some(timestamp),
-                                updated_at: Some(timestamp),
-                            };
-                            log::info!("回填 live 提示词内容，创建备份: {backup_id}");
-                            state.db.save_prompt(app.as_str(), &backup_prompt)?;
-                        }
-                    }
-                }
+                Self::backfill_live_content(state, &app, &live_content)?;
             }
         }
 
-        // 启用目标提示词并写入文件
         let mut prompts = state.db.get_prompts(app.as_str())?;
+        let Some(prompt) = prompts.get_mut(id) else {
+            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+        };
+        if !prompt.enabled {
+            prompt.enabled = true;
+            state.db.save_prompt(app.as_str(), prompt)?;
+        }
+
+        Self::sync_live_file(state, &app, None)
+    }
 
-        for prompt in prompts.values_mut() {
-            prompt.enabled = false;
+    /// 将 live 文件中手动修改的内容回填到数据库
+    fn backfill_live_content(
+        state: &AppState,
+        app: &AppType,
+        live_content: &str,
+    ) -> Result<(), AppError> {
+        if live_content.trim().is_empty() {
+            return Ok(());
         }
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        let timestamp = get_unix_timestamp()?;
 
-        if let Some(prompt) = prompts.get_mut(id) {
-            prompt.enabled = true;
-            write_text_file(&target_path, &prompt.content)?; // 原子写入
-            state.db.save_prompt(app.as_str(), prompt)?;
-        } else {
-            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+        if find_managed_block(live_content).is_some() {
+            let delimiter = crate::settings::get_prompt_segment_delimiter();
+            for segment in parse_managed_segments(live_content, &delimiter) {
+                let Some(prompt) = prompts.get_mut(&segment.prompt_id) else {
+                    continue;
+                };
+                if prompt.enabled && prompt.content.trim_end() != segment.content.trim_end() {
+                    log::info!("回填 live 提示词分段到: {}", segment.prompt_id);
+                    prompt.content = segment.content;
+                    prompt.updated_at = Some(timestamp);
+                    state.db.save_prompt(app.as_str(), prompt)?;
+                }
+            }
+            return Ok(());
         }
 
-        // Save all prompts to disable others
-        for (_, prompt) in prompts.iter() {
-            state.db.save_prompt(app.as_str(), prompt)?;
+        let live_trimmed = live_content.trim();
+        if prompts.values().any(|p| p.content.trim() == live_trimmed) {
+            return Ok(());
         }
 
-        Ok(())
+        let mut enabled = prompts.values_mut().filter(|p| p.enabled);
+        if let (Some(enabled_prompt), None) = (enabled.next(), enabled.next()) {
+            // 旧版单选模式：回填到当前已启用的提示词
+            enabled_prompt.content = live_content.to_string();
+            enabled_prompt.updated_at = Some(timestamp);
+            log::info!("回填 live 提示词内容到已启用项: {}", enabled_prompt.id);
+            state.db.save_prompt(app.as_str(), enabled_prompt)?;
+            return Ok(());
+        }
+
+        // 无法确定归属，创建一次备份
+        let backup_id = format!("backup-{timestamp}");
+        let backup_prompt = Prompt {
+            id: backup_id.clone(),
+            name: format!(
+                "原始提示词 {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M")
+            ),
+            content: live_content.to_string(),
+            description: Some("自动备份的原始提示词".to_string()),
+            enabled: false,
+            order_index: Self::next_order_index(&prompts),
+            created_at: Some(timestamp),
+            updated_at: Some(timestamp),
+        };
+        log::info!("回填 live 提示词内容，创建备份: {backup_id}");
+        state.db.save_prompt(app.as_str(), &backup_prompt)
     }
 
     pub fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
@@ -152,6 +407,13 @@ impl PromptService {
 
         let content =
             std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
+        // 受管区块由已有提示词生成，只导入区块外的内容
+        let content = strip_managed_block(&content);
+        if content.trim().is_empty() {
+            return Err(AppError::Message(
+                "提示词文件中没有可导入的内容".to_string(),
+            ));
+        }
         let timestamp = get_unix_timestamp()?;
 
         let id = format!("imported-{timestamp}");
@@ -164,6 +426,7 @@ impl PromptService {
             content,
             description: Some("从现有配置文件导入".to_string()),
             enabled: false,
+            order_index: Self::next_order_index(&state.db.get_prompts(app.as_str())?),
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -172,14 +435,78 @@ impl PromptService {
         Ok(id)
     }
 
-    pub fn get_current_file_content(app: AppType) -> Result<Option<String>, AppError> {
+    /// 从 Markdown 文件导入提示词
+    ///
+    /// front-matter 中的 name / description / enabled 写入对应字段，正文作为内容；
+    /// front-matter 缺失或格式错误时整个文件作为内容，名称取文件名。
+    pub fn import_markdown(
+        state: &AppState,
+        app: AppType,
+        path: &Path,
+    ) -> Result<String, AppError> {
+        let text = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let fallback_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .filter(|stem| !stem.trim().is_empty())
+            .unwrap_or_else(|| "Imported Prompt".to_string());
+
+        let (front_matter, content) = match parse_prompt_markdown(&text) {
+            Some(parsed) => parsed,
+            None => {
+                log::info!("提示词文件没有有效的 front-matter，整体作为内容导入: {path:?}");
+                (PromptFrontMatter::default(), text)
+            }
+        };
+
+        let timestamp = get_unix_timestamp()?;
+        let id = format!("md-{timestamp}-{}", uuid::Uuid::new_v4().simple());
+        let prompt = Prompt {
+            id: id.clone(),
+            name: front_matter
+                .name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or(fallback_name),
+            content,
+            description: front_matter.description,
+            enabled: false,
+            order_index: Self::next_order_index(&state.db.get_prompts(app.as_str())?),
+            created_at: Some(timestamp),
+            updated_at: Some(timestamp),
+        };
+        state.db.save_prompt(app.as_str(), &prompt)?;
+
+        if front_matter.enabled {
+            Self::enable_prompt(state, app, &id)?;
+        }
+        Ok(id)
+    }
+
+    /// 将提示词导出为带 front-matter 的 Markdown 文件
+    pub fn export_markdown(
+        state: &AppState,
+        app: AppType,
+        id: &str,
+        path: &Path,
+    ) -> Result<(), AppError> {
+        let prompts = state.db.get_prompts(app.as_str())?;
+        let prompt = prompts
+            .get(id)
+            .ok_or_else(|| AppError::InvalidInput(format!("提示词 {id} 不存在")))?;
+        write_text_file(path, &render_prompt_markdown(prompt)?)
+    }
+
+    /// 读取当前 live 提示词文件，并解析受管区块中各分段对应的提示词 ID
+    pub fn get_current_file_content(app: AppType) -> Result<Option<PromptFileContent>, AppError> {
         let file_path = prompt_file_path(&app)?;
         if !file_path.exists() {
             return Ok(None);
         }
         let content =
             std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
-        Ok(Some(content))
+        let delimiter = crate::settings::get_prompt_segment_delimiter();
+        let segments = parse_managed_segments(&content, &delimiter);
+        Ok(Some(PromptFileContent { content, segments }))
     }
 
     /// 首次启动时从现有提示词文件自动导入（如果存在）
@@ -229,6 +556,7 @@ impl PromptService {
             content,
             description: Some("Automatically imported on first launch".to_string()),
             enabled: true, // 首次导入时自动启用
+            order_index: 0,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -240,3 +568,116 @@ impl PromptService {
         Ok(1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_round_trips_front_matter() {
+        let prompt = Prompt {
+            id: "p1".to_string(),
+            name: "Reviewer: strict".to_string(),
+            content: "\n# Rules\n\n- be concise\n".to_string(),
+            description: Some("code review".to_string()),
+            enabled: true,
+            order_index: 0,
+            created_at: None,
+            updated_at: None,
+        };
+        let markdown = render_prompt_markdown(&prompt).expect("render");
+        let (front_matter, content) = parse_prompt_markdown(&markdown).expect("parse");
+        assert_eq!(front_matter.name.as_deref(), Some("Reviewer: strict"));
+        assert_eq!(front_matter.description.as_deref(), Some("code review"));
+        assert!(front_matter.enabled);
+        assert_eq!(content, prompt.content);
+    }
+
+    #[test]
+    fn malformed_front_matter_is_rejected() {
+        let (front_matter, content) =
+            parse_prompt_markdown("---\r\nname: Windows\r\n---\r\nbody").expect("crlf");
+        assert_eq!(front_matter.name.as_deref(), Some("Windows"));
+        assert_eq!(content, "body");
+
+        assert!(parse_prompt_markdown("# Just markdown").is_none());
+        assert!(parse_prompt_markdown("---\nname: [unclosed\n---\nbody").is_none());
+        assert!(parse_prompt_markdown("---\nname: no end\nbody").is_none());
+    }
+
+    fn enabled_prompt(id: &str, content: &str, order_index: i64) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            enabled: true,
+            order_index,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn managed_block_preserves_user_content_and_maps_segments() {
+        let style = enabled_prompt("style", "# Style\n\n- short lines\n", 0);
+        let safety = enabled_prompt("safety", "Never push to main.", 1);
+        let delimiter = "---\n";
+        let block = render_managed_block([&style, &safety], delimiter).expect("block");
+
+        let existing = "# My notes\nkeep me\n";
+        let file = splice_managed_block(existing, Some(&block), &[]);
+        assert!(file.starts_with("# My notes\nkeep me\n\n<!-- cc-switch:managed-begin -->"));
+
+        let segments = parse_managed_segments(&file, delimiter);
+        assert_eq!(
+            segments,
+            vec![
+                PromptSegment {
+                    prompt_id: "style".to_string(),
+                    content: "# Style\n\n- short lines".to_string(),
+                },
+                PromptSegment {
+                    prompt_id: "safety".to_string(),
+                    content: "Never push to main.".to_string(),
+                },
+            ]
+        );
+
+        // 重新渲染只替换区块，移除区块后恢复用户内容
+        let reordered = render_managed_block([&safety], delimiter).expect("block");
+        let file = splice_managed_block(&file, Some(&reordered), &[]);
+        assert!(file.starts_with(existing));
+        assert_eq!(parse_managed_segments(&file, delimiter).len(), 1);
+        assert_eq!(splice_managed_block(&file, None, &[]), existing);
+    }
+
+    #[test]
+    fn segments_use_delimiter_recorded_in_block() {
+        let style = enabled_prompt("style", "# Style", 0);
+        let safety = enabled_prompt("safety", "Never push to main.", 1);
+        // 分隔内容包含注释结束符，也能正确记录
+        let old_delimiter = "<!-- section -->\n";
+        let block = render_managed_block([&style, &safety], old_delimiter).expect("block");
+
+        // 分隔设置已修改为其他值，解析旧区块时仍去掉旧分隔
+        let segments = parse_managed_segments(&block, "***\n");
+        assert_eq!(segments[0].content, "# Style");
+        assert_eq!(segments[1].content, "Never push to main.");
+    }
+
+    #[test]
+    fn legacy_prompt_file_is_replaced_by_managed_block() {
+        let prompt = enabled_prompt("only", "legacy body", 0);
+        let block = render_managed_block([&prompt], "\n").expect("block");
+
+        let file = splice_managed_block("legacy body\n", Some(&block), &["legacy body"]);
+        assert_eq!(file, format!("{block}\n"));
+        assert_eq!(
+            splice_managed_block("legacy body", None, &["legacy body"]),
+            ""
+        );
+        assert_eq!(strip_managed_block(&file), "");
+        assert!(render_managed_block(std::iter::empty(), "\n").is_none());
+    }
+}
@@ -0,0 +1,107 @@
+//! 供应商变更审计
+//!
+//! 在新增、修改、删除、切换供应商后写入 `provider_audit_log`。
+//! 审计失败只记录日志，不影响原操作的结果。
+
+use serde_json::Value;
+
+use crate::database::Database;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Add,
+    Update,
+    Delete,
+    Switch,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Add => "add",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Switch => "switch",
+        }
+    }
+}
+
+/// 变更来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditSource {
+    Ui,
+    Deeplink,
+    UniversalSync,
+    Failover,
+    LocalApi,
+}
+
+impl AuditSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSource::Ui => "ui",
+            AuditSource::Deeplink => "deeplink",
+            AuditSource::UniversalSync => "universal-sync",
+            AuditSource::Failover => "failover",
+            AuditSource::LocalApi => "local-api",
+        }
+    }
+}
+
+/// 记录一条不带配置差异的审计日志
+pub(crate) fn record(
+    db: &Database,
+    app_type: &str,
+    provider_id: &str,
+    action: AuditAction,
+    source: AuditSource,
+) {
+    write(db, app_type, provider_id, action, source, None);
+}
+
+/// 记录一次修改，附带脱敏后的 `settings_config` 差异（无变化时不带差异）
+pub(crate) fn record_update(
+    db: &Database,
+    app_type: &str,
+    provider_id: &str,
+    before: &Value,
+    after: &Value,
+    source: AuditSource,
+) {
+    let changes = crate::json_diff::diff_redacted(before, after);
+    let diff = if changes.is_empty() {
+        None
+    } else {
+        serde_json::to_value(changes).ok()
+    };
+    write(
+        db,
+        app_type,
+        provider_id,
+        AuditAction::Update,
+        source,
+        diff.as_ref(),
+    );
+}
+
+fn write(
+    db: &Database,
+    app_type: &str,
+    provider_id: &str,
+    action: AuditAction,
+    source: AuditSource,
+    diff: Option<&Value>,
+) {
+    if let Err(e) = db.record_provider_audit(
+        app_type,
+        provider_id,
+        action.as_str(),
+        source.as_str(),
+        diff,
+    ) {
+        log::warn!(
+            "记录供应商审计日志失败 ({app_type}/{provider_id} {}): {e}",
+            action.as_str()
+        );
+    }
+}
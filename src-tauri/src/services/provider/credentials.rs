@@ -0,0 +1,235 @@
+//! Credential audit
+//!
+//! 只读扫描所有供应商中明显的凭据问题：API Key 为空、令牌已过期、缺少 Base URL。
+//! 官方登录类供应商（category = official）不需要 API Key 与 Base URL，只检查过期时间。
+
+use chrono::DateTime;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+use crate::services::antigravity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialIssueKind {
+    /// API Key 缺失或为空
+    EmptyApiKey,
+    /// 令牌已过期
+    Expired,
+    /// 第三方供应商缺少 Base URL
+    MissingBaseUrl,
+}
+
+/// 单个供应商的凭据问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialIssue {
+    pub app: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub issue: CredentialIssueKind,
+    /// 过期时间（秒级时间戳，仅 expired）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// 检查单个供应商，`now` 为秒级时间戳
+pub(crate) fn audit_provider(
+    app_type: &AppType,
+    provider: &Provider,
+    now: i64,
+) -> Vec<CredentialIssue> {
+    let settings = &provider.settings_config;
+    let is_official = provider.category.as_deref() == Some("official");
+    // 未分类的供应商无法判断是否为官方登录，不检查 Base URL
+    let requires_base_url = provider
+        .category
+        .as_deref()
+        .is_some_and(|c| c != "official");
+
+    let (api_key, base_url, expires_at) = match app_type {
+        AppType::Claude => (
+            first_str(
+                settings,
+                &["/env/ANTHROPIC_AUTH_TOKEN", "/env/ANTHROPIC_API_KEY"],
+            ),
+            first_str(settings, &["/env/ANTHROPIC_BASE_URL"]),
+            None,
+        ),
+        AppType::Codex => {
+            // ChatGPT 登录使用 tokens，不需要 OPENAI_API_KEY
+            let has_login_tokens = settings.pointer("/auth/tokens").is_some();
+            let api_key = first_str(settings, &["/auth/OPENAI_API_KEY"])
+                .or_else(|| has_login_tokens.then(|| "tokens".to_string()));
+            let base_url = settings
+                .get("config")
+                .and_then(Value::as_str)
+                .and_then(|toml| {
+                    crate::codex_config::get_active_base_url(toml)
+                        .ok()
+                        .flatten()
+                });
+            let expires_at = ["/auth/expires_at", "/auth/tokens/expires_at"]
+                .iter()
+                .find_map(|pointer| settings.pointer(pointer).and_then(parse_timestamp));
+            (api_key, base_url, expires_at)
+        }
+        AppType::Gemini => {
+            let api_key = first_str(settings, &["/env/GEMINI_API_KEY"]).or_else(|| {
+                (antigravity::has_official_credentials(provider)
+                    || antigravity::has_google_oauth_access_token(provider))
+                .then(|| "oauth".to_string())
+            });
+            (
+                api_key,
+                first_str(settings, &["/env/GOOGLE_GEMINI_BASE_URL"]),
+                antigravity::antigravity_expires_at(provider),
+            )
+        }
+        AppType::OpenCode => (
+            first_str(settings, &["/options/apiKey"]),
+            first_str(settings, &["/options/baseURL"]),
+            None,
+        ),
+    };
+
+    let mut kinds = Vec::new();
+    if !is_official && api_key.is_none() {
+        kinds.push((CredentialIssueKind::EmptyApiKey, None));
+    }
+    if let Some(expires_at) = expires_at.filter(|ts| *ts <= now) {
+        kinds.push((CredentialIssueKind::Expired, Some(expires_at)));
+    }
+    if requires_base_url && base_url.is_none() {
+        kinds.push((CredentialIssueKind::MissingBaseUrl, None));
+    }
+
+    kinds
+        .into_iter()
+        .map(|(issue, expires_at)| CredentialIssue {
+            app: app_type.as_str().to_string(),
+            provider_id: provider.id.clone(),
+            provider_name: provider.name.clone(),
+            issue,
+            expires_at,
+        })
+        .collect()
+}
+
+/// 依次查找第一个非空字符串字段
+fn first_str(settings: &Value, pointers: &[&str]) -> Option<String> {
+    pointers.iter().find_map(|pointer| {
+        settings
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    })
+}
+
+/// 解析过期时间：秒或毫秒级数字、数字字符串或 RFC 3339 字符串，统一为秒级时间戳
+fn parse_timestamp(value: &Value) -> Option<i64> {
+    let raw = match value {
+        Value::Number(n) => n.as_i64()?,
+        Value::String(s) => {
+            let s = s.trim();
+            match s.parse::<i64>() {
+                Ok(n) => n,
+                Err(_) => return DateTime::parse_from_rfc3339(s).ok().map(|t| t.timestamp()),
+            }
+        }
+        _ => return None,
+    };
+    // 毫秒级时间戳
+    Some(if raw > 100_000_000_000 {
+        raw / 1000
+    } else {
+        raw
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(settings: Value, category: Option<&str>) -> Provider {
+        let mut provider = Provider::with_id("p1".into(), "P1".into(), settings, None);
+        provider.category = category.map(|c| c.to_string());
+        provider
+    }
+
+    fn issues(app_type: AppType, provider: &Provider) -> Vec<CredentialIssueKind> {
+        audit_provider(&app_type, provider, 1_700_000_000)
+            .into_iter()
+            .map(|issue| issue.issue)
+            .collect()
+    }
+
+    #[test]
+    fn audit_flags_empty_key_and_missing_base_url() {
+        let claude = provider(
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": " " } }),
+            Some("third_party"),
+        );
+        assert_eq!(
+            issues(AppType::Claude, &claude),
+            vec![
+                CredentialIssueKind::EmptyApiKey,
+                CredentialIssueKind::MissingBaseUrl
+            ]
+        );
+
+        let healthy = provider(
+            json!({ "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-1",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            } }),
+            Some("third_party"),
+        );
+        assert!(issues(AppType::Claude, &healthy).is_empty());
+
+        // 官方登录不需要 API Key
+        let official = provider(json!({ "env": {} }), Some("official"));
+        assert!(issues(AppType::Claude, &official).is_empty());
+    }
+
+    #[test]
+    fn audit_flags_expired_tokens() {
+        let codex = provider(
+            json!({
+                "auth": { "tokens": { "access_token": "a", "expires_at": "2023-01-01T00:00:00Z" } },
+                "config": ""
+            }),
+            Some("official"),
+        );
+        let found = audit_provider(&AppType::Codex, &codex, 1_700_000_000);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].issue, CredentialIssueKind::Expired);
+        assert_eq!(found[0].expires_at, Some(1_672_531_200));
+
+        let antigravity = provider(
+            json!({ "env": {
+                "ANTIGRAVITY_ACCESS_TOKEN": "a",
+                "ANTIGRAVITY_REFRESH_TOKEN": "r",
+                "ANTIGRAVITY_EMAIL": "me@example.com",
+                "ANTIGRAVITY_EXPIRES_AT": "1600000000"
+            } }),
+            None,
+        );
+        assert_eq!(
+            issues(AppType::Gemini, &antigravity),
+            vec![CredentialIssueKind::Expired]
+        );
+
+        assert_eq!(
+            parse_timestamp(&json!(1_700_000_000_000i64)),
+            Some(1_700_000_000)
+        );
+        assert_eq!(parse_timestamp(&json!("1700000000")), Some(1_700_000_000));
+        assert_eq!(parse_timestamp(&json!(true)), None);
+    }
+}
@@ -0,0 +1,281 @@
+//! Per-provider default models
+//!
+//! 不同中转支持的模型名各不相同。`meta.defaultModels` 记录供应商的默认模型（角色 → 模型名），
+//! 写入 Live 配置时覆盖 settings_config 中的对应字段；切换前回填时，Live 中与注入值相同的
+//! 模型字段会恢复为供应商原有的值，避免把注入的模型当作用户修改写回。
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+
+use super::normalize_claude_models_in_value;
+
+/// Claude 角色与 env 键的对应关系
+const CLAUDE_MODEL_ROLES: [(&str, &str); 4] = [
+    ("model", "ANTHROPIC_MODEL"),
+    ("haiku", "ANTHROPIC_DEFAULT_HAIKU_MODEL"),
+    ("sonnet", "ANTHROPIC_DEFAULT_SONNET_MODEL"),
+    ("opus", "ANTHROPIC_DEFAULT_OPUS_MODEL"),
+];
+
+/// Codex 唯一的角色，对应 config.toml 顶层 `model`
+const CODEX_MODEL_ROLE: &str = "model";
+
+/// 回填时需要比对的 Claude env 键（含规范化时可能派生出的键）
+const CLAUDE_MANAGED_MODEL_KEYS: [&str; 5] = [
+    "ANTHROPIC_MODEL",
+    "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+    "ANTHROPIC_DEFAULT_SONNET_MODEL",
+    "ANTHROPIC_DEFAULT_OPUS_MODEL",
+    "ANTHROPIC_SMALL_FAST_MODEL",
+];
+
+fn supported_roles(app_type: &AppType) -> Vec<&'static str> {
+    match app_type {
+        AppType::Claude => CLAUDE_MODEL_ROLES.iter().map(|(role, _)| *role).collect(),
+        AppType::Codex => vec![CODEX_MODEL_ROLE],
+        AppType::Gemini | AppType::OpenCode => Vec::new(),
+    }
+}
+
+fn default_models(provider: &Provider) -> Option<&HashMap<String, String>> {
+    provider
+        .meta
+        .as_ref()
+        .map(|m| &m.default_models)
+        .filter(|m| !m.is_empty())
+}
+
+/// 校验默认模型：角色必须受当前应用支持，模型名不能为空
+pub(crate) fn validate_default_models(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<(), AppError> {
+    let Some(models) = default_models(provider) else {
+        return Ok(());
+    };
+    let roles = supported_roles(app_type);
+    for (role, model) in models {
+        if !roles.contains(&role.as_str()) {
+            let supported = if roles.is_empty() {
+                "-".to_string()
+            } else {
+                roles.join(", ")
+            };
+            return Err(AppError::localized(
+                "provider.default_models.unsupported_role",
+                format!(
+                    "{} 不支持默认模型角色 {role}（可用：{supported}）",
+                    app_type.as_str()
+                ),
+                format!(
+                    "Default model role {role} is not supported for {} (supported: {supported})",
+                    app_type.as_str()
+                ),
+            ));
+        }
+        if model.trim().is_empty() {
+            return Err(AppError::localized(
+                "provider.default_models.empty",
+                format!("默认模型 {role} 不能为空"),
+                format!("Default model for {role} must not be empty"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 返回注入默认模型后的 settings_config（未配置默认模型时原样克隆）
+pub(crate) fn settings_with_default_models(app_type: &AppType, provider: &Provider) -> Value {
+    let mut settings = provider.settings_config.clone();
+    let Some(models) = default_models(provider) else {
+        return settings;
+    };
+
+    match app_type {
+        AppType::Claude => {
+            let env = settings
+                .as_object_mut()
+                .map(|obj| obj.entry("env").or_insert_with(|| json!({})))
+                .and_then(Value::as_object_mut);
+            if let Some(env) = env {
+                for (role, key) in CLAUDE_MODEL_ROLES {
+                    if let Some(model) = models.get(role) {
+                        env.insert(key.to_string(), Value::String(model.trim().to_string()));
+                    }
+                }
+            }
+            normalize_claude_models_in_value(&mut settings);
+        }
+        AppType::Codex => {
+            let Some(model) = models.get(CODEX_MODEL_ROLE) else {
+                return settings;
+            };
+            let config = settings
+                .get("config")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            match set_codex_model(config, Some(model.trim())) {
+                Ok(updated) => settings["config"] = Value::String(updated),
+                Err(e) => log::warn!("注入 Codex 默认模型失败，沿用供应商配置: {e}"),
+            }
+        }
+        AppType::Gemini | AppType::OpenCode => {}
+    }
+    settings
+}
+
+/// 回填前调用：Live 中与注入值相同的模型字段恢复为供应商原有的值（原来没有则移除）
+pub(crate) fn strip_injected_default_models(
+    app_type: &AppType,
+    stored: &Provider,
+    live: &mut Value,
+) {
+    if default_models(stored).is_none() {
+        return;
+    }
+    let injected = settings_with_default_models(app_type, stored);
+
+    match app_type {
+        AppType::Claude => {
+            let stored_env = stored.settings_config.get("env");
+            let Some(live_env) = live.get_mut("env").and_then(Value::as_object_mut) else {
+                return;
+            };
+            for key in CLAUDE_MANAGED_MODEL_KEYS {
+                let injected_value = injected.get("env").and_then(|env| env.get(key));
+                if injected_value.is_none() || live_env.get(key) != injected_value {
+                    continue;
+                }
+                match stored_env.and_then(|env| env.get(key)) {
+                    Some(original) => live_env.insert(key.to_string(), original.clone()),
+                    None => live_env.remove(key),
+                };
+            }
+        }
+        AppType::Codex => {
+            let codex_model = |settings: &Value| {
+                settings
+                    .get("config")
+                    .and_then(Value::as_str)
+                    .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+                    .and_then(|doc| doc.get("model")?.as_str().map(str::to_string))
+            };
+            let Some(live_config) = live.get("config").and_then(Value::as_str) else {
+                return;
+            };
+            let injected_model = codex_model(&injected);
+            if injected_model.is_none() || codex_model(&*live) != injected_model {
+                return;
+            }
+            let original = codex_model(&stored.settings_config);
+            match set_codex_model(live_config, original.as_deref()) {
+                Ok(updated) => live["config"] = Value::String(updated),
+                Err(e) => log::warn!("回填时还原 Codex 模型失败: {e}"),
+            }
+        }
+        AppType::Gemini | AppType::OpenCode => {}
+    }
+}
+
+/// 设置（或移除）config.toml 顶层 `model`，保留其余内容与注释
+fn set_codex_model(config: &str, model: Option<&str>) -> Result<String, AppError> {
+    let mut doc = config
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Message(format!("TOML parse error: {e}")))?;
+    match model {
+        Some(model) => match doc.get_mut("model") {
+            Some(toml_edit::Item::Value(old)) => {
+                let decor = old.decor().clone();
+                *old = toml_edit::Value::from(model);
+                *old.decor_mut() = decor;
+            }
+            _ => {
+                doc.insert("model", toml_edit::value(model));
+            }
+        },
+        None => {
+            doc.remove("model");
+        }
+    }
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderMeta;
+
+    fn provider_with_defaults(settings: Value, defaults: &[(&str, &str)]) -> Provider {
+        let mut provider = Provider::with_id("p".into(), "P".into(), settings, None);
+        provider.meta = Some(ProviderMeta {
+            default_models: defaults
+                .iter()
+                .map(|(role, model)| (role.to_string(), model.to_string()))
+                .collect(),
+            ..Default::default()
+        });
+        provider
+    }
+
+    #[test]
+    fn claude_defaults_override_settings_and_are_stripped_on_backfill() {
+        let provider = provider_with_defaults(
+            json!({ "env": { "ANTHROPIC_MODEL": "stored-model", "ANTHROPIC_AUTH_TOKEN": "t" } }),
+            &[("sonnet", "relay-sonnet"), ("haiku", "relay-haiku")],
+        );
+        assert!(validate_default_models(&AppType::Claude, &provider).is_ok());
+
+        let live = settings_with_default_models(&AppType::Claude, &provider);
+        assert_eq!(
+            live["env"]["ANTHROPIC_DEFAULT_SONNET_MODEL"],
+            "relay-sonnet"
+        );
+        assert_eq!(live["env"]["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "relay-haiku");
+        assert_eq!(live["env"]["ANTHROPIC_DEFAULT_OPUS_MODEL"], "stored-model");
+        assert_eq!(live["env"]["ANTHROPIC_MODEL"], "stored-model");
+
+        // 用户在 Live 中手动改过的 opus 保留，注入的值被去掉
+        let mut edited = live.clone();
+        edited["env"]["ANTHROPIC_DEFAULT_OPUS_MODEL"] = json!("user-opus");
+        strip_injected_default_models(&AppType::Claude, &provider, &mut edited);
+        assert_eq!(
+            edited["env"],
+            json!({
+                "ANTHROPIC_MODEL": "stored-model",
+                "ANTHROPIC_AUTH_TOKEN": "t",
+                "ANTHROPIC_DEFAULT_OPUS_MODEL": "user-opus",
+            })
+        );
+    }
+
+    #[test]
+    fn codex_default_model_is_written_to_config_toml_and_restored() {
+        let provider = provider_with_defaults(
+            json!({
+                "auth": {},
+                "config": "model = \"gpt-4\" # pinned\nmodel_provider = \"relay\"\n",
+            }),
+            &[("model", "relay-gpt")],
+        );
+
+        let live = settings_with_default_models(&AppType::Codex, &provider);
+        let config = live["config"].as_str().unwrap();
+        assert!(config.contains("model = \"relay-gpt\" # pinned"));
+        assert!(config.contains("model_provider = \"relay\""));
+
+        let mut backfill = live.clone();
+        strip_injected_default_models(&AppType::Codex, &provider, &mut backfill);
+        assert!(backfill["config"]
+            .as_str()
+            .unwrap()
+            .contains("model = \"gpt-4\""));
+
+        let unsupported = provider_with_defaults(json!({}), &[("sonnet", "x")]);
+        assert!(validate_default_models(&AppType::Codex, &unsupported).is_err());
+    }
+}
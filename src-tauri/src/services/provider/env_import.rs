@@ -0,0 +1,422 @@
+//! Import a provider from the current shell environment
+//!
+//! 新用户往往已经在 shell 中导出了 `ANTHROPIC_BASE_URL` / `ANTHROPIC_AUTH_TOKEN` 等变量。
+//! 这里按应用读取进程环境（以及 `check_env_conflicts` 在注册表 / shell 配置文件中找到的变量），
+//! 组装一个名为 "Imported from environment" 的供应商。
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use toml_edit::{value as toml_value, DocumentMut, Item, Table};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::env_checker::EnvConflict;
+
+/// 导入的供应商名称
+pub(crate) const ENV_PROVIDER_NAME: &str = "Imported from environment";
+
+/// 进程环境变量的来源标识（与 env_checker 保持一致）
+const PROCESS_ENV_SOURCE: &str = "Process Environment";
+
+/// 单个环境变量的查找规则：按顺序尝试候选变量名，命中的第一个写入 `target`
+struct EnvKeySpec {
+    target: &'static str,
+    candidates: &'static [&'static str],
+    required: bool,
+}
+
+const CLAUDE_KEYS: &[EnvKeySpec] = &[
+    EnvKeySpec {
+        target: "ANTHROPIC_AUTH_TOKEN",
+        candidates: &["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"],
+        required: true,
+    },
+    EnvKeySpec {
+        target: "ANTHROPIC_BASE_URL",
+        candidates: &["ANTHROPIC_BASE_URL"],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "ANTHROPIC_MODEL",
+        candidates: &["ANTHROPIC_MODEL"],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+        candidates: &[
+            "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+            "ANTHROPIC_SMALL_FAST_MODEL",
+        ],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "ANTHROPIC_DEFAULT_SONNET_MODEL",
+        candidates: &["ANTHROPIC_DEFAULT_SONNET_MODEL"],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "ANTHROPIC_DEFAULT_OPUS_MODEL",
+        candidates: &["ANTHROPIC_DEFAULT_OPUS_MODEL"],
+        required: false,
+    },
+];
+
+const CODEX_KEYS: &[EnvKeySpec] = &[
+    EnvKeySpec {
+        target: "OPENAI_API_KEY",
+        candidates: &["OPENAI_API_KEY"],
+        required: true,
+    },
+    EnvKeySpec {
+        target: "OPENAI_BASE_URL",
+        candidates: &["OPENAI_BASE_URL", "OPENAI_API_BASE"],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "OPENAI_MODEL",
+        candidates: &["OPENAI_MODEL"],
+        required: false,
+    },
+];
+
+const GEMINI_KEYS: &[EnvKeySpec] = &[
+    EnvKeySpec {
+        target: "GEMINI_API_KEY",
+        candidates: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+        required: true,
+    },
+    EnvKeySpec {
+        target: "GOOGLE_GEMINI_BASE_URL",
+        candidates: &["GOOGLE_GEMINI_BASE_URL", "GEMINI_BASE_URL"],
+        required: false,
+    },
+    EnvKeySpec {
+        target: "GEMINI_MODEL",
+        candidates: &["GEMINI_MODEL"],
+        required: false,
+    },
+];
+
+/// 找到的环境变量（不回传变量值，避免在前端暴露密钥）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvImportFound {
+    pub var_name: String,
+    pub source: String,
+}
+
+/// 从环境变量导入供应商的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvImportResult {
+    /// 新建或已存在的供应商 ID；缺少必需变量时为 None
+    pub provider_id: Option<String>,
+    /// 是否新建了供应商（false 表示已存在相同 base_url + key 的供应商或未导入）
+    pub created: bool,
+    pub found: Vec<EnvImportFound>,
+    /// 未找到的变量名（必需与可选均列出）
+    pub missing: Vec<String>,
+}
+
+/// 从环境变量组装的草稿
+#[derive(Debug)]
+pub(crate) struct EnvImportDraft {
+    /// 缺少必需变量时为 None
+    pub provider: Option<Provider>,
+    pub found: Vec<EnvImportFound>,
+    pub missing: Vec<String>,
+}
+
+fn key_specs(app_type: &AppType) -> Result<&'static [EnvKeySpec], AppError> {
+    match app_type {
+        AppType::Claude => Ok(CLAUDE_KEYS),
+        AppType::Codex => Ok(CODEX_KEYS),
+        AppType::Gemini => Ok(GEMINI_KEYS),
+        AppType::OpenCode => Err(AppError::localized(
+            "provider.env_import.unsupported",
+            "OpenCode 不支持从环境变量导入供应商",
+            "Importing providers from environment variables is not supported for OpenCode",
+        )),
+    }
+}
+
+/// 收集候选环境变量：进程环境优先，其次为 `check_env_conflicts` 的结果
+pub(crate) fn collect_env_vars(app_type: &AppType) -> Vec<EnvConflict> {
+    let mut vars: Vec<EnvConflict> = std::env::vars()
+        .map(|(var_name, var_value)| EnvConflict {
+            var_name,
+            var_value,
+            source_type: "system".to_string(),
+            source_path: PROCESS_ENV_SOURCE.to_string(),
+        })
+        .collect();
+
+    match crate::services::env_checker::check_env_conflicts(app_type.as_str()) {
+        Ok(conflicts) => vars.extend(conflicts),
+        Err(e) => log::warn!("检查环境变量冲突失败，仅使用进程环境: {e}"),
+    }
+    vars
+}
+
+/// 按变量名查找第一个可用的值
+///
+/// shell 配置文件中引用其它变量（如 `$FOO`）的值无法在此展开，直接跳过。
+fn lookup<'a>(vars: &'a [EnvConflict], name: &str) -> Option<&'a EnvConflict> {
+    vars.iter().find(|v| {
+        v.var_name == name && {
+            let value = v.var_value.trim();
+            !value.is_empty() && !value.contains('$')
+        }
+    })
+}
+
+/// 根据环境变量组装供应商草稿（不写库）
+pub(crate) fn build_env_import(
+    app_type: &AppType,
+    vars: &[EnvConflict],
+) -> Result<EnvImportDraft, AppError> {
+    let specs = key_specs(app_type)?;
+
+    let mut values: Map<String, Value> = Map::new();
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    let mut has_required = true;
+
+    for spec in specs {
+        let hit = spec.candidates.iter().find_map(|name| lookup(vars, name));
+        match hit {
+            Some(var) => {
+                values.insert(spec.target.to_string(), json!(var.var_value.trim()));
+                found.push(EnvImportFound {
+                    var_name: var.var_name.clone(),
+                    source: var.source_path.clone(),
+                });
+            }
+            None => {
+                has_required &= !spec.required;
+                missing.push(spec.target.to_string());
+            }
+        }
+    }
+
+    let provider = has_required.then(|| {
+        let settings_config = match app_type {
+            AppType::Codex => build_codex_settings(&values),
+            _ => json!({ "env": values }),
+        };
+        Provider::with_id(
+            format!("env-{}", chrono::Utc::now().timestamp_millis()),
+            ENV_PROVIDER_NAME.to_string(),
+            settings_config,
+            None,
+        )
+    });
+
+    Ok(EnvImportDraft {
+        provider,
+        found,
+        missing,
+    })
+}
+
+/// Codex 的 base_url / 模型写入 config.toml，密钥写入 auth.json
+///
+/// 环境变量的值可能包含引号等字符，通过 toml_edit 构建以保证转义正确。
+fn build_codex_settings(values: &Map<String, Value>) -> Value {
+    let get = |key: &str| values.get(key).and_then(Value::as_str);
+    let base_url = get("OPENAI_BASE_URL")
+        .unwrap_or("https://api.openai.com/v1")
+        .trim_end_matches('/');
+    let model = get("OPENAI_MODEL").unwrap_or("gpt-5-codex");
+
+    let mut provider = Table::new();
+    provider.insert("name", toml_value("env"));
+    provider.insert("base_url", toml_value(base_url));
+    provider.insert("wire_api", toml_value("responses"));
+    provider.insert("requires_openai_auth", toml_value(true));
+    let mut providers = Table::new();
+    providers.set_implicit(true);
+    providers.insert("env", Item::Table(provider));
+
+    let mut doc = DocumentMut::new();
+    doc.insert("model_provider", toml_value("env"));
+    doc.insert("model", toml_value(model));
+    doc.insert("disable_response_storage", toml_value(true));
+    doc.insert("model_providers", Item::Table(providers));
+
+    json!({
+        "auth": { "OPENAI_API_KEY": get("OPENAI_API_KEY") },
+        "config": doc.to_string()
+    })
+}
+
+/// 提取用于去重的 (base_url, api_key)；未配置 base_url 时按官方地址比较
+pub(crate) fn dedup_key(app_type: &AppType, provider: &Provider) -> Option<(String, String)> {
+    let settings = &provider.settings_config;
+    let env_str = |key: &str| {
+        settings
+            .get("env")
+            .and_then(|env| env.get(key))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let (base_url, api_key) = match app_type {
+        AppType::Claude => (
+            env_str("ANTHROPIC_BASE_URL")
+                .unwrap_or_else(|| "https://api.anthropic.com".to_string()),
+            env_str("ANTHROPIC_AUTH_TOKEN").or_else(|| env_str("ANTHROPIC_API_KEY"))?,
+        ),
+        AppType::Codex => (
+            settings
+                .get("config")
+                .and_then(Value::as_str)
+                .and_then(|config| crate::codex_config::get_active_base_url(config).ok())
+                .flatten()
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            settings
+                .get("auth")
+                .and_then(|auth| auth.get("OPENAI_API_KEY"))
+                .and_then(Value::as_str)
+                .map(str::to_string)?,
+        ),
+        AppType::Gemini => (
+            env_str("GOOGLE_GEMINI_BASE_URL")
+                .unwrap_or_else(|| "https://generativelanguage.googleapis.com".to_string()),
+            env_str("GEMINI_API_KEY").or_else(|| env_str("GOOGLE_API_KEY"))?,
+        ),
+        AppType::OpenCode => return None,
+    };
+
+    Some((
+        base_url.trim().trim_end_matches('/').to_lowercase(),
+        api_key.trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str, value: &str, source: &str) -> EnvConflict {
+        EnvConflict {
+            var_name: name.to_string(),
+            var_value: value.to_string(),
+            source_type: "file".to_string(),
+            source_path: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn build_claude_provider_prefers_first_source_and_reports_missing() {
+        let vars = vec![
+            var(
+                "ANTHROPIC_BASE_URL",
+                "https://relay.example.com/",
+                PROCESS_ENV_SOURCE,
+            ),
+            var("ANTHROPIC_API_KEY", "sk-process", PROCESS_ENV_SOURCE),
+            var("ANTHROPIC_API_KEY", "sk-file", "~/.zshrc:3"),
+            var("ANTHROPIC_MODEL", "$MODEL", "~/.zshrc:4"),
+        ];
+
+        let draft = build_env_import(&AppType::Claude, &vars).expect("build draft");
+        let provider = draft.provider.expect("provider");
+        assert_eq!(provider.name, ENV_PROVIDER_NAME);
+        let env = &provider.settings_config["env"];
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-process");
+        assert_eq!(env["ANTHROPIC_BASE_URL"], "https://relay.example.com/");
+        assert!(env.get("ANTHROPIC_MODEL").is_none());
+        assert!(draft.missing.contains(&"ANTHROPIC_MODEL".to_string()));
+        assert_eq!(
+            draft
+                .found
+                .iter()
+                .map(|f| f.var_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["ANTHROPIC_API_KEY", "ANTHROPIC_BASE_URL"]
+        );
+    }
+
+    #[test]
+    fn build_without_required_key_returns_no_provider() {
+        let vars = vec![var(
+            "OPENAI_BASE_URL",
+            "https://relay.example.com/v1",
+            PROCESS_ENV_SOURCE,
+        )];
+
+        let draft = build_env_import(&AppType::Codex, &vars).expect("build draft");
+        assert!(draft.provider.is_none());
+        assert!(draft.missing.contains(&"OPENAI_API_KEY".to_string()));
+
+        assert!(build_env_import(&AppType::OpenCode, &vars).is_err());
+    }
+
+    #[test]
+    fn dedup_key_ignores_trailing_slash_and_defaults_base_url() {
+        let with_slash = Provider::with_id(
+            "a".into(),
+            "A".into(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://Relay.example.com/", "ANTHROPIC_AUTH_TOKEN": "sk" } }),
+            None,
+        );
+        let without_slash = Provider::with_id(
+            "b".into(),
+            "B".into(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://relay.example.com", "ANTHROPIC_API_KEY": "sk" } }),
+            None,
+        );
+        assert_eq!(
+            dedup_key(&AppType::Claude, &with_slash),
+            dedup_key(&AppType::Claude, &without_slash)
+        );
+
+        let official = Provider::with_id(
+            "c".into(),
+            "C".into(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk" } }),
+            None,
+        );
+        assert_eq!(
+            dedup_key(&AppType::Claude, &official),
+            Some(("https://api.anthropic.com".to_string(), "sk".to_string()))
+        );
+    }
+
+    #[test]
+    fn build_codex_provider_writes_toml_base_url() {
+        let vars = vec![
+            var("OPENAI_API_KEY", "sk-openai", PROCESS_ENV_SOURCE),
+            var(
+                "OPENAI_API_BASE",
+                "https://relay.example.com/v1/",
+                PROCESS_ENV_SOURCE,
+            ),
+            var("OPENAI_MODEL", r#"gpt-"5"\codex"#, PROCESS_ENV_SOURCE),
+        ];
+
+        let provider = build_env_import(&AppType::Codex, &vars)
+            .expect("build draft")
+            .provider
+            .expect("provider");
+        assert_eq!(
+            provider.settings_config["auth"]["OPENAI_API_KEY"],
+            "sk-openai"
+        );
+        let config = provider.settings_config["config"].as_str().unwrap();
+        assert_eq!(
+            crate::codex_config::get_active_base_url(config).unwrap(),
+            Some("https://relay.example.com/v1".to_string())
+        );
+        let parsed: toml::Value = toml::from_str(config).expect("valid toml");
+        assert_eq!(parsed["model"].as_str(), Some(r#"gpt-"5"\codex"#));
+        assert_eq!(
+            parsed["model_providers"]["env"]["wire_api"].as_str(),
+            Some("responses")
+        );
+    }
+}
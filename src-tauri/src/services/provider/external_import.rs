@@ -0,0 +1,305 @@
+//! Import providers from other tools' config files
+//!
+//! 目前支持 claude-code-router（`~/.claude-code-router/config.json`）：
+//! `Providers` 数组中的每一项映射为一个 Claude 供应商，
+//! `api_base_url` / `api_key` / `models` 写入 `env`，`Router.default` / `Router.background`
+//! 指向该供应商时用于选择主模型 / Haiku 模型。其余字段在结果中逐一列出，不会被静默丢弃。
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderMeta};
+
+/// claude-code-router 供应商条目中已映射的字段
+const CCR_PROVIDER_FIELDS: [&str; 4] = ["name", "api_base_url", "api_key", "models"];
+
+/// claude-code-router 顶层已处理的字段
+const CCR_TOP_LEVEL_FIELDS: [&str; 2] = ["Providers", "Router"];
+
+/// 支持的外部配置格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalFormat {
+    ClaudeCodeRouter,
+}
+
+impl FromStr for ExternalFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "claude-code-router" | "ccr" => Ok(Self::ClaudeCodeRouter),
+            other => Err(AppError::localized(
+                "import.external.unsupported_format",
+                format!("不支持的外部配置格式: {other}"),
+                format!("Unsupported external config format: {other}"),
+            )),
+        }
+    }
+}
+
+/// 单个已导入的供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportedProvider {
+    pub app_type: String,
+    pub id: String,
+    pub name: String,
+}
+
+/// 外部配置导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportResult {
+    pub imported: Vec<ExternalImportedProvider>,
+    /// 无法导入的条目及原因
+    pub skipped: Vec<String>,
+    /// 未映射的字段路径（如 `Providers[0].transformer`）
+    pub unmapped: Vec<String>,
+}
+
+/// 解析结果：待写入的供应商与报告
+#[derive(Debug, Default)]
+pub(crate) struct ParsedExternalConfig {
+    pub providers: Vec<(AppType, Provider)>,
+    pub skipped: Vec<String>,
+    pub unmapped: Vec<String>,
+}
+
+/// 按格式解析外部配置
+pub(crate) fn parse_external_config(
+    format: ExternalFormat,
+    config: &Value,
+) -> Result<ParsedExternalConfig, AppError> {
+    match format {
+        ExternalFormat::ClaudeCodeRouter => parse_claude_code_router(config),
+    }
+}
+
+fn parse_claude_code_router(config: &Value) -> Result<ParsedExternalConfig, AppError> {
+    let obj = config.as_object().ok_or_else(|| {
+        AppError::localized(
+            "import.external.invalid",
+            "claude-code-router 配置必须是 JSON 对象",
+            "claude-code-router config must be a JSON object",
+        )
+    })?;
+    let entries = obj
+        .get("Providers")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            AppError::localized(
+                "import.external.invalid",
+                "claude-code-router 配置缺少 Providers 数组",
+                "claude-code-router config is missing the Providers array",
+            )
+        })?;
+
+    let mut parsed = ParsedExternalConfig::default();
+    for key in obj.keys() {
+        if !CCR_TOP_LEVEL_FIELDS.contains(&key.as_str()) {
+            parsed.unmapped.push(key.clone());
+        }
+    }
+
+    // Router 中形如 "provider,model" 的路由
+    let router = obj.get("Router").and_then(Value::as_object);
+    let route = |key: &str| {
+        router
+            .and_then(|r| r.get(key))
+            .and_then(Value::as_str)
+            .and_then(|v| v.split_once(','))
+            .map(|(provider, model)| (provider.trim(), model.trim()))
+    };
+    if let Some(router) = router {
+        for key in router.keys() {
+            if key != "default" && key != "background" {
+                parsed.unmapped.push(format!("Router.{key}"));
+            }
+        }
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let path = format!("Providers[{index}]");
+        let Some(entry) = entry.as_object() else {
+            parsed.skipped.push(format!("{path}: 条目必须为 JSON 对象"));
+            continue;
+        };
+        let name = entry
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let base_url = entry
+            .get("api_base_url")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let (Some(name), Some(base_url)) = (name, base_url) else {
+            parsed
+                .skipped
+                .push(format!("{path}: 缺少 name 或 api_base_url"));
+            continue;
+        };
+
+        let models: Vec<&str> = entry
+            .get("models")
+            .and_then(Value::as_array)
+            .map(|arr| arr.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let pick = |key: &str| {
+            route(key)
+                .filter(|(provider, _)| *provider == name)
+                .map(|(_, model)| model)
+        };
+        let model = pick("default").or_else(|| models.first().copied());
+        let haiku = pick("background");
+
+        let (base_url, api_format) = split_chat_completions_url(base_url);
+        let mut env = Map::new();
+        env.insert("ANTHROPIC_BASE_URL".into(), json!(base_url));
+        if let Some(key) = entry.get("api_key").and_then(Value::as_str) {
+            env.insert("ANTHROPIC_AUTH_TOKEN".into(), json!(key));
+        }
+        if let Some(model) = model {
+            env.insert("ANTHROPIC_MODEL".into(), json!(model));
+        }
+        if let Some(haiku) = haiku {
+            env.insert("ANTHROPIC_DEFAULT_HAIKU_MODEL".into(), json!(haiku));
+        }
+        for other in models
+            .iter()
+            .filter(|m| Some(**m) != model && Some(**m) != haiku)
+        {
+            parsed.unmapped.push(format!("{path}.models: {other}"));
+        }
+        for key in entry.keys() {
+            if !CCR_PROVIDER_FIELDS.contains(&key.as_str()) {
+                parsed.unmapped.push(format!("{path}.{key}"));
+            }
+        }
+
+        let mut provider = Provider::with_id(
+            format!("ccr-{}", slugify(name)),
+            name.to_string(),
+            json!({ "env": env }),
+            None,
+        );
+        if let Some(api_format) = api_format {
+            provider.meta = Some(ProviderMeta {
+                api_format: Some(api_format.to_string()),
+                ..Default::default()
+            });
+        }
+        parsed.providers.push((AppType::Claude, provider));
+    }
+
+    Ok(parsed)
+}
+
+/// claude-code-router 使用 OpenAI Chat Completions 端点，转换为基础地址并标记 `openai_chat`
+fn split_chat_completions_url(url: &str) -> (&str, Option<&'static str>) {
+    let url = url.trim_end_matches('/');
+    for suffix in ["/v1/chat/completions", "/chat/completions"] {
+        if let Some(base) = url.strip_suffix(suffix) {
+            return (base, Some("openai_chat"));
+        }
+    }
+    (url, None)
+}
+
+/// 生成稳定的 ID 片段，重复导入同一配置时覆盖而不是新增
+fn slugify(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "provider".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_claude_code_router_maps_providers_and_reports_leftovers() {
+        let config = json!({
+            "LOG": true,
+            "Providers": [
+                {
+                    "name": "openrouter",
+                    "api_base_url": "https://openrouter.ai/api/v1/chat/completions",
+                    "api_key": "sk-or",
+                    "models": ["google/gemini-2.5-pro", "anthropic/claude-sonnet-4"],
+                    "transformer": { "use": ["openrouter"] }
+                },
+                {
+                    "name": "My Relay",
+                    "api_base_url": "https://relay.example.com/",
+                    "api_key": "sk-relay",
+                    "models": ["claude-haiku-4"]
+                },
+                { "name": "broken" }
+            ],
+            "Router": {
+                "default": "openrouter,anthropic/claude-sonnet-4",
+                "background": "My Relay,claude-haiku-4",
+                "think": "openrouter,google/gemini-2.5-pro"
+            }
+        });
+
+        let parsed =
+            parse_external_config(ExternalFormat::ClaudeCodeRouter, &config).expect("parse");
+
+        assert_eq!(parsed.providers.len(), 2);
+        let (app_type, openrouter) = &parsed.providers[0];
+        assert_eq!(*app_type, AppType::Claude);
+        assert_eq!(openrouter.id, "ccr-openrouter");
+        let env = &openrouter.settings_config["env"];
+        assert_eq!(env["ANTHROPIC_BASE_URL"], "https://openrouter.ai/api");
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-or");
+        assert_eq!(env["ANTHROPIC_MODEL"], "anthropic/claude-sonnet-4");
+        assert_eq!(
+            openrouter
+                .meta
+                .as_ref()
+                .and_then(|m| m.api_format.as_deref()),
+            Some("openai_chat")
+        );
+
+        let (_, relay) = &parsed.providers[1];
+        assert_eq!(relay.id, "ccr-my-relay");
+        let env = &relay.settings_config["env"];
+        assert_eq!(env["ANTHROPIC_BASE_URL"], "https://relay.example.com");
+        assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "claude-haiku-4");
+        assert!(relay.meta.is_none());
+
+        assert_eq!(parsed.skipped.len(), 1);
+        assert!(parsed.unmapped.contains(&"LOG".to_string()));
+        assert!(parsed.unmapped.contains(&"Router.think".to_string()));
+        assert!(parsed
+            .unmapped
+            .contains(&"Providers[0].transformer".to_string()));
+        assert!(parsed
+            .unmapped
+            .contains(&"Providers[0].models: google/gemini-2.5-pro".to_string()));
+    }
+
+    #[test]
+    fn parse_claude_code_router_requires_providers_array() {
+        assert!(
+            parse_external_config(ExternalFormat::ClaudeCodeRouter, &json!({ "LOG": true }))
+                .is_err()
+        );
+        assert!("ccr".parse::<ExternalFormat>().is_ok());
+        assert!("cchub".parse::<ExternalFormat>().is_err());
+    }
+}
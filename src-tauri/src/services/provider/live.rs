@@ -3,22 +3,30 @@
 //! Handles reading and writing live configuration files for Claude, Codex, and Gemini.
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use serde_json::{json, Value};
 
 use crate::app_config::AppType;
-use crate::codex_config::{get_codex_auth_path, get_codex_config_path, normalize_codex_auth};
-use crate::config::{delete_file, get_claude_settings_path, read_json_file, write_json_file};
+use crate::codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_live_atomic};
+use crate::config::{
+    claude_profile_name, delete_file, get_claude_profile_path, get_claude_settings_path,
+    read_json_file, write_json_file,
+};
+use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
 use crate::services::antigravity::{apply_account_from_provider, has_official_credentials};
 use crate::services::mcp::McpService;
+use crate::settings::ClaudeWriteMode;
 use crate::store::AppState;
 
 use super::gemini_auth::{
     detect_gemini_auth_type, ensure_google_oauth_security_flag, GeminiAuthType,
 };
+use super::live_history::record_live_history;
 use super::normalize_claude_models_in_value;
+use super::settings_with_default_models;
 
 pub(crate) fn sanitize_claude_settings_for_live(settings: &Value) -> Value {
     let mut v = settings.clone();
@@ -32,6 +40,113 @@ pub(crate) fn sanitize_claude_settings_for_live(settings: &Value) -> Value {
     v
 }
 
+/// profiles 写入模式下由 profile 承载、需从 settings.json 中移除的顶层键
+const CLAUDE_PROVIDER_TOP_LEVEL_KEYS: [&str; 2] = ["model", "apiKeyHelper"];
+
+/// profiles 写入模式下供应商的 profile 文件路径
+fn claude_profile_path_for(provider_id: &str) -> Option<PathBuf> {
+    get_claude_profile_path(&claude_profile_name(provider_id))
+}
+
+/// Claude Live 配置的读取路径：profiles 模式下为当前供应商的 profile 文件，
+/// 没有当前供应商或文件不存在时为 settings.json
+pub(crate) fn get_claude_live_path() -> PathBuf {
+    if crate::settings::get_claude_write_mode() == ClaudeWriteMode::Profiles {
+        if let Some(path) = crate::settings::get_current_provider(&AppType::Claude)
+            .and_then(|id| claude_profile_path_for(&id))
+            .filter(|path| path.exists())
+        {
+            return path;
+        }
+    }
+    get_claude_settings_path()
+}
+
+/// 指定供应商的 Claude Live 配置写入路径（profiles 模式下为其 profile 文件）
+pub(crate) fn get_claude_live_path_for(provider_id: &str) -> PathBuf {
+    if crate::settings::get_claude_write_mode() == ClaudeWriteMode::Profiles {
+        if let Some(path) = claude_profile_path_for(provider_id) {
+            return path;
+        }
+    }
+    get_claude_settings_path()
+}
+
+/// 写入 Claude Live 配置
+///
+/// - replace 模式：整体重写 settings.json；
+/// - profiles 模式：完整配置写入 `profiles/<id>.json`，并从 settings.json 中移除
+///   供应商相关的 env 与模型键，其余用户设置保留。未指定供应商时写入当前供应商的 profile，
+///   两者都没有时按 replace 模式处理。
+pub(crate) fn write_claude_live(
+    provider_id: Option<&str>,
+    settings: &Value,
+) -> Result<(), AppError> {
+    let settings = sanitize_claude_settings_for_live(settings);
+    let settings_path = get_claude_settings_path();
+
+    if crate::settings::get_claude_write_mode() == ClaudeWriteMode::Profiles {
+        let profile_path = provider_id
+            .map(str::to_string)
+            .or_else(|| crate::settings::get_current_provider(&AppType::Claude))
+            .and_then(|id| claude_profile_path_for(&id));
+        if let Some(path) = profile_path {
+            write_json_file(&path, &settings)?;
+            return strip_claude_provider_keys(&settings_path);
+        }
+    }
+
+    write_json_file(&settings_path, &settings)
+}
+
+/// 从 settings.json 中移除供应商相关的 env（`ANTHROPIC_*`）与模型键，
+/// 供 replace 模式切换到 profiles 模式后清理残留（内容未变化时不写入）
+fn strip_claude_provider_keys(settings_path: &Path) -> Result<(), AppError> {
+    if !settings_path.exists() {
+        return Ok(());
+    }
+    let mut settings: Value = read_json_file(settings_path)?;
+    let Some(obj) = settings.as_object_mut() else {
+        return Err(AppError::localized(
+            "claude.live.not_object",
+            "Claude 配置文件格式错误：根节点必须是 JSON 对象",
+            "Claude settings file must contain a JSON object",
+        ));
+    };
+
+    let mut changed = false;
+    for key in CLAUDE_PROVIDER_TOP_LEVEL_KEYS {
+        changed |= obj.remove(key).is_some();
+    }
+    if let Some(env) = obj.get_mut("env").and_then(Value::as_object_mut) {
+        let before = env.len();
+        env.retain(|key, _| !key.starts_with("ANTHROPIC_"));
+        if env.len() != before {
+            changed = true;
+            if env.is_empty() {
+                obj.remove("env");
+            }
+        }
+    }
+
+    if changed {
+        write_json_file(settings_path, &settings)?;
+    }
+    Ok(())
+}
+
+/// 删除供应商对应的 Claude profile 文件（不存在时忽略）
+pub(crate) fn remove_claude_profile(provider_id: &str) {
+    let Some(path) = claude_profile_path_for(provider_id) else {
+        return;
+    };
+    if path.exists() {
+        if let Err(e) = delete_file(&path) {
+            log::warn!("删除 Claude profile 文件失败 ({}): {e}", path.display());
+        }
+    }
+}
+
 /// Live configuration snapshot for backup/restore
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -52,13 +167,18 @@ pub(crate) enum LiveSnapshot {
 impl LiveSnapshot {
     #[allow(dead_code)]
     pub(crate) fn restore(&self) -> Result<(), AppError> {
+        crate::settings::ensure_live_writable()?;
+
         match self {
             LiveSnapshot::Claude { settings } => {
-                let path = get_claude_settings_path();
+                // 按当前写入模式恢复（profiles 模式下恢复到当前供应商的 profile）
                 if let Some(value) = settings {
-                    write_json_file(&path, value)?;
-                } else if path.exists() {
-                    delete_file(&path)?;
+                    write_claude_live(None, value)?;
+                } else {
+                    let path = get_claude_live_path();
+                    if path.exists() {
+                        delete_file(&path)?;
+                    }
                 }
             }
             LiveSnapshot::Codex { auth, config } => {
@@ -106,16 +226,25 @@ impl LiveSnapshot {
 }
 
 /// Write live configuration snapshot for a provider
-pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+///
+/// 写入前会把当前 Live 文件内容记入历史（内容未变化时跳过）。
+pub(crate) fn write_live_snapshot(
+    db: &Database,
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<(), AppError> {
+    crate::settings::ensure_live_writable()?;
+    record_live_history(db, app_type);
+
+    // 供应商的默认模型覆盖 settings_config 中的对应字段（仅 Claude / Codex）
+    let settings = settings_with_default_models(app_type, provider);
+
     match app_type {
         AppType::Claude => {
-            let path = get_claude_settings_path();
-            let settings = sanitize_claude_settings_for_live(&provider.settings_config);
-            write_json_file(&path, &settings)?;
+            write_claude_live(Some(&provider.id), &settings)?;
         }
         AppType::Codex => {
-            let obj = provider
-                .settings_config
+            let obj = settings
                 .as_object()
                 .ok_or_else(|| AppError::Config("Codex 供应商配置必须是 JSON 对象".to_string()))?;
             let auth = obj
@@ -125,10 +254,9 @@ pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Re
                 AppError::Config("Codex 供应商配置缺少 'config' 字段或不是字符串".to_string())
             })?;
 
-            let auth_path = get_codex_auth_path();
-            write_json_file(&auth_path, &normalize_codex_auth(auth))?;
-            let config_path = get_codex_config_path();
-            std::fs::write(&config_path, config_str).map_err(|e| AppError::io(&config_path, e))?;
+            // config.toml 仅定点更新托管部分，保留 profiles 等用户自定义内容
+            let merged = crate::codex_config::merge_with_live_config(config_str)?;
+            write_codex_live_atomic(auth, Some(&merged))?;
         }
         AppType::Gemini => {
             // Delegate to write_gemini_live which handles env file writing correctly
@@ -204,7 +332,7 @@ fn sync_all_providers_to_live(state: &AppState, app_type: &AppType) -> Result<()
     let providers = state.db.get_all_providers(app_type.as_str())?;
 
     for provider in providers.values() {
-        if let Err(e) = write_live_snapshot(app_type, provider) {
+        if let Err(e) = write_live_snapshot(&state.db, app_type, provider) {
             log::warn!(
                 "Failed to sync {:?} provider '{}' to live: {e}",
                 app_type,
@@ -245,7 +373,7 @@ pub fn sync_current_to_live(state: &AppState) -> Result<(), AppError> {
 
             let providers = state.db.get_all_providers(app_type.as_str())?;
             if let Some(provider) = providers.get(&current_id) {
-                write_live_snapshot(&app_type, provider)?;
+                write_live_snapshot(&state.db, &app_type, provider)?;
             }
             // Note: get_effective_current_provider already validates existence,
             // so providers.get() should always succeed here
@@ -283,7 +411,7 @@ pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
             Ok(json!({ "auth": auth, "config": cfg_text }))
         }
         AppType::Claude => {
-            let path = get_claude_settings_path();
+            let path = get_claude_live_path();
             if !path.exists() {
                 return Err(AppError::localized(
                     "claude.live.missing",
@@ -371,7 +499,7 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
             json!({ "auth": auth, "config": config_str })
         }
         AppType::Claude => {
-            let settings_path = get_claude_settings_path();
+            let settings_path = get_claude_live_path();
             if !settings_path.exists() {
                 return Err(AppError::localized(
                     "claude.live.missing",
@@ -459,6 +587,8 @@ pub(crate) fn write_gemini_live(provider: &Provider) -> Result<(), AppError> {
         validate_gemini_settings_strict, write_gemini_env_atomic,
     };
 
+    crate::settings::ensure_live_writable()?;
+
     // One-time auth type detection to avoid repeated detection
     let auth_type = detect_gemini_auth_type(provider);
 
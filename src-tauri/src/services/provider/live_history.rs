@@ -0,0 +1,158 @@
+//! Live config history
+//!
+//! 每次写入 Live 配置前，把各应用 Live 文件的原始内容存入 `live_config_history`，
+//! 便于在错误配置覆盖 settings.json 等文件后回滚。快照为 JSON 对象（文件名 -> 原始文本），
+//! 仅包含当时存在的文件；去重与数量上限由数据库层处理。
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{backfill_codex_managed_config, read_live_settings, ProviderService};
+use crate::app_config::AppType;
+use crate::codex_config::{get_codex_auth_path, get_codex_config_path};
+use crate::config::{atomic_write, get_claude_settings_path};
+use crate::database::{Database, LiveConfigVersion};
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 各应用纳入历史的 Live 文件（快照中的文件名, 路径）
+pub(super) fn live_config_files(app_type: &AppType) -> Vec<(&'static str, PathBuf)> {
+    match app_type {
+        AppType::Claude => vec![("settings.json", get_claude_settings_path())],
+        AppType::Codex => vec![
+            ("auth.json", get_codex_auth_path()),
+            ("config.toml", get_codex_config_path()),
+        ],
+        AppType::Gemini => vec![
+            (".env", crate::gemini_config::get_gemini_env_path()),
+            (
+                "settings.json",
+                crate::gemini_config::get_gemini_settings_path(),
+            ),
+        ],
+        AppType::OpenCode => vec![(
+            "opencode.json",
+            crate::opencode_config::get_opencode_config_path(),
+        )],
+    }
+}
+
+/// 读取当前 Live 文件快照；所有文件都不存在时返回 None
+fn read_live_files(app_type: &AppType) -> Result<Option<String>, AppError> {
+    let mut files = BTreeMap::new();
+    for (name, path) in live_config_files(app_type) {
+        if !path.exists() {
+            continue;
+        }
+        let text = std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+        files.insert(name, text);
+    }
+    if files.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(&files)
+        .map(Some)
+        .map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+/// 将当前 Live 文件内容记入历史
+///
+/// 失败只记录日志，不阻塞后续的 Live 写入。
+pub(crate) fn record_live_history(db: &Database, app_type: &AppType) {
+    let result = read_live_files(app_type).and_then(|content| match content {
+        Some(content) => db
+            .record_live_config_version(app_type.as_str(), &content)
+            .map(|_| ()),
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        log::warn!("记录 {} Live 配置历史失败: {e}", app_type.as_str());
+    }
+}
+
+impl ProviderService {
+    /// List saved live config versions for an app (newest first)
+    pub fn list_live_config_history(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<LiveConfigVersion>, AppError> {
+        state.db.list_live_config_history(app_type.as_str())
+    }
+
+    /// Write a saved live config version back and backfill it into the current provider
+    ///
+    /// - 恢复前先把当前 Live 内容记入历史，恢复操作本身可再次回滚；
+    /// - 快照中不存在的文件保持不动（不会删除当前文件）；
+    /// - 切换模式应用会把恢复后的 Live 配置回填到当前供应商，OpenCode 为累加模式，不回填；
+    /// - 代理运行且该应用处于接管状态时拒绝，避免覆盖接管占位配置。
+    pub fn restore_live_config_version(
+        state: &AppState,
+        app_type: AppType,
+        version_id: i64,
+    ) -> Result<(), AppError> {
+        let version = state
+            .db
+            .get_live_config_version(app_type.as_str(), version_id)?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "live_history.not_found",
+                    format!("Live 配置历史版本 {version_id} 不存在"),
+                    format!("Live config history version {version_id} not found"),
+                )
+            })?;
+        let files: BTreeMap<String, String> = serde_json::from_str(&version.content)
+            .map_err(|e| AppError::Config(format!("Live 配置历史内容无效: {e}")))?;
+
+        crate::settings::ensure_live_writable()?;
+
+        let has_live_backup =
+            futures::executor::block_on(state.db.get_live_backup(app_type.as_str()))
+                .ok()
+                .flatten()
+                .is_some();
+        let live_taken_over = state
+            .proxy_service
+            .detect_takeover_in_live_config_for_app(&app_type);
+        if (has_live_backup || live_taken_over)
+            && futures::executor::block_on(state.proxy_service.is_running())
+        {
+            return Err(AppError::localized(
+                "live_history.proxy_takeover",
+                "该应用正由代理接管，请先停止代理再恢复 Live 配置",
+                "This app is taken over by the proxy. Stop the proxy before restoring its live config",
+            ));
+        }
+
+        record_live_history(&state.db, &app_type);
+        for (name, path) in live_config_files(&app_type) {
+            if let Some(text) = files.get(name) {
+                atomic_write(&path, text.as_bytes())?;
+            }
+        }
+        log::info!(
+            "已将 {} Live 配置恢复到历史版本 {version_id}",
+            app_type.as_str()
+        );
+
+        if app_type.is_additive_mode() {
+            return Ok(());
+        }
+        let Some(current_id) =
+            crate::settings::get_effective_current_provider(&state.db, &app_type)?
+        else {
+            return Ok(());
+        };
+        let Some(mut provider) = state
+            .db
+            .get_provider_by_id(&current_id, app_type.as_str())?
+        else {
+            return Ok(());
+        };
+        let mut live_config = read_live_settings(app_type.clone())?;
+        if matches!(app_type, AppType::Codex) {
+            backfill_codex_managed_config(&provider.settings_config, &mut live_config);
+        }
+        provider.settings_config = live_config;
+        state.db.save_provider(app_type.as_str(), &provider)
+    }
+}
@@ -2,39 +2,65 @@
 //!
 //! Handles provider CRUD operations, switching, and configuration management.
 
+mod audit;
+mod credentials;
+mod default_models;
 mod endpoints;
+mod env_import;
+mod external_import;
 mod gemini_auth;
 mod live;
+mod live_history;
+mod snippets;
+mod switch_txn;
 mod usage;
 
 use indexmap::IndexMap;
-use regex::Regex;
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::app_config::AppType;
 use crate::error::AppError;
-use crate::provider::{Provider, UsageResult};
+use crate::field_error::FieldError;
+use crate::provider::{Provider, ProviderSummary, UsageResult};
 use crate::services::mcp::McpService;
+use crate::services::stream_check::StreamCheckService;
 use crate::settings::CustomEndpoint;
 use crate::store::AppState;
+use crate::usage_script::ScriptSandbox;
 
 // Re-export sub-module functions for external access
+pub use audit::{AuditAction, AuditSource};
+pub use credentials::{CredentialIssue, CredentialIssueKind};
+pub use env_import::{EnvImportFound, EnvImportResult};
+pub use external_import::{ExternalFormat, ExternalImportResult, ExternalImportedProvider};
 pub use live::{
     import_default_config, import_opencode_providers_from_live, read_live_settings,
     sync_current_to_live,
 };
 
 // Internal re-exports (pub(crate))
+pub(crate) use audit::record as record_provider_audit;
 pub(crate) use live::sanitize_claude_settings_for_live;
 pub(crate) use live::write_live_snapshot;
+pub(crate) use live::{get_claude_live_path, get_claude_live_path_for, write_claude_live};
 
 // Internal re-exports
+use default_models::{
+    settings_with_default_models, strip_injected_default_models, validate_default_models,
+};
 use live::{
-    apply_gemini_runtime_side_effects, remove_opencode_provider_from_live, write_gemini_live,
+    apply_gemini_runtime_side_effects, remove_claude_profile, remove_opencode_provider_from_live,
+    write_gemini_live,
 };
 use usage::validate_usage_script;
 
+/// 切换前健康检查的超时时间
+const VERIFY_BEFORE_SWITCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 切换前健康检查失败时返回的响应内容最大字符数
+const VERIFY_ERROR_SNIPPET_CHARS: usize = 500;
+
 /// Provider business logic service
 pub struct ProviderService;
 
@@ -59,6 +85,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_provider_settings_surfaces_first_gemini_field_error() {
+        // 缺少 API Key 只是警告，不影响保存；非法 base URL 才会阻止
+        let provider = Provider::with_id(
+            "gemini".into(),
+            "Gemini".into(),
+            json!({ "env": { "GOOGLE_GEMINI_BASE_URL": "ftp//bad" } }),
+            None,
+        );
+        let err = ProviderService::validate_provider_settings(&AppType::Gemini, &provider)
+            .expect_err("malformed base url should be rejected");
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "gemini.validation.invalid_base_url",
+                ..
+            }
+        ));
+
+        let problems = ProviderService::validate_config(
+            AppType::Gemini,
+            json!({ "env": { "GOOGLE_GEMINI_BASE_URL": "ftp//bad" } }),
+        );
+        assert_eq!(
+            problems.len(),
+            2,
+            "expected base url error + api key warning"
+        );
+    }
+
+    #[test]
+    fn parse_universal_child_id_requires_matching_app() {
+        let id = "universal-claude-7f1c2d3e-0000-4000-8000-000000000001";
+        assert_eq!(
+            parse_universal_child_id(&AppType::Claude, id),
+            Some("7f1c2d3e-0000-4000-8000-000000000001")
+        );
+        assert_eq!(parse_universal_child_id(&AppType::Codex, id), None);
+        assert_eq!(
+            parse_universal_child_id(&AppType::Claude, "universal-claude-"),
+            None
+        );
+        assert_eq!(parse_universal_child_id(&AppType::Claude, "claude-1"), None);
+    }
+
     #[test]
     fn extract_credentials_returns_expected_values() {
         let provider = Provider::with_id(
@@ -78,6 +149,30 @@ mod tests {
         assert_eq!(base_url, "https://claude.example");
     }
 
+    #[test]
+    fn extract_credentials_uses_active_codex_provider_base_url() {
+        let provider = Provider::with_id(
+            "codex".into(),
+            "Codex".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-test" },
+                "config": r#"model_provider = "azure"
+
+[mcp_servers.my_server]
+base_url = "http://localhost:8080"
+
+[model_providers.azure]
+base_url = "https://azure.example/v1"
+"#
+            }),
+            None,
+        );
+        let (api_key, base_url) =
+            ProviderService::extract_credentials(&provider, &AppType::Codex).unwrap();
+        assert_eq!(api_key, "sk-test");
+        assert_eq!(base_url, "https://azure.example/v1");
+    }
+
     #[test]
     fn extract_codex_common_config_preserves_mcp_servers_base_url() {
         let config_toml = r#"model_provider = "azure"
@@ -138,6 +233,90 @@ impl ProviderService {
         state.db.get_all_providers(app_type.as_str())
     }
 
+    /// List lightweight provider summaries (no settings_config) for list views
+    ///
+    /// is_current 使用与 [`Self::current`] 相同的有效当前供应商判定。
+    pub fn list_summaries(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<ProviderSummary>, AppError> {
+        let mut summaries = state.db.get_provider_summaries(app_type.as_str())?;
+        let current = Self::current(state, app_type)?;
+        for summary in &mut summaries {
+            summary.is_current = !current.is_empty() && summary.id == current;
+        }
+        Ok(summaries)
+    }
+
+    /// Get the full settings_config of a single provider (on demand, for the edit dialog)
+    pub fn get_settings_config(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+    ) -> Result<Value, AppError> {
+        state
+            .db
+            .get_provider_settings_config(id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })
+    }
+
+    /// Validate provider settings and return all field-level problems (for the editor)
+    ///
+    /// Gemini 使用结构化校验返回全部问题（含警告）；其他应用沿用现有校验，
+    /// 最多返回一个错误。
+    pub fn validate_config(app_type: AppType, settings_config: Value) -> Vec<FieldError> {
+        if matches!(app_type, AppType::Gemini) {
+            return crate::gemini_config::validate_gemini_settings_detailed(&settings_config)
+                .err()
+                .unwrap_or_default();
+        }
+
+        let provider = Provider::with_id(String::new(), String::new(), settings_config, None);
+        match Self::validate_provider_settings(&app_type, &provider) {
+            Ok(()) => Vec::new(),
+            Err(err) => vec![FieldError::from_app_error(&err)],
+        }
+    }
+
+    /// Search providers by name / notes / website URL, optionally filtered by category
+    ///
+    /// 匹配不区分大小写；`query` 为空时仅按 `category` 过滤。
+    pub fn search(
+        state: &AppState,
+        app_type: AppType,
+        query: &str,
+        category: Option<&str>,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        let needle = query.trim().to_lowercase();
+        let category = category.map(str::trim).filter(|c| !c.is_empty());
+
+        let providers = Self::list(state, app_type)?;
+        Ok(providers
+            .into_iter()
+            .filter(|(_, provider)| {
+                category.is_none_or(|c| provider.category.as_deref() == Some(c))
+            })
+            .filter(|(_, provider)| needle.is_empty() || Self::provider_matches(provider, &needle))
+            .collect())
+    }
+
+    fn provider_matches(provider: &Provider, needle: &str) -> bool {
+        [
+            Some(provider.name.as_str()),
+            provider.notes.as_deref(),
+            provider.website_url.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(needle))
+    }
+
     /// Get current provider ID
     ///
     /// 使用有效的当前供应商 ID（验证过存在性）。
@@ -156,6 +335,16 @@ impl ProviderService {
 
     /// Add a new provider
     pub fn add(state: &AppState, app_type: AppType, provider: Provider) -> Result<bool, AppError> {
+        Self::add_with_source(state, app_type, provider, AuditSource::Ui)
+    }
+
+    /// Add a new provider, recording `source` in the audit log
+    pub fn add_with_source(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
+        source: AuditSource,
+    ) -> Result<bool, AppError> {
         let mut provider = provider;
         // Normalize Claude model keys
         Self::normalize_provider_if_claude(&app_type, &mut provider);
@@ -163,10 +352,18 @@ impl ProviderService {
 
         // Save to database
         state.db.save_provider(app_type.as_str(), &provider)?;
+        audit::record(
+            &state.db,
+            app_type.as_str(),
+            &provider.id,
+            AuditAction::Add,
+            source,
+        );
 
         // OpenCode uses additive mode - always write to live config
         if matches!(app_type, AppType::OpenCode) {
-            write_live_snapshot(&app_type, &provider)?;
+            write_live_snapshot(&state.db, &app_type, &provider)?;
+            Self::retake_opencode_if_proxied(state, &provider)?;
             return Ok(true);
         }
 
@@ -177,12 +374,144 @@ impl ProviderService {
             state
                 .db
                 .set_current_provider(app_type.as_str(), &provider.id)?;
-            write_live_snapshot(&app_type, &provider)?;
+            write_live_snapshot(&state.db, &app_type, &provider)?;
         }
 
         Ok(true)
     }
 
+    /// Add or update multiple providers (upsert by id)
+    ///
+    /// 先校验全部条目，任一失败则不写入；随后已存在的走 `update`，其余走 `add`，
+    /// 因此 Live 同步规则与单个添加/编辑一致。
+    pub fn add_batch(
+        state: &AppState,
+        app_type: AppType,
+        providers: Vec<Provider>,
+    ) -> Result<usize, AppError> {
+        let mut providers = providers;
+        for provider in &mut providers {
+            Self::normalize_provider_if_claude(&app_type, provider);
+            Self::validate_provider_settings(&app_type, provider)?;
+        }
+
+        let existing = state.db.get_all_providers(app_type.as_str())?;
+        let count = providers.len();
+        for provider in providers {
+            if existing.contains_key(&provider.id) {
+                Self::update(state, app_type.clone(), provider)?;
+            } else {
+                Self::add(state, app_type.clone(), provider)?;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Import providers from another tool's config file
+    ///
+    /// 解析后按应用分组调用 `add_batch`；无法映射的字段与条目在结果中返回。
+    pub fn import_external(
+        state: &AppState,
+        format: ExternalFormat,
+        path: &std::path::Path,
+    ) -> Result<ExternalImportResult, AppError> {
+        let config: Value = crate::config::read_json_file(path)?;
+        let parsed = external_import::parse_external_config(format, &config)?;
+
+        let mut result = ExternalImportResult {
+            skipped: parsed.skipped,
+            unmapped: parsed.unmapped,
+            ..Default::default()
+        };
+        let mut grouped: IndexMap<String, (AppType, Vec<Provider>)> = IndexMap::new();
+        for (app_type, provider) in parsed.providers {
+            result.imported.push(ExternalImportedProvider {
+                app_type: app_type.as_str().to_string(),
+                id: provider.id.clone(),
+                name: provider.name.clone(),
+            });
+            grouped
+                .entry(app_type.as_str().to_string())
+                .or_insert_with(|| (app_type, Vec::new()))
+                .1
+                .push(provider);
+        }
+        for (_, (app_type, providers)) in grouped {
+            Self::add_batch(state, app_type, providers)?;
+        }
+
+        Ok(result)
+    }
+
+    /// OpenCode 处于代理接管时，将刚写入 Live 的条目重新指向代理，并同步更新备份
+    fn retake_opencode_if_proxied(state: &AppState, provider: &Provider) -> Result<(), AppError> {
+        let is_taken_over = futures::executor::block_on(state.db.get_live_backup("opencode"))
+            .ok()
+            .flatten()
+            .is_some();
+        if !is_taken_over || !futures::executor::block_on(state.proxy_service.is_running()) {
+            return Ok(());
+        }
+        futures::executor::block_on(state.proxy_service.retake_opencode_provider(provider))
+            .map_err(|e| AppError::Message(format!("更新 OpenCode 代理接管失败: {e}")))
+    }
+
+    /// 扫描所有供应商的凭据问题（API Key 为空、令牌已过期、缺少 Base URL），只读
+    pub fn audit_credentials(state: &AppState) -> Result<Vec<CredentialIssue>, AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let mut issues = Vec::new();
+        for app_type in AppType::all() {
+            for provider in state.db.get_all_providers(app_type.as_str())?.values() {
+                issues.extend(credentials::audit_provider(&app_type, provider, now));
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Import a provider from the current shell environment
+    ///
+    /// 缺少必需的密钥变量时不写库，只返回找到/缺失的变量；已存在相同 base_url + key 的供应商时
+    /// 直接返回其 ID，不会重复创建。新供应商走 `add`，因此校验与 Live 同步规则一致。
+    pub fn import_from_env(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<EnvImportResult, AppError> {
+        let vars = env_import::collect_env_vars(&app_type);
+        Self::import_from_env_vars(state, app_type, &vars)
+    }
+
+    pub(crate) fn import_from_env_vars(
+        state: &AppState,
+        app_type: AppType,
+        vars: &[crate::services::env_checker::EnvConflict],
+    ) -> Result<EnvImportResult, AppError> {
+        let draft = env_import::build_env_import(&app_type, vars)?;
+        let mut result = EnvImportResult {
+            found: draft.found,
+            missing: draft.missing,
+            ..Default::default()
+        };
+        let Some(provider) = draft.provider else {
+            return Ok(result);
+        };
+
+        let key = env_import::dedup_key(&app_type, &provider);
+        let existing = state.db.get_all_providers(app_type.as_str())?;
+        if let Some(duplicate) = existing
+            .values()
+            .find(|p| key.is_some() && env_import::dedup_key(&app_type, p) == key)
+        {
+            result.provider_id = Some(duplicate.id.clone());
+            return Ok(result);
+        }
+
+        result.provider_id = Some(provider.id.clone());
+        Self::add(state, app_type, provider)?;
+        result.created = true;
+        Ok(result)
+    }
+
     /// Update a provider
     pub fn update(
         state: &AppState,
@@ -194,12 +523,33 @@ impl ProviderService {
         Self::normalize_provider_if_claude(&app_type, &mut provider);
         Self::validate_provider_settings(&app_type, &provider)?;
 
-        // Save to database
+        // Save to database (keep the previous config for the audit diff)
+        let previous = state
+            .db
+            .get_provider_by_id(&provider.id, app_type.as_str())?;
         state.db.save_provider(app_type.as_str(), &provider)?;
+        match previous {
+            Some(previous) => audit::record_update(
+                &state.db,
+                app_type.as_str(),
+                &provider.id,
+                &previous.settings_config,
+                &provider.settings_config,
+                AuditSource::Ui,
+            ),
+            None => audit::record(
+                &state.db,
+                app_type.as_str(),
+                &provider.id,
+                AuditAction::Add,
+                AuditSource::Ui,
+            ),
+        }
 
         // OpenCode uses additive mode - always update in live config
         if matches!(app_type, AppType::OpenCode) {
-            write_live_snapshot(&app_type, &provider)?;
+            write_live_snapshot(&state.db, &app_type, &provider)?;
+            Self::retake_opencode_if_proxied(state, &provider)?;
             return Ok(true);
         }
 
@@ -228,7 +578,7 @@ impl ProviderService {
                 )
                 .map_err(|e| AppError::Message(format!("更新 Live 备份失败: {e}")))?;
             } else {
-                write_live_snapshot(&app_type, &provider)?;
+                write_live_snapshot(&state.db, &app_type, &provider)?;
                 // Sync MCP
                 McpService::sync_all_enabled(state)?;
             }
@@ -246,6 +596,13 @@ impl ProviderService {
         if matches!(app_type, AppType::OpenCode) {
             // Remove from database
             state.db.delete_provider(app_type.as_str(), id)?;
+            audit::record(
+                &state.db,
+                app_type.as_str(),
+                id,
+                AuditAction::Delete,
+                AuditSource::Ui,
+            );
             // Also remove from live config
             remove_opencode_provider_from_live(id)?;
             return Ok(());
@@ -261,7 +618,18 @@ impl ProviderService {
             ));
         }
 
-        state.db.delete_provider(app_type.as_str(), id)
+        state.db.delete_provider(app_type.as_str(), id)?;
+        audit::record(
+            &state.db,
+            app_type.as_str(),
+            id,
+            AuditAction::Delete,
+            AuditSource::Ui,
+        );
+        if matches!(app_type, AppType::Claude) {
+            remove_claude_profile(id);
+        }
+        Ok(())
     }
 
     /// Remove provider from live config only (for additive mode apps like OpenCode)
@@ -285,6 +653,73 @@ impl ProviderService {
         Ok(())
     }
 
+    /// Force-rewrite the live config from the provider's stored `settings_config`
+    ///
+    /// Used when the live file was corrupted by another tool. Unlike `switch`, no backfill
+    /// is performed, so the broken live content never reaches the database.
+    /// - 代理运行且该应用处于接管状态时拒绝，避免覆盖接管占位配置；
+    /// - 代理已停止但仍检测到接管残留（Live 备份或占位配置）时，需要 `confirmed` 才会写入；
+    /// - 切换模式应用只允许恢复当前供应商。
+    pub fn restore_live_from_stored(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        confirmed: bool,
+    ) -> Result<(), AppError> {
+        let provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+
+        if !app_type.is_additive_mode() {
+            let current = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+            if current.as_deref() != Some(id) {
+                return Err(AppError::localized(
+                    "provider.restore_live.not_current",
+                    "只能恢复当前供应商的 Live 配置",
+                    "Only the current provider's live config can be restored",
+                ));
+            }
+        }
+
+        let has_live_backup =
+            futures::executor::block_on(state.db.get_live_backup(app_type.as_str()))
+                .ok()
+                .flatten()
+                .is_some();
+        let live_taken_over = state
+            .proxy_service
+            .detect_takeover_in_live_config_for_app(&app_type);
+        if has_live_backup || live_taken_over {
+            if futures::executor::block_on(state.proxy_service.is_running()) {
+                return Err(AppError::localized(
+                    "provider.restore_live.proxy_takeover",
+                    "该应用正由代理接管，请先停止代理再恢复 Live 配置",
+                    "This app is taken over by the proxy. Stop the proxy before restoring its live config",
+                ));
+            }
+            if !confirmed {
+                return Err(AppError::localized(
+                    "provider.restore_live.confirm_required",
+                    "检测到代理接管残留，确认后将直接覆盖 Live 配置",
+                    "Leftover proxy takeover state detected. Confirm to overwrite the live config anyway",
+                ));
+            }
+            log::warn!(
+                "{} 存在代理接管残留，按确认强制恢复 Live 配置",
+                app_type.as_str()
+            );
+        }
+
+        write_live_snapshot(&state.db, &app_type, &provider)?;
+        log::info!(
+            "已从数据库恢复 {} 供应商 {} 的 Live 配置",
+            app_type.as_str(),
+            id
+        );
+        Ok(())
+    }
+
     /// Switch to a provider
     ///
     /// Switch flow:
@@ -298,12 +733,78 @@ impl ProviderService {
     ///    d. Write target provider config to live files
     ///    e. Sync MCP configuration
     pub fn switch(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
+        Self::switch_with_options(state, app_type, id, false)
+    }
+
+    /// Claude 企业托管配置会覆盖该供应商写入的哪些字段
+    ///
+    /// 非 Claude、没有托管配置或没有冲突时返回 None。
+    pub fn managed_settings_override(
+        state: &AppState,
+        app_type: &AppType,
+        id: &str,
+    ) -> Result<Option<crate::claude_managed::ManagedSettingsStatus>, AppError> {
+        if !matches!(app_type, AppType::Claude) {
+            return Ok(None);
+        }
+        let Some(provider) = state.db.get_provider_by_id(id, app_type.as_str())? else {
+            return Ok(None);
+        };
+        let settings =
+            sanitize_claude_settings_for_live(&settings_with_default_models(app_type, &provider));
+        Ok(crate::claude_managed::managed_settings_status(&settings)
+            .filter(|status| !status.overridden_keys.is_empty()))
+    }
+
+    /// Switch to a provider, optionally bypassing the pre-switch health probe
+    ///
+    /// When `settings.verify_before_switch` is enabled and `force` is false, the target
+    /// provider is probed first and the switch is aborted with
+    /// `AppError::ProviderVerificationFailed` if the probe fails.
+    pub fn switch_with_options(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        force: bool,
+    ) -> Result<(), AppError> {
+        Self::switch_with_source(state, app_type, id, force, AuditSource::Ui)
+    }
+
+    /// Switch to a provider, recording `source` in the audit log on success
+    pub fn switch_with_source(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        force: bool,
+        source: AuditSource,
+    ) -> Result<(), AppError> {
+        Self::perform_switch(state, app_type.clone(), id, force)?;
+        audit::record(
+            &state.db,
+            app_type.as_str(),
+            id,
+            AuditAction::Switch,
+            source,
+        );
+        Ok(())
+    }
+
+    fn perform_switch(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        force: bool,
+    ) -> Result<(), AppError> {
         // Check if provider exists
         let providers = state.db.get_all_providers(app_type.as_str())?;
-        let _provider = providers
+        let target = providers
             .get(id)
             .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
 
+        if !force && crate::settings::get_settings().verify_before_switch {
+            Self::verify_before_switch(state, &app_type, target)?;
+        }
+
         // Check if proxy takeover mode is active AND proxy server is actually running
         // Both conditions must be true to use hot-switch mode
         // Use blocking wait since this is a sync function
@@ -373,6 +874,63 @@ impl ProviderService {
         Self::switch_normal(state, app_type, id, &providers)
     }
 
+    /// Probe the target provider before switching and record the result in provider_health
+    ///
+    /// The probe always targets the provider's own base_url (never the local proxy),
+    /// so it behaves the same in proxy takeover hot-switch mode.
+    fn verify_before_switch(
+        state: &AppState,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
+        let config = state.db.get_stream_check_config()?;
+
+        // Run on a dedicated thread: the caller may already be inside an async runtime
+        let result = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    tauri::async_runtime::block_on(StreamCheckService::probe(
+                        app_type,
+                        provider,
+                        &config,
+                        VERIFY_BEFORE_SWITCH_TIMEOUT,
+                    ))
+                })
+                .join()
+        })
+        .map_err(|_| AppError::Message("切换前健康检查线程异常退出".to_string()))?;
+
+        let (success, http_status, message) = match &result {
+            Ok(r) => (r.success, r.http_status, r.message.clone()),
+            Err(e) => (false, None, e.to_string()),
+        };
+
+        let error_msg = (!success).then(|| message.clone());
+        if let Err(e) = futures::executor::block_on(state.db.update_provider_health(
+            &provider.id,
+            app_type.as_str(),
+            success,
+            error_msg,
+        )) {
+            log::warn!("记录切换前健康检查结果失败: {e}");
+        }
+
+        if success {
+            return Ok(());
+        }
+
+        log::warn!(
+            "切换前健康检查失败，取消切换到 {}: {}",
+            provider.id,
+            message
+        );
+        Err(AppError::ProviderVerificationFailed {
+            provider_id: provider.id.clone(),
+            http_status,
+            message: message.chars().take(VERIFY_ERROR_SNIPPET_CHARS).collect(),
+        })
+    }
+
     /// Normal switch flow (non-proxy mode)
     fn switch_normal(
         state: &AppState,
@@ -388,14 +946,31 @@ impl ProviderService {
         // Use effective current provider (validated existence) to ensure backfill targets valid provider
         let current_id = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
 
-        if let Some(current_id) = current_id {
+        // Backfill can be disabled per app (e.g. when the live file was intentionally broken)
+        // 只读模式下 Live 不随切换更新，回填会把旧内容写进其他供应商，因此一并跳过
+        let read_only = crate::settings::is_read_only_mode();
+        let backfill_enabled =
+            !read_only && state.db.get_backfill_on_switch_enabled(app_type.as_str())?;
+
+        if let Some(current_id) = current_id.filter(|_| backfill_enabled) {
             if current_id != id {
                 // OpenCode uses additive mode - all providers coexist in the same file,
                 // no backfill needed (backfill is for exclusive mode apps like Claude/Codex/Gemini)
                 if !matches!(app_type, AppType::OpenCode) {
                     // Only backfill when switching to a different provider
-                    if let Ok(live_config) = read_live_settings(app_type.clone()) {
+                    if let Ok(mut live_config) = read_live_settings(app_type.clone()) {
                         if let Some(mut current_provider) = providers.get(&current_id).cloned() {
+                            if matches!(app_type, AppType::Codex) {
+                                backfill_codex_managed_config(
+                                    &current_provider.settings_config,
+                                    &mut live_config,
+                                );
+                            }
+                            strip_injected_default_models(
+                                &app_type,
+                                &current_provider,
+                                &mut live_config,
+                            );
                             current_provider.settings_config = live_config;
                             // Ignore backfill failure, don't affect switch flow
                             let _ = state.db.save_provider(app_type.as_str(), &current_provider);
@@ -405,18 +980,24 @@ impl ProviderService {
             }
         }
 
-        // OpenCode uses additive mode - skip setting is_current (no such concept)
-        if !matches!(app_type, AppType::OpenCode) {
-            // Update local settings (device-level, takes priority)
-            crate::settings::set_current_provider(&app_type, Some(id))?;
-
-            // Update database is_current (as default for new devices)
-            state.db.set_current_provider(app_type.as_str(), id)?;
+        // 本地设置（设备级，优先）、数据库 is_current 与 Live 文件作为一个整体更新，
+        // 任一步失败都会回滚到切换前的供应商；OpenCode 为叠加模式，不设置 is_current
+        // 只读模式：仅更新当前供应商标记，不写入 Live 配置与 MCP
+        switch_txn::switch_current_provider(
+            &state.db,
+            &app_type,
+            provider,
+            !read_only,
+            &switch_txn::DefaultSwitchSteps,
+        )?;
+        if read_only {
+            log::warn!(
+                "只读模式：已将 {} 当前供应商标记为 {id}，未写入 Live 配置",
+                app_type.as_str()
+            );
+            return Ok(());
         }
 
-        // Sync to live (write_gemini_live handles security flag internally for Gemini)
-        write_live_snapshot(&app_type, provider)?;
-
         // Codex: clear local CLI cache to ensure new token is used immediately
         if matches!(app_type, AppType::Codex) {
             if let Err(e) = crate::services::codex_cache::clear_codex_auth_cache() {
@@ -559,22 +1140,7 @@ impl ProviderService {
         root.remove("model_providers");
 
         // Clean up multiple empty lines (keep at most one blank line).
-        let mut cleaned = String::new();
-        let mut blank_run = 0usize;
-        for line in doc.to_string().lines() {
-            if line.trim().is_empty() {
-                blank_run += 1;
-                if blank_run <= 1 {
-                    cleaned.push('\n');
-                }
-                continue;
-            }
-            blank_run = 0;
-            cleaned.push_str(line);
-            cleaned.push('\n');
-        }
-
-        Ok(cleaned.trim().to_string())
+        Ok(crate::codex_config::collapse_blank_lines(&doc.to_string()))
     }
 
     /// Extract common config for Gemini (JSON format)
@@ -683,6 +1249,11 @@ impl ProviderService {
         endpoints::update_endpoint_last_used(state, app_type, provider_id, url)
     }
 
+    /// Merge duplicate custom endpoints across all providers (re-export)
+    pub fn dedupe_custom_endpoints(state: &AppState) -> Result<usize, AppError> {
+        endpoints::dedupe_custom_endpoints(state)
+    }
+
     /// Update provider sort order
     pub fn update_sort_order(
         state: &AppState,
@@ -701,6 +1272,82 @@ impl ProviderService {
         Ok(true)
     }
 
+    /// Get the provider-specific cost multiplier (`None` = use the app default)
+    pub fn get_cost_multiplier(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+    ) -> Result<Option<String>, AppError> {
+        let provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        Ok(provider.meta.and_then(|meta| meta.cost_multiplier))
+    }
+
+    /// Set or clear the provider-specific cost multiplier
+    ///
+    /// 倍率须为正数，以文本形式保存在 `meta.costMultiplier`；传入 `None` 或空字符串时清除，
+    /// 回退到应用默认倍率。只影响之后记录的请求，历史日志保留各自记录时的倍率。
+    pub fn set_cost_multiplier(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        multiplier: Option<&str>,
+    ) -> Result<(), AppError> {
+        let multiplier = match multiplier.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => {
+                let parsed = value.parse::<rust_decimal::Decimal>().map_err(|e| {
+                    AppError::localized(
+                        "error.invalidMultiplier",
+                        format!("无效倍率: {value} - {e}"),
+                        format!("Invalid multiplier: {value} - {e}"),
+                    )
+                })?;
+                if parsed <= rust_decimal::Decimal::ZERO {
+                    return Err(AppError::localized(
+                        "error.multiplierNotPositive",
+                        format!("倍率必须大于 0: {value}"),
+                        format!("Multiplier must be greater than 0: {value}"),
+                    ));
+                }
+                Some(value.to_string())
+            }
+            None => None,
+        };
+
+        let mut provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .cost_multiplier = multiplier;
+        state.db.save_provider(app_type.as_str(), &provider)
+    }
+
+    /// Normalize legacy Claude model keys for every stored Claude provider
+    ///
+    /// 对旧版本保存的供应商执行 `normalize_claude_models_in_value`（迁移
+    /// `ANTHROPIC_SMALL_FAST_MODEL` 并补齐 `ANTHROPIC_DEFAULT_*`），只重新保存有变化的条目，
+    /// 返回更新数量。仅修改数据库，不写 Live 配置。
+    pub fn normalize_all_claude_providers(state: &AppState) -> Result<usize, AppError> {
+        let providers = state.db.get_all_providers(AppType::Claude.as_str())?;
+
+        let mut count = 0;
+        for (_, mut provider) in providers {
+            if normalize_claude_models_in_value(&mut provider.settings_config) {
+                state
+                    .db
+                    .save_provider(AppType::Claude.as_str(), &provider)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
     /// Query provider usage (re-export)
     pub async fn query_usage(
         state: &AppState,
@@ -717,24 +1364,24 @@ impl ProviderService {
         app_type: AppType,
         provider_id: &str,
         script_code: &str,
-        timeout: u64,
         api_key: Option<&str>,
         base_url: Option<&str>,
         access_token: Option<&str>,
         user_id: Option<&str>,
         template_type: Option<&str>,
+        sandbox: &ScriptSandbox,
     ) -> Result<UsageResult, AppError> {
         usage::test_usage_script(
             state,
             app_type,
             provider_id,
             script_code,
-            timeout,
             api_key,
             base_url,
             access_token,
             user_id,
             template_type,
+            sandbox,
         )
         .await
     }
@@ -795,8 +1442,15 @@ impl ProviderService {
                 }
             }
             AppType::Gemini => {
-                use crate::gemini_config::validate_gemini_settings;
-                validate_gemini_settings(&provider.settings_config)?
+                use crate::gemini_config::{validate_gemini_settings_detailed, FieldSeverity};
+                if let Err(problems) = validate_gemini_settings_detailed(&provider.settings_config)
+                {
+                    if let Some(first) =
+                        problems.iter().find(|p| p.severity == FieldSeverity::Error)
+                    {
+                        return Err(first.to_app_error());
+                    }
+                }
             }
             AppType::OpenCode => {
                 // OpenCode uses a different config structure: { npm, options, models }
@@ -817,6 +1471,7 @@ impl ProviderService {
                 validate_usage_script(usage_script)?;
             }
         }
+        validate_default_models(app_type, provider)?;
 
         Ok(())
     }
@@ -898,31 +1553,21 @@ impl ProviderService {
                     .and_then(|v| v.as_str())
                     .unwrap_or("");
 
-                let base_url = if config_toml.contains("base_url") {
-                    let re = Regex::new(r#"base_url\s*=\s*["']([^"']+)["']"#).map_err(|e| {
+                let base_url = crate::codex_config::get_active_base_url(config_toml)
+                    .map_err(|_| {
                         AppError::localized(
-                            "provider.regex_init_failed",
-                            format!("正则初始化失败: {e}"),
-                            format!("Failed to initialize regex: {e}"),
+                            "provider.codex.base_url.invalid",
+                            "config.toml 中 base_url 格式错误",
+                            "base_url in config.toml has invalid format",
+                        )
+                    })?
+                    .ok_or_else(|| {
+                        AppError::localized(
+                            "provider.codex.base_url.missing",
+                            "config.toml 中缺少 base_url 配置",
+                            "base_url is missing from config.toml",
                         )
                     })?;
-                    re.captures(config_toml)
-                        .and_then(|caps| caps.get(1))
-                        .map(|m| m.as_str().to_string())
-                        .ok_or_else(|| {
-                            AppError::localized(
-                                "provider.codex.base_url.invalid",
-                                "config.toml 中 base_url 格式错误",
-                                "base_url in config.toml has invalid format",
-                            )
-                        })?
-                } else {
-                    return Err(AppError::localized(
-                        "provider.codex.base_url.missing",
-                        "config.toml 中缺少 base_url 配置",
-                        "base_url is missing from config.toml",
-                    ));
-                };
 
                 Ok((api_key, base_url))
             }
@@ -984,6 +1629,21 @@ impl ProviderService {
     }
 }
 
+/// Codex 回填只取 Live config.toml 中的托管部分（顶层键值、当前供应商表），
+/// 其余内容沿用供应商已保存的配置，避免把 profiles、MCP 等 Live 专属内容写进供应商
+fn backfill_codex_managed_config(stored: &Value, live: &mut Value) {
+    let Some(stored_config) = stored.get("config").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(live_config) = live.get("config").and_then(Value::as_str) else {
+        return;
+    };
+    match crate::codex_config::merge_codex_config(stored_config, live_config) {
+        Ok(merged) => live["config"] = Value::String(merged),
+        Err(e) => log::warn!("回填 Codex 配置时合并失败，使用完整 Live 配置: {e}"),
+    }
+}
+
 /// Normalize Claude model keys in a JSON value
 ///
 /// Reads old key (ANTHROPIC_SMALL_FAST_MODEL), writes new keys (DEFAULT_*), and deletes old key.
@@ -1070,8 +1730,31 @@ pub struct ProviderSortUpdate {
 // ============================================================================
 
 use crate::provider::UniversalProvider;
+use serde::Serialize;
 use std::collections::HashMap;
 
+/// 父统一供应商已不存在的子供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedUniversalChild {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 从子供应商 ID 解析出的统一供应商 ID
+    pub universal_id: String,
+    /// 是否为该应用的当前供应商（清理时会跳过）
+    pub is_current: bool,
+}
+
+/// 解析 `universal-<app>-<id>` 形式的子供应商 ID，返回统一供应商 ID
+fn parse_universal_child_id<'a>(app_type: &AppType, provider_id: &'a str) -> Option<&'a str> {
+    provider_id
+        .strip_prefix("universal-")?
+        .strip_prefix(app_type.as_str())?
+        .strip_prefix('-')
+        .filter(|id| !id.is_empty())
+}
+
 impl ProviderService {
     /// 获取所有统一供应商
     pub fn list_universal(
@@ -1110,16 +1793,13 @@ impl ProviderService {
         // 删除生成的子供应商
         if let Some(p) = provider {
             if p.apps.claude {
-                let claude_id = format!("universal-claude-{id}");
-                let _ = state.db.delete_provider("claude", &claude_id);
+                Self::remove_universal_child(state, "claude", &format!("universal-claude-{id}"));
             }
             if p.apps.codex {
-                let codex_id = format!("universal-codex-{id}");
-                let _ = state.db.delete_provider("codex", &codex_id);
+                Self::remove_universal_child(state, "codex", &format!("universal-codex-{id}"));
             }
             if p.apps.gemini {
-                let gemini_id = format!("universal-gemini-{id}");
-                let _ = state.db.delete_provider("gemini", &gemini_id);
+                Self::remove_universal_child(state, "gemini", &format!("universal-gemini-{id}"));
             }
         }
 
@@ -1133,54 +1813,140 @@ impl ProviderService {
             .get_universal_provider(id)?
             .ok_or_else(|| AppError::Message(format!("统一供应商 {id} 不存在")))?;
 
-        // 同步到 Claude
-        if let Some(mut claude_provider) = provider.to_claude_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&claude_provider.id, "claude")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &claude_provider.settings_config);
-                claude_provider.settings_config = merged;
-            }
-            state.db.save_provider("claude", &claude_provider)?;
-        } else {
-            // 如果禁用了 Claude，删除对应的子供应商
-            let claude_id = format!("universal-claude-{id}");
-            let _ = state.db.delete_provider("claude", &claude_id);
+        // 同步到各应用；禁用的应用删除对应的子供应商
+        Self::sync_universal_child(state, "claude", id, provider.to_claude_provider())?;
+        Self::sync_universal_child(state, "codex", id, provider.to_codex_provider())?;
+        Self::sync_universal_child(state, "gemini", id, provider.to_gemini_provider())?;
+
+        Ok(true)
+    }
+
+    /// 写入（合并已有配置）或删除统一供应商在某个应用下的子供应商
+    fn sync_universal_child(
+        state: &AppState,
+        app_type: &str,
+        universal_id: &str,
+        child: Option<Provider>,
+    ) -> Result<(), AppError> {
+        let Some(mut child) = child else {
+            Self::remove_universal_child(
+                state,
+                app_type,
+                &format!("universal-{app_type}-{universal_id}"),
+            );
+            return Ok(());
+        };
+
+        let existing = state.db.get_provider_by_id(&child.id, app_type)?;
+        if let Some(existing) = &existing {
+            let mut merged = existing.settings_config.clone();
+            Self::merge_json(&mut merged, &child.settings_config);
+            child.settings_config = merged;
+        }
+        state.db.save_provider(app_type, &child)?;
+
+        match existing {
+            Some(existing) => audit::record_update(
+                &state.db,
+                app_type,
+                &child.id,
+                &existing.settings_config,
+                &child.settings_config,
+                AuditSource::UniversalSync,
+            ),
+            None => audit::record(
+                &state.db,
+                app_type,
+                &child.id,
+                AuditAction::Add,
+                AuditSource::UniversalSync,
+            ),
         }
+        Ok(())
+    }
 
-        // 同步到 Codex
-        if let Some(mut codex_provider) = provider.to_codex_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&codex_provider.id, "codex")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &codex_provider.settings_config);
-                codex_provider.settings_config = merged;
+    /// 删除统一供应商的子供应商（不存在或删除失败时忽略）
+    fn remove_universal_child(state: &AppState, app_type: &str, child_id: &str) {
+        let exists = matches!(state.db.get_provider_by_id(child_id, app_type), Ok(Some(_)));
+        if exists && state.db.delete_provider(app_type, child_id).is_ok() {
+            if app_type == AppType::Claude.as_str() {
+                remove_claude_profile(child_id);
             }
-            state.db.save_provider("codex", &codex_provider)?;
-        } else {
-            let codex_id = format!("universal-codex-{id}");
-            let _ = state.db.delete_provider("codex", &codex_id);
+            audit::record(
+                &state.db,
+                app_type,
+                child_id,
+                AuditAction::Delete,
+                AuditSource::UniversalSync,
+            );
         }
+    }
 
-        // 同步到 Gemini
-        if let Some(mut gemini_provider) = provider.to_gemini_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&gemini_provider.id, "gemini")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &gemini_provider.settings_config);
-                gemini_provider.settings_config = merged;
+    /// 查找父统一供应商已不存在的子供应商（`universal-<app>-<id>`）
+    ///
+    /// 同步中断或直接在数据库中删除统一供应商时，这些子供应商会残留在各应用列表中。
+    pub fn find_orphaned_universal_children(
+        state: &AppState,
+    ) -> Result<Vec<OrphanedUniversalChild>, AppError> {
+        let universals = state.db.get_all_universal_providers()?;
+        let mut orphans = Vec::new();
+
+        for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let current = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+            for (id, provider) in state.db.get_all_providers(app_type.as_str())? {
+                let Some(universal_id) = parse_universal_child_id(&app_type, &id) else {
+                    continue;
+                };
+                if universals.contains_key(universal_id) {
+                    continue;
+                }
+                orphans.push(OrphanedUniversalChild {
+                    app_type: app_type.as_str().to_string(),
+                    universal_id: universal_id.to_string(),
+                    is_current: current.as_deref() == Some(id.as_str()),
+                    provider_name: provider.name,
+                    provider_id: id,
+                });
             }
-            state.db.save_provider("gemini", &gemini_provider)?;
-        } else {
-            let gemini_id = format!("universal-gemini-{id}");
-            let _ = state.db.delete_provider("gemini", &gemini_id);
         }
 
-        Ok(true)
+        Ok(orphans)
+    }
+
+    /// 删除孤立的统一供应商子供应商，返回已删除的条目
+    ///
+    /// 正在使用的子供应商会被跳过，需先切换到其他供应商再清理。
+    pub fn cleanup_orphaned_universal_children(
+        state: &AppState,
+    ) -> Result<Vec<OrphanedUniversalChild>, AppError> {
+        let mut removed = Vec::new();
+        for orphan in Self::find_orphaned_universal_children(state)? {
+            if orphan.is_current {
+                log::warn!(
+                    "跳过正在使用的孤立子供应商 {}/{}",
+                    orphan.app_type,
+                    orphan.provider_id
+                );
+                continue;
+            }
+            state
+                .db
+                .delete_provider(&orphan.app_type, &orphan.provider_id)?;
+            if orphan.app_type == AppType::Claude.as_str() {
+                remove_claude_profile(&orphan.provider_id);
+            }
+            log::info!(
+                "已删除孤立的统一供应商子供应商 {}/{}",
+                orphan.app_type,
+                orphan.provider_id
+            );
+            removed.push(orphan);
+        }
+        Ok(removed)
     }
 
     /// 递归合并 JSON：base 为底，patch 覆盖同名字段
-    fn merge_json(base: &mut serde_json::Value, patch: &serde_json::Value) {
+    pub(crate) fn merge_json(base: &mut serde_json::Value, patch: &serde_json::Value) {
         use serde_json::Value;
 
         match (base, patch) {
@@ -0,0 +1,265 @@
+//! Named config snippets
+//!
+//! 每个应用可保存多个命名配置片段，并按需合并进指定供应商的配置。
+//! 片段格式与通用配置片段一致：Claude/OpenCode 为 settings JSON，Gemini 为 env 键值 JSON，
+//! Codex 为 config.toml 片段。合并时片段中的同名字段覆盖供应商原有值。
+//! 通用配置片段（settings 表中的 `common_config_{app}`）也可按相同规则批量合并进所有供应商。
+
+use serde_json::Value;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::store::AppState;
+
+impl ProviderService {
+    /// Validate and save a named config snippet (same name overwrites)
+    pub fn save_config_snippet(
+        state: &AppState,
+        app_type: AppType,
+        name: &str,
+        content: &str,
+    ) -> Result<(), AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::localized(
+                "config_snippet.name_empty",
+                "配置片段名称不能为空",
+                "Config snippet name cannot be empty",
+            ));
+        }
+        // 仅校验格式，合并到空配置上即可发现解析错误
+        merge_snippet(&app_type, &mut Value::Object(Default::default()), content)?;
+        state
+            .db
+            .save_named_config_snippet(app_type.as_str(), name, content)
+    }
+
+    /// Merge a named snippet into a provider's settings
+    ///
+    /// 冲突字段以片段为准。返回配置是否发生变化；有变化时通过 `update` 保存，
+    /// 当前供应商会同步写入 Live 配置。
+    pub fn apply_snippet_to_provider(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        snippet_name: &str,
+    ) -> Result<bool, AppError> {
+        let snippet = state
+            .db
+            .get_named_config_snippet(app_type.as_str(), snippet_name)?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "config_snippet.not_found",
+                    format!("配置片段不存在: {snippet_name}"),
+                    format!("Config snippet not found: {snippet_name}"),
+                )
+            })?;
+        let mut provider = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+
+        let mut settings = provider.settings_config.clone();
+        merge_snippet(&app_type, &mut settings, &snippet.content)?;
+        if settings == provider.settings_config {
+            return Ok(false);
+        }
+
+        provider.settings_config = settings;
+        Self::update(state, app_type, provider)?;
+        Ok(true)
+    }
+
+    /// Merge the app's common config snippet into every provider of that app
+    ///
+    /// 冲突字段以通用片段为准（会覆盖供应商自身的同名字段），片段中未出现的字段保持不变。
+    /// 所有供应商先在内存中合并并校验，再在同一数据库事务中写入，任一失败则不做任何修改；
+    /// 随后重新同步 Live 配置（切换模式应用仅同步当前供应商，OpenCode 同步所有变更的供应商）。
+    /// 返回配置发生变化的供应商数量。
+    pub fn apply_common_snippet_to_all(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<usize, AppError> {
+        let snippet = state
+            .db
+            .get_config_snippet(app_type.as_str())?
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| {
+                AppError::localized(
+                    "config_snippet.common_empty",
+                    "尚未设置通用配置片段",
+                    "No common config snippet has been set",
+                )
+            })?;
+
+        let mut changed = Vec::new();
+        for provider in state.db.get_all_providers(app_type.as_str())?.into_values() {
+            let mut updated = provider.clone();
+            merge_snippet(&app_type, &mut updated.settings_config, &snippet)?;
+            if updated.settings_config == provider.settings_config {
+                continue;
+            }
+            Self::validate_provider_settings(&app_type, &updated)?;
+            changed.push(updated);
+        }
+        if changed.is_empty() {
+            return Ok(0);
+        }
+
+        let updates: Vec<_> = changed
+            .iter()
+            .map(|p| (p.id.clone(), p.settings_config.clone()))
+            .collect();
+        state
+            .db
+            .update_providers_settings_config(app_type.as_str(), &updates)?;
+        log::info!(
+            "已将 {} 通用配置片段合并进 {} 个供应商",
+            app_type.as_str(),
+            changed.len()
+        );
+
+        let count = changed.len();
+        let current = if app_type.is_additive_mode() {
+            None
+        } else {
+            crate::settings::get_effective_current_provider(&state.db, &app_type)?
+        };
+        for provider in changed {
+            if app_type.is_additive_mode() || current.as_deref() == Some(provider.id.as_str()) {
+                Self::update(state, app_type.clone(), provider)?;
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// 按应用格式把片段合并进 settings_config
+fn merge_snippet(app_type: &AppType, settings: &mut Value, content: &str) -> Result<(), AppError> {
+    match app_type {
+        AppType::Claude | AppType::OpenCode => {
+            ProviderService::merge_json(settings, &parse_json_snippet(content)?);
+        }
+        AppType::Gemini => {
+            let patch = parse_json_snippet(content)?;
+            if !settings.is_object() {
+                *settings = Value::Object(Default::default());
+            }
+            let env = &mut settings["env"];
+            if !env.is_object() {
+                *env = Value::Object(Default::default());
+            }
+            ProviderService::merge_json(env, &patch);
+        }
+        AppType::Codex => {
+            let patch = content
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|e| invalid_snippet(format!("TOML: {e}")))?;
+            let current = settings
+                .get("config")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let mut doc = current
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|e| AppError::Message(format!("TOML parse error: {e}")))?;
+            merge_toml_table(doc.as_table_mut(), patch.as_table());
+
+            if !settings.is_object() {
+                *settings = Value::Object(Default::default());
+            }
+            settings["config"] = Value::String(doc.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn parse_json_snippet(content: &str) -> Result<Value, AppError> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(value @ Value::Object(_)) => Ok(value),
+        Ok(_) => Err(invalid_snippet("JSON object expected".to_string())),
+        Err(e) => Err(invalid_snippet(format!("JSON: {e}"))),
+    }
+}
+
+fn invalid_snippet(detail: String) -> AppError {
+    AppError::localized(
+        "config_snippet.invalid",
+        format!("配置片段格式无效（{detail}）"),
+        format!("Invalid config snippet ({detail})"),
+    )
+}
+
+/// 递归合并 TOML 表：两侧均为表时逐键合并，否则以 patch 覆盖
+fn merge_toml_table(base: &mut dyn toml_edit::TableLike, patch: &dyn toml_edit::TableLike) {
+    for (key, patch_item) in patch.iter() {
+        let both_tables =
+            patch_item.is_table_like() && base.get(key).is_some_and(toml_edit::Item::is_table_like);
+        if !both_tables {
+            base.insert(key, patch_item.clone());
+            continue;
+        }
+        if let (Some(base_child), Some(patch_child)) = (
+            base.get_mut(key)
+                .and_then(toml_edit::Item::as_table_like_mut),
+            patch_item.as_table_like(),
+        ) {
+            merge_toml_table(base_child, patch_child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn codex_snippet_merges_into_config_toml() {
+        let mut settings = json!({
+            "auth": { "OPENAI_API_KEY": "sk-test" },
+            "config": "model = \"gpt-5\"\n\n[tui]\nnotifications = false\ntheme = \"dark\"\n"
+        });
+
+        merge_snippet(
+            &AppType::Codex,
+            &mut settings,
+            "disable_response_storage = true\n\n[tui]\nnotifications = true\n",
+        )
+        .expect("merge codex snippet");
+
+        let config = settings["config"].as_str().expect("config string");
+        let doc = config
+            .parse::<toml_edit::DocumentMut>()
+            .expect("valid toml");
+        assert_eq!(doc["model"].as_str(), Some("gpt-5"));
+        assert_eq!(doc["disable_response_storage"].as_bool(), Some(true));
+        assert_eq!(doc["tui"]["notifications"].as_bool(), Some(true));
+        assert_eq!(doc["tui"]["theme"].as_str(), Some("dark"));
+        assert_eq!(settings["auth"]["OPENAI_API_KEY"], "sk-test");
+    }
+
+    #[test]
+    fn json_snippets_follow_common_config_format() {
+        let mut gemini = json!({ "env": { "GEMINI_API_KEY": "key", "GEMINI_MODEL": "a" } });
+        merge_snippet(&AppType::Gemini, &mut gemini, r#"{ "GEMINI_MODEL": "b" }"#)
+            .expect("merge gemini snippet");
+        assert_eq!(
+            gemini,
+            json!({ "env": { "GEMINI_API_KEY": "key", "GEMINI_MODEL": "b" } })
+        );
+
+        let mut claude = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk" } });
+        merge_snippet(
+            &AppType::Claude,
+            &mut claude,
+            r#"{ "env": { "DISABLE_TELEMETRY": "1" }, "includeCoAuthoredBy": false }"#,
+        )
+        .expect("merge claude snippet");
+        assert_eq!(claude["env"]["ANTHROPIC_AUTH_TOKEN"], "sk");
+        assert_eq!(claude["env"]["DISABLE_TELEMETRY"], "1");
+        assert_eq!(claude["includeCoAuthoredBy"], false);
+
+        assert!(merge_snippet(&AppType::Claude, &mut claude, "[1, 2]").is_err());
+    }
+}
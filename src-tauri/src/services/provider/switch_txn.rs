@@ -0,0 +1,350 @@
+//! 供应商切换的事务化处理
+//!
+//! 切换会改动三处状态：本地设置中的当前供应商、数据库 `is_current` 与 Live 文件。
+//! 任何一步写入前，先把本次可能写到的 Live 文件原始内容暂存在内存中；任一步失败时
+//! 用原子写恢复这些文件，并把本地设置与数据库改回切换前的供应商，最终返回一个错误。
+//!
+//! Live 写入器是"读取-合并-写回"式的（保留 profiles、用户自定义字段等），无法先写到
+//! 临时文件再统一重命名，因此这里采用"暂存原文件 + 失败回滚"，效果等价：失败后三处
+//! 状态都停留在切换前。
+
+use std::path::PathBuf;
+
+use super::live::write_live_snapshot;
+use super::live_history::live_config_files;
+use crate::app_config::AppType;
+use crate::config::{atomic_write, claude_profile_name, get_claude_profile_path};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::settings::ClaudeWriteMode;
+
+/// 切换中的各个写入步骤，测试中可替换为在指定步骤失败的实现
+pub(crate) trait SwitchSteps {
+    fn set_local_current(&self, app_type: &AppType, id: Option<&str>) -> Result<(), AppError> {
+        crate::settings::set_current_provider(app_type, id)
+    }
+
+    fn set_db_current(&self, db: &Database, app_type: &AppType, id: &str) -> Result<(), AppError> {
+        db.set_current_provider(app_type.as_str(), id)
+    }
+
+    fn write_live(
+        &self,
+        db: &Database,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
+        write_live_snapshot(db, app_type, provider)
+    }
+}
+
+/// 实际写入设置、数据库与 Live 文件
+pub(crate) struct DefaultSwitchSteps;
+
+impl SwitchSteps for DefaultSwitchSteps {}
+
+/// 暂存的 Live 文件原始内容（`None` 表示写入前文件不存在）
+struct StagedLiveFiles {
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl StagedLiveFiles {
+    fn capture(paths: Vec<PathBuf>) -> Result<Self, AppError> {
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let original = if path.exists() {
+                Some(std::fs::read(&path).map_err(|e| AppError::io(&path, e))?)
+            } else {
+                None
+            };
+            files.push((path, original));
+        }
+        Ok(Self { files })
+    }
+
+    /// 恢复所有暂存文件；单个文件失败不影响其余文件，返回第一个错误
+    fn restore(&self) -> Result<(), AppError> {
+        let mut first_err = None;
+        for (path, original) in &self.files {
+            let result = match original {
+                Some(bytes) => atomic_write(path, bytes),
+                None if path.exists() => {
+                    std::fs::remove_file(path).map_err(|e| AppError::io(path, e))
+                }
+                None => Ok(()),
+            };
+            if let Err(e) = result {
+                log::error!("回滚 Live 文件失败 {}: {e}", path.display());
+                first_err.get_or_insert(e);
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+}
+
+/// 本次切换可能写到的 Live 文件
+fn switch_live_paths(app_type: &AppType, provider: &Provider) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = live_config_files(app_type)
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect();
+    // profiles 模式下 Claude 还会写入目标供应商的 profile 文件
+    if matches!(app_type, AppType::Claude)
+        && crate::settings::get_claude_write_mode() == ClaudeWriteMode::Profiles
+    {
+        if let Some(path) = get_claude_profile_path(&claude_profile_name(&provider.id)) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// 以事务方式切换当前供应商
+///
+/// `write_live` 为 false（只读模式）时只更新当前供应商标记。OpenCode 为叠加模式，
+/// 没有当前供应商的概念，只写 Live。
+pub(crate) fn switch_current_provider(
+    db: &Database,
+    app_type: &AppType,
+    provider: &Provider,
+    write_live: bool,
+    steps: &dyn SwitchSteps,
+) -> Result<(), AppError> {
+    let tracks_current = !matches!(app_type, AppType::OpenCode);
+    let prev_local = crate::settings::get_current_provider(app_type);
+    let prev_db = db.get_current_provider(app_type.as_str())?;
+    let staged = if write_live {
+        Some(StagedLiveFiles::capture(switch_live_paths(
+            app_type, provider,
+        ))?)
+    } else {
+        None
+    };
+
+    let mut local_changed = false;
+    let mut db_changed = false;
+    let result = (|| -> Result<(), AppError> {
+        if tracks_current {
+            local_changed = true;
+            steps.set_local_current(app_type, Some(&provider.id))?;
+            db_changed = true;
+            steps.set_db_current(db, app_type, &provider.id)?;
+        }
+        if write_live {
+            steps.write_live(db, app_type, provider)?;
+        }
+        Ok(())
+    })();
+
+    let Err(err) = result else {
+        return Ok(());
+    };
+
+    log::warn!(
+        "切换 {} 供应商到 {} 失败，回滚到切换前的状态: {err}",
+        app_type.as_str(),
+        provider.id
+    );
+    let mut rollback_errors = Vec::new();
+    if let Some(staged) = &staged {
+        if let Err(e) = staged.restore() {
+            rollback_errors.push(e.to_string());
+        }
+    }
+    if db_changed {
+        let restored = match &prev_db {
+            Some(prev) => db.set_current_provider(app_type.as_str(), prev),
+            None => db.clear_current_provider(app_type.as_str()),
+        };
+        if let Err(e) = restored {
+            rollback_errors.push(e.to_string());
+        }
+    }
+    if local_changed {
+        if let Err(e) = crate::settings::set_current_provider(app_type, prev_local.as_deref()) {
+            rollback_errors.push(e.to_string());
+        }
+    }
+
+    let (cause_zh, cause_en) = match &err {
+        AppError::Localized { zh, en, .. } => (zh.clone(), en.clone()),
+        other => (other.to_string(), other.to_string()),
+    };
+    let id = &provider.id;
+    if rollback_errors.is_empty() {
+        Err(AppError::localized(
+            "provider.switch.rolled_back",
+            format!("切换到供应商 {id} 失败，已恢复到切换前的状态: {cause_zh}"),
+            format!("Switching to provider {id} failed and was rolled back: {cause_en}"),
+        ))
+    } else {
+        let detail = rollback_errors.join("; ");
+        Err(AppError::localized(
+            "provider.switch.rollback_failed",
+            format!("切换到供应商 {id} 失败: {cause_zh}；回滚时出错: {detail}"),
+            format!(
+                "Switching to provider {id} failed: {cause_en}; rollback also failed: {detail}"
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    struct TempHome {
+        dir: TempDir,
+        original_home: Option<String>,
+    }
+
+    impl TempHome {
+        fn new() -> Self {
+            let dir = TempDir::new().expect("failed to create temp home");
+            let original_home = env::var("HOME").ok();
+            env::set_var("HOME", dir.path());
+            crate::settings::reload_settings().expect("reload settings");
+            Self { dir, original_home }
+        }
+    }
+
+    impl Drop for TempHome {
+        fn drop(&mut self) {
+            match &self.original_home {
+                Some(value) => env::set_var("HOME", value),
+                None => env::remove_var("HOME"),
+            }
+            let _ = crate::settings::reload_settings();
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum FailAt {
+        Local,
+        Db,
+        Live,
+    }
+
+    /// 执行真实写入，但在指定步骤写入之后返回错误
+    struct FailingSteps(FailAt);
+
+    impl SwitchSteps for FailingSteps {
+        fn set_local_current(&self, app_type: &AppType, id: Option<&str>) -> Result<(), AppError> {
+            DefaultSwitchSteps.set_local_current(app_type, id)?;
+            if self.0 == FailAt::Local {
+                return Err(AppError::Message("local failed".into()));
+            }
+            Ok(())
+        }
+
+        fn set_db_current(
+            &self,
+            db: &Database,
+            app_type: &AppType,
+            id: &str,
+        ) -> Result<(), AppError> {
+            DefaultSwitchSteps.set_db_current(db, app_type, id)?;
+            if self.0 == FailAt::Db {
+                return Err(AppError::Message("db failed".into()));
+            }
+            Ok(())
+        }
+
+        fn write_live(
+            &self,
+            db: &Database,
+            app_type: &AppType,
+            provider: &Provider,
+        ) -> Result<(), AppError> {
+            DefaultSwitchSteps.write_live(db, app_type, provider)?;
+            if self.0 == FailAt::Live {
+                return Err(AppError::Message("live failed".into()));
+            }
+            Ok(())
+        }
+    }
+
+    fn claude_provider(id: &str, token: &str) -> Provider {
+        Provider::with_id(
+            id.into(),
+            id.into(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": token } }),
+            None,
+        )
+    }
+
+    #[test]
+    #[serial]
+    fn failed_switch_restores_previous_state_at_every_stage() -> Result<(), AppError> {
+        let home = TempHome::new();
+        let settings_path = home.dir.path().join(".claude").join("settings.json");
+        let original_live = "{\n  \"env\": { \"ANTHROPIC_AUTH_TOKEN\": \"old\" }\n}";
+
+        for fail_at in [FailAt::Local, FailAt::Db, FailAt::Live] {
+            let db = Database::memory()?;
+            let old = claude_provider("old", "old");
+            let new = claude_provider("new", "new");
+            db.save_provider("claude", &old)?;
+            db.save_provider("claude", &new)?;
+            db.set_current_provider("claude", "old")?;
+            crate::settings::set_current_provider(&AppType::Claude, Some("old"))?;
+            atomic_write(&settings_path, original_live.as_bytes())?;
+
+            let err =
+                switch_current_provider(&db, &AppType::Claude, &new, true, &FailingSteps(fail_at))
+                    .unwrap_err();
+            assert!(matches!(
+                err,
+                AppError::Localized {
+                    key: "provider.switch.rolled_back",
+                    ..
+                }
+            ));
+            assert_eq!(
+                crate::settings::get_current_provider(&AppType::Claude).as_deref(),
+                Some("old")
+            );
+            assert_eq!(db.get_current_provider("claude")?.as_deref(), Some("old"));
+            assert_eq!(
+                std::fs::read_to_string(&settings_path).expect("read live"),
+                original_live
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn failed_switch_removes_live_file_that_did_not_exist() -> Result<(), AppError> {
+        let home = TempHome::new();
+        let settings_path = home.dir.path().join(".claude").join("settings.json");
+        let db = Database::memory()?;
+        let new = claude_provider("new", "new");
+        db.save_provider("claude", &new)?;
+
+        let result = switch_current_provider(
+            &db,
+            &AppType::Claude,
+            &new,
+            true,
+            &FailingSteps(FailAt::Live),
+        );
+        assert!(result.is_err());
+        assert!(!settings_path.exists());
+        assert_eq!(db.get_current_provider("claude")?, None);
+        assert_eq!(
+            crate::settings::get_current_provider(&AppType::Claude),
+            None
+        );
+
+        switch_current_provider(&db, &AppType::Claude, &new, true, &DefaultSwitchSteps)?;
+        assert!(settings_path.exists());
+        assert_eq!(db.get_current_provider("claude")?.as_deref(), Some("new"));
+        Ok(())
+    }
+}
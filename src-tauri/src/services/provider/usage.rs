@@ -6,9 +6,8 @@ use crate::app_config::AppType;
 use crate::error::AppError;
 use crate::provider::{UsageData, UsageResult, UsageScript};
 use crate::services::antigravity;
-use crate::settings;
 use crate::store::AppState;
-use crate::usage_script;
+use crate::usage_script::{self, ScriptSandbox};
 use super::gemini_auth::is_google_official_gemini;
 
 /// Execute usage script and format result (private helper method)
@@ -16,19 +15,19 @@ pub(crate) async fn execute_and_format_usage_result(
     script_code: &str,
     api_key: &str,
     base_url: &str,
-    timeout: u64,
     access_token: Option<&str>,
     user_id: Option<&str>,
     template_type: Option<&str>,
+    sandbox: &ScriptSandbox,
 ) -> Result<UsageResult, AppError> {
     match usage_script::execute_usage_script(
         script_code,
         api_key,
         base_url,
-        timeout,
         access_token,
         user_id,
         template_type,
+        sandbox,
     )
     .await
     {
@@ -59,20 +58,7 @@ pub(crate) async fn execute_and_format_usage_result(
             })
         }
         Err(err) => {
-            let lang = settings::get_settings()
-                .language
-                .unwrap_or_else(|| "zh".to_string());
-
-            let msg = match err {
-                AppError::Localized { zh, en, .. } => {
-                    if lang == "en" {
-                        en
-                    } else {
-                        zh
-                    }
-                }
-                other => other.to_string(),
-            };
+            let msg = err.localized_message();
 
             Ok(UsageResult {
                 success: false,
@@ -110,6 +96,16 @@ fn extract_base_url_from_provider(provider: &crate::provider::Provider) -> Optio
             .or_else(|| env.get("BASE_URL"))
             .and_then(|v| v.as_str())
             .map(|s| s.trim_end_matches('/').to_string())
+    } else if let Some(config_toml) = provider
+        .settings_config
+        .get("config")
+        .and_then(|v| v.as_str())
+    {
+        // Codex: use the base_url of the active [model_providers.<name>] table
+        crate::codex_config::get_active_base_url(config_toml)
+            .ok()
+            .flatten()
+            .map(|s| s.trim_end_matches('/').to_string())
     } else {
         None
     }
@@ -180,10 +176,10 @@ pub async fn query_usage(
             &usage_script.code,
             &api_key,
             &base_url,
-            usage_script.timeout.unwrap_or(10),
             usage_script.access_token.as_deref(),
             usage_script.user_id.as_deref(),
             usage_script.template_type.as_deref(),
+            &ScriptSandbox::from_usage_script(usage_script),
         )
         .await;
     }
@@ -216,22 +212,22 @@ pub async fn test_usage_script(
     _app_type: AppType,
     _provider_id: &str,
     script_code: &str,
-    timeout: u64,
     api_key: Option<&str>,
     base_url: Option<&str>,
     access_token: Option<&str>,
     user_id: Option<&str>,
     template_type: Option<&str>,
+    sandbox: &ScriptSandbox,
 ) -> Result<UsageResult, AppError> {
     // Use provided credential parameters directly for testing
     execute_and_format_usage_result(
         script_code,
         api_key.unwrap_or(""),
         base_url.unwrap_or(""),
-        timeout,
         access_token,
         user_id,
         template_type,
+        sandbox,
     )
     .await
 }
@@ -0,0 +1,282 @@
+//! 供应商远程图标缓存
+//!
+//! `icon` 字段除内置图标名、emoji、base64 外，也可以是 `https://` 图片地址：
+//! - 首次使用时通过全局代理客户端下载（上限 256KB），缩放为不超过 128px 的 PNG，
+//!   保存到 `<app_config_dir>/icons/<sha256(url)>.png`；
+//! - `get_providers` 只读取本地缓存，缺失或超过 30 天的条目在后台下载/刷新，界面不会等待网络；
+//! - 下载失败时回退为供应商名称的首字母。
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use super::ProviderMetadataService;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::ProviderSummary;
+
+/// 缓存目录名（位于应用配置目录下）
+const ICON_CACHE_DIR: &str = "icons";
+/// 下载超时
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// 远程图标大小上限（256KB）
+const MAX_ICON_BYTES: usize = 256 * 1024;
+/// 缓存 PNG 的边长上限
+const ICON_SIZE: u32 = 128;
+/// 缓存过期时间（30 天），过期后在后台刷新
+const STALE_AFTER: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// 同一地址两次后台下载的最小间隔（避免失败时每次刷新列表都重试）
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 后台下载完成后发送的事件（payload 为图标 URL）
+pub const PROVIDER_ICON_CACHED_EVENT: &str = "provider-icon-cached";
+
+/// URL → 最近一次后台下载的开始时间
+static BACKGROUND_ATTEMPTS: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// `resolve_provider_icon` 的返回值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResolvedProviderIcon {
+    /// 远程图标的本地缓存
+    #[serde(rename_all = "camelCase")]
+    Cached { path: String, data_url: String },
+    /// 非 URL 图标（内置名称、emoji、base64），原样返回
+    Inline { icon: String },
+    /// 未设置图标或远程图标不可用时的首字母占位
+    Letter { letter: String },
+}
+
+pub struct ProviderIconService;
+
+impl ProviderIconService {
+    /// 是否为需要缓存的远程图标
+    pub fn is_remote(icon: &str) -> bool {
+        icon.trim()
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+    }
+
+    /// 为摘要列表中使用远程图标的供应商填充 `icon_cached`（只读本地缓存，不等待网络）
+    pub fn fill_cached_summaries(app: &AppHandle, providers: &mut [ProviderSummary]) {
+        for provider in providers {
+            provider.icon_cached = Self::cached_data_url(app, provider.icon.as_deref());
+        }
+    }
+
+    /// 远程图标的缓存 data URL；缓存缺失或过期时在后台刷新
+    fn cached_data_url(app: &AppHandle, icon: Option<&str>) -> Option<String> {
+        let url = icon
+            .filter(|icon| Self::is_remote(icon))?
+            .trim()
+            .to_string();
+        match Self::read_cached(&url) {
+            Some((_, data_url, stale)) => {
+                if stale {
+                    Self::refresh_in_background(app, url);
+                }
+                Some(data_url)
+            }
+            None => {
+                Self::refresh_in_background(app, url);
+                None
+            }
+        }
+    }
+
+    /// 解析单个供应商的图标；缓存缺失时直接下载，失败回退为首字母
+    pub async fn resolve(
+        app: &AppHandle,
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<ResolvedProviderIcon, AppError> {
+        let provider = db
+            .get_provider_by_id(provider_id, app_type)?
+            .ok_or_else(|| AppError::Message(format!("供应商不存在: {provider_id}")))?;
+        let letter = || ResolvedProviderIcon::Letter {
+            letter: Self::fallback_letter(&provider.name),
+        };
+
+        let icon = match provider.icon.as_deref().map(str::trim) {
+            Some(icon) if !icon.is_empty() => icon.to_string(),
+            _ => return Ok(letter()),
+        };
+        if !Self::is_remote(&icon) {
+            return Ok(ResolvedProviderIcon::Inline { icon });
+        }
+
+        if let Some((path, data_url, stale)) = Self::read_cached(&icon) {
+            if stale {
+                Self::refresh_in_background(app, icon);
+            }
+            return Ok(ResolvedProviderIcon::Cached {
+                path: path.to_string_lossy().to_string(),
+                data_url,
+            });
+        }
+
+        if let Err(e) = Self::download(&icon).await {
+            log::warn!("[ProviderIcon] 下载图标失败 ({icon}): {e}");
+            return Ok(letter());
+        }
+        Ok(Self::read_cached(&icon)
+            .map(|(path, data_url, _)| ResolvedProviderIcon::Cached {
+                path: path.to_string_lossy().to_string(),
+                data_url,
+            })
+            .unwrap_or_else(letter))
+    }
+
+    /// 供应商名称的首字母（大写），名称为空时为 `?`
+    pub fn fallback_letter(name: &str) -> String {
+        name.trim()
+            .chars()
+            .find(|c| c.is_alphanumeric())
+            .map(|c| c.to_uppercase().collect())
+            .unwrap_or_else(|| "?".to_string())
+    }
+
+    fn cache_path(url: &str) -> PathBuf {
+        crate::config::get_app_config_dir()
+            .join(ICON_CACHE_DIR)
+            .join(Self::cache_file_name(url))
+    }
+
+    fn cache_file_name(url: &str) -> String {
+        format!("{:x}.png", Sha256::digest(url.as_bytes()))
+    }
+
+    /// 读取缓存，返回 (路径, data URL, 是否过期)
+    fn read_cached(url: &str) -> Option<(PathBuf, String, bool)> {
+        let path = Self::cache_path(url);
+        let bytes = std::fs::read(&path).ok()?;
+        let stale = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_AFTER);
+        let data_url = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        );
+        Some((path, data_url, stale))
+    }
+
+    /// 在后台下载/刷新图标（同一地址在 RETRY_INTERVAL 内只尝试一次）
+    fn refresh_in_background(app: &AppHandle, url: String) {
+        {
+            let Ok(mut attempts) = BACKGROUND_ATTEMPTS.lock() else {
+                return;
+            };
+            if attempts
+                .get(&url)
+                .is_some_and(|last| last.elapsed() < RETRY_INTERVAL)
+            {
+                return;
+            }
+            attempts.insert(url.clone(), Instant::now());
+        }
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match Self::download(&url).await {
+                Ok(()) => {
+                    log::debug!("[ProviderIcon] 已缓存图标: {url}");
+                    if let Err(e) = app.emit(PROVIDER_ICON_CACHED_EVENT, &url) {
+                        log::error!("[ProviderIcon] 发射图标缓存事件失败: {e}");
+                    }
+                }
+                Err(e) => log::warn!("[ProviderIcon] 后台下载图标失败 ({url}): {e}"),
+            }
+        });
+    }
+
+    /// 下载图标并写入缓存文件
+    async fn download(url: &str) -> Result<(), AppError> {
+        let client = crate::proxy::http_client::get();
+        let response = client
+            .get(url)
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("请求图标失败: {e}")))?;
+        if !response.status().is_success() {
+            return Err(AppError::Message(format!(
+                "请求图标失败: HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !content_type.starts_with("image/") {
+            return Err(AppError::Message(format!(
+                "图标响应不是图片: {content_type}"
+            )));
+        }
+
+        let bytes = ProviderMetadataService::read_limited(response, MAX_ICON_BYTES).await?;
+        let png = ProviderMetadataService::resize_to_png(&bytes, ICON_SIZE)?;
+        // 文件被占用时 atomic_write 会同步重试，放到阻塞线程执行
+        let path = Self::cache_path(url);
+        tauri::async_runtime::spawn_blocking(move || crate::config::atomic_write(&path, &png))
+            .await
+            .map_err(|e| AppError::Message(format!("写入图标缓存失败: {e}")))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_remote_only_accepts_https() {
+        assert!(ProviderIconService::is_remote(
+            "https://relay.example.com/logo.png"
+        ));
+        assert!(ProviderIconService::is_remote(
+            "  HTTPS://relay.example.com/a.svg"
+        ));
+        assert!(!ProviderIconService::is_remote(
+            "http://relay.example.com/logo.png"
+        ));
+        assert!(!ProviderIconService::is_remote("openai"));
+        assert!(!ProviderIconService::is_remote(
+            "data:image/png;base64,AAAA"
+        ));
+        assert!(!ProviderIconService::is_remote("🚀"));
+    }
+
+    #[test]
+    fn fallback_letter_uses_first_alphanumeric() {
+        assert_eq!(ProviderIconService::fallback_letter("relay"), "R");
+        assert_eq!(ProviderIconService::fallback_letter("  [CN] 中转"), "C");
+        assert_eq!(ProviderIconService::fallback_letter("智谱"), "智");
+        assert_eq!(ProviderIconService::fallback_letter(" -- "), "?");
+    }
+
+    #[test]
+    fn cache_file_name_is_stable_per_url() {
+        let a = ProviderIconService::cache_file_name("https://a.example.com/logo.png");
+        let b = ProviderIconService::cache_file_name("https://b.example.com/logo.png");
+        assert_eq!(
+            a,
+            ProviderIconService::cache_file_name("https://a.example.com/logo.png")
+        );
+        assert_ne!(a, b);
+        assert!(a.ends_with(".png"));
+        assert_eq!(a.len(), 64 + ".png".len());
+    }
+}
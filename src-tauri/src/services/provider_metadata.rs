@@ -0,0 +1,373 @@
+//! 供应商网站元数据抓取
+//!
+//! 从中转站首页提取标题、描述与网站图标，供前端在新增供应商时预填名称、图标和备注。
+//! 结果按域名缓存在 settings 表中（7 天），图标会被缩放为小尺寸 PNG 并以 data URL 返回。
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::time::Duration;
+
+use crate::database::Database;
+use crate::error::AppError;
+
+/// 请求超时
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// 单个响应体上限（1MB）
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+/// 缓存有效期（7 天）
+const CACHE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+/// 图标输出边长上限
+const ICON_SIZE: u32 = 64;
+/// settings 表缓存键前缀
+const CACHE_KEY_PREFIX: &str = "provider_metadata_cache:";
+
+static TITLE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid title regex"));
+static META_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<meta\s[^>]*>").expect("valid meta regex"));
+static LINK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<link\s[^>]*>").expect("valid link regex"));
+static ATTR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)([a-z][a-z0-9_:-]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+        .expect("valid attribute regex")
+});
+
+/// 网站元数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMetadata {
+    /// 页面标题（可用于预填名称）
+    pub title: Option<String>,
+    /// `<meta name="description">` 内容（可用于预填备注）
+    pub description: Option<String>,
+    /// 原始图标地址
+    pub favicon_url: Option<String>,
+    /// 缩放后的 PNG 图标（`data:image/png;base64,...`），下载或解码失败时为空
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedMetadata {
+    fetched_at: i64,
+    metadata: ProviderMetadata,
+}
+
+pub struct ProviderMetadataService;
+
+impl ProviderMetadataService {
+    /// 抓取网站元数据（优先使用未过期的缓存）
+    pub async fn fetch(db: &Database, raw_url: &str) -> Result<ProviderMetadata, AppError> {
+        let url = Self::normalize_url(raw_url)?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| AppError::InvalidInput(format!("URL 缺少主机名: {raw_url}")))?
+            .to_ascii_lowercase();
+        let cache_key = format!("{CACHE_KEY_PREFIX}{host}");
+        let now = chrono::Utc::now().timestamp();
+
+        if let Some(cached) = db
+            .get_setting(&cache_key)?
+            .and_then(|raw| serde_json::from_str::<CachedMetadata>(&raw).ok())
+        {
+            if now - cached.fetched_at < CACHE_TTL_SECS {
+                return Ok(cached.metadata);
+            }
+        }
+
+        let client = crate::proxy::http_client::get();
+        let response = client
+            .get(url.clone())
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("请求网站失败: {e}")))?;
+        if !response.status().is_success() {
+            return Err(AppError::Message(format!(
+                "请求网站失败: HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+        // 以重定向后的最终地址解析相对路径
+        let page_url = response.url().clone();
+        let body = Self::read_limited(response, MAX_RESPONSE_BYTES).await?;
+        let html = String::from_utf8_lossy(&body);
+
+        let mut metadata = Self::parse_html(&html, &page_url);
+        if let Some(favicon_url) = metadata.favicon_url.as_deref() {
+            match Self::fetch_icon(&client, favicon_url).await {
+                Ok(icon) => metadata.icon = Some(icon),
+                Err(e) => log::debug!("下载网站图标失败 ({favicon_url}): {e}"),
+            }
+        }
+
+        let cached = CachedMetadata {
+            fetched_at: now,
+            metadata: metadata.clone(),
+        };
+        match serde_json::to_string(&cached) {
+            Ok(raw) => {
+                if let Err(e) = db.set_setting(&cache_key, &raw) {
+                    log::warn!("缓存网站元数据失败: {e}");
+                }
+            }
+            Err(e) => log::warn!("序列化网站元数据失败: {e}"),
+        }
+
+        Ok(metadata)
+    }
+
+    /// 补全协议并校验 URL
+    fn normalize_url(raw_url: &str) -> Result<Url, AppError> {
+        let trimmed = raw_url.trim();
+        if trimmed.is_empty() {
+            return Err(AppError::InvalidInput("URL 不能为空".to_string()));
+        }
+        let candidate = if trimmed.contains("://") {
+            trimmed.to_string()
+        } else {
+            format!("https://{trimmed}")
+        };
+        let url =
+            Url::parse(&candidate).map_err(|e| AppError::InvalidInput(format!("URL 无效: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::InvalidInput(format!(
+                "不支持的协议: {}",
+                url.scheme()
+            )));
+        }
+        Ok(url)
+    }
+
+    /// 从 HTML 中提取标题、描述与图标地址（未声明图标时回退到 /favicon.ico）
+    fn parse_html(html: &str, page_url: &Url) -> ProviderMetadata {
+        let title = TITLE_RE
+            .captures(html)
+            .and_then(|c| c.get(1))
+            .map(|m| Self::clean_text(m.as_str()))
+            .filter(|s| !s.is_empty());
+
+        let description = META_RE
+            .find_iter(html)
+            .map(|m| Self::parse_attrs(m.as_str()))
+            .find(|attrs| {
+                Self::attr(attrs, "name").is_some_and(|n| n.eq_ignore_ascii_case("description"))
+            })
+            .and_then(|attrs| Self::attr(&attrs, "content").map(Self::clean_text))
+            .filter(|s| !s.is_empty());
+
+        let favicon_href = LINK_RE
+            .find_iter(html)
+            .map(|m| Self::parse_attrs(m.as_str()))
+            .filter_map(|attrs| {
+                let rel = Self::attr(&attrs, "rel")?.to_ascii_lowercase();
+                let href = Self::attr(&attrs, "href")?;
+                let rank = rel
+                    .split_whitespace()
+                    .filter_map(|token| match token {
+                        "apple-touch-icon" => Some(0),
+                        "icon" => Some(1),
+                        _ => None,
+                    })
+                    .min()?;
+                Some((rank, href.to_string()))
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, href)| href);
+
+        let favicon_url = match favicon_href {
+            Some(href) => page_url.join(href.trim()).ok(),
+            None => page_url.join("/favicon.ico").ok(),
+        }
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .map(|u| u.to_string());
+
+        ProviderMetadata {
+            title,
+            description,
+            favicon_url,
+            icon: None,
+        }
+    }
+
+    /// 下载图标并转为小尺寸 PNG data URL
+    async fn fetch_icon(client: &reqwest::Client, url: &str) -> Result<String, AppError> {
+        let response = client
+            .get(url)
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("请求图标失败: {e}")))?;
+        if !response.status().is_success() {
+            return Err(AppError::Message(format!(
+                "请求图标失败: HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !content_type.starts_with("image/") {
+            return Err(AppError::Message(format!(
+                "图标响应不是图片: {content_type}"
+            )));
+        }
+
+        let bytes = Self::read_limited(response, MAX_RESPONSE_BYTES).await?;
+        Self::encode_icon(&bytes)
+    }
+
+    /// 解码图片，缩放到 ICON_SIZE 以内并编码为 PNG data URL
+    fn encode_icon(bytes: &[u8]) -> Result<String, AppError> {
+        let png = Self::resize_to_png(bytes, ICON_SIZE)?;
+        Ok(format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        ))
+    }
+
+    /// 解码图片，等比缩放到 `max_size` 以内并编码为 PNG
+    pub(crate) fn resize_to_png(bytes: &[u8], max_size: u32) -> Result<Vec<u8>, AppError> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| AppError::Message(format!("无法解析图标: {e}")))?;
+        let image = if image.width() > max_size || image.height() > max_size {
+            image.thumbnail(max_size, max_size)
+        } else {
+            image
+        };
+
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| AppError::Message(format!("图标编码失败: {e}")))?;
+        Ok(png)
+    }
+
+    /// 读取响应体，超过 `max_bytes` 时中止
+    pub(crate) async fn read_limited(
+        mut response: reqwest::Response,
+        max_bytes: usize,
+    ) -> Result<Vec<u8>, AppError> {
+        let too_large = || AppError::Message(format!("响应体超过 {} KB 上限", max_bytes / 1024));
+        if response
+            .content_length()
+            .is_some_and(|len| len > max_bytes as u64)
+        {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::Message(format!("读取响应失败: {e}")))?
+        {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    fn parse_attrs(tag: &str) -> Vec<(String, String)> {
+        ATTR_RE
+            .captures_iter(tag)
+            .filter_map(|c| {
+                let name = c.get(1)?.as_str().to_ascii_lowercase();
+                let value = c.get(2).or_else(|| c.get(3)).or_else(|| c.get(4))?;
+                Some((name, value.as_str().to_string()))
+            })
+            .collect()
+    }
+
+    fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 折叠空白并还原常见 HTML 实体
+    fn clean_text(raw: &str) -> String {
+        let text = raw
+            .replace("&amp;", "&")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&nbsp;", " ");
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_html_extracts_title_description_and_icon() {
+        let html = r#"<html><head>
+            <TITLE>
+              Relay &amp; Co
+            </TITLE>
+            <meta property="og:title" content="ignored">
+            <meta content='Fast Claude relay' name="Description">
+            <link rel="stylesheet" href="/main.css">
+            <link rel="shortcut icon" href="static/favicon.png">
+        </head></html>"#;
+        let page = Url::parse("https://relay.example.com/dashboard/").unwrap();
+
+        let metadata = ProviderMetadataService::parse_html(html, &page);
+        assert_eq!(metadata.title.as_deref(), Some("Relay & Co"));
+        assert_eq!(metadata.description.as_deref(), Some("Fast Claude relay"));
+        assert_eq!(
+            metadata.favicon_url.as_deref(),
+            Some("https://relay.example.com/dashboard/static/favicon.png")
+        );
+    }
+
+    #[test]
+    fn parse_html_falls_back_to_favicon_ico() {
+        let page = Url::parse("https://relay.example.com/login").unwrap();
+        let metadata = ProviderMetadataService::parse_html("<p>no head</p>", &page);
+        assert_eq!(metadata.title, None);
+        assert_eq!(
+            metadata.favicon_url.as_deref(),
+            Some("https://relay.example.com/favicon.ico")
+        );
+    }
+
+    #[test]
+    fn normalize_url_adds_scheme_and_rejects_others() {
+        let url = ProviderMetadataService::normalize_url("relay.example.com").unwrap();
+        assert_eq!(url.as_str(), "https://relay.example.com/");
+        assert!(ProviderMetadataService::normalize_url("ftp://relay.example.com").is_err());
+        assert!(ProviderMetadataService::normalize_url("  ").is_err());
+    }
+
+    #[test]
+    fn encode_icon_resizes_to_small_png() {
+        let source = image::DynamicImage::new_rgba8(256, 128);
+        let mut png = Vec::new();
+        source
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let data_url = ProviderMetadataService::encode_icon(&png).unwrap();
+        let encoded = data_url.strip_prefix("data:image/png;base64,").unwrap();
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let icon = image::load_from_memory(&decoded).unwrap();
+        assert_eq!((icon.width(), icon.height()), (ICON_SIZE, ICON_SIZE / 2));
+    }
+}
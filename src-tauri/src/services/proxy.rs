@@ -3,17 +3,31 @@
 //! 提供代理服务器的启动、停止和配置管理
 
 use crate::app_config::AppType;
-use crate::config::{get_claude_settings_path, read_json_file, write_json_file};
+use crate::config::{read_json_file, write_json_file};
 use crate::database::Database;
+use crate::gemini_config::GeminiLiveTargets;
 use crate::provider::Provider;
+use crate::proxy::failover_simulation::{self, FailoverSimulation, SimulationCandidate};
+use crate::proxy::port;
+use crate::proxy::replay::{CurlExport, ReplayResult};
 use crate::proxy::server::ProxyServer;
 use crate::proxy::types::*;
-use crate::services::provider::write_live_snapshot;
+use crate::services::provider::{get_claude_live_path, write_claude_live, write_live_snapshot};
 use serde_json::{json, Value};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// 熔断器状态转换时发射到前端的事件名
+pub const CIRCUIT_BREAKER_STATE_CHANGED_EVENT: &str = "circuit-breaker-state-changed";
+
+/// 端口被占用、自动改用新端口后发射到前端的事件名
+pub const PROXY_PORT_CHANGED_EVENT: &str = "proxy-port-changed";
+
+/// 熔断器历史查询的默认条数
+const CIRCUIT_BREAKER_HISTORY_DEFAULT_LIMIT: u32 = 100;
+
 /// 用于接管 Live 配置时的占位符（避免客户端提示缺少 key，同时不泄露真实 Token）
 const PROXY_TOKEN_PLACEHOLDER: &str = "PROXY_MANAGED";
 
@@ -97,14 +111,7 @@ impl ProxyService {
                 .map_err(|e| format!("更新代理总开关失败: {e}"))?;
         }
 
-        // 2. 获取配置
-        let config = self
-            .db
-            .get_proxy_config()
-            .await
-            .map_err(|e| format!("获取代理配置失败: {e}"))?;
-
-        // 3. 若已在运行：确保持久化状态（如需要）并返回当前信息
+        // 2. 若已在运行：确保持久化状态（如需要）并返回当前信息
         if let Some(server) = self.server.read().await.as_ref() {
             let status = server.get_status().await;
             return Ok(ProxyServerInfo {
@@ -115,6 +122,14 @@ impl ProxyService {
             });
         }
 
+        // 3. 检查监听端口（被占用且开启自动换端口时会写回新端口），再读取配置
+        self.ensure_listen_port().await?;
+        let config = self
+            .db
+            .get_proxy_config()
+            .await
+            .map_err(|e| format!("获取代理配置失败: {e}"))?;
+
         // 4. 创建并启动服务器
         let app_handle = self.app_handle.read().await.clone();
         let server = ProxyServer::new(config.clone(), self.db.clone(), app_handle);
@@ -127,11 +142,18 @@ impl ProxyService {
         *self.server.write().await = Some(server);
 
         log::info!("代理服务器已启动: {}:{}", info.address, info.port);
+        self.refresh_tray_menu().await;
         Ok(info)
     }
 
     /// 启动代理服务器（带 Live 配置接管）
     pub async fn start_with_takeover(&self) -> Result<ProxyServerInfo, String> {
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+        Self::preflight_live_dirs(vec![AppType::Claude, AppType::Codex, AppType::Gemini]).await?;
+
+        // 0. 先确定实际监听端口：接管写入 Live 的代理地址必须与最终绑定的端口一致
+        self.ensure_listen_port().await?;
+
         // 1. 备份各应用的 Live 配置
         self.backup_live_configs().await?;
 
@@ -210,11 +232,18 @@ impl ProxyService {
             .await
             .map(|c| c.enabled)
             .unwrap_or(false);
+        let opencode_enabled = self
+            .db
+            .get_proxy_config_for_app("opencode")
+            .await
+            .map(|c| c.enabled)
+            .unwrap_or(false);
 
         Ok(ProxyTakeoverStatus {
             claude: claude_enabled,
             codex: codex_enabled,
             gemini: gemini_enabled,
+            opencode: opencode_enabled,
         })
     }
 
@@ -223,10 +252,21 @@ impl ProxyService {
     /// - 开启：自动启动代理服务，仅接管当前 app 的 Live 配置
     /// - 关闭：仅恢复当前 app 的 Live 配置；若无其它接管，则自动停止代理服务
     pub async fn set_takeover_for_app(&self, app_type: &str, enabled: bool) -> Result<(), String> {
+        let result = self.apply_takeover_for_app(app_type, enabled).await;
+        // 成功或失败都可能已部分改变状态，统一刷新托盘
+        self.refresh_tray_menu().await;
+        result
+    }
+
+    async fn apply_takeover_for_app(&self, app_type: &str, enabled: bool) -> Result<(), String> {
         let app = AppType::from_str(app_type).map_err(|e| format!("无效的应用类型: {e}"))?;
         let app_type_str = app.as_str();
 
         if enabled {
+            // 0) 只读模式下不允许改写 Live 配置；目录不可写或磁盘已满时不开始接管
+            crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+            Self::preflight_live_dirs(vec![app.clone()]).await?;
+
             // 1) 代理服务未运行则自动启动
             if !self.is_running().await {
                 self.start().await?;
@@ -368,10 +408,7 @@ impl ProxyService {
             AppType::Claude => self.read_claude_live()?,
             AppType::Codex => self.read_codex_live()?,
             AppType::Gemini => self.read_gemini_live()?,
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
-            }
+            AppType::OpenCode => self.read_opencode_live()?,
         };
 
         self.sync_live_config_to_provider(app_type, &live_config)
@@ -586,7 +623,53 @@ impl ProxyService {
                 }
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                // OpenCode 为累加模式：逐个同步 Live 中由数据库管理的供应商 apiKey
+                let Some(live_providers) = live_config.get("provider").and_then(|v| v.as_object())
+                else {
+                    return Ok(());
+                };
+
+                for (provider_id, live_provider) in live_providers {
+                    let Some(token) = live_provider
+                        .get("options")
+                        .and_then(|v| v.get("apiKey"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty() && *s != PROXY_TOKEN_PLACEHOLDER)
+                    else {
+                        continue;
+                    };
+
+                    let Ok(Some(mut provider)) =
+                        self.db.get_provider_by_id(provider_id, "opencode")
+                    else {
+                        continue;
+                    };
+
+                    if provider.settings_config.is_null() {
+                        provider.settings_config = json!({});
+                    }
+                    let Some(root) = provider.settings_config.as_object_mut() else {
+                        log::warn!(
+                            "OpenCode provider settings_config 格式异常（非对象），跳过写入 Token (provider: {provider_id})"
+                        );
+                        continue;
+                    };
+                    let options = root.entry("options").or_insert_with(|| json!({}));
+                    if let Some(options) = options.as_object_mut() {
+                        options.insert("apiKey".to_string(), json!(token));
+                    }
+
+                    if let Err(e) = self.db.update_provider_settings_config(
+                        "opencode",
+                        provider_id,
+                        &provider.settings_config,
+                    ) {
+                        log::warn!("同步 OpenCode Token 到数据库失败: {e}");
+                    } else {
+                        log::info!("已同步 OpenCode Token 到数据库 (provider: {provider_id})");
+                    }
+                }
             }
         }
 
@@ -609,17 +692,30 @@ impl ProxyService {
                 .await?;
         }
 
+        if let Ok(live_config) = self.read_opencode_live() {
+            self.sync_live_config_to_provider(&AppType::OpenCode, &live_config)
+                .await?;
+        }
+
         log::info!("Live 配置 Token 同步完成");
         Ok(())
     }
 
     /// 停止代理服务器
     pub async fn stop(&self) -> Result<(), String> {
-        if let Some(server) = self.server.write().await.take() {
-            server
-                .stop()
-                .await
-                .map_err(|e| format!("停止代理服务器失败: {e}"))?;
+        self.stop_with_grace(None).await
+    }
+
+    /// 停止代理服务器；`grace` 为 Some 时先等待进行中的请求完成（超时后强制中断）
+    pub async fn stop_with_grace(&self, grace: Option<Duration>) -> Result<(), String> {
+        // 单独取出服务器并释放写锁，避免排空请求期间阻塞 is_running 等读取方
+        let server = self.server.write().await.take();
+        if let Some(server) = server {
+            match grace {
+                Some(timeout) => server.stop_graceful(timeout).await,
+                None => server.stop().await,
+            }
+            .map_err(|e| format!("停止代理服务器失败: {e}"))?;
 
             // 停止时设置 proxy_enabled = false
             let mut global_config = self
@@ -636,6 +732,7 @@ impl ProxyService {
             }
 
             log::info!("代理服务器已停止");
+            self.refresh_tray_menu().await;
             Ok(())
         } else {
             Err("代理服务器未运行".to_string())
@@ -646,8 +743,20 @@ impl ProxyService {
     ///
     /// 会清除 settings 表中的代理状态，下次启动不会自动恢复。
     pub async fn stop_with_restore(&self) -> Result<(), String> {
+        self.stop_with_restore_inner(None).await
+    }
+
+    /// 优雅停止代理服务器并恢复 Live 配置
+    ///
+    /// 停止接受新连接后最多等待 `timeout` 让进行中的请求（含流式响应）完成，
+    /// 再恢复 Live 配置；超时则中断剩余请求。其余行为与 `stop_with_restore` 一致。
+    pub async fn stop_graceful(&self, timeout: Duration) -> Result<(), String> {
+        self.stop_with_restore_inner(Some(timeout)).await
+    }
+
+    async fn stop_with_restore_inner(&self, grace: Option<Duration>) -> Result<(), String> {
         // 1. 停止代理服务器（即使未运行也继续执行恢复逻辑）
-        if let Err(e) = self.stop().await {
+        if let Err(e) = self.stop_with_grace(grace).await {
             log::warn!("停止代理服务器失败（将继续恢复 Live 配置）: {e}");
         }
 
@@ -661,7 +770,7 @@ impl ProxyService {
             .map_err(|e| format!("清除接管状态失败: {e}"))?;
 
         // 4. 清除所有应用的 enabled 状态（用户手动关闭，不需要下次自动恢复）
-        for app_type in ["claude", "codex", "gemini"] {
+        for app_type in ["claude", "codex", "gemini", "opencode"] {
             if let Ok(mut config) = self.db.get_proxy_config_for_app(app_type).await {
                 if config.enabled {
                     config.enabled = false;
@@ -686,15 +795,20 @@ impl ProxyService {
 
         // 注意：不清除故障转移队列和开关状态，保留供下次开启代理时使用
         log::info!("代理已停止，Live 配置已恢复");
+        self.refresh_tray_menu().await;
         Ok(())
     }
 
     /// 停止代理服务器（恢复 Live 配置，但保留 settings 表中的代理状态）
     ///
-    /// 用于程序正常退出时，保留代理状态以便下次启动时自动恢复
-    pub async fn stop_with_restore_keep_state(&self) -> Result<(), String> {
+    /// 用于程序正常退出时，保留代理状态以便下次启动时自动恢复。
+    /// `grace` 为 Some 时先等待进行中的请求完成（超时后强制中断）。
+    pub async fn stop_with_restore_keep_state(
+        &self,
+        grace: Option<Duration>,
+    ) -> Result<(), String> {
         // 1. 停止代理服务器（即使未运行也继续执行恢复逻辑）
-        if let Err(e) = self.stop().await {
+        if let Err(e) = self.stop_with_grace(grace).await {
             log::warn!("停止代理服务器失败（将继续恢复 Live 配置）: {e}");
         }
 
@@ -747,7 +861,7 @@ impl ProxyService {
         }
 
         // Gemini
-        if let Ok(config) = self.read_gemini_live() {
+        if let Ok(config) = self.read_gemini_backup_snapshot() {
             let json_str = serde_json::to_string(&config)
                 .map_err(|e| format!("序列化 Gemini 配置失败: {e}"))?;
             self.db
@@ -765,11 +879,8 @@ impl ProxyService {
         let (app_type_str, config) = match app_type {
             AppType::Claude => ("claude", self.read_claude_live()?),
             AppType::Codex => ("codex", self.read_codex_live()?),
-            AppType::Gemini => ("gemini", self.read_gemini_live()?),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
-            }
+            AppType::Gemini => ("gemini", self.read_gemini_backup_snapshot()?),
+            AppType::OpenCode => ("opencode", self.read_opencode_live()?),
         };
 
         let json_str = serde_json::to_string(&config)
@@ -858,7 +969,8 @@ impl ProxyService {
                     "ANTHROPIC_AUTH_TOKEN": PROXY_TOKEN_PLACEHOLDER
                 });
             }
-            self.write_claude_live(&live_config)?;
+            self.run_blocking(move |this| this.write_claude_live(&live_config))
+                .await?;
             log::info!("Claude Live 配置已接管，代理地址: {proxy_url}");
         }
 
@@ -877,29 +989,86 @@ impl ProxyService {
             let updated_config = Self::update_toml_base_url(config_str, &proxy_codex_base_url);
             live_config["config"] = json!(updated_config);
 
-            self.write_codex_live(&live_config)?;
+            self.run_blocking(move |this| this.write_codex_live(&live_config))
+                .await?;
             log::info!("Codex Live 配置已接管，代理地址: {proxy_codex_base_url}");
         }
 
         // Gemini: 修改 GOOGLE_GEMINI_BASE_URL，使用占位符替代真实 Token（代理会注入真实 Token）
-        if let Ok(mut live_config) = self.read_gemini_live() {
-            if let Some(env) = live_config.get_mut("env").and_then(|v| v.as_object_mut()) {
-                env.insert("GOOGLE_GEMINI_BASE_URL".to_string(), json!(&proxy_url));
-                // 使用占位符，避免显示缺少 key 的警告
-                env.insert("GEMINI_API_KEY".to_string(), json!(PROXY_TOKEN_PLACEHOLDER));
-            } else {
-                live_config["env"] = json!({
-                    "GOOGLE_GEMINI_BASE_URL": &proxy_url,
-                    "GEMINI_API_KEY": PROXY_TOKEN_PLACEHOLDER
-                });
-            }
-            self.write_gemini_live(&live_config)?;
-            log::info!("Gemini Live 配置已接管，代理地址: {proxy_url}");
+        self.takeover_gemini_live(&proxy_url).await?;
+
+        Ok(())
+    }
+
+    /// 启动前检查监听端口是否被占用
+    ///
+    /// 被占用时：开启 `auto_port` 则向上查找可用端口、写回 `proxy_config` 并发射
+    /// `proxy-port-changed` 事件（之后写入 Live 配置的代理地址也使用新端口）；
+    /// 否则返回尽量指明占用进程的错误。代理已在运行时端口由自身占用，跳过检查。
+    async fn ensure_listen_port(&self) -> Result<(), String> {
+        if self.server.read().await.is_some() {
+            return Ok(());
+        }
+
+        let mut global_config = self
+            .db
+            .get_global_proxy_config()
+            .await
+            .map_err(|e| format!("获取全局代理配置失败: {e}"))?;
+        let previous_port = global_config.listen_port;
+        if !port::is_port_in_use(&global_config.listen_address, previous_port) {
+            return Ok(());
         }
+        if !global_config.auto_port {
+            return Err(port::port_in_use_message_async(previous_port).await);
+        }
+
+        let Some(new_port) = port::find_free_port(&global_config.listen_address, previous_port)
+        else {
+            return Err(format!(
+                "{}，且其后 {} 个端口均不可用",
+                port::port_in_use_message_async(previous_port).await,
+                port::MAX_PORT_SCAN
+            ));
+        };
+        global_config.listen_port = new_port;
+        self.db
+            .update_global_proxy_config(global_config)
+            .await
+            .map_err(|e| format!("保存代理端口失败: {e}"))?;
+        log::warn!("端口 {previous_port} 已被占用，代理改用端口 {new_port}");
+
+        if let Some(app_handle) = self.app_handle.read().await.as_ref() {
+            use tauri::Emitter;
 
+            let payload = json!({ "previousPort": previous_port, "port": new_port });
+            if let Err(e) = app_handle.emit(PROXY_PORT_CHANGED_EVENT, payload) {
+                log::error!("发射代理端口变更事件失败: {e}");
+            }
+        }
         Ok(())
     }
 
+    /// 改写 Live 配置前检查目录可写且剩余空间足够，避免写入占位符到一半失败
+    ///
+    /// 目录不存在的应用不会被接管，跳过检查。剩余空间查询会启动 `df` / PowerShell
+    /// 子进程，因此放到阻塞线程池中执行，避免占用异步运行时的工作线程。
+    async fn preflight_live_dirs(apps: Vec<AppType>) -> Result<(), String> {
+        tauri::async_runtime::spawn_blocking(move || {
+            for app in &apps {
+                let dir = crate::preflight::live_config_dir(app);
+                if !dir.exists() {
+                    continue;
+                }
+                crate::preflight::check_path(&dir, crate::preflight::MIN_FREE_BYTES)
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("写入前检查任务异常: {e}"))?
+    }
+
     /// 接管指定应用的 Live 配置（严格模式：目标配置不存在则返回错误）
     async fn takeover_live_config_strict(&self, app_type: &AppType) -> Result<(), String> {
         let (proxy_url, proxy_codex_base_url) = self.build_proxy_urls().await?;
@@ -942,7 +1111,8 @@ impl ProxyService {
                     });
                 }
 
-                self.write_claude_live(&live_config)?;
+                self.run_blocking(move |this| this.write_claude_live(&live_config))
+                    .await?;
                 log::info!("Claude Live 配置已接管，代理地址: {proxy_url}");
             }
             AppType::Codex => {
@@ -959,28 +1129,21 @@ impl ProxyService {
                 let updated_config = Self::update_toml_base_url(config_str, &proxy_codex_base_url);
                 live_config["config"] = json!(updated_config);
 
-                self.write_codex_live(&live_config)?;
+                self.run_blocking(move |this| this.write_codex_live(&live_config))
+                    .await?;
                 log::info!("Codex Live 配置已接管，代理地址: {proxy_codex_base_url}");
             }
             AppType::Gemini => {
-                let mut live_config = self.read_gemini_live()?;
-
-                if let Some(env) = live_config.get_mut("env").and_then(|v| v.as_object_mut()) {
-                    env.insert("GOOGLE_GEMINI_BASE_URL".to_string(), json!(&proxy_url));
-                    env.insert("GEMINI_API_KEY".to_string(), json!(PROXY_TOKEN_PLACEHOLDER));
-                } else {
-                    live_config["env"] = json!({
-                        "GOOGLE_GEMINI_BASE_URL": &proxy_url,
-                        "GEMINI_API_KEY": PROXY_TOKEN_PLACEHOLDER
-                    });
+                if !self.takeover_gemini_live(&proxy_url).await? {
+                    return Err("Gemini 配置文件（.env / settings.json）不存在".to_string());
                 }
-
-                self.write_gemini_live(&live_config)?;
-                log::info!("Gemini Live 配置已接管，代理地址: {proxy_url}");
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                return Err("OpenCode 不支持代理功能".to_string());
+                let mut live_config = self.read_opencode_live()?;
+                let taken_over = self.apply_opencode_takeover(&mut live_config, &proxy_url);
+                self.run_blocking(move |this| this.write_opencode_live(&live_config))
+                    .await?;
+                log::info!("OpenCode Live 配置已接管 {taken_over} 个供应商，代理地址: {proxy_url}");
             }
         }
 
@@ -1029,7 +1192,9 @@ impl ProxyService {
                         });
                     }
 
-                    let _ = self.write_claude_live(&live_config);
+                    let _ = self
+                        .run_blocking(move |this| this.write_claude_live(&live_config))
+                        .await;
                 }
             }
             AppType::Codex => {
@@ -1047,26 +1212,21 @@ impl ProxyService {
                         Self::update_toml_base_url(config_str, &proxy_codex_base_url);
                     live_config["config"] = json!(updated_config);
 
-                    let _ = self.write_codex_live(&live_config);
+                    let _ = self
+                        .run_blocking(move |this| this.write_codex_live(&live_config))
+                        .await;
                 }
             }
             AppType::Gemini => {
-                if let Ok(mut live_config) = self.read_gemini_live() {
-                    if let Some(env) = live_config.get_mut("env").and_then(|v| v.as_object_mut()) {
-                        env.insert("GOOGLE_GEMINI_BASE_URL".to_string(), json!(&proxy_url));
-                        env.insert("GEMINI_API_KEY".to_string(), json!(PROXY_TOKEN_PLACEHOLDER));
-                    } else {
-                        live_config["env"] = json!({
-                            "GOOGLE_GEMINI_BASE_URL": &proxy_url,
-                            "GEMINI_API_KEY": PROXY_TOKEN_PLACEHOLDER
-                        });
-                    }
-
-                    let _ = self.write_gemini_live(&live_config);
-                }
+                let _ = self.takeover_gemini_live(&proxy_url).await;
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                if let Ok(mut live_config) = self.read_opencode_live() {
+                    self.apply_opencode_takeover(&mut live_config, &proxy_url);
+                    let _ = self
+                        .run_blocking(move |this| this.write_opencode_live(&live_config))
+                        .await;
+                }
             }
         }
 
@@ -1080,7 +1240,8 @@ impl ProxyService {
                 if let Ok(Some(backup)) = self.db.get_live_backup("claude").await {
                     let config: Value = serde_json::from_str(&backup.original_config)
                         .map_err(|e| format!("解析 Claude 备份失败: {e}"))?;
-                    self.write_claude_live(&config)?;
+                    self.run_blocking(move |this| this.write_claude_live(&config))
+                        .await?;
                     log::info!("Claude Live 配置已恢复");
                 }
             }
@@ -1088,7 +1249,8 @@ impl ProxyService {
                 if let Ok(Some(backup)) = self.db.get_live_backup("codex").await {
                     let config: Value = serde_json::from_str(&backup.original_config)
                         .map_err(|e| format!("解析 Codex 备份失败: {e}"))?;
-                    self.write_codex_live(&config)?;
+                    self.run_blocking(move |this| this.write_codex_live(&config))
+                        .await?;
                     log::info!("Codex Live 配置已恢复");
                 }
             }
@@ -1096,12 +1258,19 @@ impl ProxyService {
                 if let Ok(Some(backup)) = self.db.get_live_backup("gemini").await {
                     let config: Value = serde_json::from_str(&backup.original_config)
                         .map_err(|e| format!("解析 Gemini 备份失败: {e}"))?;
-                    self.write_gemini_live(&config)?;
+                    self.run_blocking(move |this| this.restore_gemini_live(&config))
+                        .await?;
                     log::info!("Gemini Live 配置已恢复");
                 }
             }
             AppType::OpenCode => {
-                // OpenCode doesn't support proxy features, skip silently
+                if let Ok(Some(backup)) = self.db.get_live_backup("opencode").await {
+                    let config: Value = serde_json::from_str(&backup.original_config)
+                        .map_err(|e| format!("解析 OpenCode 备份失败: {e}"))?;
+                    self.run_blocking(move |this| this.restore_opencode_live(&config))
+                        .await?;
+                    log::info!("OpenCode Live 配置已恢复");
+                }
             }
         }
 
@@ -1112,7 +1281,12 @@ impl ProxyService {
     async fn restore_live_configs(&self) -> Result<(), String> {
         let mut errors = Vec::new();
 
-        for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        for app_type in [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+        ] {
             if let Err(e) = self
                 .restore_live_config_for_app_with_fallback(&app_type)
                 .await
@@ -1143,7 +1317,9 @@ impl ProxyService {
         if let Some(backup) = backup {
             let config: Value = serde_json::from_str(&backup.original_config)
                 .map_err(|e| format!("解析 {app_type_str} 备份失败: {e}"))?;
-            self.write_live_config_for_app(app_type, &config)?;
+            let app = app_type.clone();
+            self.run_blocking(move |this| this.write_live_config_for_app(&app, &config))
+                .await?;
             log::info!("{app_type_str} Live 配置已从备份恢复");
             return Ok(());
         }
@@ -1154,7 +1330,11 @@ impl ProxyService {
         }
 
         // 2.1) 优先从 SSOT（当前供应商）重建 Live（比“清理字段”更可用）
-        match self.restore_live_from_ssot_for_app(app_type) {
+        let app = app_type.clone();
+        match self
+            .run_blocking(move |this| this.restore_live_from_ssot_for_app(&app))
+            .await
+        {
             Ok(true) => {
                 log::info!("{app_type_str} Live 配置已从 SSOT 恢复（无备份兜底）");
                 return Ok(());
@@ -1172,20 +1352,34 @@ impl ProxyService {
         }
 
         // 2.2) 最后兜底：尽力清理占位符与本地代理地址，避免长期卡在代理占位符状态
-        self.cleanup_takeover_placeholders_in_live_for_app(app_type)?;
+        let app = app_type.clone();
+        self.run_blocking(move |this| this.cleanup_takeover_placeholders_in_live_for_app(&app))
+            .await?;
         log::info!("{app_type_str} Live 接管占位符已清理（无备份兜底）");
         Ok(())
     }
 
+    /// 在阻塞线程池上执行 Live 配置读写
+    ///
+    /// 目标文件被其他程序占用时 `atomic_write` 会同步睡眠重试，不能直接占用异步运行时线程。
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T, String> + Send + 'static,
+    {
+        let this = self.clone();
+        tauri::async_runtime::spawn_blocking(move || f(&this))
+            .await
+            .map_err(|e| format!("Live 配置写入任务失败: {e}"))?
+    }
+
     fn write_live_config_for_app(&self, app_type: &AppType, config: &Value) -> Result<(), String> {
         match app_type {
             AppType::Claude => self.write_claude_live(config),
             AppType::Codex => self.write_codex_live(config),
-            AppType::Gemini => self.write_gemini_live(config),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                Err("OpenCode 不支持代理功能".to_string())
-            }
+            AppType::Gemini => self.restore_gemini_live(config),
+            // OpenCode 为累加模式：只还原被接管的供应商，保留接管期间新增的内容
+            AppType::OpenCode => self.restore_opencode_live(config),
         }
     }
 
@@ -1199,14 +1393,11 @@ impl ProxyService {
                 Ok(config) => Self::is_codex_live_taken_over(&config),
                 Err(_) => false,
             },
-            AppType::Gemini => match self.read_gemini_live() {
-                Ok(config) => Self::is_gemini_live_taken_over(&config),
+            AppType::Gemini => self.detect_gemini_takeover(),
+            AppType::OpenCode => match self.read_opencode_live() {
+                Ok(config) => Self::is_opencode_live_taken_over(&config),
                 Err(_) => false,
             },
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy takeover
-                false
-            }
         }
     }
 
@@ -1216,6 +1407,11 @@ impl ProxyService {
     /// - Ok(true)：已成功写回
     /// - Ok(false)：缺少当前供应商/供应商不存在，无法写回
     fn restore_live_from_ssot_for_app(&self, app_type: &AppType) -> Result<bool, String> {
+        // OpenCode 没有“当前供应商”，逐个写回 Live 中被接管的供应商
+        if matches!(app_type, AppType::OpenCode) {
+            return self.restore_opencode_live_from_ssot();
+        }
+
         let current_id = crate::settings::get_effective_current_provider(&self.db, app_type)
             .map_err(|e| format!("获取 {app_type:?} 当前供应商失败: {e}"))?;
 
@@ -1232,8 +1428,12 @@ impl ProxyService {
             return Ok(false);
         };
 
-        write_live_snapshot(app_type, provider)
+        write_live_snapshot(&self.db, app_type, provider)
             .map_err(|e| format!("写入 {app_type:?} Live 配置失败: {e}"))?;
+        // 供应商快照只写入 .env，settings.json 中的接管占位符需单独清理
+        if matches!(app_type, AppType::Gemini) {
+            self.cleanup_gemini_settings_takeover()?;
+        }
 
         Ok(true)
     }
@@ -1246,11 +1446,35 @@ impl ProxyService {
             AppType::Claude => self.cleanup_claude_takeover_placeholders_in_live(),
             AppType::Codex => self.cleanup_codex_takeover_placeholders_in_live(),
             AppType::Gemini => self.cleanup_gemini_takeover_placeholders_in_live(),
-            AppType::OpenCode => {
-                // OpenCode doesn't support proxy features
-                Ok(())
+            AppType::OpenCode => self.cleanup_opencode_takeover_placeholders_in_live(),
+        }
+    }
+
+    fn restore_opencode_live_from_ssot(&self) -> Result<bool, String> {
+        let live_config = self.read_opencode_live()?;
+        let providers = self
+            .db
+            .get_all_providers("opencode")
+            .map_err(|e| format!("读取 OpenCode 供应商列表失败: {e}"))?;
+
+        let mut restored = false;
+        for provider in providers.values() {
+            let taken_over = live_config
+                .get("provider")
+                .and_then(|v| v.get(&provider.id))
+                .and_then(|v| v.get("options"))
+                .and_then(|v| v.get("apiKey"))
+                .and_then(|v| v.as_str())
+                == Some(PROXY_TOKEN_PLACEHOLDER);
+            if taken_over {
+                write_live_snapshot(&self.db, &AppType::OpenCode, provider)
+                    .map_err(|e| format!("写入 OpenCode Live 配置失败: {e}"))?;
+                restored = true;
             }
         }
+
+        // 仍有未受管理的占位符时交给清理逻辑处理
+        Ok(restored && !self.detect_takeover_in_live_config_for_app(&AppType::OpenCode))
     }
 
     fn is_local_proxy_url(url: &str) -> bool {
@@ -1365,15 +1589,35 @@ impl ProxyService {
         doc.to_string()
     }
 
+    /// 清理 .env 与 settings.json 中的接管占位符（两处分别处理，任一文件不存在则跳过）
     fn cleanup_gemini_takeover_placeholders_in_live(&self) -> Result<(), String> {
-        let mut config = self.read_gemini_live()?;
+        if let Ok(mut config) = self.read_gemini_live() {
+            if Self::cleanup_gemini_takeover_env(&mut config) {
+                self.write_gemini_live(&config)?;
+            }
+        }
+        self.cleanup_gemini_settings_takeover()
+    }
+
+    fn cleanup_gemini_settings_takeover(&self) -> Result<(), String> {
+        if let Ok(mut settings) = self.read_gemini_settings_live() {
+            if Self::cleanup_gemini_takeover_env(&mut settings) {
+                self.write_gemini_settings_live(&settings)?;
+            }
+        }
+        Ok(())
+    }
 
+    /// 移除 `env` 中的占位符 Token 与本地代理地址，返回是否有修改
+    fn cleanup_gemini_takeover_env(config: &mut Value) -> bool {
         let Some(env) = config.get_mut("env").and_then(|v| v.as_object_mut()) else {
-            return Ok(());
+            return false;
         };
 
+        let mut changed = false;
         if env.get("GEMINI_API_KEY").and_then(|v| v.as_str()) == Some(PROXY_TOKEN_PLACEHOLDER) {
             env.remove("GEMINI_API_KEY");
+            changed = true;
         }
 
         if env
@@ -1383,40 +1627,252 @@ impl ProxyService {
             .unwrap_or(false)
         {
             env.remove("GOOGLE_GEMINI_BASE_URL");
+            changed = true;
         }
+        changed
+    }
 
-        self.write_gemini_live(&config)?;
+    fn cleanup_opencode_takeover_placeholders_in_live(&self) -> Result<(), String> {
+        let mut config = self.read_opencode_live()?;
+        if Self::strip_opencode_takeover(&mut config) {
+            self.write_opencode_live(&config)?;
+        }
         Ok(())
     }
 
+    /// 将数据库管理的 OpenCode 供应商指向本地代理（`{proxy_url}/opencode/{id}`），返回接管数量
+    ///
+    /// 仅改写数据库中存在的供应商：代理从数据库读取真实地址与 Token，
+    /// 用户手动写入 opencode.json 的供应商保持直连。
+    fn apply_opencode_takeover(&self, config: &mut Value, proxy_url: &str) -> usize {
+        let managed: Vec<String> = match self.db.get_all_providers("opencode") {
+            Ok(providers) => providers.keys().cloned().collect(),
+            Err(e) => {
+                log::warn!("读取 OpenCode 供应商列表失败，跳过接管: {e}");
+                return 0;
+            }
+        };
+        Self::takeover_opencode_providers(config, proxy_url, &managed)
+    }
+
+    fn takeover_opencode_providers(config: &mut Value, proxy_url: &str, ids: &[String]) -> usize {
+        let Some(providers) = config.get_mut("provider").and_then(|v| v.as_object_mut()) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (id, provider) in providers.iter_mut() {
+            if !ids.contains(id) || !provider.is_object() {
+                continue;
+            }
+            if !provider.get("options").is_some_and(|v| v.is_object()) {
+                provider["options"] = json!({});
+            }
+            let options = &mut provider["options"];
+            options["baseURL"] =
+                json!(format!("{}/opencode/{id}", proxy_url.trim_end_matches('/')));
+            options["apiKey"] = json!(PROXY_TOKEN_PLACEHOLDER);
+            count += 1;
+        }
+        count
+    }
+
+    /// 用备份还原被接管的 OpenCode 供应商条目，其余内容（含接管期间新增的供应商）保持不变
+    fn merge_opencode_backup(live: &mut Value, backup: &Value) {
+        let backup_providers = backup.get("provider").and_then(|v| v.as_object());
+        let Some(providers) = live.get_mut("provider").and_then(|v| v.as_object_mut()) else {
+            return;
+        };
+
+        for (id, provider) in providers.iter_mut() {
+            let taken_over = provider
+                .get("options")
+                .and_then(|v| v.get("apiKey"))
+                .and_then(|v| v.as_str())
+                == Some(PROXY_TOKEN_PLACEHOLDER);
+            if !taken_over {
+                continue;
+            }
+            match backup_providers.and_then(|p| p.get(id)) {
+                Some(original) => *provider = original.clone(),
+                None => {
+                    Self::strip_opencode_provider_takeover(provider);
+                }
+            }
+        }
+    }
+
+    /// 移除所有 OpenCode 供应商中的占位符 Token 与本地代理地址，返回是否有改动
+    fn strip_opencode_takeover(config: &mut Value) -> bool {
+        let Some(providers) = config.get_mut("provider").and_then(|v| v.as_object_mut()) else {
+            return false;
+        };
+        let mut changed = false;
+        for provider in providers.values_mut() {
+            changed |= Self::strip_opencode_provider_takeover(provider);
+        }
+        changed
+    }
+
+    fn strip_opencode_provider_takeover(provider: &mut Value) -> bool {
+        let Some(options) = provider.get_mut("options").and_then(|v| v.as_object_mut()) else {
+            return false;
+        };
+        let mut changed = false;
+        if options.get("apiKey").and_then(|v| v.as_str()) == Some(PROXY_TOKEN_PLACEHOLDER) {
+            options.remove("apiKey");
+            changed = true;
+        }
+        if options
+            .get("baseURL")
+            .and_then(|v| v.as_str())
+            .map(Self::is_local_proxy_url)
+            .unwrap_or(false)
+        {
+            options.remove("baseURL");
+            changed = true;
+        }
+        changed
+    }
+
     /// 检查是否处于 Live 接管模式
     pub async fn is_takeover_active(&self) -> Result<bool, String> {
         let status = self.get_takeover_status().await?;
-        Ok(status.claude || status.codex || status.gemini)
+        Ok(status.claude || status.codex || status.gemini || status.opencode)
     }
 
-    /// 从异常退出中恢复（启动时调用）
+    /// 紧急恢复：把所有受管理的 Live 配置恢复到可用状态（启动时的异常退出恢复也走这里）
     ///
-    /// 检测到 Live 备份残留时调用此方法。
-    /// 会恢复 Live 配置、清除接管标志、删除备份。
-    pub async fn recover_from_crash(&self) -> Result<(), String> {
-        // 1. 恢复 Live 配置
-        self.restore_live_configs().await?;
+    /// 1. 代理运行中则立即停止；
+    /// 2. 逐个应用从 Live 备份恢复；
+    /// 3. 仍残留接管占位符时清理占位符；
+    /// 4. 依然处于接管状态时，从当前供应商重写 Live 配置（最后兜底）；
+    /// 5. 清除 proxy_config.enabled，下次启动不再自动接管；恢复成功的应用删除其备份。
+    ///
+    /// 单个应用失败不影响其他应用，结果逐应用返回；可重复执行，
+    /// 配置已正常时不会改写任何文件。
+    pub async fn emergency_restore_all(&self) -> EmergencyRestoreReport {
+        let mut report = EmergencyRestoreReport::default();
+
+        if self.is_running().await {
+            match self.stop_with_grace(None).await {
+                Ok(()) => report.proxy_stopped = true,
+                Err(e) => log::warn!("[Emergency] 停止代理失败（继续恢复 Live 配置）: {e}"),
+            }
+        }
 
-        // 2. 清除接管标志
-        self.db
-            .set_live_takeover_active(false)
-            .await
-            .map_err(|e| format!("清除接管状态失败: {e}"))?;
+        for app_type in [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+        ] {
+            let app_report = self.emergency_restore_app(&app_type).await;
+            log::info!("[Emergency] {} 恢复结果: {app_report:?}", app_type.as_str());
+            report.apps.push(app_report);
+        }
 
-        // 3. 删除备份
-        self.db
-            .delete_all_live_backups()
-            .await
-            .map_err(|e| format!("删除备份失败: {e}"))?;
+        if let Err(e) = self.db.set_live_takeover_active(false).await {
+            log::warn!("[Emergency] 清除接管状态失败: {e}");
+        }
+        if let Err(e) = self.db.clear_all_provider_health().await {
+            log::warn!("[Emergency] 重置健康状态失败: {e}");
+        }
 
-        log::info!("已从异常退出中恢复 Live 配置");
-        Ok(())
+        self.refresh_tray_menu().await;
+        report
+    }
+
+    async fn emergency_restore_app(&self, app_type: &AppType) -> EmergencyRestoreAppReport {
+        let app_type_str = app_type.as_str();
+        let mut report = EmergencyRestoreAppReport {
+            app_type: app_type_str.to_string(),
+            ..Default::default()
+        };
+
+        // 1) Live 备份
+        let mut backup_ok = true;
+        match self.db.get_live_backup(app_type_str).await {
+            Ok(Some(backup)) => {
+                let restored = serde_json::from_str::<Value>(&backup.original_config)
+                    .map_err(|e| format!("解析 {app_type_str} 备份失败: {e}"));
+                let restored = match restored {
+                    Ok(config) => {
+                        let app = app_type.clone();
+                        self.run_blocking(move |this| this.write_live_config_for_app(&app, &config))
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                match restored {
+                    Ok(()) => report.backup_restored = true,
+                    Err(e) => {
+                        backup_ok = false;
+                        report.errors.push(e);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                backup_ok = false;
+                report
+                    .errors
+                    .push(format!("获取 {app_type_str} Live 备份失败: {e}"));
+            }
+        }
+
+        // 2) 残留占位符
+        if self.detect_takeover_in_live_config_for_app(app_type) {
+            let app = app_type.clone();
+            match self
+                .run_blocking(move |this| this.cleanup_takeover_placeholders_in_live_for_app(&app))
+                .await
+            {
+                Ok(()) => report.placeholders_removed = true,
+                Err(e) => report.errors.push(e),
+            }
+        }
+
+        // 3) 最后兜底：从当前供应商重写
+        if self.detect_takeover_in_live_config_for_app(app_type) {
+            let app = app_type.clone();
+            match self
+                .run_blocking(move |this| this.restore_live_from_ssot_for_app(&app))
+                .await
+            {
+                Ok(written) => report.rewritten_from_provider = written,
+                Err(e) => report.errors.push(e),
+            }
+        }
+        report.still_taken_over = self.detect_takeover_in_live_config_for_app(app_type);
+
+        // 4) 清除自动接管标志
+        match self.db.get_proxy_config_for_app(app_type_str).await {
+            Ok(mut config) if config.enabled => {
+                config.enabled = false;
+                match self.db.update_proxy_config_for_app(config).await {
+                    Ok(()) => report.takeover_disabled = true,
+                    Err(e) => report
+                        .errors
+                        .push(format!("清除 {app_type_str} enabled 状态失败: {e}")),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => report
+                .errors
+                .push(format!("读取 {app_type_str} 代理配置失败: {e}")),
+        }
+
+        // 5) 备份已恢复（或本就没有）且 Live 已正常时删除备份；否则保留以便再次尝试
+        if backup_ok && !report.still_taken_over {
+            if let Err(e) = self.db.delete_live_backup(app_type_str).await {
+                report
+                    .errors
+                    .push(format!("删除 {app_type_str} Live 备份失败: {e}"));
+            }
+        }
+
+        report
     }
 
     /// 检测 Live 配置是否处于“被接管”的残留状态
@@ -1436,8 +1892,12 @@ impl ProxyService {
             }
         }
 
-        if let Ok(config) = self.read_gemini_live() {
-            if Self::is_gemini_live_taken_over(&config) {
+        if self.detect_gemini_takeover() {
+            return true;
+        }
+
+        if let Ok(config) = self.read_opencode_live() {
+            if Self::is_opencode_live_taken_over(&config) {
                 return true;
             }
         }
@@ -1481,6 +1941,19 @@ impl ProxyService {
         env.get("GEMINI_API_KEY").and_then(|v| v.as_str()) == Some(PROXY_TOKEN_PLACEHOLDER)
     }
 
+    fn is_opencode_live_taken_over(config: &Value) -> bool {
+        let Some(providers) = config.get("provider").and_then(|v| v.as_object()) else {
+            return false;
+        };
+        providers.values().any(|provider| {
+            provider
+                .get("options")
+                .and_then(|v| v.get("apiKey"))
+                .and_then(|v| v.as_str())
+                == Some(PROXY_TOKEN_PLACEHOLDER)
+        })
+    }
+
     /// 从供应商配置更新 Live 备份（用于代理模式下的热切换）
     ///
     /// 与 backup_live_configs() 不同，此方法从供应商的 settings_config 生成备份，
@@ -1504,14 +1977,38 @@ impl ProxyService {
             "gemini" => {
                 // Gemini: 只提取 env 字段（与原始备份格式一致）
                 // proxy.rs 的 read_gemini_live() 返回 {"env": {...}}
-                let env_backup = if let Some(env) = provider.settings_config.get("env") {
+                let mut env_backup = if let Some(env) = provider.settings_config.get("env") {
                     json!({ "env": env })
                 } else {
                     json!({ "env": {} })
                 };
+                // settings.json 不随供应商变化，沿用原备份中的 settingsEnv
+                if let Ok(Some(existing)) = self.db.get_live_backup("gemini").await {
+                    if let Some(settings_env) =
+                        serde_json::from_str::<Value>(&existing.original_config)
+                            .ok()
+                            .and_then(|v| v.get("settingsEnv").cloned())
+                    {
+                        env_backup["settingsEnv"] = settings_env;
+                    }
+                }
                 serde_json::to_string(&env_backup)
                     .map_err(|e| format!("序列化 Gemini 配置失败: {e}"))?
             }
+            "opencode" => {
+                // OpenCode: 备份为完整的 opencode.json，仅替换该供应商条目
+                let mut backup = match self.db.get_live_backup("opencode").await {
+                    Ok(Some(existing)) => serde_json::from_str(&existing.original_config)
+                        .map_err(|e| format!("解析 OpenCode 备份失败: {e}"))?,
+                    _ => json!({}),
+                };
+                if !backup.get("provider").is_some_and(|v| v.is_object()) {
+                    backup["provider"] = json!({});
+                }
+                backup["provider"][&provider.id] = provider.settings_config.clone();
+                serde_json::to_string(&backup)
+                    .map_err(|e| format!("序列化 OpenCode 配置失败: {e}"))?
+            }
             _ => return Err(format!("未知的应用类型: {app_type}")),
         };
 
@@ -1524,11 +2021,30 @@ impl ProxyService {
         Ok(())
     }
 
-    /// 代理模式下切换供应商（热切换，不写 Live）
-    pub async fn switch_proxy_target(
-        &self,
-        app_type: &str,
-        provider_id: &str,
+    /// OpenCode 接管期间新增/编辑供应商后调用：更新备份中的该条目，并将 Live 条目重新指向代理
+    pub async fn retake_opencode_provider(&self, provider: &Provider) -> Result<(), String> {
+        self.update_live_backup_from_provider("opencode", provider)
+            .await?;
+
+        let (proxy_url, _) = self.build_proxy_urls().await?;
+        let mut live_config = self.read_opencode_live()?;
+        if Self::takeover_opencode_providers(
+            &mut live_config,
+            &proxy_url,
+            std::slice::from_ref(&provider.id),
+        ) > 0
+        {
+            self.run_blocking(move |this| this.write_opencode_live(&live_config))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 代理模式下切换供应商（热切换，不写 Live）
+    pub async fn switch_proxy_target(
+        &self,
+        app_type: &str,
+        provider_id: &str,
     ) -> Result<(), String> {
         // 代理模式切换供应商（热切换）：
         // - 更新 SSOT（数据库 is_current）
@@ -1574,6 +2090,29 @@ impl ProxyService {
         Ok(())
     }
 
+    /// 将已记录的请求重放到指定供应商（需开启请求体捕获），返回新响应摘要
+    ///
+    /// 重放记录以 `replay_of` 标记，不计入使用统计。
+    pub async fn replay_request(
+        &self,
+        request_id: &str,
+        target_provider_id: &str,
+    ) -> Result<ReplayResult, String> {
+        crate::proxy::replay::replay_request(&self.db, request_id, target_provider_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// 将已记录的请求还原为 curl 命令（认证信息默认脱敏）
+    pub fn request_as_curl(
+        &self,
+        request_id: &str,
+        include_auth: bool,
+    ) -> Result<CurlExport, String> {
+        crate::proxy::replay::request_as_curl(&self.db, request_id, include_auth)
+            .map_err(|e| e.to_string())
+    }
+
     // ==================== Live 配置读写辅助方法 ====================
 
     /// 更新 TOML 字符串中的 base_url
@@ -1621,8 +2160,9 @@ impl ProxyService {
         doc.to_string()
     }
 
+    /// 读取 Claude Live 配置（profiles 写入模式下读取 settings.json 引用的 profile）
     fn read_claude_live(&self) -> Result<Value, String> {
-        let path = get_claude_settings_path();
+        let path = get_claude_live_path();
         if !path.exists() {
             return Err("Claude 配置文件不存在".to_string());
         }
@@ -1652,10 +2192,19 @@ impl ProxyService {
         Ok(value)
     }
 
+    /// 写入 Claude Live 配置
+    ///
+    /// profiles 写入模式下写入当前供应商的 profile 并更新 settings.json 指针，
+    /// 因此代理模式热切换后停止代理时，备份会恢复到新供应商的 profile。
     fn write_claude_live(&self, config: &Value) -> Result<(), String> {
-        let path = get_claude_settings_path();
-        let settings = crate::services::provider::sanitize_claude_settings_for_live(config);
-        write_json_file(&path, &settings).map_err(|e| format!("写入 Claude 配置失败: {e}"))
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+
+        let current_id =
+            crate::settings::get_effective_current_provider(&self.db, &AppType::Claude)
+                .ok()
+                .flatten();
+        write_claude_live(current_id.as_deref(), config)
+            .map_err(|e| format!("写入 Claude 配置失败: {e}"))
     }
 
     fn read_codex_live(&self) -> Result<Value, String> {
@@ -1688,6 +2237,8 @@ impl ProxyService {
             get_codex_auth_path, get_codex_config_path, write_codex_live_atomic,
         };
 
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+
         let auth = config.get("auth");
         let config_str = config.get("config").and_then(|v| v.as_str());
 
@@ -1725,11 +2276,178 @@ impl ProxyService {
     fn write_gemini_live(&self, config: &Value) -> Result<(), String> {
         use crate::gemini_config::{json_to_env, write_gemini_env_atomic};
 
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+
         let env_map = json_to_env(config).map_err(|e| format!("转换 Gemini 配置失败: {e}"))?;
         write_gemini_env_atomic(&env_map).map_err(|e| format!("写入 Gemini env 失败: {e}"))?;
         Ok(())
     }
 
+    fn read_gemini_settings_live(&self) -> Result<Value, String> {
+        let path = crate::gemini_config::get_gemini_settings_path();
+        if !path.exists() {
+            return Err("Gemini settings.json 文件不存在".to_string());
+        }
+        read_json_file(&path).map_err(|e| format!("读取 Gemini settings.json 失败: {e}"))
+    }
+
+    fn write_gemini_settings_live(&self, settings: &Value) -> Result<(), String> {
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+        let path = crate::gemini_config::get_gemini_settings_path();
+        write_json_file(&path, settings).map_err(|e| format!("写入 Gemini settings.json 失败: {e}"))
+    }
+
+    /// 接管 Gemini 配置，返回是否写入了任一文件
+    ///
+    /// `.env` 存在即写入；settings.json 存在且已安装的 Gemini CLI 会读取其中的 `env` 时写入。
+    async fn takeover_gemini_live(&self, proxy_url: &str) -> Result<bool, String> {
+        use crate::gemini_config::{
+            gemini_live_targets, get_gemini_env_path, get_gemini_settings_path,
+        };
+
+        // 未命中版本缓存时需要执行 `gemini --version`，放到阻塞线程避免占用异步运行时
+        let cli_version = tauri::async_runtime::spawn_blocking(|| {
+            crate::commands::installed_cli_version("gemini")
+        })
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("检测 Gemini CLI 版本失败: {e}");
+            None
+        });
+        let targets = gemini_live_targets(
+            cli_version.as_deref(),
+            get_gemini_env_path().exists(),
+            get_gemini_settings_path().exists(),
+        );
+        let proxy_url = proxy_url.to_string();
+        self.run_blocking(move |this| this.takeover_gemini_targets(targets, &proxy_url))
+            .await?;
+        Ok(targets.any())
+    }
+
+    fn takeover_gemini_targets(
+        &self,
+        targets: GeminiLiveTargets,
+        proxy_url: &str,
+    ) -> Result<(), String> {
+        if targets.env_file {
+            let mut live_config = self.read_gemini_live()?;
+            Self::apply_gemini_takeover_env(&mut live_config, proxy_url);
+            self.write_gemini_live(&live_config)?;
+        }
+        if targets.settings_file {
+            let mut settings = self.read_gemini_settings_live()?;
+            if settings.is_object() {
+                Self::apply_gemini_takeover_env(&mut settings, proxy_url);
+                self.write_gemini_settings_live(&settings)?;
+            }
+        }
+
+        if targets.any() {
+            log::info!(
+                "Gemini Live 配置已接管（.env: {}, settings.json: {}），代理地址: {proxy_url}",
+                targets.env_file,
+                targets.settings_file
+            );
+        }
+        Ok(())
+    }
+
+    /// 在 `env` 中写入代理地址与占位符 Token（代理会注入真实 Token）
+    fn apply_gemini_takeover_env(config: &mut Value, proxy_url: &str) {
+        if let Some(env) = config.get_mut("env").and_then(|v| v.as_object_mut()) {
+            env.insert("GOOGLE_GEMINI_BASE_URL".to_string(), json!(proxy_url));
+            // 使用占位符，避免显示缺少 key 的警告
+            env.insert("GEMINI_API_KEY".to_string(), json!(PROXY_TOKEN_PLACEHOLDER));
+        } else {
+            config["env"] = json!({
+                "GOOGLE_GEMINI_BASE_URL": proxy_url,
+                "GEMINI_API_KEY": PROXY_TOKEN_PLACEHOLDER
+            });
+        }
+    }
+
+    /// Gemini 备份快照：`.env` 内容（`env`）与 settings.json 中的 `env`（`settingsEnv`）
+    ///
+    /// 只记录存在的文件；settings.json 没有 `env` 时 `settingsEnv` 为 null，恢复时会移除该字段。
+    fn read_gemini_backup_snapshot(&self) -> Result<Value, String> {
+        let env_config = self.read_gemini_live();
+        let settings = self.read_gemini_settings_live().ok();
+
+        let mut snapshot = match (env_config, &settings) {
+            (Ok(config), _) => config,
+            (Err(_), Some(_)) => json!({}),
+            (Err(e), None) => return Err(e),
+        };
+        if let Some(settings) = settings {
+            snapshot["settingsEnv"] = settings.get("env").cloned().unwrap_or(Value::Null);
+        }
+        Ok(snapshot)
+    }
+
+    /// 从备份恢复 Gemini 配置（.env 与 settings.json 的 `env`）
+    ///
+    /// 旧版本备份没有 `settingsEnv`，此时只清理 settings.json 中的接管占位符。
+    fn restore_gemini_live(&self, config: &Value) -> Result<(), String> {
+        if config.get("env").is_some() {
+            self.write_gemini_live(config)?;
+        }
+
+        let Some(settings_env) = config.get("settingsEnv") else {
+            return self.cleanup_gemini_settings_takeover();
+        };
+        let Ok(mut settings) = self.read_gemini_settings_live() else {
+            return Ok(());
+        };
+        let Some(obj) = settings.as_object_mut() else {
+            return Ok(());
+        };
+        if settings_env.is_null() {
+            obj.remove("env");
+        } else {
+            obj.insert("env".to_string(), settings_env.clone());
+        }
+        self.write_gemini_settings_live(&settings)
+    }
+
+    /// .env 或 settings.json 任一处包含接管占位符即视为已接管
+    fn detect_gemini_takeover(&self) -> bool {
+        self.read_gemini_live()
+            .is_ok_and(|config| Self::is_gemini_live_taken_over(&config))
+            || self
+                .read_gemini_settings_live()
+                .is_ok_and(|settings| Self::is_gemini_live_taken_over(&settings))
+    }
+
+    fn read_opencode_live(&self) -> Result<Value, String> {
+        use crate::opencode_config::{get_opencode_config_path, read_opencode_config};
+
+        if !get_opencode_config_path().exists() {
+            return Err("OpenCode 配置文件不存在".to_string());
+        }
+
+        read_opencode_config().map_err(|e| format!("读取 OpenCode 配置失败: {e}"))
+    }
+
+    fn write_opencode_live(&self, config: &Value) -> Result<(), String> {
+        crate::settings::ensure_live_writable().map_err(|e| e.to_string())?;
+
+        crate::opencode_config::write_opencode_config(config)
+            .map_err(|e| format!("写入 OpenCode 配置失败: {e}"))
+    }
+
+    /// 从备份恢复 OpenCode Live（合并还原，Live 不存在时整体写回）
+    fn restore_opencode_live(&self, backup: &Value) -> Result<(), String> {
+        let config = match self.read_opencode_live() {
+            Ok(mut live) => {
+                Self::merge_opencode_backup(&mut live, backup);
+                live
+            }
+            Err(_) => backup.clone(),
+        };
+        self.write_opencode_live(&config)
+    }
+
     // ==================== 原有方法 ====================
 
     /// 获取服务器状态
@@ -1819,6 +2537,11 @@ impl ProxyService {
                         .await?;
                     updated_any = true;
                 }
+                if takeover.opencode {
+                    self.takeover_live_config_best_effort(&AppType::OpenCode)
+                        .await?;
+                    updated_any = true;
+                }
 
                 if updated_any {
                     log::info!("已同步更新 Live 配置中的代理地址");
@@ -1839,6 +2562,38 @@ impl ProxyService {
         self.server.read().await.is_some()
     }
 
+    /// 代理运行/接管状态变化后刷新托盘菜单（任意入口触发都保持托盘一致）
+    async fn refresh_tray_menu(&self) {
+        let Some(app_handle) = self.app_handle.read().await.clone() else {
+            return;
+        };
+        // 构建菜单时会同步查询代理状态，放到阻塞线程执行
+        tauri::async_runtime::spawn_blocking(move || crate::tray::refresh_tray_menu(&app_handle));
+    }
+
+    /// 获取指定 Provider 的熔断器状态（代理未运行时返回 None）
+    pub async fn get_circuit_breaker_stats(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Option<crate::proxy::CircuitBreakerStats> {
+        let server = self.server.read().await;
+        server
+            .as_ref()?
+            .get_circuit_breaker_stats(provider_id, app_type)
+            .await
+    }
+
+    /// 获取所有熔断器状态（代理未运行时为空）
+    pub async fn get_all_circuit_breaker_stats(
+        &self,
+    ) -> Vec<(String, String, crate::proxy::CircuitBreakerStats)> {
+        match self.server.read().await.as_ref() {
+            Some(server) => server.get_all_circuit_breaker_stats().await,
+            None => Vec::new(),
+        }
+    }
+
     /// 热更新熔断器配置
     ///
     /// 如果代理服务器正在运行，将新配置应用到所有已创建的熔断器实例
@@ -1871,6 +2626,192 @@ impl ProxyService {
         }
         Ok(())
     }
+
+    /// 强制打开指定 Provider 的熔断器（故障转移时视为不可用）
+    ///
+    /// `until_ts` 为失效时间（毫秒时间戳），None 表示直到手动恢复自动
+    pub async fn force_open_circuit(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        until_ts: Option<i64>,
+    ) -> Result<(), String> {
+        self.set_circuit_override(
+            provider_id,
+            app_type,
+            Some(ForcedCircuitState::Open),
+            until_ts,
+        )
+        .await
+    }
+
+    /// 强制关闭指定 Provider 的熔断器（忽略失败统计，始终放行）
+    pub async fn force_close_circuit(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        until_ts: Option<i64>,
+    ) -> Result<(), String> {
+        self.set_circuit_override(
+            provider_id,
+            app_type,
+            Some(ForcedCircuitState::Closed),
+            until_ts,
+        )
+        .await
+    }
+
+    /// 清除手动熔断状态，恢复由熔断器自动判断
+    pub async fn force_auto_circuit(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Result<(), String> {
+        self.set_circuit_override(provider_id, app_type, None, None)
+            .await
+    }
+
+    async fn set_circuit_override(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        forced_state: Option<ForcedCircuitState>,
+        until_ts: Option<i64>,
+    ) -> Result<(), String> {
+        if until_ts.is_some_and(|until| until <= chrono::Utc::now().timestamp_millis()) {
+            return Err(crate::error::AppError::localized(
+                "circuit.override_expired",
+                "手动熔断状态的失效时间必须晚于当前时间",
+                "The override expiry must be in the future",
+            )
+            .to_string());
+        }
+
+        self.db
+            .set_provider_circuit_override(provider_id, app_type, forced_state, until_ts)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(server) = self.server.read().await.as_ref() {
+            server
+                .set_circuit_override(provider_id, app_type, forced_state, until_ts)
+                .await;
+        }
+
+        let mode = forced_state.map_or("auto", |state| state.as_str());
+        log::info!("已将 Provider {provider_id} (app: {app_type}) 的熔断器设为 {mode}");
+        Ok(())
+    }
+
+    /// 发射熔断器状态变化事件（由 ProviderRouter 在状态转换并落库后调用）
+    pub(crate) fn emit_circuit_breaker_event(
+        app_handle: &tauri::AppHandle,
+        event: &crate::proxy::circuit_breaker::CircuitBreakerEvent,
+    ) {
+        use tauri::Emitter;
+
+        if let Err(e) = app_handle.emit(CIRCUIT_BREAKER_STATE_CHANGED_EVENT, event) {
+            log::error!("[CircuitBreaker] 发射状态变化事件失败: {e}");
+        }
+    }
+
+    /// 查询熔断器状态转换历史（按时间倒序）
+    pub fn get_circuit_breaker_history(
+        &self,
+        app_type: &str,
+        provider_id: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<crate::proxy::circuit_breaker::CircuitBreakerEvent>, String> {
+        let limit = limit
+            .filter(|l| *l > 0)
+            .unwrap_or(CIRCUIT_BREAKER_HISTORY_DEFAULT_LIMIT);
+        self.db
+            .get_circuit_breaker_events(app_type, provider_id, limit)
+            .map_err(|e| format!("查询熔断器历史失败: {e}"))
+    }
+
+    /// 模拟指定供应商故障时的故障转移过程（不发送请求、不改变熔断器状态）
+    ///
+    /// 使用当前的故障转移开关、队列顺序、手动熔断状态，以及运行中代理的熔断器状态；
+    /// 代理未运行时熔断器视为关闭（与重新启动代理后的状态一致）。
+    pub async fn simulate_failover(
+        &self,
+        app_type: &str,
+        failing_provider_ids: &[String],
+    ) -> Result<FailoverSimulation, String> {
+        let auto_failover_enabled = self
+            .db
+            .get_proxy_config_for_app(app_type)
+            .await
+            .map_err(|e| e.to_string())?
+            .auto_failover_enabled;
+
+        let targets: Vec<(String, String)> = if auto_failover_enabled {
+            self.db
+                .get_failover_queue(app_type)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|item| (item.provider_id, item.provider_name))
+                .collect()
+        } else {
+            let current = crate::proxy::provider_router::current_provider_id(&self.db, app_type);
+            match current {
+                Some(id) => self
+                    .db
+                    .get_provider_by_id(&id, app_type)
+                    .map_err(|e| e.to_string())?
+                    .map(|p| vec![(p.id, p.name)])
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
+
+        let server = self.server.read().await;
+        let mut candidates = Vec::with_capacity(targets.len());
+        for (provider_id, provider_name) in targets {
+            let health = self
+                .db
+                .get_provider_health(&provider_id, app_type)
+                .await
+                .map_err(|e| e.to_string())?;
+            let circuit = match server.as_ref() {
+                Some(server) => server.peek_circuit(&provider_id, app_type).await,
+                None => None,
+            };
+            let (circuit_state, circuit_available) =
+                circuit.unwrap_or((crate::proxy::CircuitState::Closed, true));
+            // 与路由一致：仅对设置了限额的供应商查询（未开启限额强制时 limit_tripped 恒为 false）
+            let limit_tripped = match self
+                .db
+                .get_provider_by_id(&provider_id, app_type)
+                .map_err(|e| e.to_string())?
+            {
+                Some(provider) if crate::proxy::provider_router::has_spending_limits(&provider) => {
+                    self.db
+                        .check_provider_limits(&provider_id, app_type)
+                        .map_err(|e| e.to_string())?
+                        .limit_tripped
+                }
+                _ => false,
+            };
+            candidates.push(SimulationCandidate {
+                provider_id,
+                provider_name,
+                forced_state: health.forced_state,
+                is_healthy: health.is_healthy,
+                consecutive_failures: health.consecutive_failures,
+                circuit_state,
+                circuit_available,
+                limit_tripped,
+            });
+        }
+
+        Ok(failover_simulation::simulate_failover(
+            app_type,
+            auto_failover_enabled,
+            candidates,
+            failing_provider_ids,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -1980,6 +2921,56 @@ model = "gpt-5.1-codex"
         assert_eq!(base_url, new_url);
     }
 
+    #[test]
+    fn opencode_takeover_only_rewrites_managed_providers_and_restores_from_backup() {
+        let original = json!({
+            "$schema": "https://opencode.ai/config.json",
+            "provider": {
+                "relay": {
+                    "npm": "@ai-sdk/openai-compatible",
+                    "options": { "baseURL": "https://relay.example.com/v1", "apiKey": "sk-relay" }
+                },
+                "manual": {
+                    "npm": "@ai-sdk/anthropic",
+                    "options": { "apiKey": "sk-manual" }
+                }
+            }
+        });
+
+        let mut live = original.clone();
+        let count = ProxyService::takeover_opencode_providers(
+            &mut live,
+            "http://127.0.0.1:15721/",
+            &["relay".to_string()],
+        );
+        assert_eq!(count, 1);
+        assert_eq!(
+            live["provider"]["relay"]["options"]["baseURL"],
+            "http://127.0.0.1:15721/opencode/relay"
+        );
+        assert!(ProxyService::is_opencode_live_taken_over(&live));
+        assert_eq!(live["provider"]["manual"], original["provider"]["manual"]);
+
+        // 接管期间新增的供应商在恢复后保留
+        live["provider"]["added"] = json!({ "options": { "apiKey": "sk-added" } });
+        let mut restored = live.clone();
+        ProxyService::merge_opencode_backup(&mut restored, &original);
+        assert_eq!(restored["provider"]["relay"], original["provider"]["relay"]);
+        assert_eq!(
+            restored["provider"]["added"]["options"]["apiKey"],
+            "sk-added"
+        );
+        assert!(!ProxyService::is_opencode_live_taken_over(&restored));
+
+        // 无备份时仅清理占位符与本地代理地址
+        assert!(ProxyService::strip_opencode_takeover(&mut live));
+        assert!(live["provider"]["relay"]["options"].get("apiKey").is_none());
+        assert!(live["provider"]["relay"]["options"]
+            .get("baseURL")
+            .is_none());
+        assert!(!ProxyService::strip_opencode_takeover(&mut live));
+    }
+
     #[tokio::test]
     #[serial]
     async fn sync_claude_token_does_not_add_anthropic_api_key() {
@@ -2153,4 +3144,226 @@ model = "gpt-5.1-codex"
         let expected = serde_json::to_string(&provider_b.settings_config).expect("serialize");
         assert_eq!(backup.original_config, expected);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn emergency_restore_all_restores_backup_and_is_idempotent() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
+
+        // 模拟异常退出：Live 已写成占位符，备份与 enabled 标志残留
+        service
+            .write_claude_live(&json!({
+                "env": { "ANTHROPIC_AUTH_TOKEN": PROXY_TOKEN_PLACEHOLDER }
+            }))
+            .expect("seed taken-over live");
+        db.save_live_backup("claude", "{\"env\":{\"ANTHROPIC_AUTH_TOKEN\":\"real\"}}")
+            .await
+            .expect("seed live backup");
+        let mut config = db.get_proxy_config_for_app("claude").await.expect("config");
+        config.enabled = true;
+        db.update_proxy_config_for_app(config)
+            .await
+            .expect("enable takeover");
+
+        let report = service.emergency_restore_all().await;
+        let claude = &report.apps[0];
+        assert_eq!(claude.app_type, "claude");
+        assert!(claude.backup_restored);
+        assert!(claude.takeover_disabled);
+        assert!(!claude.still_taken_over);
+        assert!(claude.errors.is_empty(), "{:?}", claude.errors);
+        assert!(!report.proxy_stopped);
+
+        let live = service.read_claude_live().expect("read live");
+        assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "real");
+        assert!(db
+            .get_live_backup("claude")
+            .await
+            .expect("backup")
+            .is_none());
+        assert!(
+            !db.get_proxy_config_for_app("claude")
+                .await
+                .expect("config")
+                .enabled
+        );
+
+        // 再次执行：没有需要处理的内容
+        let again = service.emergency_restore_all().await;
+        for app in &again.apps {
+            assert!(!app.backup_restored && !app.placeholders_removed);
+            assert!(!app.rewritten_from_provider && !app.takeover_disabled);
+            assert!(app.errors.is_empty(), "{:?}", app.errors);
+        }
+    }
+
+    fn write_gemini_files(env: Option<&str>, settings: Option<Value>) {
+        let dir = crate::gemini_config::get_gemini_dir();
+        std::fs::create_dir_all(&dir).expect("create gemini dir");
+        if let Some(env) = env {
+            std::fs::write(dir.join(".env"), env).expect("write .env");
+        }
+        if let Some(settings) = settings {
+            std::fs::write(dir.join("settings.json"), settings.to_string())
+                .expect("write settings.json");
+        }
+    }
+
+    fn read_gemini_settings_file() -> Value {
+        let path = crate::gemini_config::get_gemini_settings_path();
+        serde_json::from_str(&std::fs::read_to_string(path).expect("read settings.json"))
+            .expect("parse settings.json")
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gemini_takeover_and_restore_cover_env_and_settings() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
+
+        write_gemini_files(
+            Some("GEMINI_API_KEY=real-key\nGOOGLE_GEMINI_BASE_URL=https://up.example\n"),
+            Some(json!({
+                "mcpServers": {"fs": {"command": "npx"}},
+                "env": {"GEMINI_API_KEY": "settings-key"}
+            })),
+        );
+
+        service
+            .backup_live_config_strict(&AppType::Gemini)
+            .await
+            .expect("backup");
+        let targets = GeminiLiveTargets {
+            env_file: true,
+            settings_file: true,
+        };
+        service
+            .takeover_gemini_targets(targets, "http://127.0.0.1:15721")
+            .expect("takeover");
+
+        let env = crate::gemini_config::read_gemini_env().expect("read env");
+        assert_eq!(env["GEMINI_API_KEY"], PROXY_TOKEN_PLACEHOLDER);
+        assert_eq!(env["GOOGLE_GEMINI_BASE_URL"], "http://127.0.0.1:15721");
+        let settings = read_gemini_settings_file();
+        assert_eq!(settings["env"]["GEMINI_API_KEY"], PROXY_TOKEN_PLACEHOLDER);
+        assert_eq!(
+            settings["env"]["GOOGLE_GEMINI_BASE_URL"],
+            "http://127.0.0.1:15721"
+        );
+        assert!(service.detect_takeover_in_live_config_for_app(&AppType::Gemini));
+
+        service
+            .restore_live_config_for_app(&AppType::Gemini)
+            .await
+            .expect("restore");
+
+        let env = crate::gemini_config::read_gemini_env().expect("read env");
+        assert_eq!(env["GEMINI_API_KEY"], "real-key");
+        assert_eq!(env["GOOGLE_GEMINI_BASE_URL"], "https://up.example");
+        let settings = read_gemini_settings_file();
+        assert_eq!(settings["env"], json!({"GEMINI_API_KEY": "settings-key"}));
+        assert_eq!(settings["mcpServers"]["fs"]["command"], "npx");
+        assert!(!service.detect_takeover_in_live_config_for_app(&AppType::Gemini));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gemini_restore_removes_settings_env_added_by_takeover() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
+
+        // 只有 settings.json（新版 CLI），且原本没有 env 字段
+        write_gemini_files(None, Some(json!({"theme": "dark"})));
+
+        service
+            .backup_live_config_strict(&AppType::Gemini)
+            .await
+            .expect("backup");
+        let targets = GeminiLiveTargets {
+            env_file: false,
+            settings_file: true,
+        };
+        service
+            .takeover_gemini_targets(targets, "http://127.0.0.1:15721")
+            .expect("takeover");
+        assert!(service.detect_takeover_in_live_config_for_app(&AppType::Gemini));
+
+        service
+            .restore_live_config_for_app(&AppType::Gemini)
+            .await
+            .expect("restore");
+
+        assert_eq!(read_gemini_settings_file(), json!({"theme": "dark"}));
+        assert!(
+            !crate::gemini_config::get_gemini_env_path().exists(),
+            "restore should not create a .env that did not exist"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gemini_takeover_for_old_cli_leaves_settings_untouched() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
+
+        let original_settings = json!({"env": {"GEMINI_API_KEY": "settings-key"}});
+        write_gemini_files(
+            Some("GEMINI_API_KEY=real-key\n"),
+            Some(original_settings.clone()),
+        );
+
+        let targets = crate::gemini_config::gemini_live_targets(Some("0.9.0"), true, true);
+        service
+            .takeover_gemini_targets(targets, "http://127.0.0.1:15721")
+            .expect("takeover");
+
+        let env = crate::gemini_config::read_gemini_env().expect("read env");
+        assert_eq!(env["GEMINI_API_KEY"], PROXY_TOKEN_PLACEHOLDER);
+        assert_eq!(read_gemini_settings_file(), original_settings);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gemini_crash_recovery_cleans_both_files_without_backup() {
+        let _home = TempHome::new();
+        crate::settings::reload_settings().expect("reload settings");
+        let db = Arc::new(Database::memory().expect("init db"));
+        let service = ProxyService::new(db.clone());
+
+        write_gemini_files(
+            Some(&format!(
+                "GEMINI_API_KEY={PROXY_TOKEN_PLACEHOLDER}\nGOOGLE_GEMINI_BASE_URL=http://127.0.0.1:15721\nGEMINI_MODEL=gemini-2.5-pro\n"
+            )),
+            Some(json!({
+                "env": {
+                    "GEMINI_API_KEY": PROXY_TOKEN_PLACEHOLDER,
+                    "GOOGLE_GEMINI_BASE_URL": "http://127.0.0.1:15721"
+                }
+            })),
+        );
+        assert!(service.detect_takeover_in_live_config_for_app(&AppType::Gemini));
+
+        // 无备份、无当前供应商：走占位符清理兜底
+        service
+            .restore_live_config_for_app_with_fallback(&AppType::Gemini)
+            .await
+            .expect("fallback restore");
+
+        let env = crate::gemini_config::read_gemini_env().expect("read env");
+        assert!(!env.contains_key("GEMINI_API_KEY"));
+        assert!(!env.contains_key("GOOGLE_GEMINI_BASE_URL"));
+        assert_eq!(env["GEMINI_MODEL"], "gemini-2.5-pro");
+        assert_eq!(read_gemini_settings_file()["env"], json!({}));
+        assert!(!service.detect_takeover_in_live_config_for_app(&AppType::Gemini));
+    }
 }
@@ -0,0 +1,223 @@
+//! 应用偏好设置导入/导出
+//!
+//! 只包含 `AppSettings`、日志配置与本地代理监听默认值，不包含任何供应商密钥
+//! （供应商数据请通过配置导出功能迁移）。
+
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::proxy::types::LogConfig;
+use crate::settings::AppSettings;
+
+/// 导出文件格式版本
+pub const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+/// 本地代理监听默认值（不含代理总开关，导入后不会自动启动代理）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyDefaults {
+    pub listen_address: String,
+    pub listen_port: u16,
+    pub enable_logging: bool,
+}
+
+/// 偏好设置导出文件内容
+///
+/// 各段均可缺省，未知字段会被忽略，以兼容新版本导出的文件。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: i64,
+    #[serde(default)]
+    pub settings: AppSettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_config: Option<LogConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_defaults: Option<ProxyDefaults>,
+}
+
+pub struct SettingsTransferService;
+
+impl SettingsTransferService {
+    /// 导出当前偏好设置到 JSON 文件
+    pub async fn export_to_file(db: &Database, path: &Path) -> Result<SettingsExport, AppError> {
+        let proxy = db.get_global_proxy_config().await?;
+        let export = SettingsExport {
+            version: SETTINGS_EXPORT_VERSION,
+            exported_at: Utc::now().timestamp(),
+            settings: Self::strip_device_local(crate::settings::get_settings()),
+            log_config: Some(db.get_log_config()?),
+            proxy_defaults: Some(ProxyDefaults {
+                listen_address: proxy.listen_address,
+                listen_port: proxy.listen_port,
+                enable_logging: proxy.enable_logging,
+            }),
+        };
+        // 文件被占用时写入会同步重试，放到阻塞线程执行
+        let target = path.to_path_buf();
+        let content = export.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::config::write_json_file(&target, &content)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("导出偏好设置失败: {e}")))??;
+        log::info!("已导出偏好设置到 {}", path.display());
+        Ok(export)
+    }
+
+    /// 从 JSON 文件导入偏好设置，返回导入后的 `AppSettings`
+    ///
+    /// 日志配置会立即生效；代理监听配置在下次启动代理时生效。
+    pub async fn import_from_file(db: &Database, path: &Path) -> Result<AppSettings, AppError> {
+        let content = fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let import = Self::parse(&content)?;
+
+        if let Some(log_config) = import.log_config.as_ref() {
+            crate::log_filter::validate(log_config).map_err(|e| {
+                AppError::localized(
+                    "settings.import.invalid_log_config",
+                    format!("日志配置无效: {e}"),
+                    format!("Invalid log config: {e}"),
+                )
+            })?;
+        }
+
+        let settings = Self::merge_into_current(import.settings, crate::settings::get_settings());
+        crate::settings::update_settings(settings)?;
+
+        if let Some(log_config) = import.log_config {
+            db.set_log_config(&log_config)?;
+            crate::log_filter::apply(&log_config);
+        }
+
+        if let Some(defaults) = import.proxy_defaults {
+            let mut proxy = db.get_global_proxy_config().await?;
+            proxy.listen_address = defaults.listen_address;
+            proxy.listen_port = defaults.listen_port;
+            proxy.enable_logging = defaults.enable_logging;
+            db.update_global_proxy_config(proxy).await?;
+        }
+
+        log::info!(
+            "已从 {} 导入偏好设置（版本 {}）",
+            path.display(),
+            import.version
+        );
+        Ok(crate::settings::get_settings())
+    }
+
+    /// 解析导出文件：字段类型错误时报错，未知字段忽略
+    fn parse(content: &str) -> Result<SettingsExport, AppError> {
+        let invalid = |detail: String| {
+            AppError::localized(
+                "settings.import.invalid_file",
+                format!("偏好设置文件格式错误: {detail}"),
+                format!("Invalid settings file: {detail}"),
+            )
+        };
+        let value: Value = serde_json::from_str(content).map_err(|e| invalid(e.to_string()))?;
+        if !value.is_object() {
+            return Err(invalid("root must be an object".to_string()));
+        }
+        let import: SettingsExport =
+            serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+        if import.version > SETTINGS_EXPORT_VERSION {
+            log::warn!(
+                "偏好设置文件版本 {} 高于当前支持的版本 {}，未知字段将被忽略",
+                import.version,
+                SETTINGS_EXPORT_VERSION
+            );
+        }
+        Ok(import)
+    }
+
+    /// 去掉与本机绑定的字段（当前供应商 ID、配置目录覆盖、开机自启状态）
+    fn strip_device_local(mut settings: AppSettings) -> AppSettings {
+        settings.current_provider_claude = None;
+        settings.current_provider_codex = None;
+        settings.current_provider_gemini = None;
+        settings.current_provider_opencode = None;
+        settings.claude_config_dir = None;
+        settings.codex_config_dir = None;
+        settings.gemini_config_dir = None;
+        settings.opencode_config_dir = None;
+        settings.launch_on_startup = false;
+        settings
+    }
+
+    /// 导入的设置覆盖当前设置，但保留本机绑定的字段
+    fn merge_into_current(mut imported: AppSettings, current: AppSettings) -> AppSettings {
+        imported.current_provider_claude = current.current_provider_claude;
+        imported.current_provider_codex = current.current_provider_codex;
+        imported.current_provider_gemini = current.current_provider_gemini;
+        imported.current_provider_opencode = current.current_provider_opencode;
+        imported.claude_config_dir = current.claude_config_dir;
+        imported.codex_config_dir = current.codex_config_dir;
+        imported.gemini_config_dir = current.gemini_config_dir;
+        imported.opencode_config_dir = current.opencode_config_dir;
+        imported.launch_on_startup = current.launch_on_startup;
+        imported
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ignores_unknown_keys_and_rejects_wrong_types() {
+        let content = r#"{
+            "version": 2,
+            "futureSection": {"a": 1},
+            "settings": {"silentStartup": true, "preferredTerminal": "iterm2", "newFlag": 1},
+            "logConfig": {"enabled": true, "level": "debug"}
+        }"#;
+        let import = SettingsTransferService::parse(content).expect("should parse");
+        assert!(import.settings.silent_startup);
+        assert_eq!(
+            import.settings.preferred_terminal.as_deref(),
+            Some("iterm2")
+        );
+        assert_eq!(import.log_config.unwrap().level, "debug");
+        assert!(import.proxy_defaults.is_none());
+
+        let wrong_type = r#"{"version": 1, "settings": {"silentStartup": "yes"}}"#;
+        assert!(SettingsTransferService::parse(wrong_type).is_err());
+        assert!(SettingsTransferService::parse("[]").is_err());
+    }
+
+    #[test]
+    fn merge_keeps_device_local_fields() {
+        let current = AppSettings {
+            current_provider_claude: Some("local".to_string()),
+            claude_config_dir: Some("/local/.claude".to_string()),
+            launch_on_startup: true,
+            ..AppSettings::default()
+        };
+        let imported = AppSettings {
+            current_provider_claude: Some("remote".to_string()),
+            claude_config_dir: Some("/remote/.claude".to_string()),
+            minimize_to_tray_on_close: false,
+            ..AppSettings::default()
+        };
+
+        let merged = SettingsTransferService::merge_into_current(imported, current);
+        assert_eq!(merged.current_provider_claude.as_deref(), Some("local"));
+        assert_eq!(merged.claude_config_dir.as_deref(), Some("/local/.claude"));
+        assert!(merged.launch_on_startup);
+        assert!(!merged.minimize_to_tray_on_close);
+
+        let exported = SettingsTransferService::strip_device_local(merged);
+        assert!(exported.current_provider_claude.is_none());
+        assert!(exported.claude_config_dir.is_none());
+        assert!(!exported.launch_on_startup);
+    }
+}
@@ -8,7 +8,7 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -18,6 +18,7 @@ use crate::app_config::{AppType, InstalledSkill, SkillApps, UnmanagedSkill};
 use crate::config::get_app_config_dir;
 use crate::database::Database;
 use crate::error::format_skill_error;
+use crate::preflight;
 
 // ========== 数据结构 ==========
 
@@ -86,10 +87,73 @@ pub struct Skill {
     pub repo_branch: Option<String>,
 }
 
-/// 仓库配置
+/// 批量安装中单个技能的结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillBulkInstallStatus {
+    /// 新安装
+    Installed,
+    /// 同一仓库的技能已安装（仅更新当前应用启用状态）
+    AlreadyInstalled,
+    /// 同名目录已被其他来源的技能占用，未安装
+    Conflict,
+    /// 安装失败
+    Failed,
+}
+
+/// 批量安装中单个技能的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillBulkInstallResult {
+    pub key: String,
+    pub directory: String,
+    pub status: SkillBulkInstallStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill: Option<InstalledSkill>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Skill 启用矩阵中的一行（每个应用一个开关）
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillEnablementRow {
+    pub skill_id: String,
+    pub name: String,
+    pub directory: String,
+    /// 应用 -> 是否启用（包含所有应用）
+    pub apps: BTreeMap<String, bool>,
+}
+
+impl SkillEnablementRow {
+    fn from_skill(skill: &InstalledSkill) -> Self {
+        Self {
+            skill_id: skill.id.clone(),
+            name: skill.name.clone(),
+            directory: skill.directory.clone(),
+            apps: AppType::all()
+                .map(|app| (app.as_str().to_string(), skill.apps.is_enabled_for(&app)))
+                .collect(),
+        }
+    }
+}
+
+/// 仓库托管类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillRepoHost {
+    /// GitHub（下载分支 ZIP 归档）
+    #[default]
+    Github,
+    /// GitLab（通过 v4 API 拉取目录树与原始文件）
+    Gitlab,
+    /// 任意 git 地址（`git clone --depth 1`，需要本机安装 git）
+    RawGit,
+}
+
+/// 仓库配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SkillRepo {
-    /// GitHub 用户/组织名
+    /// 用户/组织名（GitLab 可为多级 group 路径）
     pub owner: String,
     /// 仓库名称
     pub name: String,
@@ -97,6 +161,155 @@ pub struct SkillRepo {
     pub branch: String,
     /// 是否启用
     pub enabled: bool,
+    /// 托管类型（旧数据默认为 GitHub）
+    #[serde(default, rename = "hostKind")]
+    pub host_kind: SkillRepoHost,
+    /// 地址覆盖：
+    /// - GitHub：站点根地址（默认 `https://github.com`）
+    /// - GitLab：API 地址（默认 `https://gitlab.com/api/v4`）
+    /// - raw git：clone 地址（必填）
+    #[serde(default, rename = "baseUrl", skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// 访问令牌（保存在 settings 表，不写入 skill_repos，也不返回给前端）
+    #[serde(skip)]
+    pub token: Option<String>,
+}
+
+impl SkillRepoHost {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+            Self::RawGit => "raw_git",
+        }
+    }
+
+    /// 解析数据库中的托管类型（未知值按 GitHub 处理）
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "gitlab" => Self::Gitlab,
+            "raw_git" => Self::RawGit,
+            _ => Self::Github,
+        }
+    }
+}
+
+const DEFAULT_GITHUB_BASE_URL: &str = "https://github.com";
+const DEFAULT_GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+
+impl SkillRepo {
+    fn base_url_or(&self, default: &str) -> String {
+        self.base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// GitLab 项目路径（`owner/name`，URL 编码后用作 project id）
+    fn gitlab_project_id(&self) -> String {
+        encode_path_segment(&format!("{}/{}", self.owner, self.name))
+    }
+
+    /// 浏览器中查看技能目录的地址（raw git 无法推断，返回 None）
+    fn web_url(&self, directory: &str) -> Option<String> {
+        match self.host_kind {
+            SkillRepoHost::Github => Some(format!(
+                "{}/{}/{}/tree/{}/{}",
+                self.base_url_or(DEFAULT_GITHUB_BASE_URL),
+                self.owner,
+                self.name,
+                self.branch,
+                directory
+            )),
+            SkillRepoHost::Gitlab => {
+                let api = self.base_url_or(DEFAULT_GITLAB_API_URL);
+                let web = api.strip_suffix("/api/v4").unwrap_or(&api);
+                Some(format!(
+                    "{}/{}/{}/-/tree/{}/{}",
+                    web, self.owner, self.name, self.branch, directory
+                ))
+            }
+            SkillRepoHost::RawGit => None,
+        }
+    }
+
+    /// 依次尝试的分支：配置的分支优先，再回退到 main / master
+    fn candidate_branches(&self) -> Vec<&str> {
+        let mut branches = Vec::new();
+        if !self.branch.is_empty() {
+            branches.push(self.branch.as_str());
+        }
+        for fallback in ["main", "master"] {
+            if !branches.contains(&fallback) {
+                branches.push(fallback);
+            }
+        }
+        branches
+    }
+}
+
+/// 简单 glob 匹配（支持 `*` 与 `?`，不区分大小写）
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // 让上一个 `*` 多吞一个字符后重试
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 对单个 URL 路径段做百分号编码（`/` 也会被编码）
+fn encode_path_segment(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// GitLab 目录树条目
+#[derive(Debug, Deserialize)]
+struct GitlabTreeEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// GitLab 按文件拉取时的范围（GitHub / raw git 始终获取整个仓库）
+#[derive(Debug, Clone, Copy)]
+enum DownloadScope<'a> {
+    /// 仅拉取各目录下的 SKILL.md（用于发现）
+    SkillManifests,
+    /// 拉取指定技能目录下的全部文件（用于安装）
+    Directory(&'a str),
+}
+
+impl DownloadScope<'_> {
+    fn includes(&self, path: &str) -> bool {
+        match self {
+            Self::SkillManifests => path == "SKILL.md" || path.ends_with("/SKILL.md"),
+            Self::Directory(dir) => path
+                .strip_prefix(dir.trim_matches('/'))
+                .is_some_and(|rest| rest.starts_with('/')),
+        }
+    }
 }
 
 /// 技能安装状态（旧版兼容）
@@ -128,24 +341,28 @@ impl Default for SkillStore {
                     name: "skills".to_string(),
                     branch: "main".to_string(),
                     enabled: true,
+                    ..Default::default()
                 },
                 SkillRepo {
                     owner: "ComposioHQ".to_string(),
                     name: "awesome-claude-skills".to_string(),
                     branch: "master".to_string(),
                     enabled: true,
+                    ..Default::default()
                 },
                 SkillRepo {
                     owner: "cexll".to_string(),
                     name: "myclaude".to_string(),
                     branch: "master".to_string(),
                     enabled: true,
+                    ..Default::default()
                 },
                 SkillRepo {
                     owner: "JimLiu".to_string(),
                     name: "baoyu-skills".to_string(),
                     branch: "main".to_string(),
                     enabled: true,
+                    ..Default::default()
                 },
             ],
         }
@@ -300,17 +517,12 @@ impl SkillService {
 
         // 如果已存在则跳过下载
         if !dest.exists() {
-            let repo = SkillRepo {
-                owner: skill.repo_owner.clone(),
-                name: skill.repo_name.clone(),
-                branch: skill.repo_branch.clone(),
-                enabled: true,
-            };
+            let repo = Self::resolve_repo(db, skill)?;
 
             // 下载仓库
             let temp_dir = timeout(
                 std::time::Duration::from_secs(60),
-                self.download_repo(&repo),
+                self.download_repo(&repo, DownloadScope::Directory(&skill.directory)),
             )
             .await
             .map_err(|_| {
@@ -336,6 +548,11 @@ impl SkillService {
                 )));
             }
 
+            if let Err(e) = Self::preflight_install(current_app, preflight::dir_size(&source)) {
+                let _ = fs::remove_dir_all(&temp_dir);
+                return Err(e);
+            }
+
             Self::copy_dir_recursive(&source, &dest)?;
             let _ = fs::remove_dir_all(&temp_dir);
         }
@@ -373,6 +590,113 @@ impl SkillService {
         Ok(installed_skill)
     }
 
+    /// 复制前检查 SSOT 目录与应用 Skills 目录可写、剩余空间足够
+    ///
+    /// 目录不可写或磁盘已满时直接报错，避免复制到一半留下不完整的 Skill。
+    fn preflight_install(current_app: &AppType, bytes: u64) -> Result<()> {
+        let required = bytes.saturating_add(preflight::MIN_FREE_BYTES);
+        preflight::check_path(&Self::get_ssot_dir()?, required)?;
+        preflight::check_path(&Self::get_app_skills_dir(current_app)?, required)?;
+        Ok(())
+    }
+
+    /// 从仓库批量安装技能
+    ///
+    /// 通过与发现列表相同的扫描逻辑枚举仓库中的技能，按 `filter`（glob，匹配目录名最后一段，
+    /// 不区分大小写）筛选后逐个安装。同名目录已被其他来源占用时记为冲突，不会覆盖。
+    pub async fn install_all_from_repo(
+        &self,
+        db: &Arc<Database>,
+        owner: &str,
+        name: &str,
+        branch: Option<&str>,
+        filter: Option<&str>,
+        current_app: &AppType,
+    ) -> Result<Vec<SkillBulkInstallResult>> {
+        let configured = db
+            .get_skill_repos()?
+            .into_iter()
+            .find(|r| r.owner.eq_ignore_ascii_case(owner) && r.name.eq_ignore_ascii_case(name));
+        let mut repo = configured.unwrap_or_else(|| SkillRepo {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            branch: "main".to_string(),
+            ..Default::default()
+        });
+        if let Some(branch) = branch.map(str::trim).filter(|b| !b.is_empty()) {
+            repo.branch = branch.to_string();
+        }
+        repo.enabled = true;
+
+        let mut skills = self.fetch_repo_skills(&repo).await?;
+        Self::deduplicate_discoverable_skills(&mut skills);
+        let filter = filter.map(str::trim).filter(|f| !f.is_empty());
+
+        let mut results = Vec::new();
+        for skill in skills {
+            let install_name = Path::new(&skill.directory)
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| skill.directory.clone());
+            if filter.is_some_and(|pattern| !glob_match(pattern, &install_name)) {
+                continue;
+            }
+
+            let existing = db
+                .get_all_installed_skills()?
+                .into_values()
+                .find(|s| s.directory.eq_ignore_ascii_case(&install_name));
+            let same_repo = existing.as_ref().map(|s| {
+                s.repo_owner.as_deref() == Some(skill.repo_owner.as_str())
+                    && s.repo_name.as_deref() == Some(skill.repo_name.as_str())
+            });
+            if same_repo == Some(false) {
+                let existing_id = existing.map(|s| s.id).unwrap_or_default();
+                results.push(SkillBulkInstallResult {
+                    key: skill.key,
+                    directory: install_name,
+                    status: SkillBulkInstallStatus::Conflict,
+                    skill: None,
+                    error: Some(format!("目录已被 {existing_id} 占用")),
+                });
+                continue;
+            }
+
+            let result = match self.install(db, &skill, current_app).await {
+                Ok(installed) => SkillBulkInstallResult {
+                    key: skill.key,
+                    directory: install_name,
+                    status: if same_repo == Some(true) {
+                        SkillBulkInstallStatus::AlreadyInstalled
+                    } else {
+                        SkillBulkInstallStatus::Installed
+                    },
+                    skill: Some(installed),
+                    error: None,
+                },
+                Err(e) => {
+                    log::warn!("批量安装技能 {} 失败: {e}", skill.key);
+                    SkillBulkInstallResult {
+                        key: skill.key,
+                        directory: install_name,
+                        status: SkillBulkInstallStatus::Failed,
+                        skill: None,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        log::info!(
+            "从仓库 {}/{} 批量安装技能完成: {} 个匹配",
+            repo.owner,
+            repo.name,
+            results.len()
+        );
+        Ok(results)
+    }
+
     /// 卸载 Skill
     ///
     /// 流程：
@@ -438,6 +762,33 @@ impl SkillService {
         Ok(())
     }
 
+    /// 获取所有已安装 Skill 的应用启用矩阵（按名称排序）
+    pub fn get_enablement_matrix(db: &Arc<Database>) -> Result<Vec<SkillEnablementRow>> {
+        let mut rows: Vec<_> = db
+            .get_all_installed_skills()?
+            .values()
+            .map(SkillEnablementRow::from_skill)
+            .collect();
+        rows.sort_by_cached_key(|row| row.name.to_lowercase());
+        Ok(rows)
+    }
+
+    /// 切换矩阵中的单个开关，与 [`Self::toggle_app`] 一致地同步应用目录
+    ///
+    /// 返回更新后的矩阵行。
+    pub fn set_enablement(
+        db: &Arc<Database>,
+        id: &str,
+        app: &AppType,
+        enabled: bool,
+    ) -> Result<SkillEnablementRow> {
+        Self::toggle_app(db, id, app, enabled)?;
+        let skill = db
+            .get_installed_skill(id)?
+            .ok_or_else(|| anyhow!("Skill not found: {id}"))?;
+        Ok(SkillEnablementRow::from_skill(&skill))
+    }
+
     /// 扫描未管理的 Skills
     ///
     /// 扫描各应用目录，找出未被 CC Switch 管理的 Skills
@@ -658,6 +1009,8 @@ impl SkillService {
     /// - Symlink: 仅使用 symlink
     /// - Copy: 仅使用文件复制
     pub fn sync_to_app_dir(directory: &str, app: &AppType) -> Result<()> {
+        crate::settings::ensure_live_writable()?;
+
         let ssot_dir = Self::get_ssot_dir()?;
         let source = ssot_dir.join(directory);
 
@@ -736,6 +1089,8 @@ impl SkillService {
 
     /// 从应用目录删除 Skill（支持 symlink 和真实目录）
     pub fn remove_from_app(directory: &str, app: &AppType) -> Result<()> {
+        crate::settings::ensure_live_writable()?;
+
         let app_dir = Self::get_app_skills_dir(app)?;
         let skill_path = app_dir.join(directory);
 
@@ -862,19 +1217,22 @@ impl SkillService {
 
     /// 从仓库获取技能列表
     async fn fetch_repo_skills(&self, repo: &SkillRepo) -> Result<Vec<DiscoverableSkill>> {
-        let temp_dir = timeout(std::time::Duration::from_secs(60), self.download_repo(repo))
-            .await
-            .map_err(|_| {
-                anyhow!(format_skill_error(
-                    "DOWNLOAD_TIMEOUT",
-                    &[
-                        ("owner", &repo.owner),
-                        ("name", &repo.name),
-                        ("timeout", "60")
-                    ],
-                    Some("checkNetwork"),
-                ))
-            })??;
+        let temp_dir = timeout(
+            std::time::Duration::from_secs(60),
+            self.download_repo(repo, DownloadScope::SkillManifests),
+        )
+        .await
+        .map_err(|_| {
+            anyhow!(format_skill_error(
+                "DOWNLOAD_TIMEOUT",
+                &[
+                    ("owner", &repo.owner),
+                    ("name", &repo.name),
+                    ("timeout", "60")
+                ],
+                Some("checkNetwork"),
+            ))
+        })??;
 
         let mut skills = Vec::new();
         let scan_dir = temp_dir.clone();
@@ -940,10 +1298,7 @@ impl SkillService {
             name: meta.name.unwrap_or_else(|| directory.to_string()),
             description: meta.description.unwrap_or_default(),
             directory: directory.to_string(),
-            readme_url: Some(format!(
-                "https://github.com/{}/{}/tree/{}/{}",
-                repo.owner, repo.name, repo.branch, directory
-            )),
+            readme_url: repo.web_url(directory),
             repo_owner: repo.owner.clone(),
             repo_name: repo.name.clone(),
             repo_branch: repo.branch.clone(),
@@ -993,26 +1348,52 @@ impl SkillService {
         });
     }
 
-    /// 下载仓库
-    async fn download_repo(&self, repo: &SkillRepo) -> Result<PathBuf> {
+    /// 查找技能来源仓库的配置（托管类型、地址、令牌），仓库已被删除时按 GitHub 处理
+    fn resolve_repo(db: &Arc<Database>, skill: &DiscoverableSkill) -> Result<SkillRepo> {
+        let configured = db.get_skill_repos()?.into_iter().find(|r| {
+            r.owner.eq_ignore_ascii_case(&skill.repo_owner)
+                && r.name.eq_ignore_ascii_case(&skill.repo_name)
+        });
+        Ok(SkillRepo {
+            owner: skill.repo_owner.clone(),
+            name: skill.repo_name.clone(),
+            branch: skill.repo_branch.clone(),
+            enabled: true,
+            ..configured.unwrap_or_default()
+        })
+    }
+
+    /// 下载仓库到临时目录
+    async fn download_repo(&self, repo: &SkillRepo, scope: DownloadScope<'_>) -> Result<PathBuf> {
         let temp_dir = tempfile::tempdir()?;
         let temp_path = temp_dir.path().to_path_buf();
         let _ = temp_dir.keep();
 
-        let branches = if repo.branch.is_empty() {
-            vec!["main", "master"]
-        } else {
-            vec![repo.branch.as_str(), "main", "master"]
-        };
-
         let mut last_error = None;
-        for branch in branches {
-            let url = format!(
-                "https://github.com/{}/{}/archive/refs/heads/{}.zip",
-                repo.owner, repo.name, branch
-            );
+        for branch in repo.candidate_branches() {
+            let result = match repo.host_kind {
+                SkillRepoHost::Github => {
+                    let url = format!(
+                        "{}/{}/{}/archive/refs/heads/{}.zip",
+                        repo.base_url_or(DEFAULT_GITHUB_BASE_URL),
+                        repo.owner,
+                        repo.name,
+                        branch
+                    );
+                    let mut request = crate::proxy::http_client::get().get(url);
+                    if let Some(token) = &repo.token {
+                        request = request.bearer_auth(token);
+                    }
+                    self.download_and_extract(request, &temp_path).await
+                }
+                SkillRepoHost::Gitlab => {
+                    self.download_gitlab_files(repo, branch, scope, &temp_path)
+                        .await
+                }
+                SkillRepoHost::RawGit => Self::clone_raw_git(repo, branch, &temp_path).await,
+            };
 
-            match self.download_and_extract(&url, &temp_path).await {
+            match result {
                 Ok(_) => {
                     return Ok(temp_path);
                 }
@@ -1023,13 +1404,13 @@ impl SkillService {
             }
         }
 
+        let _ = fs::remove_dir_all(&temp_path);
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支下载失败")))
     }
 
-    /// 下载并解压 ZIP
-    async fn download_and_extract(&self, url: &str, dest: &Path) -> Result<()> {
-        let client = crate::proxy::http_client::get();
-        let response = client.get(url).send().await?;
+    /// 发送请求，非 2xx 状态转换为 DOWNLOAD_FAILED 错误
+    async fn send_checked(request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await?;
         if !response.status().is_success() {
             let status = response.status().as_u16().to_string();
             return Err(anyhow::anyhow!(format_skill_error(
@@ -1043,7 +1424,169 @@ impl SkillService {
                 },
             )));
         }
+        Ok(response)
+    }
 
+    /// 通过 GitLab v4 API 拉取目录树，并按范围下载原始文件到 `dest`
+    async fn download_gitlab_files(
+        &self,
+        repo: &SkillRepo,
+        branch: &str,
+        scope: DownloadScope<'_>,
+        dest: &Path,
+    ) -> Result<()> {
+        let client = crate::proxy::http_client::get();
+        let api = repo.base_url_or(DEFAULT_GITLAB_API_URL);
+        let project = repo.gitlab_project_id();
+        let with_token = |request: reqwest::RequestBuilder| match &repo.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        };
+
+        // 分页获取完整目录树
+        let mut paths = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let url = format!(
+                "{api}/projects/{project}/repository/tree?ref={}&recursive=true&per_page=100&page={page}",
+                encode_path_segment(branch)
+            );
+            let response = Self::send_checked(with_token(client.get(url))).await?;
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u32>().ok());
+            let entries: Vec<GitlabTreeEntry> = response.json().await?;
+            let page_len = entries.len();
+            paths.extend(
+                entries
+                    .into_iter()
+                    .filter(|e| e.kind == "blob" && scope.includes(&e.path))
+                    .map(|e| e.path),
+            );
+            match next_page {
+                Some(next) if next > page => page = next,
+                None if page_len == 100 => page += 1,
+                _ => break,
+            }
+        }
+
+        if paths.is_empty() {
+            if let DownloadScope::Directory(dir) = scope {
+                return Err(anyhow!(format_skill_error(
+                    "SKILL_DIR_NOT_FOUND",
+                    &[("path", dir)],
+                    Some("checkRepoUrl"),
+                )));
+            }
+        }
+
+        for path in paths {
+            // 防御：拒绝包含 `..` 或绝对路径的条目，避免写出临时目录
+            let relative = Path::new(&path);
+            if relative
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            {
+                log::warn!("跳过不安全的 GitLab 路径: {path}");
+                continue;
+            }
+
+            let url = format!(
+                "{api}/projects/{project}/repository/files/{}/raw?ref={}",
+                encode_path_segment(&path),
+                encode_path_segment(branch)
+            );
+            let bytes = Self::send_checked(with_token(client.get(url)))
+                .await?
+                .bytes()
+                .await?;
+            let outpath = dest.join(relative);
+            if let Some(parent) = outpath.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&outpath, &bytes)?;
+        }
+
+        Ok(())
+    }
+
+    /// 使用本机 git 浅克隆仓库到 `dest`（raw git 仓库）
+    async fn clone_raw_git(repo: &SkillRepo, branch: &str, dest: &Path) -> Result<()> {
+        let url = repo
+            .base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| {
+                anyhow!(format_skill_error(
+                    "MISSING_REPO_INFO",
+                    &[("owner", &repo.owner), ("name", &repo.name)],
+                    Some("checkRepoUrl"),
+                ))
+            })?
+            .to_string();
+        let branch = branch.to_string();
+        let token = repo.token.clone();
+        let dest = dest.to_path_buf();
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let git_available = std::process::Command::new("git")
+                .arg("--version")
+                .output()
+                .is_ok_and(|o| o.status.success());
+            if !git_available {
+                return Err(anyhow!(format_skill_error(
+                    "GIT_NOT_AVAILABLE",
+                    &[],
+                    Some("installGit"),
+                )));
+            }
+
+            // 上一个分支失败时可能留下部分文件
+            if dest.exists() {
+                let _ = fs::remove_dir_all(&dest);
+            }
+
+            let mut command = std::process::Command::new("git");
+            // 禁止交互式凭据提示，避免阻塞
+            command.env("GIT_TERMINAL_PROMPT", "0");
+            // 令牌通过环境变量注入配置（git 2.31+），不出现在命令行参数中，
+            // 避免被同机其他用户从进程列表读取
+            if let Some(token) = &token {
+                command
+                    .env("GIT_CONFIG_COUNT", "1")
+                    .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+                    .env(
+                        "GIT_CONFIG_VALUE_0",
+                        format!("Authorization: Bearer {token}"),
+                    );
+            }
+            let output = command
+                .args(["clone", "--depth", "1", "--branch", &branch, "--", &url])
+                .arg(&dest)
+                .output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow!(format_skill_error(
+                    "GIT_CLONE_FAILED",
+                    &[("branch", &branch), ("message", stderr.trim())],
+                    Some("checkRepoUrl"),
+                )));
+            }
+            Ok(())
+        })
+        .await?
+    }
+
+    /// 下载并解压 ZIP
+    async fn download_and_extract(
+        &self,
+        request: reqwest::RequestBuilder,
+        dest: &Path,
+    ) -> Result<()> {
+        let response = Self::send_checked(request).await?;
         let bytes = response.bytes().await?;
         let cursor = std::io::Cursor::new(bytes);
         let mut archive = zip::ZipArchive::new(cursor)?;
@@ -1140,6 +1683,11 @@ impl SkillService {
         }
 
         let ssot_dir = Self::get_ssot_dir()?;
+        let total_size = skill_dirs.iter().map(|dir| preflight::dir_size(dir)).sum();
+        if let Err(e) = Self::preflight_install(current_app, total_size) {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
         let mut installed = Vec::new();
         let existing_skills = db.get_all_installed_skills()?;
 
@@ -1422,3 +1970,192 @@ pub fn migrate_skills_to_ssot(db: &Arc<Database>) -> Result<usize> {
 
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{HeaderMap, StatusCode, Uri},
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    };
+    use serde_json::json;
+    use std::io::Write;
+
+    const SKILL_MD: &str = "---\nname: Lint\ndescription: Lint the repo\n---\n";
+
+    #[test]
+    fn glob_match_filters_directory_names() {
+        assert!(glob_match("*", "lint"));
+        assert!(glob_match("lint*", "Lint-Rust"));
+        assert!(glob_match("*-rust", "lint-rust"));
+        assert!(glob_match("l?nt", "lint"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(!glob_match("lint*", "format"));
+        assert!(!glob_match("l?nt", "linnt"));
+        assert!(!glob_match("*.md", "readme"));
+    }
+
+    async fn serve(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        format!("http://{addr}")
+    }
+
+    fn github_archive() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer
+            .start_file("skills-main/lint/SKILL.md", options)
+            .expect("start file");
+        writer.write_all(SKILL_MD.as_bytes()).expect("write file");
+        writer.finish().expect("finish zip").into_inner()
+    }
+
+    /// 模拟 GitLab v4 API：目录树 + 原始文件，要求 PRIVATE-TOKEN
+    async fn gitlab_api(uri: Uri, headers: HeaderMap) -> Response {
+        if headers.get("PRIVATE-TOKEN").and_then(|v| v.to_str().ok()) != Some("glpat-test") {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        let query = uri.query().unwrap_or("");
+        if !query.contains("ref=main") {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        match uri.path() {
+            "/api/v4/projects/team%2Fskills/repository/tree" => axum::Json(json!([
+                { "path": "README.md", "type": "blob" },
+                { "path": "lint", "type": "tree" },
+                { "path": "lint/SKILL.md", "type": "blob" },
+                { "path": "lint/run.sh", "type": "blob" }
+            ]))
+            .into_response(),
+            "/api/v4/projects/team%2Fskills/repository/files/lint%2FSKILL.md/raw" => {
+                Body::from(SKILL_MD).into_response()
+            }
+            "/api/v4/projects/team%2Fskills/repository/files/lint%2Frun.sh/raw" => {
+                Body::from("echo lint").into_response()
+            }
+            _ => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    #[tokio::test]
+    async fn discovers_skills_from_github_archive() {
+        let archive = github_archive();
+        let base = serve(Router::new().route(
+            "/acme/skills/archive/refs/heads/main.zip",
+            get(move || async move { archive }),
+        ))
+        .await;
+
+        let repo = SkillRepo {
+            owner: "acme".to_string(),
+            name: "skills".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            base_url: Some(base.clone()),
+            ..Default::default()
+        };
+        let skills = SkillService::new()
+            .discover_available(vec![repo])
+            .await
+            .expect("discover");
+
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].key, "acme/skills:lint");
+        assert_eq!(skills[0].name, "Lint");
+        assert_eq!(
+            skills[0].readme_url.as_deref(),
+            Some(format!("{base}/acme/skills/tree/main/lint").as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn gitlab_repo_fetches_tree_and_raw_files() {
+        let base = serve(Router::new().fallback(gitlab_api)).await;
+        let repo = SkillRepo {
+            owner: "team".to_string(),
+            name: "skills".to_string(),
+            branch: "main".to_string(),
+            enabled: true,
+            host_kind: SkillRepoHost::Gitlab,
+            base_url: Some(format!("{base}/api/v4")),
+            token: Some("glpat-test".to_string()),
+        };
+        let service = SkillService::new();
+
+        let skills = service
+            .discover_available(vec![repo.clone()])
+            .await
+            .expect("discover");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].key, "team/skills:lint");
+        assert_eq!(skills[0].description, "Lint the repo");
+        assert_eq!(
+            skills[0].readme_url.as_deref(),
+            Some(format!("{base}/team/skills/-/tree/main/lint").as_str())
+        );
+
+        // 安装只拉取技能目录下的文件
+        let dir = service
+            .download_repo(&repo, DownloadScope::Directory("lint"))
+            .await
+            .expect("download skill directory");
+        assert_eq!(
+            fs::read_to_string(dir.join("lint/run.sh")).expect("run.sh"),
+            "echo lint"
+        );
+        assert!(dir.join("lint/SKILL.md").exists());
+        assert!(!dir.join("README.md").exists());
+        let _ = fs::remove_dir_all(&dir);
+
+        // 缺少令牌时请求被拒绝
+        let anonymous = SkillRepo {
+            token: None,
+            ..repo
+        };
+        assert!(service
+            .download_repo(&anonymous, DownloadScope::SkillManifests)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn enablement_matrix_lists_every_app_per_skill() {
+        let db = Arc::new(Database::memory().expect("memory db"));
+        for (id, name, claude) in [("local:b", "beta", false), ("local:a", "Alpha", true)] {
+            db.save_skill(&InstalledSkill {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: None,
+                directory: id.trim_start_matches("local:").to_string(),
+                repo_owner: None,
+                repo_name: None,
+                repo_branch: None,
+                readme_url: None,
+                apps: SkillApps {
+                    claude,
+                    ..Default::default()
+                },
+                installed_at: 0,
+            })
+            .expect("save skill");
+        }
+
+        let rows = SkillService::get_enablement_matrix(&db).expect("matrix");
+        let names: Vec<_> = rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["Alpha", "beta"]);
+        assert_eq!(rows[0].skill_id, "local:a");
+        assert_eq!(rows[0].apps.len(), 4);
+        assert!(rows[0].apps["claude"]);
+        assert!(!rows[0].apps["opencode"]);
+        assert!(!rows[1].apps["claude"]);
+    }
+}
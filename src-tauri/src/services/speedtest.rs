@@ -3,6 +3,7 @@ use reqwest::{Client, Url};
 use serde::Serialize;
 use std::time::Instant;
 
+use crate::database::Database;
 use crate::error::AppError;
 
 const DEFAULT_TIMEOUT_SECS: u64 = 8;
@@ -116,6 +117,34 @@ impl SpeedtestService {
         Ok(results.into_iter().flatten().collect::<Vec<_>>())
     }
 
+    /// 将测速结果写入供应商的端点测速历史
+    ///
+    /// 在测速完成后统一写入（单个事务），写入失败只记录日志，不影响测速结果。
+    pub fn record_history(
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+        results: &[EndpointLatency],
+    ) {
+        let rows: Vec<_> = results
+            .iter()
+            .filter(|result| Url::parse(&result.url).is_ok())
+            .map(|result| {
+                (
+                    result.url.trim_end_matches('/').to_string(),
+                    result
+                        .latency
+                        .map(|ms| u64::try_from(ms).unwrap_or(u64::MAX)),
+                    result.status,
+                )
+            })
+            .collect();
+
+        if let Err(e) = db.record_endpoint_latencies(app_type, provider_id, &rows) {
+            log::warn!("[{app_type}] 保存供应商 {provider_id} 端点测速历史失败: {e}");
+        }
+    }
+
     fn build_client(timeout_secs: u64) -> Result<(Client, std::time::Duration), AppError> {
         // 使用全局 HTTP 客户端（已包含代理配置）
         // 返回 timeout Duration 供请求级别使用
@@ -2,14 +2,16 @@
 //!
 //! 使用流式 API 进行快速健康检查，只需接收首个 chunk 即判定成功。
 
-use futures::StreamExt;
+use futures::stream::{self, StreamExt};
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use crate::app_config::AppType;
+use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
 use crate::proxy::providers::{get_adapter, AuthInfo, AuthStrategy};
@@ -39,6 +41,43 @@ pub struct StreamCheckConfig {
     /// 检查提示词
     #[serde(default = "default_test_prompt")]
     pub test_prompt: String,
+    /// 各应用的定时检查间隔（分钟），0 或未设置表示不定时检查
+    #[serde(default)]
+    pub stream_check_interval_minutes: HashMap<String, u32>,
+    /// 代理未运行时也执行定时检查（默认仅在代理运行时检查）
+    #[serde(default)]
+    pub schedule_when_proxy_stopped: bool,
+    /// 按供应商固定的检查模型（应用 → 供应商 ID → 模型），优先于其他模型来源
+    #[serde(default)]
+    pub provider_models: HashMap<String, HashMap<String, String>>,
+}
+
+impl StreamCheckConfig {
+    /// 指定应用的定时检查间隔（未启用时返回 None）
+    pub fn schedule_interval(&self, app_type: &AppType) -> Option<Duration> {
+        self.stream_check_interval_minutes
+            .get(app_type.as_str())
+            .copied()
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    /// 指定供应商固定的检查模型（未设置或为空时返回 None）
+    pub fn pinned_model(&self, app_type: &AppType, provider_id: &str) -> Option<&str> {
+        self.provider_models
+            .get(app_type.as_str())
+            .and_then(|models| models.get(provider_id))
+            .map(|model| model.trim())
+            .filter(|model| !model.is_empty())
+    }
+
+    /// 为指定供应商固定检查模型
+    pub fn pin_model(&mut self, app_type: &AppType, provider_id: &str, model: String) {
+        self.provider_models
+            .entry(app_type.as_str().to_string())
+            .or_default()
+            .insert(provider_id.to_string(), model);
+    }
 }
 
 fn default_test_prompt() -> String {
@@ -55,6 +94,9 @@ impl Default for StreamCheckConfig {
             codex_model: "gpt-5.1-codex@low".to_string(),
             gemini_model: "gemini-3-pro-preview".to_string(),
             test_prompt: default_test_prompt(),
+            stream_check_interval_minutes: HashMap::new(),
+            schedule_when_proxy_stopped: false,
+            provider_models: HashMap::new(),
         }
     }
 }
@@ -73,6 +115,67 @@ pub struct StreamCheckResult {
     pub retry_count: u32,
 }
 
+/// 一轮批量检查的汇总（随 `stream-check-completed` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamCheckSummary {
+    pub app_type: String,
+    pub total: usize,
+    pub operational: usize,
+    pub degraded: usize,
+    pub failed: usize,
+    pub checked_at: i64,
+}
+
+impl StreamCheckSummary {
+    pub fn from_results(app_type: &AppType, results: &[(String, StreamCheckResult)]) -> Self {
+        let count =
+            |status: HealthStatus| results.iter().filter(|(_, r)| r.status == status).count();
+        Self {
+            app_type: app_type.as_str().to_string(),
+            total: results.len(),
+            operational: count(HealthStatus::Operational),
+            degraded: count(HealthStatus::Degraded),
+            failed: count(HealthStatus::Failed),
+            checked_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 批量检查的默认并发数
+pub const DEFAULT_CHECK_CONCURRENCY: usize = 4;
+
+/// 批量检查的最大并发数
+pub const MAX_CHECK_CONCURRENCY: usize = 16;
+
+/// 批量检查中单个供应商完成时的进度（随 `stream-check-progress` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamCheckProgress {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 已完成的数量（含本次）
+    pub completed: usize,
+    pub total: usize,
+    pub result: StreamCheckResult,
+}
+
+/// 检查历史的时间桶（用于绘制趋势线）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamCheckHistoryBucket {
+    /// 桶起始时间（Unix 秒）
+    pub bucket_start: i64,
+    pub total: u32,
+    pub success_count: u32,
+    /// 成功率（0.0 - 1.0）
+    pub success_rate: f64,
+    /// 平均响应时间（仅统计有响应时间的记录）
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<u64>,
+}
+
 /// 流式健康检查服务
 pub struct StreamCheckService;
 
@@ -132,6 +235,102 @@ impl StreamCheckService {
         }))
     }
 
+    /// 切换前的轻量探测：单次请求、不重试，超时不超过 `timeout`
+    ///
+    /// 直接请求供应商自身的 base_url，不经过本地代理。
+    pub async fn probe(
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
+        timeout: std::time::Duration,
+    ) -> Result<StreamCheckResult, AppError> {
+        let mut effective_config = Self::merge_provider_config(provider, config);
+        effective_config.timeout_secs = effective_config.timeout_secs.min(timeout.as_secs());
+        effective_config.max_retries = 0;
+        Self::check_once(app_type, provider, &effective_config).await
+    }
+
+    /// 批量检查并记录日志
+    ///
+    /// `proxy_targets_only` 为 true 时只检查当前供应商与故障转移队列中的供应商；
+    /// `provider_ids` 非空时只检查其中列出的供应商（用于重新检查失败项）。
+    /// 最多同时进行 `concurrency` 个检查，每个结果完成后立即写入日志并回调 `on_progress`，
+    /// 返回值按完成顺序排列。
+    pub async fn check_providers(
+        db: &Database,
+        app_type: &AppType,
+        proxy_targets_only: bool,
+        provider_ids: Option<&[String]>,
+        concurrency: usize,
+        mut on_progress: impl FnMut(StreamCheckProgress),
+    ) -> Result<Vec<(String, StreamCheckResult)>, AppError> {
+        let config = db.get_stream_check_config()?;
+        let providers = db.get_all_providers(app_type.as_str())?;
+
+        let allowed_ids: Option<HashSet<String>> = if proxy_targets_only {
+            let mut ids = HashSet::new();
+            if let Ok(Some(current_id)) = db.get_current_provider(app_type.as_str()) {
+                ids.insert(current_id);
+            }
+            if let Ok(queue) = db.get_failover_queue(app_type.as_str()) {
+                for item in queue {
+                    ids.insert(item.provider_id);
+                }
+            }
+            Some(ids)
+        } else {
+            None
+        };
+        let requested_ids: Option<HashSet<&str>> =
+            provider_ids.map(|ids| ids.iter().map(String::as_str).collect());
+
+        let targets: Vec<(String, Provider)> = providers
+            .into_iter()
+            .filter(|(id, _)| allowed_ids.as_ref().is_none_or(|ids| ids.contains(id)))
+            .filter(|(id, _)| {
+                requested_ids
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(id.as_str()))
+            })
+            .collect();
+        let total = targets.len();
+        let config = &config;
+
+        let mut checks = stream::iter(targets)
+            .map(|(id, provider)| async move {
+                let result = Self::check_with_retry(app_type, &provider, config)
+                    .await
+                    .unwrap_or_else(|e| StreamCheckResult {
+                        status: HealthStatus::Failed,
+                        success: false,
+                        message: e.to_string(),
+                        response_time_ms: None,
+                        http_status: None,
+                        model_used: String::new(),
+                        tested_at: chrono::Utc::now().timestamp(),
+                        retry_count: 0,
+                    });
+                (id, provider.name, result)
+            })
+            .buffer_unordered(concurrency.clamp(1, MAX_CHECK_CONCURRENCY));
+
+        let mut results = Vec::with_capacity(total);
+        while let Some((id, name, result)) = checks.next().await {
+            let _ = db.save_stream_check_log(&id, &name, app_type.as_str(), &result);
+            on_progress(StreamCheckProgress {
+                app_type: app_type.as_str().to_string(),
+                provider_id: id.clone(),
+                provider_name: name,
+                completed: results.len() + 1,
+                total,
+                result: result.clone(),
+            });
+            results.push((id, result));
+        }
+
+        Ok(results)
+    }
+
     /// 合并供应商单独配置和全局配置
     ///
     /// 如果供应商配置了 meta.testConfig 且 enabled 为 true，则使用供应商配置覆盖全局配置
@@ -168,6 +367,7 @@ impl StreamCheckService {
                     .test_prompt
                     .clone()
                     .unwrap_or_else(|| global_config.test_prompt.clone()),
+                ..global_config.clone()
             },
             None => global_config.clone(),
         }
@@ -265,8 +465,8 @@ impl StreamCheckService {
                 success: false,
                 message: e.to_string(),
                 response_time_ms: Some(response_time),
-                http_status: None,
-                model_used: String::new(),
+                http_status: Self::extract_http_status(&e.to_string()),
+                model_used: model_to_test,
                 tested_at,
                 retry_count: 0,
             }),
@@ -534,6 +734,16 @@ impl StreamCheckService {
         (model.to_string(), None)
     }
 
+    /// 从 "HTTP {status}: ..." 形式的错误信息中提取状态码
+    fn extract_http_status(msg: &str) -> Option<u16> {
+        msg.strip_prefix("HTTP ")?
+            .split(':')
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
     fn should_retry(msg: &str) -> bool {
         let lower = msg.to_lowercase();
         lower.contains("timeout") || lower.contains("abort") || lower.contains("timed out")
@@ -549,11 +759,15 @@ impl StreamCheckService {
         }
     }
 
+    /// 选择检查使用的模型：固定模型 > 供应商配置中的模型 > 供应商单独配置/全局配置的默认模型
     fn resolve_test_model(
         app_type: &AppType,
         provider: &Provider,
         config: &StreamCheckConfig,
     ) -> String {
+        if let Some(model) = config.pinned_model(app_type, &provider.id) {
+            return model.to_string();
+        }
         match app_type {
             AppType::Claude => Self::extract_env_model(provider, "ANTHROPIC_MODEL")
                 .unwrap_or_else(|| config.claude_model.clone()),
@@ -655,12 +869,75 @@ mod tests {
         assert!(!StreamCheckService::should_retry("API Key invalid"));
     }
 
+    #[test]
+    fn test_extract_http_status() {
+        assert_eq!(
+            StreamCheckService::extract_http_status("HTTP 401: {\"error\":\"expired\"}"),
+            Some(401)
+        );
+        assert_eq!(
+            StreamCheckService::extract_http_status("Request timeout"),
+            None
+        );
+    }
+
     #[test]
     fn test_default_config() {
         let config = StreamCheckConfig::default();
         assert_eq!(config.timeout_secs, 45);
         assert_eq!(config.max_retries, 2);
         assert_eq!(config.degraded_threshold_ms, 6000);
+        assert!(config.schedule_interval(&AppType::Claude).is_none());
+    }
+
+    #[test]
+    fn test_schedule_interval_from_json() {
+        let config: StreamCheckConfig = serde_json::from_value(json!({
+            "timeoutSecs": 45,
+            "maxRetries": 2,
+            "degradedThresholdMs": 6000,
+            "claudeModel": "m",
+            "codexModel": "m",
+            "geminiModel": "m",
+            "streamCheckIntervalMinutes": { "claude": 15, "codex": 0 }
+        }))
+        .unwrap();
+        assert_eq!(
+            config.schedule_interval(&AppType::Claude),
+            Some(Duration::from_secs(15 * 60))
+        );
+        assert!(config.schedule_interval(&AppType::Codex).is_none());
+        assert!(config.schedule_interval(&AppType::Gemini).is_none());
+        assert!(!config.schedule_when_proxy_stopped);
+        assert!(config.provider_models.is_empty());
+    }
+
+    #[test]
+    fn test_pinned_model_takes_precedence() {
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "P1".to_string(),
+            json!({ "env": { "ANTHROPIC_MODEL": "provider-model" } }),
+            None,
+        );
+        let mut config = StreamCheckConfig::default();
+        assert_eq!(
+            StreamCheckService::resolve_test_model(&AppType::Claude, &provider, &config),
+            "provider-model"
+        );
+
+        config.pin_model(&AppType::Claude, "p1", "pinned-model".to_string());
+        assert_eq!(
+            StreamCheckService::resolve_test_model(&AppType::Claude, &provider, &config),
+            "pinned-model"
+        );
+        // 固定模型按应用区分，空白值视为未设置
+        assert!(config.pinned_model(&AppType::Codex, "p1").is_none());
+        config.pin_model(&AppType::Claude, "p1", "  ".to_string());
+        assert_eq!(
+            StreamCheckService::resolve_test_model(&AppType::Claude, &provider, &config),
+            "provider-model"
+        );
     }
 
     #[test]
@@ -727,4 +1004,44 @@ mod tests {
         assert_eq!(claude_auth, AuthStrategy::ClaudeAuth);
         assert_eq!(bearer, AuthStrategy::Bearer);
     }
+
+    #[tokio::test]
+    async fn test_check_providers_subset_reports_progress() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        for id in ["a", "b", "c"] {
+            // 未配置 base_url，检查会立即失败而不发起网络请求
+            let provider =
+                Provider::with_id(id.to_string(), format!("Provider {id}"), json!({}), None);
+            db.save_provider("claude", &provider)?;
+        }
+
+        let subset = vec!["a".to_string(), "c".to_string()];
+        let mut progress = Vec::new();
+        let results = StreamCheckService::check_providers(
+            &db,
+            &AppType::Claude,
+            false,
+            Some(&subset),
+            8,
+            |p| progress.push((p.provider_id, p.completed, p.total)),
+        )
+        .await?;
+
+        let mut ids: Vec<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["a", "c"]);
+        assert!(results
+            .iter()
+            .all(|(_, r)| r.status == HealthStatus::Failed));
+        assert_eq!(progress.len(), 2);
+        assert_eq!(
+            progress
+                .iter()
+                .map(|(_, done, _)| *done)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert!(progress.iter().all(|(_, _, total)| *total == 2));
+        Ok(())
+    }
 }
@@ -0,0 +1,152 @@
+//! 流式健康检查定时调度
+//!
+//! 按 `StreamCheckConfig.stream_check_interval_minutes` 为每个应用启动一个后台任务，
+//! 定期检查代理目标（当前供应商 + 故障转移队列）。默认只在代理运行时检查，
+//! 开启 `schedule_when_proxy_stopped` 后始终检查。
+//!
+//! - 保存配置时调用 [`StreamCheckScheduler::reschedule`] 重建任务；
+//! - 每个应用一把互斥锁，定时检查与手动批量检查共用：上一轮未结束时定时任务跳过本轮，
+//!   手动检查等待其结束，避免同一应用的检查重叠；
+//! - 退出前调用 [`StreamCheckScheduler::shutdown`] 停止所有任务。
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::stream_check::{
+    StreamCheckConfig, StreamCheckService, StreamCheckSummary, DEFAULT_CHECK_CONCURRENCY,
+};
+use crate::app_config::AppType;
+use crate::store::AppState;
+
+/// 每轮定时检查完成后发送的事件
+pub const STREAM_CHECK_COMPLETED_EVENT: &str = "stream-check-completed";
+
+static SCHEDULER: Lazy<StreamCheckScheduler> = Lazy::new(StreamCheckScheduler::default);
+
+#[derive(Default)]
+pub struct StreamCheckScheduler {
+    /// 应用 → 后台任务
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// 应用 → 检查锁，防止同一应用的多轮检查重叠执行
+    run_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl StreamCheckScheduler {
+    /// 获取指定应用的检查锁（不存在时创建）
+    fn run_lock(app_type: &AppType) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = SCHEDULER
+            .run_locks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        locks
+            .entry(app_type.as_str().to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// 手动批量检查前调用：等待该应用正在进行的定时检查结束，并在持有期间阻止定时检查
+    pub async fn acquire_run_lock(app_type: &AppType) -> tokio::sync::OwnedMutexGuard<()> {
+        Self::run_lock(app_type).lock_owned().await
+    }
+
+    /// 按配置重建所有定时任务（间隔为 0 的应用不启动任务）
+    pub fn reschedule(app: &AppHandle, config: &StreamCheckConfig) {
+        let scheduler = &*SCHEDULER;
+        let Ok(mut tasks) = scheduler.tasks.lock() else {
+            log::error!("[StreamCheck] 调度器锁已损坏，无法更新定时检查");
+            return;
+        };
+
+        for (_, handle) in tasks.drain() {
+            handle.abort();
+        }
+
+        for app_type in AppType::all() {
+            let Some(interval) = config.schedule_interval(&app_type) else {
+                continue;
+            };
+            log::info!(
+                "[StreamCheck] 启动 {} 定时检查，间隔 {} 分钟",
+                app_type.as_str(),
+                interval.as_secs() / 60
+            );
+            let handle = tauri::async_runtime::spawn(Self::run_loop(
+                app.clone(),
+                app_type.clone(),
+                interval,
+                config.schedule_when_proxy_stopped,
+                Self::run_lock(&app_type),
+            ));
+            tasks.insert(app_type.as_str().to_string(), handle);
+        }
+    }
+
+    /// 停止所有定时任务
+    pub fn shutdown() {
+        if let Ok(mut tasks) = SCHEDULER.tasks.lock() {
+            for (app, handle) in tasks.drain() {
+                handle.abort();
+                log::debug!("[StreamCheck] 已停止 {app} 定时检查");
+            }
+        }
+    }
+
+    async fn run_loop(
+        app: AppHandle,
+        app_type: AppType,
+        interval: std::time::Duration,
+        when_proxy_stopped: bool,
+        run_lock: Arc<tokio::sync::Mutex<()>>,
+    ) {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if !when_proxy_stopped && !state.proxy_service.is_running().await {
+                log::debug!(
+                    "[StreamCheck] 代理未运行，跳过 {} 定时检查",
+                    app_type.as_str()
+                );
+                continue;
+            }
+
+            let Ok(_guard) = run_lock.try_lock() else {
+                log::debug!(
+                    "[StreamCheck] 上一轮检查尚未结束，跳过 {} 定时检查",
+                    app_type.as_str()
+                );
+                continue;
+            };
+
+            match StreamCheckService::check_providers(
+                &state.db,
+                &app_type,
+                true,
+                None,
+                DEFAULT_CHECK_CONCURRENCY,
+                |_| {},
+            )
+            .await
+            {
+                Ok(results) => {
+                    let summary = StreamCheckSummary::from_results(&app_type, &results);
+                    log::info!(
+                        "[StreamCheck] {} 定时检查完成: total={}, failed={}",
+                        summary.app_type,
+                        summary.total,
+                        summary.failed
+                    );
+                    if let Err(e) = app.emit(STREAM_CHECK_COMPLETED_EVENT, &summary) {
+                        log::error!("[StreamCheck] 发射检查完成事件失败: {e}");
+                    }
+                }
+                Err(e) => log::warn!("[StreamCheck] {} 定时检查失败: {e}", app_type.as_str()),
+            }
+        }
+    }
+}
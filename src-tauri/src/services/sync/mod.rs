@@ -0,0 +1,480 @@
+//! 配置数据库云同步
+//!
+//! 将加密的导出载荷推送到 WebDAV 或 S3 兼容存储，并从远端拉取覆盖本地。
+//!
+//! 远端保存两个对象：
+//! - `cc-switch-sync.json`：同步清单（修订号、设备 ID、载荷对象名及其 SHA-256）
+//! - `cc-switch-sync-r{revision}-{device}.enc`：加密载荷
+//!
+//! 冲突检测基于单调递增的修订号：本机记录最近一次同步的修订号与本地数据指纹，
+//! 远端修订号更新时拒绝推送，本地有未同步修改时拒绝拉取，除非显式强制。
+//! 清单写入使用 ETag 条件请求，避免并发推送相互覆盖。
+
+mod s3;
+mod webdav;
+
+use crate::database::Database;
+use crate::error::AppError;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// 同步清单对象名
+const MANIFEST_NAME: &str = "cc-switch-sync.json";
+/// 当前清单格式版本
+const MANIFEST_FORMAT_VERSION: u32 = 1;
+
+/// 同步后端类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncBackend {
+    #[default]
+    Webdav,
+    S3,
+}
+
+/// 同步范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncScope {
+    /// 仅同步配置（供应商、MCP、提示词、Skills、设置等）
+    #[default]
+    Config,
+    /// 同时同步请求日志等运行数据
+    Full,
+}
+
+impl SyncScope {
+    fn include_runtime_data(self) -> bool {
+        matches!(self, SyncScope::Full)
+    }
+}
+
+/// 云同步配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConfig {
+    #[serde(default)]
+    pub backend: SyncBackend,
+    /// WebDAV 地址或 S3 服务端点（如 `https://dav.example.com/remote.php/dav/files/me`）
+    #[serde(default)]
+    pub endpoint: String,
+    /// 远端目录（WebDAV）或对象前缀（S3），为空表示根目录
+    #[serde(default)]
+    pub remote_path: String,
+    /// WebDAV 用户名 / S3 Access Key ID
+    #[serde(default)]
+    pub username: String,
+    /// WebDAV 密码 / S3 Secret Access Key
+    ///
+    /// 以明文保存在本机数据库的 settings 表，不参与同步，也不会写入 SQL 导出文件。
+    #[serde(default)]
+    pub password: String,
+    /// S3 存储桶
+    #[serde(default)]
+    pub bucket: Option<String>,
+    /// S3 区域，未填写时使用 `us-east-1`
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub scope: SyncScope,
+}
+
+/// 本机同步状态（保存在 settings 表，不参与同步）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState {
+    /// 本机设备 ID
+    #[serde(default)]
+    pub device_id: String,
+    /// 最近一次成功同步对应的远端修订号
+    #[serde(default)]
+    pub revision: u64,
+    /// 最近一次同步时本地数据的指纹
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    /// 最近一次同步时间（Unix 毫秒）
+    #[serde(default)]
+    pub last_synced_at: Option<i64>,
+}
+
+/// 远端同步清单
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncManifest {
+    pub format_version: u32,
+    pub revision: u64,
+    pub device_id: String,
+    pub updated_at: i64,
+    pub scope: SyncScope,
+    pub payload: String,
+    pub payload_sha256: String,
+}
+
+/// 推送 / 拉取结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    /// 执行后的修订号
+    pub revision: u64,
+    /// 是否实际传输了数据（已是最新时为 false）
+    pub transferred: bool,
+    /// 远端清单写入方的设备 ID
+    pub device_id: String,
+}
+
+/// 远端对象
+pub(crate) struct RemoteObject {
+    pub data: Vec<u8>,
+    pub etag: Option<String>,
+}
+
+/// 写入条件
+pub(crate) enum PutCondition<'a> {
+    /// 无条件写入
+    Always,
+    /// 对象不存在时才写入
+    IfAbsent,
+    /// ETag 匹配时才写入
+    IfMatch(&'a str),
+}
+
+/// 远端存储
+enum Remote {
+    Webdav(webdav::WebdavRemote),
+    S3(s3::S3Remote),
+}
+
+impl Remote {
+    fn from_config(config: &SyncConfig) -> Result<Self, AppError> {
+        if config.endpoint.trim().is_empty() {
+            return Err(AppError::localized(
+                "sync.not_configured",
+                "尚未配置云同步地址",
+                "Sync endpoint is not configured",
+            ));
+        }
+        match config.backend {
+            SyncBackend::Webdav => Ok(Self::Webdav(webdav::WebdavRemote::new(config)?)),
+            SyncBackend::S3 => Ok(Self::S3(s3::S3Remote::new(config)?)),
+        }
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<RemoteObject>, AppError> {
+        match self {
+            Self::Webdav(r) => r.get(name).await,
+            Self::S3(r) => r.get(name).await,
+        }
+    }
+
+    async fn put(
+        &self,
+        name: &str,
+        data: Vec<u8>,
+        condition: PutCondition<'_>,
+    ) -> Result<(), AppError> {
+        match self {
+            Self::Webdav(r) => r.put(name, data, condition).await,
+            Self::S3(r) => r.put(name, data, condition).await,
+        }
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), AppError> {
+        match self {
+            Self::Webdav(r) => r.delete(name).await,
+            Self::S3(r) => r.delete(name).await,
+        }
+    }
+
+    async fn fetch_manifest(&self) -> Result<Option<(SyncManifest, Option<String>)>, AppError> {
+        let Some(object) = self.get(MANIFEST_NAME).await? else {
+            return Ok(None);
+        };
+        let manifest: SyncManifest = serde_json::from_slice(&object.data)
+            .map_err(|e| AppError::Message(format!("解析远端同步清单失败: {e}")))?;
+        if manifest.format_version > MANIFEST_FORMAT_VERSION {
+            return Err(AppError::localized(
+                "sync.unsupported_version",
+                "远端同步数据由更新版本的 CC Switch 写入，请先升级",
+                "Remote sync data was written by a newer CC Switch version, please upgrade",
+            ));
+        }
+        Ok(Some((manifest, object.etag)))
+    }
+}
+
+pub struct SyncService;
+
+impl SyncService {
+    /// 推送本地数据到远端
+    ///
+    /// 远端修订号高于本机最近同步的修订号时返回 `sync.conflict.remote_newer`，
+    /// `force` 为 true 时忽略该检查直接覆盖远端。
+    pub async fn push(
+        db: Arc<Database>,
+        passphrase: String,
+        force: bool,
+    ) -> Result<SyncResult, AppError> {
+        let config = db.get_sync_config()?;
+        let remote = Remote::from_config(&config)?;
+        let state = db.get_sync_state()?;
+
+        let current = remote.fetch_manifest().await?;
+        let remote_revision = current.as_ref().map(|(m, _)| m.revision);
+        if !force {
+            check_push(&state, remote_revision)?;
+        }
+
+        let include_runtime = config.scope.include_runtime_data();
+        let db_for_export = db.clone();
+        let payload = tauri::async_runtime::spawn_blocking(move || {
+            db_for_export.export_sync_payload(&passphrase, include_runtime)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("生成同步数据失败: {e}")))??;
+
+        let revision = remote_revision.unwrap_or(0).max(state.revision) + 1;
+        let payload_name = format!("cc-switch-sync-r{revision}-{}.enc", state.device_id);
+        let manifest = SyncManifest {
+            format_version: MANIFEST_FORMAT_VERSION,
+            revision,
+            device_id: state.device_id.clone(),
+            updated_at: Utc::now().timestamp_millis(),
+            scope: config.scope,
+            payload: payload_name.clone(),
+            payload_sha256: sha256_hex(&payload.data),
+        };
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+
+        remote
+            .put(&payload_name, payload.data, PutCondition::Always)
+            .await?;
+
+        let condition = match &current {
+            Some((_, Some(etag))) if !force => PutCondition::IfMatch(etag),
+            Some(_) => PutCondition::Always,
+            None => PutCondition::IfAbsent,
+        };
+        if let Err(err) = remote.put(MANIFEST_NAME, manifest_bytes, condition).await {
+            // 清单未更新，新载荷不会被引用
+            if let Err(e) = remote.delete(&payload_name).await {
+                log::warn!("[Sync] 清理未引用的同步载荷失败: {e}");
+            }
+            return Err(err);
+        }
+
+        if let Some((previous, _)) = &current {
+            if previous.payload != payload_name {
+                if let Err(e) = remote.delete(&previous.payload).await {
+                    log::warn!("[Sync] 清理旧同步载荷失败: {e}");
+                }
+            }
+        }
+
+        db.set_sync_state(&SyncState {
+            revision,
+            fingerprint: Some(payload.fingerprint),
+            last_synced_at: Some(Utc::now().timestamp_millis()),
+            ..state
+        })?;
+        log::info!("[Sync] 推送完成，修订号 {revision}");
+
+        Ok(SyncResult {
+            revision,
+            transferred: true,
+            device_id: manifest.device_id,
+        })
+    }
+
+    /// 从远端拉取数据覆盖本地
+    ///
+    /// 本地自上次同步以来有修改时返回 `sync.conflict.local_modified`，
+    /// `force` 为 true 时丢弃本地修改（拉取前仍会生成数据库备份）。
+    pub async fn pull(
+        db: Arc<Database>,
+        passphrase: String,
+        force: bool,
+    ) -> Result<SyncResult, AppError> {
+        let config = db.get_sync_config()?;
+        let remote = Remote::from_config(&config)?;
+        let state = db.get_sync_state()?;
+
+        let Some((manifest, _)) = remote.fetch_manifest().await? else {
+            return Err(AppError::localized(
+                "sync.remote_empty",
+                "远端尚无同步数据，请先在任一设备上推送",
+                "No sync data on the remote yet, push from a device first",
+            ));
+        };
+
+        if manifest.revision <= state.revision && !force {
+            return Ok(SyncResult {
+                revision: state.revision,
+                transferred: false,
+                device_id: manifest.device_id,
+            });
+        }
+
+        let include_runtime = config.scope.include_runtime_data();
+        if !force {
+            let db_for_check = db.clone();
+            let local_fingerprint = tauri::async_runtime::spawn_blocking(move || {
+                db_for_check.sync_fingerprint(include_runtime)
+            })
+            .await
+            .map_err(|e| AppError::Message(format!("计算本地数据指纹失败: {e}")))??;
+            check_pull(&state, &local_fingerprint)?;
+        }
+
+        let object = remote.get(&manifest.payload).await?.ok_or_else(|| {
+            AppError::localized(
+                "sync.payload_missing",
+                "远端同步载荷缺失，可能正在被其他设备更新，请稍后重试",
+                "Remote sync payload is missing, another device may be pushing; retry later",
+            )
+        })?;
+        if sha256_hex(&object.data) != manifest.payload_sha256 {
+            return Err(AppError::localized(
+                "sync.payload_mismatch",
+                "远端同步载荷校验失败，可能正在被其他设备更新，请稍后重试",
+                "Remote sync payload checksum mismatch, another device may be pushing; retry later",
+            ));
+        }
+
+        let db_for_apply = db.clone();
+        let fingerprint = tauri::async_runtime::spawn_blocking(move || {
+            db_for_apply.apply_sync_payload(&object.data, &passphrase, include_runtime)?;
+            db_for_apply.sync_fingerprint(include_runtime)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("应用同步数据失败: {e}")))??;
+
+        db.set_sync_state(&SyncState {
+            revision: manifest.revision,
+            fingerprint: Some(fingerprint),
+            last_synced_at: Some(Utc::now().timestamp_millis()),
+            ..state
+        })?;
+        log::info!(
+            "[Sync] 拉取完成，修订号 {}（来自设备 {}）",
+            manifest.revision,
+            manifest.device_id
+        );
+
+        Ok(SyncResult {
+            revision: manifest.revision,
+            transferred: true,
+            device_id: manifest.device_id,
+        })
+    }
+}
+
+/// 推送前检查：远端存在本机尚未拉取的修订时拒绝
+fn check_push(state: &SyncState, remote_revision: Option<u64>) -> Result<(), AppError> {
+    match remote_revision {
+        Some(remote) if remote > state.revision => Err(AppError::localized(
+            "sync.conflict.remote_newer",
+            format!(
+                "远端修订号 {remote} 比本机最近同步的 {} 更新，请先拉取",
+                state.revision
+            ),
+            format!(
+                "Remote revision {remote} is newer than last synced revision {}, pull first",
+                state.revision
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 拉取前检查：本地自上次同步以来有修改时拒绝
+fn check_pull(state: &SyncState, local_fingerprint: &str) -> Result<(), AppError> {
+    match &state.fingerprint {
+        Some(synced) if synced != local_fingerprint => Err(AppError::localized(
+            "sync.conflict.local_modified",
+            "本地配置自上次同步后已修改，拉取会覆盖这些修改",
+            "Local configuration changed since the last sync and would be overwritten by pull",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 清单条件写入失败（其他设备同时推送）
+fn concurrent_update() -> AppError {
+    AppError::localized(
+        "sync.conflict.concurrent_update",
+        "远端同步数据已被其他设备更新，请先拉取",
+        "Remote sync data was updated by another device, pull first",
+    )
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// 拼接远端目录与对象名
+fn join_remote_path(remote_path: &str, name: &str) -> String {
+    let dir = remote_path.trim_matches('/');
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(revision: u64, fingerprint: Option<&str>) -> SyncState {
+        SyncState {
+            device_id: "device-a".to_string(),
+            revision,
+            fingerprint: fingerprint.map(str::to_string),
+            last_synced_at: None,
+        }
+    }
+
+    fn error_key(err: AppError) -> &'static str {
+        match err {
+            AppError::Localized { key, .. } => key,
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_push_rejected_when_remote_newer() {
+        assert!(check_push(&state(3, None), None).is_ok());
+        assert!(check_push(&state(3, None), Some(3)).is_ok());
+        let err = check_push(&state(3, None), Some(4)).unwrap_err();
+        assert_eq!(error_key(err), "sync.conflict.remote_newer");
+    }
+
+    #[test]
+    fn test_pull_rejected_when_local_modified() {
+        assert!(check_pull(&state(0, None), "abc").is_ok());
+        assert!(check_pull(&state(2, Some("abc")), "abc").is_ok());
+        let err = check_pull(&state(2, Some("abc")), "def").unwrap_err();
+        assert_eq!(error_key(err), "sync.conflict.local_modified");
+    }
+
+    #[test]
+    fn test_sync_config_serde_default() {
+        let config: SyncConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.backend, SyncBackend::Webdav);
+        assert_eq!(config.scope, SyncScope::Config);
+        assert!(config.endpoint.is_empty());
+    }
+
+    #[test]
+    fn test_join_remote_path() {
+        assert_eq!(join_remote_path("", "a.json"), "a.json");
+        assert_eq!(
+            join_remote_path("/cc-switch/", "a.json"),
+            "cc-switch/a.json"
+        );
+        assert_eq!(join_remote_path("x/y", "a.json"), "x/y/a.json");
+    }
+}
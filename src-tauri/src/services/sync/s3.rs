@@ -0,0 +1,280 @@
+//! S3 兼容同步后端（路径风格寻址 + AWS Signature V4）
+
+use super::{join_remote_path, PutCondition, RemoteObject, SyncConfig};
+use crate::error::AppError;
+use chrono::Utc;
+use reqwest::{header, Method, StatusCode};
+use ring::hmac;
+use sha2::{Digest, Sha256};
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+pub(super) struct S3Remote {
+    endpoint: url::Url,
+    bucket: String,
+    prefix: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3Remote {
+    pub(super) fn new(config: &SyncConfig) -> Result<Self, AppError> {
+        let endpoint = url::Url::parse(config.endpoint.trim().trim_end_matches('/'))
+            .map_err(|e| AppError::InvalidInput(format!("无效的 S3 服务端点: {e}")))?;
+        let bucket = config
+            .bucket
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .ok_or_else(|| AppError::InvalidInput("S3 存储桶不能为空".to_string()))?
+            .to_string();
+        let region = config
+            .region
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .unwrap_or(DEFAULT_REGION)
+            .to_string();
+
+        Ok(Self {
+            endpoint,
+            bucket,
+            prefix: config.remote_path.trim_matches('/').to_string(),
+            region,
+            access_key_id: config.username.clone(),
+            secret_access_key: config.password.clone(),
+        })
+    }
+
+    pub(super) async fn get(&self, name: &str) -> Result<Option<RemoteObject>, AppError> {
+        let response = self
+            .send(Method::GET, name, Vec::new(), None)
+            .await
+            .map_err(|e| AppError::Message(format!("S3 下载失败: {e}")))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(status_error("下载", status));
+        }
+
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Message(format!("S3 读取响应失败: {e}")))?
+            .to_vec();
+
+        Ok(Some(RemoteObject { data, etag }))
+    }
+
+    pub(super) async fn put(
+        &self,
+        name: &str,
+        data: Vec<u8>,
+        condition: PutCondition<'_>,
+    ) -> Result<(), AppError> {
+        let condition_header = match condition {
+            PutCondition::Always => None,
+            PutCondition::IfAbsent => Some((header::IF_NONE_MATCH, "*")),
+            PutCondition::IfMatch(etag) => Some((header::IF_MATCH, etag)),
+        };
+        let response = self
+            .send(Method::PUT, name, data, condition_header)
+            .await
+            .map_err(|e| AppError::Message(format!("S3 上传失败: {e}")))?;
+
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED || status == StatusCode::CONFLICT {
+            return Err(super::concurrent_update());
+        }
+        if !status.is_success() {
+            return Err(status_error("上传", status));
+        }
+        Ok(())
+    }
+
+    pub(super) async fn delete(&self, name: &str) -> Result<(), AppError> {
+        let response = self
+            .send(Method::DELETE, name, Vec::new(), None)
+            .await
+            .map_err(|e| AppError::Message(format!("S3 删除失败: {e}")))?;
+
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(status_error("删除", status))
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        name: &str,
+        body: Vec<u8>,
+        extra_header: Option<(header::HeaderName, &str)>,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let key = join_remote_path(&self.prefix, name);
+        let canonical_uri = canonical_uri(self.endpoint.path(), &self.bucket, &key);
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{port}", self.endpoint.host_str().unwrap_or_default()),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+        let url = format!("{}://{host}{canonical_uri}", self.endpoint.scheme());
+
+        let payload_hash = format!("{:x}", Sha256::digest(&body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sign_v4(&SigningRequest {
+            method: method.as_str(),
+            canonical_uri: &canonical_uri,
+            host: &host,
+            payload_hash: &payload_hash,
+            amz_date: &amz_date,
+            region: &self.region,
+            access_key_id: &self.access_key_id,
+            secret_access_key: &self.secret_access_key,
+        });
+
+        let mut request = crate::proxy::http_client::get()
+            .request(method, url)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header(header::AUTHORIZATION, authorization);
+        if let Some((name, value)) = extra_header {
+            request = request.header(name, value);
+        }
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+        request.send().await
+    }
+}
+
+/// 待签名请求的要素
+struct SigningRequest<'a> {
+    method: &'a str,
+    canonical_uri: &'a str,
+    host: &'a str,
+    payload_hash: &'a str,
+    amz_date: &'a str,
+    region: &'a str,
+    access_key_id: &'a str,
+    secret_access_key: &'a str,
+}
+
+/// 生成 AWS Signature V4 的 Authorization 头
+fn sign_v4(req: &SigningRequest<'_>) -> String {
+    let date = &req.amz_date[..8];
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{signed_headers}\n{}",
+        req.method, req.canonical_uri, req.host, req.payload_hash, req.amz_date, req.payload_hash
+    );
+    let scope = format!("{date}/{}/s3/aws4_request", req.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{scope}\n{:x}",
+        req.amz_date,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+
+    let signing_key = derive_signing_key(req.secret_access_key, date, req.region, "s3");
+    let signature = hmac::sign(
+        &hmac::Key::new(hmac::HMAC_SHA256, &signing_key),
+        string_to_sign.as_bytes(),
+    );
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+        req.access_key_id,
+        to_hex(signature.as_ref())
+    )
+}
+
+fn derive_signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let mut key = format!("AWS4{secret}").into_bytes();
+    for part in [date, region, service, "aws4_request"] {
+        key = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, &key), part.as_bytes())
+            .as_ref()
+            .to_vec();
+    }
+    key
+}
+
+/// 构造路径风格的规范 URI（各段按 SigV4 规则编码）
+fn canonical_uri(base_path: &str, bucket: &str, key: &str) -> String {
+    let mut uri = String::new();
+    let segments = base_path
+        .split('/')
+        .chain(std::iter::once(bucket))
+        .chain(key.split('/'))
+        .filter(|s| !s.is_empty());
+    for segment in segments {
+        uri.push('/');
+        uri.push_str(&uri_encode(segment));
+    }
+    uri
+}
+
+fn uri_encode(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for b in segment.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn status_error(action: &str, status: StatusCode) -> AppError {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return AppError::localized(
+            "sync.auth_failed",
+            format!("S3 认证失败（HTTP {status}）"),
+            format!("S3 authentication failed (HTTP {status})"),
+        );
+    }
+    AppError::Message(format!("S3 {action}失败: HTTP {status}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_signing_key_matches_aws_example() {
+        // AWS 文档中的签名密钥派生示例
+        let key = derive_signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            to_hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_canonical_uri_encodes_segments() {
+        assert_eq!(
+            canonical_uri("/", "my-bucket", "cc switch/cc-switch-sync.json"),
+            "/my-bucket/cc%20switch/cc-switch-sync.json"
+        );
+        assert_eq!(canonical_uri("/storage", "b", "a.enc"), "/storage/b/a.enc");
+    }
+}
@@ -0,0 +1,144 @@
+//! WebDAV 同步后端（PUT / GET / DELETE + ETag 条件请求）
+
+use super::{join_remote_path, PutCondition, RemoteObject, SyncConfig};
+use crate::error::AppError;
+use reqwest::{header, Method, RequestBuilder, StatusCode};
+
+pub(super) struct WebdavRemote {
+    base_url: String,
+    remote_path: String,
+    username: String,
+    password: String,
+}
+
+impl WebdavRemote {
+    pub(super) fn new(config: &SyncConfig) -> Result<Self, AppError> {
+        let base_url = config.endpoint.trim().trim_end_matches('/').to_string();
+        url::Url::parse(&base_url)
+            .map_err(|e| AppError::InvalidInput(format!("无效的 WebDAV 地址: {e}")))?;
+
+        Ok(Self {
+            base_url,
+            remote_path: config.remote_path.trim_matches('/').to_string(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+        })
+    }
+
+    pub(super) async fn get(&self, name: &str) -> Result<Option<RemoteObject>, AppError> {
+        let response = self
+            .request(Method::GET, name)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("WebDAV 下载失败: {e}")))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(status_error("下载", status));
+        }
+
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Message(format!("WebDAV 读取响应失败: {e}")))?
+            .to_vec();
+
+        Ok(Some(RemoteObject { data, etag }))
+    }
+
+    pub(super) async fn put(
+        &self,
+        name: &str,
+        data: Vec<u8>,
+        condition: PutCondition<'_>,
+    ) -> Result<(), AppError> {
+        self.ensure_collection().await;
+
+        let mut request = self.request(Method::PUT, name).body(data);
+        request = match condition {
+            PutCondition::Always => request,
+            PutCondition::IfAbsent => request.header(header::IF_NONE_MATCH, "*"),
+            PutCondition::IfMatch(etag) => request.header(header::IF_MATCH, etag),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("WebDAV 上传失败: {e}")))?;
+
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Err(super::concurrent_update());
+        }
+        if !status.is_success() {
+            return Err(status_error("上传", status));
+        }
+        Ok(())
+    }
+
+    pub(super) async fn delete(&self, name: &str) -> Result<(), AppError> {
+        let response = self
+            .request(Method::DELETE, name)
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("WebDAV 删除失败: {e}")))?;
+
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(status_error("删除", status))
+        }
+    }
+
+    /// 逐级创建远端目录（已存在时服务器返回 405，忽略）
+    async fn ensure_collection(&self) {
+        if self.remote_path.is_empty() {
+            return;
+        }
+
+        let mut current = String::new();
+        for segment in self.remote_path.split('/').filter(|s| !s.is_empty()) {
+            current = join_remote_path(&current, segment);
+            let url = format!("{}/{current}/", self.base_url);
+            let result = crate::proxy::http_client::get()
+                .request(Method::from_bytes(b"MKCOL").expect("valid method"), &url)
+                .basic_auth(&self.username, Some(&self.password))
+                .send()
+                .await;
+            if let Err(e) = result {
+                log::debug!("[Sync] WebDAV MKCOL {current} 失败: {e}");
+            }
+        }
+    }
+
+    fn request(&self, method: Method, name: &str) -> RequestBuilder {
+        let url = format!(
+            "{}/{}",
+            self.base_url,
+            join_remote_path(&self.remote_path, name)
+        );
+        crate::proxy::http_client::get()
+            .request(method, url)
+            .basic_auth(&self.username, Some(&self.password))
+    }
+}
+
+fn status_error(action: &str, status: StatusCode) -> AppError {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return AppError::localized(
+            "sync.auth_failed",
+            format!("WebDAV 认证失败（HTTP {status}）"),
+            format!("WebDAV authentication failed (HTTP {status})"),
+        );
+    }
+    AppError::Message(format!("WebDAV {action}失败: HTTP {status}"))
+}
@@ -4,7 +4,11 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use chrono::{Local, TimeZone};
+use crate::provider::Provider;
+use crate::proxy::types::ForwardAttempt;
+use crate::proxy::usage::calculator::{CostBreakdown, CostCalculator, ModelPricing};
+use crate::services::currency::DisplayCost;
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, TimeZone, Timelike};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -22,6 +26,9 @@ pub struct UsageSummary {
     pub total_cache_creation_tokens: u64,
     pub total_cache_read_tokens: u64,
     pub success_rate: f32,
+    /// total_cost 按展示货币换算后的金额（未配置展示货币时为 None）
+    #[serde(default)]
+    pub display_cost: Option<DisplayCost>,
 }
 
 /// 每日统计
@@ -36,8 +43,53 @@ pub struct DailyStats {
     pub total_output_tokens: u64,
     pub total_cache_creation_tokens: u64,
     pub total_cache_read_tokens: u64,
+    /// total_cost 按展示货币换算后的金额（未配置展示货币时为 None）
+    #[serde(default)]
+    pub display_cost: Option<DisplayCost>,
 }
 
+impl DailyStats {
+    fn empty(date: String) -> Self {
+        Self {
+            date,
+            request_count: 0,
+            total_cost: "0.000000".to_string(),
+            total_tokens: 0,
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            total_cache_creation_tokens: 0,
+            total_cache_read_tokens: 0,
+            display_cost: None,
+        }
+    }
+}
+
+/// 趋势统计的分桶粒度（按本地时区的自然小时/日/周/月对齐）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl TrendBucket {
+    /// 未指定起始时间时的默认窗口（秒）
+    fn default_window_seconds(self) -> i64 {
+        const DAY: i64 = 24 * 60 * 60;
+        match self {
+            TrendBucket::Hour => DAY,
+            TrendBucket::Day => 30 * DAY,
+            TrendBucket::Week => 12 * 7 * DAY,
+            TrendBucket::Month => 365 * DAY,
+        }
+    }
+}
+
+/// 单次趋势查询允许的最大分桶数
+const MAX_TREND_BUCKETS: usize = 5000;
+
 /// Provider 统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,7 +100,12 @@ pub struct ProviderStats {
     pub total_tokens: u64,
     pub total_cost: String,
     pub success_rate: f32,
+    /// 非 2xx 请求占比（百分比）
+    pub error_rate: f32,
     pub avg_latency_ms: u64,
+    /// total_cost 按展示货币换算后的金额（未配置展示货币时为 None）
+    #[serde(default)]
+    pub display_cost: Option<DisplayCost>,
 }
 
 /// 模型统计
@@ -62,6 +119,34 @@ pub struct ModelStats {
     pub avg_cost_per_request: String,
 }
 
+/// 供应商成本对比（按供应商 + 模型分组）
+///
+/// 成本只统计有计费的请求：产生了 token 但成本为 0 的请求视为缺少定价，计入
+/// `unpriced_requests`，不按 $0 参与平均值。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCostComparison {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub app_type: String,
+    pub model: String,
+    pub request_count: u64,
+    /// 错误率（百分比）
+    pub error_rate: f32,
+    pub avg_latency_ms: u64,
+    /// 实际花费（含倍率）
+    pub total_spend: String,
+    /// 有定价请求的输出 token 数
+    pub priced_output_tokens: u64,
+    pub unpriced_requests: u64,
+    /// 每 1K 输出 token 的实际成本（含倍率），无有效数据时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_1k_output: Option<String>,
+    /// 每 1K 输出 token 的基础成本（扣除倍率）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_cost_per_1k_output: Option<String>,
+}
+
 /// 请求日志过滤器
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -113,6 +198,31 @@ pub struct RequestLogDetail {
     pub status_code: u16,
     pub error_message: Option<String>,
     pub created_at: i64,
+    /// 上游尝试次数（含重试、故障转移与熔断跳过；旧记录为 0）
+    #[serde(default)]
+    pub attempt_count: u32,
+    /// 上游尝试时间线（仅详情查询返回）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<Vec<ForwardAttempt>>,
+    /// 捕获的请求/响应体（仅详情查询返回，需开启 capture_bodies）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bodies: Option<RequestBodies>,
+    /// 重放来源请求 ID（仅手动重放的请求有值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
+}
+
+/// 失败请求捕获的请求/响应体（已脱敏、已截断）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestBodies {
+    /// 上游端点（旧记录为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    pub request_headers: Option<String>,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    pub truncated: bool,
 }
 
 impl Database {
@@ -124,23 +234,18 @@ impl Database {
     ) -> Result<UsageSummary, AppError> {
         let conn = lock_conn!(self.conn);
 
-        let (where_clause, params_vec) = if start_date.is_some() || end_date.is_some() {
-            let mut conditions = Vec::new();
-            let mut params = Vec::new();
-
-            if let Some(start) = start_date {
-                conditions.push("created_at >= ?");
-                params.push(start);
-            }
-            if let Some(end) = end_date {
-                conditions.push("created_at <= ?");
-                params.push(end);
-            }
-
-            (format!("WHERE {}", conditions.join(" AND ")), params)
-        } else {
-            (String::new(), Vec::new())
-        };
+        // 手动重放的请求不计入统计
+        let mut conditions = vec!["replay_of IS NULL"];
+        let mut params_vec = Vec::new();
+        if let Some(start) = start_date {
+            conditions.push("created_at >= ?");
+            params_vec.push(start);
+        }
+        if let Some(end) = end_date {
+            conditions.push("created_at <= ?");
+            params_vec.push(end);
+        }
+        let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
         let sql = format!(
             "SELECT
@@ -178,6 +283,7 @@ impl Database {
                 total_cache_creation_tokens: total_cache_creation_tokens as u64,
                 total_cache_read_tokens: total_cache_read_tokens as u64,
                 success_rate,
+                display_cost: None,
             })
         })?;
 
@@ -231,7 +337,7 @@ impl Database {
                 COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
                 COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens
             FROM proxy_request_logs
-            WHERE created_at >= ?1 AND created_at <= ?2
+            WHERE created_at >= ?1 AND created_at <= ?2 AND replay_of IS NULL
             GROUP BY bucket_idx
             ORDER BY bucket_idx ASC";
 
@@ -248,6 +354,7 @@ impl Database {
                     total_output_tokens: row.get::<_, i64>(5)? as u64,
                     total_cache_creation_tokens: row.get::<_, i64>(6)? as u64,
                     total_cache_read_tokens: row.get::<_, i64>(7)? as u64,
+                    display_cost: None,
                 },
             ))
         })?;
@@ -287,6 +394,7 @@ impl Database {
                     total_output_tokens: 0,
                     total_cache_creation_tokens: 0,
                     total_cache_read_tokens: 0,
+                    display_cost: None,
                 });
             }
         }
@@ -294,11 +402,101 @@ impl Database {
         Ok(stats)
     }
 
+    /// 获取按自然时间分桶的使用趋势，时间范围为 [start_date, end_date)
+    ///
+    /// 分桶边界按本地时区对齐（与日志的 `TimezoneStrategy::UseLocal` 一致）：小时桶从本地整点开始，
+    /// 日/周/月桶从本地零点开始（周从周一开始）。夏令时切换当天的日桶为 23 或 25 小时，
+    /// 时钟回拨时重复出现的本地小时是两个独立的小时桶。
+    pub fn get_usage_trends_bucketed(
+        &self,
+        bucket: TrendBucket,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+    ) -> Result<Vec<DailyStats>, AppError> {
+        let end_ts = end_date.unwrap_or_else(|| Local::now().timestamp());
+        let start_ts = start_date.unwrap_or(end_ts - bucket.default_window_seconds());
+        let starts = trend_bucket_starts(&Local, bucket, start_ts, end_ts)?;
+
+        // 现行时区的偏移都是 15 分钟的整数倍，先按 15 分钟预聚合，再归入本地分桶
+        let sql = "
+            SELECT
+                (created_at / 900) * 900 as slot,
+                COUNT(*) as request_count,
+                COALESCE(SUM(CAST(total_cost_usd AS REAL)), 0) as total_cost,
+                COALESCE(SUM(input_tokens), 0) as total_input_tokens,
+                COALESCE(SUM(output_tokens), 0) as total_output_tokens,
+                COALESCE(SUM(cache_creation_tokens), 0) as total_cache_creation_tokens,
+                COALESCE(SUM(cache_read_tokens), 0) as total_cache_read_tokens
+            FROM proxy_request_logs
+            WHERE created_at >= ?1 AND created_at < ?2 AND replay_of IS NULL
+            GROUP BY slot";
+
+        let mut stats: Vec<DailyStats> = starts
+            .iter()
+            .map(|start| DailyStats::empty(start.to_rfc3339()))
+            .collect();
+        let mut costs = vec![0.0_f64; stats.len()];
+
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![start_ts, end_ts], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, f64>(2)?,
+                row.get::<_, i64>(3)? as u64,
+                row.get::<_, i64>(4)? as u64,
+                row.get::<_, i64>(5)? as u64,
+                row.get::<_, i64>(6)? as u64,
+            ))
+        })?;
+
+        for row in rows {
+            let (slot, count, cost, input, output, cache_creation, cache_read) = row?;
+            let idx = starts
+                .partition_point(|start| start.timestamp() <= slot)
+                .saturating_sub(1);
+            let stat = &mut stats[idx];
+            stat.request_count += count;
+            stat.total_input_tokens += input;
+            stat.total_output_tokens += output;
+            stat.total_tokens += input + output;
+            stat.total_cache_creation_tokens += cache_creation;
+            stat.total_cache_read_tokens += cache_read;
+            costs[idx] += cost;
+        }
+
+        for (stat, cost) in stats.iter_mut().zip(costs) {
+            stat.total_cost = format!("{cost:.6}");
+        }
+        Ok(stats)
+    }
+
     /// 获取 Provider 统计
-    pub fn get_provider_stats(&self) -> Result<Vec<ProviderStats>, AppError> {
+    ///
+    /// 可选的 `start_date`/`end_date`（Unix 秒，闭区间）在聚合前过滤请求日志；
+    /// 均未指定时统计全部历史记录。
+    pub fn get_provider_stats(
+        &self,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+    ) -> Result<Vec<ProviderStats>, AppError> {
         let conn = lock_conn!(self.conn);
 
-        let sql = "SELECT
+        let mut conditions = vec!["l.replay_of IS NULL"];
+        let mut params_vec = Vec::new();
+        if let Some(start) = start_date {
+            conditions.push("l.created_at >= ?");
+            params_vec.push(start);
+        }
+        if let Some(end) = end_date {
+            conditions.push("l.created_at <= ?");
+            params_vec.push(end);
+        }
+        let where_clause = format!("WHERE {}", conditions.join(" AND "));
+
+        let sql = format!(
+            "SELECT
                 l.provider_id,
                 p.name as provider_name,
                 COUNT(*) as request_count,
@@ -308,17 +506,20 @@ impl Database {
                 COALESCE(AVG(l.latency_ms), 0) as avg_latency
              FROM proxy_request_logs l
              LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
+             {where_clause}
              GROUP BY l.provider_id, l.app_type
-             ORDER BY total_cost DESC";
+             ORDER BY total_cost DESC"
+        );
 
-        let mut stmt = conn.prepare(sql)?;
-        let rows = stmt.query_map([], |row| {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params_vec), |row| {
             let request_count: i64 = row.get(2)?;
             let success_count: i64 = row.get(5)?;
-            let success_rate = if request_count > 0 {
-                (success_count as f32 / request_count as f32) * 100.0
+            let (success_rate, error_rate) = if request_count > 0 {
+                let success_rate = (success_count as f32 / request_count as f32) * 100.0;
+                (success_rate, 100.0 - success_rate)
             } else {
-                0.0
+                (0.0, 0.0)
             };
 
             Ok(ProviderStats {
@@ -330,7 +531,9 @@ impl Database {
                 total_tokens: row.get::<_, i64>(3)? as u64,
                 total_cost: format!("{:.6}", row.get::<_, f64>(4)?),
                 success_rate,
+                error_rate,
                 avg_latency_ms: row.get::<_, f64>(6)? as u64,
+                display_cost: None,
             })
         })?;
 
@@ -352,6 +555,7 @@ impl Database {
                 COALESCE(SUM(input_tokens + output_tokens), 0) as total_tokens,
                 COALESCE(SUM(CAST(total_cost_usd AS REAL)), 0) as total_cost
              FROM proxy_request_logs
+             WHERE replay_of IS NULL
              GROUP BY model
              ORDER BY total_cost DESC";
 
@@ -382,6 +586,117 @@ impl Database {
         Ok(stats)
     }
 
+    /// 对比各供应商的实际成本，按每 1K 输出 token 的实际成本升序排列
+    ///
+    /// `model_filter` 为模型名子串匹配；没有有效计费数据的分组排在最后。
+    pub fn get_provider_cost_comparison(
+        &self,
+        app_type: Option<&str>,
+        model_filter: Option<&str>,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+    ) -> Result<Vec<ProviderCostComparison>, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let mut conditions = vec!["l.replay_of IS NULL"];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(app_type) = app_type {
+            conditions.push("l.app_type = ?");
+            params.push(Box::new(app_type.to_string()));
+        }
+        if let Some(model) = model_filter.map(str::trim).filter(|m| !m.is_empty()) {
+            conditions.push("l.model LIKE ?");
+            params.push(Box::new(format!("%{model}%")));
+        }
+        if let Some(start) = start_date {
+            conditions.push("l.created_at >= ?");
+            params.push(Box::new(start));
+        }
+        if let Some(end) = end_date {
+            conditions.push("l.created_at <= ?");
+            params.push(Box::new(end));
+        }
+        let where_clause = format!("WHERE {}", conditions.join(" AND "));
+
+        // priced: 成本 > 0；unpriced: 有 token 但成本为 0（缺少模型定价）
+        let sql = format!(
+            "SELECT
+                l.provider_id,
+                p.name as provider_name,
+                l.app_type,
+                l.model,
+                COUNT(*) as request_count,
+                COALESCE(SUM(CASE WHEN l.status_code >= 200 AND l.status_code < 300 THEN 0 ELSE 1 END), 0) as error_count,
+                COALESCE(AVG(l.latency_ms), 0) as avg_latency,
+                COALESCE(SUM(CASE WHEN CAST(l.total_cost_usd AS REAL) > 0
+                    THEN CAST(l.total_cost_usd AS REAL) ELSE 0 END), 0) as total_spend,
+                COALESCE(SUM(CASE WHEN CAST(l.total_cost_usd AS REAL) > 0
+                    THEN l.output_tokens ELSE 0 END), 0) as priced_output_tokens,
+                COALESCE(SUM(CASE WHEN CAST(l.total_cost_usd AS REAL) > 0
+                    THEN CAST(l.total_cost_usd AS REAL) / COALESCE(NULLIF(CAST(l.cost_multiplier AS REAL), 0), 1)
+                    ELSE 0 END), 0) as base_spend,
+                COALESCE(SUM(CASE WHEN CAST(l.total_cost_usd AS REAL) <= 0
+                    AND (l.input_tokens + l.output_tokens + l.cache_read_tokens + l.cache_creation_tokens) > 0
+                    THEN 1 ELSE 0 END), 0) as unpriced_requests
+             FROM proxy_request_logs l
+             LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
+             {where_clause}
+             GROUP BY l.provider_id, l.app_type, l.model"
+        );
+
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+            let request_count: i64 = row.get(4)?;
+            let error_count: i64 = row.get(5)?;
+            let total_spend: f64 = row.get(7)?;
+            let priced_output_tokens: i64 = row.get(8)?;
+            let base_spend: f64 = row.get(9)?;
+
+            let per_1k = |spend: f64| {
+                (priced_output_tokens > 0).then(|| spend * 1000.0 / priced_output_tokens as f64)
+            };
+            let error_rate = if request_count > 0 {
+                (error_count as f32 / request_count as f32) * 100.0
+            } else {
+                0.0
+            };
+
+            Ok((
+                per_1k(total_spend),
+                ProviderCostComparison {
+                    provider_id: row.get(0)?,
+                    provider_name: row
+                        .get::<_, Option<String>>(1)?
+                        .unwrap_or_else(|| "Unknown".to_string()),
+                    app_type: row.get(2)?,
+                    model: row.get(3)?,
+                    request_count: request_count as u64,
+                    error_rate,
+                    avg_latency_ms: row.get::<_, f64>(6)? as u64,
+                    total_spend: format!("{total_spend:.6}"),
+                    priced_output_tokens: priced_output_tokens as u64,
+                    unpriced_requests: row.get::<_, i64>(10)? as u64,
+                    cost_per_1k_output: per_1k(total_spend).map(|v| format!("{v:.6}")),
+                    base_cost_per_1k_output: per_1k(base_spend).map(|v| format!("{v:.6}")),
+                },
+            ))
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+        stats.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+
+        Ok(stats.into_iter().map(|(_, stat)| stat).collect())
+    }
+
     /// 获取请求日志列表（分页）
     pub fn get_request_logs(
         &self,
@@ -447,7 +762,8 @@ impl Database {
                     l.input_tokens, l.output_tokens, l.cache_read_tokens, l.cache_creation_tokens,
                     l.input_cost_usd, l.output_cost_usd, l.cache_read_cost_usd, l.cache_creation_cost_usd, l.total_cost_usd,
                     l.is_streaming, l.latency_ms, l.first_token_ms, l.duration_ms,
-                    l.status_code, l.error_message, l.created_at
+                    l.status_code, l.error_message, l.created_at,
+                    COALESCE(json_array_length(l.attempts), 0), l.replay_of
              FROM proxy_request_logs l
              LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
              {where_clause}
@@ -484,6 +800,10 @@ impl Database {
                 status_code: row.get::<_, i64>(20)? as u16,
                 error_message: row.get(21)?,
                 created_at: row.get(22)?,
+                attempt_count: row.get::<_, i64>(23)? as u32,
+                attempts: None,
+                bodies: None,
+                replay_of: row.get(24)?,
             })
         })?;
 
@@ -523,12 +843,15 @@ impl Database {
                     input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens,
                     input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd, total_cost_usd,
                     is_streaming, latency_ms, first_token_ms, duration_ms,
-                    status_code, error_message, created_at
+                    status_code, error_message, created_at, l.attempts, l.replay_of
              FROM proxy_request_logs l
              LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type
              WHERE l.request_id = ?",
             [request_id],
             |row| {
+                let attempts = row
+                    .get::<_, Option<String>>(23)?
+                    .and_then(|raw| serde_json::from_str::<Vec<ForwardAttempt>>(&raw).ok());
                 Ok(RequestLogDetail {
                     request_id: row.get(0)?,
                     provider_id: row.get(1)?,
@@ -553,6 +876,10 @@ impl Database {
                     status_code: row.get::<_, i64>(20)? as u16,
                     error_message: row.get(21)?,
                     created_at: row.get(22)?,
+                    attempt_count: attempts.as_ref().map_or(0, |a| a.len() as u32),
+                    attempts,
+                    bodies: None,
+                    replay_of: row.get(24)?,
                 })
             },
         );
@@ -567,6 +894,22 @@ impl Database {
                     &mut provider_cache,
                     &mut pricing_cache,
                 )?;
+                detail.bodies = conn
+                    .query_row(
+                        "SELECT request_headers, request_body, response_body, truncated, endpoint
+                         FROM proxy_request_bodies WHERE request_id = ?",
+                        [request_id],
+                        |row| {
+                            Ok(RequestBodies {
+                                endpoint: row.get(4)?,
+                                request_headers: row.get(0)?,
+                                request_body: row.get(1)?,
+                                response_body: row.get(2)?,
+                                truncated: row.get::<_, i64>(3)? != 0,
+                            })
+                        },
+                    )
+                    .optional()?;
                 Ok(Some(detail))
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
@@ -575,11 +918,32 @@ impl Database {
     }
 
     /// 检查 Provider 使用限额
+    ///
+    /// 开启限额强制（`enforceProviderLimits`）且超出任一限额时标记为 `limit_tripped`，
+    /// 代理故障转移会跳过该供应商，直到对应窗口重置。
     pub fn check_provider_limits(
         &self,
         provider_id: &str,
         app_type: &str,
     ) -> Result<ProviderLimitStatus, AppError> {
+        self.check_provider_limits_at(
+            provider_id,
+            app_type,
+            &Local,
+            chrono::Utc::now().timestamp(),
+            crate::settings::enforce_provider_limits(),
+        )
+    }
+
+    fn check_provider_limits_at<Tz: TimeZone>(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        tz: &Tz,
+        now_ts: i64,
+        enforce: bool,
+    ) -> Result<ProviderLimitStatus, AppError> {
+        let windows = SpendWindows::at(tz, now_ts)?;
         let conn = lock_conn!(self.conn);
 
         // 获取 provider 的限额设置
@@ -607,45 +971,286 @@ impl Database {
             })
             .unwrap_or((None, None));
 
-        // 计算今日使用量
-        let daily_usage: f64 = conn
-            .query_row(
+        // 限额查询总是重新汇总，并顺带刷新代理路由使用的缓存
+        let spend = self.reload_provider_spend(&conn, provider_id, app_type, windows, now_ts);
+        Ok(limit_status(
+            provider_id,
+            limit_daily,
+            limit_monthly,
+            &spend,
+            enforce,
+        ))
+    }
+
+    /// 代理路由使用的限额判断
+    ///
+    /// 限额取自调用方已加载的供应商，消费取自内存中的窗口累计（见 [`ProviderSpend`]），
+    /// 缓存命中时不访问数据库。
+    pub fn check_provider_limits_cached(
+        &self,
+        provider: &Provider,
+        app_type: &str,
+    ) -> Result<ProviderLimitStatus, AppError> {
+        self.check_provider_limits_cached_at(
+            provider,
+            app_type,
+            &Local,
+            chrono::Utc::now().timestamp(),
+            crate::settings::enforce_provider_limits(),
+        )
+    }
+
+    fn check_provider_limits_cached_at<Tz: TimeZone>(
+        &self,
+        provider: &Provider,
+        app_type: &str,
+        tz: &Tz,
+        now_ts: i64,
+        enforce: bool,
+    ) -> Result<ProviderLimitStatus, AppError> {
+        let windows = SpendWindows::at(tz, now_ts)?;
+        let key = (provider.id.clone(), app_type.to_string());
+        let cached = self
+            .spend_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .filter(|spend| spend.is_fresh(&windows, now_ts))
+            .copied();
+        let spend = match cached {
+            Some(spend) => spend,
+            None => {
+                let conn = lock_conn!(self.conn);
+                self.reload_provider_spend(&conn, &provider.id, app_type, windows, now_ts)
+            }
+        };
+
+        let parse_limit = |limit: Option<&String>| limit.and_then(|s| s.parse::<f64>().ok());
+        let meta = provider.meta.as_ref();
+        Ok(limit_status(
+            &provider.id,
+            parse_limit(meta.and_then(|m| m.limit_daily_usd.as_ref())),
+            parse_limit(meta.and_then(|m| m.limit_monthly_usd.as_ref())),
+            &spend,
+            enforce,
+        ))
+    }
+
+    /// 从请求日志重新汇总当前日/月窗口的消费并写入缓存
+    ///
+    /// 调用方需持有连接锁，使汇总与 [`Self::record_provider_spend`] 的累加互斥。
+    fn reload_provider_spend(
+        &self,
+        conn: &Connection,
+        provider_id: &str,
+        app_type: &str,
+        windows: SpendWindows,
+        now_ts: i64,
+    ) -> ProviderSpend {
+        let usage_between = |(start, end): (i64, i64)| -> f64 {
+            conn.query_row(
                 "SELECT COALESCE(SUM(CAST(total_cost_usd AS REAL)), 0)
-             FROM proxy_request_logs
-             WHERE provider_id = ? AND app_type = ?
-               AND date(datetime(created_at, 'unixepoch', 'localtime')) = date('now', 'localtime')",
-                params![provider_id, app_type],
+                 FROM proxy_request_logs
+                 WHERE provider_id = ?1 AND app_type = ?2
+                   AND created_at >= ?3 AND created_at < ?4",
+                params![provider_id, app_type, start, end],
                 |row| row.get(0),
             )
-            .unwrap_or(0.0);
+            .unwrap_or(0.0)
+        };
+        let spend = ProviderSpend {
+            windows,
+            daily_usage: usage_between(windows.day),
+            monthly_usage: usage_between(windows.month),
+            loaded_at: now_ts,
+        };
+        self.spend_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((provider_id.to_string(), app_type.to_string()), spend);
+        spend
+    }
 
-        // 计算本月使用量
-        let monthly_usage: f64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(CAST(total_cost_usd AS REAL)), 0)
-             FROM proxy_request_logs
-             WHERE provider_id = ? AND app_type = ?
-               AND strftime('%Y-%m', datetime(created_at, 'unixepoch', 'localtime')) = strftime('%Y-%m', 'now', 'localtime')",
-                params![provider_id, app_type],
-                |row| row.get(0),
+    /// 请求日志写入后累加缓存中的窗口消费（未缓存的供应商下次判断时再汇总）
+    ///
+    /// 由请求日志写入方在持有连接锁时调用。
+    pub(crate) fn record_provider_spend(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        created_at: i64,
+        cost_usd: f64,
+    ) {
+        if cost_usd == 0.0 {
+            return;
+        }
+        let mut cache = self.spend_cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(spend) = cache.get_mut(&(provider_id.to_string(), app_type.to_string())) {
+            spend.add(created_at, cost_usd);
+        }
+    }
+
+    /// 清空消费缓存（删除请求日志后调用）
+    pub(crate) fn invalidate_provider_spend(&self) {
+        self.spend_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+/// 缓存的窗口消费最长有效期（秒）：到期后从请求日志重新汇总，
+/// 兜底覆盖导入、同步、成本回填等不经过请求日志写入方的变更
+const SPEND_CACHE_TTL_SECS: i64 = 60;
+
+/// 限额统计的本地日/月窗口（秒级时间戳，左闭右开）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SpendWindows {
+    day: (i64, i64),
+    month: (i64, i64),
+}
+
+impl SpendWindows {
+    fn at<Tz: TimeZone>(tz: &Tz, now_ts: i64) -> Result<Self, AppError> {
+        let window_error = || {
+            AppError::localized(
+                "usage.limit_window_invalid",
+                format!("无法计算限额统计窗口: {now_ts}"),
+                format!("Failed to compute the usage limit window: {now_ts}"),
+            )
+        };
+        Ok(Self {
+            day: limit_window(tz, TrendBucket::Day, now_ts).ok_or_else(window_error)?,
+            month: limit_window(tz, TrendBucket::Month, now_ts).ok_or_else(window_error)?,
+        })
+    }
+}
+
+/// 单个供应商在当前窗口内的累计消费（内存缓存，键为 provider_id + app_type）
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProviderSpend {
+    windows: SpendWindows,
+    daily_usage: f64,
+    monthly_usage: f64,
+    /// 汇总时间（秒级时间戳）
+    loaded_at: i64,
+}
+
+impl ProviderSpend {
+    fn is_fresh(&self, windows: &SpendWindows, now_ts: i64) -> bool {
+        self.windows == *windows && now_ts - self.loaded_at < SPEND_CACHE_TTL_SECS
+    }
+
+    fn add(&mut self, created_at: i64, cost_usd: f64) {
+        let within = |(start, end): (i64, i64)| created_at >= start && created_at < end;
+        if within(self.windows.day) {
+            self.daily_usage += cost_usd;
+        }
+        if within(self.windows.month) {
+            self.monthly_usage += cost_usd;
+        }
+    }
+}
+
+fn limit_status(
+    provider_id: &str,
+    limit_daily: Option<f64>,
+    limit_monthly: Option<f64>,
+    spend: &ProviderSpend,
+    enforce: bool,
+) -> ProviderLimitStatus {
+    let daily_exceeded = limit_daily
+        .map(|limit| spend.daily_usage >= limit)
+        .unwrap_or(false);
+    let monthly_exceeded = limit_monthly
+        .map(|limit| spend.monthly_usage >= limit)
+        .unwrap_or(false);
+
+    let daily_resets_at = spend.windows.day.1 * 1000;
+    let monthly_resets_at = spend.windows.month.1 * 1000;
+    let limit_tripped = enforce && (daily_exceeded || monthly_exceeded);
+    let tripped_until = limit_tripped.then(|| {
+        if monthly_exceeded {
+            monthly_resets_at
+        } else {
+            daily_resets_at
+        }
+    });
+
+    ProviderLimitStatus {
+        provider_id: provider_id.to_string(),
+        daily_usage: format!("{:.6}", spend.daily_usage),
+        daily_limit: limit_daily.map(|l| format!("{l:.2}")),
+        daily_exceeded,
+        monthly_usage: format!("{:.6}", spend.monthly_usage),
+        monthly_limit: limit_monthly.map(|l| format!("{l:.2}")),
+        monthly_exceeded,
+        daily_resets_at,
+        monthly_resets_at,
+        limit_tripped,
+        tripped_until,
+    }
+}
+
+/// 请求成本预估（各项均为完整精度的十进制字符串）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+    pub model_id: String,
+    /// 是否找到模型定价；未找到时各项成本为 0
+    pub pricing_found: bool,
+    pub cost_multiplier: String,
+    pub input_cost: String,
+    pub output_cost: String,
+    pub cache_read_cost: String,
+    pub cache_creation_cost: String,
+    pub total_cost: String,
+}
+
+impl Database {
+    /// 预估请求成本
+    ///
+    /// 定价查找（含模型名清洗）与计算逻辑与代理记录 `*_cost_usd` 时完全相同，
+    /// 只读取 model_pricing，不写入任何数据。
+    pub fn estimate_cost(
+        &self,
+        model_id: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        cache_read_tokens: u64,
+        cache_creation_tokens: u64,
+        cost_multiplier: rust_decimal::Decimal,
+    ) -> Result<CostEstimate, AppError> {
+        let pricing = {
+            let conn = lock_conn!(self.conn);
+            load_model_pricing(&conn, model_id)?
+        };
+
+        let cost = pricing.as_ref().map(|pricing| {
+            CostCalculator::calculate_tokens(
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_creation_tokens,
+                pricing,
+                cost_multiplier,
             )
-            .unwrap_or(0.0);
-
-        let daily_exceeded = limit_daily
-            .map(|limit| daily_usage >= limit)
-            .unwrap_or(false);
-        let monthly_exceeded = limit_monthly
-            .map(|limit| monthly_usage >= limit)
-            .unwrap_or(false);
-
-        Ok(ProviderLimitStatus {
-            provider_id: provider_id.to_string(),
-            daily_usage: format!("{daily_usage:.6}"),
-            daily_limit: limit_daily.map(|l| format!("{l:.2}")),
-            daily_exceeded,
-            monthly_usage: format!("{monthly_usage:.6}"),
-            monthly_limit: limit_monthly.map(|l| format!("{l:.2}")),
-            monthly_exceeded,
+        });
+        let field = |get: fn(&CostBreakdown) -> rust_decimal::Decimal| {
+            cost.as_ref()
+                .map(|c| get(c).normalize().to_string())
+                .unwrap_or_else(|| "0".to_string())
+        };
+
+        Ok(CostEstimate {
+            model_id: model_id.to_string(),
+            pricing_found: cost.is_some(),
+            cost_multiplier: cost_multiplier.normalize().to_string(),
+            input_cost: field(|c| c.input_cost),
+            output_cost: field(|c| c.output_cost),
+            cache_read_cost: field(|c| c.cache_read_cost),
+            cache_creation_cost: field(|c| c.cache_creation_cost),
+            total_cost: field(|c| c.total_cost),
         })
     }
 }
@@ -661,14 +1266,15 @@ pub struct ProviderLimitStatus {
     pub monthly_usage: String,
     pub monthly_limit: Option<String>,
     pub monthly_exceeded: bool,
-}
-
-#[derive(Clone)]
-struct PricingInfo {
-    input: rust_decimal::Decimal,
-    output: rust_decimal::Decimal,
-    cache_read: rust_decimal::Decimal,
-    cache_creation: rust_decimal::Decimal,
+    /// 今日窗口重置时间（次日本地零点，毫秒时间戳）
+    pub daily_resets_at: i64,
+    /// 本月窗口重置时间（次月 1 日本地零点，毫秒时间戳）
+    pub monthly_resets_at: i64,
+    /// 已开启限额强制且超出限额：代理故障转移时跳过该供应商
+    pub limit_tripped: bool,
+    /// 限额触发后恢复可用的时间（毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tripped_until: Option<i64>,
 }
 
 impl Database {
@@ -676,7 +1282,7 @@ impl Database {
         conn: &Connection,
         log: &mut RequestLogDetail,
         provider_cache: &mut HashMap<(String, String), rust_decimal::Decimal>,
-        pricing_cache: &mut HashMap<String, PricingInfo>,
+        pricing_cache: &mut HashMap<String, ModelPricing>,
     ) -> Result<(), AppError> {
         let total_cost = rust_decimal::Decimal::from_str(&log.total_cost_usd)
             .unwrap_or(rust_decimal::Decimal::ZERO);
@@ -694,40 +1300,31 @@ impl Database {
             Some(info) => info,
             None => return Ok(()),
         };
-        let multiplier = Self::get_cost_multiplier_cached(
-            conn,
-            provider_cache,
-            &log.provider_id,
-            &log.app_type,
-        )?;
+        // 优先使用该行记录时保存的倍率：之后修改供应商倍率不会追溯影响历史记录
+        let multiplier = match rust_decimal::Decimal::from_str(&log.cost_multiplier) {
+            Ok(value) => value,
+            Err(_) => Self::get_cost_multiplier_cached(
+                conn,
+                provider_cache,
+                &log.provider_id,
+                &log.app_type,
+            )?,
+        };
 
-        let million = rust_decimal::Decimal::from(1_000_000u64);
-
-        // 与 CostCalculator::calculate 保持一致的计算逻辑：
-        // 1. input_cost 需要扣除 cache_read_tokens（避免缓存部分被重复计费）
-        // 2. 各项成本是基础成本（不含倍率）
-        // 3. 倍率只作用于最终总价
-        let billable_input_tokens =
-            (log.input_tokens as u64).saturating_sub(log.cache_read_tokens as u64);
-        let input_cost =
-            rust_decimal::Decimal::from(billable_input_tokens) * pricing.input / million;
-        let output_cost =
-            rust_decimal::Decimal::from(log.output_tokens as u64) * pricing.output / million;
-        let cache_read_cost = rust_decimal::Decimal::from(log.cache_read_tokens as u64)
-            * pricing.cache_read
-            / million;
-        let cache_creation_cost = rust_decimal::Decimal::from(log.cache_creation_tokens as u64)
-            * pricing.cache_creation
-            / million;
-        // 总成本 = 基础成本之和 × 倍率
-        let base_total = input_cost + output_cost + cache_read_cost + cache_creation_cost;
-        let total_cost = base_total * multiplier;
-
-        log.input_cost_usd = format!("{input_cost:.6}");
-        log.output_cost_usd = format!("{output_cost:.6}");
-        log.cache_read_cost_usd = format!("{cache_read_cost:.6}");
-        log.cache_creation_cost_usd = format!("{cache_creation_cost:.6}");
-        log.total_cost_usd = format!("{total_cost:.6}");
+        let cost = CostCalculator::calculate_tokens(
+            log.input_tokens as u64,
+            log.output_tokens as u64,
+            log.cache_read_tokens as u64,
+            log.cache_creation_tokens as u64,
+            &pricing,
+            multiplier,
+        );
+
+        log.input_cost_usd = format!("{:.6}", cost.input_cost);
+        log.output_cost_usd = format!("{:.6}", cost.output_cost);
+        log.cache_read_cost_usd = format!("{:.6}", cost.cache_read_cost);
+        log.cache_creation_cost_usd = format!("{:.6}", cost.cache_creation_cost);
+        log.total_cost_usd = format!("{:.6}", cost.total_cost);
 
         conn.execute(
             "UPDATE proxy_request_logs
@@ -771,14 +1368,28 @@ impl Database {
             .optional()
             .map_err(|e| AppError::Database(format!("查询 provider meta 失败: {e}")))?;
 
-        let multiplier = meta_json
+        let provider_multiplier = meta_json
             .and_then(|meta| serde_json::from_str::<Value>(&meta).ok())
             .and_then(|value| value.get("costMultiplier").cloned())
             .and_then(|val| {
                 val.as_str()
                     .and_then(|s| rust_decimal::Decimal::from_str(s).ok())
-            })
-            .unwrap_or(rust_decimal::Decimal::ONE);
+            });
+
+        // 供应商未单独设置时回退到应用默认倍率
+        let multiplier = match provider_multiplier {
+            Some(value) => value,
+            None => conn
+                .query_row(
+                    "SELECT default_cost_multiplier FROM proxy_config WHERE app_type = ?",
+                    params![app_type],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(|e| AppError::Database(format!("查询默认倍率失败: {e}")))?
+                .and_then(|raw| rust_decimal::Decimal::from_str(raw.trim()).ok())
+                .unwrap_or(rust_decimal::Decimal::ONE),
+        };
 
         cache.insert(key, multiplier);
         Ok(multiplier)
@@ -786,34 +1397,36 @@ impl Database {
 
     fn get_model_pricing_cached(
         conn: &Connection,
-        cache: &mut HashMap<String, PricingInfo>,
+        cache: &mut HashMap<String, ModelPricing>,
         model: &str,
-    ) -> Result<Option<PricingInfo>, AppError> {
+    ) -> Result<Option<ModelPricing>, AppError> {
         if let Some(info) = cache.get(model) {
             return Ok(Some(info.clone()));
         }
 
-        let row = find_model_pricing_row(conn, model)?;
-        let Some((input, output, cache_read, cache_creation)) = row else {
+        let Some(pricing) = load_model_pricing(conn, model)? else {
             return Ok(None);
         };
 
-        let pricing = PricingInfo {
-            input: rust_decimal::Decimal::from_str(&input)
-                .map_err(|e| AppError::Database(format!("解析输入价格失败: {e}")))?,
-            output: rust_decimal::Decimal::from_str(&output)
-                .map_err(|e| AppError::Database(format!("解析输出价格失败: {e}")))?,
-            cache_read: rust_decimal::Decimal::from_str(&cache_read)
-                .map_err(|e| AppError::Database(format!("解析缓存读取价格失败: {e}")))?,
-            cache_creation: rust_decimal::Decimal::from_str(&cache_creation)
-                .map_err(|e| AppError::Database(format!("解析缓存写入价格失败: {e}")))?,
-        };
-
         cache.insert(model.to_string(), pricing.clone());
         Ok(Some(pricing))
     }
 }
 
+/// 查找并解析模型定价（模型名按 [`find_model_pricing_row`] 的规则清洗）
+pub(crate) fn load_model_pricing(
+    conn: &Connection,
+    model_id: &str,
+) -> Result<Option<ModelPricing>, AppError> {
+    let Some((input, output, cache_read, cache_creation)) = find_model_pricing_row(conn, model_id)?
+    else {
+        return Ok(None);
+    };
+    ModelPricing::from_strings(&input, &output, &cache_read, &cache_creation)
+        .map(Some)
+        .map_err(|e| AppError::Database(format!("解析定价数据失败: {e}")))
+}
+
 pub(crate) fn find_model_pricing_row(
     conn: &Connection,
     model_id: &str,
@@ -856,9 +1469,268 @@ pub(crate) fn find_model_pricing_row(
     Ok(exact)
 }
 
+/// 本地日期零点对应的时刻；零点因夏令时不存在时取当天第一个存在的整点
+fn local_day_start<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> Option<DateTime<Tz>> {
+    (0..24).find_map(|hour| {
+        tz.from_local_datetime(&date.and_hms_opt(hour, 0, 0)?)
+            .earliest()
+    })
+}
+
+/// 时间戳所在分桶的起始时刻
+fn floor_to_bucket<Tz: TimeZone>(tz: &Tz, bucket: TrendBucket, ts: i64) -> Option<DateTime<Tz>> {
+    let local = tz.timestamp_opt(ts, 0).single()?;
+    let date = local.date_naive();
+    match bucket {
+        TrendBucket::Hour => {
+            let into_hour = i64::from(local.minute() * 60 + local.second());
+            tz.timestamp_opt(ts - into_hour, 0).single()
+        }
+        TrendBucket::Day => local_day_start(tz, date),
+        TrendBucket::Week => {
+            let since_monday = u64::from(date.weekday().num_days_from_monday());
+            local_day_start(tz, date.checked_sub_days(Days::new(since_monday))?)
+        }
+        TrendBucket::Month => local_day_start(tz, date.with_day(1)?),
+    }
+}
+
+/// 下一个分桶的起始时刻
+fn next_bucket<Tz: TimeZone>(
+    tz: &Tz,
+    bucket: TrendBucket,
+    start: &DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    let date = start.date_naive();
+    match bucket {
+        // 整点之间总是相隔 3600 秒，回拨时重复的本地小时自然成为两个桶
+        TrendBucket::Hour => tz.timestamp_opt(start.timestamp() + 60 * 60, 0).single(),
+        TrendBucket::Day => local_day_start(tz, date.succ_opt()?),
+        TrendBucket::Week => local_day_start(tz, date.checked_add_days(Days::new(7))?),
+        TrendBucket::Month => local_day_start(tz, date.checked_add_months(Months::new(1))?),
+    }
+}
+
+/// 限额统计窗口 [start, end)（Unix 秒）：当日为本地零点至次日零点，当月为 1 日至次月 1 日
+fn limit_window<Tz: TimeZone>(tz: &Tz, bucket: TrendBucket, now_ts: i64) -> Option<(i64, i64)> {
+    let start = floor_to_bucket(tz, bucket, now_ts)?;
+    let end = next_bucket(tz, bucket, &start)?;
+    Some((start.timestamp(), end.timestamp()))
+}
+
+/// 覆盖 [start_ts, end_ts) 的所有分桶起始时刻（第一个分桶可能早于 start_ts）
+fn trend_bucket_starts<Tz: TimeZone>(
+    tz: &Tz,
+    bucket: TrendBucket,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<Vec<DateTime<Tz>>, AppError> {
+    let invalid_range = || {
+        AppError::localized(
+            "usage.trend_range_invalid",
+            format!("无效的统计时间范围: {start_ts} - {end_ts}"),
+            format!("Invalid usage trend range: {start_ts} - {end_ts}"),
+        )
+    };
+    if start_ts >= end_ts {
+        return Err(invalid_range());
+    }
+
+    let mut current = floor_to_bucket(tz, bucket, start_ts).ok_or_else(invalid_range)?;
+    let mut starts = Vec::new();
+    while current.timestamp() < end_ts {
+        if starts.len() >= MAX_TREND_BUCKETS {
+            return Err(AppError::localized(
+                "usage.trend_too_many_buckets",
+                format!("统计范围过大：最多 {MAX_TREND_BUCKETS} 个分桶，请缩小范围或使用更大的粒度"),
+                format!(
+                    "Range too large: at most {MAX_TREND_BUCKETS} buckets, narrow the range or use a coarser bucket"
+                ),
+            ));
+        }
+        let next = next_bucket(tz, bucket, &current).ok_or_else(invalid_range)?;
+        starts.push(current);
+        current = next;
+    }
+    Ok(starts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{FixedOffset, LocalResult, NaiveDateTime, Offset, Utc};
+
+    /// 测试用时区：2024 年美国东部时间（3 月 10 日 02:00 进入夏令时，11 月 3 日 02:00 结束）
+    #[derive(Debug, Clone, Copy)]
+    struct Eastern2024;
+
+    impl Eastern2024 {
+        fn offset_at_utc(utc: &NaiveDateTime) -> FixedOffset {
+            let dst_start = Utc
+                .with_ymd_and_hms(2024, 3, 10, 7, 0, 0)
+                .unwrap()
+                .naive_utc();
+            let dst_end = Utc
+                .with_ymd_and_hms(2024, 11, 3, 6, 0, 0)
+                .unwrap()
+                .naive_utc();
+            let hours = if (dst_start..dst_end).contains(utc) {
+                -4
+            } else {
+                -5
+            };
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    impl TimeZone for Eastern2024 {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Eastern2024
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let valid: Vec<FixedOffset> = [-4, -5]
+                .into_iter()
+                .map(|hours| FixedOffset::east_opt(hours * 3600).unwrap())
+                .filter(|offset| {
+                    let utc = *local - offset.fix();
+                    Self::offset_at_utc(&utc) == *offset
+                })
+                .collect();
+            match valid.as_slice() {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(*offset),
+                [earlier, later, ..] => LocalResult::Ambiguous(*earlier, *later),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Self::offset_at_utc(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset_at_utc(utc)
+        }
+    }
+
+    fn eastern_ts(y: i32, m: u32, d: u32, h: u32) -> i64 {
+        Eastern2024
+            .with_ymd_and_hms(y, m, d, h, 0, 0)
+            .earliest()
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn trend_buckets_follow_local_calendar_across_dst() -> Result<(), AppError> {
+        let tz = Eastern2024;
+
+        // 春季跳过 02:00：3 月 10 日的日桶只有 23 小时
+        let days = trend_bucket_starts(
+            &tz,
+            TrendBucket::Day,
+            eastern_ts(2024, 3, 9, 12),
+            eastern_ts(2024, 3, 12, 0),
+        )?;
+        let lengths: Vec<i64> = days
+            .windows(2)
+            .map(|w| w[1].timestamp() - w[0].timestamp())
+            .collect();
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0].hour(), 0);
+        assert_eq!(lengths, vec![24 * 3600, 23 * 3600]);
+
+        // 秋季回拨：本地 01:00 出现两次，是两个独立的小时桶
+        let hours = trend_bucket_starts(
+            &tz,
+            TrendBucket::Hour,
+            eastern_ts(2024, 11, 3, 0),
+            eastern_ts(2024, 11, 3, 3),
+        )?;
+        let local_hours: Vec<u32> = hours.iter().map(|h| h.hour()).collect();
+        assert_eq!(local_hours, vec![0, 1, 1, 2]);
+
+        // 周从周一开始，月从 1 日开始
+        let weeks = trend_bucket_starts(
+            &tz,
+            TrendBucket::Week,
+            eastern_ts(2024, 11, 6, 9),
+            eastern_ts(2024, 11, 7, 0),
+        )?;
+        assert_eq!(
+            weeks[0].date_naive(),
+            NaiveDate::from_ymd_opt(2024, 11, 4).unwrap()
+        );
+        let months = trend_bucket_starts(
+            &tz,
+            TrendBucket::Month,
+            eastern_ts(2024, 1, 15, 0),
+            eastern_ts(2024, 4, 1, 0),
+        )?;
+        let month_numbers: Vec<u32> = months.iter().map(|m| m.month()).collect();
+        assert_eq!(month_numbers, vec![1, 2, 3]);
+
+        assert!(trend_bucket_starts(&tz, TrendBucket::Day, 10, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_usage_trends_bucketed_by_local_day() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let day_start = Local
+            .with_ymd_and_hms(2024, 1, 10, 0, 0, 0)
+            .earliest()
+            .expect("local midnight")
+            .timestamp();
+
+        {
+            let conn = lock_conn!(db.conn);
+            for (id, offset, cost) in [
+                ("req1", 3600, "0.01"),
+                ("req2", 20 * 3600, "0.02"),
+                ("req3", 30 * 3600, "0.04"),
+            ] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        input_tokens, output_tokens, total_cost_usd,
+                        latency_ms, status_code, created_at
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        "p1",
+                        "claude",
+                        "claude-3",
+                        100,
+                        50,
+                        cost,
+                        100,
+                        200,
+                        day_start + offset
+                    ],
+                )?;
+            }
+        }
+
+        let end = Local
+            .with_ymd_and_hms(2024, 1, 12, 0, 0, 0)
+            .earliest()
+            .expect("local midnight")
+            .timestamp();
+        let trends = db.get_usage_trends_bucketed(TrendBucket::Day, Some(day_start), Some(end))?;
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[0].request_count, 2);
+        assert_eq!(trends[0].total_tokens, 300);
+        assert_eq!(trends[0].total_cost, "0.030000");
+        assert_eq!(trends[1].request_count, 1);
+        Ok(())
+    }
 
     #[test]
     fn test_get_usage_summary() -> Result<(), AppError> {
@@ -892,6 +1764,217 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_replayed_requests_are_excluded_from_stats() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            for (id, status, replay_of) in [("req1", 500, None), ("req1-replay", 200, Some("req1"))]
+            {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model, total_cost_usd,
+                        latency_ms, status_code, created_at, replay_of
+                    ) VALUES (?, 'p1', 'claude', 'claude-3', '0.01', 100, ?, 1000, ?)",
+                    params![id, status, replay_of],
+                )?;
+            }
+        }
+
+        let summary = db.get_usage_summary(None, None)?;
+        assert_eq!(summary.total_requests, 1);
+        assert_eq!(summary.success_rate, 0.0);
+        assert_eq!(db.get_provider_stats(None, None)?[0].request_count, 1);
+        assert_eq!(db.get_model_stats()?[0].request_count, 1);
+
+        // 请求日志列表仍展示重放记录
+        let logs = db.get_request_logs(&LogFilters::default(), 0, 10)?;
+        assert_eq!(logs.total, 2);
+        let replay = logs
+            .data
+            .iter()
+            .find(|log| log.request_id == "req1-replay")
+            .expect("replay listed");
+        assert_eq!(replay.replay_of.as_deref(), Some("req1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_provider_stats_filters_by_date_range() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            let rows = [
+                // (id, output_tokens, cost, latency, status, created_at)
+                ("r1", 100, "0.01", 100, 200, 1000),
+                ("r2", 200, "0.02", 300, 500, 2000),
+                ("r3", 300, "0.03", 500, 200, 3000),
+            ];
+            for (id, output, cost, latency, status, created_at) in rows {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model, output_tokens,
+                        total_cost_usd, latency_ms, status_code, created_at
+                    ) VALUES (?, 'p1', 'claude', 'claude-3', ?, ?, ?, ?, ?)",
+                    params![id, output, cost, latency, status, created_at],
+                )?;
+            }
+        }
+
+        let all = db.get_provider_stats(None, None)?;
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].request_count, 3);
+        assert_eq!(all[0].total_tokens, 600);
+
+        let ranged = db.get_provider_stats(Some(1500), Some(3000))?;
+        assert_eq!(ranged[0].request_count, 2);
+        assert_eq!(ranged[0].total_tokens, 500);
+        assert_eq!(ranged[0].total_cost, "0.050000");
+        assert_eq!(ranged[0].error_rate, 50.0);
+        assert_eq!(ranged[0].avg_latency_ms, 400);
+
+        assert!(db.get_provider_stats(Some(4000), None)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_provider_cost_comparison_sorts_by_effective_cost() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            let rows = [
+                // (id, provider, model, output_tokens, cost, multiplier, status)
+                ("r1", "cheap", "claude-sonnet", 1000, "0.002", "0.5", 200),
+                ("r2", "cheap", "claude-sonnet", 0, "0", "0.5", 502),
+                ("r3", "pricey", "claude-sonnet", 1000, "0.009", "1.5", 200),
+                // 缺少定价：有 token 但成本为 0，不能当作免费
+                ("r4", "unknown", "claude-sonnet", 1000, "0", "1", 200),
+                ("r5", "cheap", "gpt-5", 1000, "0.001", "1", 200),
+            ];
+            for (id, provider, model, output, cost, multiplier, status) in rows {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model, output_tokens,
+                        total_cost_usd, cost_multiplier, latency_ms, status_code, created_at
+                    ) VALUES (?, ?, 'claude', ?, ?, ?, ?, 100, ?, 1000)",
+                    params![id, provider, model, output, cost, multiplier, status],
+                )?;
+            }
+        }
+
+        let stats = db.get_provider_cost_comparison(Some("claude"), Some("sonnet"), None, None)?;
+        let order: Vec<_> = stats.iter().map(|s| s.provider_id.as_str()).collect();
+        assert_eq!(order, vec!["cheap", "pricey", "unknown"]);
+
+        assert_eq!(stats[0].cost_per_1k_output.as_deref(), Some("0.002000"));
+        assert_eq!(
+            stats[0].base_cost_per_1k_output.as_deref(),
+            Some("0.004000")
+        );
+        assert_eq!(stats[0].error_rate, 50.0);
+        assert_eq!(stats[2].unpriced_requests, 1);
+        assert!(stats[2].cost_per_1k_output.is_none());
+
+        assert!(db
+            .get_provider_cost_comparison(Some("codex"), None, None, None)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_request_detail_includes_captured_bodies() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model,
+                    latency_ms, status_code, error_message, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    "req-fail",
+                    "p1",
+                    "claude",
+                    "claude-3",
+                    100,
+                    400,
+                    "bad request",
+                    1000
+                ],
+            )?;
+        }
+        db.save_request_bodies(
+            "req-fail",
+            Some("/v1/messages"),
+            Some(r#"{"authorization":"[REDACTED]"}"#),
+            Some(r#"{"model":"claude-3"}"#),
+            Some(r#"{"error":"invalid"}"#),
+            true,
+        )?;
+
+        let detail = db.get_request_detail("req-fail")?.expect("detail exists");
+        let bodies = detail.bodies.expect("bodies captured");
+        assert_eq!(
+            bodies.response_body.as_deref(),
+            Some(r#"{"error":"invalid"}"#)
+        );
+        assert!(bodies.truncated);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_logs_expose_attempt_timeline() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model,
+                    latency_ms, status_code, created_at, attempts
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    "req-failover",
+                    "p2",
+                    "claude",
+                    "claude-3",
+                    900,
+                    200,
+                    1000,
+                    r#"[{"providerId":"p1","kind":"initial","startOffsetMs":0,"durationMs":400,"statusCode":503},{"providerId":"p2","kind":"failover","startOffsetMs":410,"durationMs":480,"statusCode":200}]"#
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model,
+                    latency_ms, status_code, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params!["req-legacy", "p1", "claude", "claude-3", 100, 200, 500],
+            )?;
+        }
+
+        let logs = db.get_request_logs(&LogFilters::default(), 0, 10)?;
+        let count_of = |id: &str| {
+            logs.data
+                .iter()
+                .find(|l| l.request_id == id)
+                .expect("log")
+                .attempt_count
+        };
+        assert_eq!(count_of("req-failover"), 2);
+        assert_eq!(count_of("req-legacy"), 0);
+        assert!(logs.data.iter().all(|l| l.attempts.is_none()));
+
+        let detail = db.get_request_detail("req-failover")?.expect("detail");
+        let attempts = detail.attempts.expect("attempts");
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[1].provider_id, "p2");
+        assert_eq!(attempts[1].kind, crate::proxy::types::AttemptKind::Failover);
+        assert_eq!(attempts[0].status_code, Some(503));
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_model_stats() -> Result<(), AppError> {
         let db = Database::memory()?;
@@ -981,4 +2064,214 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_estimate_cost_uses_normalized_pricing() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT OR REPLACE INTO model_pricing (
+                    model_id, display_name, input_cost_per_million, output_cost_per_million,
+                    cache_read_cost_per_million, cache_creation_cost_per_million
+                ) VALUES ('estimate-model', 'Estimate Model', '3', '15', '0.3', '3.75')",
+                [],
+            )?;
+        }
+
+        let multiplier = rust_decimal::Decimal::from_str("1.5").unwrap();
+        let estimate = db.estimate_cost(
+            "vendor/estimate-model:beta",
+            1000,
+            500,
+            200,
+            100,
+            multiplier,
+        )?;
+        assert!(estimate.pricing_found);
+        assert_eq!(estimate.input_cost, "0.0024");
+        assert_eq!(estimate.output_cost, "0.0075");
+        assert_eq!(estimate.cache_read_cost, "0.00006");
+        assert_eq!(estimate.cache_creation_cost, "0.000375");
+        // (0.0024 + 0.0075 + 0.00006 + 0.000375) × 1.5
+        assert_eq!(estimate.total_cost, "0.0155025");
+        assert_eq!(estimate.cost_multiplier, "1.5");
+
+        let unknown = db.estimate_cost("no-such-model", 1000, 0, 0, 0, multiplier)?;
+        assert!(!unknown.pricing_found);
+        assert_eq!(unknown.total_cost, "0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_backfill_prefers_logged_cost_multiplier() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT OR REPLACE INTO model_pricing (
+                    model_id, display_name, input_cost_per_million, output_cost_per_million,
+                    cache_read_cost_per_million, cache_creation_cost_per_million
+                ) VALUES (?, ?, ?, ?, ?, ?)",
+                params!["test-model", "Test Model", "1.0", "0.0", "0.0", "0.0"],
+            )?;
+            conn.execute(
+                "INSERT INTO providers (id, app_type, name, settings_config, meta)
+                 VALUES (?, ?, ?, ?, ?)",
+                params!["p1", "claude", "Relay", "{}", r#"{"costMultiplier":"3"}"#],
+            )?;
+            // 记录时倍率为 2，之后供应商倍率改为 3
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, input_tokens,
+                    total_cost_usd, cost_multiplier, latency_ms, status_code, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    "req-logged",
+                    "p1",
+                    "claude",
+                    "test-model",
+                    1_000_000,
+                    "0",
+                    "2",
+                    100,
+                    200,
+                    2000
+                ],
+            )?;
+            // 无法解析的倍率回退到供应商当前倍率
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, input_tokens,
+                    total_cost_usd, cost_multiplier, latency_ms, status_code, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    "req-invalid",
+                    "p1",
+                    "claude",
+                    "test-model",
+                    1_000_000,
+                    "0",
+                    "n/a",
+                    100,
+                    200,
+                    1000
+                ],
+            )?;
+        }
+
+        let logs = db.get_request_logs(&LogFilters::default(), 0, 10)?;
+        let total_of = |id: &str| {
+            let log = logs.data.iter().find(|l| l.request_id == id).expect("log");
+            rust_decimal::Decimal::from_str(&log.total_cost_usd).expect("decimal")
+        };
+        assert_eq!(total_of("req-logged"), rust_decimal::Decimal::from(2));
+        assert_eq!(total_of("req-invalid"), rust_decimal::Decimal::from(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn provider_limits_trip_until_local_window_resets() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let tz = Eastern2024;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO providers (id, app_type, name, settings_config, meta)
+                 VALUES ('p1', 'claude', 'P1', '{}', ?1)",
+                params![r#"{"limitDailyUsd":"1","limitMonthlyUsd":"10"}"#],
+            )?;
+            // 前一天 23 点的 5 美元只计入本月；当天 1 点的 2 美元计入当日
+            for (id, ts, cost) in [
+                ("r1", eastern_ts(2024, 6, 14, 23), "5"),
+                ("r2", eastern_ts(2024, 6, 15, 1), "2"),
+                ("r3", eastern_ts(2024, 5, 31, 12), "100"),
+            ] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        input_tokens, output_tokens, total_cost_usd,
+                        latency_ms, status_code, created_at
+                    ) VALUES (?1, 'p1', 'claude', 'm', 1, 1, ?2, 1, 200, ?3)",
+                    params![id, cost, ts],
+                )?;
+            }
+        }
+
+        let now = eastern_ts(2024, 6, 15, 12);
+        let status = db.check_provider_limits_at("p1", "claude", &tz, now, false)?;
+        assert!(status.daily_exceeded);
+        assert!(!status.monthly_exceeded);
+        assert_eq!(status.monthly_usage, "7.000000");
+        assert!(
+            !status.limit_tripped,
+            "reporting only when enforcement is off"
+        );
+        assert_eq!(status.daily_resets_at, eastern_ts(2024, 6, 16, 0) * 1000);
+        assert_eq!(status.monthly_resets_at, eastern_ts(2024, 7, 1, 0) * 1000);
+
+        let status = db.check_provider_limits_at("p1", "claude", &tz, now, true)?;
+        assert!(status.limit_tripped);
+        assert_eq!(status.tripped_until, Some(status.daily_resets_at));
+
+        // 次日零点后当日窗口重置
+        let next_day = eastern_ts(2024, 6, 16, 0);
+        let status = db.check_provider_limits_at("p1", "claude", &tz, next_day, true)?;
+        assert!(!status.daily_exceeded);
+        assert!(!status.limit_tripped);
+        assert_eq!(status.tripped_until, None);
+        Ok(())
+    }
+
+    #[test]
+    fn cached_provider_limits_follow_logged_spend_without_requerying() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let tz = Eastern2024;
+        let mut provider = Provider::with_id("p1".into(), "P1".into(), serde_json::json!({}), None);
+        provider.meta = Some(crate::provider::ProviderMeta {
+            limit_daily_usd: Some("1".into()),
+            ..Default::default()
+        });
+        let insert_log = |id: &str, ts: i64, cost: &str| -> Result<(), AppError> {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model,
+                    input_tokens, output_tokens, total_cost_usd,
+                    latency_ms, status_code, created_at
+                ) VALUES (?1, 'p1', 'claude', 'm', 1, 1, ?2, 1, 200, ?3)",
+                params![id, cost, ts],
+            )?;
+            Ok(())
+        };
+
+        let now = eastern_ts(2024, 6, 15, 12);
+        let status = db.check_provider_limits_cached_at(&provider, "claude", &tz, now, true)?;
+        assert!(!status.limit_tripped);
+
+        // 绕过请求日志写入方插入的行不会立即反映到缓存
+        insert_log("r1", now, "5")?;
+        let status = db.check_provider_limits_cached_at(&provider, "claude", &tz, now, true)?;
+        assert!(!status.limit_tripped, "cache hit should not requery logs");
+
+        // 写入方累加后立即生效
+        db.record_provider_spend("p1", "claude", now, 5.0);
+        let status = db.check_provider_limits_cached_at(&provider, "claude", &tz, now, true)?;
+        assert!(status.limit_tripped);
+        assert_eq!(status.daily_usage, "5.000000");
+
+        // 缓存过期后从日志重新汇总
+        let later = now + SPEND_CACHE_TTL_SECS;
+        let status = db.check_provider_limits_cached_at(&provider, "claude", &tz, later, true)?;
+        assert_eq!(status.daily_usage, "5.000000");
+
+        // 跨日后窗口变化，按新窗口汇总
+        let next_day = eastern_ts(2024, 6, 16, 0);
+        let status =
+            db.check_provider_limits_cached_at(&provider, "claude", &tz, next_day, true)?;
+        assert!(!status.limit_tripped);
+        assert_eq!(status.daily_usage, "0.000000");
+        Ok(())
+    }
 }
@@ -15,12 +15,29 @@ pub struct CustomEndpoint {
     pub added_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_used: Option<i64>,
+    /// 最近一次测速延迟（毫秒，读取时由测速历史填充）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_latency: Option<u64>,
+    /// 最近一次测速时间（毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_tested_at: Option<i64>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Claude Live 配置写入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeWriteMode {
+    /// 切换时整体重写 settings.json（默认）
+    #[default]
+    Replace,
+    /// 每个供应商写入 `profiles/<id>.json`，settings.json 中的供应商 env 与模型键被移除
+    Profiles,
+}
+
 /// 主页面显示的应用配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,6 +75,44 @@ impl VisibleApps {
     }
 }
 
+/// 由 CC Switch 管理 MCP 的应用
+///
+/// 未管理的应用，其 Live 配置中的 MCP 条目既不会被写入也不会被删除。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpManagedApps {
+    #[serde(default = "default_true")]
+    pub claude: bool,
+    #[serde(default = "default_true")]
+    pub codex: bool,
+    #[serde(default = "default_true")]
+    pub gemini: bool,
+    #[serde(default = "default_true")]
+    pub opencode: bool,
+}
+
+impl Default for McpManagedApps {
+    fn default() -> Self {
+        Self {
+            claude: true,
+            codex: true,
+            gemini: true,
+            opencode: true,
+        }
+    }
+}
+
+impl McpManagedApps {
+    pub fn is_managed(&self, app: &AppType) -> bool {
+        match app {
+            AppType::Claude => self.claude,
+            AppType::Codex => self.codex,
+            AppType::Gemini => self.gemini,
+            AppType::OpenCode => self.opencode,
+        }
+    }
+}
+
 /// 应用设置结构
 ///
 /// 存储设备级别设置，保存在本地 `~/.cc-switch/settings.json`，不随数据库同步。
@@ -82,13 +137,29 @@ pub struct AppSettings {
     /// 静默启动（程序启动时不显示主窗口，仅托盘运行）
     #[serde(default)]
     pub silent_startup: bool,
+    /// 启动延迟（秒）：恢复代理状态等依赖网络的初始化前先等待，避免与其他开机启动程序竞争
+    #[serde(default)]
+    pub startup_delay_seconds: u32,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    /// 切换供应商前先进行健康检查，失败时拒绝切换
+    #[serde(default)]
+    pub verify_before_switch: bool,
+    /// 只读模式：禁止写入任何 Live 配置文件（仅数据库操作生效），用于演示或调试
+    #[serde(default)]
+    pub read_only_mode: bool,
+    /// 强制执行供应商消费限额：超出每日/每月限额后，代理故障转移时跳过该供应商直到窗口重置
+    #[serde(default)]
+    pub enforce_provider_limits: bool,
 
     // ===== 主页面显示的应用 =====
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub visible_apps: Option<VisibleApps>,
 
+    // ===== MCP 管理范围（未设置时管理全部应用）=====
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_managed_apps: Option<McpManagedApps>,
+
     // ===== 设备级目录覆盖 =====
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub claude_config_dir: Option<String>,
@@ -98,6 +169,9 @@ pub struct AppSettings {
     pub gemini_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub opencode_config_dir: Option<String>,
+    /// Claude Live 配置写入方式：replace（默认）或 profiles
+    #[serde(default)]
+    pub claude_write_mode: ClaudeWriteMode,
 
     // ===== 当前供应商 ID（设备级）=====
     /// 当前 Claude 供应商 ID（本地存储，优先于数据库 is_current）
@@ -125,8 +199,39 @@ pub struct AppSettings {
     /// - Linux: "gnome-terminal" | "konsole" | "xfce4-terminal" | "alacritty" | "kitty" | "ghostty"
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preferred_terminal: Option<String>,
+
+    // ===== 预设目录 =====
+    /// 远程供应商预设目录地址（可选，未配置时不下载远程预设）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset_catalog_url: Option<String>,
+
+    // ===== 提示词 =====
+    /// 多个启用的提示词写入 live 文件时的分隔内容（默认空行）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_segment_delimiter: Option<String>,
+
+    // ===== 用量展示 =====
+    /// 成本展示货币（ISO 4217 代码，如 "EUR"；未设置时只展示 USD）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<String>,
+    /// 1 USD 兑换展示货币的汇率（十进制字符串，可手动填写或从汇率接口获取）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_exchange_rate: Option<String>,
+
+    // ===== 外观 =====
+    /// 窗口主题模式（固定 / 跟随系统 / 按时间计划切换）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme_config: Option<crate::theme::ThemeConfig>,
+
+    // ===== 快捷键 =====
+    /// 全局快捷键绑定（切换下一个供应商 / 启停代理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkeys: Option<crate::hotkeys::HotkeyConfig>,
 }
 
+/// 启动延迟上限（秒）
+pub const MAX_STARTUP_DELAY_SECONDS: u32 = 300;
+
 fn default_show_in_tray() -> bool {
     true
 }
@@ -144,18 +249,30 @@ impl Default for AppSettings {
             skip_claude_onboarding: false,
             launch_on_startup: false,
             silent_startup: false,
+            startup_delay_seconds: 0,
             language: None,
+            verify_before_switch: false,
+            read_only_mode: false,
+            enforce_provider_limits: false,
             visible_apps: None,
+            mcp_managed_apps: None,
             claude_config_dir: None,
             codex_config_dir: None,
             gemini_config_dir: None,
             opencode_config_dir: None,
+            claude_write_mode: ClaudeWriteMode::default(),
             current_provider_claude: None,
             current_provider_codex: None,
             current_provider_gemini: None,
             current_provider_opencode: None,
             skill_sync_method: SyncMethod::default(),
             preferred_terminal: None,
+            preset_catalog_url: None,
+            prompt_segment_delimiter: None,
+            display_currency: None,
+            usd_exchange_rate: None,
+            theme_config: None,
+            hotkeys: None,
         }
     }
 }
@@ -203,7 +320,28 @@ impl AppSettings {
             .language
             .as_ref()
             .map(|s| s.trim())
-            .filter(|s| matches!(*s, "en" | "zh" | "ja"))
+            .filter(|s| is_supported_language(s))
+            .map(|s| s.to_string());
+
+        self.startup_delay_seconds = self.startup_delay_seconds.min(MAX_STARTUP_DELAY_SECONDS);
+
+        self.preset_catalog_url = self
+            .preset_catalog_url
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        self.display_currency = self
+            .display_currency
+            .as_deref()
+            .and_then(crate::services::currency::normalize_currency_code);
+
+        self.usd_exchange_rate = self
+            .usd_exchange_rate
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
     }
 
@@ -339,6 +477,89 @@ pub fn get_opencode_override_dir() -> Option<PathBuf> {
         .map(|p| resolve_override_path(p))
 }
 
+impl AppSettings {
+    /// 指定应用的配置目录覆盖（原始设置值）
+    fn override_dir_for(&self, app_type: &AppType) -> Option<&str> {
+        match app_type {
+            AppType::Claude => self.claude_config_dir.as_deref(),
+            AppType::Codex => self.codex_config_dir.as_deref(),
+            AppType::Gemini => self.gemini_config_dir.as_deref(),
+            AppType::OpenCode => self.opencode_config_dir.as_deref(),
+        }
+    }
+}
+
+/// 获取指定应用当前生效的配置目录覆盖
+pub fn get_override_dir(app_type: &AppType) -> Option<PathBuf> {
+    match app_type {
+        AppType::Claude => get_claude_override_dir(),
+        AppType::Codex => get_codex_override_dir(),
+        AppType::Gemini => get_gemini_override_dir(),
+        AppType::OpenCode => get_opencode_override_dir(),
+    }
+}
+
+/// 检查配置目录覆盖（支持 `~` 开头的路径）
+pub fn validate_override_dir(app_type: &AppType, raw: &str) -> crate::config::OverrideDirReport {
+    crate::config::validate_override_dir(app_type, &resolve_override_path(raw.trim()))
+}
+
+/// 保存设置前检查有变化的配置目录覆盖
+///
+/// 存在错误（不可写、不是目录等）时拒绝保存；只有警告（不存在、WSL 路径、rename 不可用等）时
+/// 需要 `acknowledge_warnings` 为 true 才允许保存。未改动的目录不重复检查。
+pub fn check_override_dir_changes(
+    new_settings: &AppSettings,
+    acknowledge_warnings: bool,
+) -> Result<(), AppError> {
+    let mut normalized = new_settings.clone();
+    normalized.normalize_paths();
+    let current = get_settings();
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for app_type in AppType::all() {
+        let Some(raw) = normalized.override_dir_for(&app_type) else {
+            continue;
+        };
+        if current.override_dir_for(&app_type) == Some(raw) {
+            continue;
+        }
+        let report = validate_override_dir(&app_type, raw);
+        if !report.errors.is_empty() {
+            errors.push(format!(
+                "{}: {}",
+                app_type.as_str(),
+                report.errors.join(", ")
+            ));
+        } else if !report.warnings.is_empty() {
+            warnings.push(format!(
+                "{}: {}",
+                app_type.as_str(),
+                report.warnings.join(", ")
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        let detail = errors.join("; ");
+        return Err(AppError::localized(
+            "settings.override_dir.invalid",
+            format!("配置目录不可用（{detail}）"),
+            format!("Config directory override is not usable ({detail})"),
+        ));
+    }
+    if !warnings.is_empty() && !acknowledge_warnings {
+        let detail = warnings.join("; ");
+        return Err(AppError::localized(
+            "settings.override_dir.warnings",
+            format!("配置目录存在潜在问题（{detail}），确认后才能保存"),
+            format!("Config directory override has warnings ({detail}); confirm to save anyway"),
+        ));
+    }
+    Ok(())
+}
+
 // ===== 当前供应商管理函数 =====
 
 /// 获取指定应用类型的当前供应商 ID（从本地 settings 读取）
@@ -420,6 +641,161 @@ pub fn get_skill_sync_method() -> SyncMethod {
         .skill_sync_method
 }
 
+/// 获取 Claude Live 配置写入方式
+pub fn get_claude_write_mode() -> ClaudeWriteMode {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .claude_write_mode
+}
+
+// ===== MCP 管理范围 =====
+
+/// 获取由 CC Switch 管理 MCP 的应用
+pub fn get_mcp_managed_apps() -> McpManagedApps {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .mcp_managed_apps
+        .clone()
+        .unwrap_or_default()
+}
+
+/// 指定应用的 MCP 是否由 CC Switch 管理
+pub fn is_mcp_managed(app: &AppType) -> bool {
+    get_mcp_managed_apps().is_managed(app)
+}
+
+/// 设置由 CC Switch 管理 MCP 的应用
+pub fn set_mcp_managed_apps(apps: McpManagedApps) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.mcp_managed_apps = Some(apps);
+    update_settings(settings)
+}
+
+// ===== 界面语言管理函数 =====
+
+/// 是否为支持的界面语言
+pub fn is_supported_language(lang: &str) -> bool {
+    matches!(lang, "en" | "zh" | "ja")
+}
+
+/// 获取设置中的界面语言（未设置时返回 None）
+pub fn get_language() -> Option<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .language
+        .clone()
+}
+
+/// 设置界面语言
+pub fn set_language(lang: &str) -> Result<(), AppError> {
+    let lang = lang.trim();
+    if !is_supported_language(lang) {
+        return Err(AppError::localized(
+            "settings.invalid_language",
+            format!("不支持的界面语言: {lang}"),
+            format!("Unsupported language: {lang}"),
+        ));
+    }
+    let mut settings = get_settings();
+    settings.language = Some(lang.to_string());
+    update_settings(settings)
+}
+
+/// 原生对话框与错误信息是否使用中文
+///
+/// 优先使用设置中的界面语言，其次是 Store 中缓存的语言（AppState 创建前可用），
+/// 都未设置时才回退到系统语言环境。
+pub fn is_chinese_ui() -> bool {
+    match get_language().or_else(crate::app_store::get_language_override) {
+        Some(lang) => lang == "zh",
+        None => is_chinese_system_locale(),
+    }
+}
+
+fn is_chinese_system_locale() -> bool {
+    std::env::var("LANG")
+        .or_else(|_| std::env::var("LC_ALL"))
+        .or_else(|_| std::env::var("LC_MESSAGES"))
+        .map(|lang| lang.starts_with("zh"))
+        .unwrap_or(false)
+}
+
+// ===== 启动设置管理函数 =====
+
+/// 获取启动延迟（秒）
+pub fn get_startup_delay_seconds() -> u32 {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .startup_delay_seconds
+}
+
+/// 设置启动延迟（秒），超过上限时返回错误
+pub fn set_startup_delay_seconds(seconds: u32) -> Result<(), AppError> {
+    if seconds > MAX_STARTUP_DELAY_SECONDS {
+        return Err(AppError::InvalidInput(format!(
+            "启动延迟不能超过 {MAX_STARTUP_DELAY_SECONDS} 秒"
+        )));
+    }
+    let mut settings = get_settings();
+    settings.startup_delay_seconds = seconds;
+    update_settings(settings)
+}
+
+// ===== 只读模式管理函数 =====
+
+/// 是否开启只读模式
+pub fn is_read_only_mode() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .read_only_mode
+}
+
+/// 是否强制执行供应商消费限额（默认仅报告）
+pub fn enforce_provider_limits() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .enforce_provider_limits
+}
+
+/// 开启/关闭只读模式
+pub fn set_read_only_mode(enabled: bool) -> Result<(), AppError> {
+    let mut settings = get_settings();
+    settings.read_only_mode = enabled;
+    update_settings(settings)
+}
+
+/// 写入 Live 配置前调用：只读模式下返回 `AppError::ReadOnlyMode`
+pub fn ensure_live_writable() -> Result<(), AppError> {
+    if is_read_only_mode() {
+        return Err(AppError::ReadOnlyMode);
+    }
+    Ok(())
+}
+
 // ===== 终端设置管理函数 =====
 
 /// 获取首选终端应用
@@ -433,3 +809,80 @@ pub fn get_preferred_terminal() -> Option<String> {
         .preferred_terminal
         .clone()
 }
+
+/// 获取远程预设目录地址（未配置时返回 None）
+pub fn get_preset_catalog_url() -> Option<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .preset_catalog_url
+        .clone()
+}
+
+// ===== 外观设置管理函数 =====
+
+/// 获取窗口主题配置（未配置时跟随系统）
+pub fn get_theme_config() -> crate::theme::ThemeConfig {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .theme_config
+        .clone()
+        .unwrap_or_default()
+}
+
+/// 保存窗口主题配置（先校验计划时间格式）
+pub fn set_theme_config(config: crate::theme::ThemeConfig) -> Result<(), AppError> {
+    config.validate()?;
+    let mut settings = get_settings();
+    settings.theme_config = Some(config);
+    update_settings(settings)
+}
+
+// ===== 快捷键设置管理函数 =====
+
+/// 获取全局快捷键配置（未配置时为空）
+pub fn get_hotkey_config() -> crate::hotkeys::HotkeyConfig {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .hotkeys
+        .clone()
+        .unwrap_or_default()
+}
+
+/// 保存全局快捷键配置（先校验应用 ID 与快捷键格式）
+pub fn set_hotkey_config(config: crate::hotkeys::HotkeyConfig) -> Result<(), AppError> {
+    config.validate()?;
+    let mut settings = get_settings();
+    settings.hotkeys = Some(config);
+    update_settings(settings)
+}
+
+// ===== 提示词设置管理函数 =====
+
+/// 获取提示词分段分隔内容（未配置时为一个空行；非空时保证以换行结尾）
+pub fn get_prompt_segment_delimiter() -> String {
+    let mut delimiter = settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .prompt_segment_delimiter
+        .clone()
+        .unwrap_or_else(|| "\n".to_string());
+    if !delimiter.is_empty() && !delimiter.ends_with('\n') {
+        delimiter.push('\n');
+    }
+    delimiter
+}
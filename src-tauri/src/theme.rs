@@ -0,0 +1,272 @@
+//! 窗口主题自动切换
+//!
+//! 主题模式保存在 `AppSettings.theme_config` 中：
+//! - `light` / `dark`：固定主题；
+//! - `system`：跟随系统外观（监听窗口的 `ThemeChanged` 事件，并定时轮询兜底）；
+//! - `schedule`：按浅色/深色开始时间切换。
+//!
+//! 实际生效的主题变化时重新设置窗口主题，并发送 [`THEME_CHANGED_EVENT`] 供前端同步。
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppError;
+
+/// 生效主题变化时发送的事件
+pub const THEME_CHANGED_EVENT: &str = "theme-changed";
+
+/// 检查计划时间边界与系统外观的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 上一次应用的结果，避免重复设置窗口主题和重复发事件
+static LAST_APPLIED: Mutex<Option<ThemeChangedPayload>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    #[default]
+    System,
+    Schedule,
+}
+
+/// 实际生效的主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectiveTheme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub mode: ThemeMode,
+    /// 计划模式下切换为浅色的时间（本地时间 HH:MM）
+    #[serde(default = "default_light_start")]
+    pub light_start: String,
+    /// 计划模式下切换为深色的时间（本地时间 HH:MM）
+    #[serde(default = "default_dark_start")]
+    pub dark_start: String,
+}
+
+fn default_light_start() -> String {
+    "07:00".to_string()
+}
+
+fn default_dark_start() -> String {
+    "19:00".to_string()
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::default(),
+            light_start: default_light_start(),
+            dark_start: default_dark_start(),
+        }
+    }
+}
+
+impl ThemeConfig {
+    /// 校验计划时间格式（HH:MM）
+    pub fn validate(&self) -> Result<(), AppError> {
+        for value in [&self.light_start, &self.dark_start] {
+            parse_time(value).ok_or_else(|| {
+                AppError::localized(
+                    "theme.invalid_time",
+                    format!("无效的时间格式: {value}（应为 HH:MM）"),
+                    format!("Invalid time: {value} (expected HH:MM)"),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// 计划模式下指定时刻应使用的主题；非计划模式返回 None
+    pub fn scheduled_theme(&self, now: NaiveTime) -> Option<EffectiveTheme> {
+        if self.mode != ThemeMode::Schedule {
+            return None;
+        }
+        let light = parse_time(&self.light_start)?;
+        let dark = parse_time(&self.dark_start)?;
+        let is_light = match light.cmp(&dark) {
+            std::cmp::Ordering::Less => now >= light && now < dark,
+            // 浅色时段跨越午夜
+            std::cmp::Ordering::Greater => now >= light || now < dark,
+            std::cmp::Ordering::Equal => true,
+        };
+        Some(if is_light {
+            EffectiveTheme::Light
+        } else {
+            EffectiveTheme::Dark
+        })
+    }
+}
+
+/// [`THEME_CHANGED_EVENT`] 的 payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeChangedPayload {
+    pub mode: ThemeMode,
+    pub theme: EffectiveTheme,
+}
+
+fn last_applied() -> std::sync::MutexGuard<'static, Option<ThemeChangedPayload>> {
+    LAST_APPLIED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// 按当前配置设置主窗口主题；生效主题变化（或 `force` 为 true）时发送事件
+pub fn apply(app: &AppHandle, force: bool) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let config = crate::settings::get_theme_config();
+
+    let native = match config.mode {
+        ThemeMode::Light => Some(tauri::Theme::Light),
+        ThemeMode::Dark => Some(tauri::Theme::Dark),
+        ThemeMode::System => None,
+        ThemeMode::Schedule => match config.scheduled_theme(Local::now().time()) {
+            Some(EffectiveTheme::Dark) => Some(tauri::Theme::Dark),
+            _ => Some(tauri::Theme::Light),
+        },
+    };
+
+    let expected = native.map(|theme| match theme {
+        tauri::Theme::Dark => EffectiveTheme::Dark,
+        _ => EffectiveTheme::Light,
+    });
+    let previous = last_applied().clone();
+    let needs_set = force
+        || previous.as_ref().map(|p| p.mode) != Some(config.mode)
+        || (expected.is_some() && previous.as_ref().map(|p| p.theme) != expected);
+    // 设置主题可能同步触发 ThemeChanged 事件，因此不能在持有锁时调用
+    if needs_set {
+        if let Err(e) = window.set_theme(native) {
+            log::warn!("设置窗口主题失败: {e}");
+        }
+    }
+
+    // 跟随系统时以窗口当前主题（即系统外观）为准
+    let theme = expected.unwrap_or_else(|| match window.theme() {
+        Ok(tauri::Theme::Dark) => EffectiveTheme::Dark,
+        _ => EffectiveTheme::Light,
+    });
+    let payload = ThemeChangedPayload {
+        mode: config.mode,
+        theme,
+    };
+    {
+        let mut last = last_applied();
+        if !force && last.as_ref() == Some(&payload) {
+            return;
+        }
+        *last = Some(payload.clone());
+    }
+
+    log::debug!("窗口主题已更新: {payload:?}");
+    if let Err(e) = app.emit(THEME_CHANGED_EVENT, &payload) {
+        log::error!("发射主题变化事件失败: {e}");
+    }
+}
+
+/// 启动后台任务：定时检查计划时间边界与系统外观变化
+pub fn spawn_watcher(app: AppHandle) {
+    apply(&app, true);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let mode = crate::settings::get_theme_config().mode;
+            if matches!(mode, ThemeMode::System | ThemeMode::Schedule) {
+                apply(&app, false);
+            }
+        }
+    });
+}
+
+/// 系统外观变化（窗口 `ThemeChanged` 事件）时调用
+pub fn on_system_theme_changed(app: &AppHandle) {
+    if crate::settings::get_theme_config().mode == ThemeMode::System {
+        apply(app, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn schedule_switches_at_boundaries() {
+        let config = ThemeConfig {
+            mode: ThemeMode::Schedule,
+            ..ThemeConfig::default()
+        };
+        assert_eq!(
+            config.scheduled_theme(at(6, 59)),
+            Some(EffectiveTheme::Dark)
+        );
+        assert_eq!(
+            config.scheduled_theme(at(7, 0)),
+            Some(EffectiveTheme::Light)
+        );
+        assert_eq!(
+            config.scheduled_theme(at(18, 59)),
+            Some(EffectiveTheme::Light)
+        );
+        assert_eq!(
+            config.scheduled_theme(at(19, 0)),
+            Some(EffectiveTheme::Dark)
+        );
+
+        // 浅色时段跨越午夜
+        let inverted = ThemeConfig {
+            mode: ThemeMode::Schedule,
+            light_start: "22:00".to_string(),
+            dark_start: "06:00".to_string(),
+        };
+        assert_eq!(
+            inverted.scheduled_theme(at(23, 0)),
+            Some(EffectiveTheme::Light)
+        );
+        assert_eq!(
+            inverted.scheduled_theme(at(5, 59)),
+            Some(EffectiveTheme::Light)
+        );
+        assert_eq!(
+            inverted.scheduled_theme(at(12, 0)),
+            Some(EffectiveTheme::Dark)
+        );
+
+        assert_eq!(ThemeConfig::default().scheduled_theme(at(12, 0)), None);
+    }
+
+    #[test]
+    fn validate_rejects_malformed_times() {
+        assert!(ThemeConfig::default().validate().is_ok());
+        let config = ThemeConfig {
+            light_start: "7am".to_string(),
+            ..ThemeConfig::default()
+        };
+        assert!(config.validate().is_err());
+        let config = ThemeConfig {
+            dark_start: "25:00".to_string(),
+            ..ThemeConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
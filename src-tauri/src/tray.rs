@@ -16,6 +16,11 @@ pub struct TrayTexts {
     pub no_provider_hint: &'static str,
     pub quit: &'static str,
     pub auto_label: &'static str,
+    pub start_proxy: &'static str,
+    pub stop_proxy: &'static str,
+    pub takeover_prefix: &'static str,
+    pub proxy_action_failed: &'static str,
+    pub read_only_hint: &'static str,
 }
 
 impl TrayTexts {
@@ -26,6 +31,11 @@ impl TrayTexts {
                 no_provider_hint: "  (No providers yet, please add them from the main window)",
                 quit: "Quit",
                 auto_label: "Auto (Failover)",
+                start_proxy: "Start proxy",
+                stop_proxy: "Stop proxy (restore)",
+                takeover_prefix: "Takeover",
+                proxy_action_failed: "Proxy operation failed",
+                read_only_hint: "Read-only mode (config files are not modified)",
             },
             "ja" => Self {
                 show_main: "メインウィンドウを開く",
@@ -33,12 +43,22 @@ impl TrayTexts {
                     "  (プロバイダーがまだありません。メイン画面から追加してください)",
                 quit: "終了",
                 auto_label: "自動 (フェイルオーバー)",
+                start_proxy: "プロキシを起動",
+                stop_proxy: "プロキシを停止（設定を復元）",
+                takeover_prefix: "引き継ぎ",
+                proxy_action_failed: "プロキシ操作に失敗しました",
+                read_only_hint: "読み取り専用モード（設定ファイルは変更されません）",
             },
             _ => Self {
                 show_main: "打开主界面",
                 no_provider_hint: "  (无供应商，请在主界面添加)",
                 quit: "退出",
                 auto_label: "自动 (故障转移)",
+                start_proxy: "启动代理",
+                stop_proxy: "停止代理（恢复配置）",
+                takeover_prefix: "接管",
+                proxy_action_failed: "代理操作失败",
+                read_only_hint: "只读模式（不修改配置文件）",
             },
         }
     }
@@ -57,6 +77,14 @@ pub struct TrayAppSection {
 /// Auto 菜单项后缀
 pub const AUTO_SUFFIX: &str = "auto";
 
+/// 代理快捷操作菜单项 ID
+pub const PROXY_START_ID: &str = "proxy_start";
+pub const PROXY_STOP_ID: &str = "proxy_stop";
+/// 接管开关菜单项 ID 前缀（后接应用类型）
+pub const PROXY_TAKEOVER_PREFIX: &str = "proxy_takeover_";
+/// 只读模式提示菜单项 ID
+pub const READ_ONLY_HINT_ID: &str = "read_only_hint";
+
 pub const TRAY_SECTIONS: [TrayAppSection; 3] = [
     TrayAppSection {
         app_type: AppType::Claude,
@@ -173,6 +201,134 @@ fn append_provider_section<'a>(
     Ok(menu_builder)
 }
 
+/// 添加代理快捷操作分区（启动/停止代理 + 各应用接管开关）
+fn append_proxy_section<'a>(
+    app: &'a tauri::AppHandle,
+    mut menu_builder: MenuBuilder<'a, tauri::Wry, tauri::AppHandle<tauri::Wry>>,
+    tray_texts: &TrayTexts,
+    app_state: &AppState,
+    visible_apps: &crate::settings::VisibleApps,
+    proxy_running: bool,
+) -> Result<MenuBuilder<'a, tauri::Wry, tauri::AppHandle<tauri::Wry>>, AppError> {
+    let (toggle_id, toggle_label) = if proxy_running {
+        (PROXY_STOP_ID, tray_texts.stop_proxy)
+    } else {
+        (PROXY_START_ID, tray_texts.start_proxy)
+    };
+    let toggle_item = MenuItem::with_id(app, toggle_id, toggle_label, true, None::<&str>)
+        .map_err(|e| AppError::Message(format!("创建代理开关菜单失败: {e}")))?;
+    menu_builder = menu_builder.item(&toggle_item);
+
+    for section in TRAY_SECTIONS.iter() {
+        if !visible_apps.is_visible(&section.app_type) {
+            continue;
+        }
+        let app_type_str = section.app_type.as_str();
+        let (takeover_enabled, _) = app_state.db.get_proxy_flags_sync(app_type_str);
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{PROXY_TAKEOVER_PREFIX}{app_type_str}"),
+            format!("{}: {}", tray_texts.takeover_prefix, section.header_label),
+            true,
+            takeover_enabled,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建{}接管菜单项失败: {e}", section.log_name)))?;
+        menu_builder = menu_builder.item(&item);
+    }
+
+    Ok(menu_builder)
+}
+
+/// 处理代理快捷操作托盘事件
+///
+/// 调用与前端相同的 ProxyService 方法；菜单刷新由 ProxyService 在状态变化后统一完成，
+/// 这里只负责在失败时提示用户。
+fn handle_proxy_tray_event(app: &tauri::AppHandle, event_id: &str) -> bool {
+    let action = if event_id == PROXY_START_ID {
+        ProxyTrayAction::Start
+    } else if event_id == PROXY_STOP_ID {
+        ProxyTrayAction::Stop
+    } else if let Some(app_type) = event_id.strip_prefix(PROXY_TAKEOVER_PREFIX) {
+        ProxyTrayAction::ToggleTakeover(app_type.to_string())
+    } else {
+        return false;
+    };
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(app_state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        let proxy_service = &app_state.proxy_service;
+        let result = match &action {
+            ProxyTrayAction::Start => {
+                log::info!("[Tray] 启动代理服务");
+                proxy_service.start().await.map(|_| ())
+            }
+            ProxyTrayAction::Stop => {
+                log::info!("[Tray] 停止代理服务并恢复配置");
+                proxy_service.stop_with_restore().await
+            }
+            ProxyTrayAction::ToggleTakeover(app_type) => {
+                let (enabled, _) = app_state.db.get_proxy_flags_sync(app_type);
+                log::info!("[Tray] 切换 {app_type} 接管: {}", !enabled);
+                proxy_service.set_takeover_for_app(app_type, !enabled).await
+            }
+        };
+
+        if let Err(e) = result {
+            log::error!("[Tray] 代理操作失败: {e}");
+            // 失败时菜单仍可能显示点击后的勾选状态，重建以回到真实状态
+            refresh_tray_menu(&app_handle);
+            show_proxy_error(&app_handle, &e);
+        }
+    });
+    true
+}
+
+enum ProxyTrayAction {
+    Start,
+    Stop,
+    ToggleTakeover(String),
+}
+
+/// 以系统对话框提示代理操作失败（如端口被占用）
+fn show_proxy_error(app: &tauri::AppHandle, error: &str) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+    let app_settings = crate::settings::get_settings();
+    let tray_texts = TrayTexts::from_language(app_settings.language.as_deref().unwrap_or("zh"));
+    app.dialog()
+        .message(error)
+        .title(tray_texts.proxy_action_failed)
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+/// 重建托盘菜单（反映最新的供应商与代理状态）
+///
+/// 在后台任务中读取代理运行状态后重建，同步与异步上下文均可调用。
+pub fn refresh_tray_menu(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(app_state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let proxy_running = app_state.proxy_service.is_running().await;
+        match create_tray_menu(&app, app_state.inner(), proxy_running) {
+            Ok(new_menu) => {
+                if let Some(tray) = app.tray_by_id("main") {
+                    if let Err(e) = tray.set_menu(Some(new_menu)) {
+                        log::error!("[Tray] 更新托盘菜单失败: {e}");
+                    }
+                }
+            }
+            Err(e) => log::error!("[Tray] 构建托盘菜单失败: {e}"),
+        }
+    });
+}
+
 /// 处理供应商托盘事件
 pub fn handle_provider_tray_event(app: &tauri::AppHandle, event_id: &str) -> bool {
     for section in TRAY_SECTIONS.iter() {
@@ -271,11 +427,7 @@ fn handle_auto_click(app: &tauri::AppHandle, app_type: &AppType) -> Result<(), A
         }
 
         // 4) 更新托盘菜单
-        if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
-            if let Some(tray) = app.tray_by_id("main") {
-                let _ = tray.set_menu(Some(new_menu));
-            }
-        }
+        refresh_tray_menu(app);
 
         // 5) 发射事件到前端
         let event_data = serde_json::json!({
@@ -311,19 +463,11 @@ fn handle_provider_click(
             .set_proxy_flags_sync(app_type_str, proxy_enabled, false)?;
 
         // 切换供应商
-        crate::commands::switch_provider(
-            app_state.clone(),
-            app_type_str.to_string(),
-            provider_id.to_string(),
-        )
-        .map_err(AppError::Message)?;
+        crate::commands::switch_provider_blocking(&app_state, app_type.clone(), provider_id, false)
+            .map_err(AppError::Message)?;
 
         // 更新托盘菜单
-        if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
-            if let Some(tray) = app.tray_by_id("main") {
-                let _ = tray.set_menu(Some(new_menu));
-            }
-        }
+        refresh_tray_menu(app);
 
         // 发射事件到前端
         let event_data = serde_json::json!({
@@ -344,9 +488,12 @@ fn handle_provider_click(
 }
 
 /// 创建动态托盘菜单
+///
+/// `proxy_running` 由调用方提供（异步上下文中 await `is_running()`），避免在此阻塞等待。
 pub fn create_tray_menu(
     app: &tauri::AppHandle,
     app_state: &AppState,
+    proxy_running: bool,
 ) -> Result<Menu<tauri::Wry>, AppError> {
     let app_settings = crate::settings::get_settings();
     let tray_texts = TrayTexts::from_language(app_settings.language.as_deref().unwrap_or("zh"));
@@ -362,6 +509,30 @@ pub fn create_tray_menu(
             .map_err(|e| AppError::Message(format!("创建打开主界面菜单失败: {e}")))?;
     menu_builder = menu_builder.item(&show_main_item).separator();
 
+    // 只读模式提示（不可点击）
+    if app_settings.read_only_mode {
+        let read_only_item = MenuItem::with_id(
+            app,
+            READ_ONLY_HINT_ID,
+            tray_texts.read_only_hint,
+            false,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建只读模式提示失败: {e}")))?;
+        menu_builder = menu_builder.item(&read_only_item).separator();
+    }
+
+    // 代理快捷操作
+    menu_builder = append_proxy_section(
+        app,
+        menu_builder,
+        &tray_texts,
+        app_state,
+        &visible_apps,
+        proxy_running,
+    )?;
+    menu_builder = menu_builder.separator();
+
     // 直接添加所有供应商到主菜单（扁平化结构，更简单可靠）
     // Only add visible app sections
     for section in TRAY_SECTIONS.iter() {
@@ -451,7 +622,7 @@ pub fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
             app.exit(0);
         }
         _ => {
-            if handle_provider_tray_event(app, event_id) {
+            if handle_proxy_tray_event(app, event_id) || handle_provider_tray_event(app, event_id) {
                 return;
             }
             log::warn!("未处理的菜单事件: {event_id}");
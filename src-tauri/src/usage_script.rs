@@ -1,20 +1,122 @@
-use rquickjs::{Context, Function, Runtime};
+use rquickjs::{Context, Ctx, Function, Runtime};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use url::{Host, Url};
 
 use crate::error::AppError;
+use crate::provider::UsageScript;
+
+/// 未配置超时时脚本的默认超时（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// 单个 JS 运行时的内存上限
+const SCRIPT_MEMORY_LIMIT: usize = 32 * 1024 * 1024;
+
+/// 脚本请求的响应体大小上限
+const MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+
+/// 脚本请求最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 沙箱限制被触发时的错误类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SandboxViolation {
+    /// 执行超过墙钟时间上限，解释器已被中断
+    Timeout(u64),
+    /// 超出 JS 运行时内存上限
+    MemoryLimit,
+    /// 响应体超过大小上限
+    ResponseTooLarge,
+    /// 请求的域名不在脚本的网络白名单中
+    HostNotAllowed(String),
+    /// 重定向目标未通过 URL 安全校验或网络白名单，或重定向次数过多
+    RedirectBlocked(String),
+}
+
+impl From<SandboxViolation> for AppError {
+    fn from(violation: SandboxViolation) -> Self {
+        match violation {
+            SandboxViolation::Timeout(secs) => AppError::localized(
+                "usage_script.sandbox_timeout",
+                format!("脚本执行超时（{secs} 秒），已中止"),
+                format!("Script timed out after {secs}s and was aborted"),
+            ),
+            SandboxViolation::MemoryLimit => AppError::localized(
+                "usage_script.sandbox_memory_limit",
+                format!(
+                    "脚本内存占用超过上限（{} MB）",
+                    SCRIPT_MEMORY_LIMIT / 1024 / 1024
+                ),
+                format!(
+                    "Script exceeded the memory limit ({} MB)",
+                    SCRIPT_MEMORY_LIMIT / 1024 / 1024
+                ),
+            ),
+            SandboxViolation::ResponseTooLarge => AppError::localized(
+                "usage_script.sandbox_response_too_large",
+                format!("响应体超过上限（{} MB）", MAX_RESPONSE_BYTES / 1024 / 1024),
+                format!(
+                    "Response body exceeded the limit ({} MB)",
+                    MAX_RESPONSE_BYTES / 1024 / 1024
+                ),
+            ),
+            SandboxViolation::HostNotAllowed(host) => AppError::localized(
+                "usage_script.sandbox_host_not_allowed",
+                format!("请求域名 {host} 不在脚本的网络白名单中"),
+                format!("Request host {host} is not in the script's network allowlist"),
+            ),
+            SandboxViolation::RedirectBlocked(url) => AppError::localized(
+                "usage_script.sandbox_redirect_blocked",
+                format!("已阻止重定向到 {url}：目标未通过安全校验或重定向次数过多"),
+                format!(
+                    "Blocked redirect to {url}: target failed validation or too many redirects"
+                ),
+            ),
+        }
+    }
+}
+
+/// 脚本沙箱配置
+///
+/// `timeout_secs` 同时约束脚本执行与 HTTP 请求的总耗时；`allowed_hosts` 非空时，
+/// 脚本只能请求匹配的域名（支持 `*.example.com` 通配子域名）。
+/// 内存与响应体大小上限对所有脚本相同，见 `SCRIPT_MEMORY_LIMIT` / `MAX_RESPONSE_BYTES`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptSandbox {
+    pub timeout_secs: u64,
+    pub allowed_hosts: Vec<String>,
+}
+
+impl ScriptSandbox {
+    pub fn new(timeout_secs: Option<u64>, allowed_hosts: Option<&[String]>) -> Self {
+        Self {
+            timeout_secs: timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            allowed_hosts: allowed_hosts.map(<[String]>::to_vec).unwrap_or_default(),
+        }
+    }
 
-/// 执行用量查询脚本
+    /// 从已保存的脚本配置读取沙箱参数
+    pub fn from_usage_script(script: &UsageScript) -> Self {
+        Self::new(script.timeout, script.allowed_hosts.as_deref())
+    }
+}
+
+/// 执行用量查询脚本，受 `sandbox` 中的超时与网络白名单约束
 pub async fn execute_usage_script(
     script_code: &str,
     api_key: &str,
     base_url: &str,
-    timeout_secs: u64,
     access_token: Option<&str>,
     user_id: Option<&str>,
     template_type: Option<&str>,
+    sandbox: &ScriptSandbox,
 ) -> Result<Value, AppError> {
+    let allowed_hosts = sandbox.allowed_hosts.as_slice();
+    // 约束超时范围，防止异常配置导致长时间阻塞（最小 2 秒，最大 30 秒）
+    let timeout_secs = sandbox.timeout_secs.clamp(2, 30);
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+
     // 检测是否为自定义模板模式
     // 优先使用前端传递的 template_type
     let is_custom_template = template_type.map(|t| t == "custom").unwrap_or(false);
@@ -31,13 +133,7 @@ pub async fn execute_usage_script(
 
     // 3. 在独立作用域中提取 request 配置（确保 Runtime/Context 在 await 前释放）
     let request_config = {
-        let runtime = Runtime::new().map_err(|e| {
-            AppError::localized(
-                "usage_script.runtime_create_failed",
-                format!("创建 JS 运行时失败: {e}"),
-                format!("Failed to create JS runtime: {e}"),
-            )
-        })?;
+        let runtime = create_sandboxed_runtime(deadline)?;
         let context = Context::full(&runtime).map_err(|e| {
             AppError::localized(
                 "usage_script.context_create_failed",
@@ -49,11 +145,13 @@ pub async fn execute_usage_script(
         context.with(|ctx| {
             // 执行用户代码，获取配置对象
             let config: rquickjs::Object = ctx.eval(script_with_vars.clone()).map_err(|e| {
-                AppError::localized(
-                    "usage_script.config_parse_failed",
-                    format!("解析配置失败: {e}"),
-                    format!("Failed to parse config: {e}"),
-                )
+                js_error(&ctx, e, deadline, timeout_secs, |e| {
+                    AppError::localized(
+                        "usage_script.config_parse_failed",
+                        format!("解析配置失败: {e}"),
+                        format!("Failed to parse config: {e}"),
+                    )
+                })
             })?;
 
             // 提取 request 配置
@@ -107,19 +205,36 @@ pub async fn execute_usage_script(
     // 5. 验证请求 URL 是否安全（防止 SSRF）
     // 如果提供了 base_url，则验证同源；否则只做基本安全检查
     validate_request_url(&request.url, base_url, is_custom_template)?;
+    validate_allowed_host(&request.url, allowed_hosts)?;
 
-    // 6. 发送 HTTP 请求
-    let response_data = send_http_request(&request, timeout_secs).await?;
+    // 6. 发送 HTTP 请求（使用剩余的时间预算）
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return Err(SandboxViolation::Timeout(timeout_secs).into());
+    }
+    // 每一跳重定向都重新校验，避免通过重定向绕过同源检查与网络白名单
+    let redirect_policy = {
+        let base_url = base_url.to_string();
+        let allowed_hosts = allowed_hosts.to_vec();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                let target = attempt.url().to_string();
+                return attempt.error(AppError::from(SandboxViolation::RedirectBlocked(target)));
+            }
+            let url = attempt.url().as_str();
+            match validate_request_url(url, &base_url, is_custom_template)
+                .and_then(|_| validate_allowed_host(url, &allowed_hosts))
+            {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        })
+    };
+    let response_data = send_http_request(&request, remaining, redirect_policy).await?;
 
     // 7. 在独立作用域中执行 extractor（确保 Runtime/Context 在函数结束前释放）
     let result: Value = {
-        let runtime = Runtime::new().map_err(|e| {
-            AppError::localized(
-                "usage_script.runtime_create_failed",
-                format!("创建 JS 运行时失败: {e}"),
-                format!("Failed to create JS runtime: {e}"),
-            )
-        })?;
+        let runtime = create_sandboxed_runtime(deadline)?;
         let context = Context::full(&runtime).map_err(|e| {
             AppError::localized(
                 "usage_script.context_create_failed",
@@ -131,11 +246,13 @@ pub async fn execute_usage_script(
         context.with(|ctx| {
             // 重新 eval 获取配置对象
             let config: rquickjs::Object = ctx.eval(script_with_vars.clone()).map_err(|e| {
-                AppError::localized(
-                    "usage_script.config_reparse_failed",
-                    format!("重新解析配置失败: {e}"),
-                    format!("Failed to re-parse config: {e}"),
-                )
+                js_error(&ctx, e, deadline, timeout_secs, |e| {
+                    AppError::localized(
+                        "usage_script.config_reparse_failed",
+                        format!("重新解析配置失败: {e}"),
+                        format!("Failed to re-parse config: {e}"),
+                    )
+                })
             })?;
 
             // 提取 extractor 函数
@@ -159,11 +276,13 @@ pub async fn execute_usage_script(
 
             // 调用 extractor(response)
             let result_js: rquickjs::Value = extractor.call((response_js,)).map_err(|e| {
-                AppError::localized(
-                    "usage_script.extractor_exec_failed",
-                    format!("执行 extractor 失败: {e}"),
-                    format!("Failed to execute extractor: {e}"),
-                )
+                js_error(&ctx, e, deadline, timeout_secs, |e| {
+                    AppError::localized(
+                        "usage_script.extractor_exec_failed",
+                        format!("执行 extractor 失败: {e}"),
+                        format!("Failed to execute extractor: {e}"),
+                    )
+                })
             })?;
 
             // 转换为 JSON 字符串
@@ -220,12 +339,63 @@ struct RequestConfig {
     body: Option<String>,
 }
 
+/// 创建带沙箱限制的 JS 运行时：超过 `deadline` 时中断解释器，并限制内存占用
+fn create_sandboxed_runtime(deadline: Instant) -> Result<Runtime, AppError> {
+    let runtime = Runtime::new().map_err(|e| {
+        AppError::localized(
+            "usage_script.runtime_create_failed",
+            format!("创建 JS 运行时失败: {e}"),
+            format!("Failed to create JS runtime: {e}"),
+        )
+    })?;
+    runtime.set_memory_limit(SCRIPT_MEMORY_LIMIT);
+    runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() >= deadline)));
+    Ok(runtime)
+}
+
+/// 将 JS 执行错误转换为 AppError：超时与内存超限映射为对应的沙箱错误，其余交给 `fallback`
+fn js_error(
+    ctx: &Ctx<'_>,
+    err: rquickjs::Error,
+    deadline: Instant,
+    timeout_secs: u64,
+    fallback: impl FnOnce(String) -> AppError,
+) -> AppError {
+    // 中断处理器触发后 QuickJS 抛出不可捕获的异常，以截止时间判断即可
+    if Instant::now() >= deadline {
+        return SandboxViolation::Timeout(timeout_secs).into();
+    }
+
+    let detail = if err.is_exception() {
+        let caught = ctx.catch();
+        caught
+            .as_exception()
+            .and_then(|exception| exception.message())
+            .unwrap_or_else(|| err.to_string())
+    } else {
+        err.to_string()
+    };
+    if matches!(err, rquickjs::Error::Allocation) || detail.contains("out of memory") {
+        return SandboxViolation::MemoryLimit.into();
+    }
+    fallback(detail)
+}
+
 /// 发送 HTTP 请求
-async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<String, AppError> {
-    // 使用全局 HTTP 客户端（已包含代理配置）
-    let client = crate::proxy::http_client::get();
-    // 约束超时范围，防止异常配置导致长时间阻塞（最小 2 秒，最大 30 秒）
-    let request_timeout = std::time::Duration::from_secs(timeout_secs.clamp(2, 30));
+async fn send_http_request(
+    config: &RequestConfig,
+    request_timeout: Duration,
+    redirect_policy: reqwest::redirect::Policy,
+) -> Result<String, AppError> {
+    // 沿用全局代理配置，重定向按传入的策略逐跳校验
+    let client = crate::proxy::http_client::build_client_with_redirect_policy(redirect_policy)
+        .map_err(|e| {
+            AppError::localized(
+                "usage_script.request_failed",
+                format!("请求失败: {e}"),
+                format!("Request failed: {e}"),
+            )
+        })?;
 
     // 严格校验 HTTP 方法，非法值不回退为 GET
     let method: reqwest::Method = config.method.parse().map_err(|_| {
@@ -252,6 +422,10 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
 
     // 发送请求
     let resp = req.send().await.map_err(|e| {
+        if e.is_redirect() {
+            let target = e.url().map(|u| u.to_string()).unwrap_or_default();
+            return SandboxViolation::RedirectBlocked(target).into();
+        }
         AppError::localized(
             "usage_script.request_failed",
             format!("请求失败: {e}"),
@@ -260,13 +434,7 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
     })?;
 
     let status = resp.status();
-    let text = resp.text().await.map_err(|e| {
-        AppError::localized(
-            "usage_script.read_response_failed",
-            format!("读取响应失败: {e}"),
-            format!("Failed to read response: {e}"),
-        )
-    })?;
+    let text = read_limited_body(resp).await?;
 
     if !status.is_success() {
         let preview = if text.len() > 200 {
@@ -288,6 +456,31 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
     Ok(text)
 }
 
+/// 读取响应体，超过 [`MAX_RESPONSE_BYTES`] 时中止读取
+async fn read_limited_body(mut resp: reqwest::Response) -> Result<String, AppError> {
+    if resp
+        .content_length()
+        .is_some_and(|len| len > MAX_RESPONSE_BYTES as u64)
+    {
+        return Err(SandboxViolation::ResponseTooLarge.into());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| {
+        AppError::localized(
+            "usage_script.read_response_failed",
+            format!("读取响应失败: {e}"),
+            format!("Failed to read response: {e}"),
+        )
+    })? {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(SandboxViolation::ResponseTooLarge.into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
 /// 验证脚本返回值（支持单对象或数组）
 fn validate_result(result: &Value) -> Result<(), AppError> {
     // 如果是数组，验证每个元素
@@ -706,6 +899,40 @@ fn is_suspicious_hostname(hostname: &str) -> bool {
     false
 }
 
+/// 校验请求域名是否在网络白名单中（白名单为空时不限制）
+fn validate_allowed_host(request_url: &str, allowed_hosts: &[String]) -> Result<(), AppError> {
+    let patterns: Vec<&str> = allowed_hosts
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let host = Url::parse(request_url)
+        .ok()
+        .and_then(|url| url.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
+    if patterns.iter().any(|p| host_matches_pattern(&host, p)) {
+        Ok(())
+    } else {
+        Err(SandboxViolation::HostNotAllowed(host).into())
+    }
+}
+
+/// 域名匹配（不区分大小写）：`*.example.com` 匹配任意子域名，但不匹配 `example.com` 本身
+fn host_matches_pattern(host: &str, pattern: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let pattern = pattern.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
 /// 判断 URL 是否指向本机（localhost / loopback）
 fn is_loopback_host(url: &Url) -> bool {
     match url.host() {
@@ -832,6 +1059,106 @@ mod tests {
         assert!(!is_private_ip_addr(ipv6_public));
     }
 
+    fn sandbox_error_key(err: AppError) -> &'static str {
+        match err {
+            AppError::Localized { key, .. } => key,
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_infinite_loop_is_interrupted() {
+        let started = Instant::now();
+        let sandbox = ScriptSandbox::new(Some(2), None);
+        let err = execute_usage_script(
+            "while (true) {}",
+            "",
+            "",
+            None,
+            None,
+            Some("custom"),
+            &sandbox,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(sandbox_error_key(err), "usage_script.sandbox_timeout");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_host_is_rejected() {
+        let script = r#"({
+            request: { url: "https://evil.example.net/steal", method: "GET" },
+            extractor: function (response) { return { remaining: 1 }; }
+        })"#;
+        let allowed = vec!["api.example.com".to_string(), "*.example.org".to_string()];
+        let sandbox = ScriptSandbox::new(Some(5), Some(&allowed));
+        let err = execute_usage_script(script, "", "", None, None, Some("custom"), &sandbox)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            sandbox_error_key(err),
+            "usage_script.sandbox_host_not_allowed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_disallowed_host_is_blocked() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 302 Found\r\nLocation: https://evil.example.net/steal\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+        });
+
+        let script = format!(
+            r#"({{
+            request: {{ url: "http://127.0.0.1:{port}/usage", method: "GET" }},
+            extractor: function (response) {{ return {{ remaining: 1 }}; }}
+        }})"#
+        );
+        let allowed = vec!["127.0.0.1".to_string()];
+        let sandbox = ScriptSandbox::new(Some(5), Some(&allowed));
+        let err = execute_usage_script(&script, "", "", None, None, Some("custom"), &sandbox)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            sandbox_error_key(err),
+            "usage_script.sandbox_redirect_blocked"
+        );
+    }
+
+    #[test]
+    fn test_host_pattern_matching() {
+        assert!(host_matches_pattern("api.example.com", "api.example.com"));
+        assert!(host_matches_pattern("API.Example.com", "api.example.COM"));
+        assert!(host_matches_pattern("a.b.example.com", "*.example.com"));
+        assert!(!host_matches_pattern("example.com", "*.example.com"));
+        assert!(!host_matches_pattern("evilexample.com", "*.example.com"));
+        assert!(!host_matches_pattern(
+            "api.example.com.evil.io",
+            "api.example.com"
+        ));
+
+        assert!(validate_allowed_host("https://x.example.com/a", &[]).is_ok());
+        assert!(validate_allowed_host("https://x.example.com/a", &[" ".to_string()]).is_ok());
+        assert!(
+            validate_allowed_host("https://x.example.com/a", &["*.example.com".into()]).is_ok()
+        );
+        assert!(validate_allowed_host("https://other.io/a", &["*.example.com".into()]).is_err());
+    }
+
     #[test]
     fn test_port_comparison() {
         // 测试端口比较逻辑是否正确处理默认端口和显式端口
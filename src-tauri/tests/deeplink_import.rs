@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
+use base64::prelude::*;
 use cc_switch_lib::{
-    import_provider_from_deeplink, parse_deeplink_url, AppState, Database, ProxyService,
+    import_provider_batch_from_deeplink, import_provider_from_deeplink,
+    import_provider_from_deeplink_with_strategy, parse_deeplink_url, AppState, Database,
+    ImportAction, MergeStrategy, ProxyService,
 };
 
 #[path = "support.rs"]
@@ -94,3 +97,126 @@ fn deeplink_import_codex_provider_builds_auth_and_config() {
         "config.toml content should contain model setting"
     );
 }
+
+#[test]
+fn deeplink_import_merge_strategies_handle_existing_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let url = "ccswitch://v1/import?resource=provider&app=claude&name=Relay&homepage=https%3A%2F%2Fexample.com&endpoint=https%3A%2F%2Fapi.example.com&apiKey=sk-first";
+    let request = parse_deeplink_url(url).expect("parse deeplink url");
+
+    let db = Arc::new(Database::memory().expect("create memory db"));
+    let proxy_service = ProxyService::new(db.clone());
+    let state = AppState {
+        db: db.clone(),
+        proxy_service,
+    };
+
+    // 未指定策略时保持原有行为：始终新建
+    let first_id = import_provider_from_deeplink(&state, request.clone()).expect("first import");
+
+    let skipped = import_provider_from_deeplink_with_strategy(
+        &state,
+        request.clone(),
+        Some(MergeStrategy::SkipExisting),
+    )
+    .expect("skip import");
+    assert_eq!(skipped.action, ImportAction::Skipped);
+    assert_eq!(skipped.id, first_id);
+
+    let mut updated = request.clone();
+    updated.api_key = Some("sk-second".to_string());
+    let merged = import_provider_from_deeplink_with_strategy(
+        &state,
+        updated.clone(),
+        Some(MergeStrategy::MergeFields),
+    )
+    .expect("merge import");
+    assert_eq!(merged.action, ImportAction::Merged);
+    assert_eq!(merged.id, first_id);
+    let provider = db
+        .get_provider_by_id(&first_id, "claude")
+        .expect("get provider")
+        .expect("provider exists");
+    assert_eq!(
+        provider
+            .settings_config
+            .pointer("/env/ANTHROPIC_AUTH_TOKEN")
+            .and_then(|v| v.as_str()),
+        Some("sk-second")
+    );
+
+    let renamed =
+        import_provider_from_deeplink_with_strategy(&state, updated, Some(MergeStrategy::Rename))
+            .expect("rename import");
+    assert_eq!(renamed.action, ImportAction::Renamed);
+    assert_ne!(renamed.id, first_id);
+    let providers = db.get_all_providers("claude").expect("get providers");
+    assert_eq!(providers.len(), 2);
+    assert_eq!(providers[&renamed.id].name, "Relay (2)");
+}
+
+#[test]
+fn deeplink_batch_import_suffixes_ids_and_switches_last_enabled() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let db = Arc::new(Database::memory().expect("create memory db"));
+    let proxy_service = ProxyService::new(db.clone());
+    let state = AppState {
+        db: db.clone(),
+        proxy_service,
+    };
+
+    let existing = parse_deeplink_url(
+        "ccswitch://v1/import?resource=provider&app=claude&name=Relay%20A&endpoint=https%3A%2F%2Fa.example.com&apiKey=sk-old",
+    )
+    .expect("parse single deeplink");
+    let existing_id = import_provider_from_deeplink(&state, existing).expect("seed provider");
+
+    let providers = serde_json::json!([
+        { "app": "claude", "name": "Relay A", "endpoint": "https://a.example.com", "apiKey": "sk-a", "enabled": true },
+        { "app": "claude", "name": "Relay B", "endpoint": "https://b.example.com", "apiKey": "sk-b", "enabled": true },
+        { "app": "claude", "name": "Relay C", "endpoint": "https://c.example.com", "apiKey": "sk-c", "enabled": true },
+        { "app": "codex", "name": "Relay D", "endpoint": "https://d.example.com/v1", "apiKey": "sk-d" },
+    ]);
+    let encoded = BASE64_URL_SAFE_NO_PAD.encode(providers.to_string());
+    let request = parse_deeplink_url(&format!(
+        "ccswitch://v1/import?resource=batch&providers={encoded}"
+    ))
+    .expect("parse batch deeplink");
+
+    let result =
+        import_provider_batch_from_deeplink(&state, request, Some(MergeStrategy::SkipExisting))
+            .expect("import batch");
+    assert!(result.failed.is_empty(), "{:?}", result.failed);
+    assert_eq!(result.providers.len(), 4);
+
+    // 已存在的同名供应商按策略跳过，且不会被设为当前供应商
+    assert_eq!(result.providers[0].id, existing_id);
+    assert_eq!(result.providers[0].action, ImportAction::Skipped);
+
+    // 新建条目的 ID 追加批次序号，彼此不冲突
+    for (i, outcome) in result.providers.iter().enumerate().skip(1) {
+        assert_eq!(outcome.action, ImportAction::Added);
+        assert!(outcome.id.ends_with(&format!("-{i}")), "{}", outcome.id);
+    }
+    assert_ne!(result.providers[1].id, result.providers[2].id);
+
+    let claude = db
+        .get_all_providers("claude")
+        .expect("get claude providers");
+    assert_eq!(claude.len(), 3);
+    assert_eq!(
+        db.get_current_provider("claude").expect("current claude"),
+        Some(result.providers[2].id.clone()),
+        "the last enabled item becomes current"
+    );
+    assert!(db
+        .get_all_providers("codex")
+        .expect("get codex providers")
+        .contains_key(&result.providers[3].id));
+}
@@ -3,8 +3,8 @@ use std::fs;
 use std::path::PathBuf;
 
 use cc_switch_lib::{
-    get_claude_settings_path, read_json_file, AppError, AppType, ConfigService, MultiAppConfig,
-    Provider, ProviderMeta,
+    get_claude_settings_path, read_json_file, AppError, AppType, ConfigService, ImportResolution,
+    MultiAppConfig, Provider, ProviderMeta,
 };
 
 #[path = "support.rs"]
@@ -1074,3 +1074,143 @@ fn import_sql_accepts_cc_switch_exported_backup() {
         "imported providers should contain test-provider"
     );
 }
+
+#[test]
+fn export_sql_omits_sync_credentials_and_import_keeps_local_ones() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "p1".to_string(),
+            Provider::with_id(
+                "p1".to_string(),
+                "P1".to_string(),
+                json!({"env": {"ANTHROPIC_API_KEY": "test-key"}}),
+                None,
+            ),
+        );
+    let state = create_test_state_with_config(&config).expect("create test state");
+    state
+        .db
+        .set_setting("sync_config", r#"{"password":"exported-secret"}"#)
+        .expect("save sync config");
+    state
+        .db
+        .set_setting("language", "en")
+        .expect("save language");
+    let export_path = home.join("cc-switch-sync-secret.sql");
+    state
+        .db
+        .export_sql(&export_path)
+        .expect("export should succeed");
+    let dump = fs::read_to_string(&export_path).expect("read export");
+    assert!(!dump.contains("exported-secret"));
+    assert!(dump.contains("language"));
+
+    reset_test_fs();
+    let state = create_test_state().expect("create test state");
+    state
+        .db
+        .set_setting("sync_config", r#"{"password":"local-secret"}"#)
+        .expect("save local sync config");
+    state
+        .db
+        .import_sql(&export_path)
+        .expect("import should succeed");
+    assert_eq!(
+        state
+            .db
+            .get_setting("sync_config")
+            .expect("read sync config"),
+        Some(r#"{"password":"local-secret"}"#.to_string())
+    );
+}
+
+#[test]
+fn selective_import_previews_and_applies_resolutions() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let provider = |id: &str, name: &str| {
+        Provider::with_id(
+            id.to_string(),
+            name.to_string(),
+            json!({"env": {"ANTHROPIC_API_KEY": "test-key"}}),
+            None,
+        )
+    };
+
+    // 导出端：a（名称不同，产生冲突）+ b（本地不存在）
+    let mut export_config = MultiAppConfig::default();
+    {
+        let manager = export_config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "a".to_string();
+        manager
+            .providers
+            .insert("a".to_string(), provider("a", "Imported A"));
+        manager
+            .providers
+            .insert("b".to_string(), provider("b", "Imported B"));
+    }
+    let state = create_test_state_with_config(&export_config).expect("create export state");
+    let export_path = home.join("cc-switch-selective.sql");
+    state
+        .db
+        .export_sql(&export_path)
+        .expect("export should succeed");
+
+    // 本地端：仅有 a
+    reset_test_fs();
+    let mut local_config = MultiAppConfig::default();
+    {
+        let manager = local_config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "a".to_string();
+        manager
+            .providers
+            .insert("a".to_string(), provider("a", "Local A"));
+    }
+    let state = create_test_state_with_config(&local_config).expect("create local state");
+
+    let preview = state
+        .db
+        .preview_import(&export_path, None)
+        .expect("preview should succeed");
+    assert_eq!(preview.conflict_count, 1);
+    let conflict = preview
+        .items
+        .iter()
+        .find(|item| item.key == "provider:claude:a")
+        .expect("conflict item for provider a");
+    assert!(conflict.diff.iter().any(|d| d.field == "name"));
+
+    let resolutions = [(
+        "provider:claude:a".to_string(),
+        ImportResolution::DuplicateRename,
+    )]
+    .into_iter()
+    .collect();
+    let result = state
+        .db
+        .apply_import(&export_path, None, &resolutions)
+        .expect("apply should succeed");
+    assert_eq!(result.renamed, 1);
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("load providers");
+    assert_eq!(providers["a"].name, "Local A");
+    assert_eq!(providers["a-imported"].name, "Imported A (imported)");
+    assert_eq!(providers["b"].name, "Imported B");
+}
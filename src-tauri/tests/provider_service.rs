@@ -1,8 +1,9 @@
 use serde_json::json;
 
 use cc_switch_lib::{
-    get_claude_settings_path, read_json_file, write_codex_live_atomic, AppError, AppType, McpApps,
-    McpServer, MultiAppConfig, Provider, ProviderMeta, ProviderService,
+    get_claude_settings_path, read_json_file, switch_provider_blocking, update_settings,
+    write_codex_live_atomic, AppError, AppSettings, AppType, ClaudeWriteMode, ExternalFormat,
+    McpApps, McpServer, MultiAppConfig, Provider, ProviderMeta, ProviderService,
 };
 
 #[path = "support.rs"]
@@ -420,6 +421,561 @@ fn provider_service_switch_claude_updates_live_and_state() {
     );
 }
 
+#[test]
+fn provider_service_claude_profiles_mode_writes_profile_and_strips_settings() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    update_settings(AppSettings {
+        claude_write_mode: ClaudeWriteMode::Profiles,
+        current_provider_claude: Some("old-provider".to_string()),
+        ..AppSettings::default()
+    })
+    .expect("enable profiles mode");
+
+    let claude_dir = home.join(".claude");
+    let profiles_dir = claude_dir.join("profiles");
+    std::fs::create_dir_all(&profiles_dir).expect("create profiles dir");
+    let settings_path = claude_dir.join("settings.json");
+    std::fs::write(
+        &settings_path,
+        // 从 replace 模式切换过来时 settings.json 中残留的供应商配置
+        serde_json::to_string_pretty(&json!({
+            "theme": "dark",
+            "model": "opus",
+            "env": { "ANTHROPIC_API_KEY": "stale-key", "DISABLE_TELEMETRY": "1" }
+        }))
+        .expect("serialize settings"),
+    )
+    .expect("seed settings.json");
+    let edited_live = json!({ "env": { "ANTHROPIC_API_KEY": "edited-key" } });
+    std::fs::write(
+        profiles_dir.join("old-provider.json"),
+        serde_json::to_string_pretty(&edited_live).expect("serialize profile"),
+    )
+    .expect("seed old profile");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        for (id, key) in [("old-provider", "old-key"), ("new-provider", "fresh-key")] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({ "env": { "ANTHROPIC_API_KEY": key } }),
+                    None,
+                ),
+            );
+        }
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .expect("switch provider should succeed");
+
+    let new_profile: serde_json::Value =
+        read_json_file(&profiles_dir.join("new-provider.json")).expect("read new profile");
+    assert_eq!(
+        new_profile["env"]["ANTHROPIC_API_KEY"], "fresh-key",
+        "full provider config should be written to its profile"
+    );
+    let settings_after: serde_json::Value =
+        read_json_file(&settings_path).expect("read settings.json");
+    assert_eq!(
+        settings_after,
+        json!({ "theme": "dark", "env": { "DISABLE_TELEMETRY": "1" } }),
+        "settings.json should keep user settings and drop provider env and model keys"
+    );
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("get all providers");
+    assert_eq!(
+        providers["old-provider"].settings_config, edited_live,
+        "backfill should read from the current provider's profile"
+    );
+
+    ProviderService::delete(&state, AppType::Claude, "old-provider").expect("delete old provider");
+    assert!(
+        !profiles_dir.join("old-provider.json").exists(),
+        "deleting a provider should remove its profile file"
+    );
+
+    update_settings(AppSettings::default()).expect("reset settings");
+}
+
+#[test]
+fn provider_service_restore_live_config_version_writes_back_and_backfills() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent).expect("create claude settings dir");
+    }
+    let legacy_text = serde_json::to_string_pretty(&json!({
+        "env": { "ANTHROPIC_API_KEY": "legacy-key" }
+    }))
+    .expect("serialize legacy live");
+    std::fs::write(&settings_path, &legacy_text).expect("seed claude live config");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        for (id, key) in [("old-provider", "stale-key"), ("new-provider", "fresh-key")] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({ "env": { "ANTHROPIC_API_KEY": key } }),
+                    None,
+                ),
+            );
+        }
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .expect("switch provider should succeed");
+
+    let history =
+        ProviderService::list_live_config_history(&state, AppType::Claude).expect("list history");
+    assert_eq!(history.len(), 1, "previous live file should be recorded");
+    let legacy_version = history[0].id;
+
+    ProviderService::restore_live_config_version(&state, AppType::Claude, legacy_version)
+        .expect("restore live config version");
+
+    assert_eq!(
+        std::fs::read_to_string(&settings_path).expect("read restored live"),
+        legacy_text,
+        "live file should be restored byte for byte"
+    );
+    let current = state
+        .db
+        .get_provider_by_id("new-provider", AppType::Claude.as_str())
+        .expect("get provider")
+        .expect("current provider exists");
+    assert_eq!(
+        current.settings_config,
+        json!({ "env": { "ANTHROPIC_API_KEY": "legacy-key" } }),
+        "restored content should be backfilled into the current provider"
+    );
+
+    let history = ProviderService::list_live_config_history(&state, AppType::Claude)
+        .expect("list history after restore");
+    assert_eq!(history.len(), 2, "restore keeps the overwritten content");
+
+    let err = ProviderService::restore_live_config_version(&state, AppType::Codex, legacy_version)
+        .expect_err("version belongs to another app");
+    assert!(matches!(err, AppError::Localized { key, .. } if key == "live_history.not_found"));
+}
+
+#[test]
+fn provider_service_switch_skips_backfill_when_disabled() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent).expect("create claude settings dir");
+    }
+    std::fs::write(&settings_path, "{ intentionally broken").expect("seed broken live config");
+
+    let stored_old = json!({
+        "env": { "ANTHROPIC_API_KEY": "stored-key" }
+    });
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        manager.providers.insert(
+            "old-provider".to_string(),
+            Provider::with_id(
+                "old-provider".to_string(),
+                "Old Claude".to_string(),
+                stored_old.clone(),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "new-provider".to_string(),
+            Provider::with_id(
+                "new-provider".to_string(),
+                "New Claude".to_string(),
+                json!({
+                    "env": { "ANTHROPIC_API_KEY": "fresh-key" }
+                }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    assert!(state
+        .db
+        .get_backfill_on_switch_enabled(AppType::Claude.as_str())
+        .expect("read default backfill setting"));
+    state
+        .db
+        .set_backfill_on_switch_enabled(AppType::Claude.as_str(), false)
+        .expect("disable backfill");
+
+    ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .expect("switch provider should succeed");
+
+    let live_after: serde_json::Value =
+        read_json_file(&settings_path).expect("read claude live settings");
+    assert_eq!(
+        live_after
+            .get("env")
+            .and_then(|env| env.get("ANTHROPIC_API_KEY"))
+            .and_then(|key| key.as_str()),
+        Some("fresh-key"),
+        "live settings.json should reflect new provider"
+    );
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("get all providers");
+    assert_eq!(
+        providers
+            .get("old-provider")
+            .expect("old provider still exists")
+            .settings_config,
+        stored_old,
+        "outgoing provider should keep its stored config when backfill is disabled"
+    );
+}
+
+#[test]
+fn provider_service_switch_in_read_only_mode_only_updates_current() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent).expect("create claude settings dir");
+    }
+    let original_live = json!({
+        "env": { "ANTHROPIC_API_KEY": "live-key" }
+    });
+    std::fs::write(
+        &settings_path,
+        serde_json::to_string_pretty(&original_live).expect("serialize live"),
+    )
+    .expect("seed claude live config");
+
+    let stored_old = json!({
+        "env": { "ANTHROPIC_API_KEY": "stored-key" }
+    });
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        manager.providers.insert(
+            "old-provider".to_string(),
+            Provider::with_id(
+                "old-provider".to_string(),
+                "Old Claude".to_string(),
+                stored_old.clone(),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "new-provider".to_string(),
+            Provider::with_id(
+                "new-provider".to_string(),
+                "New Claude".to_string(),
+                json!({ "env": { "ANTHROPIC_API_KEY": "fresh-key" } }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    update_settings(AppSettings {
+        read_only_mode: true,
+        ..AppSettings::default()
+    })
+    .expect("enable read-only mode");
+
+    ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .expect("switch should still update the current marker");
+
+    let current_id = state
+        .db
+        .get_current_provider(AppType::Claude.as_str())
+        .expect("get current provider");
+    assert_eq!(current_id.as_deref(), Some("new-provider"));
+
+    let live_after: serde_json::Value =
+        read_json_file(&settings_path).expect("read claude live settings");
+    assert_eq!(
+        live_after, original_live,
+        "live settings.json must not be touched in read-only mode"
+    );
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("get all providers");
+    assert_eq!(
+        providers
+            .get("old-provider")
+            .expect("old provider still exists")
+            .settings_config,
+        stored_old,
+        "read-only switch must not backfill the outgoing provider"
+    );
+
+    let err =
+        ProviderService::restore_live_from_stored(&state, AppType::Claude, "new-provider", false)
+            .expect_err("live writes should be rejected in read-only mode");
+    assert!(matches!(err, AppError::ReadOnlyMode));
+
+    let _ = update_settings(AppSettings::default());
+}
+
+#[test]
+fn provider_service_switch_with_options_verifies_unless_forced() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        manager.providers.insert(
+            "old-provider".to_string(),
+            Provider::with_id(
+                "old-provider".to_string(),
+                "Old Claude".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "old-key" } }),
+                None,
+            ),
+        );
+        // 端口 1 上没有服务，健康检查必然失败
+        manager.providers.insert(
+            "unreachable".to_string(),
+            Provider::with_id(
+                "unreachable".to_string(),
+                "Unreachable Claude".to_string(),
+                json!({
+                    "env": {
+                        "ANTHROPIC_AUTH_TOKEN": "new-key",
+                        "ANTHROPIC_BASE_URL": "http://127.0.0.1:1"
+                    }
+                }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    update_settings(AppSettings {
+        verify_before_switch: true,
+        ..AppSettings::default()
+    })
+    .expect("enable verify before switch");
+
+    let err = ProviderService::switch_with_options(&state, AppType::Claude, "unreachable", false)
+        .expect_err("failed probe should abort the switch");
+    assert!(
+        matches!(err, AppError::ProviderVerificationFailed { ref provider_id, .. } if provider_id == "unreachable")
+    );
+    let current_id = state
+        .db
+        .get_current_provider(AppType::Claude.as_str())
+        .expect("get current provider");
+    assert_eq!(current_id.as_deref(), Some("old-provider"));
+
+    ProviderService::switch_with_options(&state, AppType::Claude, "unreachable", true)
+        .expect("forced switch should skip the probe");
+    let current_id = state
+        .db
+        .get_current_provider(AppType::Claude.as_str())
+        .expect("get current provider");
+    assert_eq!(current_id.as_deref(), Some("unreachable"));
+
+    let _ = update_settings(AppSettings::default());
+}
+
+#[test]
+fn switch_provider_command_reports_verify_failed_code_unless_forced() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        manager.providers.insert(
+            "old-provider".to_string(),
+            Provider::with_id(
+                "old-provider".to_string(),
+                "Old Claude".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "old-key" } }),
+                None,
+            ),
+        );
+        // 端口 1 上没有服务，健康检查必然失败
+        manager.providers.insert(
+            "unreachable".to_string(),
+            Provider::with_id(
+                "unreachable".to_string(),
+                "Unreachable Claude".to_string(),
+                json!({
+                    "env": {
+                        "ANTHROPIC_AUTH_TOKEN": "new-key",
+                        "ANTHROPIC_BASE_URL": "http://127.0.0.1:1"
+                    }
+                }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    update_settings(AppSettings {
+        verify_before_switch: true,
+        ..AppSettings::default()
+    })
+    .expect("enable verify before switch");
+
+    let err = switch_provider_blocking(&state, AppType::Claude, "unreachable", false)
+        .err()
+        .expect("failed probe should abort the switch");
+    let payload: serde_json::Value =
+        serde_json::from_str(&err).expect("verify failure should be a JSON payload");
+    assert_eq!(payload["code"], "provider.verify_failed");
+    assert_eq!(payload["providerId"], "unreachable");
+    assert_eq!(
+        state
+            .db
+            .get_current_provider(AppType::Claude.as_str())
+            .expect("get current provider")
+            .as_deref(),
+        Some("old-provider")
+    );
+
+    // force: true 跳过健康检查，端口 1 不可达也能切换
+    switch_provider_blocking(&state, AppType::Claude, "unreachable", true)
+        .expect("forced switch should skip the probe");
+    assert_eq!(
+        state
+            .db
+            .get_current_provider(AppType::Claude.as_str())
+            .expect("get current provider")
+            .as_deref(),
+        Some("unreachable")
+    );
+
+    let _ = update_settings(AppSettings::default());
+}
+
+#[test]
+fn provider_service_restore_live_rewrites_from_stored_config() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent).expect("create claude settings dir");
+    }
+    std::fs::write(&settings_path, "{ corrupted by another tool").expect("seed broken live");
+
+    let stored = json!({
+        "env": { "ANTHROPIC_API_KEY": "stored-key" }
+    });
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "current-provider".to_string();
+        manager.providers.insert(
+            "current-provider".to_string(),
+            Provider::with_id(
+                "current-provider".to_string(),
+                "Current Claude".to_string(),
+                stored.clone(),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "other-provider".to_string(),
+            Provider::with_id(
+                "other-provider".to_string(),
+                "Other Claude".to_string(),
+                json!({ "env": { "ANTHROPIC_API_KEY": "other-key" } }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    let err =
+        ProviderService::restore_live_from_stored(&state, AppType::Claude, "other-provider", false)
+            .expect_err("restoring a non-current provider should fail");
+    assert!(matches!(err, AppError::Localized { .. }));
+
+    ProviderService::restore_live_from_stored(&state, AppType::Claude, "current-provider", false)
+        .expect("restore live config should succeed");
+
+    let live_after: serde_json::Value =
+        read_json_file(&settings_path).expect("read restored claude live settings");
+    assert_eq!(
+        live_after
+            .get("env")
+            .and_then(|env| env.get("ANTHROPIC_API_KEY"))
+            .and_then(|key| key.as_str()),
+        Some("stored-key"),
+        "live settings.json should be rewritten from the stored config"
+    );
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("get all providers");
+    assert_eq!(
+        providers
+            .get("current-provider")
+            .expect("current provider exists")
+            .settings_config,
+        stored,
+        "restore must not backfill the stored config"
+    );
+}
+
 #[test]
 fn provider_service_switch_missing_provider_returns_error() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -649,3 +1205,412 @@ fn provider_service_delete_current_provider_returns_error() {
         other => panic!("expected Config/Message error, got {other:?}"),
     }
 }
+
+#[test]
+fn provider_service_search_filters_by_text_and_category() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        let mut official = Provider::with_id(
+            "official".to_string(),
+            "Claude Official".to_string(),
+            json!({ "env": {} }),
+            Some("https://www.anthropic.com".to_string()),
+        );
+        official.category = Some("official".to_string());
+        manager.providers.insert("official".to_string(), official);
+
+        let mut relay = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({ "env": {} }),
+            Some("https://relay.example.com".to_string()),
+        );
+        relay.category = Some("third_party".to_string());
+        relay.notes = Some("Backup for Team Account".to_string());
+        manager.providers.insert("relay".to_string(), relay);
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    let by_notes =
+        ProviderService::search(&state, AppType::Claude, "team", None).expect("search by notes");
+    assert_eq!(by_notes.keys().collect::<Vec<_>>(), vec!["relay"]);
+
+    let by_url = ProviderService::search(&state, AppType::Claude, "ANTHROPIC.com", None)
+        .expect("search by website url");
+    assert_eq!(by_url.keys().collect::<Vec<_>>(), vec!["official"]);
+
+    let by_category = ProviderService::search(&state, AppType::Claude, "", Some("third_party"))
+        .expect("search by category");
+    assert_eq!(by_category.keys().collect::<Vec<_>>(), vec!["relay"]);
+
+    let none = ProviderService::search(&state, AppType::Claude, "relay", Some("official"))
+        .expect("search with mismatched category");
+    assert!(none.is_empty());
+}
+
+#[test]
+fn provider_service_normalize_all_claude_providers_updates_legacy_only() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "legacy".to_string(),
+            Provider::with_id(
+                "legacy".to_string(),
+                "Legacy".to_string(),
+                json!({ "env": {
+                    "ANTHROPIC_MODEL": "claude-sonnet-4",
+                    "ANTHROPIC_SMALL_FAST_MODEL": "claude-haiku-4"
+                } }),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "plain".to_string(),
+            Provider::with_id(
+                "plain".to_string(),
+                "Plain".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token" } }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    let count =
+        ProviderService::normalize_all_claude_providers(&state).expect("normalize providers");
+    assert_eq!(count, 1, "only the legacy provider should change");
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    let env = &providers["legacy"].settings_config["env"];
+    assert!(env.get("ANTHROPIC_SMALL_FAST_MODEL").is_none());
+    assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "claude-haiku-4");
+    assert_eq!(env["ANTHROPIC_DEFAULT_SONNET_MODEL"], "claude-sonnet-4");
+    assert_eq!(
+        providers["plain"].settings_config,
+        json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token" } })
+    );
+
+    let again = ProviderService::normalize_all_claude_providers(&state).expect("normalize again");
+    assert_eq!(again, 0, "second run should be a no-op");
+}
+
+#[test]
+fn provider_service_import_external_claude_code_router_upserts_providers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config_path = home.join("ccr-config.json");
+    let write_config = |api_key: &str| {
+        let config = json!({
+            "PORT": 3456,
+            "Providers": [{
+                "name": "deepseek",
+                "api_base_url": "https://api.deepseek.com/chat/completions",
+                "api_key": api_key,
+                "models": ["deepseek-chat"],
+                "transformer": { "use": ["deepseek"] }
+            }]
+        });
+        std::fs::write(&config_path, config.to_string()).expect("write ccr config");
+    };
+
+    write_config("sk-first");
+    let state = create_test_state().expect("create test state");
+    let result =
+        ProviderService::import_external(&state, ExternalFormat::ClaudeCodeRouter, &config_path)
+            .expect("import ccr config");
+    assert_eq!(result.imported.len(), 1);
+    assert_eq!(result.imported[0].id, "ccr-deepseek");
+    assert!(result.unmapped.contains(&"PORT".to_string()));
+    assert!(result
+        .unmapped
+        .contains(&"Providers[0].transformer".to_string()));
+
+    // 没有当前供应商时，首个导入项成为当前并写入 Live
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live settings");
+    assert_eq!(
+        live["env"]["ANTHROPIC_BASE_URL"],
+        "https://api.deepseek.com"
+    );
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-first");
+
+    // 重复导入覆盖同一供应商，不会新增
+    write_config("sk-second");
+    ProviderService::import_external(&state, ExternalFormat::ClaudeCodeRouter, &config_path)
+        .expect("re-import ccr config");
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    assert_eq!(providers.len(), 1);
+    let provider = &providers["ccr-deepseek"];
+    assert_eq!(
+        provider.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        "sk-second"
+    );
+    assert_eq!(
+        provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.api_format.as_deref()),
+        Some("openai_chat")
+    );
+}
+
+#[test]
+fn provider_service_import_from_env_reuses_identical_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("ANTHROPIC_BASE_URL", "https://relay.example.com/");
+    std::env::set_var("ANTHROPIC_AUTH_TOKEN", "sk-env");
+
+    let state = create_test_state().expect("create test state");
+    let first = ProviderService::import_from_env(&state, AppType::Claude).expect("import from env");
+    let second =
+        ProviderService::import_from_env(&state, AppType::Claude).expect("re-import from env");
+
+    std::env::remove_var("ANTHROPIC_BASE_URL");
+    std::env::remove_var("ANTHROPIC_AUTH_TOKEN");
+
+    assert!(first.created);
+    assert!(first
+        .found
+        .iter()
+        .any(|f| f.var_name == "ANTHROPIC_AUTH_TOKEN"));
+    let id = first.provider_id.expect("provider id");
+
+    // 相同 base_url + key 不会重复创建
+    assert!(!second.created);
+    assert_eq!(second.provider_id.as_deref(), Some(id.as_str()));
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    assert_eq!(providers.len(), 1);
+    let provider = &providers[&id];
+    assert_eq!(provider.name, "Imported from environment");
+    assert_eq!(
+        provider.settings_config["env"]["ANTHROPIC_BASE_URL"],
+        "https://relay.example.com/"
+    );
+}
+
+#[test]
+fn provider_service_apply_named_snippet_merges_into_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token", "DISABLE_TELEMETRY": "0" } }),
+                None,
+            ),
+        );
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    ProviderService::save_config_snippet(
+        &state,
+        AppType::Claude,
+        "telemetry off",
+        r#"{ "env": { "DISABLE_TELEMETRY": "1" } }"#,
+    )
+    .expect("save snippet");
+    assert!(
+        ProviderService::save_config_snippet(&state, AppType::Claude, "broken", "{").is_err(),
+        "invalid JSON snippets should be rejected"
+    );
+
+    let changed = ProviderService::apply_snippet_to_provider(
+        &state,
+        AppType::Claude,
+        "relay",
+        "telemetry off",
+    )
+    .expect("apply snippet");
+    assert!(changed);
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    assert_eq!(
+        providers["relay"].settings_config,
+        json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token", "DISABLE_TELEMETRY": "1" } })
+    );
+
+    let again = ProviderService::apply_snippet_to_provider(
+        &state,
+        AppType::Claude,
+        "relay",
+        "telemetry off",
+    )
+    .expect("apply snippet again");
+    assert!(!again, "re-applying the same snippet is a no-op");
+    assert!(ProviderService::apply_snippet_to_provider(
+        &state,
+        AppType::Claude,
+        "relay",
+        "missing"
+    )
+    .is_err());
+}
+
+#[test]
+fn provider_service_apply_common_snippet_to_all_overwrites_and_syncs_current() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "a".to_string();
+        manager.providers.insert(
+            "a".to_string(),
+            Provider::with_id(
+                "a".to_string(),
+                "A".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token-a", "DISABLE_TELEMETRY": "0" } }),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "b".to_string(),
+            Provider::with_id(
+                "b".to_string(),
+                "B".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token-b", "DISABLE_TELEMETRY": "1" } }),
+                None,
+            ),
+        );
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    assert!(
+        ProviderService::apply_common_snippet_to_all(&state, AppType::Claude).is_err(),
+        "no common snippet has been set yet"
+    );
+
+    state
+        .db
+        .set_config_snippet(
+            AppType::Claude.as_str(),
+            Some(r#"{ "env": { "DISABLE_TELEMETRY": "1" } }"#.to_string()),
+        )
+        .expect("set common snippet");
+
+    let changed = ProviderService::apply_common_snippet_to_all(&state, AppType::Claude)
+        .expect("apply common snippet");
+    assert_eq!(changed, 1, "only provider a differs from the snippet");
+
+    let providers = ProviderService::list(&state, AppType::Claude).expect("list providers");
+    assert_eq!(
+        providers["a"].settings_config,
+        json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token-a", "DISABLE_TELEMETRY": "1" } })
+    );
+
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live settings");
+    assert_eq!(live["env"]["DISABLE_TELEMETRY"], "1");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "token-a");
+
+    assert_eq!(
+        ProviderService::apply_common_snippet_to_all(&state, AppType::Claude).expect("apply again"),
+        0
+    );
+}
+
+#[test]
+fn provider_service_custom_endpoints_normalize_on_add_and_remove() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "relay".to_string();
+        manager.providers.insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-relay" } }),
+                None,
+            ),
+        );
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    ProviderService::add_custom_endpoint(
+        &state,
+        AppType::Claude,
+        "relay",
+        "https://API.Relay.example.com/v1/".to_string(),
+    )
+    .expect("add endpoint");
+
+    let err = ProviderService::add_custom_endpoint(
+        &state,
+        AppType::Claude,
+        "relay",
+        "https://api.relay.example.com/v1".to_string(),
+    )
+    .expect_err("duplicate endpoint should be rejected");
+    assert!(
+        matches!(err, AppError::Localized { key, .. } if key == "provider.endpoint.already_exists")
+    );
+
+    // 旧数据可能以未规范化的形式存储
+    state
+        .db
+        .add_custom_endpoint("claude", "relay", "https://Legacy.example.com/")
+        .expect("insert legacy endpoint");
+
+    ProviderService::remove_custom_endpoint(
+        &state,
+        AppType::Claude,
+        "relay",
+        "https://api.relay.example.com/v1/".to_string(),
+    )
+    .expect("remove trailing-slash variant");
+    ProviderService::remove_custom_endpoint(
+        &state,
+        AppType::Claude,
+        "relay",
+        "https://legacy.example.com".to_string(),
+    )
+    .expect("remove legacy endpoint");
+
+    let endpoints = ProviderService::get_custom_endpoints(&state, AppType::Claude, "relay")
+        .expect("list endpoints");
+    assert!(
+        endpoints.is_empty(),
+        "all endpoint variants should be removed"
+    );
+}
@@ -22,6 +22,7 @@ import type { Provider, VisibleApps } from "@/types";
 import type { EnvConflict } from "@/types/env";
 import { useProvidersQuery, useSettingsQuery } from "@/lib/query";
 import {
+  codexApi,
   providersApi,
   settingsApi,
   type AppId,
@@ -253,6 +254,29 @@ function App() {
     };
   }, [queryClient]);
 
+  // 后台刷新 Codex 额度后直接写入查询缓存，卡片无需重新请求
+  useEffect(() => {
+    let unsubscribe: (() => void) | undefined;
+
+    const setupListener = async () => {
+      try {
+        unsubscribe = await codexApi.onQuotaUpdated(({ providerId, quota }) => {
+          queryClient.setQueryData(["codex-quota", providerId], quota);
+        });
+      } catch (error) {
+        console.error(
+          "[App] Failed to subscribe codex-quota-updated event",
+          error,
+        );
+      }
+    };
+
+    setupListener();
+    return () => {
+      unsubscribe?.();
+    };
+  }, [queryClient]);
+
   // 应用启动时检测所有应用的环境变量冲突
   useEffect(() => {
     const checkEnvOnStartup = async () => {
@@ -298,7 +322,10 @@ function App() {
   }, [t]);
 
   // 应用启动时检查是否刚完成了 Skills 自动导入（统一管理 SSOT）
+  // 导入在窗口显示后于后台执行，完成时（initial-import-finished）再检查一次并刷新数据
   useEffect(() => {
+    let unsubscribe: (() => void) | undefined;
+
     const checkSkillsMigration = async () => {
       try {
         const result = await invoke<{ count: number; error?: string } | null>(
@@ -324,6 +351,34 @@ function App() {
     };
 
     checkSkillsMigration();
+
+    settingsApi
+      .onInitialImportFinished(async () => {
+        await Promise.all([
+          queryClient.invalidateQueries({ queryKey: ["providers"] }),
+          queryClient.invalidateQueries({ queryKey: ["mcp"] }),
+          queryClient.invalidateQueries({ queryKey: ["skills"] }),
+        ]);
+        for (const app of ["claude", "codex", "gemini"]) {
+          window.dispatchEvent(
+            new CustomEvent("prompt-imported", { detail: { app } }),
+          );
+        }
+        await checkSkillsMigration();
+      })
+      .then((fn) => {
+        unsubscribe = fn;
+      })
+      .catch((error) => {
+        console.error(
+          "[App] Failed to subscribe initial-import-finished event",
+          error,
+        );
+      });
+
+    return () => {
+      unsubscribe?.();
+    };
   }, [t, queryClient]);
 
   // 切换应用时检测当前应用的环境变量冲突
@@ -928,20 +983,19 @@ function App() {
             )}
             {currentView === "providers" && (
               <>
+                <ProxyToggle activeApp={activeApp} />
+                {/* OpenCode 为累加模式，按供应商路由，不参与故障转移 */}
                 {activeApp !== "opencode" && (
-                  <>
-                    <ProxyToggle activeApp={activeApp} />
-                    <div
-                      className={cn(
-                        "transition-all duration-300 ease-in-out overflow-hidden",
-                        isCurrentAppTakeoverActive
-                          ? "opacity-100 max-w-[100px] scale-100"
-                          : "opacity-0 max-w-0 scale-75 pointer-events-none",
-                      )}
-                    >
-                      <FailoverToggle activeApp={activeApp} />
-                    </div>
-                  </>
+                  <div
+                    className={cn(
+                      "transition-all duration-300 ease-in-out overflow-hidden",
+                      isCurrentAppTakeoverActive
+                        ? "opacity-100 max-w-[100px] scale-100"
+                        : "opacity-0 max-w-0 scale-75 pointer-events-none",
+                    )}
+                  >
+                    <FailoverToggle activeApp={activeApp} />
+                  </div>
                 )}
 
                 <AppSwitcher
@@ -1,6 +1,10 @@
 import { useState, useEffect, useMemo } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { DeepLinkImportRequest, deeplinkApi } from "@/lib/api/deeplink";
+import {
+  DeepLinkError,
+  DeepLinkImportRequest,
+  deeplinkApi,
+} from "@/lib/api/deeplink";
 import {
   Dialog,
   DialogContent,
@@ -16,12 +20,13 @@ import { useQueryClient } from "@tanstack/react-query";
 import { PromptConfirmation } from "./deeplink/PromptConfirmation";
 import { McpConfirmation } from "./deeplink/McpConfirmation";
 import { SkillConfirmation } from "./deeplink/SkillConfirmation";
+import { BatchConfirmation } from "./deeplink/BatchConfirmation";
 import { ProviderIcon } from "./ProviderIcon";
 
-interface DeeplinkError {
+type DeeplinkError = DeepLinkError & {
   url: string;
   error: string;
-}
+};
 
 export function DeepLinkImportDialog() {
   const { t } = useTranslation();
@@ -56,7 +61,11 @@ export function DeepLinkImportDialog() {
         console.log("Deep link import event received:", event.payload);
 
         // If config is present, merge it to get the complete configuration
-        if (event.payload.config || event.payload.configUrl) {
+        const hasConfig =
+          event.payload.config ||
+          event.payload.configUrl ||
+          event.payload.providers?.some((p) => p.config || p.configUrl);
+        if (hasConfig) {
           try {
             const mergedRequest = await deeplinkApi.mergeDeeplinkConfig(
               event.payload,
@@ -160,6 +169,32 @@ export function DeepLinkImportDialog() {
           });
         } else if (result.type === "mcp") {
           await refreshMcp(result);
+        } else if (result.type === "batch") {
+          const apps = new Set(
+            (request.providers || []).map((provider) => provider.app),
+          );
+          await Promise.all(
+            [...apps].map((app) =>
+              queryClient.invalidateQueries({ queryKey: ["providers", app] }),
+            ),
+          );
+          if (result.failed.length > 0) {
+            toast.warning(t("deeplink.batchPartialSuccess"), {
+              description: t("deeplink.batchPartialSuccessDescription", {
+                failed: result.failed
+                  .map((item) => `${item.name}: ${item.error}`)
+                  .join("; "),
+              }),
+              closeButton: true,
+            });
+          } else {
+            toast.success(t("deeplink.batchImportSuccess"), {
+              description: t("deeplink.batchImportSuccessDescription", {
+                count: result.providers.length,
+              }),
+              closeButton: true,
+            });
+          }
         } else if (result.type === "skill") {
           // Refresh Skills with aggressive strategy
           queryClient.invalidateQueries({
@@ -301,6 +336,8 @@ export function DeepLinkImportDialog() {
         return t("deeplink.importMcp");
       case "skill":
         return t("deeplink.importSkill");
+      case "batch":
+        return t("deeplink.importBatch");
       default:
         return t("deeplink.confirmImport");
     }
@@ -315,6 +352,8 @@ export function DeepLinkImportDialog() {
         return t("deeplink.importMcpDescription");
       case "skill":
         return t("deeplink.importSkillDescription");
+      case "batch":
+        return t("deeplink.importBatchDescription");
       default:
         return t("deeplink.confirmImportDescription");
     }
@@ -342,6 +381,9 @@ export function DeepLinkImportDialog() {
               {request.resource === "skill" && (
                 <SkillConfirmation request={request} />
               )}
+              {request.resource === "batch" && (
+                <BatchConfirmation request={request} />
+              )}
 
               {/* Legacy Provider View */}
               {(request.resource === "provider" || !request.resource) && (
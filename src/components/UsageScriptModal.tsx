@@ -282,6 +282,7 @@ const UsageScriptModal: React.FC<UsageScriptModalProps> = ({
         script.accessToken,
         script.userId,
         selectedTemplate as "custom" | "general" | "newapi" | undefined,
+        script.allowedHosts,
       );
       if (result.success && result.data && result.data.length > 0) {
         const summary = result.data
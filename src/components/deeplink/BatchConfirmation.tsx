@@ -0,0 +1,49 @@
+import { useTranslation } from "react-i18next";
+import { DeepLinkImportRequest } from "../../lib/api/deeplink";
+
+export function BatchConfirmation({
+  request,
+}: {
+  request: DeepLinkImportRequest;
+}) {
+  const { t } = useTranslation();
+  const providers = request.providers || [];
+  const hasEnabled = providers.some((provider) => provider.enabled);
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <label className="block text-sm font-medium text-muted-foreground">
+          {t("deeplink.batchProviderCount", { count: providers.length })}
+        </label>
+        <div className="mt-1 space-y-2 max-h-64 overflow-auto border rounded p-2 bg-muted/30">
+          {providers.map((provider, index) => (
+            <div
+              key={`${provider.app}-${provider.name}-${index}`}
+              className="p-2 bg-background rounded border"
+            >
+              <div className="flex items-center gap-2">
+                <span className="font-semibold text-sm">{provider.name}</span>
+                <span className="px-2 py-0.5 bg-primary/10 text-primary text-xs rounded capitalize">
+                  {provider.app}
+                </span>
+              </div>
+              {provider.endpoint && (
+                <div className="text-xs text-muted-foreground mt-1 font-mono truncate">
+                  {provider.endpoint.split(",")[0].trim()}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      </div>
+
+      {hasEnabled && (
+        <div className="text-yellow-600 dark:text-yellow-500 text-sm flex items-center gap-2">
+          <span>⚠️</span>
+          <span>{t("deeplink.batchEnabledWarning")}</span>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -93,15 +93,18 @@ const PromptPanel = React.forwardRef<PromptPanelHandle, PromptPanelProps>(
 
     const promptEntries = useMemo(() => Object.entries(prompts), [prompts]);
 
-    const enabledPrompt = promptEntries.find(([_, p]) => p.enabled);
+    // 可同时启用多个提示词，按写入顺序展示
+    const enabledNames = promptEntries
+      .filter(([_, p]) => p.enabled)
+      .map(([_, p]) => p.name);
 
     return (
       <div className="flex flex-col h-[calc(100vh-8rem)] px-6">
         <div className="flex-shrink-0 py-4 glass rounded-xl border border-white/10 mb-4 px-6">
           <div className="text-sm text-muted-foreground">
             {t("prompts.count", { count: promptEntries.length })} ·{" "}
-            {enabledPrompt
-              ? t("prompts.enabledName", { name: enabledPrompt[1].name })
+            {enabledNames.length > 0
+              ? t("prompts.enabledName", { name: enabledNames.join(", ") })
               : t("prompts.noneEnabled")}
           </div>
         </div>
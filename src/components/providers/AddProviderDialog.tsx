@@ -14,9 +14,20 @@ import {
 } from "@/components/providers/forms/ProviderForm";
 import { UniversalProviderFormModal } from "@/components/universal/UniversalProviderFormModal";
 import { UniversalProviderPanel } from "@/components/universal";
-import { providerPresets } from "@/config/claudeProviderPresets";
-import { codexProviderPresets } from "@/config/codexProviderPresets";
-import { geminiProviderPresets } from "@/config/geminiProviderPresets";
+import {
+  providerPresets,
+  type ProviderPreset,
+} from "@/config/claudeProviderPresets";
+import {
+  codexProviderPresets,
+  type CodexProviderPreset,
+} from "@/config/codexProviderPresets";
+import {
+  geminiProviderPresets,
+  type GeminiProviderPreset,
+} from "@/config/geminiProviderPresets";
+import { mergeRemotePresets } from "@/config/presetCatalog";
+import { useRemotePresetsQuery } from "@/lib/query/queries";
 // Note: opencodeProviderPresets is loaded via ProviderForm, not needed here
 import type { UniversalProviderPreset } from "@/config/universalProviderPresets";
 
@@ -42,6 +53,8 @@ export function AddProviderDialog({
     "app-specific",
   );
   const [universalFormOpen, setUniversalFormOpen] = useState(false);
+  // 与 ProviderForm 使用同一份合并后的预设列表，保证 presetId 索引一致
+  const { data: remotePresets } = useRemotePresetsQuery(appId);
   const [selectedUniversalPreset, setSelectedUniversalPreset] =
     useState<UniversalProviderPreset | null>(null);
 
@@ -120,7 +133,10 @@ export function AddProviderDialog({
 
         if (values.presetId) {
           if (appId === "claude") {
-            const presets = providerPresets;
+            const presets = mergeRemotePresets(
+              providerPresets,
+              (remotePresets ?? []) as ProviderPreset[],
+            );
             const presetIndex = parseInt(
               values.presetId.replace("claude-", ""),
             );
@@ -135,7 +151,10 @@ export function AddProviderDialog({
               }
             }
           } else if (appId === "codex") {
-            const presets = codexProviderPresets;
+            const presets = mergeRemotePresets(
+              codexProviderPresets,
+              (remotePresets ?? []) as CodexProviderPreset[],
+            );
             const presetIndex = parseInt(values.presetId.replace("codex-", ""));
             if (
               !isNaN(presetIndex) &&
@@ -148,7 +167,10 @@ export function AddProviderDialog({
               }
             }
           } else if (appId === "gemini") {
-            const presets = geminiProviderPresets;
+            const presets = mergeRemotePresets(
+              geminiProviderPresets,
+              (remotePresets ?? []) as GeminiProviderPreset[],
+            );
             const presetIndex = parseInt(
               values.presetId.replace("gemini-", ""),
             );
@@ -218,7 +240,7 @@ export function AddProviderDialog({
       await onSubmit(providerData);
       onOpenChange(false);
     },
-    [appId, onSubmit, onOpenChange],
+    [appId, onSubmit, onOpenChange, remotePresets],
   );
 
   // 动态 footer：根据当前 Tab 显示不同按钮
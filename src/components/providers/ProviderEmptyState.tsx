@@ -4,9 +4,16 @@ import { Button } from "@/components/ui/button";
 
 interface ProviderEmptyStateProps {
   onCreate?: () => void;
+  /** 从环境变量导入（OpenCode 不支持时不传） */
+  onImportFromEnv?: () => void;
+  isImporting?: boolean;
 }
 
-export function ProviderEmptyState({ onCreate }: ProviderEmptyStateProps) {
+export function ProviderEmptyState({
+  onCreate,
+  onImportFromEnv,
+  isImporting = false,
+}: ProviderEmptyStateProps) {
   const { t } = useTranslation();
 
   return (
@@ -18,11 +25,22 @@ export function ProviderEmptyState({ onCreate }: ProviderEmptyStateProps) {
       <p className="mt-2 max-w-sm text-sm text-muted-foreground">
         {t("provider.noProvidersDescription")}
       </p>
-      {onCreate && (
-        <Button className="mt-6" onClick={onCreate}>
-          {t("provider.addProvider")}
-        </Button>
-      )}
+      <div className="mt-6 flex gap-2">
+        {onCreate && (
+          <Button onClick={onCreate}>{t("provider.addProvider")}</Button>
+        )}
+        {onImportFromEnv && (
+          <Button
+            variant="outline"
+            onClick={onImportFromEnv}
+            disabled={isImporting}
+          >
+            {t("provider.importFromEnv", {
+              defaultValue: "从环境变量导入",
+            })}
+          </Button>
+        )}
+      </div>
     </div>
   );
 }
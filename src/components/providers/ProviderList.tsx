@@ -86,6 +86,7 @@ export function ProviderList({
   );
   const [isRefreshingCodexQuota, setIsRefreshingCodexQuota] = useState(false);
   const [isRefreshingGeminiUsage, setIsRefreshingGeminiUsage] = useState(false);
+  const [isImportingFromEnv, setIsImportingFromEnv] = useState(false);
 
   // OpenCode: 查询 live 配置中的供应商 ID 列表，用于判断 isInConfig
   const { data: opencodeLiveIds } = useQuery({
@@ -227,7 +228,7 @@ export function ProviderList({
         codexOfficialProviders.map((provider) =>
           queryClient.fetchQuery({
             queryKey: ["codex-quota", provider.id],
-            queryFn: async () => codexApi.getQuota(provider.id),
+            queryFn: async () => codexApi.getQuota(provider.id, true),
             staleTime: 0,
           }),
         ),
@@ -323,6 +324,37 @@ export function ProviderList({
     t,
   ]);
 
+  const handleImportFromEnv = async () => {
+    setIsImportingFromEnv(true);
+    try {
+      const result = await providersApi.importFromEnv(appId);
+      if (!result.providerId) {
+        toast.warning(
+          t("provider.importFromEnvMissing", {
+            defaultValue: `未找到所需的环境变量：${result.missing.join(", ")}`,
+            vars: result.missing.join(", "),
+          }),
+        );
+        return;
+      }
+      await queryClient.invalidateQueries({ queryKey: ["providers", appId] });
+      toast.success(
+        result.created
+          ? t("provider.importFromEnvSuccess", {
+              defaultValue: `已从 ${result.found.length} 个环境变量导入供应商`,
+              count: result.found.length,
+            })
+          : t("provider.importFromEnvExisting", {
+              defaultValue: "已存在相同地址和密钥的供应商，未重复创建",
+            }),
+      );
+    } catch (error) {
+      toast.error(String(error));
+    } finally {
+      setIsImportingFromEnv(false);
+    }
+  };
+
   if (isLoading) {
     return (
       <div className="space-y-3">
@@ -337,7 +369,15 @@ export function ProviderList({
   }
 
   if (sortedProviders.length === 0) {
-    return <ProviderEmptyState onCreate={onCreate} />;
+    return (
+      <ProviderEmptyState
+        onCreate={onCreate}
+        onImportFromEnv={
+          appId === "opencode" ? undefined : handleImportFromEnv
+        }
+        isImporting={isImportingFromEnv}
+      />
+    );
   }
 
   const renderProviderList = () => (
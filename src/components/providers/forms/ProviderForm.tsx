@@ -34,6 +34,8 @@ import {
 import { OpenCodeFormFields } from "./OpenCodeFormFields";
 import type { OpenCodeModel } from "@/types";
 import type { UniversalProviderPreset } from "@/config/universalProviderPresets";
+import { mergeRemotePresets } from "@/config/presetCatalog";
+import { useRemotePresetsQuery } from "@/lib/query/queries";
 import { applyTemplateValues } from "@/utils/providerConfigUtils";
 import { mergeProviderMeta } from "@/utils/providerMetaUtils";
 import { getCodexCustomTemplate } from "@/config/codexTemplates";
@@ -520,28 +522,44 @@ export function ProviderForm({
     [t],
   );
 
+  // 远程预设目录按名称覆盖内置预设（未配置目录时为空，仅使用内置预设）
+  const { data: remotePresets } = useRemotePresetsQuery(appId);
+
   const presetEntries = useMemo(() => {
+    const remote = remotePresets ?? [];
     if (appId === "codex") {
-      return codexProviderPresets.map<PresetEntry>((preset, index) => ({
+      return mergeRemotePresets(
+        codexProviderPresets,
+        remote as CodexProviderPreset[],
+      ).map<PresetEntry>((preset, index) => ({
         id: `codex-${index}`,
         preset,
       }));
     } else if (appId === "gemini") {
-      return geminiProviderPresets.map<PresetEntry>((preset, index) => ({
+      return mergeRemotePresets(
+        geminiProviderPresets,
+        remote as GeminiProviderPreset[],
+      ).map<PresetEntry>((preset, index) => ({
         id: `gemini-${index}`,
         preset,
       }));
     } else if (appId === "opencode") {
-      return opencodeProviderPresets.map<PresetEntry>((preset, index) => ({
+      return mergeRemotePresets(
+        opencodeProviderPresets,
+        remote as OpenCodeProviderPreset[],
+      ).map<PresetEntry>((preset, index) => ({
         id: `opencode-${index}`,
         preset,
       }));
     }
-    return providerPresets.map<PresetEntry>((preset, index) => ({
+    return mergeRemotePresets(
+      providerPresets,
+      remote as ProviderPreset[],
+    ).map<PresetEntry>((preset, index) => ({
       id: `claude-${index}`,
       preset,
     }));
-  }, [appId]);
+  }, [appId, remotePresets]);
 
   // 使用模板变量 hook (仅 Claude 模式)
   const {
@@ -26,6 +26,9 @@ import {
 import type { ProxyStatus } from "@/types/proxy";
 import { useTranslation } from "react-i18next";
 
+/** 支持代理接管的应用 */
+const TAKEOVER_APPS = ["claude", "codex", "gemini", "opencode"] as const;
+
 export function ProxyPanel() {
   const { t } = useTranslation();
   const { status, isRunning } = useProxyStatus();
@@ -100,6 +103,22 @@ export function ProxyPanel() {
     }
   };
 
+  const handleAutoPortChange = async (enabled: boolean) => {
+    if (!globalConfig) return;
+    try {
+      await updateGlobalConfig.mutateAsync({
+        ...globalConfig,
+        autoPort: enabled,
+      });
+    } catch (error) {
+      toast.error(
+        t("proxy.settings.fields.autoPort.failed", {
+          defaultValue: "切换自动换端口失败",
+        }),
+      );
+    }
+  };
+
   const handleSaveBasicConfig = async () => {
     if (!globalConfig) return;
 
@@ -270,8 +289,8 @@ export function ProxyPanel() {
                     defaultValue: "应用接管",
                   })}
                 </p>
-                <div className="grid gap-2 sm:grid-cols-3">
-                  {(["claude", "codex", "gemini"] as const).map((appType) => {
+                <div className="grid gap-2 sm:grid-cols-2">
+                  {TAKEOVER_APPS.map((appType) => {
                     const isEnabled =
                       takeoverStatus?.[
                         appType as keyof typeof takeoverStatus
@@ -318,6 +337,26 @@ export function ProxyPanel() {
                     disabled={updateGlobalConfig.isPending}
                   />
                 </div>
+                <div className="mt-2 flex items-center justify-between rounded-md border border-border bg-background/60 px-3 py-2">
+                  <div className="space-y-0.5">
+                    <Label className="text-sm font-medium">
+                      {t("proxy.settings.fields.autoPort.label", {
+                        defaultValue: "端口占用时自动换端口",
+                      })}
+                    </Label>
+                    <p className="text-xs text-muted-foreground">
+                      {t("proxy.settings.fields.autoPort.description", {
+                        defaultValue:
+                          "启动时若端口已被占用，自动使用下一个可用端口并保存",
+                      })}
+                    </p>
+                  </div>
+                  <Switch
+                    checked={globalConfig?.autoPort ?? false}
+                    onCheckedChange={handleAutoPortChange}
+                    disabled={updateGlobalConfig.isPending}
+                  />
+                </div>
               </div>
 
               {/* 供应商队列 - 按应用类型分组展示 */}
@@ -0,0 +1,92 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { useQueryClient } from "@tanstack/react-query";
+import { Loader2, RefreshCw } from "lucide-react";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { providersApi } from "@/lib/api";
+import { extractErrorMessage } from "@/utils/errorUtils";
+
+export interface PresetCatalogSettingsProps {
+  value?: string;
+  onChange: (value: string | undefined) => Promise<void> | void;
+}
+
+export function PresetCatalogSettings({
+  value,
+  onChange,
+}: PresetCatalogSettingsProps) {
+  const { t } = useTranslation();
+  const queryClient = useQueryClient();
+  const [draft, setDraft] = useState(value ?? "");
+  const [refreshing, setRefreshing] = useState(false);
+
+  useEffect(() => {
+    setDraft(value ?? "");
+  }, [value]);
+
+  // 失焦时保存，空值表示关闭远程预设；地址变更后旧缓存不再生效，需重新读取
+  const handleBlur = async () => {
+    const next = draft.trim() || undefined;
+    if (next !== (value || undefined)) {
+      await onChange(next);
+      await queryClient.invalidateQueries({ queryKey: ["providerPresets"] });
+    }
+  };
+
+  const handleRefresh = async () => {
+    setRefreshing(true);
+    try {
+      const status = await providersApi.refreshPresets();
+      await queryClient.invalidateQueries({ queryKey: ["providerPresets"] });
+      toast.success(
+        t("settings.presetCatalog.refreshSuccess", {
+          count: status.presetCount,
+        }),
+      );
+    } catch (error) {
+      toast.error(
+        t("settings.presetCatalog.refreshFailed", {
+          error: extractErrorMessage(error),
+        }),
+      );
+    } finally {
+      setRefreshing(false);
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <header className="space-y-1">
+        <h3 className="text-sm font-medium">
+          {t("settings.presetCatalog.title")}
+        </h3>
+        <p className="text-xs text-muted-foreground">
+          {t("settings.presetCatalog.description")}
+        </p>
+      </header>
+      <div className="flex items-center gap-2">
+        <Input
+          value={draft}
+          placeholder={t("settings.presetCatalog.placeholder")}
+          onChange={(event) => setDraft(event.target.value)}
+          onBlur={handleBlur}
+        />
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={handleRefresh}
+          disabled={!value || refreshing}
+        >
+          {refreshing ? (
+            <Loader2 className="h-4 w-4 animate-spin" />
+          ) : (
+            <RefreshCw className="h-4 w-4" />
+          )}
+          {t("settings.presetCatalog.refresh")}
+        </Button>
+      </div>
+    </section>
+  );
+}
@@ -37,11 +37,13 @@ import { WindowSettings } from "@/components/settings/WindowSettings";
 import { AppVisibilitySettings } from "@/components/settings/AppVisibilitySettings";
 import { SkillSyncMethodSettings } from "@/components/settings/SkillSyncMethodSettings";
 import { TerminalSettings } from "@/components/settings/TerminalSettings";
+import { PresetCatalogSettings } from "@/components/settings/PresetCatalogSettings";
 import { DirectorySettings } from "@/components/settings/DirectorySettings";
 import { ImportExportSection } from "@/components/settings/ImportExportSection";
 import { AboutSection } from "@/components/settings/AboutSection";
 import { GlobalProxySettings } from "@/components/settings/GlobalProxySettings";
 import { ProxyPanel } from "@/components/proxy";
+import { ConfirmDialog } from "@/components/ConfirmDialog";
 import { PricingConfigPanel } from "@/components/usage/PricingConfigPanel";
 import { ModelTestConfigPanel } from "@/components/usage/ModelTestConfigPanel";
 import { AutoFailoverConfigPanel } from "@/components/proxy/AutoFailoverConfigPanel";
@@ -106,6 +108,9 @@ export function SettingsPage({
 
   const [activeTab, setActiveTab] = useState<string>("general");
   const [showRestartPrompt, setShowRestartPrompt] = useState(false);
+  const [overrideDirWarnings, setOverrideDirWarnings] = useState<
+    string[] | null
+  >(null);
 
   useEffect(() => {
     if (open) {
@@ -128,19 +133,34 @@ export function SettingsPage({
     onOpenChange(false);
   }, [acknowledgeRestart, clearSelection, onOpenChange, resetStatus]);
 
-  const handleSave = useCallback(async () => {
-    try {
-      const result = await saveSettings(undefined, { silent: false });
-      if (!result) return;
-      if (result.requiresRestart) {
-        setShowRestartPrompt(true);
-        return;
+  const handleSave = useCallback(
+    async (acknowledgeWarnings = false) => {
+      try {
+        const result = await saveSettings(undefined, {
+          silent: false,
+          acknowledgeWarnings,
+        });
+        if (!result) return;
+        if (result.overrideDirWarnings?.length) {
+          setOverrideDirWarnings(result.overrideDirWarnings);
+          return;
+        }
+        if (result.requiresRestart) {
+          setShowRestartPrompt(true);
+          return;
+        }
+        closeAfterSave();
+      } catch (error) {
+        console.error("[SettingsPage] Failed to save settings", error);
       }
-      closeAfterSave();
-    } catch (error) {
-      console.error("[SettingsPage] Failed to save settings", error);
-    }
-  }, [closeAfterSave, saveSettings]);
+    },
+    [closeAfterSave, saveSettings],
+  );
+
+  const handleConfirmOverrideDirWarnings = useCallback(() => {
+    setOverrideDirWarnings(null);
+    void handleSave(true);
+  }, [handleSave]);
 
   const handleRestartLater = useCallback(() => {
     setShowRestartPrompt(false);
@@ -264,6 +284,12 @@ export function SettingsPage({
                         handleAutoSave({ preferredTerminal: terminal })
                       }
                     />
+                    <PresetCatalogSettings
+                      value={settings.presetCatalogUrl}
+                      onChange={(url) =>
+                        handleAutoSave({ presetCatalogUrl: url })
+                      }
+                    />
                   </motion.div>
                 ) : null}
               </TabsContent>
@@ -639,7 +665,7 @@ export function SettingsPage({
                 style={{ backgroundColor: "hsl(var(--background))" }}
               >
                 <div className="px-6 flex items-center justify-end gap-3">
-                  <Button onClick={handleSave} disabled={isSaving}>
+                  <Button onClick={() => handleSave()} disabled={isSaving}>
                     {isSaving ? (
                       <span className="inline-flex items-center gap-2">
                         <Loader2 className="h-4 w-4 animate-spin" />
@@ -659,6 +685,15 @@ export function SettingsPage({
         </Tabs>
       )}
 
+      <ConfirmDialog
+        isOpen={overrideDirWarnings !== null}
+        title={t("settings.overrideDirWarningTitle")}
+        message={(overrideDirWarnings ?? []).join("\n")}
+        confirmText={t("settings.overrideDirWarningConfirm")}
+        onConfirm={handleConfirmOverrideDirWarnings}
+        onCancel={() => setOverrideDirWarnings(null)}
+      />
+
       <Dialog
         open={showRestartPrompt}
         onOpenChange={(open) => !open && handleRestartLater()}
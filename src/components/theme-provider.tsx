@@ -3,9 +3,12 @@ import React, {
   useContext,
   useEffect,
   useMemo,
+  useRef,
   useState,
 } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { ThemeChangedPayload, ThemeConfig } from "@/types";
 
 type Theme = "light" | "dark" | "system";
 
@@ -43,6 +46,51 @@ export function ThemeProvider({
   };
 
   const [theme, setThemeState] = useState<Theme>(getInitialTheme);
+  // 后端按时间计划切换主题时的生效主题（非计划模式为 null）
+  const [scheduledTheme, setScheduledTheme] = useState<
+    "light" | "dark" | null
+  >(null);
+  const themeConfigRef = useRef<ThemeConfig | null>(null);
+
+  // 同步后端主题配置，并监听计划切换 / 系统外观变化
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let isCancelled = false;
+
+    const setup = async () => {
+      try {
+        const config = await invoke<ThemeConfig>("get_theme_config");
+        if (isCancelled) return;
+        themeConfigRef.current = config;
+        if (config.mode === "schedule") {
+          setScheduledTheme(resolveScheduledTheme(config, new Date()));
+        }
+        unlisten = await listen<ThemeChangedPayload>(
+          "theme-changed",
+          (event) => {
+            if (themeConfigRef.current) {
+              themeConfigRef.current = {
+                ...themeConfigRef.current,
+                mode: event.payload.mode,
+              };
+            }
+            setScheduledTheme(
+              event.payload.mode === "schedule" ? event.payload.theme : null,
+            );
+          },
+        );
+      } catch (e) {
+        // Ignore errors (e.g., when not running in Tauri)
+        console.debug("Failed to sync theme config:", e);
+      }
+    };
+
+    setup();
+    return () => {
+      isCancelled = true;
+      unlisten?.();
+    };
+  }, []);
 
   useEffect(() => {
     if (typeof window === "undefined") {
@@ -60,6 +108,11 @@ export function ThemeProvider({
     const root = window.document.documentElement;
     root.classList.remove("light", "dark");
 
+    if (scheduledTheme) {
+      root.classList.add(scheduledTheme);
+      return;
+    }
+
     if (theme === "system") {
       const isDark =
         window.matchMedia &&
@@ -69,7 +122,7 @@ export function ThemeProvider({
     }
 
     root.classList.add(theme);
-  }, [theme]);
+  }, [theme, scheduledTheme]);
 
   useEffect(() => {
     if (typeof window === "undefined") {
@@ -78,7 +131,7 @@ export function ThemeProvider({
 
     const mediaQuery = window.matchMedia("(prefers-color-scheme: dark)");
     const handleChange = () => {
-      if (theme !== "system") {
+      if (theme !== "system" || scheduledTheme) {
         return;
       }
 
@@ -93,11 +146,12 @@ export function ThemeProvider({
 
     mediaQuery.addEventListener("change", handleChange);
     return () => mediaQuery.removeEventListener("change", handleChange);
-  }, [theme]);
+  }, [theme, scheduledTheme]);
 
   // Sync native window theme (Windows/macOS title bar)
   useEffect(() => {
-    if (typeof window === "undefined") {
+    // 计划模式下由后端设置窗口主题
+    if (typeof window === "undefined" || scheduledTheme) {
       return;
     }
 
@@ -141,14 +195,25 @@ export function ThemeProvider({
         mediaQuery.removeEventListener("change", handleChange);
       }
     };
-  }, [theme]);
+  }, [theme, scheduledTheme]);
 
   const value = useMemo<ThemeContextValue>(
     () => ({
       theme,
       setTheme: (nextTheme: Theme, event?: React.MouseEvent) => {
         // Skip if same theme
-        if (nextTheme === theme) return;
+        if (nextTheme === theme && !scheduledTheme) return;
+
+        // 手动选择主题时退出计划模式，避免下一个时间边界又被切换回去
+        const config = themeConfigRef.current;
+        if (config && config.mode !== nextTheme) {
+          const nextConfig = { ...config, mode: nextTheme };
+          themeConfigRef.current = nextConfig;
+          setScheduledTheme(null);
+          invoke("set_theme_config", { config: nextConfig }).catch((e) =>
+            console.debug("Failed to save theme config:", e),
+          );
+        }
 
         // Set transition origin coordinates from click event
         const x = event?.clientX ?? window.innerWidth / 2;
@@ -172,7 +237,7 @@ export function ThemeProvider({
         }
       },
     }),
-    [theme],
+    [theme, scheduledTheme],
   );
 
   return (
@@ -182,6 +247,27 @@ export function ThemeProvider({
   );
 }
 
+// 与后端 ThemeConfig::scheduled_theme 的规则保持一致
+function resolveScheduledTheme(
+  config: ThemeConfig,
+  now: Date,
+): "light" | "dark" {
+  const toMinutes = (value: string) => {
+    const [h, m] = value.split(":").map(Number);
+    return h * 60 + m;
+  };
+  const light = toMinutes(config.lightStart);
+  const dark = toMinutes(config.darkStart);
+  const current = now.getHours() * 60 + now.getMinutes();
+  const isLight =
+    light < dark
+      ? current >= light && current < dark
+      : light > dark
+        ? current >= light || current < dark
+        : true;
+  return isLight ? "light" : "dark";
+}
+
 export function useTheme() {
   const context = useContext(ThemeProviderContext);
   if (context === undefined) {
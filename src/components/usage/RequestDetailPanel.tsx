@@ -269,6 +269,52 @@ export function RequestDetailPanel({
             </dl>
           </div>
 
+          {/* 上游尝试时间线（仅发生重试/故障转移时显示） */}
+          {request.attempts && request.attempts.length > 1 && (
+            <div className="rounded-lg border p-4">
+              <h3 className="mb-3 font-semibold">
+                {t("usage.attempts", "上游尝试")}
+              </h3>
+              <ol className="space-y-2 text-sm">
+                {request.attempts.map((attempt, index) => (
+                  <li
+                    key={`${attempt.providerId}-${index}`}
+                    className="flex items-start gap-3"
+                  >
+                    <span className="w-16 shrink-0 font-mono text-muted-foreground">
+                      +{attempt.startOffsetMs}ms
+                    </span>
+                    <div className="min-w-0 flex-1">
+                      <div className="flex flex-wrap items-center gap-2">
+                        <span className="font-medium">
+                          {t(`usage.attemptKind.${attempt.kind}`, attempt.kind)}
+                        </span>
+                        <span className="font-mono">{attempt.providerId}</span>
+                        {attempt.statusCode !== undefined && (
+                          <span
+                            className={`font-mono ${attempt.statusCode >= 400 ? "text-red-600" : "text-green-600"}`}
+                          >
+                            {attempt.statusCode}
+                          </span>
+                        )}
+                        {attempt.kind !== "circuit_skip" && (
+                          <span className="font-mono text-muted-foreground">
+                            {attempt.durationMs}ms
+                          </span>
+                        )}
+                      </div>
+                      {attempt.error && (
+                        <p className="break-all text-xs text-red-700">
+                          {attempt.error}
+                        </p>
+                      )}
+                    </div>
+                  </li>
+                ))}
+              </ol>
+            </div>
+          )}
+
           {/* 错误信息 */}
           {request.errorMessage && (
             <div className="rounded-lg border border-red-200 bg-red-50 p-4">
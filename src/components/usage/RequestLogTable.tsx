@@ -505,6 +505,14 @@ export function RequestLogTable({ refreshIntervalMs }: RequestLogTableProps) {
                         >
                           {log.statusCode}
                         </span>
+                        {log.attemptCount > 1 && (
+                          <span
+                            className="ml-1 text-xs text-muted-foreground"
+                            title={t("usage.attempts", "上游尝试")}
+                          >
+                            ×{log.attemptCount}
+                          </span>
+                        )}
                       </TableCell>
                     </TableRow>
                   ))
@@ -0,0 +1,19 @@
+/**
+ * 将远程预设目录合并到内置预设之上：
+ * - 同名预设以远程版本为准（保持内置顺序）
+ * - 内置中不存在的远程预设追加到末尾
+ */
+export function mergeRemotePresets<T extends { name: string }>(
+  builtin: T[],
+  remote: T[],
+): T[] {
+  const remoteByName = new Map(remote.map((preset) => [preset.name, preset]));
+  const merged = builtin.map(
+    (preset) => remoteByName.get(preset.name) ?? preset,
+  );
+  const builtinNames = new Set(builtin.map((preset) => preset.name));
+  return [
+    ...merged,
+    ...remote.filter((preset) => !builtinNames.has(preset.name)),
+  ];
+}
@@ -19,8 +19,8 @@ export function usePromptActions(appId: AppId) {
 
       // 同时加载当前文件内容
       try {
-        const content = await promptsApi.getCurrentFileContent(appId);
-        setCurrentFileContent(content);
+        const file = await promptsApi.getCurrentFileContent(appId);
+        setCurrentFileContent(file?.content ?? null);
       } catch (error) {
         setCurrentFileContent(null);
       }
@@ -78,28 +78,14 @@ export function usePromptActions(appId: AppId) {
       // Optimistic update
       const previousPrompts = prompts;
 
-      // 如果要启用当前提示词，先禁用其他所有提示词
-      if (enabled) {
-        const updatedPrompts = Object.keys(prompts).reduce(
-          (acc, key) => {
-            acc[key] = {
-              ...prompts[key],
-              enabled: key === id,
-            };
-            return acc;
-          },
-          {} as Record<string, Prompt>,
-        );
-        setPrompts(updatedPrompts);
-      } else {
-        setPrompts((prev) => ({
-          ...prev,
-          [id]: {
-            ...prev[id],
-            enabled: false,
-          },
-        }));
-      }
+      // 可同时启用多个提示词，只更新当前项
+      setPrompts((prev) => ({
+        ...prev,
+        [id]: {
+          ...prev[id],
+          enabled,
+        },
+      }));
 
       try {
         if (enabled) {
@@ -82,9 +82,21 @@ export function useProviderActions(activeApp: AppId) {
   const switchProvider = useCallback(
     async (provider: Provider) => {
       try {
-        await switchProviderMutation.mutateAsync(provider.id);
+        const result = await switchProviderMutation.mutateAsync(provider.id);
         await syncClaudePlugin(provider);
 
+        // 企业托管配置会覆盖部分字段：切换仍然生效，但需提示用户
+        if (result.managedOverride) {
+          toast.warning(
+            t("notifications.managedSettingsOverride", {
+              defaultValue: "托管配置 {{path}} 将覆盖以下设置：{{keys}}",
+              path: result.managedOverride.path,
+              keys: result.managedOverride.overriddenKeys.join(", "),
+            }),
+            { duration: 8000, closeButton: true },
+          );
+        }
+
         // 根据供应商类型显示不同的成功提示
         if (
           activeApp === "claude" &&
@@ -14,8 +14,13 @@ import { useSettingsMetadata } from "./useSettingsMetadata";
 
 type Language = "zh" | "en" | "ja";
 
+/** [应用, 新目录, 原目录] */
+type DirCandidate = [AppId, string | undefined, string | undefined];
+
 interface SaveResult {
   requiresRestart: boolean;
+  /** 目录覆盖存在警告且未确认时不保存，返回待用户确认的警告 */
+  overrideDirWarnings?: string[];
 }
 
 export interface UseSettingsResult {
@@ -146,7 +151,7 @@ export function useSettings(): UseSettingsResult {
         };
 
         // 保存到配置文件
-        await saveMutation.mutateAsync(payload);
+        await saveMutation.mutateAsync({ settings: payload });
 
         // 如果开机自启状态改变，调用系统 API
         if (
@@ -234,7 +239,7 @@ export function useSettings(): UseSettingsResult {
   const saveSettings = useCallback(
     async (
       overrides?: Partial<SettingsFormState>,
-      options?: { silent?: boolean },
+      options?: { silent?: boolean; acknowledgeWarnings?: boolean },
     ): Promise<SaveResult | null> => {
       const mergedSettings = settings ? { ...settings, ...overrides } : null;
       if (!mergedSettings) return null;
@@ -261,7 +266,57 @@ export function useSettings(): UseSettingsResult {
           language: mergedSettings.language,
         };
 
-        await saveMutation.mutateAsync(payload);
+        // 有变化的目录覆盖先做健康检查：有错误时中止保存，只有警告时需用户确认后再保存
+        const dirCandidates: DirCandidate[] = [
+          ["claude", sanitizedClaudeDir, previousClaudeDir],
+          ["codex", sanitizedCodexDir, previousCodexDir],
+          ["gemini", sanitizedGeminiDir, previousGeminiDir],
+          ["opencode", sanitizedOpencodeDir, previousOpencodeDir],
+        ];
+        const dirReports = await Promise.all(
+          dirCandidates
+            .filter(([, next, previous]) => next && next !== previous)
+            .map(async ([appId, dir]) => ({
+              appId,
+              report: await settingsApi.validateOverrideDir(appId, dir!),
+            })),
+        );
+        const invalidDirs = dirReports.filter(
+          ({ report }) => report.errors.length > 0,
+        );
+        if (invalidDirs.length > 0) {
+          throw new Error(
+            t("settings.overrideDirInvalid", {
+              defaultValue: "配置目录不可用：{{detail}}",
+              detail: invalidDirs
+                .map(
+                  ({ appId, report }) =>
+                    `${appId} (${report.errors.join(", ")})`,
+                )
+                .join("; "),
+            }),
+          );
+        }
+        const warnedDirs = dirReports.filter(
+          ({ report }) => report.warnings.length > 0,
+        );
+        if (warnedDirs.length > 0 && !options?.acknowledgeWarnings) {
+          return {
+            requiresRestart: false,
+            overrideDirWarnings: warnedDirs.map(({ appId, report }) =>
+              t("settings.overrideDirWarning", {
+                defaultValue: "{{app}} 配置目录可能无法正常使用：{{warnings}}",
+                app: appId,
+                warnings: report.warnings.join(", "),
+              }),
+            ),
+          };
+        }
+
+        await saveMutation.mutateAsync({
+          settings: payload,
+          acknowledgeWarnings: warnedDirs.length > 0,
+        });
 
         await settingsApi.setAppConfigDirOverride(sanitizedAppDir ?? null);
 
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { Provider } from "@/types";
 import type { AppId } from "./types";
 
 export interface AntigravityImportedSession {
@@ -23,6 +24,11 @@ export interface AntigravityQuotaResponse {
   fetchedAt: number;
 }
 
+export interface AntigravityProviderResult {
+  action: "created" | "updated";
+  provider: Provider;
+}
+
 export const antigravityApi = {
   async startLogin(): Promise<boolean> {
     return invoke("antigravity_start_login");
@@ -32,6 +38,10 @@ export const antigravityApi = {
     return invoke("antigravity_import_current_session");
   },
 
+  async createProvider(name: string): Promise<AntigravityProviderResult> {
+    return invoke("create_antigravity_provider", { name });
+  },
+
   async getQuota(
     providerId: string,
     appId: AppId = "gemini",
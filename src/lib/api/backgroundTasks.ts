@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BackgroundTaskKind = "codexOauth" | "geminiOauth";
+
+/** CC Switch 启动的后台任务（OAuth 登录会话及其本地回调监听） */
+export interface BackgroundTask {
+  /** 任务 ID，用于取消 */
+  id: string;
+  kind: BackgroundTaskKind;
+  /** 本地回调监听端口 */
+  port?: number | null;
+  startedAt: number;
+  expiresAt: number;
+  /** 已收到授权回调、等待换取 Token */
+  callbackReceived: boolean;
+}
+
+export const backgroundTasksApi = {
+  async list(): Promise<BackgroundTask[]> {
+    return await invoke("list_background_tasks");
+  },
+
+  /** 取消任务并释放回调端口，返回任务是否存在 */
+  async cancel(id: string): Promise<boolean> {
+    return await invoke("cancel_background_task", { id });
+  },
+};
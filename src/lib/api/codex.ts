@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 export interface CodexOAuthDeviceFlowResponse {
   deviceCode: string;
@@ -30,6 +31,14 @@ export interface CodexQuotaResponse {
   fetchedAt: number;
 }
 
+/** 后台刷新额度后发送的事件 */
+export const CODEX_QUOTA_UPDATED_EVENT = "codex-quota-updated";
+
+export interface CodexQuotaUpdatedPayload {
+  providerId: string;
+  quota: CodexQuotaResponse;
+}
+
 export const codexApi = {
   initOAuthDeviceFlow: async (): Promise<CodexOAuthDeviceFlowResponse> => {
     return invoke("codex_oauth_init_device_flow");
@@ -41,8 +50,29 @@ export const codexApi = {
     return invoke("codex_oauth_poll_token", { deviceCode });
   },
 
-  getQuota: async (providerId: string): Promise<CodexQuotaResponse> => {
-    return invoke("codex_get_quota", { providerId });
+  /** 缓存未过期时返回缓存值，forceRefresh 为 true 时强制查询 */
+  getQuota: async (
+    providerId: string,
+    forceRefresh = false,
+  ): Promise<CodexQuotaResponse> => {
+    return invoke("codex_get_quota", { providerId, forceRefresh });
+  },
+
+  getQuotaTtl: async (): Promise<number> => {
+    return invoke("get_codex_quota_ttl");
+  },
+
+  setQuotaTtl: async (seconds: number): Promise<number> => {
+    return invoke("set_codex_quota_ttl", { seconds });
+  },
+
+  onQuotaUpdated: async (
+    handler: (payload: CodexQuotaUpdatedPayload) => void,
+  ): Promise<UnlistenFn> => {
+    return listen<CodexQuotaUpdatedPayload>(
+      CODEX_QUOTA_UPDATED_EVENT,
+      (event) => handler(event.payload),
+    );
   },
 
   restartCli: async (): Promise<boolean> => {
@@ -1,5 +1,6 @@
 // 配置相关 API
 import { invoke } from "@tauri-apps/api/core";
+import type { ProviderFieldError } from "./providers";
 
 export type AppType = "claude" | "codex" | "gemini";
 
@@ -75,3 +76,91 @@ export async function extractCommonConfigSnippet(
 
   return invoke<string>("extract_common_config_snippet", args);
 }
+
+export interface ConfigSnippet {
+  appType: string;
+  name: string;
+  content: string;
+  createdAt: number;
+  updatedAt: number;
+}
+
+/**
+ * 获取命名配置片段列表（按名称排序）
+ * @param appType - 应用类型
+ */
+export async function listConfigSnippets(
+  appType: AppType,
+): Promise<ConfigSnippet[]> {
+  return invoke<ConfigSnippet[]>("list_config_snippets", { appType });
+}
+
+/**
+ * 保存命名配置片段（同名覆盖）
+ * @param appType - 应用类型
+ * @param name - 片段名称
+ * @param content - 片段内容（格式与通用配置片段一致）
+ * @throws 如果名称为空或格式无效
+ */
+export async function saveConfigSnippet(
+  appType: AppType,
+  name: string,
+  content: string,
+): Promise<void> {
+  return invoke("save_config_snippet", { appType, name, content });
+}
+
+/**
+ * 删除命名配置片段
+ * @returns 是否存在并被删除
+ */
+export async function deleteConfigSnippet(
+  appType: AppType,
+  name: string,
+): Promise<boolean> {
+  return invoke<boolean>("delete_config_snippet", { appType, name });
+}
+
+/**
+ * 将命名配置片段合并进指定供应商（冲突字段以片段为准）
+ * @returns 供应商配置是否发生变化
+ */
+export async function applySnippetToProvider(
+  appType: AppType,
+  providerId: string,
+  snippetName: string,
+): Promise<boolean> {
+  return invoke<boolean>("apply_snippet_to_provider", {
+    appType,
+    providerId,
+    snippetName,
+  });
+}
+
+/**
+ * 将通用配置片段合并进该应用的所有供应商（冲突字段以片段为准，整体事务执行）
+ * @returns 配置发生变化的供应商数量
+ */
+export async function applyCommonConfigToAll(
+  appType: AppType,
+): Promise<number> {
+  return invoke<number>("apply_common_config_to_all", { appType });
+}
+
+export interface CodexConfigFormat {
+  formatted: string;
+  warnings: ProviderFieldError[];
+}
+
+/**
+ * 格式化 Codex config.toml（按键排序）
+ *
+ * @param tomlString - 编辑器中的 TOML 文本
+ * @returns 格式化后的 TOML 以及 model_provider 引用缺失等警告
+ * @throws 如果 TOML 语法无效
+ */
+export async function formatCodexConfig(
+  tomlString: string,
+): Promise<CodexConfigFormat> {
+  return invoke<CodexConfigFormat>("format_codex_config", { tomlString });
+}
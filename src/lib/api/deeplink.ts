@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { ProviderFieldError } from "./providers";
+import type { ImportAction, MergeStrategy } from "./settings";
 
-export type ResourceType = "provider" | "prompt" | "mcp" | "skill";
+export type ResourceType = "provider" | "prompt" | "mcp" | "skill" | "batch";
 
 export interface DeepLinkImportRequest {
   version: string;
@@ -47,6 +49,9 @@ export interface DeepLinkImportRequest {
   usageAccessToken?: string;
   usageUserId?: string;
   usageAutoInterval?: number;
+
+  // Batch fields: one provider request per entry
+  providers?: DeepLinkImportRequest[];
 }
 
 export interface McpImportResult {
@@ -59,7 +64,7 @@ export interface McpImportResult {
 }
 
 export type ImportResult =
-  | { type: "provider"; id: string }
+  | { type: "provider"; id: string; action: ImportAction }
   | { type: "prompt"; id: string }
   | {
       type: "mcp";
@@ -67,7 +72,50 @@ export type ImportResult =
       importedIds: string[];
       failed: Array<{ id: string; error: string }>;
     }
-  | { type: "skill"; key: string };
+  | { type: "skill"; key: string }
+  | {
+      type: "batch";
+      providers: Array<{ id: string; action: ImportAction }>;
+      failed: Array<{ index: number; name: string; error: string }>;
+    };
+
+export type DeepLinkErrorReason =
+  | "invalid_url"
+  | "unsupported_resource"
+  | "missing_param"
+  | "invalid_base64"
+  | "payload_too_large"
+  | "schema_mismatch";
+
+/** Structured error returned by validateDeeplink and sent with `deeplink-error` */
+export interface DeepLinkError {
+  reason: DeepLinkErrorReason;
+  message: string;
+  resource?: string;
+  param?: string;
+  field?: string;
+  size?: number;
+  limit?: number;
+}
+
+export interface DeepLinkProviderPreview {
+  app: string;
+  name: string;
+  websiteUrl?: string;
+  endpoints: string[];
+  model?: string;
+  icon?: string;
+  notes?: string;
+  enabled: boolean;
+}
+
+export interface DeepLinkValidation {
+  request: DeepLinkImportRequest;
+  provider?: DeepLinkProviderPreview;
+  /** Batch links only, in the same order as request.providers */
+  providers?: DeepLinkProviderPreview[];
+  warnings: ProviderFieldError[];
+}
 
 export const deeplinkApi = {
   /**
@@ -91,14 +139,28 @@ export const deeplinkApi = {
     return invoke("merge_deeplink_config", { request });
   },
 
+  /**
+   * Parse and validate a deep link URL without importing it
+   * @param url The ccswitch:// URL to validate
+   * @returns Provider preview and warnings; rejects with a DeepLinkError
+   */
+  validateDeeplink: async (url: string): Promise<DeepLinkValidation> => {
+    return invoke("validate_deeplink", { url });
+  },
+
   /**
    * Import a resource from a deep link request (unified handler)
    * @param request The deep link import request
+   * @param mergeStrategy How to handle a provider with the same name (default: always add)
    * @returns Import result based on resource type
    */
   importFromDeeplink: async (
     request: DeepLinkImportRequest,
+    mergeStrategy?: MergeStrategy,
   ): Promise<ImportResult> => {
-    return invoke("import_from_deeplink_unified", { request });
+    return invoke("import_from_deeplink_unified", {
+      request,
+      mergeStrategy: mergeStrategy ?? null,
+    });
   },
 };
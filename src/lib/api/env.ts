@@ -1,5 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { EnvConflict, BackupInfo } from "@/types/env";
+import type {
+  EnvConflict,
+  BackupInfo,
+  EnvAutoResolveReport,
+} from "@/types/env";
 
 /**
  * 环境变量管理 API
@@ -27,6 +31,19 @@ export async function deleteEnvVars(
   return invoke<BackupInfo>("delete_env_vars", { conflicts });
 }
 
+/**
+ * 自动注释掉 shell 配置文件中与该应用冲突的环境变量 (会自动备份，可重复执行)
+ * @param appType 应用类型 ("claude" | "codex" | "gemini")
+ * @returns 处理结果；可用 backup.backupPath 调用 restoreEnvBackup 撤销
+ */
+export async function autoResolveEnvConflicts(
+  appType: string,
+): Promise<EnvAutoResolveReport> {
+  return invoke<EnvAutoResolveReport>("auto_resolve_env_conflicts", {
+    app: appType,
+  });
+}
+
 /**
  * 从备份文件恢复环境变量
  * @param backupPath 备份文件路径
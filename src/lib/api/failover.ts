@@ -3,7 +3,10 @@ import type {
   ProviderHealth,
   CircuitBreakerConfig,
   CircuitBreakerStats,
+  CircuitBreakerEvent,
+  CircuitOverrideMode,
   FailoverQueueItem,
+  FailoverSimulation,
 } from "@/types/proxy";
 
 export interface Provider {
@@ -39,6 +42,21 @@ export const failoverApi = {
     return invoke("reset_circuit_breaker", { providerId, appType });
   },
 
+  // 手动设置熔断器状态（until 为毫秒时间戳，省略表示直到恢复 auto）
+  async setProviderCircuitState(
+    providerId: string,
+    appType: string,
+    mode: CircuitOverrideMode,
+    until?: number,
+  ): Promise<void> {
+    return invoke("set_provider_circuit_state", {
+      providerId,
+      appType,
+      mode,
+      until: until ?? null,
+    });
+  },
+
   // 获取熔断器配置
   async getCircuitBreakerConfig(): Promise<CircuitBreakerConfig> {
     return invoke("get_circuit_breaker_config");
@@ -59,6 +77,27 @@ export const failoverApi = {
     return invoke("get_circuit_breaker_stats", { providerId, appType });
   },
 
+  // 获取熔断器状态转换历史
+  async getCircuitBreakerHistory(
+    appType: string,
+    providerId?: string,
+    limit?: number,
+  ): Promise<CircuitBreakerEvent[]> {
+    return invoke("get_circuit_breaker_history", {
+      appType,
+      providerId,
+      limit,
+    });
+  },
+
+  // 模拟指定供应商故障时的故障转移过程（不发送真实请求）
+  async simulateFailover(
+    appType: string,
+    failingProviderIds: string[],
+  ): Promise<FailoverSimulation> {
+    return invoke("simulate_failover", { appType, failingProviderIds });
+  },
+
   // ========== 故障转移队列 API（新） ==========
 
   // 获取故障转移队列
@@ -15,11 +15,22 @@ export interface ProxyTestResult {
   error: string | null;
 }
 
+/**
+ * 全局出站代理模式
+ *
+ * - manual：使用手动配置的代理 URL
+ * - system：跟随操作系统代理设置
+ * - none：强制直连
+ */
+export type ProxyMode = "manual" | "system" | "none";
+
 /**
  * 出站代理状态
  */
 export interface UpstreamProxyStatus {
+  mode: ProxyMode;
   enabled: boolean;
+  /** 实际生效的代理 URL（system 模式下为检测结果） */
   proxyUrl: string | null;
 }
 
@@ -30,6 +41,23 @@ export interface DetectedProxy {
   url: string;
   proxyType: string;
   port: number;
+  /** 监听该端口的代理软件进程名 */
+  process: string | null;
+  /** 经该代理请求探测地址的耗时 */
+  latencyMs: number | null;
+  /** 是否能通过该代理完成真实的 HTTPS 请求 */
+  works: boolean;
+  error: string | null;
+}
+
+/**
+ * 全局出站网络配置
+ */
+export interface GlobalNetworkConfig {
+  /** 主机名 → IP 地址的固定解析 */
+  dnsOverrides: Record<string, string>;
+  /** 优先使用 IPv6 地址 */
+  preferIpv6: boolean;
 }
 
 /**
@@ -56,6 +84,55 @@ export async function setGlobalProxyUrl(url: string): Promise<void> {
   }
 }
 
+/**
+ * 获取全局出站代理模式
+ */
+export async function getProxyMode(): Promise<ProxyMode> {
+  return invoke<ProxyMode>("get_proxy_mode");
+}
+
+/**
+ * 设置全局出站代理模式，保存后立即重建全局 HTTP 客户端
+ *
+ * @returns 切换后的出站代理状态
+ */
+export async function setProxyMode(
+  mode: ProxyMode,
+): Promise<UpstreamProxyStatus> {
+  try {
+    return await invoke<UpstreamProxyStatus>("set_proxy_mode", { mode });
+  } catch (error) {
+    throw new Error(typeof error === "string" ? error : String(error));
+  }
+}
+
+/**
+ * 重新检测系统代理（仅 system 模式下生效）
+ */
+export async function refreshSystemProxy(): Promise<UpstreamProxyStatus> {
+  return invoke<UpstreamProxyStatus>("refresh_system_proxy");
+}
+
+/**
+ * 获取全局出站网络配置（DNS 覆盖 / IPv6 优先）
+ */
+export async function getGlobalNetworkConfig(): Promise<GlobalNetworkConfig> {
+  return invoke<GlobalNetworkConfig>("get_global_network_config");
+}
+
+/**
+ * 设置全局出站网络配置，保存后立即重建全局 HTTP 客户端
+ */
+export async function setGlobalNetworkConfig(
+  config: GlobalNetworkConfig,
+): Promise<void> {
+  try {
+    return await invoke("set_global_network_config", { config });
+  } catch (error) {
+    throw new Error(typeof error === "string" ? error : String(error));
+  }
+}
+
 /**
  * 测试代理连接
  *
@@ -69,7 +146,7 @@ export async function testProxyUrl(url: string): Promise<ProxyTestResult> {
 /**
  * 获取当前出站代理状态
  *
- * @returns 代理状态，包含是否启用和代理 URL
+ * @returns 代理状态，包含代理模式、是否启用和实际生效的代理 URL
  */
 export async function getUpstreamProxyStatus(): Promise<UpstreamProxyStatus> {
   return invoke<UpstreamProxyStatus>("get_upstream_proxy_status");
@@ -78,8 +155,16 @@ export async function getUpstreamProxyStatus(): Promise<UpstreamProxyStatus> {
 /**
  * 扫描本地代理
  *
- * @returns 检测到的代理列表
+ * 除默认端口与进程扫描发现的端口外，还会探测 `ports` 中的端口，
+ * 并通过真实 HTTPS 请求验证每个候选是否可用。
+ *
+ * @param ports - 额外探测的端口
+ * @returns 检测到的代理列表（可用优先，按延迟升序）
  */
-export async function scanLocalProxies(): Promise<DetectedProxy[]> {
-  return invoke<DetectedProxy[]>("scan_local_proxies");
+export async function scanLocalProxies(
+  ports?: number[],
+): Promise<DetectedProxy[]> {
+  return invoke<DetectedProxy[]>("scan_local_proxies", {
+    ports: ports ?? null,
+  });
 }
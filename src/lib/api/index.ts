@@ -11,6 +11,11 @@ export { sessionsApi } from "./sessions";
 export { codexApi } from "./codex";
 export { antigravityApi } from "./antigravity";
 export { geminiApi } from "./gemini";
+export { backgroundTasksApi } from "./backgroundTasks";
 export * as configApi from "./config";
-export type { ProviderSwitchEvent } from "./providers";
-export type { Prompt } from "./prompts";
+export type {
+  LiveConfigVersion,
+  PresetCatalogStatus,
+  ProviderSwitchEvent,
+} from "./providers";
+export type { Prompt, PromptFileContent, PromptSegment } from "./prompts";
@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  McpApps,
   McpConfigResponse,
+  McpManagedApps,
   McpServer,
   McpServerSpec,
   McpServersMap,
@@ -8,6 +10,36 @@ import type {
 } from "@/types";
 import type { AppId } from "./types";
 
+export interface ClaudeDesktopImportResult {
+  imported: string[];
+  renamed: Array<{ from: string; to: string }>;
+  unchanged: string[];
+  warnings: string[];
+}
+
+export interface ClaudeDesktopExportResult {
+  path: string;
+  exported: string[];
+  warnings: string[];
+}
+
+export interface McpProfile {
+  name: string;
+  /** 服务器 ID -> 应用该 Profile 后的启用状态 */
+  servers: Record<string, McpApps>;
+  /** 不在 Profile 中时保持当前状态的服务器 */
+  pinned: string[];
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+export interface McpProfileApplyReport {
+  profile: string;
+  changed: string[];
+  /** 应用 -> 同步失败信息 */
+  syncErrors: Record<string, string[]>;
+}
+
 export const mcpApi = {
   async getStatus(): Promise<McpStatus> {
     return await invoke("get_claude_mcp_status");
@@ -126,4 +158,61 @@ export const mcpApi = {
   async importFromApps(): Promise<number> {
     return await invoke("import_mcp_from_apps");
   },
+
+  /**
+   * 从 Claude Desktop 配置导入 MCP 服务器（path 省略时使用默认位置）
+   */
+  async importFromClaudeDesktop(
+    path?: string,
+  ): Promise<ClaudeDesktopImportResult> {
+    return await invoke("import_mcp_from_claude_desktop", {
+      path: path ?? null,
+    });
+  },
+
+  /**
+   * 导出 MCP 服务器到 Claude Desktop 配置（serverIds 省略时导出全部）
+   */
+  async exportToClaudeDesktop(
+    path?: string,
+    serverIds?: string[],
+  ): Promise<ClaudeDesktopExportResult> {
+    return await invoke("export_mcp_to_claude_desktop", {
+      path: path ?? null,
+      serverIds: serverIds ?? null,
+    });
+  },
+
+  async getProfiles(): Promise<McpProfile[]> {
+    return await invoke("get_mcp_profiles");
+  },
+
+  async upsertProfile(profile: McpProfile): Promise<void> {
+    return await invoke("upsert_mcp_profile", { profile });
+  },
+
+  async deleteProfile(name: string): Promise<boolean> {
+    return await invoke("delete_mcp_profile", { name });
+  },
+
+  /**
+   * 一键应用 MCP Profile（不在 Profile 中且未固定的服务器会被禁用）
+   */
+  async applyProfile(name: string): Promise<McpProfileApplyReport> {
+    return await invoke("apply_mcp_profile", { name });
+  },
+
+  /**
+   * 获取由 CC Switch 管理 MCP 的应用（未管理的应用不会被写入或删除 MCP 条目）
+   */
+  async getManagedApps(): Promise<McpManagedApps> {
+    return await invoke("get_mcp_managed_apps");
+  },
+
+  /**
+   * 设置由 CC Switch 管理 MCP 的应用；重新纳入管理的应用会立即同步
+   */
+  async setManagedApps(apps: McpManagedApps): Promise<boolean> {
+    return await invoke("set_mcp_managed_apps", { apps });
+  },
 };
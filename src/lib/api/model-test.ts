@@ -13,6 +13,12 @@ export interface StreamCheckConfig {
   codexModel: string;
   geminiModel: string;
   testPrompt: string;
+  /** 各应用定时检查间隔（分钟），0 表示不定时检查 */
+  streamCheckIntervalMinutes?: Partial<Record<AppId, number>>;
+  /** 代理未运行时也执行定时检查 */
+  scheduleWhenProxyStopped?: boolean;
+  /** 按供应商固定的检查模型（应用 → 供应商 ID → 模型） */
+  providerModels?: Partial<Record<AppId, Record<string, string>>>;
 }
 
 export interface StreamCheckResult {
@@ -26,26 +32,68 @@ export interface StreamCheckResult {
   retryCount: number;
 }
 
+/** 定时检查完成事件（stream-check-completed）的载荷 */
+export interface StreamCheckSummary {
+  appType: AppId;
+  total: number;
+  operational: number;
+  degraded: number;
+  failed: number;
+  checkedAt: number;
+}
+
+/** 批量检查进度事件（stream-check-progress）的载荷 */
+export interface StreamCheckProgress {
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  completed: number;
+  total: number;
+  result: StreamCheckResult;
+}
+
+export interface StreamCheckHistoryBucket {
+  bucketStart: number;
+  total: number;
+  successCount: number;
+  successRate: number;
+  avgLatencyMs?: number | null;
+  maxLatencyMs?: number | null;
+}
+
 // ===== 流式健康检查 API =====
 
 /**
  * 流式健康检查（单个供应商）
+ *
+ * @param model 仅本次检查使用的模型（不传则按固定模型/供应商配置/默认模型选择）
  */
 export async function streamCheckProvider(
   appType: AppId,
   providerId: string,
+  model?: string,
 ): Promise<StreamCheckResult> {
-  return invoke("stream_check_provider", { appType, providerId });
+  return invoke("stream_check_provider", { appType, providerId, model });
 }
 
 /**
  * 批量流式健康检查
+ *
+ * @param providerIds 只检查这些供应商（如重新检查失败项），不传则检查全部
+ * @param concurrency 同时进行的检查数，默认 4，上限 16
  */
 export async function streamCheckAllProviders(
   appType: AppId,
   proxyTargetsOnly: boolean = false,
+  providerIds?: string[],
+  concurrency?: number,
 ): Promise<Array<[string, StreamCheckResult]>> {
-  return invoke("stream_check_all_providers", { appType, proxyTargetsOnly });
+  return invoke("stream_check_all_providers", {
+    appType,
+    proxyTargetsOnly,
+    providerIds,
+    concurrency,
+  });
 }
 
 /**
@@ -63,3 +111,18 @@ export async function saveStreamCheckConfig(
 ): Promise<void> {
   return invoke("save_stream_check_config", { config });
 }
+
+/**
+ * 获取供应商检查历史（按小时聚合的成功率与延迟）
+ */
+export async function getStreamCheckHistory(
+  appType: AppId,
+  providerId: string,
+  limit?: number,
+): Promise<StreamCheckHistoryBucket[]> {
+  return invoke("get_stream_check_history", {
+    appType,
+    providerId,
+    limit: limit ?? null,
+  });
+}
@@ -7,10 +7,23 @@ export interface Prompt {
   content: string;
   description?: string;
   enabled: boolean;
+  /** 多个启用的提示词写入文件时的顺序（升序） */
+  orderIndex?: number;
   createdAt?: number;
   updatedAt?: number;
 }
 
+/** live 文件受管区块中与某个提示词对应的分段 */
+export interface PromptSegment {
+  promptId: string;
+  content: string;
+}
+
+export interface PromptFileContent {
+  content: string;
+  segments: PromptSegment[];
+}
+
 export const promptsApi = {
   async getPrompts(app: AppId): Promise<Record<string, Prompt>> {
     return await invoke("get_prompts", { app });
@@ -28,11 +41,32 @@ export const promptsApi = {
     return await invoke("enable_prompt", { app, id });
   },
 
+  /** 按给定 ID 顺序重排提示词，并重新写入受管区块 */
+  async reorderPrompts(app: AppId, ids: string[]): Promise<void> {
+    return await invoke("reorder_prompts", { app, ids });
+  },
+
   async importFromFile(app: AppId): Promise<string> {
     return await invoke("import_prompt_from_file", { app });
   },
 
-  async getCurrentFileContent(app: AppId): Promise<string | null> {
+  async getCurrentFileContent(
+    app: AppId,
+  ): Promise<PromptFileContent | null> {
     return await invoke("get_current_prompt_file_content", { app });
   },
+
+  /** 导入 Markdown 提示词；不传路径时弹出文件选择框，取消返回 null */
+  async importMarkdown(app: AppId, path?: string): Promise<string | null> {
+    return await invoke("import_prompt_markdown", { app, path });
+  },
+
+  /** 导出为 Markdown；不传路径时弹出保存对话框，返回写入路径，取消返回 null */
+  async exportMarkdown(
+    app: AppId,
+    id: string,
+    path?: string,
+  ): Promise<string | null> {
+    return await invoke("export_prompt_markdown", { app, id, path });
+  },
 };
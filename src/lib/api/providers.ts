@@ -5,6 +5,7 @@ import type {
   UniversalProvider,
   UniversalProvidersMap,
 } from "@/types";
+import type { InvalidProviderRow } from "./settings";
 import type { AppId } from "./types";
 
 export interface ProviderSortUpdate {
@@ -12,16 +13,183 @@ export interface ProviderSortUpdate {
   sortIndex: number;
 }
 
+export interface ProviderMetadata {
+  title?: string | null;
+  description?: string | null;
+  faviconUrl?: string | null;
+  /** 缩放后的 PNG 图标（data URL） */
+  icon?: string | null;
+}
+
+/** Claude Code 企业托管配置（managed-settings.json），cc-switch 只读 */
+export interface ManagedSettingsStatus {
+  path: string;
+  readOnly: boolean;
+  /** 会被托管配置覆盖的字段（env.X 或顶层键） */
+  overriddenKeys: string[];
+}
+
+export interface SwitchProviderResult {
+  /** Live 配置是否已写入；只读模式下仅更新当前供应商，为 false */
+  liveWritten: boolean;
+  /** 托管配置会覆盖本次写入的字段时返回 */
+  managedOverride?: ManagedSettingsStatus;
+}
+
+export interface LiveConfigVersion {
+  id: number;
+  appType: string;
+  /** JSON 对象字符串：文件名 -> 原始文本 */
+  content: string;
+  contentHash: string;
+  createdAt: number;
+}
+
+export type ProviderAuditAction = "add" | "update" | "delete" | "switch";
+
+export type ProviderAuditSource =
+  | "ui"
+  | "deeplink"
+  | "universal-sync"
+  | "failover"
+  | "local-api";
+
+export interface ProviderAuditChange {
+  /** JSON Pointer 路径，如 /env/ANTHROPIC_BASE_URL */
+  path: string;
+  kind: "added" | "removed" | "changed";
+  before?: unknown;
+  after?: unknown;
+}
+
+export interface ProviderAuditEntry {
+  id: number;
+  appType: string;
+  providerId: string;
+  action: ProviderAuditAction;
+  source: ProviderAuditSource;
+  /** 脱敏后的 settingsConfig 差异（仅修改操作） */
+  diff?: ProviderAuditChange[];
+  createdAt: number;
+}
+
+export type CredentialIssueKind = "emptyApiKey" | "expired" | "missingBaseUrl";
+
+export interface CredentialIssue {
+  app: AppId;
+  providerId: string;
+  providerName: string;
+  issue: CredentialIssueKind;
+  /** 过期时间（秒级时间戳，仅 expired） */
+  expiresAt?: number;
+}
+
+export interface PresetCatalogStatus {
+  fetchedAt: number;
+  updatedAt?: string | null;
+  presetCount: number;
+}
+
+export type ResolvedProviderIcon =
+  | { kind: "cached"; path: string; dataUrl: string }
+  | { kind: "inline"; icon: string }
+  | { kind: "letter"; letter: string };
+
 export interface ProviderSwitchEvent {
   appType: AppId;
   providerId: string;
 }
 
+export interface OrphanedUniversalChild {
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  universalId: string;
+  /** 当前正在使用（清理时会跳过） */
+  isCurrent: boolean;
+}
+
+export interface EnvImportFound {
+  varName: string;
+  /** 来源：进程环境、注册表路径或 shell 配置文件:行号 */
+  source: string;
+}
+
+export interface EnvImportResult {
+  /** 新建或已存在的供应商 ID；缺少必需变量时为 null */
+  providerId: string | null;
+  /** false 表示复用了相同 base_url + key 的已有供应商，或未导入 */
+  created: boolean;
+  found: EnvImportFound[];
+  missing: string[];
+}
+
+/** 供应商列表摘要（不含 settingsConfig） */
+export interface ProviderSummary {
+  id: string;
+  name: string;
+  category?: string;
+  websiteUrl?: string;
+  icon?: string;
+  iconColor?: string;
+  iconCached?: string;
+  sortIndex?: number;
+  createdAt?: number;
+  isCurrent: boolean;
+  inFailoverQueue: boolean;
+  isPartner: boolean;
+  usageScriptEnabled: boolean;
+  baseUrl?: string;
+}
+
+export interface ProviderFieldError {
+  path: string;
+  code: string;
+  severity: "error" | "warning";
+  messageZh: string;
+  messageEn: string;
+}
+
 export const providersApi = {
+  /** 返回完整 settingsConfig，数据量较大；列表视图优先使用 getSummaries */
   async getAll(appId: AppId): Promise<Record<string, Provider>> {
     return await invoke("get_providers", { app: appId });
   },
 
+  async getSummaries(appId: AppId): Promise<ProviderSummary[]> {
+    return await invoke("get_providers_summary", { app: appId });
+  },
+
+  /** 编辑对话框打开时按需获取完整配置 */
+  async getSettings(
+    appId: AppId,
+    id: string,
+  ): Promise<Provider["settingsConfig"]> {
+    return await invoke("get_provider_settings", { app: appId, id });
+  },
+
+  async search(
+    appId: AppId,
+    query: string,
+    category?: string,
+  ): Promise<Record<string, Provider>> {
+    return await invoke("search_providers", {
+      app: appId,
+      query,
+      category: category ?? null,
+    });
+  },
+
+  async validateConfig(
+    appId: AppId,
+    settingsConfig: unknown,
+  ): Promise<ProviderFieldError[]> {
+    return await invoke("validate_provider_config", {
+      app: appId,
+      settingsConfig,
+    });
+  },
+
   async getCurrent(appId: AppId): Promise<string> {
     return await invoke("get_current_provider", { app: appId });
   },
@@ -46,14 +214,123 @@ export const providersApi = {
     return await invoke("remove_provider_from_live_config", { id, app: appId });
   },
 
-  async switch(id: string, appId: AppId): Promise<boolean> {
-    return await invoke("switch_provider", { id, app: appId });
+  async restoreLiveConfig(
+    id: string,
+    appId: AppId,
+    confirmed = false,
+  ): Promise<boolean> {
+    return await invoke("restore_live_config", { id, app: appId, confirmed });
+  },
+
+  async listLiveConfigHistory(appId: AppId): Promise<LiveConfigVersion[]> {
+    return await invoke("list_live_config_history", { app: appId });
+  },
+
+  /** 恢复到指定历史版本，并回填到当前供应商 */
+  async restoreLiveConfigVersion(
+    appId: AppId,
+    versionId: number,
+  ): Promise<boolean> {
+    return await invoke("restore_live_config_version", {
+      app: appId,
+      versionId,
+    });
+  },
+
+  /** 扫描所有供应商的凭据问题（只读） */
+  async auditCredentials(): Promise<CredentialIssue[]> {
+    return await invoke("audit_credentials");
+  },
+
+  /** 启动时的凭据体检结果 */
+  async onCredentialsAudit(
+    handler: (issues: CredentialIssue[]) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<CredentialIssue[]>("credentials-audit", (event) => {
+      handler(event.payload);
+    });
+  },
+
+  /** 获取供应商变更审计日志（最新在前），不传 providerId 时返回该应用全部记录 */
+  async getAuditLog(
+    appId: AppId,
+    providerId?: string,
+    limit?: number,
+  ): Promise<ProviderAuditEntry[]> {
+    return await invoke("get_provider_audit_log", {
+      app: appId,
+      providerId,
+      limit,
+    });
+  },
+
+  async getAuditRetentionDays(): Promise<number> {
+    return await invoke("get_provider_audit_retention_days");
+  },
+
+  /** 0 表示永久保留 */
+  async setAuditRetentionDays(days: number): Promise<boolean> {
+    return await invoke("set_provider_audit_retention_days", { days });
+  },
+
+  /** 检查供应商表中 settingsConfig / meta 无法解析的行 */
+  async validateProviders(): Promise<InvalidProviderRow[]> {
+    return await invoke("validate_providers");
+  },
+
+  /** 将无法解析的供应商行移入隔离表，返回被隔离的行 */
+  async repairProviders(): Promise<InvalidProviderRow[]> {
+    return await invoke("repair_providers");
+  },
+
+  async switch(
+    id: string,
+    appId: AppId,
+    force = false,
+  ): Promise<SwitchProviderResult> {
+    return await invoke("switch_provider", { id, app: appId, force });
+  },
+
+  async getBackfillSetting(appId: AppId): Promise<boolean> {
+    return await invoke("get_backfill_setting", { app: appId });
+  },
+
+  async setBackfillSetting(appId: AppId, enabled: boolean): Promise<boolean> {
+    return await invoke("set_backfill_setting", { app: appId, enabled });
+  },
+
+  async fetchMetadata(url: string): Promise<ProviderMetadata> {
+    return await invoke("fetch_provider_metadata", { url });
+  },
+
+  /** 下载并校验远程预设目录；签名或格式无效时抛错，已有缓存保持不变 */
+  async refreshPresets(): Promise<PresetCatalogStatus> {
+    return await invoke("refresh_provider_presets");
+  },
+
+  /** 缓存的远程预设（结构与内置预设一致），无缓存时为空数组 */
+  async getRemotePresets<T extends { name: string }>(
+    appId: AppId,
+  ): Promise<T[]> {
+    return await invoke("get_provider_presets", { app: appId });
+  },
+
+  async resolveIcon(
+    providerId: string,
+    appId: AppId,
+  ): Promise<ResolvedProviderIcon> {
+    return await invoke("resolve_provider_icon", { app: appId, providerId });
   },
 
   async importDefault(appId: AppId): Promise<boolean> {
     return await invoke("import_default_config", { app: appId });
   },
 
+  /** 从当前环境变量导入供应商（不会重复创建相同 base_url + key 的供应商） */
+  async importFromEnv(appId: AppId): Promise<EnvImportResult> {
+    return await invoke("import_provider_from_env", { app: appId });
+  },
+
   async updateTrayMenu(): Promise<boolean> {
     return await invoke("update_tray_menu");
   },
@@ -65,6 +342,29 @@ export const providersApi = {
     return await invoke("update_providers_sort_order", { updates, app: appId });
   },
 
+  /** 迁移所有 Claude 供应商的旧模型键，返回更新数量 */
+  async normalizeClaudeModels(): Promise<number> {
+    return await invoke("normalize_claude_models");
+  },
+
+  /** 供应商单独设置的成本倍率，null 表示使用应用默认倍率 */
+  async getCostMultiplier(id: string, appId: AppId): Promise<string | null> {
+    return await invoke("get_provider_cost_multiplier", { app: appId, id });
+  },
+
+  /** 设置供应商成本倍率（正数）；传 null 清除并回退到应用默认倍率 */
+  async setCostMultiplier(
+    id: string,
+    appId: AppId,
+    multiplier: string | null,
+  ): Promise<boolean> {
+    return await invoke("set_provider_cost_multiplier", {
+      app: appId,
+      id,
+      multiplier,
+    });
+  },
+
   async onSwitched(
     handler: (event: ProviderSwitchEvent) => void,
   ): Promise<UnlistenFn> {
@@ -139,4 +439,12 @@ export const universalProvidersApi = {
   async sync(id: string): Promise<boolean> {
     return await invoke("sync_universal_provider", { id });
   },
+
+  async findOrphanedChildren(): Promise<OrphanedUniversalChild[]> {
+    return await invoke("find_orphaned_universal_children");
+  },
+
+  async cleanupOrphanedChildren(): Promise<OrphanedUniversalChild[]> {
+    return await invoke("cleanup_orphaned_universal_children");
+  },
 };
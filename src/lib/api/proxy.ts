@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
   ProxyConfig,
   ProxyStatus,
@@ -6,6 +7,10 @@ import type {
   ProxyTakeoverStatus,
   GlobalProxyConfig,
   AppProxyConfig,
+  EmergencyRestoreReport,
+  ReplayResult,
+  CurlExport,
+  ProxyPortChangedEvent,
 } from "@/types/proxy";
 
 export const proxyApi = {
@@ -21,6 +26,16 @@ export const proxyApi = {
     return invoke("stop_proxy_with_restore");
   },
 
+  // 优雅停止：等待进行中的请求完成（默认最多 10 秒）后恢复配置
+  async stopProxyGraceful(timeoutSecs?: number): Promise<void> {
+    return invoke("stop_proxy_graceful", { timeoutSecs });
+  },
+
+  // 紧急恢复：停止代理并把所有应用的配置恢复到可用状态（可重复执行）
+  async emergencyRestoreAll(): Promise<EmergencyRestoreReport> {
+    return invoke("emergency_restore_all");
+  },
+
   // 获取代理服务器状态
   async getProxyStatus(): Promise<ProxyStatus> {
     return invoke("get_proxy_status");
@@ -44,6 +59,22 @@ export const proxyApi = {
     return invoke("switch_proxy_provider", { appType, providerId });
   },
 
+  // 将已记录的请求重放到指定供应商（需开启请求体捕获）
+  async replayProxyRequest(
+    requestId: string,
+    targetProviderId: string,
+  ): Promise<ReplayResult> {
+    return invoke("replay_proxy_request", { requestId, targetProviderId });
+  },
+
+  // 将已记录的请求还原为 curl 命令；认证信息默认脱敏，需显式传入 includeAuth
+  async getRequestAsCurl(
+    requestId: string,
+    includeAuth = false,
+  ): Promise<CurlExport> {
+    return invoke("get_request_as_curl", { requestId, includeAuth });
+  },
+
   // ========== 接管状态 API ==========
 
   // 获取各应用接管状态
@@ -83,6 +114,18 @@ export const proxyApi = {
     return invoke("update_global_proxy_config", { config });
   },
 
+  // 监听自动换端口事件（配置端口被占用且开启 autoPort 时触发）
+  async onPortChanged(
+    handler: (event: ProxyPortChangedEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<ProxyPortChangedEvent>(
+      "proxy-port-changed",
+      (event) => {
+        handler(event.payload);
+      },
+    );
+  },
+
   // 获取指定应用的代理配置
   async getProxyConfigForApp(appType: string): Promise<AppProxyConfig> {
     return invoke("get_proxy_config_for_app", { appType });
@@ -117,4 +160,21 @@ export const proxyApi = {
   async setPricingModelSource(appType: string, value: string): Promise<void> {
     return invoke("set_pricing_model_source", { appType, value });
   },
+
+  // ========== 本地 HTTP API ==========
+
+  // 获取本地 API 访问 token（每次启动重新生成）
+  async getLocalApiToken(): Promise<string> {
+    return invoke("get_local_api_token");
+  },
+
+  // 获取是否启用本地 API
+  async getLocalApiEnabled(): Promise<boolean> {
+    return invoke("get_local_api_enabled");
+  },
+
+  // 设置是否启用本地 API
+  async setLocalApiEnabled(enabled: boolean): Promise<void> {
+    return invoke("set_local_api_enabled", { enabled });
+  },
 };
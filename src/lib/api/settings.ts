@@ -1,5 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings } from "@/types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  HotkeyBindingError,
+  HotkeyConfig,
+  Settings,
+  ThemeConfig,
+} from "@/types";
 import type { AppId } from "./types";
 
 export interface ConfigTransferResult {
@@ -7,6 +13,186 @@ export interface ConfigTransferResult {
   message: string;
   filePath?: string;
   backupId?: string;
+  // 指定合并策略导入时的逐项处理结果
+  summary?: ImportApplyResult;
+}
+
+export interface OverrideDirReport {
+  path: string;
+  exists: boolean;
+  writable: boolean;
+  wslDistro?: string;
+  /** 仅显式检查（validateOverrideDir）时探测 */
+  atomicRename?: boolean;
+  /** not_absolute / not_directory / not_writable */
+  errors: string[];
+  /** missing / wsl_path / atomic_rename_failed / config_not_found */
+  warnings: string[];
+}
+
+export interface ConfigDirRelocation {
+  oldPath: string;
+  newPath: string;
+  copiedFiles: number;
+  restartRequired: boolean;
+}
+
+export interface ToolUpdateOutput {
+  tool: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+export type JsonReimportSection =
+  | "providers"
+  | "mcp"
+  | "prompts"
+  | "skills"
+  | "settings";
+
+export interface JsonReimportSectionReport {
+  section: JsonReimportSection;
+  imported: number;
+  /** ID 已存在而跳过的条目 */
+  skipped: number;
+  failed: number;
+  errors: string[];
+}
+
+export interface JsonReimportReport {
+  dryRun: boolean;
+  sections: JsonReimportSectionReport[];
+}
+
+export type ImportItemStatus = "new" | "identical" | "conflict";
+
+export type ImportResolution =
+  | "keep_local"
+  | "take_import"
+  | "duplicate_rename"
+  | "merge_fields";
+
+// 导入时与已有条目冲突的统一处理策略（未指定时保持原有行为）
+export type MergeStrategy =
+  | "skip_existing"
+  | "overwrite"
+  | "merge_fields"
+  | "rename";
+
+export type ImportAction =
+  | "added"
+  | "unchanged"
+  | "skipped"
+  | "replaced"
+  | "merged"
+  | "renamed";
+
+export interface ImportItemOutcome {
+  key: string;
+  kind: ImportItem["kind"];
+  appType?: string;
+  id: string;
+  name: string;
+  action: ImportAction;
+  newId?: string;
+}
+
+export interface ImportItem {
+  key: string;
+  kind:
+    | "provider"
+    | "universalProvider"
+    | "mcpServer"
+    | "prompt"
+    | "customEndpoint";
+  appType?: string;
+  id: string;
+  name: string;
+  status: ImportItemStatus;
+  diff?: { field: string; local: string; import: string }[];
+}
+
+export interface ImportPreview {
+  items: ImportItem[];
+  newCount: number;
+  identicalCount: number;
+  conflictCount: number;
+}
+
+export interface ImportApplyResult {
+  added: number;
+  replaced: number;
+  renamed: number;
+  skipped: number;
+  merged: number;
+  items: ImportItemOutcome[];
+  backupId: string;
+}
+
+export interface BundleManifest {
+  mcpServers: string[];
+  prompts: string[];
+  skills: string[];
+  skillRepos: string[];
+}
+
+export interface ConfigBundle {
+  format: string;
+  version: number;
+  exportedAt: string;
+  manifest: BundleManifest;
+}
+
+export type BundleCategory = "mcpServers" | "prompts" | "skills";
+
+export interface BundleImportItem {
+  key: string;
+  category: BundleCategory;
+  id: string;
+  name: string;
+  status: ImportItemStatus;
+  outcome:
+    | "added"
+    | "replaced"
+    | "renamed"
+    | "skipped"
+    | "unchanged"
+    | "pendingInstall";
+}
+
+export interface BundleImportResult {
+  items: BundleImportItem[];
+  conflictCount: number;
+  errors: { category: BundleCategory; message: string }[];
+}
+
+export type ExternalConfigFormat = "claude-code-router";
+
+export interface ExternalImportResult {
+  imported: { appType: AppId; id: string; name: string }[];
+  skipped: string[];
+  unmapped: string[];
+}
+
+export type SyncBackend = "webdav" | "s3";
+
+export type SyncScope = "config" | "full";
+
+export interface SyncConfig {
+  backend: SyncBackend;
+  endpoint: string;
+  remotePath: string;
+  username: string;
+  password: string;
+  bucket?: string | null;
+  region?: string | null;
+  scope: SyncScope;
+}
+
+export interface SyncResult {
+  revision: number;
+  transferred: boolean;
+  deviceId: string;
 }
 
 export const settingsApi = {
@@ -14,8 +200,21 @@ export const settingsApi = {
     return await invoke("get_settings");
   },
 
-  async save(settings: Settings): Promise<boolean> {
-    return await invoke("save_settings", { settings });
+  async save(
+    settings: Settings,
+    options?: { acknowledgeWarnings?: boolean },
+  ): Promise<boolean> {
+    return await invoke("save_settings", {
+      settings,
+      acknowledgeWarnings: options?.acknowledgeWarnings ?? false,
+    });
+  },
+
+  async validateOverrideDir(
+    appId: AppId,
+    path: string,
+  ): Promise<OverrideDirReport> {
+    return await invoke("validate_override_dir", { app: appId, path });
   },
 
   async restart(): Promise<boolean> {
@@ -62,6 +261,10 @@ export const settingsApi = {
     return await invoke("set_app_config_dir_override", { path });
   },
 
+  async relocateConfigDir(newPath: string): Promise<ConfigDirRelocation> {
+    return await invoke("relocate_config_dir", { newPath });
+  },
+
   async applyClaudePluginConfig(options: {
     official: boolean;
   }): Promise<boolean> {
@@ -89,8 +292,81 @@ export const settingsApi = {
     return await invoke("export_config_to_file", { filePath });
   },
 
-  async importConfigFromFile(filePath: string): Promise<ConfigTransferResult> {
-    return await invoke("import_config_from_file", { filePath });
+  async importConfigFromFile(
+    filePath: string,
+    mergeStrategy?: MergeStrategy,
+  ): Promise<ConfigTransferResult> {
+    return await invoke("import_config_from_file", {
+      filePath,
+      mergeStrategy: mergeStrategy ?? null,
+    });
+  },
+
+  async previewImportConfig(
+    filePath: string,
+    passphrase?: string,
+  ): Promise<ImportPreview> {
+    return await invoke("preview_import_config", {
+      filePath,
+      passphrase: passphrase ?? null,
+    });
+  },
+
+  async applyImportConfig(
+    filePath: string,
+    resolutions: Record<string, ImportResolution>,
+    passphrase?: string,
+  ): Promise<ImportApplyResult> {
+    return await invoke("apply_import_config", {
+      filePath,
+      resolutions,
+      passphrase: passphrase ?? null,
+    });
+  },
+
+  async exportBundle(filePath: string): Promise<ConfigBundle> {
+    return await invoke("export_bundle", { filePath });
+  },
+
+  async importBundle(
+    filePath: string,
+    resolutions?: Record<string, ImportResolution>,
+  ): Promise<BundleImportResult> {
+    return await invoke("import_bundle", {
+      filePath,
+      resolutions: resolutions ?? null,
+    });
+  },
+
+  /** 从归档的旧配置（config.json.migrated）按分区重新导入，只补充缺失的条目 */
+  async reimportFromArchivedJson(
+    sections: JsonReimportSection[],
+    dryRun = false,
+  ): Promise<JsonReimportReport> {
+    return await invoke("reimport_from_archived_json", { sections, dryRun });
+  },
+
+  async importFromExternalFormat(
+    format: ExternalConfigFormat,
+    filePath: string,
+  ): Promise<ExternalImportResult> {
+    return await invoke("import_from_external_format", { format, filePath });
+  },
+
+  async getSyncConfig(): Promise<SyncConfig> {
+    return await invoke("get_sync_config");
+  },
+
+  async setSyncConfig(config: SyncConfig): Promise<boolean> {
+    return await invoke("set_sync_config", { config });
+  },
+
+  async syncPush(passphrase: string, force = false): Promise<SyncResult> {
+    return await invoke("sync_push", { passphrase, force });
+  },
+
+  async syncPull(passphrase: string, force = false): Promise<SyncResult> {
+    return await invoke("sync_pull", { passphrase, force });
   },
 
   async syncCurrentProvidersLive(): Promise<void> {
@@ -124,6 +400,26 @@ export const settingsApi = {
     return await invoke("get_auto_launch_status");
   },
 
+  async getStartupDelay(): Promise<number> {
+    return await invoke("get_startup_delay");
+  },
+
+  async setStartupDelay(seconds: number): Promise<boolean> {
+    return await invoke("set_startup_delay", { seconds });
+  },
+
+  async getReadOnlyMode(): Promise<boolean> {
+    return await invoke("get_read_only_mode");
+  },
+
+  async setReadOnlyMode(enabled: boolean): Promise<boolean> {
+    return await invoke("set_read_only_mode", { enabled });
+  },
+
+  async setLanguage(lang: "zh" | "en" | "ja"): Promise<boolean> {
+    return await invoke("set_language", { lang });
+  },
+
   async getToolVersions(): Promise<
     Array<{
       name: string;
@@ -135,6 +431,19 @@ export const settingsApi = {
     return await invoke("get_tool_versions");
   },
 
+  /** 更新已安装的 CLI 到最新版本，返回是否成功；输出通过 onToolUpdateOutput 推送 */
+  async updateTool(tool: string): Promise<boolean> {
+    return await invoke("update_tool", { tool });
+  },
+
+  async onToolUpdateOutput(
+    handler: (output: ToolUpdateOutput) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<ToolUpdateOutput>("tool-update-output", (event) => {
+      handler(event.payload);
+    });
+  },
+
   async getRectifierConfig(): Promise<RectifierConfig> {
     return await invoke("get_rectifier_config");
   },
@@ -150,14 +459,214 @@ export const settingsApi = {
   async setLogConfig(config: LogConfig): Promise<boolean> {
     return await invoke("set_log_config", { config });
   },
+
+  async exportSettings(path: string): Promise<SettingsExport> {
+    return await invoke("export_settings", { path });
+  },
+
+  async importSettings(path: string): Promise<Settings> {
+    return await invoke("import_settings", { path });
+  },
+
+  async getThemeConfig(): Promise<ThemeConfig> {
+    return await invoke("get_theme_config");
+  },
+
+  async setThemeConfig(config: ThemeConfig): Promise<boolean> {
+    return await invoke("set_theme_config", { config });
+  },
+
+  async getHotkeyConfig(): Promise<HotkeyConfig> {
+    return await invoke("get_hotkey_config");
+  },
+
+  /** 保存并重新注册快捷键，返回注册失败的绑定 */
+  async setHotkeyConfig(config: HotkeyConfig): Promise<HotkeyBindingError[]> {
+    return await invoke("set_hotkey_config", { config });
+  },
+
+  async getStartupStatus(): Promise<StartupStatus> {
+    return await invoke("get_startup_status");
+  },
+
+  /** 启动各阶段耗时（排查启动慢） */
+  async getStartupTimings(): Promise<StartupTimings> {
+    return await invoke("get_startup_timings");
+  },
+
+  /** 启动时延后执行的导入完成 */
+  async onInitialImportFinished(
+    handler: (status: StartupStatus) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<StartupStatus>("initial-import-finished", (event) => {
+      handler(event.payload);
+    });
+  },
+
+  /** 检查数据库、Live 配置与 Skills 目录的可写性与剩余空间 */
+  async runPreflightChecks(): Promise<PreflightCheck[]> {
+    return await invoke("run_preflight_checks");
+  },
+
+  async getOnboardingReport(): Promise<OnboardingReport> {
+    return await invoke("get_onboarding_report");
+  },
+
+  /** 安装缺失的 CLI 后重新导入该应用的配置 */
+  async rerunInitialImport(appId: AppId): Promise<AppOnboardingStatus> {
+    return await invoke("rerun_initial_import", { app: appId });
+  },
+
+  async getStatusSnapshot(): Promise<StatusSnapshot> {
+    return await invoke("get_status_snapshot");
+  },
+
+  async getCrashLog(maxKb?: number): Promise<LogTail> {
+    return await invoke("get_crash_log", { maxKb: maxKb ?? null });
+  },
+
+  async clearCrashLog(): Promise<boolean> {
+    return await invoke("clear_crash_log");
+  },
+
+  async getAppLogTail(lines?: number): Promise<LogTail> {
+    return await invoke("get_app_log_tail", { lines: lines ?? null });
+  },
 };
 
+export interface StartupStatus {
+  initError: { path: string; error: string } | null;
+  jsonMigrated: boolean;
+  skillsMigration: { count: number; error?: string } | null;
+  /** 代理状态恢复在后台进行，完成前为 false */
+  proxyRestoreDone: boolean;
+  proxyRestoredApps: string[];
+  /** 启动时发现的无法解析的供应商行 */
+  invalidProviders: InvalidProviderRow[];
+  /** 延后执行的导入是否已完成（完成时发射 initial-import-finished） */
+  initialImportDone: boolean;
+}
+
+export interface StartupPhaseTiming {
+  name: string;
+  durationMs: number;
+  /** 是否在窗口显示后于后台执行 */
+  deferred: boolean;
+  /** 阶段开始时距 setup 开始的毫秒数 */
+  startedAtMs: number;
+}
+
+export interface StartupTimings {
+  phases: StartupPhaseTiming[];
+  windowShownMs: number | null;
+  initialImportFinishedMs: number | null;
+}
+
+export interface PreflightCheck {
+  /** database / claude / codex / gemini / opencode / skills */
+  name: string;
+  path: string;
+  writable: boolean;
+  /** 无法查询剩余空间时缺省 */
+  availableBytes?: number;
+  requiredBytes: number;
+  ok: boolean;
+  error?: string;
+}
+
+export interface InvalidProviderRow {
+  appType: string;
+  id: string;
+  name: string;
+  field: "settings_config" | "meta";
+  error: string;
+}
+
+export interface AppImportOutcome {
+  providersImported: number;
+  providerError?: string;
+  mcpImported: number;
+  mcpError?: string;
+  promptsImported: number;
+  promptsError?: string;
+}
+
+export interface AppOnboardingStatus {
+  app: AppId;
+  cliFound: boolean;
+  cliVersion?: string;
+  liveConfigPath: string;
+  liveConfigExists: boolean;
+  providerCount: number;
+  mcpServers: number;
+  promptFileExists: boolean;
+  skillsDetected: number;
+  import: AppImportOutcome;
+  /** Live 配置不存在导致的“未找到”不计入 */
+  errors: string[];
+}
+
+export interface OnboardingReport {
+  generatedAt: number;
+  apps: AppOnboardingStatus[];
+}
+
+export interface StatusSnapshot {
+  generatedAt: string;
+  currentProviders: Record<string, { id: string; name: string | null } | null>;
+  proxyRunning: boolean;
+  proxyApps: Record<string, { takeover: boolean; autoFailover: boolean }>;
+  /** 未检测过工具版本时为 null（快照不会触发检测） */
+  toolVersions: {
+    checkedAt: string;
+    tools: Array<{
+      name: string;
+      version: string | null;
+      latest_version: string | null;
+      error: string | null;
+    }>;
+  } | null;
+  openCircuits: Array<{
+    appType: string;
+    providerId: string;
+    state: "closed" | "open" | "half_open";
+    consecutiveFailures: number;
+  }>;
+}
+
+export interface LogTail {
+  path: string;
+  exists: boolean;
+  size: number;
+  truncated: boolean;
+  content: string;
+}
+
 export interface RectifierConfig {
   enabled: boolean;
   requestThinkingSignature: boolean;
 }
 
+export interface SettingsProxyDefaults {
+  listenAddress: string;
+  listenPort: number;
+  enableLogging: boolean;
+}
+
+export interface SettingsExport {
+  version: number;
+  exportedAt: number;
+  settings: Settings;
+  logConfig?: LogConfig;
+  proxyDefaults?: SettingsProxyDefaults;
+}
+
 export interface LogConfig {
   enabled: boolean;
   level: "error" | "warn" | "info" | "debug" | "trace";
+  /** 按模块覆盖的级别，如 { proxy: "debug", tray: "warn" }，优先于全局级别 */
+  moduleLevels?: Record<
+    string,
+    "off" | "error" | "warn" | "info" | "debug" | "trace"
+  >;
 }
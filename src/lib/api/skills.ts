@@ -38,6 +38,23 @@ export interface DiscoverableSkill {
   repoBranch: string;
 }
 
+/** 批量安装中单个 Skill 的结果 */
+export interface SkillBulkInstallResult {
+  key: string;
+  directory: string;
+  status: "installed" | "already_installed" | "conflict" | "failed";
+  skill?: InstalledSkill;
+  error?: string;
+}
+
+/** Skill 启用矩阵中的一行 */
+export interface SkillEnablementRow {
+  skillId: string;
+  name: string;
+  directory: string;
+  apps: Record<AppId, boolean>;
+}
+
 /** 未管理的 Skill（用于导入） */
 export interface UnmanagedSkill {
   directory: string;
@@ -59,12 +76,19 @@ export interface Skill {
   repoBranch?: string;
 }
 
+/** 仓库托管类型 */
+export type SkillRepoHost = "github" | "gitlab" | "raw_git";
+
 /** 仓库配置 */
 export interface SkillRepo {
   owner: string;
   name: string;
   branch: string;
   enabled: boolean;
+  /** 托管类型（缺省为 GitHub） */
+  hostKind?: SkillRepoHost;
+  /** GitHub 站点根地址 / GitLab API 地址 / raw git 的 clone 地址 */
+  baseUrl?: string;
 }
 
 // ========== API ==========
@@ -85,6 +109,21 @@ export const skillsApi = {
     return await invoke("install_skill_unified", { skill, currentApp });
   },
 
+  /** 从仓库批量安装 Skills（filter 为目录名 glob） */
+  async installFromRepo(
+    repo: { owner: string; name: string; branch?: string },
+    currentApp: AppId,
+    filter?: string,
+  ): Promise<SkillBulkInstallResult[]> {
+    return await invoke("install_skills_from_repo", {
+      owner: repo.owner,
+      name: repo.name,
+      branch: repo.branch,
+      filter,
+      currentApp,
+    });
+  },
+
   /** 卸载 Skill（统一卸载） */
   async uninstallUnified(id: string): Promise<boolean> {
     return await invoke("uninstall_skill_unified", { id });
@@ -95,6 +134,20 @@ export const skillsApi = {
     return await invoke("toggle_skill_app", { id, app, enabled });
   },
 
+  /** 获取所有 Skill 的应用启用矩阵 */
+  async getEnablementMatrix(): Promise<SkillEnablementRow[]> {
+    return await invoke("get_skill_enablement_matrix");
+  },
+
+  /** 切换启用矩阵中的单个开关 */
+  async setEnablement(
+    skillId: string,
+    appType: AppId,
+    enabled: boolean,
+  ): Promise<SkillEnablementRow> {
+    return await invoke("set_skill_enablement", { skillId, appType, enabled });
+  },
+
   /** 扫描未管理的 Skills */
   async scanUnmanaged(): Promise<UnmanagedSkill[]> {
     return await invoke("scan_unmanaged_skills");
@@ -153,6 +206,15 @@ export const skillsApi = {
     return await invoke("remove_skill_repo", { owner, name });
   },
 
+  /** 设置或清除仓库访问令牌（保存在设置中，不随仓库列表返回） */
+  async setRepoToken(
+    owner: string,
+    name: string,
+    token: string | null,
+  ): Promise<boolean> {
+    return await invoke("set_skill_repo_token", { owner, name, token });
+  },
+
   // ========== ZIP 安装 ==========
 
   /** 打开 ZIP 文件选择对话框 */
@@ -2,13 +2,17 @@ import { invoke } from "@tauri-apps/api/core";
 import type {
   UsageSummary,
   DailyStats,
+  TrendBucket,
   ProviderStats,
   ModelStats,
+  ProviderCostComparison,
   RequestLog,
   LogFilters,
   ModelPricing,
+  CostEstimate,
   ProviderLimitStatus,
   PaginatedLogs,
+  DatabaseStats,
 } from "@/types/usage";
 import type { UsageResult } from "@/types";
 import type { AppId } from "./types";
@@ -29,6 +33,7 @@ export const usageApi = {
     accessToken?: string,
     userId?: string,
     templateType?: "custom" | "general" | "newapi",
+    allowedHosts?: string[],
   ): Promise<UsageResult> => {
     return invoke("testUsageScript", {
       providerId,
@@ -40,6 +45,7 @@ export const usageApi = {
       accessToken,
       userId,
       templateType,
+      allowedHosts,
     });
   },
 
@@ -51,21 +57,46 @@ export const usageApi = {
     return invoke("get_usage_summary", { startDate, endDate });
   },
 
+  /** 不传 bucket 时按窗口自动选择小时/天；传入时按本地时区的自然边界分桶 */
   getUsageTrends: async (
     startDate?: number,
     endDate?: number,
+    bucket?: TrendBucket,
   ): Promise<DailyStats[]> => {
-    return invoke("get_usage_trends", { startDate, endDate });
+    return invoke("get_usage_trends", { startDate, endDate, bucket });
   },
 
-  getProviderStats: async (): Promise<ProviderStats[]> => {
-    return invoke("get_provider_stats");
+  /** 不传时间范围时返回全部历史统计 */
+  getProviderStats: async (
+    startDate?: number,
+    endDate?: number,
+  ): Promise<ProviderStats[]> => {
+    return invoke("get_provider_stats", { startDate, endDate });
+  },
+
+  /** 获取 1 USD 兑换指定货币的汇率，只返回结果不写入设置 */
+  fetchUsdExchangeRate: async (currency: string): Promise<string> => {
+    return invoke("fetch_usd_exchange_rate", { currency });
   },
 
   getModelStats: async (): Promise<ModelStats[]> => {
     return invoke("get_model_stats");
   },
 
+  getProviderCostComparison: async (options?: {
+    appId?: AppId;
+    modelFilter?: string;
+    startDate?: number;
+    endDate?: number;
+  }): Promise<ProviderCostComparison[]> => {
+    return invoke("get_provider_cost_comparison", {
+      app: options?.appId,
+      modelFilter: options?.modelFilter,
+      startDate: options?.startDate,
+      endDate: options?.endDate,
+    });
+  },
+
   getRequestLogs: async (
     filters: LogFilters,
     page: number = 0,
@@ -86,6 +117,18 @@ export const usageApi = {
     return invoke("get_model_pricing");
   },
 
+  /** inputTokens 含缓存命中部分，costMultiplier 缺省为 1 */
+  estimateRequestCost: async (params: {
+    model: string;
+    inputTokens: number;
+    outputTokens: number;
+    cacheReadTokens?: number;
+    cacheCreationTokens?: number;
+    costMultiplier?: string;
+  }): Promise<CostEstimate> => {
+    return invoke("estimate_request_cost", params);
+  },
+
   updateModelPricing: async (
     modelId: string,
     displayName: string,
@@ -114,4 +157,8 @@ export const usageApi = {
   ): Promise<ProviderLimitStatus> => {
     return invoke("check_provider_limits", { providerId, appType });
   },
+
+  getDatabaseStats: async (): Promise<DatabaseStats> => {
+    return invoke("get_database_stats");
+  },
 };
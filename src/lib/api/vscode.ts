@@ -9,18 +9,45 @@ export interface EndpointLatencyResult {
   error?: string;
 }
 
+export interface EndpointLatencyRecord {
+  id: number;
+  providerId: string;
+  appType: string;
+  url: string;
+  latency: number | null;
+  status: number | null;
+  testedAt: number;
+}
+
 export const vscodeApi = {
   async getLiveProviderSettings(appId: AppId) {
     return await invoke("read_live_provider_settings", { app: appId });
   },
 
+  // 传入 appId 与 providerId 时，结果会记入该供应商的端点测速历史
   async testApiEndpoints(
     urls: string[],
-    options?: { timeoutSecs?: number },
+    options?: { timeoutSecs?: number; appId?: AppId; providerId?: string },
   ): Promise<EndpointLatencyResult[]> {
     return await invoke("test_api_endpoints", {
       urls,
       timeoutSecs: options?.timeoutSecs,
+      app: options?.appId,
+      providerId: options?.providerId,
+    });
+  },
+
+  async getEndpointLatencyHistory(
+    appId: AppId,
+    providerId: string,
+    url: string,
+    limit?: number,
+  ): Promise<EndpointLatencyRecord[]> {
+    return await invoke("get_endpoint_latency_history", {
+      app: appId,
+      providerId,
+      url,
+      limit,
     });
   },
 
@@ -70,6 +97,11 @@ export const vscodeApi = {
     });
   },
 
+  /** 合并所有供应商中重复的自定义端点，返回删除的条数 */
+  async dedupeCustomEndpoints(): Promise<number> {
+    return await invoke("dedupe_custom_endpoints");
+  },
+
   async exportConfigToFile(filePath: string) {
     return await invoke("export_config_to_file", {
       filePath,
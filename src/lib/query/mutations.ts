@@ -205,8 +205,14 @@ export const useSaveSettingsMutation = () => {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: async (settings: Settings) => {
-      await settingsApi.save(settings);
+    mutationFn: async ({
+      settings,
+      acknowledgeWarnings,
+    }: {
+      settings: Settings;
+      acknowledgeWarnings?: boolean;
+    }) => {
+      await settingsApi.save(settings, { acknowledgeWarnings });
     },
     onSuccess: async () => {
       await queryClient.invalidateQueries({ queryKey: ["settings"] });
@@ -163,6 +163,22 @@ export const useCodexQuotaQuery = (
   });
 };
 
+/** 缓存的远程预设（按名称覆盖到内置预设之上），未配置目录或读取失败时为空数组 */
+export const useRemotePresetsQuery = (appId: AppId) => {
+  return useQuery<Array<{ name: string }>>({
+    queryKey: ["providerPresets", appId],
+    queryFn: async () => {
+      try {
+        return await providersApi.getRemotePresets(appId);
+      } catch (error) {
+        console.error("获取远程预设失败:", error);
+        return [];
+      }
+    },
+    staleTime: Infinity,
+  });
+};
+
 export const useSessionsQuery = () => {
   return useQuery<SessionMeta[]>({
     queryKey: ["sessions"],
@@ -94,7 +94,7 @@ export function useUsageTrends(days: number, options?: UsageQueryOptions) {
 export function useProviderStats(options?: UsageQueryOptions) {
   return useQuery({
     queryKey: usageKeys.providerStats(),
-    queryFn: usageApi.getProviderStats,
+    queryFn: () => usageApi.getProviderStats(),
     refetchInterval: options?.refetchInterval ?? DEFAULT_REFETCH_INTERVAL_MS, // 每30秒自动刷新
     refetchIntervalInBackground: options?.refetchIntervalInBackground ?? false,
   });
@@ -21,7 +21,7 @@ export interface Provider {
   // 可选：供应商元数据（仅存于 ~/.cc-switch/config.json，不写入 live 配置）
   meta?: ProviderMeta;
   // 图标配置
-  icon?: string; // 图标名称（如 "openai", "anthropic"）
+  icon?: string; // 图标名称（如 "openai", "anthropic"），或 https 图片地址
   iconColor?: string; // 图标颜色（Hex 格式，如 "#00A67E"）
   // 是否加入故障转移队列
   inFailoverQueue?: boolean;
@@ -37,6 +37,9 @@ export interface CustomEndpoint {
   url: string;
   addedAt: number;
   lastUsed?: number;
+  // 最近一次测速延迟（毫秒）与测速时间
+  lastLatency?: number;
+  lastTestedAt?: number;
 }
 
 // 端点候选项（用于端点测速弹窗）
@@ -59,6 +62,7 @@ export interface UsageScript {
   userId?: string; // 用户ID（NewAPI 模板使用）
   autoQueryInterval?: number; // 自动查询间隔（单位：分钟，0 表示禁用）
   autoIntervalMinutes?: number; // 自动查询间隔（分钟）- 别名字段
+  allowedHosts?: string[]; // 网络白名单（支持 *.example.com，为空时不限制）
   request?: {
     // 请求配置
     url?: string; // 请求 URL
@@ -119,6 +123,16 @@ export interface ProviderProxyConfig {
   proxyPassword?: string;
 }
 
+// 供应商单独的请求重试配置（优先于应用级 max_retries；未配置时不在同一供应商上重试）
+export interface ProviderRetryConfig {
+  // 同一供应商的最大重试次数（0 = 失败后立即切换下一个供应商）
+  maxRetries?: number;
+  // 退避策略：固定间隔 / 指数增长
+  backoff?: "fixed" | "exponential";
+  // 退避基础间隔（毫秒，默认 500）
+  backoffMs?: number;
+}
+
 // 供应商元数据（字段名与后端一致，保持 snake_case）
 export interface ProviderMeta {
   // 自定义端点：以 URL 为键，值为端点信息
@@ -135,6 +149,12 @@ export interface ProviderMeta {
   testConfig?: ProviderTestConfig;
   // 供应商单独的代理配置
   proxyConfig?: ProviderProxyConfig;
+  // 供应商单独的请求重试配置
+  retryConfig?: ProviderRetryConfig;
+  // 代理转发的每分钟请求数上限（未设置或 0 = 不限流）
+  rateLimitRpm?: number;
+  // 超出速率限制时：排队等待 / 切换下一个供应商
+  rateLimitOverflow?: "queue" | "failover";
   // 供应商成本倍率
   costMultiplier?: string;
   // 供应商计费模式来源
@@ -143,11 +163,22 @@ export interface ProviderMeta {
   // - "anthropic": 原生 Anthropic Messages API 格式，直接透传
   // - "openai_chat": OpenAI Chat Completions 格式，需要格式转换
   apiFormat?: "anthropic" | "openai_chat";
+  // 模型别名：规范模型名 → 供应商实际模型名（代理转发时改写，统计仍按规范名归类）
+  modelAliases?: Record<string, string>;
+  // 默认模型：角色 → 模型名，写入 Live 配置时覆盖 settingsConfig
+  // - Claude：model / haiku / sonnet / opus
+  // - Codex：model
+  defaultModels?: Record<string, string>;
 }
 
 // Skill 同步方式
 export type SkillSyncMethod = "auto" | "symlink" | "copy";
 
+// Claude Live 配置写入方式
+// - "replace": 切换时整体重写 settings.json（默认）
+// - "profiles": 写入 ~/.claude/profiles/<id>.json，并从 settings.json 中移除供应商 env 与模型键
+export type ClaudeWriteMode = "replace" | "profiles";
+
 // Claude API 格式类型
 // - "anthropic": 原生 Anthropic Messages API 格式，直接透传
 // - "openai_chat": OpenAI Chat Completions 格式，需要格式转换
@@ -161,6 +192,14 @@ export interface VisibleApps {
   opencode: boolean;
 }
 
+// 由 CC Switch 管理 MCP 的应用
+export interface McpManagedApps {
+  claude: boolean;
+  codex: boolean;
+  gemini: boolean;
+  opencode: boolean;
+}
+
 // 应用设置类型（用于设置对话框与 Tauri API）
 // 存储在本地 ~/.cc-switch/settings.json，不随数据库同步
 export interface Settings {
@@ -177,11 +216,21 @@ export interface Settings {
   launchOnStartup?: boolean;
   // 静默启动（程序启动时不显示主窗口）
   silentStartup?: boolean;
+  // 启动延迟（秒）：恢复代理状态前先等待，避免与其他开机启动程序竞争
+  startupDelaySeconds?: number;
   // 首选语言（可选，默认中文）
   language?: "en" | "zh" | "ja";
+  // 切换供应商前先进行健康检查，失败时拒绝切换
+  verifyBeforeSwitch?: boolean;
+  // 只读模式：不写入任何 Live 配置文件，仅修改数据库
+  readOnlyMode?: boolean;
+  // 强制执行供应商消费限额：超出后代理故障转移时跳过该供应商，直到窗口重置
+  enforceProviderLimits?: boolean;
 
   // 主页面显示的应用（默认全部显示）
   visibleApps?: VisibleApps;
+  // 由 CC Switch 管理 MCP 的应用（未设置时管理全部应用）
+  mcpManagedApps?: McpManagedApps;
 
   // ===== 设备级目录覆盖 =====
   // 覆盖 Claude Code 配置目录（可选）
@@ -192,6 +241,8 @@ export interface Settings {
   geminiConfigDir?: string;
   // 覆盖 OpenCode 配置目录（可选）
   opencodeConfigDir?: string;
+  // Claude Live 配置写入方式（默认 replace）
+  claudeWriteMode?: ClaudeWriteMode;
 
   // ===== 当前供应商 ID（设备级）=====
   // 当前 Claude 供应商 ID（优先于数据库 is_current）
@@ -211,6 +262,57 @@ export interface Settings {
   // Windows: "cmd" | "powershell" | "wt"
   // Linux: "gnome-terminal" | "konsole" | "xfce4-terminal" | "alacritty" | "kitty" | "ghostty"
   preferredTerminal?: string;
+
+  // ===== 预设目录 =====
+  // 远程供应商预设目录地址（可选，未配置时不下载远程预设）
+  presetCatalogUrl?: string;
+
+  // ===== 提示词 =====
+  // 多个启用的提示词写入 live 文件时的分隔内容（默认空行）
+  promptSegmentDelimiter?: string;
+
+  // ===== 用量展示 =====
+  // 成本展示货币（ISO 4217 代码，如 "EUR"；未设置时只展示 USD）
+  displayCurrency?: string;
+  // 1 USD 兑换展示货币的汇率（可手动填写或通过 fetchUsdExchangeRate 获取）
+  usdExchangeRate?: string;
+
+  // ===== 外观 =====
+  // 窗口主题模式（固定 / 跟随系统 / 按时间计划切换）
+  themeConfig?: ThemeConfig;
+
+  // ===== 快捷键 =====
+  // 全局快捷键绑定（切换下一个供应商 / 启停代理）
+  hotkeys?: HotkeyConfig;
+}
+
+export type ThemeMode = "light" | "dark" | "system" | "schedule";
+
+export interface ThemeConfig {
+  mode: ThemeMode;
+  // 计划模式下切换为浅色/深色的本地时间（HH:MM）
+  lightStart: string;
+  darkStart: string;
+}
+
+// theme-changed 事件载荷
+export interface ThemeChangedPayload {
+  mode: ThemeMode;
+  theme: "light" | "dark";
+}
+
+export interface HotkeyConfig {
+  // 按应用切换到下一个供应商，如 { claude: "CmdOrCtrl+Alt+1" }
+  nextProvider?: Record<string, string>;
+  toggleProxy?: string;
+}
+
+// 注册失败的快捷键（如已被其他应用占用）
+export interface HotkeyBindingError {
+  // nextProvider:<app> 或 toggleProxy
+  action: string;
+  accelerator: string;
+  message: string;
 }
 
 export interface SessionMeta {
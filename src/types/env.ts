@@ -27,3 +27,15 @@ export interface BackupInfo {
   /** 被备份的环境变量冲突列表 */
   conflicts: EnvConflict[];
 }
+
+/**
+ * 自动处理环境变量冲突的结果
+ */
+export interface EnvAutoResolveReport {
+  /** 已在 shell 配置文件中注释掉的变量 */
+  resolved: EnvConflict[];
+  /** 无法自动处理的变量（进程/系统环境变量、主目录以外的配置文件） */
+  skipped: EnvConflict[];
+  /** 本次修改前的备份（无修改时为 null），可通过 restoreEnvBackup 撤销 */
+  backup: BackupInfo | null;
+}
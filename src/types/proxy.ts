@@ -48,6 +48,22 @@ export interface ProxyTakeoverStatus {
   opencode: boolean;
 }
 
+// 紧急恢复中单个应用的处理结果
+export interface EmergencyRestoreAppReport {
+  appType: string;
+  backupRestored: boolean;
+  placeholdersRemoved: boolean;
+  rewrittenFromProvider: boolean;
+  takeoverDisabled: boolean;
+  stillTakenOver: boolean;
+  errors?: string[];
+}
+
+export interface EmergencyRestoreReport {
+  proxyStopped: boolean;
+  apps: EmergencyRestoreAppReport[];
+}
+
 export interface ProviderHealth {
   provider_id: string;
   app_type: string;
@@ -57,8 +73,14 @@ export interface ProviderHealth {
   last_failure_at: string | null;
   last_error: string | null;
   updated_at: string;
+  forced_state?: "open" | "closed" | null;
+  /** 手动状态失效时间（毫秒时间戳），为空表示直到手动恢复 */
+  forced_until?: number | null;
 }
 
+/** 熔断器手动模式：auto 为自动判断 */
+export type CircuitOverrideMode = "auto" | "open" | "closed";
+
 // 熔断器相关类型
 export interface CircuitBreakerConfig {
   failureThreshold: number;
@@ -78,6 +100,51 @@ export interface CircuitBreakerStats {
   failedRequests: number;
 }
 
+// 熔断器状态转换事件（circuit-breaker-state-changed 事件载荷 / 历史记录）
+export interface CircuitBreakerEvent {
+  id?: number;
+  appType: string;
+  providerId: string;
+  fromState: CircuitState;
+  toState: CircuitState;
+  consecutiveFailures: number;
+  failedRequests: number;
+  totalRequests: number;
+  error?: string | null;
+  createdAt: number;
+}
+
+// 故障转移模拟中单个供应商的结果
+export type SimulatedOutcome =
+  | "forced_open"
+  | "circuit_open"
+  | "limit_tripped"
+  | "failed"
+  | "selected"
+  | "not_reached";
+
+export interface SimulatedProvider {
+  providerId: string;
+  providerName: string;
+  forcedState: Exclude<CircuitOverrideMode, "auto"> | null;
+  isHealthy: boolean;
+  consecutiveFailures: number;
+  circuitState: CircuitState;
+  circuitAvailable: boolean;
+  limitTripped: boolean;
+  outcome: SimulatedOutcome;
+}
+
+// 故障转移模拟结果（simulate_failover）
+export interface FailoverSimulation {
+  appType: string;
+  autoFailoverEnabled: boolean;
+  providers: SimulatedProvider[];
+  // 实际会发起请求的供应商 ID（按尝试顺序）
+  tried: string[];
+  selected: string | null;
+}
+
 // 供应商健康状态枚举
 export enum ProviderHealthStatus {
   Healthy = "healthy",
@@ -117,6 +184,14 @@ export interface GlobalProxyConfig {
   listenAddress: string;
   listenPort: number;
   enableLogging: boolean;
+  // 端口被占用时自动向上查找可用端口
+  autoPort: boolean;
+}
+
+// 自动换端口事件（proxy-port-changed 事件载荷）
+export interface ProxyPortChangedEvent {
+  previousPort: number;
+  port: number;
 }
 
 // 应用级代理配置（每个 app 独立）
@@ -133,4 +208,28 @@ export interface AppProxyConfig {
   circuitTimeoutSeconds: number;
   circuitErrorRateThreshold: number;
   circuitMinRequests: number;
+  captureBodies?: boolean;
+  captureMaxBytes?: number;
+}
+
+// 请求重放结果
+export interface ReplayResult {
+  requestId: string;
+  replayOf: string;
+  providerId: string;
+  url: string;
+  statusCode: number;
+  success: boolean;
+  latencyMs: number;
+  responseBody?: string;
+  truncated: boolean;
+  errorMessage?: string;
+}
+
+// "复制为 curl" 的结果
+export interface CurlExport {
+  command: string;
+  bodyIncluded: boolean;
+  bodyTruncated: boolean;
+  authIncluded: boolean;
 }
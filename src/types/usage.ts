@@ -31,6 +31,28 @@ export interface RequestLog {
   statusCode: number;
   errorMessage?: string;
   createdAt: number;
+  /** 上游尝试次数（重试/故障转移/熔断跳过），旧记录为 0 */
+  attemptCount: number;
+  /** 上游尝试时间线（仅详情接口返回） */
+  attempts?: RequestAttempt[];
+  /** 重放来源请求 ID（仅手动重放的请求有值，不计入统计） */
+  replayOf?: string;
+}
+
+export type RequestAttemptKind =
+  | "initial"
+  | "retry"
+  | "failover"
+  | "circuit_skip"
+  | "rate_limited";
+
+export interface RequestAttempt {
+  providerId: string;
+  kind: RequestAttemptKind;
+  startOffsetMs: number;
+  durationMs: number;
+  statusCode?: number;
+  error?: string;
 }
 
 export interface PaginatedLogs {
@@ -49,6 +71,24 @@ export interface ModelPricing {
   cacheCreationCostPerMillion: string;
 }
 
+export interface CostEstimate {
+  modelId: string;
+  /** 未找到模型定价时各项成本为 "0" */
+  pricingFound: boolean;
+  costMultiplier: string;
+  inputCost: string;
+  outputCost: string;
+  cacheReadCost: string;
+  cacheCreationCost: string;
+  totalCost: string;
+}
+
+/** 按展示货币换算后的金额（原始 USD 金额保留在 totalCost 中） */
+export interface DisplayCost {
+  currency: string;
+  amount: string;
+}
+
 export interface UsageSummary {
   totalRequests: number;
   totalCost: string;
@@ -57,8 +97,12 @@ export interface UsageSummary {
   totalCacheCreationTokens: number;
   totalCacheReadTokens: number;
   successRate: number;
+  displayCost?: DisplayCost | null;
 }
 
+/** 趋势分桶粒度（按本地时区的自然小时/日/周/月对齐） */
+export type TrendBucket = "hour" | "day" | "week" | "month";
+
 export interface DailyStats {
   date: string;
   requestCount: number;
@@ -68,6 +112,7 @@ export interface DailyStats {
   totalOutputTokens: number;
   totalCacheCreationTokens: number;
   totalCacheReadTokens: number;
+  displayCost?: DisplayCost | null;
 }
 
 export interface ProviderStats {
@@ -77,7 +122,9 @@ export interface ProviderStats {
   totalTokens: number;
   totalCost: string;
   successRate: number;
+  errorRate: number;
   avgLatencyMs: number;
+  displayCost?: DisplayCost | null;
 }
 
 export interface ModelStats {
@@ -88,6 +135,24 @@ export interface ModelStats {
   avgCostPerRequest: string;
 }
 
+export interface ProviderCostComparison {
+  providerId: string;
+  providerName: string;
+  appType: string;
+  model: string;
+  requestCount: number;
+  /** 错误率（百分比） */
+  errorRate: number;
+  avgLatencyMs: number;
+  /** 实际花费（含倍率） */
+  totalSpend: string;
+  pricedOutputTokens: number;
+  /** 有 token 但缺少定价的请求数（不计入成本） */
+  unpricedRequests: number;
+  costPer1kOutput?: string;
+  baseCostPer1kOutput?: string;
+}
+
 export interface LogFilters {
   appType?: string;
   providerName?: string;
@@ -105,6 +170,12 @@ export interface ProviderLimitStatus {
   monthlyUsage: string;
   monthlyLimit?: string;
   monthlyExceeded: boolean;
+  // 窗口重置时间（毫秒时间戳）：次日本地零点 / 次月 1 日本地零点
+  dailyResetsAt: number;
+  monthlyResetsAt: number;
+  // 已开启限额强制且超出限额：代理故障转移时跳过该供应商
+  limitTripped: boolean;
+  trippedUntil?: number;
 }
 
 export type TimeRange = "1d" | "7d" | "30d";
@@ -114,3 +185,10 @@ export interface StatsFilters {
   providerId?: string;
   appType?: string;
 }
+
+export interface DatabaseStats {
+  rowCounts: { table: string; rows: number }[];
+  fileSizeBytes: number;
+  walSizeBytes: number;
+  walPendingPages: number;
+}
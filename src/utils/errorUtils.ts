@@ -1,3 +1,16 @@
+import i18n from "@/i18n";
+
+/**
+ * 后端本地化错误同时携带中英文文本，按当前界面语言选择（切换语言后无需重新请求）
+ */
+const pickLocalizedMessage = (
+  errObject: Record<string, unknown>,
+): string | undefined => {
+  const { zh, en } = errObject;
+  if (typeof zh !== "string" || typeof en !== "string") return undefined;
+  return i18n.language?.startsWith("zh") ? zh : en;
+};
+
 /**
  * 从各种错误对象中提取错误信息
  * @param error 错误对象
@@ -15,6 +28,11 @@ export const extractErrorMessage = (error: unknown): string => {
   if (typeof error === "object") {
     const errObject = error as Record<string, unknown>;
 
+    const localized = pickLocalizedMessage(errObject);
+    if (localized?.trim()) {
+      return localized;
+    }
+
     const candidate = errObject.message ?? errObject.error ?? errObject.detail;
     if (typeof candidate === "string" && candidate.trim()) {
       return candidate;
@@ -24,6 +24,10 @@ vi.mock("@/components/ui/dialog", () => ({
   ),
 }));
 
+vi.mock("@/lib/query/queries", () => ({
+  useRemotePresetsQuery: () => ({ data: [] }),
+}));
+
 let mockFormValues: ProviderFormValues;
 
 vi.mock("@/components/providers/forms/ProviderForm", () => ({
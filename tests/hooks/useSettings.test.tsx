@@ -11,8 +11,10 @@ const applyClaudeOnboardingSkipMock = vi.fn();
 const clearClaudeOnboardingSkipMock = vi.fn();
 const syncCurrentProvidersLiveMock = vi.fn();
 const updateTrayMenuMock = vi.fn();
+const validateOverrideDirMock = vi.fn();
 const toastErrorMock = vi.fn();
 const toastSuccessMock = vi.fn();
+const toastWarningMock = vi.fn();
 
 let settingsFormMock: any;
 let directorySettingsMock: any;
@@ -23,6 +25,7 @@ vi.mock("sonner", () => ({
   toast: {
     error: (...args: unknown[]) => toastErrorMock(...args),
     success: (...args: unknown[]) => toastSuccessMock(...args),
+    warning: (...args: unknown[]) => toastWarningMock(...args),
   },
 }));
 
@@ -58,6 +61,8 @@ vi.mock("@/lib/api", () => ({
       clearClaudeOnboardingSkipMock(...args),
     syncCurrentProvidersLive: (...args: unknown[]) =>
       syncCurrentProvidersLiveMock(...args),
+    validateOverrideDir: (...args: unknown[]) =>
+      validateOverrideDirMock(...args),
   },
   providersApi: {
     updateTrayMenu: (...args: unknown[]) => updateTrayMenuMock(...args),
@@ -121,8 +126,10 @@ describe("useSettings hook", () => {
     applyClaudeOnboardingSkipMock.mockReset();
     clearClaudeOnboardingSkipMock.mockReset();
     syncCurrentProvidersLiveMock.mockReset();
+    validateOverrideDirMock.mockReset();
     toastErrorMock.mockReset();
     toastSuccessMock.mockReset();
+    toastWarningMock.mockReset();
     window.localStorage.clear();
 
     serverSettings = {
@@ -154,6 +161,16 @@ describe("useSettings hook", () => {
     applyClaudePluginConfigMock.mockResolvedValue(true);
     applyClaudeOnboardingSkipMock.mockResolvedValue(true);
     clearClaudeOnboardingSkipMock.mockResolvedValue(true);
+    validateOverrideDirMock.mockImplementation(
+      async (_app: string, path: string) => ({
+        path,
+        exists: true,
+        writable: true,
+        atomicRename: true,
+        errors: [],
+        warnings: [],
+      }),
+    );
   });
 
   it("auto-saves and applies Claude onboarding skip when toggled on", async () => {
@@ -250,8 +267,15 @@ describe("useSettings hook", () => {
 
     expect(saveResult).toEqual({ requiresRestart: true });
     expect(mutateAsyncMock).toHaveBeenCalledTimes(1);
-    const payload = mutateAsyncMock.mock.calls[0][0] as Settings;
+    const { settings: payload, acknowledgeWarnings } = mutateAsyncMock.mock
+      .calls[0][0] as { settings: Settings; acknowledgeWarnings: boolean };
     expect(payload.claudeConfigDir).toBe("/custom/claude");
+    expect(acknowledgeWarnings).toBe(false);
+    expect(validateOverrideDirMock).toHaveBeenCalledTimes(1);
+    expect(validateOverrideDirMock).toHaveBeenCalledWith(
+      "claude",
+      "/custom/claude",
+    );
     expect(payload.codexConfigDir).toBeUndefined();
     expect(payload.language).toBe("en");
     expect(setAppConfigDirOverrideMock).toHaveBeenCalledWith("/override/app");
@@ -309,6 +333,64 @@ describe("useSettings hook", () => {
     expect(syncCurrentProvidersLiveMock).not.toHaveBeenCalled();
   });
 
+  it("refuses to save when a changed override directory is invalid", async () => {
+    validateOverrideDirMock.mockResolvedValueOnce({
+      path: "/claude",
+      exists: true,
+      writable: false,
+      atomicRename: false,
+      errors: ["not_writable"],
+      warnings: [],
+    });
+
+    const { result } = renderHook(() => useSettings());
+
+    await expect(
+      act(async () => {
+        await result.current.saveSettings();
+      }),
+    ).rejects.toThrow("not_writable");
+
+    expect(mutateAsyncMock).not.toHaveBeenCalled();
+    expect(toastErrorMock).toHaveBeenCalled();
+  });
+
+  it("asks for confirmation before saving degraded override directories", async () => {
+    const degraded = {
+      path: "/claude",
+      exists: false,
+      writable: false,
+      atomicRename: false,
+      errors: [],
+      warnings: ["missing"],
+    };
+    validateOverrideDirMock.mockResolvedValue(degraded);
+
+    const { result } = renderHook(() => useSettings());
+
+    let pending: { overrideDirWarnings?: string[] } | null = null;
+    await act(async () => {
+      pending = await result.current.saveSettings();
+    });
+
+    expect(pending).toEqual(
+      expect.objectContaining({
+        overrideDirWarnings: [expect.stringContaining("missing")],
+      }),
+    );
+    expect(mutateAsyncMock).not.toHaveBeenCalled();
+
+    await act(async () => {
+      await result.current.saveSettings(undefined, {
+        acknowledgeWarnings: true,
+      });
+    });
+
+    expect(mutateAsyncMock).toHaveBeenCalledWith(
+      expect.objectContaining({ acknowledgeWarnings: true }),
+    );
+  });
+
   it("shows toast when Claude plugin sync fails but continues flow", async () => {
     // 设置服务器状态为 false,本地状态为 true,触发状态变化
     serverSettings = {
@@ -69,7 +69,7 @@ export const handlers = [
       return HttpResponse.json(false, { status: 404 });
     }
     setCurrentProviderId(app, id);
-    return success(true);
+    return success({ liveWritten: true });
   }),
 
   http.post(`${TAURI_ENDPOINT}/add_provider`, async ({ request }) => {
@@ -156,6 +156,18 @@ export const handlers = [
     return success(true);
   }),
 
+  http.post(`${TAURI_ENDPOINT}/validate_override_dir`, async ({ request }) => {
+    const { path } = await withJson<{ path: string }>(request);
+    return success({
+      path,
+      exists: true,
+      writable: true,
+      atomicRename: true,
+      errors: [],
+      warnings: [],
+    });
+  }),
+
   http.post(
     `${TAURI_ENDPOINT}/set_app_config_dir_override`,
     async ({ request }) => {
@@ -310,4 +322,6 @@ export const handlers = [
   ),
   http.post(`${TAURI_ENDPOINT}/reset_circuit_breaker`, () => success(true)),
   http.post(`${TAURI_ENDPOINT}/get_circuit_breaker_stats`, () => success(null)),
+
+  http.post(`${TAURI_ENDPOINT}/get_provider_presets`, () => success([])),
 ];